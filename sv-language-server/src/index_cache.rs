@@ -0,0 +1,101 @@
+//! On-disk cache of the workspace symbol index
+//!
+//! The symbols (declarations and references) extracted from every indexed file
+//! are written to `<cache directory>/index.json` on shutdown and loaded again on
+//! startup. Each entry stores a hash of the file content it was built from, and
+//! entries whose file changed on disk since are dropped when the cache is loaded.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tower_lsp::lsp_types::Url;
+
+use crate::Symbol;
+
+/// Bumped whenever the layout of the cache or of `Symbol` changes
+pub const INDEX_CACHE_VERSION: u32 = 4;
+
+/// Name of the cache file inside the cache directory
+pub const INDEX_CACHE_FILE: &str = "index.json";
+
+/// Default cache directory, relative to the workspace root
+pub const DEFAULT_CACHE_DIRECTORY: &str = ".sv-lsp-cache";
+
+/// Index entry for a single file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexedFile {
+    /// Hash of the content the symbols were extracted from
+    pub hash: u64,
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct IndexCache {
+    pub version: u32,
+    pub files: HashMap<Url, IndexedFile>,
+}
+
+impl IndexCache {
+    pub fn new(files: HashMap<Url, IndexedFile>) -> Self {
+        Self {
+            version: INDEX_CACHE_VERSION,
+            files,
+        }
+    }
+
+    /// Load the cache from `cache_dir`
+    ///
+    /// Returns None if there is no cache, it cannot be read, or it was written by
+    /// a different cache version.
+    pub fn load(cache_dir: &Path) -> Option<Self> {
+        let content = std::fs::read_to_string(cache_dir.join(INDEX_CACHE_FILE)).ok()?;
+        let cache: IndexCache = serde_json::from_str(&content).ok()?;
+        (cache.version == INDEX_CACHE_VERSION).then_some(cache)
+    }
+
+    /// Write the cache to `cache_dir`, creating the directory if needed
+    pub fn save(&self, cache_dir: &Path) -> std::io::Result<()> {
+        std::fs::create_dir_all(cache_dir)?;
+        let content = serde_json::to_string(self)?;
+        std::fs::write(cache_dir.join(INDEX_CACHE_FILE), content)
+    }
+
+    /// Keep only the entries whose file still exists with the same content
+    pub fn into_valid_files(self) -> HashMap<Url, IndexedFile> {
+        self.files
+            .into_iter()
+            .filter(|(uri, indexed)| {
+                uri.to_file_path()
+                    .ok()
+                    .and_then(|path| std::fs::read_to_string(path).ok())
+                    .is_some_and(|content| content_hash(&content) == indexed.hash)
+            })
+            .collect()
+    }
+}
+
+/// Resolve the cache directory from the configured value and the workspace root
+///
+/// Relative paths are taken relative to the workspace root. Without a workspace
+/// root only an absolute configured path can be used.
+pub fn cache_directory(configured: Option<&str>, workspace_root: Option<&Path>) -> Option<PathBuf> {
+    let path = PathBuf::from(configured.unwrap_or(DEFAULT_CACHE_DIRECTORY));
+    if path.is_absolute() {
+        Some(path)
+    } else {
+        workspace_root.map(|root| root.join(path))
+    }
+}
+
+/// Hash of a file's content (64-bit FNV-1a)
+///
+/// The standard library hasher is not guaranteed to be stable between Rust
+/// releases, which would silently invalidate every cache entry.
+pub fn content_hash(content: &str) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    content.bytes().fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
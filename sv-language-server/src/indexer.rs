@@ -0,0 +1,156 @@
+//! Background indexing
+//!
+//! An indexing run re-analyzes the open documents and then indexes every
+//! SystemVerilog file under the source directories, on a small pool of tokio
+//! tasks that parse on the blocking pool, so that request handling is never
+//! blocked behind it. Jobs are taken from a queue in priority order (open
+//! documents first). Starting a new run cancels the previous one. An edit
+//! supersedes the job queued for an earlier version of its document: the
+//! edit is analyzed as it arrives, so the job is skipped, or its results are
+//! discarded if it was already running.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use tower_lsp::lsp_types::Url;
+
+/// File extensions picked up by workspace indexing
+pub const SOURCE_EXTENSIONS: &[&str] = &["sv", "svh", "v", "vh"];
+
+/// Upper bound on the number of concurrent indexing tasks
+pub const MAX_INDEX_WORKERS: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobPriority {
+    /// Re-analyze a document open in the editor, as of its `version`
+    OpenDocument { version: i32 },
+    /// Index a file on disk that is not open
+    Workspace,
+}
+
+/// Queue of pending indexing jobs, drained in priority order
+#[derive(Debug, Default)]
+pub struct IndexQueue {
+    open_documents: VecDeque<(Url, i32)>,
+    workspace: VecDeque<Url>,
+}
+
+impl IndexQueue {
+    pub fn push(&mut self, uri: Url, priority: JobPriority) {
+        match priority {
+            JobPriority::OpenDocument { version } => self.open_documents.push_back((uri, version)),
+            JobPriority::Workspace => self.workspace.push_back(uri),
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<(Url, JobPriority)> {
+        if let Some((uri, version)) = self.open_documents.pop_front() {
+            return Some((uri, JobPriority::OpenDocument { version }));
+        }
+        self.workspace
+            .pop_front()
+            .map(|uri| (uri, JobPriority::Workspace))
+    }
+
+    pub fn len(&self) -> usize {
+        self.open_documents.len() + self.workspace.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Shared state for indexing runs
+#[derive(Debug, Default)]
+pub struct IndexingState {
+    /// Id of the current run; bumping it cancels the run in progress
+    run: AtomicU64,
+    /// Whether the client accepts `window/workDoneProgress/create`
+    pub supports_progress: AtomicBool,
+}
+
+impl IndexingState {
+    /// Start a new run, cancelling any run in progress, and return its id
+    pub fn start_run(&self) -> u64 {
+        self.run.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Whether `run` has been superseded by a newer run
+    pub fn is_cancelled(&self, run: u64) -> bool {
+        self.run.load(Ordering::SeqCst) != run
+    }
+}
+
+/// Find all SystemVerilog files under `roots`, skipping hidden directories
+pub fn collect_source_files(roots: &[PathBuf]) -> Vec<PathBuf> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+        let mut paths: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).collect();
+        paths.sort();
+
+        for path in paths {
+            let hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with('.'));
+            if path.is_dir() {
+                if !hidden {
+                    walk(&path, files);
+                }
+            } else if path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| SOURCE_EXTENSIONS.contains(&ext))
+            {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    for root in roots {
+        walk(root, &mut files);
+    }
+    files.dedup();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uri(path: &str) -> Url {
+        Url::parse(&format!("file://{}", path)).unwrap()
+    }
+
+    #[test]
+    fn test_queue_returns_open_documents_first() {
+        let mut queue = IndexQueue::default();
+        queue.push(uri("/a.sv"), JobPriority::Workspace);
+        queue.push(uri("/b.sv"), JobPriority::OpenDocument { version: 3 });
+        queue.push(uri("/c.sv"), JobPriority::Workspace);
+
+        assert_eq!(queue.len(), 3);
+        assert_eq!(
+            queue.pop(),
+            Some((uri("/b.sv"), JobPriority::OpenDocument { version: 3 }))
+        );
+        assert_eq!(queue.pop(), Some((uri("/a.sv"), JobPriority::Workspace)));
+        assert_eq!(queue.pop(), Some((uri("/c.sv"), JobPriority::Workspace)));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn test_new_run_cancels_previous() {
+        let state = IndexingState::default();
+        let first = state.start_run();
+        assert!(!state.is_cancelled(first));
+
+        let second = state.start_run();
+        assert!(state.is_cancelled(first));
+        assert!(!state.is_cancelled(second));
+    }
+}
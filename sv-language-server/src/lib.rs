@@ -1,8 +1,22 @@
+pub mod index_cache;
+pub mod indexer;
+pub mod panic_guard;
+
+use index_cache::{IndexCache, IndexedFile};
+use indexer::{IndexQueue, IndexingState, JobPriority};
+use panic_guard::{catch_panic, PanicReport};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
-use sv_parser::{Expression, ModuleItem, SourceUnit, SystemVerilogParser};
+use std::time::Instant;
+use sv_parser::resolve::{self, PackageExports};
+use sv_parser::{explain, timescale};
+use sv_parser::{
+    Expression, ImportScope, MacroDefinition, ModuleItem, NumberLiteral, PositionEncoding,
+    Provenance, Resolution, SourceUnit, SystemVerilogParser,
+};
 use tokio::sync::RwLock;
 use tower_lsp::jsonrpc::Result as LspResult;
 use tower_lsp::lsp_types::request::{
@@ -12,7 +26,7 @@ use tower_lsp::lsp_types::request::{
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ServerConfig {
     /// Include directories for SystemVerilog (+incdir+)
     #[serde(default)]
@@ -29,20 +43,45 @@ pub struct ServerConfig {
     /// Override config file location
     #[serde(skip_serializing_if = "Option::is_none")]
     config_file_path: Option<String>,
-}
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self {
-            include_directories: Vec::new(),
-            defines: HashMap::new(),
-            source_directories: Vec::new(),
-            config_file_path: None,
-        }
-    }
+    /// Directory for the persistent symbol index (default: `.sv-lsp-cache` in the workspace)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_directory: Option<String>,
+
+    /// Maximum number of closed documents kept in memory with their parsed
+    /// ASTs; an evicted document is read and parsed again when a request needs
+    /// it (default: 32)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_cached_documents: Option<usize>,
+
+    /// Offer the code action that splits an always block by the variables it
+    /// drives (default: true)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    split_always_blocks: Option<bool>,
+
+    /// Deepest nesting of macro expansions and includes before a document is
+    /// reported as failing to preprocess (default: 64)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_preprocess_depth: Option<usize>,
+
+    /// Most characters a document may hold once its macros are expanded
+    /// (default: 16 MiB)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_preprocessed_size: Option<usize>,
+
+    /// Milliseconds a document may take to parse before it is reported as
+    /// too complex to parse (default: 10000; 0 sets no limit)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_parse_time_ms: Option<u64>,
 }
 
-#[derive(Debug, Clone)]
+/// Number of closed documents kept when `max_cached_documents` is not configured
+const DEFAULT_MAX_CACHED_DOCUMENTS: usize = 32;
+
+/// Milliseconds a document may take to parse when `max_parse_time_ms` is not configured
+const DEFAULT_MAX_PARSE_TIME_MS: u64 = 10_000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Symbol {
     pub name: String,
     pub symbol_type: SymbolType,
@@ -50,19 +89,298 @@ pub struct Symbol {
     pub uri: Url,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SymbolType {
     Module,
-    Class,
+    Interface,
+    Package,
+    /// A class or interface class, with the classes it extends or implements
+    Class {
+        bases: Vec<String>,
+    },
     Function,
+    /// A method declared `extern` in its class, or its body outside the
+    /// class, named `class::method` so each leads to the other
+    ExternMethod,
     #[allow(dead_code)]
     Task,
     Variable,
     Port,
     #[allow(dead_code)]
     Parameter,
+    /// A type named by a `typedef`
+    Typedef,
+    /// A named value of an enum type
+    EnumMember,
+    /// A variable of type `event`, triggered with `->` and waited on with `@`
+    Event,
+    /// A clocking block, named by `@(cb)` and its signals by `cb.signal`
+    Clocking,
+    Sequence,
+    Property,
     Define,
     Include,
+    /// The module name at an instantiation site, with the module the instance
+    /// lives in and the instance name
+    Instantiation {
+        parent: String,
+        instance: String,
+    },
+}
+
+/// Type hierarchy entry for a class declaration, listing its bases as detail
+fn type_hierarchy_item(symbol: &Symbol) -> TypeHierarchyItem {
+    let detail = match &symbol.symbol_type {
+        SymbolType::Class { bases } if !bases.is_empty() => Some(bases.join(", ")),
+        _ => None,
+    };
+    TypeHierarchyItem {
+        name: symbol.name.clone(),
+        kind: SymbolKind::CLASS,
+        tags: None,
+        detail,
+        uri: symbol.uri.clone(),
+        range: symbol.range,
+        selection_range: symbol.range,
+        data: None,
+    }
+}
+
+/// Workspace edit of a rename, with the renamed connections of a port, if
+/// any, in a group the client asks the user to confirm, since instances of
+/// the port's module are easy to miss
+///
+/// A connection already renamed as a reference, like the implicit `.clk`
+/// when the signal `clk` is renamed with the port, keeps that edit.
+fn rename_edit(
+    mut changes: HashMap<Url, Vec<TextEdit>>,
+    connections: Option<(String, HashMap<Url, Vec<TextEdit>>)>,
+) -> WorkspaceEdit {
+    let Some((module, connections)) = connections else {
+        return WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+            change_annotations: None,
+        };
+    };
+    const ANNOTATION: &str = "port-connections";
+    let mut uris: Vec<Url> = changes.keys().chain(connections.keys()).cloned().collect();
+    uris.sort();
+    uris.dedup();
+    let document_edits = uris
+        .into_iter()
+        .map(|uri| {
+            let references = changes.remove(&uri).unwrap_or_default();
+            let annotated = connections
+                .get(&uri)
+                .into_iter()
+                .flatten()
+                .filter(|edit| !references.iter().any(|other| other.range == edit.range))
+                .map(|edit| {
+                    OneOf::Right(AnnotatedTextEdit {
+                        text_edit: edit.clone(),
+                        annotation_id: ANNOTATION.to_string(),
+                    })
+                })
+                .collect::<Vec<_>>();
+            TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version: None },
+                edits: references
+                    .into_iter()
+                    .map(OneOf::Left)
+                    .chain(annotated)
+                    .collect(),
+            }
+        })
+        .collect();
+    WorkspaceEdit {
+        changes: None,
+        document_changes: Some(DocumentChanges::Edits(document_edits)),
+        change_annotations: Some(HashMap::from([(
+            ANNOTATION.to_string(),
+            ChangeAnnotation {
+                label: "Rename port connections".to_string(),
+                needs_confirmation: Some(true),
+                description: Some(format!(
+                    "Named connections to the port in instances of '{}'",
+                    module
+                )),
+            },
+        )])),
+    }
+}
+
+/// Hover text for a number: its width, signedness and value in each base
+fn number_hover_text(code: &str, literal: &NumberLiteral) -> String {
+    let width = match literal.width {
+        Some(width) => format!("{} bit{}", width, if width == 1 { "" } else { "s" }),
+        None => format!("{} bits (unsized)", literal.bit_width()),
+    };
+    let signedness = if literal.signed { "signed" } else { "unsigned" };
+
+    let values = match (
+        literal.to_decimal_string(),
+        literal.to_hex_string(),
+        literal.to_binary_string(),
+    ) {
+        (Some(decimal), Some(hex), Some(binary)) => format!(
+            "- Decimal: `{}`\n- Hex: `{}`\n- Binary: `{}`",
+            decimal, hex, binary
+        ),
+        _ => "Contains x/z bits, so it has no numeric value".to_string(),
+    };
+
+    format!(
+        "```systemverilog\n{}\n```\n\n{}, {}\n\n{}",
+        code, width, signedness, values
+    )
+}
+
+/// Hover text for the delay `#literal` of `value` time units in a design
+/// element with the time unit and precision of `scope`
+fn delay_hover_text(literal: &str, value: f64, scope: Option<&sv_parser::TimeScope>) -> String {
+    let code = format!("```systemverilog\n#{}\n```", literal);
+    let Some(unit) = scope.and_then(|scope| scope.unit) else {
+        return format!(
+            "{}\n\nNo `timescale or timeunit applies here, so the delay is counted in the \
+             simulator's default time unit",
+            code
+        );
+    };
+    let femtoseconds = value * unit.value.femtoseconds() as f64;
+    let mut text = format!(
+        "{}\n\n{} × {} = {}",
+        code,
+        literal,
+        unit.value.text(),
+        timescale::format_time(femtoseconds)
+    );
+    let precision = scope.and_then(|scope| scope.precision);
+    if let Some(precision) = precision {
+        let step = precision.value.femtoseconds() as f64;
+        let rounded = (femtoseconds / step).round() * step;
+        if rounded != femtoseconds {
+            text.push_str(&format!(
+                ", rounded to {} by the precision",
+                timescale::format_time(rounded)
+            ));
+        }
+    }
+    text.push_str(&format!(
+        "\n\n- Time unit: {}, from {}",
+        unit.value.text(),
+        unit.source.description()
+    ));
+    if let Some(precision) = precision {
+        text.push_str(&format!(
+            "\n- Time precision: {}, from {}",
+            precision.value.text(),
+            precision.source.description()
+        ));
+    }
+    text
+}
+
+/// Hover text for a macro: its definition as it stands at the end of the
+/// document, or just the name if it isn't defined there
+fn define_hover_text(name: &str, definition: Option<&MacroDefinition>) -> String {
+    let Some(definition) = definition else {
+        return format!("```systemverilog\n`define {}\n```", name);
+    };
+    let mut code = format!("`define {}", name);
+    if !definition.parameters.is_empty() {
+        code.push_str(&format!("({})", definition.parameters.join(", ")));
+    }
+    if !definition.value.is_empty() {
+        code.push(' ');
+        code.push_str(&definition.value);
+    }
+    let mut text = format!("```systemverilog\n{}\n```", code);
+    if definition.span.is_none() {
+        text.push_str("\n\nDefined in the server configuration");
+    }
+    text
+}
+
+/// Error response for a request whose handler panicked
+fn panic_error(method: &str, report: &PanicReport) -> tower_lsp::jsonrpc::Error {
+    tower_lsp::jsonrpc::Error {
+        code: tower_lsp::jsonrpc::ErrorCode::InternalError,
+        message: format!(
+            "Internal error while handling {}: {}",
+            method, report.message
+        )
+        .into(),
+        data: Some(serde_json::json!({
+            "location": report.location,
+            "backtrace": report.backtrace,
+        })),
+    }
+}
+
+/// Diagnostic shown on a document whose analysis panicked
+fn panic_diagnostic(report: &PanicReport) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(Position::new(0, 0), Position::new(0, 1)),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: None,
+        code_description: None,
+        source: Some("sv-language-server".to_string()),
+        message: format!(
+            "Internal error while analyzing this file: {}. Please report this as a bug.",
+            report.message
+        ),
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
+fn port_direction_keyword(direction: &sv_parser::PortDirection) -> &'static str {
+    match direction {
+        sv_parser::PortDirection::Input => "input",
+        sv_parser::PortDirection::Output => "output",
+        sv_parser::PortDirection::Inout => "inout",
+    }
+}
+
+/// Shrink a span to exclude leading whitespace and comments and trailing whitespace
+fn trim_span(content: &str, span: sv_parser::Span) -> sv_parser::Span {
+    let Some(text) = content.get(span.0..span.1) else {
+        return span;
+    };
+
+    let mut rest = text;
+    loop {
+        let trimmed = rest.trim_start();
+        if let Some(comment) = trimmed.strip_prefix("//") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = trimmed.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            rest = trimmed;
+            break;
+        }
+    }
+
+    let start = span.0 + (text.len() - rest.len());
+    let end = start + rest.trim_end().len();
+    (start, end)
+}
+
+fn format_range(range: &sv_parser::Range) -> String {
+    format!("[{}:{}]", range.msb, range.lsb)
+}
+
+/// Join the present parts of a declaration (`input logic [7:0]`), or None if there are none
+fn format_declaration(parts: &[Option<&str>]) -> Option<String> {
+    let parts: Vec<&str> = parts
+        .iter()
+        .flatten()
+        .copied()
+        .filter(|part| !part.is_empty())
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
 }
 
 #[derive(Debug, Clone)]
@@ -226,125 +544,164 @@ fn get_system_function_info(name: &str) -> Option<SystemFunctionInfo> {
 #[derive(Debug, Clone)]
 pub struct DocumentState {
     pub content: String,
+    /// Parsed AST, or None if the document could not be parsed
     pub ast: Option<SourceUnit>,
     pub symbols: Vec<Symbol>,
+    /// The editor's version of the content
+    pub version: i32,
+    /// Whether the document is currently open in the editor
+    pub open: bool,
+    pub last_used: Instant,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Backend {
     pub client: Client,
     pub documents: Arc<RwLock<HashMap<Url, DocumentState>>>,
     pub workspace_symbols: Arc<RwLock<HashMap<String, Vec<Symbol>>>>, // symbol_name -> all locations
     pub config: Arc<RwLock<ServerConfig>>,
     pub workspace_root: Arc<RwLock<Option<PathBuf>>>,
+    pub indexed_files: Arc<RwLock<HashMap<Url, IndexedFile>>>, // uri -> symbols, persisted on shutdown
+    pub indexing: Arc<IndexingState>,
+    /// Whether the client accepts a dynamic registration of the type hierarchy,
+    /// which the server capabilities of lsp-types 0.94 cannot advertise
+    pub register_type_hierarchy: Arc<AtomicBool>,
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> LspResult<InitializeResult> {
-        // Store workspace root
-        {
-            let mut workspace_root = self.workspace_root.write().await;
-            *workspace_root = params.root_uri.and_then(|uri| uri.to_file_path().ok());
-        }
+        self.guard_request("initialize", async move {
+            // Store workspace root
+            {
+                let mut workspace_root = self.workspace_root.write().await;
+                *workspace_root = params.root_uri.and_then(|uri| uri.to_file_path().ok());
+            }
 
-        // Load configuration from initialization options
-        let mut config = ServerConfig::default();
-        if let Some(init_options) = params.initialization_options {
-            match serde_json::from_value::<ServerConfig>(init_options) {
-                Ok(parsed_config) => {
-                    config = parsed_config;
-                    self.client
-                        .log_message(
-                            MessageType::INFO,
-                            "Configuration loaded from initialization options",
-                        )
-                        .await;
-                }
-                Err(e) => {
-                    self.client
-                        .log_message(
-                            MessageType::WARNING,
-                            format!("Failed to parse initialization options: {}", e),
-                        )
-                        .await;
+            let supports_progress = params
+                .capabilities
+                .window
+                .as_ref()
+                .and_then(|window| window.work_done_progress)
+                .unwrap_or(false);
+            self.indexing
+                .supports_progress
+                .store(supports_progress, Ordering::SeqCst);
+            let type_hierarchy_registration = params
+                .capabilities
+                .text_document
+                .as_ref()
+                .and_then(|text_document| text_document.type_hierarchy.as_ref())
+                .and_then(|type_hierarchy| type_hierarchy.dynamic_registration)
+                .unwrap_or(false);
+            self.register_type_hierarchy
+                .store(type_hierarchy_registration, Ordering::SeqCst);
+
+            // Load configuration from initialization options
+            let mut config = ServerConfig::default();
+            if let Some(init_options) = params.initialization_options {
+                match serde_json::from_value::<ServerConfig>(init_options) {
+                    Ok(parsed_config) => {
+                        config = parsed_config;
+                        self.client
+                            .log_message(
+                                MessageType::INFO,
+                                "Configuration loaded from initialization options",
+                            )
+                            .await;
+                    }
+                    Err(e) => {
+                        self.client
+                            .log_message(
+                                MessageType::WARNING,
+                                format!("Failed to parse initialization options: {}", e),
+                            )
+                            .await;
+                    }
                 }
             }
-        }
 
-        // Try to load from config file if not provided in initialization options
-        if config.include_directories.is_empty()
-            && config.defines.is_empty()
-            && config.source_directories.is_empty()
-        {
-            if let Some(file_config) = self.load_config_file(&config).await {
-                config = file_config;
+            // Try to load from config file if not provided in initialization options
+            if config.include_directories.is_empty()
+                && config.defines.is_empty()
+                && config.source_directories.is_empty()
+            {
+                if let Some(file_config) = self.load_config_file(&config).await {
+                    config = file_config;
+                }
             }
-        }
 
-        // Validate and store the configuration
-        self.validate_config(&config).await;
-        {
-            let mut stored_config = self.config.write().await;
-            *stored_config = config;
-        }
+            // Validate and store the configuration
+            self.validate_config(&config).await;
+            {
+                let mut stored_config = self.config.write().await;
+                *stored_config = config;
+            }
 
-        Ok(InitializeResult {
-            server_info: Some(ServerInfo {
-                name: "sv-language-server".to_string(),
-                version: Some("0.1.0".to_string()),
-            }),
-            capabilities: ServerCapabilities {
-                text_document_sync: Some(TextDocumentSyncCapability::Kind(
-                    TextDocumentSyncKind::FULL,
-                )),
-                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
-                    DiagnosticOptions {
-                        identifier: Some("sv-language-server".to_string()),
-                        inter_file_dependencies: true,
-                        workspace_diagnostics: false,
-                        work_done_progress_options: WorkDoneProgressOptions::default(),
-                    },
-                )),
-                rename_provider: Some(OneOf::Left(true)),
-                folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
-                definition_provider: Some(OneOf::Left(true)),
-                declaration_provider: Some(DeclarationCapability::Simple(true)),
-                type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
-                implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
-                references_provider: Some(OneOf::Left(true)),
-                hover_provider: Some(HoverProviderCapability::Simple(true)),
-                workspace_symbol_provider: Some(OneOf::Left(true)),
-                document_symbol_provider: Some(OneOf::Left(true)),
-                document_highlight_provider: Some(OneOf::Left(true)),
-                selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
-                completion_provider: Some(CompletionOptions {
-                    resolve_provider: Some(false),
-                    trigger_characters: Some(vec![
-                        "$".to_string(),
-                        ".".to_string(),
-                        "`".to_string(),
-                    ]),
-                    all_commit_characters: None,
-                    work_done_progress_options: WorkDoneProgressOptions::default(),
-                    completion_item: None,
-                }),
-                signature_help_provider: Some(SignatureHelpOptions {
-                    trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
-                    retrigger_characters: None,
-                    work_done_progress_options: WorkDoneProgressOptions::default(),
+            Ok(InitializeResult {
+                server_info: Some(ServerInfo {
+                    name: "sv-language-server".to_string(),
+                    version: Some("0.1.0".to_string()),
                 }),
-                inlay_hint_provider: Some(OneOf::Left(true)),
-                workspace: Some(WorkspaceServerCapabilities {
-                    workspace_folders: Some(WorkspaceFoldersServerCapabilities {
-                        supported: Some(true),
-                        change_notifications: Some(OneOf::Left(true)),
+                capabilities: ServerCapabilities {
+                    text_document_sync: Some(TextDocumentSyncCapability::Kind(
+                        TextDocumentSyncKind::FULL,
+                    )),
+                    diagnostic_provider: Some(DiagnosticServerCapabilities::Options(
+                        DiagnosticOptions {
+                            identifier: Some("sv-language-server".to_string()),
+                            inter_file_dependencies: true,
+                            workspace_diagnostics: false,
+                            work_done_progress_options: WorkDoneProgressOptions::default(),
+                        },
+                    )),
+                    rename_provider: Some(OneOf::Left(true)),
+                    folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+                    definition_provider: Some(OneOf::Left(true)),
+                    declaration_provider: Some(DeclarationCapability::Simple(true)),
+                    type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+                    implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+                    references_provider: Some(OneOf::Left(true)),
+                    hover_provider: Some(HoverProviderCapability::Simple(true)),
+                    workspace_symbol_provider: Some(OneOf::Left(true)),
+                    document_symbol_provider: Some(OneOf::Left(true)),
+                    document_highlight_provider: Some(OneOf::Left(true)),
+                    selection_range_provider: Some(SelectionRangeProviderCapability::Simple(true)),
+                    code_action_provider: Some(CodeActionProviderCapability::Options(
+                        CodeActionOptions {
+                            code_action_kinds: Some(vec![CodeActionKind::REFACTOR_REWRITE]),
+                            ..CodeActionOptions::default()
+                        },
+                    )),
+                    completion_provider: Some(CompletionOptions {
+                        resolve_provider: Some(false),
+                        trigger_characters: Some(vec![
+                            "$".to_string(),
+                            ".".to_string(),
+                            "`".to_string(),
+                        ]),
+                        all_commit_characters: None,
+                        work_done_progress_options: WorkDoneProgressOptions::default(),
+                        completion_item: None,
                     }),
-                    file_operations: None,
-                }),
-                ..ServerCapabilities::default()
-            },
+                    signature_help_provider: Some(SignatureHelpOptions {
+                        trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                        retrigger_characters: None,
+                        work_done_progress_options: WorkDoneProgressOptions::default(),
+                    }),
+                    inlay_hint_provider: Some(OneOf::Left(true)),
+                    workspace: Some(WorkspaceServerCapabilities {
+                        workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                            supported: Some(true),
+                            change_notifications: Some(OneOf::Left(true)),
+                        }),
+                        file_operations: None,
+                    }),
+                    ..ServerCapabilities::default()
+                },
+            })
         })
+        .await
     }
 
     async fn initialized(&self, _: InitializedParams) {
@@ -354,9 +711,42 @@ impl LanguageServer for Backend {
                 "SystemVerilog Language Server initialized!",
             )
             .await;
+
+        if self.register_type_hierarchy.load(Ordering::SeqCst) {
+            let registration = Registration {
+                id: "type-hierarchy".to_string(),
+                method: "textDocument/prepareTypeHierarchy".to_string(),
+                register_options: serde_json::to_value(TypeHierarchyRegistrationOptions {
+                    text_document_registration_options: TextDocumentRegistrationOptions {
+                        document_selector: Some(vec![DocumentFilter {
+                            language: Some("systemverilog".to_string()),
+                            scheme: None,
+                            pattern: None,
+                        }]),
+                    },
+                    type_hierarchy_options: TypeHierarchyOptions::default(),
+                    static_registration_options: StaticRegistrationOptions::default(),
+                })
+                .ok(),
+            };
+            if let Err(err) = self.client.register_capability(vec![registration]).await {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!("Failed to register the type hierarchy: {}", err),
+                    )
+                    .await;
+            }
+        }
+
+        self.load_index_cache().await;
+
+        let backend = self.clone();
+        tokio::spawn(async move { backend.index_workspace().await });
     }
 
     async fn shutdown(&self) -> LspResult<()> {
+        self.save_index_cache().await;
         Ok(())
     }
 
@@ -396,22 +786,15 @@ impl LanguageServer for Backend {
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
-        // Remove document from storage and workspace symbols
+        // The document stays cached until evicted; its symbols stay in the workspace index
         {
             let mut docs = self.documents.write().await;
-            if let Some(doc_state) = docs.remove(&params.text_document.uri) {
-                // Remove symbols from workspace index
-                let mut workspace_symbols = self.workspace_symbols.write().await;
-                for symbol in doc_state.symbols {
-                    if let Some(symbol_list) = workspace_symbols.get_mut(&symbol.name) {
-                        symbol_list.retain(|s| s.uri != params.text_document.uri);
-                        if symbol_list.is_empty() {
-                            workspace_symbols.remove(&symbol.name);
-                        }
-                    }
-                }
+            if let Some(doc_state) = docs.get_mut(&params.text_document.uri) {
+                doc_state.open = false;
+                doc_state.last_used = Instant::now();
             }
         }
+        self.evict_closed_documents(None).await;
 
         self.client
             .log_message(MessageType::INFO, "file closed!")
@@ -439,458 +822,650 @@ impl LanguageServer for Backend {
     }
 
     async fn rename(&self, params: RenameParams) -> LspResult<Option<WorkspaceEdit>> {
-        self.client
-            .log_message(MessageType::INFO, "rename requested!")
-            .await;
-
-        let uri = params.text_document_position.text_document.uri;
-        let position = params.text_document_position.position;
-        let new_name = params.new_name;
-
-        self.client
-            .log_message(
-                MessageType::INFO,
-                format!(
-                    "Rename at position {}:{}",
-                    position.line, position.character
-                ),
-            )
-            .await;
-
-        // Get the document state
-        let doc_symbols = {
-            let docs = self.documents.read().await;
-            match docs.get(&uri) {
-                Some(doc_state) => doc_state.symbols.clone(),
-                None => {
-                    self.client
-                        .log_message(MessageType::ERROR, "Document not found")
-                        .await;
-                    return Ok(None);
-                }
-            }
-        };
+        self.guard_request("textDocument/rename", async move {
+            self.client
+                .log_message(MessageType::INFO, "rename requested!")
+                .await;
 
-        self.client
-            .log_message(
-                MessageType::INFO,
-                format!("Found {} symbols in document", doc_symbols.len()),
-            )
-            .await;
+            let uri = params.text_document_position.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position.position;
+            let new_name = params.new_name;
 
-        // Log all symbols with their ranges
-        for symbol in &doc_symbols {
             self.client
                 .log_message(
                     MessageType::INFO,
                     format!(
-                        "Symbol '{}' at {}:{}-{}:{}",
-                        symbol.name,
-                        symbol.range.start.line,
-                        symbol.range.start.character,
-                        symbol.range.end.line,
-                        symbol.range.end.character
+                        "Rename at position {}:{}",
+                        position.line, position.character
                     ),
                 )
                 .await;
-        }
 
-        // Find the symbol at the requested position
-        let symbol_at_position = doc_symbols
-            .iter()
-            .find(|symbol| self.position_in_range(position, symbol.range));
+            // Get the document state
+            let doc_symbols = {
+                let docs = self.documents.read().await;
+                match docs.get(&uri) {
+                    Some(doc_state) => doc_state.symbols.clone(),
+                    None => {
+                        self.client
+                            .log_message(MessageType::ERROR, "Document not found")
+                            .await;
+                        return Ok(None);
+                    }
+                }
+            };
 
-        if let Some(symbol) = symbol_at_position {
             self.client
                 .log_message(
                     MessageType::INFO,
-                    format!("Found symbol '{}' at position", symbol.name),
+                    format!("Found {} symbols in document", doc_symbols.len()),
                 )
                 .await;
-            // Find all workspace references to this symbol
-            let workspace_references = {
-                let workspace_symbols = self.workspace_symbols.read().await;
-                workspace_symbols
-                    .get(&symbol.name)
-                    .cloned()
-                    .unwrap_or_default()
-            };
 
-            if workspace_references.is_empty() {
-                return Ok(None);
+            // Log all symbols with their ranges
+            for symbol in &doc_symbols {
+                self.client
+                    .log_message(
+                        MessageType::INFO,
+                        format!(
+                            "Symbol '{}' at {}:{}-{}:{}",
+                            symbol.name,
+                            symbol.range.start.line,
+                            symbol.range.start.character,
+                            symbol.range.end.line,
+                            symbol.range.end.character
+                        ),
+                    )
+                    .await;
             }
 
-            // Group edits by file URI
-            let mut changes = HashMap::new();
-            for reference in workspace_references {
-                // Only rename symbols of compatible types
-                let should_rename = match (&symbol.symbol_type, &reference.symbol_type) {
-                    (SymbolType::Module, SymbolType::Module) => true,
-                    (SymbolType::Class, SymbolType::Class) => true,
-                    (SymbolType::Function, SymbolType::Function) => true,
-                    (SymbolType::Task, SymbolType::Task) => true,
-                    (SymbolType::Variable, _)
-                    | (SymbolType::Port, _)
-                    | (SymbolType::Parameter, _) => true,
-                    (_, SymbolType::Variable)
-                    | (_, SymbolType::Port)
-                    | (_, SymbolType::Parameter) => true,
-                    (SymbolType::Define, SymbolType::Define) => true,
-                    (SymbolType::Include, SymbolType::Include) => true,
-                    _ => false,
+            // Find the symbol at the requested position
+            let symbol_at_position = doc_symbols
+                .iter()
+                .find(|symbol| self.position_in_range(position, symbol.range));
+
+            if let Some(symbol) = symbol_at_position {
+                self.client
+                    .log_message(
+                        MessageType::INFO,
+                        format!("Found symbol '{}' at position", symbol.name),
+                    )
+                    .await;
+                // Find all workspace references to this symbol
+                let workspace_references = {
+                    let workspace_symbols = self.workspace_symbols.read().await;
+                    workspace_symbols
+                        .get(&symbol.name)
+                        .cloned()
+                        .unwrap_or_default()
                 };
 
-                if should_rename {
-                    changes
-                        .entry(reference.uri.clone())
-                        .or_insert_with(Vec::new)
-                        .push(TextEdit {
-                            range: reference.range,
-                            new_text: new_name.clone(),
-                        });
+                if workspace_references.is_empty() {
+                    return Ok(None);
                 }
-            }
 
-            if changes.is_empty() {
-                return Ok(None);
-            }
+                // Group edits by file URI
+                let mut changes = HashMap::new();
+                for reference in workspace_references {
+                    // Only rename symbols of compatible types
+                    let should_rename = matches!(
+                        (&symbol.symbol_type, &reference.symbol_type),
+                        (SymbolType::Module, SymbolType::Module)
+                            | (SymbolType::Module, SymbolType::Instantiation { .. })
+                            | (SymbolType::Instantiation { .. }, SymbolType::Module)
+                            | (SymbolType::Interface, SymbolType::Interface)
+                            | (SymbolType::Interface, SymbolType::Instantiation { .. })
+                            | (SymbolType::Instantiation { .. }, SymbolType::Interface)
+                            | (SymbolType::Package, SymbolType::Package)
+                            | (
+                                SymbolType::Instantiation { .. },
+                                SymbolType::Instantiation { .. }
+                            )
+                            | (SymbolType::Class { .. }, SymbolType::Class { .. })
+                            | (SymbolType::Function, SymbolType::Function)
+                            | (SymbolType::ExternMethod, SymbolType::ExternMethod)
+                            | (SymbolType::Task, SymbolType::Task)
+                            | (SymbolType::Variable, _)
+                            | (SymbolType::Port, _)
+                            | (SymbolType::Parameter, _)
+                            | (SymbolType::EnumMember, _)
+                            | (_, SymbolType::Variable)
+                            | (_, SymbolType::Port)
+                            | (_, SymbolType::Parameter)
+                            | (_, SymbolType::EnumMember)
+                            | (SymbolType::Typedef, SymbolType::Typedef)
+                            | (SymbolType::Event, SymbolType::Event)
+                            | (SymbolType::Clocking, SymbolType::Clocking)
+                            | (SymbolType::Sequence, SymbolType::Sequence)
+                            | (SymbolType::Property, SymbolType::Property)
+                            | (SymbolType::Define, SymbolType::Define)
+                            | (SymbolType::Include, SymbolType::Include)
+                    );
 
-            return Ok(Some(WorkspaceEdit {
-                changes: Some(changes),
-                document_changes: None,
-                change_annotations: None,
-            }));
-        }
+                    if should_rename {
+                        changes
+                            .entry(reference.uri.clone())
+                            .or_insert_with(Vec::new)
+                            .push(TextEdit {
+                                range: reference.range,
+                                new_text: new_name.clone(),
+                            });
+                    }
+                }
 
-        self.client
-            .log_message(MessageType::WARNING, "No symbol found at position")
-            .await;
-        Ok(None)
+                if changes.is_empty() {
+                    return Ok(None);
+                }
+
+                // A port may be renamed from its declaration or from a use in its module
+                let connection_edits = match symbol.symbol_type {
+                    SymbolType::Port | SymbolType::Variable => {
+                        self.port_connection_edits(&uri, position, &symbol.name, &new_name)
+                            .await?
+                    }
+                    _ => None,
+                };
+                return Ok(Some(rename_edit(changes, connection_edits)));
+            }
+
+            self.client
+                .log_message(MessageType::WARNING, "No symbol found at position")
+                .await;
+            Ok(None)
+        })
+        .await
     }
 
     async fn folding_range(
         &self,
         params: FoldingRangeParams,
     ) -> LspResult<Option<Vec<FoldingRange>>> {
-        let uri = params.text_document.uri;
-
-        // Get the document AST
-        let ast = {
-            let docs = self.documents.read().await;
-            match docs.get(&uri) {
-                Some(doc_state) => doc_state.ast.clone(),
-                None => {
-                    self.client
-                        .log_message(MessageType::WARNING, "Document not found for folding")
-                        .await;
-                    return Ok(None);
-                }
-            }
-        };
+        self.guard_request("textDocument/foldingRange", async move {
+            let uri = params.text_document.uri;
+            self.ensure_document(&uri).await?;
 
-        if let Some(ast) = ast {
-            let content = {
+            // Get the document AST
+            let ast = {
                 let docs = self.documents.read().await;
-                docs.get(&uri)
-                    .map(|doc| doc.content.clone())
-                    .unwrap_or_default()
+                match docs.get(&uri) {
+                    Some(doc_state) => doc_state.ast.clone(),
+                    None => {
+                        self.client
+                            .log_message(MessageType::WARNING, "Document not found for folding")
+                            .await;
+                        return Ok(None);
+                    }
+                }
             };
 
-            let folding_ranges = self.extract_folding_ranges(&ast, &content);
-            Ok(Some(folding_ranges))
-        } else {
-            Ok(None)
-        }
+            if let Some(ast) = ast {
+                let content = {
+                    let docs = self.documents.read().await;
+                    docs.get(&uri)
+                        .map(|doc| doc.content.clone())
+                        .unwrap_or_default()
+                };
+
+                let folding_ranges = self.extract_folding_ranges(&ast, &content);
+                Ok(Some(folding_ranges))
+            } else {
+                Ok(None)
+            }
+        })
+        .await
     }
 
     async fn goto_definition(
         &self,
         params: GotoDefinitionParams,
     ) -> LspResult<Option<GotoDefinitionResponse>> {
-        let uri = params.text_document_position_params.text_document.uri;
-        let position = params.text_document_position_params.position;
+        self.guard_request("textDocument/definition", async move {
+            let uri = params.text_document_position_params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position_params.position;
 
-        self.client
-            .log_message(
-                MessageType::INFO,
-                format!(
-                    "goto_definition called at {}:{}",
-                    position.line, position.character
-                ),
-            )
-            .await;
-
-        // Find the symbol at the cursor position
-        let symbol_info = {
-            let docs = self.documents.read().await;
-            match docs.get(&uri) {
-                Some(doc_state) => {
-                    self.client
-                        .log_message(
-                            MessageType::INFO,
-                            format!("Document has {} symbols", doc_state.symbols.len()),
-                        )
-                        .await;
-
-                    let found_symbol = doc_state
-                        .symbols
-                        .iter()
-                        .find(|symbol| self.position_in_range(position, symbol.range))
-                        .map(|s| (s.name.clone(), s.symbol_type.clone()));
+            self.client
+                .log_message(
+                    MessageType::INFO,
+                    format!(
+                        "goto_definition called at {}:{}",
+                        position.line, position.character
+                    ),
+                )
+                .await;
 
-                    if let Some((ref name, ref stype)) = found_symbol {
+            // Find the symbol at the cursor position
+            let symbol_info = {
+                let docs = self.documents.read().await;
+                match docs.get(&uri) {
+                    Some(doc_state) => {
                         self.client
                             .log_message(
                                 MessageType::INFO,
-                                format!("Found symbol: name='{}', type={:?}", name, stype),
+                                format!("Document has {} symbols", doc_state.symbols.len()),
                             )
                             .await;
-                    } else {
+
+                        let found_symbol = doc_state
+                            .symbols
+                            .iter()
+                            .find(|symbol| self.position_in_range(position, symbol.range))
+                            .map(|s| (s.name.clone(), s.symbol_type.clone()));
+
+                        if let Some((ref name, ref stype)) = found_symbol {
+                            self.client
+                                .log_message(
+                                    MessageType::INFO,
+                                    format!("Found symbol: name='{}', type={:?}", name, stype),
+                                )
+                                .await;
+                        } else {
+                            self.client
+                                .log_message(MessageType::INFO, "No symbol found at position")
+                                .await;
+                        }
+
+                        found_symbol
+                    }
+                    None => {
                         self.client
-                            .log_message(MessageType::INFO, "No symbol found at position")
+                            .log_message(MessageType::WARNING, "Document not found")
                             .await;
+                        None
                     }
-
-                    found_symbol
                 }
-                None => {
+            };
+
+            if let Some((name, symbol_type)) = symbol_info {
+                // Special handling for include directives
+                if matches!(symbol_type, SymbolType::Include) {
                     self.client
-                        .log_message(MessageType::WARNING, "Document not found")
+                        .log_message(
+                            MessageType::INFO,
+                            format!("Processing include: path='{}'", name),
+                        )
                         .await;
-                    None
-                }
-            }
-        };
-
-        if let Some((name, symbol_type)) = symbol_info {
-            // Special handling for include directives
-            if matches!(symbol_type, SymbolType::Include) {
-                self.client
-                    .log_message(
-                        MessageType::INFO,
-                        format!("Processing include: path='{}'", name),
-                    )
-                    .await;
-
-                // Try to resolve the include path
-                let include_path = std::path::Path::new(&name);
 
-                // If it's already an absolute path (resolved), use it directly
-                let resolved = if include_path.is_absolute() {
-                    Some(include_path.to_path_buf())
-                } else {
-                    // Try to resolve relative to the current file's directory
-                    uri.to_file_path().ok().and_then(|current_file| {
-                        let current_dir = current_file.parent()?;
-                        let candidate = current_dir.join(&name);
-                        if candidate.exists() {
-                            return Some(candidate);
-                        }
+                    // Try to resolve the include path
+                    let include_path = std::path::Path::new(&name);
 
-                        // Try looking in common include directories relative to current file
-                        for include_dir in &["include", "../include", "../../include"] {
-                            let candidate = current_dir.join(include_dir).join(&name);
+                    // If it's already an absolute path (resolved), use it directly
+                    let resolved = if include_path.is_absolute() {
+                        Some(include_path.to_path_buf())
+                    } else {
+                        // Try to resolve relative to the current file's directory
+                        uri.to_file_path().ok().and_then(|current_file| {
+                            let current_dir = current_file.parent()?;
+                            let candidate = current_dir.join(&name);
                             if candidate.exists() {
                                 return Some(candidate);
                             }
-                        }
-                        None
-                    })
-                };
 
-                if let Some(resolved_path) = resolved {
-                    if let Ok(file_uri) = Url::from_file_path(&resolved_path) {
-                        self.client
-                            .log_message(MessageType::INFO, format!("Navigating to: {}", file_uri))
-                            .await;
+                            // Try looking in common include directories relative to current file
+                            for include_dir in &["include", "../include", "../../include"] {
+                                let candidate = current_dir.join(include_dir).join(&name);
+                                if candidate.exists() {
+                                    return Some(candidate);
+                                }
+                            }
+                            None
+                        })
+                    };
 
-                        return Ok(Some(GotoDefinitionResponse::Scalar(Location {
-                            uri: file_uri,
-                            range: Range {
-                                start: Position {
-                                    line: 0,
-                                    character: 0,
-                                },
-                                end: Position {
-                                    line: 0,
-                                    character: 0,
+                    if let Some(resolved_path) = resolved {
+                        if let Ok(file_uri) = Url::from_file_path(&resolved_path) {
+                            self.client
+                                .log_message(
+                                    MessageType::INFO,
+                                    format!("Navigating to: {}", file_uri),
+                                )
+                                .await;
+
+                            return Ok(Some(GotoDefinitionResponse::Scalar(Location {
+                                uri: file_uri,
+                                range: Range {
+                                    start: Position {
+                                        line: 0,
+                                        character: 0,
+                                    },
+                                    end: Position {
+                                        line: 0,
+                                        character: 0,
+                                    },
                                 },
-                            },
-                        })));
+                            })));
+                        }
                     }
+
+                    self.client
+                        .log_message(
+                            MessageType::WARNING,
+                            format!("Could not resolve include file: {}", name),
+                        )
+                        .await;
+                    return Ok(None);
                 }
 
-                self.client
-                    .log_message(
-                        MessageType::WARNING,
-                        format!("Could not resolve include file: {}", name),
-                    )
-                    .await;
-                return Ok(None);
-            }
+                // A name imported from a package leads to the declaration its
+                // scope's imports choose, even where other packages declare it too
+                let imported = {
+                    let docs = self.documents.read().await;
+                    self.imported_declaration(&docs, &uri, &name, position)
+                };
+                if let Some((_, location)) = imported {
+                    return Ok(Some(GotoDefinitionResponse::Scalar(location)));
+                }
 
-            // Look up all occurrences of this symbol
-            let workspace_symbols = self.workspace_symbols.read().await;
-            if let Some(symbol_list) = workspace_symbols.get(&name) {
-                // For definition, we want the first declaration (typically the module/class/function declaration)
-                // We'll prioritize declaration-type symbols (Module, Class, Function, Task, Port) as definitions
-                let definition = symbol_list
-                    .iter()
-                    .find(|s| {
-                        matches!(
-                            s.symbol_type,
-                            SymbolType::Module
-                                | SymbolType::Class
-                                | SymbolType::Function
-                                | SymbolType::Task
-                                | SymbolType::Port
-                        )
-                    })
-                    .or_else(|| symbol_list.first());
+                // The definition may live in an include file the editor hasn't
+                // opened, or one changed on disk since it was indexed
+                self.index_includes(&uri).await;
+
+                // Look up all occurrences of this symbol
+                let workspace_symbols = self.workspace_symbols.read().await;
+
+                // The prototype of an extern method and its body outside the
+                // class lead to each other
+                if matches!(symbol_type, SymbolType::ExternMethod) {
+                    let counterpart =
+                        workspace_symbols
+                            .get(&name)
+                            .into_iter()
+                            .flatten()
+                            .find(|symbol| {
+                                matches!(symbol.symbol_type, SymbolType::ExternMethod)
+                                    && !(symbol.uri == uri
+                                        && self.position_in_range(position, symbol.range))
+                            });
+                    return Ok(counterpart.map(|symbol| {
+                        GotoDefinitionResponse::Scalar(Location {
+                            uri: symbol.uri.clone(),
+                            range: symbol.range,
+                        })
+                    }));
+                }
+
+                if let Some(symbol_list) = workspace_symbols.get(&name) {
+                    // For definition, we want the first declaration (typically the module/class/function declaration)
+                    // We'll prioritize declaration-type symbols (Module, Class, Function, Task, Port) as definitions
+                    let definition = symbol_list
+                        .iter()
+                        .find(|s| {
+                            matches!(
+                                s.symbol_type,
+                                SymbolType::Module
+                                    | SymbolType::Interface
+                                    | SymbolType::Package
+                                    | SymbolType::Class { .. }
+                                    | SymbolType::Function
+                                    | SymbolType::Task
+                                    | SymbolType::Port
+                                    | SymbolType::Event
+                                    | SymbolType::Clocking
+                                    | SymbolType::Sequence
+                                    | SymbolType::Property
+                            )
+                        })
+                        .or_else(|| symbol_list.first());
 
-                if let Some(def_symbol) = definition {
-                    return Ok(Some(GotoDefinitionResponse::Scalar(Location {
-                        uri: def_symbol.uri.clone(),
-                        range: def_symbol.range,
-                    })));
+                    if let Some(def_symbol) = definition {
+                        return Ok(Some(GotoDefinitionResponse::Scalar(Location {
+                            uri: def_symbol.uri.clone(),
+                            range: def_symbol.range,
+                        })));
+                    }
                 }
             }
-        }
 
-        Ok(None)
+            Ok(None)
+        })
+        .await
     }
 
     async fn goto_declaration(
         &self,
         params: GotoDeclarationParams,
     ) -> LspResult<Option<GotoDeclarationResponse>> {
-        // For SystemVerilog, declaration is the same as definition
-        let def_params = GotoDefinitionParams {
-            text_document_position_params: params.text_document_position_params,
-            work_done_progress_params: params.work_done_progress_params,
-            partial_result_params: params.partial_result_params,
-        };
+        self.guard_request("textDocument/declaration", async move {
+            // For SystemVerilog, declaration is the same as definition
+            let def_params = GotoDefinitionParams {
+                text_document_position_params: params.text_document_position_params,
+                work_done_progress_params: params.work_done_progress_params,
+                partial_result_params: params.partial_result_params,
+            };
 
-        match self.goto_definition(def_params).await? {
-            Some(GotoDefinitionResponse::Scalar(loc)) => {
-                Ok(Some(GotoDeclarationResponse::Scalar(loc)))
-            }
-            Some(GotoDefinitionResponse::Array(locs)) => {
-                Ok(Some(GotoDeclarationResponse::Array(locs)))
-            }
-            Some(GotoDefinitionResponse::Link(links)) => {
-                Ok(Some(GotoDeclarationResponse::Link(links)))
+            match self.goto_definition(def_params).await? {
+                Some(GotoDefinitionResponse::Scalar(loc)) => {
+                    Ok(Some(GotoDeclarationResponse::Scalar(loc)))
+                }
+                Some(GotoDefinitionResponse::Array(locs)) => {
+                    Ok(Some(GotoDeclarationResponse::Array(locs)))
+                }
+                Some(GotoDefinitionResponse::Link(links)) => {
+                    Ok(Some(GotoDeclarationResponse::Link(links)))
+                }
+                None => Ok(None),
             }
-            None => Ok(None),
-        }
+        })
+        .await
     }
 
     async fn goto_type_definition(
         &self,
         params: GotoTypeDefinitionParams,
     ) -> LspResult<Option<GotoTypeDefinitionResponse>> {
-        // For SystemVerilog, we'll look for module type definitions
-        let uri = params.text_document_position_params.text_document.uri;
-        let position = params.text_document_position_params.position;
-
-        // Find the symbol at the cursor position
-        let symbol_name = {
-            let docs = self.documents.read().await;
-            match docs.get(&uri) {
-                Some(doc_state) => doc_state
-                    .symbols
-                    .iter()
-                    .find(|symbol| self.position_in_range(position, symbol.range))
-                    .map(|s| s.name.clone()),
-                None => None,
-            }
-        };
+        self.guard_request("textDocument/typeDefinition", async move {
+            // For SystemVerilog, we'll look for module type definitions
+            let uri = params.text_document_position_params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position_params.position;
+
+            // Find the symbol at the cursor position
+            let symbol_name = {
+                let docs = self.documents.read().await;
+                match docs.get(&uri) {
+                    Some(doc_state) => doc_state
+                        .symbols
+                        .iter()
+                        .find(|symbol| self.position_in_range(position, symbol.range))
+                        .map(|s| s.name.clone()),
+                    None => None,
+                }
+            };
 
-        if let Some(name) = symbol_name {
-            // Look for module and class type definitions
-            let workspace_symbols = self.workspace_symbols.read().await;
-            if let Some(symbol_list) = workspace_symbols.get(&name) {
-                let type_def = symbol_list
-                    .iter()
-                    .find(|s| matches!(s.symbol_type, SymbolType::Module | SymbolType::Class));
+            if let Some(name) = symbol_name {
+                // Look for module and class type definitions
+                let workspace_symbols = self.workspace_symbols.read().await;
+                if let Some(symbol_list) = workspace_symbols.get(&name) {
+                    let type_def = symbol_list.iter().find(|s| {
+                        matches!(
+                            s.symbol_type,
+                            SymbolType::Module | SymbolType::Interface | SymbolType::Class { .. }
+                        )
+                    });
 
-                if let Some(def_symbol) = type_def {
-                    return Ok(Some(GotoTypeDefinitionResponse::Scalar(Location {
-                        uri: def_symbol.uri.clone(),
-                        range: def_symbol.range,
-                    })));
+                    if let Some(def_symbol) = type_def {
+                        return Ok(Some(GotoTypeDefinitionResponse::Scalar(Location {
+                            uri: def_symbol.uri.clone(),
+                            range: def_symbol.range,
+                        })));
+                    }
                 }
             }
-        }
 
-        Ok(None)
+            Ok(None)
+        })
+        .await
     }
 
     async fn goto_implementation(
         &self,
         params: GotoImplementationParams,
     ) -> LspResult<Option<GotoImplementationResponse>> {
-        // For SystemVerilog, implementation is similar to definition
-        // We look for module instantiations
-        let uri = params.text_document_position_params.text_document.uri;
-        let position = params.text_document_position_params.position;
+        self.guard_request("textDocument/implementation", async move {
+            // For SystemVerilog, implementation is similar to definition
+            // We look for module instantiations
+            let uri = params.text_document_position_params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position_params.position;
+
+            // Find the symbol at the cursor position
+            let symbol_name = {
+                let docs = self.documents.read().await;
+                match docs.get(&uri) {
+                    Some(doc_state) => doc_state
+                        .symbols
+                        .iter()
+                        .find(|symbol| self.position_in_range(position, symbol.range))
+                        .map(|s| s.name.clone()),
+                    None => None,
+                }
+            };
 
-        // Find the symbol at the cursor position
-        let symbol_name = {
-            let docs = self.documents.read().await;
-            match docs.get(&uri) {
-                Some(doc_state) => doc_state
-                    .symbols
-                    .iter()
-                    .find(|symbol| self.position_in_range(position, symbol.range))
-                    .map(|s| s.name.clone()),
-                None => None,
+            if let Some(name) = symbol_name {
+                // Look for all module and class implementations with this name
+                let workspace_symbols = self.workspace_symbols.read().await;
+                if let Some(symbol_list) = workspace_symbols.get(&name) {
+                    let implementations: Vec<Location> = symbol_list
+                        .iter()
+                        .filter(|s| {
+                            matches!(
+                                s.symbol_type,
+                                SymbolType::Module
+                                    | SymbolType::Interface
+                                    | SymbolType::Class { .. }
+                            )
+                        })
+                        .map(|s| Location {
+                            uri: s.uri.clone(),
+                            range: s.range,
+                        })
+                        .collect();
+
+                    if !implementations.is_empty() {
+                        return Ok(Some(GotoImplementationResponse::Array(implementations)));
+                    }
+                }
             }
-        };
 
-        if let Some(name) = symbol_name {
-            // Look for all module and class implementations with this name
-            let workspace_symbols = self.workspace_symbols.read().await;
-            if let Some(symbol_list) = workspace_symbols.get(&name) {
-                let implementations: Vec<Location> = symbol_list
-                    .iter()
-                    .filter(|s| matches!(s.symbol_type, SymbolType::Module | SymbolType::Class))
-                    .map(|s| Location {
-                        uri: s.uri.clone(),
-                        range: s.range,
-                    })
-                    .collect();
+            Ok(None)
+        })
+        .await
+    }
 
-                if !implementations.is_empty() {
-                    return Ok(Some(GotoImplementationResponse::Array(implementations)));
+    async fn prepare_type_hierarchy(
+        &self,
+        params: TypeHierarchyPrepareParams,
+    ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+        self.guard_request("textDocument/prepareTypeHierarchy", async move {
+            let uri = params.text_document_position_params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position_params.position;
+
+            let symbol_name = {
+                let docs = self.documents.read().await;
+                match docs.get(&uri) {
+                    Some(doc_state) => doc_state
+                        .symbols
+                        .iter()
+                        .find(|symbol| self.position_in_range(position, symbol.range))
+                        .map(|s| s.name.clone()),
+                    None => None,
+                }
+            };
+
+            let Some(name) = symbol_name else {
+                return Ok(None);
+            };
+            let items: Vec<TypeHierarchyItem> = self
+                .class_symbols(&name)
+                .await
+                .iter()
+                .map(type_hierarchy_item)
+                .collect();
+            Ok((!items.is_empty()).then_some(items))
+        })
+        .await
+    }
+
+    async fn supertypes(
+        &self,
+        params: TypeHierarchySupertypesParams,
+    ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+        self.guard_request("typeHierarchy/supertypes", async move {
+            let mut items = Vec::new();
+            for class in self.class_symbols(&params.item.name).await {
+                let SymbolType::Class { bases } = &class.symbol_type else {
+                    continue;
+                };
+                for base in bases {
+                    items.extend(
+                        self.class_symbols(base)
+                            .await
+                            .iter()
+                            .map(type_hierarchy_item),
+                    );
                 }
             }
-        }
+            Ok(Some(items))
+        })
+        .await
+    }
 
-        Ok(None)
+    async fn subtypes(
+        &self,
+        params: TypeHierarchySubtypesParams,
+    ) -> LspResult<Option<Vec<TypeHierarchyItem>>> {
+        self.guard_request("typeHierarchy/subtypes", async move {
+            let workspace_symbols = self.workspace_symbols.read().await;
+            let mut items: Vec<TypeHierarchyItem> = workspace_symbols
+                .values()
+                .flatten()
+                .filter(|symbol| match &symbol.symbol_type {
+                    SymbolType::Class { bases } => bases.contains(&params.item.name),
+                    _ => false,
+                })
+                .map(type_hierarchy_item)
+                .collect();
+            items.sort_by(|a, b| a.name.cmp(&b.name));
+            Ok(Some(items))
+        })
+        .await
     }
 
     async fn references(&self, params: ReferenceParams) -> LspResult<Option<Vec<Location>>> {
-        let uri = params.text_document_position.text_document.uri;
-        let position = params.text_document_position.position;
+        self.guard_request("textDocument/references", async move {
+            let uri = params.text_document_position.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position.position;
 
-        // Find the symbol at the cursor position
-        let symbol_name = {
-            let docs = self.documents.read().await;
-            match docs.get(&uri) {
-                Some(doc_state) => doc_state
-                    .symbols
-                    .iter()
-                    .find(|symbol| self.position_in_range(position, symbol.range))
-                    .map(|s| s.name.clone()),
-                None => None,
-            }
-        };
+            // Find the symbol at the cursor position
+            let symbol_at_position = {
+                let docs = self.documents.read().await;
+                match docs.get(&uri) {
+                    Some(doc_state) => doc_state
+                        .symbols
+                        .iter()
+                        .find(|symbol| self.position_in_range(position, symbol.range))
+                        .map(|s| (s.name.clone(), s.symbol_type.clone())),
+                    None => None,
+                }
+            };
+
+            let Some((name, symbol_type)) = symbol_at_position else {
+                return Ok(None);
+            };
 
-        if let Some(name) = symbol_name {
             // Get all references to this symbol from the workspace
             let workspace_symbols = self.workspace_symbols.read().await;
-            if let Some(symbol_list) = workspace_symbols.get(&name) {
+            let Some(symbol_list) = workspace_symbols.get(&name) else {
+                return Ok(None);
+            };
+
+            if !matches!(
+                symbol_type,
+                SymbolType::Module | SymbolType::Interface | SymbolType::Instantiation { .. }
+            ) {
                 let references: Vec<Location> = symbol_list
                     .iter()
                     .map(|s| Location {
@@ -901,154 +1476,281 @@ impl LanguageServer for Backend {
 
                 return Ok(Some(references));
             }
-        }
 
-        Ok(None)
+            // A module is referenced where it is instantiated, not wherever its name appears
+            let mut sites: Vec<Symbol> = symbol_list
+                .iter()
+                .filter(|s| match s.symbol_type {
+                    SymbolType::Instantiation { .. } => true,
+                    SymbolType::Module | SymbolType::Interface => {
+                        params.context.include_declaration
+                    }
+                    _ => false,
+                })
+                .cloned()
+                .collect();
+            drop(workspace_symbols);
+            sites.sort_by(|a, b| {
+                (a.uri.as_str(), a.range.start.line, a.range.start.character).cmp(&(
+                    b.uri.as_str(),
+                    b.range.start.line,
+                    b.range.start.character,
+                ))
+            });
+
+            for site in &sites {
+                if let SymbolType::Instantiation { parent, instance } = &site.symbol_type {
+                    self.client
+                        .log_message(
+                            MessageType::INFO,
+                            format!(
+                                "Module '{}' is instantiated as {}.{} in {}",
+                                name, parent, instance, site.uri
+                            ),
+                        )
+                        .await;
+                }
+            }
+
+            Ok(Some(
+                sites
+                    .into_iter()
+                    .map(|s| Location {
+                        uri: s.uri,
+                        range: s.range,
+                    })
+                    .collect(),
+            ))
+        })
+        .await
     }
 
     async fn symbol(
         &self,
         params: WorkspaceSymbolParams,
     ) -> LspResult<Option<Vec<SymbolInformation>>> {
-        let query = params.query.to_lowercase();
+        self.guard_request("workspace/symbol", async move {
+            let query = params.query.to_lowercase();
 
-        let workspace_symbols = self.workspace_symbols.read().await;
-        let mut results = Vec::new();
+            let workspace_symbols = self.workspace_symbols.read().await;
+            let mut results = Vec::new();
+
+            // Search through all symbols
+            for (name, symbols) in workspace_symbols.iter() {
+                // Case-insensitive substring match
+                if name.to_lowercase().contains(&query) {
+                    for symbol in symbols {
+                        // Convert SymbolType to LSP SymbolKind and get display prefix
+                        let (kind, type_prefix) = match &symbol.symbol_type {
+                            SymbolType::Module => (SymbolKind::MODULE, "module"),
+                            SymbolType::Interface => (SymbolKind::INTERFACE, "interface"),
+                            SymbolType::Package => (SymbolKind::PACKAGE, "package"),
+                            SymbolType::Class { .. } => (SymbolKind::CLASS, "class"),
+                            SymbolType::Function => (SymbolKind::FUNCTION, "function"),
+                            SymbolType::ExternMethod => (SymbolKind::METHOD, "extern function"),
+                            SymbolType::Task => (SymbolKind::FUNCTION, "task"),
+                            SymbolType::Variable => (SymbolKind::VARIABLE, "variable"),
+                            SymbolType::Port => (SymbolKind::PROPERTY, "port"),
+                            SymbolType::Parameter => (SymbolKind::CONSTANT, "parameter"),
+                            SymbolType::Typedef => (SymbolKind::TYPE_PARAMETER, "typedef"),
+                            SymbolType::EnumMember => (SymbolKind::ENUM_MEMBER, "enum member"),
+                            SymbolType::Event => (SymbolKind::EVENT, "event"),
+                            SymbolType::Clocking => (SymbolKind::EVENT, "clocking"),
+                            SymbolType::Sequence => (SymbolKind::FUNCTION, "sequence"),
+                            SymbolType::Property => (SymbolKind::FUNCTION, "property"),
+                            SymbolType::Define => (SymbolKind::CONSTANT, "`define"),
+                            SymbolType::Include => (SymbolKind::FILE, "`include"),
+                            SymbolType::Instantiation { .. } => (SymbolKind::OBJECT, "instance of"),
+                        };
 
-        // Search through all symbols
-        for (name, symbols) in workspace_symbols.iter() {
-            // Case-insensitive substring match
-            if name.to_lowercase().contains(&query) {
-                for symbol in symbols {
-                    // Convert SymbolType to LSP SymbolKind and get display prefix
-                    let (kind, type_prefix) = match symbol.symbol_type {
-                        SymbolType::Module => (SymbolKind::MODULE, "module"),
-                        SymbolType::Class => (SymbolKind::CLASS, "class"),
-                        SymbolType::Function => (SymbolKind::FUNCTION, "function"),
-                        SymbolType::Task => (SymbolKind::FUNCTION, "task"),
-                        SymbolType::Variable => (SymbolKind::VARIABLE, "variable"),
-                        SymbolType::Port => (SymbolKind::PROPERTY, "port"),
-                        SymbolType::Parameter => (SymbolKind::CONSTANT, "parameter"),
-                        SymbolType::Define => (SymbolKind::CONSTANT, "`define"),
-                        SymbolType::Include => (SymbolKind::FILE, "`include"),
-                    };
+                        // Display name with type prefix (e.g., "module top")
+                        let display_name = format!("{} {}", type_prefix, symbol.name);
+
+                        #[allow(deprecated)]
+                        results.push(SymbolInformation {
+                            name: display_name,
+                            kind,
+                            tags: None,
+                            deprecated: None,
+                            location: Location {
+                                uri: symbol.uri.clone(),
+                                range: symbol.range,
+                            },
+                            container_name: match &symbol.symbol_type {
+                                SymbolType::Instantiation { parent, .. } => Some(parent.clone()),
+                                _ => None,
+                            },
+                        });
+                    }
+                }
+            }
 
-                    // Display name with type prefix (e.g., "module top")
-                    let display_name = format!("{} {}", type_prefix, symbol.name);
-
-                    #[allow(deprecated)]
-                    results.push(SymbolInformation {
-                        name: display_name,
-                        kind,
-                        tags: None,
-                        deprecated: None,
-                        location: Location {
-                            uri: symbol.uri.clone(),
-                            range: symbol.range,
-                        },
-                        container_name: None,
-                    });
-                }
+            if results.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(results))
             }
-        }
-
-        if results.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(results))
-        }
+        })
+        .await
     }
 
     async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
-        let uri = params.text_document_position_params.text_document.uri;
-        let position = params.text_document_position_params.position;
+        self.guard_request("textDocument/hover", async move {
+            let uri = params.text_document_position_params.text_document.uri;
+            let position = params.text_document_position_params.position;
+            self.ensure_document(&uri).await?;
 
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-        // Check if hovering over a system function call
-        if let Some(ast) = &doc_state.ast {
-            if let Some(hover_info) = self.find_hover_at_position(
-                &ast.items,
-                &ast.expr_arena,
-                &ast.stmt_arena,
-                &ast.module_item_arena,
-                &doc_state.content,
-                position,
-            ) {
-                return Ok(Some(hover_info));
+            // Check if hovering over a system function call
+            if let Some(ast) = &doc_state.ast {
+                if let Some(hover_info) = self.find_hover_at_position(
+                    &ast.items,
+                    &ast.expr_arena,
+                    &ast.stmt_arena,
+                    &ast.module_item_arena,
+                    &doc_state.content,
+                    position,
+                ) {
+                    return Ok(Some(hover_info));
+                }
             }
-        }
 
-        // Check if hovering over a symbol (module, variable, etc.)
-        if let Some(symbol) = doc_state
-            .symbols
-            .iter()
-            .find(|s| self.position_in_range(position, s.range))
-        {
-            let hover_text = match symbol.symbol_type {
-                SymbolType::Module => format!("```systemverilog\nmodule {}\n```", symbol.name),
-                SymbolType::Class => format!("```systemverilog\nclass {}\n```", symbol.name),
-                SymbolType::Function => format!("```systemverilog\nfunction {}\n```", symbol.name),
-                SymbolType::Task => format!("```systemverilog\ntask {}\n```", symbol.name),
-                SymbolType::Variable => format!("```systemverilog\n{}\n```", symbol.name),
-                SymbolType::Port => format!("```systemverilog\nport {}\n```", symbol.name),
-                SymbolType::Parameter => {
-                    format!("```systemverilog\nparameter {}\n```", symbol.name)
-                }
-                SymbolType::Define => format!("```systemverilog\n`define {}\n```", symbol.name),
-                SymbolType::Include => {
-                    let include_path = std::path::Path::new(&symbol.name);
+            // Check if hovering over a delay like #10
+            if let Some(ast) = &doc_state.ast {
+                if let Some(hover) = self.find_delay_hover(ast, &doc_state.content, position) {
+                    return Ok(Some(hover));
+                }
+            }
 
-                    // Try to resolve the include path
-                    let resolved = if include_path.is_absolute() {
-                        Some(include_path.to_path_buf())
-                    } else {
-                        // Try to resolve relative to the current file's directory
-                        uri.to_file_path().ok().and_then(|current_file| {
-                            let current_dir = current_file.parent()?;
-                            let candidate = current_dir.join(&symbol.name);
-                            if candidate.exists() {
-                                return Some(candidate);
-                            }
+            // Check if hovering over a symbol (module, variable, etc.)
+            if let Some(found) = doc_state
+                .symbols
+                .iter()
+                .find(|s| self.position_in_range(position, s.range))
+            {
+                // A name imported from a package is shown as the package declares it
+                let imported = self.imported_declaration(&docs, &uri, &found.name, position);
+                let symbol = imported
+                    .as_ref()
+                    .and_then(|(_, location)| {
+                        docs.get(&location.uri)?.symbols.iter().find(|symbol| {
+                            symbol.name == found.name && symbol.range == location.range
+                        })
+                    })
+                    .unwrap_or(found);
+                let hover_text = match &symbol.symbol_type {
+                    SymbolType::Module => format!("```systemverilog\nmodule {}\n```", symbol.name),
+                    SymbolType::Interface => {
+                        format!("```systemverilog\ninterface {}\n```", symbol.name)
+                    }
+                    SymbolType::Package => {
+                        format!("```systemverilog\npackage {}\n```", symbol.name)
+                    }
+                    SymbolType::Class { .. } => {
+                        format!("```systemverilog\nclass {}\n```", symbol.name)
+                    }
+                    SymbolType::Function => {
+                        format!("```systemverilog\nfunction {}\n```", symbol.name)
+                    }
+                    SymbolType::ExternMethod => {
+                        format!("```systemverilog\nextern function {}\n```", symbol.name)
+                    }
+                    SymbolType::Task => format!("```systemverilog\ntask {}\n```", symbol.name),
+                    SymbolType::Variable | SymbolType::EnumMember => {
+                        format!("```systemverilog\n{}\n```", symbol.name)
+                    }
+                    SymbolType::Port => format!("```systemverilog\nport {}\n```", symbol.name),
+                    SymbolType::Parameter => {
+                        format!("```systemverilog\nparameter {}\n```", symbol.name)
+                    }
+                    SymbolType::Typedef => {
+                        format!("```systemverilog\ntypedef {}\n```", symbol.name)
+                    }
+                    SymbolType::Event => format!("```systemverilog\nevent {}\n```", symbol.name),
+                    SymbolType::Clocking => {
+                        format!("```systemverilog\nclocking {}\n```", symbol.name)
+                    }
+                    SymbolType::Sequence => {
+                        format!("```systemverilog\nsequence {}\n```", symbol.name)
+                    }
+                    SymbolType::Property => {
+                        format!("```systemverilog\nproperty {}\n```", symbol.name)
+                    }
+                    SymbolType::Define => {
+                        let defines = self
+                            .config
+                            .read()
+                            .await
+                            .defines
+                            .iter()
+                            .map(|(name, value)| (name.clone(), value.clone().unwrap_or_default()))
+                            .collect();
+                        let macros = doc_state.ast.as_ref().map(|ast| {
+                            SystemVerilogParser::new(Vec::new(), defines).macro_definitions(ast)
+                        });
+                        define_hover_text(
+                            &symbol.name,
+                            macros.as_ref().and_then(|macros| macros.get(&symbol.name)),
+                        )
+                    }
+                    SymbolType::Include => {
+                        let include_path = std::path::Path::new(&symbol.name);
 
-                            // Try looking in common include directories relative to current file
-                            for include_dir in &["include", "../include", "../../include"] {
-                                let candidate = current_dir.join(include_dir).join(&symbol.name);
+                        // Try to resolve the include path
+                        let resolved = if include_path.is_absolute() {
+                            Some(include_path.to_path_buf())
+                        } else {
+                            // Try to resolve relative to the current file's directory
+                            uri.to_file_path().ok().and_then(|current_file| {
+                                let current_dir = current_file.parent()?;
+                                let candidate = current_dir.join(&symbol.name);
                                 if candidate.exists() {
                                     return Some(candidate);
                                 }
-                            }
-                            None
-                        })
-                    };
 
-                    // Format the path for display (relative to workspace root if possible)
-                    let display_path = if let Some(resolved_path) = resolved {
-                        // Canonicalize to resolve .. and . components
-                        let canonical = resolved_path.canonicalize().unwrap_or(resolved_path);
-
-                        // Try to make it relative to workspace root
-                        if let Ok(current_file) = uri.to_file_path() {
-                            if let Some(current_dir) = current_file.parent() {
-                                // Find workspace root by looking for .git or .sv-lsp.toml
-                                let mut workspace_root = current_dir;
-                                while let Some(parent) = workspace_root.parent() {
-                                    if parent.join(".git").exists()
-                                        || parent.join(".sv-lsp.toml").exists()
-                                    {
-                                        workspace_root = parent;
-                                        break;
+                                // Try looking in common include directories relative to current file
+                                for include_dir in &["include", "../include", "../../include"] {
+                                    let candidate =
+                                        current_dir.join(include_dir).join(&symbol.name);
+                                    if candidate.exists() {
+                                        return Some(candidate);
                                     }
-                                    workspace_root = parent;
                                 }
+                                None
+                            })
+                        };
+
+                        // Format the path for display (relative to workspace root if possible)
+                        let display_path = if let Some(resolved_path) = resolved {
+                            // Canonicalize to resolve .. and . components
+                            let canonical = resolved_path.canonicalize().unwrap_or(resolved_path);
+
+                            // Try to make it relative to workspace root
+                            if let Ok(current_file) = uri.to_file_path() {
+                                if let Some(current_dir) = current_file.parent() {
+                                    // Find workspace root by looking for .git or .sv-lsp.toml
+                                    let mut workspace_root = current_dir;
+                                    while let Some(parent) = workspace_root.parent() {
+                                        if parent.join(".git").exists()
+                                            || parent.join(".sv-lsp.toml").exists()
+                                        {
+                                            workspace_root = parent;
+                                            break;
+                                        }
+                                        workspace_root = parent;
+                                    }
 
-                                // Make path relative to workspace root
-                                if let Ok(rel) = canonical.strip_prefix(workspace_root) {
-                                    rel.display().to_string()
+                                    // Make path relative to workspace root
+                                    if let Ok(rel) = canonical.strip_prefix(workspace_root) {
+                                        rel.display().to_string()
+                                    } else {
+                                        canonical.display().to_string()
+                                    }
                                 } else {
                                     canonical.display().to_string()
                                 }
@@ -1056,152 +1758,240 @@ impl LanguageServer for Backend {
                                 canonical.display().to_string()
                             }
                         } else {
-                            canonical.display().to_string()
-                        }
-                    } else {
-                        // Couldn't resolve, just show the original path
-                        symbol.name.clone()
-                    };
+                            // Couldn't resolve, just show the original path
+                            symbol.name.clone()
+                        };
 
-                    format!("```systemverilog\n`include \"{}\"\n```", display_path)
-                }
-            };
+                        format!("```systemverilog\n`include \"{}\"\n```", display_path)
+                    }
+                    SymbolType::Instantiation { parent, instance } => format!(
+                        "```systemverilog\nmodule {}\n```\nInstance `{}` in module `{}`",
+                        symbol.name, instance, parent
+                    ),
+                };
+                let hover_text = match imported {
+                    Some((package, _)) => {
+                        format!("{}\nImported from package `{}`", hover_text, package)
+                    }
+                    None => hover_text,
+                };
 
-            return Ok(Some(Hover {
-                contents: HoverContents::Markup(MarkupContent {
-                    kind: MarkupKind::Markdown,
-                    value: hover_text,
-                }),
-                range: Some(symbol.range),
-            }));
-        }
+                return Ok(Some(Hover {
+                    contents: HoverContents::Markup(MarkupContent {
+                        kind: MarkupKind::Markdown,
+                        value: hover_text,
+                    }),
+                    range: Some(found.range),
+                }));
+            }
 
-        Ok(None)
+            Ok(None)
+        })
+        .await
     }
 
     async fn document_symbol(
         &self,
         params: DocumentSymbolParams,
     ) -> LspResult<Option<DocumentSymbolResponse>> {
-        let uri = params.text_document.uri;
+        self.guard_request("textDocument/documentSymbol", async move {
+            let uri = params.text_document.uri;
+            self.ensure_document(&uri).await?;
 
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-        let mut symbols = Vec::new();
+            // Build the outline from the AST when the document parses
+            if let Some(ast) = &doc_state.ast {
+                let symbols = self.document_symbols_from_items(&ast.items, ast, &doc_state.content);
+                return Ok((!symbols.is_empty()).then_some(DocumentSymbolResponse::Nested(symbols)));
+            }
 
-        // Convert our symbols to LSP DocumentSymbol format
-        for symbol in &doc_state.symbols {
-            let kind = match symbol.symbol_type {
-                SymbolType::Module => SymbolKind::MODULE,
-                SymbolType::Class => SymbolKind::CLASS,
-                SymbolType::Function => SymbolKind::FUNCTION,
-                SymbolType::Task => SymbolKind::FUNCTION,
-                SymbolType::Variable => SymbolKind::VARIABLE,
-                SymbolType::Port => SymbolKind::PROPERTY,
-                SymbolType::Parameter => SymbolKind::CONSTANT,
-                SymbolType::Define => SymbolKind::CONSTANT,
-                SymbolType::Include => SymbolKind::FILE,
-            };
+            let mut symbols = Vec::new();
+
+            // Otherwise fall back to a flat list of the symbols found so far
+            for symbol in &doc_state.symbols {
+                let kind = match symbol.symbol_type {
+                    SymbolType::Module => SymbolKind::MODULE,
+                    SymbolType::Interface => SymbolKind::INTERFACE,
+                    SymbolType::Package => SymbolKind::PACKAGE,
+                    SymbolType::Class { .. } => SymbolKind::CLASS,
+                    SymbolType::Function => SymbolKind::FUNCTION,
+                    SymbolType::ExternMethod => SymbolKind::METHOD,
+                    SymbolType::Task => SymbolKind::FUNCTION,
+                    SymbolType::Variable => SymbolKind::VARIABLE,
+                    SymbolType::Port => SymbolKind::PROPERTY,
+                    SymbolType::Parameter => SymbolKind::CONSTANT,
+                    SymbolType::Typedef => SymbolKind::TYPE_PARAMETER,
+                    SymbolType::EnumMember => SymbolKind::ENUM_MEMBER,
+                    SymbolType::Event | SymbolType::Clocking => SymbolKind::EVENT,
+                    SymbolType::Sequence | SymbolType::Property => SymbolKind::FUNCTION,
+                    SymbolType::Define => SymbolKind::CONSTANT,
+                    SymbolType::Include => SymbolKind::FILE,
+                    SymbolType::Instantiation { .. } => SymbolKind::OBJECT,
+                };
 
-            #[allow(deprecated)]
-            symbols.push(DocumentSymbol {
-                name: symbol.name.clone(),
-                detail: None,
-                kind,
-                tags: None,
-                deprecated: None,
-                range: symbol.range,
-                selection_range: symbol.range,
-                children: None,
-            });
-        }
+                #[allow(deprecated)]
+                symbols.push(DocumentSymbol {
+                    name: symbol.name.clone(),
+                    detail: None,
+                    kind,
+                    tags: None,
+                    deprecated: None,
+                    range: symbol.range,
+                    selection_range: symbol.range,
+                    children: None,
+                });
+            }
 
-        if symbols.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(DocumentSymbolResponse::Nested(symbols)))
-        }
+            if symbols.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(DocumentSymbolResponse::Nested(symbols)))
+            }
+        })
+        .await
     }
 
     async fn document_highlight(
         &self,
         params: DocumentHighlightParams,
     ) -> LspResult<Option<Vec<DocumentHighlight>>> {
-        let uri = params.text_document_position_params.text_document.uri;
-        let position = params.text_document_position_params.position;
-
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+        self.guard_request("textDocument/documentHighlight", async move {
+            let uri = params.text_document_position_params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position_params.position;
 
-        // Find the symbol at the cursor position
-        let symbol_at_position = doc_state
-            .symbols
-            .iter()
-            .find(|symbol| self.position_in_range(position, symbol.range));
-
-        if let Some(symbol) = symbol_at_position {
-            let mut highlights = Vec::new();
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-            // Find all occurrences of this symbol in the current document
-            for other_symbol in &doc_state.symbols {
-                if other_symbol.name == symbol.name {
-                    highlights.push(DocumentHighlight {
-                        range: other_symbol.range,
-                        kind: Some(DocumentHighlightKind::TEXT),
-                    });
+            // Find the symbol at the cursor position
+            let symbol_at_position = doc_state
+                .symbols
+                .iter()
+                .find(|symbol| self.position_in_range(position, symbol.range));
+
+            if let Some(symbol) = symbol_at_position {
+                let mut highlights = Vec::new();
+
+                // Find all occurrences of this symbol in the current document
+                for other_symbol in &doc_state.symbols {
+                    if other_symbol.name == symbol.name {
+                        highlights.push(DocumentHighlight {
+                            range: other_symbol.range,
+                            kind: Some(DocumentHighlightKind::TEXT),
+                        });
+                    }
                 }
-            }
 
-            if highlights.is_empty() {
-                Ok(None)
+                if highlights.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some(highlights))
+                }
             } else {
-                Ok(Some(highlights))
+                Ok(None)
             }
-        } else {
-            Ok(None)
-        }
+        })
+        .await
     }
 
     async fn selection_range(
         &self,
         params: SelectionRangeParams,
     ) -> LspResult<Option<Vec<SelectionRange>>> {
-        let uri = params.text_document.uri;
-        let positions = params.positions;
+        self.guard_request("textDocument/selectionRange", async move {
+            let uri = params.text_document.uri;
+            let positions = params.positions;
+            self.ensure_document(&uri).await?;
 
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-        if doc_state.ast.is_none() {
-            return Ok(None);
-        }
+            if doc_state.ast.is_none() {
+                return Ok(None);
+            }
 
-        let ast = doc_state.ast.as_ref().unwrap();
-        let content = &doc_state.content;
+            let ast = doc_state.ast.as_ref().unwrap();
+            let content = &doc_state.content;
 
-        let mut results = Vec::new();
+            let mut results = Vec::new();
 
-        for position in positions {
-            if let Some(selection) = self.find_selection_range_at_position(ast, content, position) {
-                results.push(selection);
+            for position in positions {
+                if let Some(selection) =
+                    self.find_selection_range_at_position(ast, content, position)
+                {
+                    results.push(selection);
+                }
             }
-        }
 
-        if results.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(results))
-        }
+            if results.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(results))
+            }
+        })
+        .await
+    }
+
+    async fn code_action(&self, params: CodeActionParams) -> LspResult<Option<CodeActionResponse>> {
+        self.guard_request("textDocument/codeAction", async move {
+            if self.config.read().await.split_always_blocks == Some(false) {
+                return Ok(None);
+            }
+            let uri = params.text_document.uri;
+            self.ensure_document(&uri).await?;
+
+            let docs = self.documents.read().await;
+            let Some(doc_state) = docs.get(&uri) else {
+                return Ok(None);
+            };
+            let Some(ast) = doc_state.ast.as_ref() else {
+                return Ok(None);
+            };
+            let content = &doc_state.content;
+
+            let offset = sv_parser::position::position_to_offset(
+                content,
+                params.range.start,
+                PositionEncoding::Utf16,
+            );
+            let Some(edit) = sv_parser::refactor::procedural_block_at(ast, offset)
+                .and_then(|block| sv_parser::refactor::split_always_block(ast, content, block))
+            else {
+                return Ok(None);
+            };
+            let Some(range) = self.span_to_range(content, edit.span) else {
+                return Ok(None);
+            };
+
+            let action = CodeAction {
+                title: "Split always block by driven variables".to_string(),
+                kind: Some(CodeActionKind::REFACTOR_REWRITE),
+                edit: Some(WorkspaceEdit {
+                    changes: Some(HashMap::from([(
+                        uri.clone(),
+                        vec![TextEdit {
+                            range,
+                            new_text: edit.new_text,
+                        }],
+                    )])),
+                    ..WorkspaceEdit::default()
+                }),
+                ..CodeAction::default()
+            };
+            Ok(Some(vec![CodeActionOrCommand::CodeAction(action)]))
+        })
+        .await
     }
 
     async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
@@ -1211,12 +2001,18 @@ impl LanguageServer for Backend {
 
         // Try to extract configuration from the settings
         if let Ok(config) = serde_json::from_value::<ServerConfig>(params.settings) {
-            let mut stored_config = self.config.write().await;
-            *stored_config = config;
+            {
+                let mut stored_config = self.config.write().await;
+                *stored_config = config;
+            }
 
             self.client
                 .log_message(MessageType::INFO, "Configuration updated successfully")
                 .await;
+
+            // Include directories and defines may have changed; restart indexing
+            let backend = self.clone();
+            tokio::spawn(async move { backend.index_workspace().await });
         } else {
             self.client
                 .log_message(MessageType::WARNING, "Failed to parse new configuration")
@@ -1225,297 +2021,829 @@ impl LanguageServer for Backend {
     }
 
     async fn completion(&self, params: CompletionParams) -> LspResult<Option<CompletionResponse>> {
-        let uri = params.text_document_position.text_document.uri;
-        let position = params.text_document_position.position;
+        self.guard_request("textDocument/completion", async move {
+            let uri = params.text_document_position.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position.position;
 
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-        let mut items = Vec::new();
+            let mut items = Vec::new();
 
-        // Get the current line to determine context
-        let lines: Vec<&str> = doc_state.content.lines().collect();
-        let current_line = if (position.line as usize) < lines.len() {
-            lines[position.line as usize]
-        } else {
-            ""
-        };
-        let prefix = &current_line[..position.character.min(current_line.len() as u32) as usize];
-
-        // Determine what kind of completion to provide based on context
-        let is_after_dollar = prefix.trim_end().ends_with('$');
-        let is_after_backtick = prefix.trim_end().ends_with('`');
-        let is_after_dot = prefix.trim_end().ends_with('.');
-
-        // Check if we're in the middle of typing a system function (e.g., "$dis")
-        let is_typing_system_function = prefix
-            .trim_end()
-            .chars()
-            .rev()
-            .take_while(|c| c.is_alphanumeric() || *c == '_')
-            .count()
-            > 0
-            && prefix.trim_end().contains('$');
-
-        // 1. System function/task completions (after '$' or while typing a system function)
-        if is_after_dollar || is_typing_system_function {
-            items.extend(self.get_system_function_completions());
-        }
+            // Get the current line to determine context
+            let lines: Vec<&str> = doc_state.content.lines().collect();
+            let current_line = if (position.line as usize) < lines.len() {
+                lines[position.line as usize]
+            } else {
+                ""
+            };
+            let prefix =
+                &current_line[..position.character.min(current_line.len() as u32) as usize];
+
+            // Determine what kind of completion to provide based on context
+            let is_after_dollar = prefix.trim_end().ends_with('$');
+            let is_after_backtick = prefix.trim_end().ends_with('`');
+            let is_after_dot = prefix.trim_end().ends_with('.');
+
+            // Check if we're in the middle of typing a system function (e.g., "$dis")
+            let is_typing_system_function = prefix
+                .trim_end()
+                .chars()
+                .rev()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .count()
+                > 0
+                && prefix.trim_end().contains('$');
+
+            // 1. System function/task completions (after '$' or while typing a system function)
+            if is_after_dollar || is_typing_system_function {
+                items.extend(self.get_system_function_completions());
+            }
 
-        // 2. Preprocessor directive completions (after '`')
-        if is_after_backtick {
-            items.extend(self.get_preprocessor_completions());
-        }
+            // 2. Preprocessor directive completions (after '`')
+            if is_after_backtick {
+                items.extend(self.get_preprocessor_completions());
+            }
 
-        // 3. Member access completions (after '.')
-        if is_after_dot {
-            // For now, we'll provide common class members
-            // In the future, this could be context-aware based on the object type
-            items.extend(self.get_member_completions());
-        }
+            // 3. Member access completions (after '.')
+            if is_after_dot {
+                // For now, we'll provide common class members
+                // In the future, this could be context-aware based on the object type
+                items.extend(self.get_member_completions());
+            }
 
-        // 4. Symbol completions (variables, modules, classes, etc.)
-        if !is_after_dollar && !is_after_backtick && !is_typing_system_function {
-            items.extend(self.get_symbol_completions().await);
-        }
+            // 4. Symbol completions (variables, modules, classes, etc.)
+            if !is_after_dollar && !is_after_backtick && !is_typing_system_function {
+                items.extend(self.get_symbol_completions().await);
+            }
 
-        // 5. Keyword completions (always include unless after special character)
-        if !is_after_dollar && !is_after_backtick && !is_after_dot && !is_typing_system_function {
-            items.extend(self.get_keyword_completions());
-        }
+            // 5. Keyword completions (always include unless after special character)
+            if !is_after_dollar && !is_after_backtick && !is_after_dot && !is_typing_system_function
+            {
+                items.extend(self.get_keyword_completions());
+            }
 
-        if items.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(CompletionResponse::Array(items)))
-        }
+            if items.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(CompletionResponse::Array(items)))
+            }
+        })
+        .await
     }
 
     async fn signature_help(
         &self,
         params: SignatureHelpParams,
     ) -> LspResult<Option<SignatureHelp>> {
-        let uri = params.text_document_position_params.text_document.uri;
-        let position = params.text_document_position_params.position;
-
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+        self.guard_request("textDocument/signatureHelp", async move {
+            let uri = params.text_document_position_params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let position = params.text_document_position_params.position;
 
-        // Get the current line and extract function name and parameter position
-        let lines: Vec<&str> = doc_state.content.lines().collect();
-        let current_line = if (position.line as usize) < lines.len() {
-            lines[position.line as usize]
-        } else {
-            return Ok(None);
-        };
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-        // Extract text up to cursor position
-        let text_before_cursor =
-            &current_line[..position.character.min(current_line.len() as u32) as usize];
-
-        // Find the function call by looking backwards for the function name
-        // Look for patterns like $function_name( or function_name(
-        let (function_name, active_parameter) =
-            self.extract_function_call_info(text_before_cursor)?;
-
-        // Check if it's a system function
-        if let Some(info) = get_system_function_info(&function_name) {
-            let signature_info = SignatureInformation {
-                label: info.signature.clone(),
-                documentation: Some(Documentation::String(info.description.clone())),
-                parameters: self.parse_parameters(&info.signature),
-                active_parameter: None, // LSP will compute this from cursor position
+            // Get the current line and extract function name and parameter position
+            let lines: Vec<&str> = doc_state.content.lines().collect();
+            let current_line = if (position.line as usize) < lines.len() {
+                lines[position.line as usize]
+            } else {
+                return Ok(None);
             };
 
-            return Ok(Some(SignatureHelp {
-                signatures: vec![signature_info],
-                active_signature: Some(0),
-                active_parameter: Some(active_parameter),
-            }));
-        }
+            // Extract text up to cursor position
+            let text_before_cursor =
+                &current_line[..position.character.min(current_line.len() as u32) as usize];
+
+            // Find the function call by looking backwards for the function name
+            // Look for patterns like $function_name( or function_name(
+            let (function_name, active_parameter) =
+                self.extract_function_call_info(text_before_cursor)?;
+
+            // Check if it's a system function
+            if let Some(info) = get_system_function_info(&function_name) {
+                let signature_info = SignatureInformation {
+                    label: info.signature.clone(),
+                    documentation: Some(Documentation::String(info.description.clone())),
+                    parameters: self.parse_parameters(&info.signature),
+                    active_parameter: None, // LSP will compute this from cursor position
+                };
 
-        Ok(None)
+                return Ok(Some(SignatureHelp {
+                    signatures: vec![signature_info],
+                    active_signature: Some(0),
+                    active_parameter: Some(active_parameter),
+                }));
+            }
+
+            Ok(None)
+        })
+        .await
     }
 
     async fn inlay_hint(&self, params: InlayHintParams) -> LspResult<Option<Vec<InlayHint>>> {
-        let uri = params.text_document.uri;
-        let range = params.range;
-
-        let docs = self.documents.read().await;
-        let doc_state = match docs.get(&uri) {
-            Some(state) => state,
-            None => return Ok(None),
-        };
+        self.guard_request("textDocument/inlayHint", async move {
+            let uri = params.text_document.uri;
+            self.ensure_document(&uri).await?;
+            let range = params.range;
 
-        let mut hints = Vec::new();
-
-        // Get lines within the requested range
-        let lines: Vec<&str> = doc_state.content.lines().collect();
-        let start_line = range.start.line as usize;
-        let end_line = range.end.line.min(lines.len() as u32 - 1) as usize;
-
-        // Scan each line for function calls
-        for (line_idx, line) in lines.iter().enumerate().take(end_line + 1).skip(start_line) {
-            // Find system function calls (pattern: $function_name(...))
-            let mut char_idx = 0;
-            while char_idx < line.len() {
-                if line[char_idx..].starts_with('$') {
-                    // Found potential system function
-                    if let Some(end_idx) = line[char_idx..].find('(') {
-                        let func_name_with_dollar = &line[char_idx..char_idx + end_idx];
-                        let func_name =
-                            func_name_with_dollar.trim().strip_prefix('$').unwrap_or("");
-
-                        // Check if it's a known system function
-                        if let Some(info) = get_system_function_info(func_name) {
-                            // Parse the parameters from the signature
-                            if let Some(params) = self.parse_parameters(&info.signature) {
-                                // Find the arguments in the actual function call
-                                let open_paren_pos = char_idx + end_idx;
-                                if let Some(close_paren) =
-                                    self.find_matching_paren(line, open_paren_pos)
-                                {
-                                    let args_str = &line[open_paren_pos + 1..close_paren];
-                                    let args: Vec<&str> = if args_str.trim().is_empty() {
-                                        Vec::new()
-                                    } else {
-                                        args_str.split(',').map(|s| s.trim()).collect()
-                                    };
+            let docs = self.documents.read().await;
+            let doc_state = match docs.get(&uri) {
+                Some(state) => state,
+                None => return Ok(None),
+            };
 
-                                    // Create inlay hints for each argument
-                                    let mut arg_offset = open_paren_pos + 1;
-                                    for (idx, (arg, param)) in
-                                        args.iter().zip(params.iter()).enumerate()
+            let mut hints = Vec::new();
+
+            // Get lines within the requested range
+            let lines: Vec<&str> = doc_state.content.lines().collect();
+            let start_line = range.start.line as usize;
+            let end_line = range.end.line.min(lines.len() as u32 - 1) as usize;
+
+            // Scan each line for function calls
+            for (line_idx, line) in lines.iter().enumerate().take(end_line + 1).skip(start_line) {
+                // Find system function calls (pattern: $function_name(...))
+                let mut char_idx = 0;
+                while char_idx < line.len() {
+                    if line[char_idx..].starts_with('$') {
+                        // Found potential system function
+                        if let Some(end_idx) = line[char_idx..].find('(') {
+                            let func_name_with_dollar = &line[char_idx..char_idx + end_idx];
+                            let func_name =
+                                func_name_with_dollar.trim().strip_prefix('$').unwrap_or("");
+
+                            // Check if it's a known system function
+                            if let Some(info) = get_system_function_info(func_name) {
+                                // Parse the parameters from the signature
+                                if let Some(params) = self.parse_parameters(&info.signature) {
+                                    // Find the arguments in the actual function call
+                                    let open_paren_pos = char_idx + end_idx;
+                                    if let Some(close_paren) =
+                                        self.find_matching_paren(line, open_paren_pos)
                                     {
-                                        // Skip whitespace
-                                        while arg_offset < line.len()
-                                            && line
-                                                .chars()
-                                                .nth(arg_offset)
-                                                .map_or(false, |c| c.is_whitespace())
-                                        {
-                                            arg_offset += 1;
-                                        }
+                                        let args_str = &line[open_paren_pos + 1..close_paren];
+                                        let args: Vec<&str> = if args_str.trim().is_empty() {
+                                            Vec::new()
+                                        } else {
+                                            args_str.split(',').map(|s| s.trim()).collect()
+                                        };
 
-                                        if arg_offset >= line.len() {
-                                            break;
-                                        }
+                                        // Create inlay hints for each argument
+                                        let mut arg_offset = open_paren_pos + 1;
+                                        for (idx, (arg, param)) in
+                                            args.iter().zip(params.iter()).enumerate()
+                                        {
+                                            // Skip whitespace
+                                            while arg_offset < line.len()
+                                                && line
+                                                    .chars()
+                                                    .nth(arg_offset)
+                                                    .is_some_and(|c| c.is_whitespace())
+                                            {
+                                                arg_offset += 1;
+                                            }
 
-                                        // Extract parameter name from ParameterLabel
-                                        let param_name = match &param.label {
-                                            ParameterLabel::Simple(s) => {
-                                                // Extract just the parameter name (last word)
-                                                s.split_whitespace().last().unwrap_or("")
+                                            if arg_offset >= line.len() {
+                                                break;
                                             }
-                                            ParameterLabel::LabelOffsets(_) => "",
-                                        };
 
-                                        if !param_name.is_empty() {
-                                            hints.push(InlayHint {
-                                                position: Position {
-                                                    line: line_idx as u32,
-                                                    character: arg_offset as u32,
-                                                },
-                                                label: InlayHintLabel::String(format!(
-                                                    "{}:",
-                                                    param_name
-                                                )),
-                                                kind: Some(InlayHintKind::PARAMETER),
-                                                text_edits: None,
-                                                tooltip: None,
-                                                padding_left: None,
-                                                padding_right: Some(true),
-                                                data: None,
-                                            });
-                                        }
+                                            // Extract parameter name from ParameterLabel
+                                            let param_name = match &param.label {
+                                                ParameterLabel::Simple(s) => {
+                                                    // Extract just the parameter name (last word)
+                                                    s.split_whitespace().last().unwrap_or("")
+                                                }
+                                                ParameterLabel::LabelOffsets(_) => "",
+                                            };
+
+                                            if !param_name.is_empty() {
+                                                hints.push(InlayHint {
+                                                    position: Position {
+                                                        line: line_idx as u32,
+                                                        character: arg_offset as u32,
+                                                    },
+                                                    label: InlayHintLabel::String(format!(
+                                                        "{}:",
+                                                        param_name
+                                                    )),
+                                                    kind: Some(InlayHintKind::PARAMETER),
+                                                    text_edits: None,
+                                                    tooltip: None,
+                                                    padding_left: None,
+                                                    padding_right: Some(true),
+                                                    data: None,
+                                                });
+                                            }
 
-                                        // Move past this argument
-                                        arg_offset += arg.len();
-                                        // Skip comma if not last argument
-                                        if idx < args.len() - 1 {
-                                            if let Some(comma_pos) = line[arg_offset..].find(',') {
-                                                arg_offset += comma_pos + 1;
+                                            // Move past this argument
+                                            arg_offset += arg.len();
+                                            // Skip comma if not last argument
+                                            if idx < args.len() - 1 {
+                                                if let Some(comma_pos) =
+                                                    line[arg_offset..].find(',')
+                                                {
+                                                    arg_offset += comma_pos + 1;
+                                                }
                                             }
                                         }
                                     }
                                 }
                             }
+                            char_idx += end_idx + 1;
+                        } else {
+                            char_idx += 1;
                         }
-                        char_idx += end_idx + 1;
                     } else {
                         char_idx += 1;
                     }
-                } else {
-                    char_idx += 1;
                 }
             }
-        }
 
-        if hints.is_empty() {
-            Ok(None)
-        } else {
-            Ok(Some(hints))
-        }
+            if hints.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(hints))
+            }
+        })
+        .await
     }
 }
 
 impl Backend {
     async fn on_change(&self, params: TextDocumentItem) {
-        // Parse and cache AST, extract symbols, and validate
-        let (diagnostics, ast, symbols) = self
-            .parse_and_analyze_document(&params.text, &params.uri)
+        // Record the new text before parsing it, so that an edit arriving
+        // while it is parsed supersedes it and its result is dropped
+        {
+            let mut docs = self.documents.write().await;
+            let doc_state = docs
+                .entry(params.uri.clone())
+                .or_insert_with(|| DocumentState {
+                    content: String::new(),
+                    ast: None,
+                    symbols: Vec::new(),
+                    version: params.version,
+                    open: true,
+                    last_used: Instant::now(),
+                });
+            doc_state.content = params.text;
+            doc_state.version = params.version;
+            doc_state.open = true;
+            doc_state.last_used = Instant::now();
+        }
+
+        self.reanalyze_open_document(&params.uri, params.version)
+            .await;
+    }
+
+    // Swap a file's entries in the workspace symbol index
+    fn replace_workspace_symbols(
+        &self,
+        workspace_symbols: &mut HashMap<String, Vec<Symbol>>,
+        uri: &Url,
+        old_symbols: impl IntoIterator<Item = Symbol>,
+        new_symbols: Vec<Symbol>,
+    ) {
+        for old_symbol in old_symbols {
+            if let Some(symbol_list) = workspace_symbols.get_mut(&old_symbol.name) {
+                symbol_list.retain(|s| &s.uri != uri);
+                if symbol_list.is_empty() {
+                    workspace_symbols.remove(&old_symbol.name);
+                }
+            }
+        }
+
+        // Add new symbols (skip Include symbols as they're file-specific)
+        for symbol in new_symbols {
+            if !matches!(symbol.symbol_type, SymbolType::Include) {
+                workspace_symbols
+                    .entry(symbol.name.clone())
+                    .or_default()
+                    .push(symbol);
+            }
+        }
+    }
+
+    /// Re-analyze the open documents, then index every source file in the workspace
+    ///
+    /// Runs on a pool of tokio tasks and returns once the run finishes or is
+    /// cancelled by a newer run.
+    pub async fn index_workspace(&self) {
+        let run = self.indexing.start_run();
+
+        let mut queue = IndexQueue::default();
+        {
+            let docs = self.documents.read().await;
+            for (uri, doc_state) in docs.iter() {
+                if doc_state.open {
+                    let version = doc_state.version;
+                    queue.push(uri.clone(), JobPriority::OpenDocument { version });
+                }
+            }
+        }
+        for path in indexer::collect_source_files(&self.source_roots().await) {
+            if let Ok(uri) = Url::from_file_path(&path) {
+                queue.push(uri, JobPriority::Workspace);
+            }
+        }
+        if queue.is_empty() {
+            return;
+        }
+
+        let total = queue.len();
+        let token = self.begin_index_progress(run, total).await;
+        let queue = Arc::new(std::sync::Mutex::new(queue));
+        let finished = Arc::new(AtomicUsize::new(0));
+
+        let worker_count = std::thread::available_parallelism()
+            .map_or(1, |n| n.get())
+            .min(indexer::MAX_INDEX_WORKERS);
+        let workers: Vec<_> = (0..worker_count)
+            .map(|_| {
+                let backend = self.clone();
+                let queue = Arc::clone(&queue);
+                let finished = Arc::clone(&finished);
+                let token = token.clone();
+                tokio::spawn(async move {
+                    backend
+                        .run_index_worker(run, &queue, &finished, total, token.as_ref())
+                        .await
+                })
+            })
+            .collect();
+        for worker in workers {
+            let _ = worker.await;
+        }
+
+        if let Some(token) = token {
+            let message = if self.indexing.is_cancelled(run) {
+                "Cancelled".to_string()
+            } else {
+                format!("Indexed {} files", total)
+            };
+            self.client
+                .send_notification::<notification::Progress>(ProgressParams {
+                    token,
+                    value: ProgressParamsValue::WorkDone(WorkDoneProgress::End(
+                        WorkDoneProgressEnd {
+                            message: Some(message),
+                        },
+                    )),
+                })
+                .await;
+        }
+    }
+
+    async fn run_index_worker(
+        &self,
+        run: u64,
+        queue: &std::sync::Mutex<IndexQueue>,
+        finished: &AtomicUsize,
+        total: usize,
+        token: Option<&NumberOrString>,
+    ) {
+        while !self.indexing.is_cancelled(run) {
+            let Some((uri, priority)) = queue.lock().unwrap().pop() else {
+                return;
+            };
+            match priority {
+                JobPriority::OpenDocument { version } => {
+                    self.reanalyze_open_document(&uri, version).await
+                }
+                JobPriority::Workspace => self.index_workspace_file(&uri).await,
+            }
+
+            let done = finished.fetch_add(1, Ordering::SeqCst) + 1;
+            if let Some(token) = token {
+                // Only report when the percentage changes to keep the message volume down
+                let percentage = (done * 100 / total) as u32;
+                if done == 1 || percentage != ((done - 1) * 100 / total) as u32 {
+                    self.client
+                        .send_notification::<notification::Progress>(ProgressParams {
+                            token: token.clone(),
+                            value: ProgressParamsValue::WorkDone(WorkDoneProgress::Report(
+                                WorkDoneProgressReport {
+                                    cancellable: Some(false),
+                                    message: Some(format!("{}/{} files", done, total)),
+                                    percentage: Some(percentage),
+                                },
+                            )),
+                        })
+                        .await;
+                }
+            }
+        }
+    }
+
+    // Create a `$/progress` token for an indexing run if the client supports it
+    async fn begin_index_progress(&self, run: u64, total: usize) -> Option<NumberOrString> {
+        if !self.indexing.supports_progress.load(Ordering::SeqCst) {
+            return None;
+        }
+
+        let token = NumberOrString::String(format!("sv-indexing-{}", run));
+        self.client
+            .send_request::<request::WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                token: token.clone(),
+            })
+            .await
+            .ok()?;
+        self.client
+            .send_notification::<notification::Progress>(ProgressParams {
+                token: token.clone(),
+                value: ProgressParamsValue::WorkDone(WorkDoneProgress::Begin(
+                    WorkDoneProgressBegin {
+                        title: "Indexing SystemVerilog files".to_string(),
+                        cancellable: Some(false),
+                        message: Some(format!("0/{} files", total)),
+                        percentage: Some(0),
+                    },
+                )),
+            })
             .await;
+        Some(token)
+    }
+
+    // Directories searched by workspace indexing: the configured source
+    // directories, or the workspace root if there are none
+    async fn source_roots(&self) -> Vec<PathBuf> {
+        let config = self.config.read().await;
+        let workspace_root = self.workspace_root.read().await;
+        let Some(root) = workspace_root.as_ref() else {
+            return config
+                .source_directories
+                .iter()
+                .map(PathBuf::from)
+                .filter(|path| path.is_absolute())
+                .collect();
+        };
+
+        if config.source_directories.is_empty() {
+            return vec![root.clone()];
+        }
+        config
+            .source_directories
+            .iter()
+            .map(|dir| root.join(dir))
+            .collect()
+    }
+
+    // Analyze `version` of an open document on the blocking pool and publish
+    // its diagnostics, unless an edit has superseded that version
+    async fn reanalyze_open_document(&self, uri: &Url, version: i32) {
+        let current = |doc_state: &DocumentState| doc_state.open && doc_state.version == version;
+        let content = {
+            let docs = self.documents.read().await;
+            match docs.get(uri) {
+                Some(doc_state) if current(doc_state) => doc_state.content.clone(),
+                _ => return,
+            }
+        };
 
-        // Update document state
+        let (diagnostics, ast, symbols) = self
+            .parse_and_analyze_in_background(&content, uri)
+            .await
+            .unwrap_or_else(|report| (vec![panic_diagnostic(&report)], None, Vec::new()));
         {
             let mut docs = self.documents.write().await;
-            let old_doc = docs.insert(
-                params.uri.clone(),
-                DocumentState {
-                    content: params.text.clone(),
-                    ast: ast.clone(),
+            let Some(doc_state) = docs.get_mut(uri) else {
+                return;
+            };
+            // If the document changed or closed meanwhile, on_change/did_close have newer state
+            if !current(doc_state) {
+                return;
+            }
+            doc_state.ast = ast;
+            let old_symbols = std::mem::replace(&mut doc_state.symbols, symbols.clone());
+
+            let mut workspace_symbols = self.workspace_symbols.write().await;
+            let old_indexed = self.indexed_files.write().await.insert(
+                uri.clone(),
+                IndexedFile {
+                    hash: index_cache::content_hash(&content),
                     symbols: symbols.clone(),
                 },
             );
 
-            // Update workspace symbol index
-            let mut workspace_symbols = self.workspace_symbols.write().await;
+            // Replace old symbols from this document (including ones loaded from the index cache)
+            let old_symbols = old_symbols
+                .into_iter()
+                .chain(old_indexed.into_iter().flat_map(|indexed| indexed.symbols));
+            self.replace_workspace_symbols(&mut workspace_symbols, uri, old_symbols, symbols);
+        }
 
-            // Remove old symbols from this document
-            if let Some(old_state) = old_doc {
-                for old_symbol in old_state.symbols {
-                    if let Some(symbol_list) = workspace_symbols.get_mut(&old_symbol.name) {
-                        symbol_list.retain(|s| s.uri != params.uri);
-                        if symbol_list.is_empty() {
-                            workspace_symbols.remove(&old_symbol.name);
-                        }
-                    }
-                }
+        self.client
+            .publish_diagnostics(uri.clone(), diagnostics, Some(version))
+            .await;
+    }
+
+    // Index a file on disk that the editor does not know about
+    async fn index_workspace_file(&self, uri: &Url) {
+        let Ok(path) = uri.to_file_path() else {
+            return;
+        };
+        let Ok(content) = tokio::fs::read_to_string(&path).await else {
+            return;
+        };
+
+        let hash = index_cache::content_hash(&content);
+        let up_to_date = self
+            .indexed_files
+            .read()
+            .await
+            .get(uri)
+            .is_some_and(|indexed| indexed.hash == hash);
+        if up_to_date || self.documents.read().await.contains_key(uri) {
+            return;
+        }
+
+        // A file that crashes the parser has already been reported; leave it unindexed
+        let Ok((_, _, symbols)) = self.parse_and_analyze_in_background(&content, uri).await else {
+            return;
+        };
+
+        // The editor's version of a document wins over the file on disk
+        let docs = self.documents.read().await;
+        if docs.contains_key(uri) {
+            return;
+        }
+        let mut workspace_symbols = self.workspace_symbols.write().await;
+        let old_indexed = self.indexed_files.write().await.insert(
+            uri.clone(),
+            IndexedFile {
+                hash,
+                symbols: symbols.clone(),
+            },
+        );
+        let old_symbols = old_indexed.into_iter().flat_map(|indexed| indexed.symbols);
+        self.replace_workspace_symbols(&mut workspace_symbols, uri, old_symbols, symbols);
+    }
+
+    // Index the files a document includes, and the files they include in
+    // turn, so their symbols can be found without opening them
+    async fn index_includes(&self, uri: &Url) {
+        let included = |symbols: &[Symbol]| -> Vec<PathBuf> {
+            symbols
+                .iter()
+                .filter(|symbol| matches!(symbol.symbol_type, SymbolType::Include))
+                .map(|symbol| PathBuf::from(&symbol.name))
+                .filter(|path| path.is_absolute())
+                .collect()
+        };
+
+        let mut pending = match self.documents.read().await.get(uri) {
+            Some(doc_state) => included(&doc_state.symbols),
+            None => return,
+        };
+        let mut seen = std::collections::HashSet::new();
+        while let Some(path) = pending.pop() {
+            if !seen.insert(path.clone()) {
+                continue;
             }
+            let Ok(include_uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            self.index_workspace_file(&include_uri).await;
 
-            // Add new symbols (skip Include symbols as they're file-specific)
-            for symbol in symbols {
-                if !matches!(symbol.symbol_type, SymbolType::Include) {
-                    workspace_symbols
-                        .entry(symbol.name.clone())
-                        .or_insert_with(Vec::new)
-                        .push(symbol);
-                }
+            if let Some(doc_state) = self.documents.read().await.get(&include_uri) {
+                pending.extend(included(&doc_state.symbols));
+            } else if let Some(indexed) = self.indexed_files.read().await.get(&include_uri) {
+                pending.extend(included(&indexed.symbols));
+            }
+        }
+    }
+
+    // Mark a document as recently used, so it is the last closed document
+    // evicted, reading and parsing it again from disk if it was evicted
+    async fn ensure_document(&self, uri: &Url) -> LspResult<()> {
+        if let Some(doc_state) = self.documents.write().await.get_mut(uri) {
+            doc_state.last_used = Instant::now();
+            return Ok(());
+        }
+        let Some(content) = uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+        else {
+            return Ok(());
+        };
+
+        let (_, ast, symbols) = self
+            .parse_and_analyze_in_background(&content, uri)
+            .await
+            .map_err(|report| panic_error(&format!("analyzing {}", uri), &report))?;
+        self.documents
+            .write()
+            .await
+            .entry(uri.clone())
+            .or_insert_with(|| DocumentState {
+                content,
+                ast,
+                symbols,
+                version: 0,
+                open: false,
+                last_used: Instant::now(),
+            });
+        self.evict_closed_documents(Some(uri)).await;
+        Ok(())
+    }
+
+    /// Edits renaming the named connections to a port in the instances of
+    /// its module, when the rename at `position` is of a port of the module or
+    /// interface around it, with the module's name
+    async fn port_connection_edits(
+        &self,
+        uri: &Url,
+        position: Position,
+        port: &str,
+        new_name: &str,
+    ) -> LspResult<Option<(String, HashMap<Url, Vec<TextEdit>>)>> {
+        self.ensure_document(uri).await?;
+        let module = {
+            let docs = self.documents.read().await;
+            let Some(doc_state) = docs.get(uri) else {
+                return Ok(None);
+            };
+            let Some(ast) = doc_state.ast.as_ref() else {
+                return Ok(None);
+            };
+            let offset = sv_parser::position::position_to_offset(
+                &doc_state.content,
+                position,
+                PositionEncoding::Utf16,
+            );
+            match sv_parser::refactor::port_owner(ast, offset, port) {
+                Some(module) => module.to_string(),
+                None => return Ok(None),
+            }
+        };
+
+        // Only the files with an instance of the module need parsing
+        let mut instance_uris: Vec<Url> = {
+            let workspace_symbols = self.workspace_symbols.read().await;
+            workspace_symbols
+                .get(&module)
+                .into_iter()
+                .flatten()
+                .filter(|symbol| matches!(symbol.symbol_type, SymbolType::Instantiation { .. }))
+                .map(|symbol| symbol.uri.clone())
+                .collect()
+        };
+        instance_uris.sort();
+        instance_uris.dedup();
+
+        let mut edits = HashMap::new();
+        for instance_uri in instance_uris {
+            self.ensure_document(&instance_uri).await?;
+            let docs = self.documents.read().await;
+            let Some(doc_state) = docs.get(&instance_uri) else {
+                continue;
+            };
+            let Some(ast) = doc_state.ast.as_ref() else {
+                continue;
+            };
+            let file_edits: Vec<TextEdit> =
+                sv_parser::refactor::rename_port_connections(ast, &module, port, new_name)
+                    .into_iter()
+                    .filter_map(|edit| {
+                        Some(TextEdit {
+                            range: self.span_to_range(&doc_state.content, edit.span)?,
+                            new_text: edit.new_text,
+                        })
+                    })
+                    .collect();
+            if !file_edits.is_empty() {
+                edits.insert(instance_uri, file_edits);
+            }
+        }
+        Ok((!edits.is_empty()).then_some((module, edits)))
+    }
+
+    // Drop the least recently used closed documents beyond the configured
+    // budget, other than `keep`
+    //
+    // Their symbols stay in the workspace index, like those of a file that was
+    // never opened, and a request on one reads it again from disk.
+    async fn evict_closed_documents(&self, keep: Option<&Url>) {
+        let max_cached_documents = self
+            .config
+            .read()
+            .await
+            .max_cached_documents
+            .unwrap_or(DEFAULT_MAX_CACHED_DOCUMENTS);
+
+        let mut docs = self.documents.write().await;
+        let mut closed: Vec<(Instant, Url)> = docs
+            .iter()
+            .filter(|(uri, doc)| !doc.open && Some(*uri) != keep)
+            .map(|(uri, doc)| (doc.last_used, uri.clone()))
+            .collect();
+        if closed.len() <= max_cached_documents {
+            return;
+        }
+
+        closed.sort();
+        let evict_count = closed.len() - max_cached_documents;
+        for (_, uri) in closed.into_iter().take(evict_count) {
+            docs.remove(&uri);
+        }
+    }
+
+    /// Parse and analyze a document, recovering from a panic in the parser or analysis
+    ///
+    /// A panic is logged and sent to the client as a telemetry event before the
+    /// report is returned, so callers only decide how to degrade.
+    async fn parse_and_analyze_document(
+        &self,
+        text: &str,
+        uri: &Url,
+    ) -> Result<(Vec<Diagnostic>, Option<SourceUnit>, Vec<Symbol>), PanicReport> {
+        let result = catch_panic(self.parse_and_analyze_unguarded(text, uri)).await;
+        if let Err(report) = &result {
+            self.report_panic(uri, report).await;
+        }
+        result
+    }
+
+    // Parse and analyze a document on the blocking pool, so that a long parse
+    // for indexing doesn't hold up one of the threads serving requests
+    async fn parse_and_analyze_in_background(
+        &self,
+        text: &str,
+        uri: &Url,
+    ) -> Result<(Vec<Diagnostic>, Option<SourceUnit>, Vec<Symbol>), PanicReport> {
+        let backend = self.clone();
+        let (text, uri) = (text.to_string(), uri.clone());
+        let runtime = tokio::runtime::Handle::current();
+        tokio::task::spawn_blocking(move || {
+            runtime.block_on(backend.parse_and_analyze_document(&text, &uri))
+        })
+        .await
+        .expect("the runtime shut down while a document was being indexed")
+    }
+
+    /// Run the handler of a request, answering with an internal error that
+    /// carries the report if it panics
+    async fn guard_request<T>(
+        &self,
+        method: &str,
+        handler: impl std::future::Future<Output = LspResult<T>>,
+    ) -> LspResult<T> {
+        match catch_panic(handler).await {
+            Ok(result) => result,
+            Err(report) => {
+                self.log_panic(
+                    &format!("handling {}", method),
+                    ("request", method),
+                    &report,
+                )
+                .await;
+                Err(panic_error(method, &report))
             }
         }
+    }
 
+    async fn report_panic(&self, uri: &Url, report: &PanicReport) {
+        self.log_panic(
+            &format!("analyzing {}", uri),
+            ("file", uri.as_str()),
+            report,
+        )
+        .await;
+    }
+
+    /// Log a panic and send it as a telemetry event, `subject` naming what
+    /// was being worked on
+    async fn log_panic(&self, activity: &str, subject: (&str, &str), report: &PanicReport) {
+        let location = report.location.as_deref().unwrap_or("unknown location");
+        self.client
+            .log_message(
+                MessageType::ERROR,
+                format!(
+                    "Internal error while {}: {} at {}\n{}",
+                    activity, report.message, location, report.backtrace
+                ),
+            )
+            .await;
         self.client
-            .publish_diagnostics(params.uri.clone(), diagnostics, Some(params.version))
+            .telemetry_event(serde_json::json!({
+                "event": "panic",
+                subject.0: subject.1,
+                "message": report.message,
+                "location": report.location,
+                "backtrace": report.backtrace,
+            }))
             .await;
     }
 
-    async fn parse_and_analyze_document(
+    async fn parse_and_analyze_unguarded(
         &self,
         text: &str,
         uri: &Url,
@@ -1525,7 +2853,7 @@ impl Backend {
         let mut symbols = Vec::new();
 
         // Get configuration for parser
-        let (include_paths, defines) = {
+        let (include_paths, defines, limits, timeout) = {
             let config = self.config.read().await;
             let workspace_root = self.workspace_root.read().await;
 
@@ -1548,14 +2876,43 @@ impl Backend {
                 defines.insert(key.clone(), value.clone().unwrap_or_default());
             }
 
-            (include_paths, defines)
+            // A runaway macro fails the document instead of stalling the server
+            let default_limits = sv_parser::PreprocessLimits::default();
+            let limits = sv_parser::PreprocessLimits {
+                max_depth: config
+                    .max_preprocess_depth
+                    .unwrap_or(default_limits.max_depth),
+                max_output: config
+                    .max_preprocessed_size
+                    .unwrap_or(default_limits.max_output),
+            };
+            // Likewise a document that takes too long to parse fails instead
+            let timeout = match config
+                .max_parse_time_ms
+                .unwrap_or(DEFAULT_MAX_PARSE_TIME_MS)
+            {
+                0 => None,
+                ms => Some(std::time::Duration::from_millis(ms)),
+            };
+
+            (include_paths, defines, limits, timeout)
         };
 
         // Create parser with configuration
-        let parser = SystemVerilogParser::new(include_paths, defines);
-
-        // Parse content
-        let result = parser.parse_content(text);
+        let mut parser = SystemVerilogParser::new(include_paths, defines);
+        parser.set_preprocess_limits(limits);
+        parser.set_parse_timeout(timeout);
+
+        // Parse content with the keywords of the file's language, and as a
+        // header for .svh and .vh files. Parsing as the file also resolves
+        // its includes, for navigation into them
+        let result = match uri.to_file_path() {
+            Ok(path) => {
+                parser.set_language_version(parser.language_version_for(&path));
+                parser.parse_file_content(text, &path)
+            }
+            Err(()) => parser.parse_content(text),
+        };
 
         // Extract symbols from AST
         if let Ok(parsed_ast) = &result {
@@ -1592,14 +2949,43 @@ impl Backend {
                             Range::new(Position::new(0, 0), Position::new(0, 1))
                         };
 
+                    let mut related_information: Vec<DiagnosticRelatedInformation> = error
+                        .related
+                        .and_then(|related| {
+                            let start = self.char_offset_to_position(text, related.span.0)?;
+                            let end = self
+                                .char_offset_to_position(text, related.span.1)
+                                .unwrap_or(start);
+                            Some(DiagnosticRelatedInformation {
+                                location: Location::new(uri.clone(), Range::new(start, end)),
+                                message: related.message,
+                            })
+                        })
+                        .into_iter()
+                        .collect();
+                    related_information.extend(
+                        parsed_ast
+                            .source_map
+                            .provenance(error.span)
+                            .into_iter()
+                            .filter_map(|provenance| {
+                                self.provenance_information(text, uri, provenance)
+                            }),
+                    );
+                    let related_information =
+                        (!related_information.is_empty()).then_some(related_information);
+
+                    let code = error.error_type.code();
                     let diagnostic = Diagnostic {
                         range,
                         severity: Some(DiagnosticSeverity::ERROR),
-                        code: None,
-                        code_description: None,
+                        code: Some(NumberOrString::String(code.to_string())),
+                        code_description: Url::parse(&explain::documentation_url(code))
+                            .ok()
+                            .map(|href| CodeDescription { href }),
                         source: Some("sv-semantic".to_string()),
                         message: error.message,
-                        related_information: None,
+                        related_information,
                         tags: None,
                         data: None,
                     };
@@ -1667,6 +3053,16 @@ impl Backend {
                     _ => DiagnosticSeverity::ERROR,
                 };
 
+                let related_information: Vec<DiagnosticRelatedInformation> = error
+                    .location
+                    .as_ref()
+                    .and_then(|location| location.span)
+                    .map(|span| parse_error.source_map.provenance(span))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|provenance| self.provenance_information(text, uri, provenance))
+                    .collect();
+
                 let mut diagnostic = Diagnostic {
                     range,
                     severity: Some(severity),
@@ -1674,7 +3070,8 @@ impl Backend {
                     code_description: None,
                     source: Some("sv-parser".to_string()),
                     message: error.message.clone(),
-                    related_information: None,
+                    related_information: (!related_information.is_empty())
+                        .then_some(related_information),
                     tags: None,
                     data: None,
                 };
@@ -1697,31 +3094,121 @@ impl Backend {
             }
         }
 
+        // Publish in a stable order so clients don't see the list reshuffle between edits
+        diagnostics.sort_by(|a, b| {
+            let key = |d: &Diagnostic| {
+                (
+                    d.range.start.line,
+                    d.range.start.character,
+                    d.range.end.line,
+                    d.range.end.character,
+                )
+            };
+            key(a)
+                .cmp(&key(b))
+                .then_with(|| a.source.cmp(&b.source))
+                .then_with(|| a.message.cmp(&b.message))
+        });
+
         (diagnostics, ast, symbols)
     }
 
     // Helper function to convert character offset to LSP Position
     fn char_offset_to_position(&self, text: &str, offset: usize) -> Option<Position> {
-        if offset > text.len() {
-            // Clamp to end of text
-            let prefix = text;
-            let line = prefix.matches('\n').count();
-            let column = prefix.split('\n').last().unwrap_or("").len();
-            return Some(Position::new(line as u32, column as u32));
-        }
-
-        let prefix = &text[..offset];
-        let line = prefix.matches('\n').count();
-        let column = prefix.split('\n').last().unwrap_or("").len();
+        Some(sv_parser::position::offset_to_position(
+            text,
+            offset,
+            PositionEncoding::Utf16,
+        ))
+    }
 
-        Some(Position::new(line as u32, column as u32))
+    // Note on where the text of a diagnostic comes from: the definition of
+    // the macro it was expanded from, or the directive of its branch
+    fn provenance_information(
+        &self,
+        text: &str,
+        uri: &Url,
+        provenance: Provenance,
+    ) -> Option<DiagnosticRelatedInformation> {
+        let location = match &provenance {
+            Provenance::Expansion {
+                definition: Some(site),
+                ..
+            } if site.file.is_some() => {
+                let file = site.file.as_ref()?;
+                let position = Position::new(site.line as u32, 0);
+                Location::new(
+                    Url::from_file_path(file).ok()?,
+                    Range::new(position, position),
+                )
+            }
+            Provenance::Expansion { span, .. } | Provenance::Branch { span, .. } => {
+                Location::new(uri.clone(), self.span_to_range(text, *span)?)
+            }
+        };
+        Some(DiagnosticRelatedInformation {
+            location,
+            message: provenance.to_string(),
+        })
     }
 
     // Helper function to convert a span to LSP Range
     fn span_to_range(&self, text: &str, span: sv_parser::Span) -> Option<Range> {
-        let start_pos = self.char_offset_to_position(text, span.0)?;
-        let end_pos = self.char_offset_to_position(text, span.1)?;
-        Some(Range::new(start_pos, end_pos))
+        Some(sv_parser::position::span_to_range(
+            text,
+            span,
+            PositionEncoding::Utf16,
+        ))
+    }
+
+    // The declaration `name`, used at `position` of a document, resolves to
+    // through the package imports of its scope, with the name of the package;
+    // packages are looked up in the documents with an AST, the used one first
+    fn imported_declaration(
+        &self,
+        docs: &HashMap<Url, DocumentState>,
+        uri: &Url,
+        name: &str,
+        position: Position,
+    ) -> Option<(String, Location)> {
+        let doc_state = docs.get(uri)?;
+        let offset = sv_parser::position::position_to_offset(
+            &doc_state.content,
+            position,
+            PositionEncoding::Utf16,
+        );
+        let scope = ImportScope::at(doc_state.ast.as_ref()?, offset);
+        if scope.imports().is_empty() {
+            return None;
+        }
+
+        let mut exports = PackageExports::new();
+        let mut package_uris = HashMap::new();
+        let others = docs.iter().filter(|(other, _)| *other != uri);
+        for (package_uri, state) in others.chain(std::iter::once((uri, doc_state))) {
+            let Some(ast) = &state.ast else {
+                continue;
+            };
+            for (package, names) in resolve::package_exports(ast) {
+                package_uris.insert(package.clone(), package_uri);
+                exports.insert(package, names);
+            }
+        }
+
+        let Resolution::Imported {
+            package,
+            declaration,
+            ..
+        } = scope.resolve(name, &exports)
+        else {
+            return None;
+        };
+        let package_uri = package_uris.get(package)?;
+        let range = self.span_to_range(&docs.get(*package_uri)?.content, declaration)?;
+        Some((
+            package.to_string(),
+            Location::new((*package_uri).clone(), range),
+        ))
     }
 
     // Find hover information at a specific position
@@ -1751,6 +3238,67 @@ impl Backend {
         None
     }
 
+    // Hover for the delay literal of `#10` under the cursor, which waits 10
+    // time units of the design element around it
+    fn find_delay_hover(
+        &self,
+        ast: &SourceUnit,
+        content: &str,
+        position: Position,
+    ) -> Option<Hover> {
+        let chars: Vec<char> = content.chars().collect();
+        let offset =
+            sv_parser::position::position_to_offset(content, position, PositionEncoding::Utf16)
+                .min(chars.len());
+        let is_blank = |c: char| c == ' ' || c == '\t';
+        let in_literal = |c: char| c.is_ascii_digit() || c == '_' || c == '.';
+
+        let mut start = offset;
+        if chars.get(start) == Some(&'#') {
+            start += 1;
+            while chars.get(start).is_some_and(|c| is_blank(*c)) {
+                start += 1;
+            }
+        }
+        while start > 0 && in_literal(chars[start - 1]) {
+            start -= 1;
+        }
+        let mut end = start;
+        while chars.get(end).is_some_and(|c| in_literal(*c)) {
+            end += 1;
+        }
+        let mut hash = start;
+        while hash > 0 && is_blank(chars[hash - 1]) {
+            hash -= 1;
+        }
+        // `##1` is a cycle delay, and `#8'h10` or `#5ns` is not in time units
+        if end == start
+            || hash == 0
+            || chars[hash - 1] != '#'
+            || (hash > 1 && chars[hash - 2] == '#')
+            || chars
+                .get(end)
+                .is_some_and(|c| c.is_alphanumeric() || *c == '\'')
+            || offset + 1 < hash
+            || offset >= end
+        {
+            return None;
+        }
+
+        let literal: String = chars[start..end].iter().collect();
+        let value: f64 = literal.replace('_', "").parse().ok()?;
+        let scope = sv_parser::time_scopes(ast)
+            .into_iter()
+            .find(|scope| scope.span.0 <= offset && offset < scope.span.1);
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: delay_hover_text(&literal, value, scope.as_ref()),
+            }),
+            range: self.span_to_range(content, (hash - 1, end)),
+        })
+    }
+
     // Recursively search for hover information in a module item
     fn find_hover_in_item(
         &self,
@@ -1767,20 +3315,84 @@ impl Backend {
                 name_span,
                 items,
                 ..
+            }
+            | ModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                items,
+                ..
+            }
+            | ModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                items,
+                ..
+            }
+            | ModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items,
+                ..
             } => {
-                // Check if hovering over module name
+                // Check if hovering over the module, interface, checker or package name
+                let keyword = match item {
+                    ModuleItem::InterfaceDeclaration { .. } => "interface",
+                    ModuleItem::CheckerDeclaration { .. } => "checker",
+                    ModuleItem::PackageDeclaration { .. } => "package",
+                    _ => "module",
+                };
                 if let Some(range) = self.span_to_range(content, *name_span) {
                     if self.position_in_range(position, range) {
                         return Some(Hover {
                             contents: HoverContents::Markup(MarkupContent {
                                 kind: MarkupKind::Markdown,
-                                value: format!("```systemverilog\nmodule {}\n```", name),
+                                value: format!("```systemverilog\n{} {}\n```", keyword, name),
                             }),
                             range: Some(range),
                         });
                     }
                 }
 
+                // Check if hovering over a parameter name; show its evaluated value
+                let parameter_values =
+                    sv_parser::number::parameter_values(items, module_item_arena, expr_arena);
+                for &sub_item_ref in items {
+                    let ModuleItem::ParameterDeclaration {
+                        local,
+                        name,
+                        name_span,
+                        value,
+                        ..
+                    } = module_item_arena.get(sub_item_ref)
+                    else {
+                        continue;
+                    };
+                    let Some(range) = self.span_to_range(content, *name_span) else {
+                        continue;
+                    };
+                    if !self.position_in_range(position, range) {
+                        continue;
+                    }
+                    let keyword = if *local { "localparam" } else { "parameter" };
+                    let code = format!("{} {}", keyword, name);
+                    let value = match parameter_values.get(name) {
+                        Some(literal) => number_hover_text(&code, literal),
+                        // Not a constant; show the value in canonical form instead
+                        None => format!(
+                            "```systemverilog\n{} = {}\n```",
+                            code,
+                            sv_parser::simplify(*value, expr_arena)
+                        ),
+                    };
+                    return Some(Hover {
+                        contents: HoverContents::Markup(MarkupContent {
+                            kind: MarkupKind::Markdown,
+                            value,
+                        }),
+                        range: Some(range),
+                    });
+                }
+
                 // Recursively search in module items - items are refs into the arena
                 for &sub_item_ref in items {
                     let sub_item = module_item_arena.get(sub_item_ref);
@@ -1796,12 +3408,27 @@ impl Backend {
                     }
                 }
             }
-            ModuleItem::ProceduralBlock { statements, .. } => {
+            ModuleItem::ProceduralBlock {
+                event_control,
+                statements,
+                ..
+            } => {
+                for expr_ref in event_control
+                    .iter()
+                    .flat_map(|control| control.expressions())
+                {
+                    let expr_val = expr_arena.get(expr_ref);
+                    if let Some(hover) =
+                        self.find_hover_in_expression(expr_val, expr_arena, content, position)
+                    {
+                        return Some(hover);
+                    }
+                }
                 // Check for system function calls in statements - statements is now Vec<StmtRef>
                 for &stmt_ref in statements {
                     let stmt = stmt_arena.get(stmt_ref);
-                    if let Some(hover) =
-                        self.find_hover_in_statement(stmt, expr_arena, content, position)
+                    if let Some(hover) = self
+                        .find_hover_in_statement(stmt, expr_arena, stmt_arena, content, position)
                     {
                         return Some(hover);
                     }
@@ -1823,27 +3450,62 @@ impl Backend {
                     return Some(hover);
                 }
             }
-            ModuleItem::VariableDeclaration { initial_value, .. } => {
+            ModuleItem::VariableDeclaration {
+                initial_value: Some(expr_ref),
+                ..
+            } => {
                 // Check for system function calls in module-level variable initialization
-                if let Some(expr_ref) = initial_value {
-                    let expr_val = expr_arena.get(*expr_ref);
+                let expr_val = expr_arena.get(*expr_ref);
+                if let Some(hover) =
+                    self.find_hover_in_expression(expr_val, expr_arena, content, position)
+                {
+                    return Some(hover);
+                }
+            }
+            ModuleItem::ConcurrentAssertion {
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                // Check the property, then its action blocks
+                for expr_ref in property.expressions() {
+                    let expr = expr_arena.get(expr_ref);
                     if let Some(hover) =
-                        self.find_hover_in_expression(expr_val, expr_arena, content, position)
+                        self.find_hover_in_expression(expr, expr_arena, content, position)
+                    {
+                        return Some(hover);
+                    }
+                }
+                for &action in pass_action.iter().chain(fail_action) {
+                    let stmt = stmt_arena.get(action);
+                    if let Some(hover) = self
+                        .find_hover_in_statement(stmt, expr_arena, stmt_arena, content, position)
+                    {
+                        return Some(hover);
+                    }
+                }
+            }
+            ModuleItem::AssertionDeclaration { body, .. } => {
+                for expr_ref in body.expressions() {
+                    let expr = expr_arena.get(expr_ref);
+                    if let Some(hover) =
+                        self.find_hover_in_expression(expr, expr_arena, content, position)
                     {
                         return Some(hover);
                     }
                 }
             }
-            ModuleItem::ConcurrentAssertion { statement, .. } => {
-                // Check concurrent assertion statement
-                let stmt = stmt_arena.get(*statement);
+            ModuleItem::ParameterDeclaration { value, .. } => {
+                let value_expr = expr_arena.get(*value);
                 if let Some(hover) =
-                    self.find_hover_in_statement(stmt, expr_arena, content, position)
+                    self.find_hover_in_expression(value_expr, expr_arena, content, position)
                 {
                     return Some(hover);
                 }
             }
-            ModuleItem::GlobalClocking { clocking_event, .. } => {
+            ModuleItem::GlobalClocking { clocking_event, .. }
+            | ModuleItem::ClockingBlock { clocking_event, .. } => {
                 // Check clocking event expression
                 let event_expr = expr_arena.get(*clocking_event);
                 if let Some(hover) =
@@ -1852,7 +3514,8 @@ impl Backend {
                     return Some(hover);
                 }
             }
-            ModuleItem::ClassDeclaration { items, .. } => {
+            ModuleItem::ClassDeclaration { items, .. }
+            | ModuleItem::InterfaceClassDeclaration { items, .. } => {
                 // Check class items for system function calls
                 for class_item in items {
                     match class_item {
@@ -1869,13 +3532,36 @@ impl Backend {
                         sv_parser::ClassItem::Method { body, .. } => {
                             for &stmt_ref in body {
                                 let stmt = stmt_arena.get(stmt_ref);
-                                if let Some(hover) = self
-                                    .find_hover_in_statement(stmt, expr_arena, content, position)
-                                {
+                                if let Some(hover) = self.find_hover_in_statement(
+                                    stmt, expr_arena, stmt_arena, content, position,
+                                ) {
                                     return Some(hover);
                                 }
                             }
                         }
+                        sv_parser::ClassItem::Constraint(block) => {
+                            for constraint in &block.constraints {
+                                for expr_ref in constraint.expressions() {
+                                    let expr_val = expr_arena.get(expr_ref);
+                                    if let Some(hover) = self.find_hover_in_expression(
+                                        expr_val, expr_arena, content, position,
+                                    ) {
+                                        return Some(hover);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            ModuleItem::OutOfBlockMethod { body, .. }
+            | ModuleItem::SubroutineDeclaration { body, .. } => {
+                for &stmt_ref in body {
+                    let stmt = stmt_arena.get(stmt_ref);
+                    if let Some(hover) = self
+                        .find_hover_in_statement(stmt, expr_arena, stmt_arena, content, position)
+                    {
+                        return Some(hover);
                     }
                 }
             }
@@ -1884,11 +3570,12 @@ impl Backend {
         None
     }
 
-    // Find hover information in a statement
+    // Find hover information in a statement or the statements nested in it
     fn find_hover_in_statement(
         &self,
         stmt: &sv_parser::Statement,
         expr_arena: &sv_parser::ExprArena,
+        stmt_arena: &sv_parser::StmtArena,
         content: &str,
         position: Position,
     ) -> Option<Hover> {
@@ -1938,8 +3625,22 @@ impl Backend {
                     return Some(hover);
                 }
             }
-            sv_parser::Statement::CaseStatement { expr, .. } => {
-                // Check if there's a system function call in the case expression
+            sv_parser::Statement::CaseStatement { expr, .. }
+            | sv_parser::Statement::CaseMatches { expr, .. }
+            | sv_parser::Statement::Conditional {
+                condition: expr, ..
+            }
+            | sv_parser::Statement::ImmediateAssertion {
+                condition: expr, ..
+            }
+            | sv_parser::Statement::While {
+                condition: expr, ..
+            }
+            | sv_parser::Statement::DoWhile {
+                condition: expr, ..
+            }
+            | sv_parser::Statement::Repeat { count: expr, .. } => {
+                // Check if there's a system function call in the case expression or condition
                 let expr_val = expr_arena.get(*expr);
                 if let Some(hover) =
                     self.find_hover_in_expression(expr_val, expr_arena, content, position)
@@ -1947,7 +3648,8 @@ impl Backend {
                     return Some(hover);
                 }
             }
-            sv_parser::Statement::ExpressionStatement { expr, .. } => {
+            sv_parser::Statement::ExpressionStatement { expr, .. }
+            | sv_parser::Statement::EventTrigger { event: expr, .. } => {
                 // Check if there's a system function call in the expression
                 let expr_val = expr_arena.get(*expr);
                 if let Some(hover) =
@@ -1956,25 +3658,46 @@ impl Backend {
                     return Some(hover);
                 }
             }
-            sv_parser::Statement::AssertProperty {
-                property_expr,
-                action_block,
+            sv_parser::Statement::AssertProperty { property, .. } => {
+                // Check if there's a system function call in the property
+                for expr_ref in property.expressions() {
+                    let expr = expr_arena.get(expr_ref);
+                    if let Some(hover) =
+                        self.find_hover_in_expression(expr, expr_arena, content, position)
+                    {
+                        return Some(hover);
+                    }
+                }
+            }
+            sv_parser::Statement::VariableDeclaration { initial_value, .. }
+            | sv_parser::Statement::Return {
+                value: initial_value,
+                ..
+            } => {
+                // Check if there's a system function call in the initializer or returned value
+                if let Some(expr_ref) = initial_value {
+                    let expr_val = expr_arena.get(*expr_ref);
+                    if let Some(hover) =
+                        self.find_hover_in_expression(expr_val, expr_arena, content, position)
+                    {
+                        return Some(hover);
+                    }
+                }
+            }
+            sv_parser::Statement::ForLoop {
+                condition: Some(expr),
                 ..
             } => {
-                // Check if there's a system function call in the property expression
-                let property = expr_arena.get(*property_expr);
+                let expr_val = expr_arena.get(*expr);
                 if let Some(hover) =
-                    self.find_hover_in_expression(property, expr_arena, content, position)
+                    self.find_hover_in_expression(expr_val, expr_arena, content, position)
                 {
                     return Some(hover);
                 }
-                // TODO: Check action block if present - needs stmt_arena
-                let _ = action_block; // Silence unused warning for now
             }
-            sv_parser::Statement::VariableDeclaration { initial_value, .. } => {
-                // Check if there's a system function call in the initializer
-                if let Some(expr_ref) = initial_value {
-                    let expr_val = expr_arena.get(*expr_ref);
+            sv_parser::Statement::Timed { control, .. } => {
+                for expr_ref in control.expressions() {
+                    let expr_val = expr_arena.get(expr_ref);
                     if let Some(hover) =
                         self.find_hover_in_expression(expr_val, expr_arena, content, position)
                     {
@@ -1982,8 +3705,21 @@ impl Backend {
                     }
                 }
             }
+            sv_parser::Statement::Block { .. }
+            | sv_parser::Statement::Fork { .. }
+            | sv_parser::Statement::Labeled { .. }
+            | sv_parser::Statement::ForLoop { .. }
+            | sv_parser::Statement::Forever { .. }
+            | sv_parser::Statement::Foreach { .. }
+            | sv_parser::Statement::WaitFork { .. }
+            | sv_parser::Statement::DisableFork { .. }
+            | sv_parser::Statement::Disable { .. } => {}
         }
-        None
+        // The branches, bodies and action blocks nested in the statement
+        stmt.children().into_iter().find_map(|child| {
+            let child = stmt_arena.get(child);
+            self.find_hover_in_statement(child, expr_arena, stmt_arena, content, position)
+        })
     }
 
     // Find hover information in an expression
@@ -1995,6 +3731,19 @@ impl Backend {
         position: Position,
     ) -> Option<Hover> {
         match expr {
+            Expression::Number(text, span) => {
+                let literal = NumberLiteral::parse(text)?;
+                let range = self.span_to_range(content, *span)?;
+                if self.position_in_range(position, range) {
+                    return Some(Hover {
+                        contents: HoverContents::Markup(MarkupContent {
+                            kind: MarkupKind::Markdown,
+                            value: number_hover_text(text, &literal),
+                        }),
+                        range: Some(range),
+                    });
+                }
+            }
             Expression::SystemFunctionCall {
                 name,
                 span,
@@ -2041,7 +3790,7 @@ impl Backend {
                     return Some(hover);
                 }
             }
-            Expression::Unary { operand, .. } => {
+            Expression::Unary { operand, .. } | Expression::IncDec { operand, .. } => {
                 let operand_expr = expr_arena.get(*operand);
                 if let Some(hover) =
                     self.find_hover_in_expression(operand_expr, expr_arena, content, position)
@@ -2126,6 +3875,7 @@ impl Backend {
     }
 
     // Extract symbols from a module item
+    #[allow(clippy::too_many_arguments)]
     fn extract_symbols_from_module_item(
         &self,
         item: &ModuleItem,
@@ -2143,12 +3893,32 @@ impl Backend {
                 ports,
                 items,
                 ..
+            }
+            | ModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                ..
+            }
+            | ModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                ..
             } => {
-                // Add module name as a symbol using span from AST
+                // Add module or interface name as a symbol using span from AST;
+                // checkers are instantiated like modules
+                let symbol_type = if matches!(item, ModuleItem::InterfaceDeclaration { .. }) {
+                    SymbolType::Interface
+                } else {
+                    SymbolType::Module
+                };
                 if let Some(range) = self.span_to_range(content, *name_span) {
                     symbols.push(Symbol {
                         name: name.clone(),
-                        symbol_type: SymbolType::Module,
+                        symbol_type,
                         range,
                         uri: uri.clone(),
                     });
@@ -2167,6 +3937,43 @@ impl Backend {
                 }
 
                 // Recursively process module items - items are refs into the arena
+                let first_item_symbol = symbols.len();
+                for &sub_item_ref in items {
+                    let sub_item = module_item_arena.get(sub_item_ref);
+                    self.extract_symbols_from_module_item(
+                        sub_item,
+                        expr_arena,
+                        stmt_arena,
+                        module_item_arena,
+                        content,
+                        uri,
+                        symbols,
+                    );
+                }
+
+                // Instantiations found in this module (and not in a nested one) live here
+                for symbol in &mut symbols[first_item_symbol..] {
+                    if let SymbolType::Instantiation { parent, .. } = &mut symbol.symbol_type {
+                        if parent.is_empty() {
+                            *parent = name.clone();
+                        }
+                    }
+                }
+            }
+            ModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items,
+                ..
+            } => {
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Package,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
                 for &sub_item_ref in items {
                     let sub_item = module_item_arena.get(sub_item_ref);
                     self.extract_symbols_from_module_item(
@@ -2193,16 +4000,23 @@ impl Backend {
                 }
             }
             ModuleItem::VariableDeclaration {
+                data_type,
                 name,
                 name_span,
                 initial_value,
                 ..
             } => {
+                self.extract_symbols_from_enum_members(item, expr_arena, content, uri, symbols);
+
                 // Add variable declaration using span from AST
                 if let Some(range) = self.span_to_range(content, *name_span) {
                     symbols.push(Symbol {
                         name: name.clone(),
-                        symbol_type: SymbolType::Variable,
+                        symbol_type: if data_type.name() == "event" {
+                            SymbolType::Event
+                        } else {
+                            SymbolType::Variable
+                        },
                         range,
                         uri: uri.clone(),
                     });
@@ -2214,27 +4028,61 @@ impl Backend {
                     self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
                 }
             }
-            ModuleItem::Assignment { target, expr, .. } => {
-                // Extract identifiers from the target expression (e.g., for member access)
-                let target_expr = expr_arena.get(*target);
-                self.extract_symbols_from_expression(
-                    target_expr,
-                    expr_arena,
-                    content,
-                    uri,
-                    symbols,
-                );
+            ModuleItem::TypedefDeclaration {
+                name, name_span, ..
+            } => {
+                self.extract_symbols_from_enum_members(item, expr_arena, content, uri, symbols);
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Typedef,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+            }
+            ModuleItem::Assignment {
+                target,
+                expr,
+                implicit,
+                ..
+            } => {
+                // Extract identifiers from the target expression (e.g., for member access).
+                // The target of an implicit assignment is the declared net itself.
+                if !implicit {
+                    let target_expr = expr_arena.get(*target);
+                    self.extract_symbols_from_expression(
+                        target_expr,
+                        expr_arena,
+                        content,
+                        uri,
+                        symbols,
+                    );
+                }
 
                 // Extract identifiers from the value expression
                 let expr_val = expr_arena.get(*expr);
                 self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
             }
-            ModuleItem::ProceduralBlock { statements, .. } => {
+            ModuleItem::ProceduralBlock {
+                event_control,
+                statements,
+                ..
+            } => {
+                for expr_ref in event_control
+                    .iter()
+                    .flat_map(|control| control.expressions())
+                {
+                    let expr_val = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(
+                        expr_val, expr_arena, content, uri, symbols,
+                    );
+                }
                 // Extract symbols from statements in procedural block - statements is now Vec<StmtRef>
                 for &stmt_ref in statements {
                     let statement = stmt_arena.get(stmt_ref);
                     self.extract_symbols_from_statement(
-                        statement, expr_arena, content, uri, symbols,
+                        statement, expr_arena, stmt_arena, content, uri, symbols,
                     );
                 }
             }
@@ -2278,112 +4126,1205 @@ impl Backend {
                 name_span,
                 items,
                 ..
+            }
+            | ModuleItem::InterfaceClassDeclaration {
+                name,
+                name_span,
+                items,
+                ..
+            } => {
+                // Add class declaration as a symbol, with its base classes for
+                // type hierarchy navigation
+                let bases = match item {
+                    ModuleItem::ClassDeclaration {
+                        extends,
+                        implements,
+                        ..
+                    } => extends.iter().chain(implements).cloned().collect(),
+                    ModuleItem::InterfaceClassDeclaration { extends, .. } => extends.clone(),
+                    _ => Vec::new(),
+                };
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Class { bases },
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                // Parameter ports: a type parameter names a type like a typedef
+                if let ModuleItem::ClassDeclaration { parameters, .. } = item {
+                    for parameter in parameters {
+                        if let Some(range) = self.span_to_range(content, parameter.name_span) {
+                            symbols.push(Symbol {
+                                name: parameter.name.clone(),
+                                symbol_type: if parameter.is_type {
+                                    SymbolType::Typedef
+                                } else {
+                                    SymbolType::Parameter
+                                },
+                                range,
+                                uri: uri.clone(),
+                            });
+                        }
+                    }
+                }
+                // Extract class members (properties and methods) as symbols
+                for class_item in items {
+                    // An extern method is also named like its body outside the class
+                    if let sv_parser::ClassItem::Method {
+                        is_extern: true,
+                        name: method,
+                        name_span,
+                        ..
+                    } = class_item
+                    {
+                        if let Some(range) = self.span_to_range(content, *name_span) {
+                            symbols.push(Symbol {
+                                name: sv_parser::scoped_method_name(name, method),
+                                symbol_type: SymbolType::ExternMethod,
+                                range,
+                                uri: uri.clone(),
+                            });
+                        }
+                    }
+                    self.extract_symbols_from_class_item(
+                        class_item, expr_arena, stmt_arena, content, uri, symbols,
+                    );
+                }
+            }
+            ModuleItem::OutOfBlockMethod {
+                class_name,
+                name,
+                name_span,
+                body,
+                ..
+            } => {
+                // Named like its prototype, and by its own name like the
+                // methods declared in a class
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: sv_parser::scoped_method_name(class_name, name),
+                        symbol_type: SymbolType::ExternMethod,
+                        range,
+                        uri: uri.clone(),
+                    });
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Function,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                for &stmt_ref in body {
+                    let statement = stmt_arena.get(stmt_ref);
+                    self.extract_symbols_from_statement(
+                        statement, expr_arena, stmt_arena, content, uri, symbols,
+                    );
+                }
+            }
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                name,
+                name_span,
+                body,
+                ..
+            } => {
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: if *is_task {
+                            SymbolType::Task
+                        } else {
+                            SymbolType::Function
+                        },
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                for &stmt_ref in body {
+                    let statement = stmt_arena.get(stmt_ref);
+                    self.extract_symbols_from_statement(
+                        statement, expr_arena, stmt_arena, content, uri, symbols,
+                    );
+                }
+            }
+            ModuleItem::ConcurrentAssertion {
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                for expr_ref in property.expressions() {
+                    let expr = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+                for &action in pass_action.iter().chain(fail_action) {
+                    let stmt = stmt_arena.get(action);
+                    self.extract_symbols_from_statement(
+                        stmt, expr_arena, stmt_arena, content, uri, symbols,
+                    );
+                }
+            }
+            ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                body,
+                ..
+            } => {
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: match kind {
+                            sv_parser::AssertionDeclarationKind::Sequence => SymbolType::Sequence,
+                            sv_parser::AssertionDeclarationKind::Property => SymbolType::Property,
+                        },
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                for expr_ref in body.expressions() {
+                    let expr = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+            }
+            ModuleItem::DefaultDisableIff { condition, .. } => {
+                let condition = expr_arena.get(*condition);
+                self.extract_symbols_from_expression(condition, expr_arena, content, uri, symbols);
+            }
+            ModuleItem::DefaultClocking { .. } => {}
+            ModuleItem::ClockingBlock {
+                name,
+                name_span,
+                clocking_event,
+                ..
+            } => {
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Clocking,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                let event = expr_arena.get(*clocking_event);
+                self.extract_symbols_from_expression(event, expr_arena, content, uri, symbols);
+            }
+            ModuleItem::GlobalClocking {
+                identifier,
+                identifier_span,
+                ..
+            } => {
+                // Add global clocking identifier as a symbol if present
+                if let (Some(name), Some(span)) = (identifier, identifier_span) {
+                    if let Some(range) = self.span_to_range(content, *span) {
+                        symbols.push(Symbol {
+                            name: name.clone(),
+                            symbol_type: SymbolType::Variable, // Use Variable for now
+                            range,
+                            uri: uri.clone(),
+                        });
+                    }
+                }
+            }
+            ModuleItem::ParameterDeclaration {
+                name,
+                name_span,
+                value,
+                ..
+            } => {
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Parameter,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                let value_expr = expr_arena.get(*value);
+                self.extract_symbols_from_expression(value_expr, expr_arena, content, uri, symbols);
+            }
+            ModuleItem::ModuleInstantiation {
+                module_name,
+                module_name_span,
+                instance_name,
+                instance_name_span,
+                parameters,
+                connections,
+                ..
+            } => {
+                // Add the instantiated module name as a symbol; the enclosing module
+                // declaration fills in the parent
+                if let Some(range) = self.span_to_range(content, *module_name_span) {
+                    symbols.push(Symbol {
+                        name: module_name.clone(),
+                        symbol_type: SymbolType::Instantiation {
+                            parent: String::new(),
+                            instance: instance_name.clone(),
+                        },
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+
+                // Add the instance name as a symbol
+                if let Some(range) = self.span_to_range(content, *instance_name_span) {
+                    symbols.push(Symbol {
+                        name: instance_name.clone(),
+                        symbol_type: SymbolType::Variable,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+
+                // Extract identifiers from the connected expressions
+                for connection in parameters.iter().chain(connections) {
+                    if let sv_parser::PortConnection::Positional(expr_ref)
+                    | sv_parser::PortConnection::Named {
+                        expr: Some(expr_ref),
+                        ..
+                    } = connection
+                    {
+                        let expr = expr_arena.get(*expr_ref);
+                        self.extract_symbols_from_expression(
+                            expr, expr_arena, content, uri, symbols,
+                        );
+                    }
+                }
+            }
+            ModuleItem::ElaborationTask { arguments, .. } => {
+                for arg in arguments {
+                    let expr = expr_arena.get(*arg);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+            }
+            ModuleItem::GenvarDeclaration { genvars, .. } => {
+                for genvar in genvars {
+                    if let Some(range) = self.span_to_range(content, genvar.name_span) {
+                        symbols.push(Symbol {
+                            name: genvar.name.clone(),
+                            symbol_type: SymbolType::Variable,
+                            range,
+                            uri: uri.clone(),
+                        });
+                    }
+                }
+            }
+            ModuleItem::GenerateFor {
+                declares_genvar,
+                genvar,
+                genvar_span,
+                init,
+                condition,
+                step,
+                body,
+                ..
+            } => {
+                // `for (genvar i = ...` declares the genvar; `for (i = ...` uses one
+                if *declares_genvar {
+                    if let Some(range) = self.span_to_range(content, *genvar_span) {
+                        symbols.push(Symbol {
+                            name: genvar.clone(),
+                            symbol_type: SymbolType::Variable,
+                            range,
+                            uri: uri.clone(),
+                        });
+                    }
+                }
+                for expr_ref in [init, condition, step] {
+                    let expr = expr_arena.get(*expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+                let sub_item = module_item_arena.get(*body);
+                self.extract_symbols_from_module_item(
+                    sub_item,
+                    expr_arena,
+                    stmt_arena,
+                    module_item_arena,
+                    content,
+                    uri,
+                    symbols,
+                );
+            }
+            ModuleItem::GenerateIf { condition, .. } => {
+                let expr = expr_arena.get(*condition);
+                self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                for sub_item_ref in item.generate_items() {
+                    let sub_item = module_item_arena.get(sub_item_ref);
+                    self.extract_symbols_from_module_item(
+                        sub_item,
+                        expr_arena,
+                        stmt_arena,
+                        module_item_arena,
+                        content,
+                        uri,
+                        symbols,
+                    );
+                }
+            }
+            ModuleItem::GenerateCase {
+                expr: case_expr,
+                items,
+                ..
+            } => {
+                let case_values = items.iter().flat_map(|case_item| &case_item.values);
+                for expr_ref in std::iter::once(case_expr).chain(case_values) {
+                    let expr = expr_arena.get(*expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+                for sub_item_ref in item.generate_items() {
+                    let sub_item = module_item_arena.get(sub_item_ref);
+                    self.extract_symbols_from_module_item(
+                        sub_item,
+                        expr_arena,
+                        stmt_arena,
+                        module_item_arena,
+                        content,
+                        uri,
+                        symbols,
+                    );
+                }
+            }
+            ModuleItem::GenerateRegion { items, .. } | ModuleItem::GenerateBlock { items, .. } => {
+                for &sub_item_ref in items {
+                    let sub_item = module_item_arena.get(sub_item_ref);
+                    self.extract_symbols_from_module_item(
+                        sub_item,
+                        expr_arena,
+                        stmt_arena,
+                        module_item_arena,
+                        content,
+                        uri,
+                        symbols,
+                    );
+                }
+            }
+            ModuleItem::ConfigDeclaration { .. }
+            | ModuleItem::ModportDeclaration { .. }
+            | ModuleItem::ImportDeclaration { .. }
+            | ModuleItem::ExportDeclaration { .. }
+            | ModuleItem::TimescaleDirective { .. }
+            | ModuleItem::DefaultNettypeDirective { .. }
+            | ModuleItem::TimeunitsDeclaration { .. } => {}
+        }
+    }
+
+    // Extract the members of an inline enum type and the identifiers in their values
+    fn extract_symbols_from_enum_members(
+        &self,
+        item: &ModuleItem,
+        expr_arena: &sv_parser::ExprArena,
+        content: &str,
+        uri: &Url,
+        symbols: &mut Vec<Symbol>,
+    ) {
+        for member in item.enum_members() {
+            if let Some(range) = self.span_to_range(content, member.name_span) {
+                symbols.push(Symbol {
+                    name: member.name.clone(),
+                    symbol_type: SymbolType::EnumMember,
+                    range,
+                    uri: uri.clone(),
+                });
+            }
+            if let Some(expr_ref) = member.value {
+                let expr = expr_arena.get(expr_ref);
+                self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+            }
+        }
+    }
+
+    // Extract symbols from a statement and the statements nested in it
+    fn extract_symbols_from_statement(
+        &self,
+        statement: &sv_parser::Statement,
+        expr_arena: &sv_parser::ExprArena,
+        stmt_arena: &sv_parser::StmtArena,
+        content: &str,
+        uri: &Url,
+        symbols: &mut Vec<Symbol>,
+    ) {
+        use sv_parser::Statement;
+        match statement {
+            Statement::Assignment { target, expr, .. } => {
+                // Extract identifiers from the target expression (e.g., for member access)
+                let target_expr = expr_arena.get(*target);
+                self.extract_symbols_from_expression(
+                    target_expr,
+                    expr_arena,
+                    content,
+                    uri,
+                    symbols,
+                );
+
+                // Extract identifiers from the value expression
+                let expr_val = expr_arena.get(*expr);
+                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
+            }
+            Statement::SystemCall { args, .. } => {
+                for &arg_ref in args {
+                    let arg = expr_arena.get(arg_ref);
+                    self.extract_symbols_from_expression(arg, expr_arena, content, uri, symbols);
+                }
+            }
+            Statement::CaseStatement { expr, items, .. } => {
+                let expr_val = expr_arena.get(*expr);
+                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
+                for value in items.iter().flat_map(|item| &item.values) {
+                    let value = expr_arena.get(*value);
+                    self.extract_symbols_from_expression(value, expr_arena, content, uri, symbols);
+                }
+            }
+            Statement::CaseMatches { expr, items, .. } => {
+                let expr_val = expr_arena.get(*expr);
+                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
+                for guard in items.iter().filter_map(|item| item.guard) {
+                    let guard = expr_arena.get(guard);
+                    self.extract_symbols_from_expression(guard, expr_arena, content, uri, symbols);
+                }
+            }
+            Statement::ExpressionStatement { expr, .. }
+            | Statement::EventTrigger { event: expr, .. } => {
+                let expr_val = expr_arena.get(*expr);
+                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
+            }
+            Statement::Conditional { condition, .. } => {
+                let condition = expr_arena.get(*condition);
+                self.extract_symbols_from_expression(condition, expr_arena, content, uri, symbols);
+            }
+            Statement::ImmediateAssertion { condition, .. } => {
+                let condition = expr_arena.get(*condition);
+                self.extract_symbols_from_expression(condition, expr_arena, content, uri, symbols);
+            }
+            Statement::ForLoop {
+                condition: Some(condition),
+                ..
+            }
+            | Statement::While { condition, .. }
+            | Statement::DoWhile { condition, .. }
+            | Statement::Repeat {
+                count: condition, ..
+            } => {
+                let condition = expr_arena.get(*condition);
+                self.extract_symbols_from_expression(condition, expr_arena, content, uri, symbols);
+            }
+            Statement::AssertProperty { property, .. } => {
+                for expr_ref in property.expressions() {
+                    let expr = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+            }
+            Statement::VariableDeclaration { initial_value, .. }
+            | Statement::Return {
+                value: initial_value,
+                ..
+            } => {
+                if let Some(expr_ref) = initial_value {
+                    let expr_val = expr_arena.get(*expr_ref);
+                    self.extract_symbols_from_expression(
+                        expr_val, expr_arena, content, uri, symbols,
+                    );
+                }
+            }
+            Statement::Timed { control, .. } => {
+                for expr_ref in control.expressions() {
+                    let expr_val = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(
+                        expr_val, expr_arena, content, uri, symbols,
+                    );
+                }
+            }
+            Statement::Block { .. }
+            | Statement::Fork { .. }
+            | Statement::Labeled { .. }
+            | Statement::ForLoop { .. }
+            | Statement::Forever { .. }
+            | Statement::Foreach { .. }
+            | Statement::WaitFork { .. }
+            | Statement::DisableFork { .. }
+            | Statement::Disable { .. } => {}
+        }
+        // The branches, bodies and action blocks nested in the statement
+        for child in statement.children() {
+            let child = stmt_arena.get(child);
+            self.extract_symbols_from_statement(
+                child, expr_arena, stmt_arena, content, uri, symbols,
+            );
+        }
+    }
+
+    // Build outline entries for a list of module items
+    fn document_symbols_from_items(
+        &self,
+        items: &[sv_parser::ModuleItemRef],
+        ast: &SourceUnit,
+        content: &str,
+    ) -> Vec<DocumentSymbol> {
+        items
+            .iter()
+            .flat_map(|&item_ref| match ast.module_item_arena.get(item_ref) {
+                // One entry per modport, so a declaration listing several shows all of them
+                ModuleItem::ModportDeclaration { modports, .. } => modports
+                    .iter()
+                    .filter_map(|modport| self.document_symbol_for_modport(modport, content))
+                    .collect(),
+                ModuleItem::GenvarDeclaration { genvars, span } => genvars
+                    .iter()
+                    .filter_map(|genvar| {
+                        self.new_document_symbol(
+                            content,
+                            genvar.name.clone(),
+                            SymbolKind::VARIABLE,
+                            Some("genvar".to_string()),
+                            genvar.name_span,
+                            *span,
+                            Vec::new(),
+                        )
+                    })
+                    .collect(),
+                // A named generate block is a scope of its own; the items of other
+                // generate constructs are listed where the construct is
+                ModuleItem::GenerateBlock {
+                    label: Some(label),
+                    items,
+                    span,
+                    ..
+                } => self
+                    .new_document_symbol(
+                        content,
+                        label.name.clone(),
+                        SymbolKind::NAMESPACE,
+                        Some("generate block".to_string()),
+                        label.span,
+                        *span,
+                        self.document_symbols_from_items(items, ast, content),
+                    )
+                    .into_iter()
+                    .collect(),
+                item @ (ModuleItem::GenerateRegion { .. }
+                | ModuleItem::GenerateBlock { .. }
+                | ModuleItem::GenerateFor { .. }
+                | ModuleItem::GenerateIf { .. }
+                | ModuleItem::GenerateCase { .. }) => {
+                    self.document_symbols_from_items(&item.generate_items(), ast, content)
+                }
+                item => self
+                    .document_symbol_for_item(item, ast, content)
+                    .into_iter()
+                    .collect::<Vec<_>>(),
+            })
+            .collect()
+    }
+
+    // Outline entry for a modport, with its signals as children
+    fn document_symbol_for_modport(
+        &self,
+        modport: &sv_parser::Modport,
+        content: &str,
+    ) -> Option<DocumentSymbol> {
+        let children = modport
+            .ports
+            .iter()
+            .filter_map(|port| {
+                self.new_document_symbol(
+                    content,
+                    port.name.clone(),
+                    SymbolKind::PROPERTY,
+                    Some(port_direction_keyword(&port.direction).to_string()),
+                    port.name_span,
+                    port.name_span,
+                    Vec::new(),
+                )
+            })
+            .collect();
+        self.new_document_symbol(
+            content,
+            modport.name.clone(),
+            SymbolKind::INTERFACE,
+            Some("modport".to_string()),
+            modport.name_span,
+            modport.span,
+            children,
+        )
+    }
+
+    // Outline entry for a module item, with a detail string and children where relevant
+    fn document_symbol_for_item(
+        &self,
+        item: &ModuleItem,
+        ast: &SourceUnit,
+        content: &str,
+    ) -> Option<DocumentSymbol> {
+        let (name, kind, detail, name_span, span, children) = match item {
+            ModuleItem::ModuleDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            }
+            | ModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            }
+            | ModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            } => {
+                // Non-ANSI ports are listed through their port declarations instead
+                let declared_ports: Vec<&str> = items
+                    .iter()
+                    .filter_map(|&item_ref| match ast.module_item_arena.get(item_ref) {
+                        ModuleItem::PortDeclaration { name, .. } => Some(name.as_str()),
+                        _ => None,
+                    })
+                    .collect();
+                let mut children: Vec<DocumentSymbol> = ports
+                    .iter()
+                    .filter(|port| !declared_ports.contains(&port.name.as_str()))
+                    .filter_map(|port| {
+                        let interface =
+                            port.interface
+                                .as_ref()
+                                .map(|interface| match &port.modport {
+                                    Some(modport) => format!("{}.{}", interface, modport),
+                                    None => interface.clone(),
+                                });
+                        let detail = format_declaration(&[
+                            port.direction.as_ref().map(port_direction_keyword),
+                            interface.as_deref(),
+                            port.range.as_ref().map(format_range).as_deref(),
+                        ]);
+                        self.new_document_symbol(
+                            content,
+                            port.name.clone(),
+                            SymbolKind::PROPERTY,
+                            detail,
+                            port.name_span,
+                            port.span,
+                            Vec::new(),
+                        )
+                    })
+                    .collect();
+                children.extend(self.document_symbols_from_items(items, ast, content));
+                let port_names: Vec<&str> = ports.iter().map(|port| port.name.as_str()).collect();
+                let detail = format!("({})", port_names.join(", "));
+                let kind = if matches!(item, ModuleItem::InterfaceDeclaration { .. }) {
+                    SymbolKind::INTERFACE
+                } else {
+                    SymbolKind::MODULE
+                };
+                (
+                    name.clone(),
+                    kind,
+                    Some(detail),
+                    *name_span,
+                    *span,
+                    children,
+                )
+            }
+            ModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items,
+                span,
+            } => (
+                name.clone(),
+                SymbolKind::PACKAGE,
+                None,
+                *name_span,
+                *span,
+                self.document_symbols_from_items(items, ast, content),
+            ),
+            ModuleItem::PortDeclaration {
+                direction,
+                port_type,
+                name,
+                name_span,
+                span,
+            } => (
+                name.clone(),
+                SymbolKind::PROPERTY,
+                format_declaration(&[Some(port_direction_keyword(direction)), Some(port_type)]),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::VariableDeclaration {
+                data_type,
+                signing,
+                range,
+                name,
+                name_span,
+                span,
+                ..
+            } => (
+                name.clone(),
+                if data_type.name() == "event" {
+                    SymbolKind::EVENT
+                } else {
+                    SymbolKind::VARIABLE
+                },
+                format_declaration(&[
+                    Some(data_type.name()),
+                    signing.as_deref(),
+                    range.as_ref().map(format_range).as_deref(),
+                ]),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::TypedefDeclaration {
+                data_type,
+                signing,
+                range,
+                name,
+                name_span,
+                span,
+                ..
+            } => {
+                let children = item
+                    .enum_members()
+                    .iter()
+                    .filter_map(|member| {
+                        self.new_document_symbol(
+                            content,
+                            member.name.clone(),
+                            SymbolKind::ENUM_MEMBER,
+                            None,
+                            member.name_span,
+                            member.name_span,
+                            Vec::new(),
+                        )
+                    })
+                    .collect();
+                (
+                    name.clone(),
+                    SymbolKind::TYPE_PARAMETER,
+                    format_declaration(&[
+                        Some("typedef"),
+                        Some(data_type.name()),
+                        signing.as_deref(),
+                        range.as_ref().map(format_range).as_deref(),
+                    ]),
+                    *name_span,
+                    *span,
+                    children,
+                )
+            }
+            ModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                name_span,
+                span,
+                ..
+            } => {
+                // Show the default value as written in the source
+                let default = content
+                    .get(span.0..span.1)
+                    .and_then(|text| text.split_once('='))
+                    .map(|(_, value)| format!("= {}", value.trim().trim_end_matches(';').trim()));
+                (
+                    name.clone(),
+                    SymbolKind::CONSTANT,
+                    format_declaration(&[
+                        Some(if *local { "localparam" } else { "parameter" }),
+                        data_type.as_deref(),
+                        range.as_ref().map(format_range).as_deref(),
+                        default.as_deref(),
+                    ]),
+                    *name_span,
+                    *span,
+                    Vec::new(),
+                )
+            }
+            ModuleItem::ClassDeclaration {
+                name,
+                name_span,
+                extends,
+                implements,
+                items,
+                span,
+                ..
             } => {
-                // Add class declaration as a symbol
-                if let Some(range) = self.span_to_range(content, *name_span) {
-                    symbols.push(Symbol {
-                        name: name.clone(),
-                        symbol_type: SymbolType::Class,
-                        range,
-                        uri: uri.clone(),
-                    });
+                let children = items
+                    .iter()
+                    .filter_map(|class_item| {
+                        self.document_symbol_for_class_item(class_item, content)
+                    })
+                    .collect();
+                let mut clauses = Vec::new();
+                if let Some(base) = extends {
+                    clauses.push(format!("extends {}", base));
                 }
-                // Extract class members (properties and methods) as symbols
-                for class_item in items {
-                    self.extract_symbols_from_class_item(
-                        class_item, expr_arena, stmt_arena, content, uri, symbols,
-                    );
+                if !implements.is_empty() {
+                    clauses.push(format!("implements {}", implements.join(", ")));
                 }
+                (
+                    name.clone(),
+                    SymbolKind::CLASS,
+                    (!clauses.is_empty()).then(|| clauses.join(" ")),
+                    *name_span,
+                    *span,
+                    children,
+                )
             }
-            ModuleItem::ConcurrentAssertion { statement, .. } => {
-                // Extract symbols from the assertion statement - statement is now StmtRef
-                let stmt = stmt_arena.get(*statement);
-                self.extract_symbols_from_statement(stmt, expr_arena, content, uri, symbols);
+            ModuleItem::InterfaceClassDeclaration {
+                name,
+                name_span,
+                extends,
+                items,
+                span,
+            } => {
+                let children = items
+                    .iter()
+                    .filter_map(|class_item| {
+                        self.document_symbol_for_class_item(class_item, content)
+                    })
+                    .collect();
+                (
+                    name.clone(),
+                    SymbolKind::INTERFACE,
+                    (!extends.is_empty()).then(|| format!("extends {}", extends.join(", "))),
+                    *name_span,
+                    *span,
+                    children,
+                )
             }
-            ModuleItem::GlobalClocking {
-                identifier,
-                identifier_span,
+            ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                name,
+                name_span,
+                parameters,
+                span,
+                ..
+            } => (
+                sv_parser::scoped_method_name(class_name, name),
+                if name == "new" {
+                    SymbolKind::CONSTRUCTOR
+                } else {
+                    SymbolKind::METHOD
+                },
+                Some(format!(
+                    "function {}({})",
+                    return_type.as_deref().unwrap_or("void"),
+                    parameters.join(", ")
+                )),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                span,
+                ..
+            } => (
+                name.clone(),
+                SymbolKind::FUNCTION,
+                Some(if *is_task {
+                    format!("task ({})", parameters.join(", "))
+                } else {
+                    format!(
+                        "function {}({})",
+                        return_type.as_deref().unwrap_or("void"),
+                        parameters.join(", ")
+                    )
+                }),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::ModuleInstantiation {
+                module_name,
+                instance_name,
+                instance_name_span,
+                span,
                 ..
+            } => (
+                instance_name.clone(),
+                SymbolKind::OBJECT,
+                Some(module_name.clone()),
+                *instance_name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::DefineDirective {
+                name,
+                name_span,
+                parameters,
+                value,
+                span,
             } => {
-                // Add global clocking identifier as a symbol if present
-                if let (Some(name), Some(span)) = (identifier, identifier_span) {
-                    if let Some(range) = self.span_to_range(content, *span) {
-                        symbols.push(Symbol {
-                            name: name.clone(),
-                            symbol_type: SymbolType::Variable, // Use Variable for now
-                            range,
-                            uri: uri.clone(),
-                        });
-                    }
-                }
+                let detail = if parameters.is_empty() {
+                    value.trim().to_string()
+                } else {
+                    format!("({}) {}", parameters.join(", "), value.trim())
+                };
+                (
+                    name.clone(),
+                    SymbolKind::CONSTANT,
+                    (!detail.is_empty()).then_some(detail),
+                    *name_span,
+                    *span,
+                    Vec::new(),
+                )
             }
-        }
+            ModuleItem::IncludeDirective {
+                path,
+                path_span,
+                span,
+                ..
+            } => (
+                path.clone(),
+                SymbolKind::FILE,
+                None,
+                *path_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::GlobalClocking {
+                identifier: Some(name),
+                identifier_span: Some(name_span),
+                span,
+                ..
+            } => (
+                name.clone(),
+                SymbolKind::EVENT,
+                Some("global clocking".to_string()),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::ClockingBlock {
+                default,
+                name,
+                name_span,
+                signals,
+                span,
+                ..
+            } => (
+                name.clone(),
+                SymbolKind::EVENT,
+                Some(
+                    if *default {
+                        "default clocking"
+                    } else {
+                        "clocking"
+                    }
+                    .to_string(),
+                ),
+                *name_span,
+                *span,
+                signals
+                    .iter()
+                    .filter_map(|signal| {
+                        let direction = port_direction_keyword(&signal.direction);
+                        let detail = match &signal.skew {
+                            Some(skew) => format!("{} {}", direction, skew),
+                            None => direction.to_string(),
+                        };
+                        self.new_document_symbol(
+                            content,
+                            signal.name.clone(),
+                            SymbolKind::FIELD,
+                            Some(detail),
+                            signal.name_span,
+                            signal.span,
+                            Vec::new(),
+                        )
+                    })
+                    .collect(),
+            ),
+            ModuleItem::ConfigDeclaration {
+                name,
+                name_span,
+                span,
+                ..
+            } => (
+                name.clone(),
+                SymbolKind::NAMESPACE,
+                Some("config".to_string()),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                span,
+                ..
+            } => (
+                name.clone(),
+                SymbolKind::FUNCTION,
+                Some(kind.keyword().to_string()),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            ModuleItem::GlobalClocking { .. }
+            | ModuleItem::Assignment { .. }
+            | ModuleItem::ProceduralBlock { .. }
+            | ModuleItem::ConcurrentAssertion { .. }
+            | ModuleItem::DefaultClocking { .. }
+            | ModuleItem::DefaultDisableIff { .. }
+            | ModuleItem::ModportDeclaration { .. }
+            | ModuleItem::ImportDeclaration { .. }
+            | ModuleItem::ExportDeclaration { .. }
+            | ModuleItem::ElaborationTask { .. }
+            | ModuleItem::TimescaleDirective { .. }
+            | ModuleItem::DefaultNettypeDirective { .. }
+            | ModuleItem::TimeunitsDeclaration { .. }
+            | ModuleItem::GenvarDeclaration { .. }
+            | ModuleItem::GenerateRegion { .. }
+            | ModuleItem::GenerateBlock { .. }
+            | ModuleItem::GenerateFor { .. }
+            | ModuleItem::GenerateIf { .. }
+            | ModuleItem::GenerateCase { .. } => return None,
+        };
+
+        self.new_document_symbol(content, name, kind, detail, name_span, span, children)
     }
 
-    // Extract symbols from statements
-    fn extract_symbols_from_statement(
+    // Outline entry for a class property or method
+    fn document_symbol_for_class_item(
         &self,
-        statement: &sv_parser::Statement,
-        expr_arena: &sv_parser::ExprArena,
+        class_item: &sv_parser::ClassItem,
         content: &str,
-        uri: &Url,
-        symbols: &mut Vec<Symbol>,
-    ) {
-        use sv_parser::Statement;
-        match statement {
-            Statement::Assignment { target, expr, .. } => {
-                // Extract identifiers from the target expression (e.g., for member access)
-                let target_expr = expr_arena.get(*target);
-                self.extract_symbols_from_expression(
-                    target_expr,
-                    expr_arena,
-                    content,
-                    uri,
-                    symbols,
-                );
-
-                // Extract identifiers from the value expression
-                let expr_val = expr_arena.get(*expr);
-                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
-            }
-            Statement::SystemCall { args, .. } => {
-                for &arg_ref in args {
-                    let arg = expr_arena.get(arg_ref);
-                    self.extract_symbols_from_expression(arg, expr_arena, content, uri, symbols);
-                }
-            }
-            Statement::CaseStatement { expr, .. } => {
-                let expr_val = expr_arena.get(*expr);
-                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
-            }
-            Statement::ExpressionStatement { expr, .. } => {
-                let expr_val = expr_arena.get(*expr);
-                self.extract_symbols_from_expression(expr_val, expr_arena, content, uri, symbols);
-            }
-            Statement::AssertProperty {
-                property_expr,
-                action_block,
+    ) -> Option<DocumentSymbol> {
+        match class_item {
+            sv_parser::ClassItem::Property {
+                is_static,
+                rand,
+                data_type,
+                name,
+                name_span,
+                span,
+                ..
+            } => self.new_document_symbol(
+                content,
+                name.clone(),
+                SymbolKind::FIELD,
+                Some(format!(
+                    "{}{}{}",
+                    if *is_static { "static " } else { "" },
+                    match rand {
+                        Some(sv_parser::RandQualifier::Rand) => "rand ",
+                        Some(sv_parser::RandQualifier::Randc) => "randc ",
+                        None => "",
+                    },
+                    data_type.name()
+                )),
+                *name_span,
+                *span,
+                Vec::new(),
+            ),
+            sv_parser::ClassItem::Method {
+                is_static,
+                is_task,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                span,
                 ..
             } => {
-                let property = expr_arena.get(*property_expr);
-                self.extract_symbols_from_expression(property, expr_arena, content, uri, symbols);
-                if let Some(action_stmt_ref) = action_block {
-                    // TODO: Need stmt_arena to dereference action_stmt_ref
-                    let _ = action_stmt_ref; // Silence unused warning for now
-                                             // self.extract_symbols_from_statement(action_stmt, expr_arena, content, uri, symbols);
-                }
-            }
-            Statement::VariableDeclaration { initial_value, .. } => {
-                if let Some(expr_ref) = initial_value {
-                    let expr_val = expr_arena.get(*expr_ref);
-                    self.extract_symbols_from_expression(
-                        expr_val, expr_arena, content, uri, symbols,
-                    );
-                }
+                let detail = if *is_task {
+                    format!(
+                        "{}task({})",
+                        if *is_static { "static " } else { "" },
+                        parameters.join(", ")
+                    )
+                } else {
+                    format!(
+                        "{}function {}({})",
+                        if *is_static { "static " } else { "" },
+                        return_type.as_deref().unwrap_or("void"),
+                        parameters.join(", ")
+                    )
+                };
+                let kind = if class_item.is_constructor() {
+                    SymbolKind::CONSTRUCTOR
+                } else {
+                    SymbolKind::METHOD
+                };
+                self.new_document_symbol(
+                    content,
+                    name.clone(),
+                    kind,
+                    Some(detail),
+                    *name_span,
+                    *span,
+                    Vec::new(),
+                )
             }
+            sv_parser::ClassItem::Constraint(block) => self.new_document_symbol(
+                content,
+                block.name.clone(),
+                SymbolKind::PROPERTY,
+                Some(
+                    if block.is_static {
+                        "static constraint"
+                    } else {
+                        "constraint"
+                    }
+                    .to_string(),
+                ),
+                block.name_span,
+                block.span,
+                Vec::new(),
+            ),
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn new_document_symbol(
+        &self,
+        content: &str,
+        name: String,
+        kind: SymbolKind,
+        detail: Option<String>,
+        name_span: sv_parser::Span,
+        span: sv_parser::Span,
+        children: Vec<DocumentSymbol>,
+    ) -> Option<DocumentSymbol> {
+        let selection_range = self.span_to_range(content, name_span)?;
+        // Item spans can include the whitespace and comments before and after
+        // the item; the outline range should cover just the item itself
+        let range = self
+            .span_to_range(content, trim_span(content, span))
+            .filter(|range| {
+                range.start <= selection_range.start && selection_range.end <= range.end
+            })
+            .unwrap_or(selection_range);
+
+        #[allow(deprecated)]
+        Some(DocumentSymbol {
+            name,
+            detail,
+            kind,
+            tags: None,
+            deprecated: None,
+            range,
+            selection_range,
+            children: (!children.is_empty()).then_some(children),
+        })
+    }
+
     // Extract symbols from expressions
     fn extract_symbols_from_expression(
         &self,
@@ -2410,7 +5351,7 @@ impl Backend {
                 let right_expr = expr_arena.get(*right);
                 self.extract_symbols_from_expression(right_expr, expr_arena, content, uri, symbols);
             }
-            Expression::Unary { operand, .. } => {
+            Expression::Unary { operand, .. } | Expression::IncDec { operand, .. } => {
                 let operand_expr = expr_arena.get(*operand);
                 self.extract_symbols_from_expression(
                     operand_expr,
@@ -2480,6 +5421,43 @@ impl Backend {
                     });
                 }
             }
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                for &operand_ref in &[*condition, *then_expr, *else_expr] {
+                    let operand = expr_arena.get(operand_ref);
+                    self.extract_symbols_from_expression(
+                        operand, expr_arena, content, uri, symbols,
+                    );
+                }
+            }
+            Expression::ValueRange { low, high, .. } => {
+                for &bound_ref in &[*low, *high] {
+                    let bound = expr_arena.get(bound_ref);
+                    self.extract_symbols_from_expression(bound, expr_arena, content, uri, symbols);
+                }
+            }
+            Expression::TaggedUnion { value, .. } => {
+                if let Some(value) = value {
+                    let value = expr_arena.get(*value);
+                    self.extract_symbols_from_expression(value, expr_arena, content, uri, symbols);
+                }
+            }
+            Expression::ScopedIdentifier {
+                name, name_span, ..
+            } => {
+                if let Some(range) = self.span_to_range(content, *name_span) {
+                    symbols.push(Symbol {
+                        name: name.clone(),
+                        symbol_type: SymbolType::Variable,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+            }
             Expression::FunctionCall {
                 function,
                 arguments,
@@ -2500,8 +5478,27 @@ impl Backend {
                     self.extract_symbols_from_expression(arg, expr_arena, content, uri, symbols);
                 }
             }
-            Expression::Number(_, _) | Expression::StringLiteral(_, _) => {
-                // Numbers and string literals are not identifiers we care about for renaming
+            Expression::AssignmentPattern { items, .. } => {
+                for item in items {
+                    let value = expr_arena.get(item.value);
+                    self.extract_symbols_from_expression(value, expr_arena, content, uri, symbols);
+                }
+            }
+            Expression::RandomizeWith {
+                call, constraints, ..
+            } => {
+                let call_expr = expr_arena.get(*call);
+                self.extract_symbols_from_expression(call_expr, expr_arena, content, uri, symbols);
+                for expr_ref in constraints.iter().flat_map(|c| c.expressions()) {
+                    let expr = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+            }
+            Expression::Number(_, _)
+            | Expression::StringLiteral(_, _)
+            | Expression::This(_)
+            | Expression::Super(_) => {
+                // Literals and class handles are not identifiers we care about for renaming
             }
         }
     }
@@ -2558,13 +5555,40 @@ impl Backend {
                 for &stmt_ref in body {
                     let statement = stmt_arena.get(stmt_ref);
                     self.extract_symbols_from_statement(
-                        statement, expr_arena, content, uri, symbols,
+                        statement, expr_arena, stmt_arena, content, uri, symbols,
                     );
                 }
             }
+            ClassItem::Constraint(block) => {
+                // Add the constraint block as a symbol, like a property
+                if let Some(range) = self.span_to_range(content, block.name_span) {
+                    symbols.push(Symbol {
+                        name: block.name.clone(),
+                        symbol_type: SymbolType::Variable,
+                        range,
+                        uri: uri.clone(),
+                    });
+                }
+                for expr_ref in block.constraints.iter().flat_map(|c| c.expressions()) {
+                    let expr = expr_arena.get(expr_ref);
+                    self.extract_symbols_from_expression(expr, expr_arena, content, uri, symbols);
+                }
+            }
         }
     }
 
+    /// Class declarations named `name` anywhere in the workspace
+    async fn class_symbols(&self, name: &str) -> Vec<Symbol> {
+        let workspace_symbols = self.workspace_symbols.read().await;
+        workspace_symbols
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter(|symbol| matches!(symbol.symbol_type, SymbolType::Class { .. }))
+            .cloned()
+            .collect()
+    }
+
     // Check if a position is within a range
     fn position_in_range(&self, position: Position, range: Range) -> bool {
         (position.line > range.start.line
@@ -2626,6 +5650,76 @@ impl Backend {
         }
     }
 
+    async fn index_cache_directory(&self) -> Option<PathBuf> {
+        let config = self.config.read().await;
+        let workspace_root = self.workspace_root.read().await;
+        index_cache::cache_directory(config.cache_directory.as_deref(), workspace_root.as_deref())
+    }
+
+    // Load the persisted symbol index, skipping files that changed since it was written
+    async fn load_index_cache(&self) {
+        let Some(cache_dir) = self.index_cache_directory().await else {
+            return;
+        };
+        let Some(cache) = IndexCache::load(&cache_dir) else {
+            return;
+        };
+
+        let valid_files = cache.into_valid_files();
+        let file_count = valid_files.len();
+        {
+            let mut workspace_symbols = self.workspace_symbols.write().await;
+            let mut indexed_files = self.indexed_files.write().await;
+            for (uri, indexed) in valid_files {
+                // Documents opened before the cache was loaded are already indexed
+                if indexed_files.contains_key(&uri) {
+                    continue;
+                }
+                for symbol in &indexed.symbols {
+                    if !matches!(symbol.symbol_type, SymbolType::Include) {
+                        workspace_symbols
+                            .entry(symbol.name.clone())
+                            .or_insert_with(Vec::new)
+                            .push(symbol.clone());
+                    }
+                }
+                indexed_files.insert(uri, indexed);
+            }
+        }
+
+        self.client
+            .log_message(
+                MessageType::INFO,
+                format!(
+                    "Loaded symbol index for {} files from {}",
+                    file_count,
+                    cache_dir.display()
+                ),
+            )
+            .await;
+    }
+
+    // Persist the symbol index so the next session starts with it
+    async fn save_index_cache(&self) {
+        let Some(cache_dir) = self.index_cache_directory().await else {
+            return;
+        };
+
+        let cache = IndexCache::new(self.indexed_files.read().await.clone());
+        if let Err(e) = cache.save(&cache_dir) {
+            self.client
+                .log_message(
+                    MessageType::WARNING,
+                    format!(
+                        "Failed to write symbol index to {}: {}",
+                        cache_dir.display(),
+                        e
+                    ),
+                )
+                .await;
+        }
+    }
+
     // Validate configuration and warn about issues
     async fn validate_config(&self, config: &ServerConfig) {
         let workspace_root = self.workspace_root.read().await;
@@ -2718,12 +5812,7 @@ impl Backend {
         // ast.items is now Vec<ModuleItemRef>
         for &item_ref in &ast.items {
             let item = ast.module_item_arena.get(item_ref);
-            self.extract_folding_ranges_from_item(
-                item,
-                &ast.module_item_arena,
-                content,
-                &mut ranges,
-            );
+            self.extract_folding_ranges_from_item(item, ast, content, &mut ranges);
         }
 
         ranges
@@ -2732,18 +5821,33 @@ impl Backend {
     fn extract_folding_ranges_from_item(
         &self,
         item: &ModuleItem,
-        module_item_arena: &sv_parser::ModuleItemArena,
+        ast: &SourceUnit,
         content: &str,
         ranges: &mut Vec<FoldingRange>,
     ) {
         match item {
             ModuleItem::ModuleDeclaration {
                 name, items, span, ..
+            }
+            | ModuleItem::InterfaceDeclaration {
+                name, items, span, ..
+            }
+            | ModuleItem::CheckerDeclaration {
+                name, items, span, ..
+            }
+            | ModuleItem::PackageDeclaration {
+                name, items, span, ..
             } => {
+                let keyword = match item {
+                    ModuleItem::InterfaceDeclaration { .. } => "interface",
+                    ModuleItem::CheckerDeclaration { .. } => "checker",
+                    ModuleItem::PackageDeclaration { .. } => "package",
+                    _ => "module",
+                };
                 // Create folding range from span
                 if let Some(range) = self.span_to_folding_range(content, *span) {
                     ranges.push(FoldingRange {
-                        collapsed_text: Some(format!("module {} ...", name)),
+                        collapsed_text: Some(format!("{} {} ...", keyword, name)),
                         kind: Some(FoldingRangeKind::Region),
                         ..range
                     });
@@ -2751,16 +5855,13 @@ impl Backend {
 
                 // Recursively process nested items - items are refs into the arena
                 for &sub_item_ref in items {
-                    let sub_item = module_item_arena.get(sub_item_ref);
-                    self.extract_folding_ranges_from_item(
-                        sub_item,
-                        module_item_arena,
-                        content,
-                        ranges,
-                    );
+                    let sub_item = ast.module_item_arena.get(sub_item_ref);
+                    self.extract_folding_ranges_from_item(sub_item, ast, content, ranges);
                 }
             }
-            ModuleItem::ProceduralBlock { span, .. } => {
+            ModuleItem::ProceduralBlock {
+                statements, span, ..
+            } => {
                 // Add folding range for procedural blocks (always, initial, etc.)
                 if let Some(range) = self.span_to_folding_range(content, *span) {
                     ranges.push(FoldingRange {
@@ -2768,14 +5869,43 @@ impl Backend {
                         ..range
                     });
                 }
+
+                for &stmt_ref in statements {
+                    self.extract_folding_ranges_from_statement(stmt_ref, ast, content, ranges);
+                }
+            }
+            ModuleItem::ClockingBlock { name, span, .. } => {
+                if let Some(range) = self.span_to_folding_range(content, *span) {
+                    ranges.push(FoldingRange {
+                        collapsed_text: Some(format!("clocking {} ...", name)),
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
+            }
+            ModuleItem::ConfigDeclaration { name, span, .. } => {
+                if let Some(range) = self.span_to_folding_range(content, *span) {
+                    ranges.push(FoldingRange {
+                        collapsed_text: Some(format!("config {} ...", name)),
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
             }
             ModuleItem::ClassDeclaration {
                 name, items, span, ..
+            }
+            | ModuleItem::InterfaceClassDeclaration {
+                name, items, span, ..
             } => {
+                let keyword = match item {
+                    ModuleItem::InterfaceClassDeclaration { .. } => "interface class",
+                    _ => "class",
+                };
                 // Add folding range for class
                 if let Some(range) = self.span_to_folding_range(content, *span) {
                     ranges.push(FoldingRange {
-                        collapsed_text: Some(format!("class {} ...", name)),
+                        collapsed_text: Some(format!("{} {} ...", keyword, name)),
                         kind: Some(FoldingRangeKind::Region),
                         ..range
                     });
@@ -2783,7 +5913,10 @@ impl Backend {
 
                 // Also add folding ranges for class methods
                 for class_item in items {
-                    if let sv_parser::ClassItem::Method { name, span, .. } = class_item {
+                    if let sv_parser::ClassItem::Method {
+                        name, body, span, ..
+                    } = class_item
+                    {
                         if let Some(range) = self.span_to_folding_range(content, *span) {
                             ranges.push(FoldingRange {
                                 collapsed_text: Some(format!("function {} ...", name)),
@@ -2791,21 +5924,142 @@ impl Backend {
                                 ..range
                             });
                         }
+                        for &stmt_ref in body {
+                            self.extract_folding_ranges_from_statement(
+                                stmt_ref, ast, content, ranges,
+                            );
+                        }
                     }
                 }
             }
+            ModuleItem::OutOfBlockMethod {
+                class_name,
+                name,
+                body,
+                span,
+                ..
+            } => {
+                if let Some(range) = self.span_to_folding_range(content, *span) {
+                    ranges.push(FoldingRange {
+                        collapsed_text: Some(format!(
+                            "function {} ...",
+                            sv_parser::scoped_method_name(class_name, name)
+                        )),
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
+                for &stmt_ref in body {
+                    self.extract_folding_ranges_from_statement(stmt_ref, ast, content, ranges);
+                }
+            }
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                name,
+                body,
+                span,
+                ..
+            } => {
+                if let Some(range) = self.span_to_folding_range(content, *span) {
+                    let keyword = if *is_task { "task" } else { "function" };
+                    ranges.push(FoldingRange {
+                        collapsed_text: Some(format!("{} {} ...", keyword, name)),
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
+                for &stmt_ref in body {
+                    self.extract_folding_ranges_from_statement(stmt_ref, ast, content, ranges);
+                }
+            }
+            ModuleItem::GenerateRegion { span, .. }
+            | ModuleItem::GenerateBlock { span, .. }
+            | ModuleItem::GenerateFor { span, .. }
+            | ModuleItem::GenerateIf { span, .. }
+            | ModuleItem::GenerateCase { span, .. } => {
+                let collapsed_text = match item {
+                    ModuleItem::GenerateRegion { .. } => Some("generate ...".to_string()),
+                    ModuleItem::GenerateBlock {
+                        label: Some(label), ..
+                    } => Some(format!("begin : {} ...", label.name)),
+                    _ => None,
+                };
+                if let Some(range) = self.span_to_folding_range(content, *span) {
+                    ranges.push(FoldingRange {
+                        collapsed_text,
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
+
+                for sub_item_ref in item.generate_items() {
+                    let sub_item = ast.module_item_arena.get(sub_item_ref);
+                    self.extract_folding_ranges_from_item(sub_item, ast, content, ranges);
+                }
+            }
+            ModuleItem::AssertionDeclaration {
+                kind, name, span, ..
+            } => {
+                if let Some(range) = self.span_to_folding_range(content, *span) {
+                    ranges.push(FoldingRange {
+                        collapsed_text: Some(format!("{} {} ...", kind.keyword(), name)),
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
+            }
             ModuleItem::VariableDeclaration { .. }
+            | ModuleItem::TypedefDeclaration { .. }
             | ModuleItem::Assignment { .. }
             | ModuleItem::PortDeclaration { .. }
             | ModuleItem::DefineDirective { .. }
             | ModuleItem::IncludeDirective { .. }
+            | ModuleItem::TimescaleDirective { .. }
+            | ModuleItem::DefaultNettypeDirective { .. }
+            | ModuleItem::TimeunitsDeclaration { .. }
             | ModuleItem::ConcurrentAssertion { .. }
-            | ModuleItem::GlobalClocking { .. } => {
+            | ModuleItem::GlobalClocking { .. }
+            | ModuleItem::DefaultClocking { .. }
+            | ModuleItem::DefaultDisableIff { .. }
+            | ModuleItem::ParameterDeclaration { .. }
+            | ModuleItem::ModuleInstantiation { .. }
+            | ModuleItem::ModportDeclaration { .. }
+            | ModuleItem::ImportDeclaration { .. }
+            | ModuleItem::ExportDeclaration { .. }
+            | ModuleItem::ElaborationTask { .. }
+            | ModuleItem::GenvarDeclaration { .. } => {
                 // These items typically don't need folding
             }
         }
     }
 
+    // Fold case statements and each of their items, in this statement and
+    // the statements nested in it
+    fn extract_folding_ranges_from_statement(
+        &self,
+        stmt_ref: sv_parser::StmtRef,
+        ast: &SourceUnit,
+        content: &str,
+        ranges: &mut Vec<FoldingRange>,
+    ) {
+        let statement = ast.stmt_arena.get(stmt_ref);
+        if let sv_parser::Statement::CaseStatement { items, span, .. } = statement {
+            let spans = std::iter::once(*span).chain(items.iter().map(|item| item.span));
+            for span in spans {
+                if let Some(range) = self.span_to_folding_range(content, span) {
+                    ranges.push(FoldingRange {
+                        kind: Some(FoldingRangeKind::Region),
+                        ..range
+                    });
+                }
+            }
+        }
+
+        for child in statement.children() {
+            self.extract_folding_ranges_from_statement(child, ast, content, ranges);
+        }
+    }
+
     fn span_to_folding_range(&self, content: &str, span: (usize, usize)) -> Option<FoldingRange> {
         // Convert byte offsets to line numbers
         let start_line = content[..span.0].matches('\n').count();
@@ -2813,7 +6067,7 @@ impl Backend {
 
         // Create folding range if it spans multiple lines (at least 2)
         // Some editors require at least 1 line of difference to show fold indicators
-        if end_line >= start_line + 1 {
+        if end_line > start_line {
             Some(FoldingRange {
                 start_line: start_line as u32,
                 start_character: None,
@@ -2895,6 +6149,24 @@ impl Backend {
                 items,
                 name_span,
                 ..
+            }
+            | ModuleItem::InterfaceDeclaration {
+                span,
+                items,
+                name_span,
+                ..
+            }
+            | ModuleItem::CheckerDeclaration {
+                span,
+                items,
+                name_span,
+                ..
+            }
+            | ModuleItem::PackageDeclaration {
+                span,
+                items,
+                name_span,
+                ..
             } => {
                 if contains(*span) {
                     ranges.push(*span);
@@ -2920,6 +6192,12 @@ impl Backend {
                 name_span,
                 items: class_items,
                 ..
+            }
+            | ModuleItem::InterfaceClassDeclaration {
+                span,
+                name_span,
+                items: class_items,
+                ..
             } => {
                 if contains(*span) {
                     ranges.push(*span);
@@ -2948,6 +6226,13 @@ impl Backend {
                             if contains(*name_span) {
                                 ranges.push(*name_span);
                             }
+                        } else if let sv_parser::ClassItem::Constraint(block) = class_item {
+                            if contains(block.span) {
+                                ranges.push(block.span);
+                            }
+                            if contains(block.name_span) {
+                                ranges.push(block.name_span);
+                            }
                         }
                     }
                 }
@@ -2960,8 +6245,17 @@ impl Backend {
             ModuleItem::VariableDeclaration {
                 span, name_span, ..
             }
+            | ModuleItem::TypedefDeclaration {
+                span, name_span, ..
+            }
             | ModuleItem::PortDeclaration {
                 span, name_span, ..
+            }
+            | ModuleItem::OutOfBlockMethod {
+                span, name_span, ..
+            }
+            | ModuleItem::SubroutineDeclaration {
+                span, name_span, ..
             } => {
                 if contains(*span) {
                     ranges.push(*span);
@@ -2981,12 +6275,20 @@ impl Backend {
                     Expression::MemberAccess { span: s, .. } => *s,
                     Expression::Number(_, s) => *s,
                     Expression::StringLiteral(_, s) => *s,
+                    Expression::This(s) | Expression::Super(s) => *s,
                     Expression::Binary { span: s, .. } => *s,
                     Expression::Unary { span: s, .. } => *s,
+                    Expression::IncDec { span: s, .. } => *s,
                     Expression::MacroUsage { span: s, .. } => *s,
                     Expression::SystemFunctionCall { span: s, .. } => *s,
                     Expression::New { span: s, .. } => *s,
                     Expression::FunctionCall { span: s, .. } => *s,
+                    Expression::ScopedIdentifier { span: s, .. } => *s,
+                    Expression::Conditional { span: s, .. } => *s,
+                    Expression::TaggedUnion { span: s, .. } => *s,
+                    Expression::AssignmentPattern { span: s, .. } => *s,
+                    Expression::ValueRange { span: s, .. } => *s,
+                    Expression::RandomizeWith { span: s, .. } => *s,
                 };
                 if contains(target_span) {
                     ranges.push(target_span);
@@ -2995,6 +6297,9 @@ impl Backend {
             ModuleItem::DefineDirective {
                 span, name_span, ..
             }
+            | ModuleItem::ParameterDeclaration {
+                span, name_span, ..
+            }
             | ModuleItem::IncludeDirective {
                 span,
                 path_span: name_span,
@@ -3026,6 +6331,112 @@ impl Backend {
                     }
                 }
             }
+            ModuleItem::ModuleInstantiation {
+                span,
+                instance_name_span,
+                ..
+            } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                }
+                if contains(*instance_name_span) {
+                    ranges.push(*instance_name_span);
+                }
+            }
+            ModuleItem::ImportDeclaration { imports, span }
+            | ModuleItem::ExportDeclaration {
+                exports: imports,
+                span,
+            } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                    for import in imports.iter().filter(|import| contains(import.span)) {
+                        ranges.push(import.span);
+                        if contains(import.package_span) {
+                            ranges.push(import.package_span);
+                        } else if contains(import.name_span) {
+                            ranges.push(import.name_span);
+                        }
+                    }
+                }
+            }
+            ModuleItem::GenvarDeclaration { genvars, span } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                    for genvar in genvars.iter().filter(|genvar| contains(genvar.name_span)) {
+                        ranges.push(genvar.name_span);
+                    }
+                }
+            }
+            ModuleItem::GenerateRegion { span, .. }
+            | ModuleItem::GenerateBlock { span, .. }
+            | ModuleItem::GenerateFor { span, .. }
+            | ModuleItem::GenerateIf { span, .. }
+            | ModuleItem::GenerateCase { span, .. } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                    if let ModuleItem::GenerateFor { genvar_span, .. } = item {
+                        if contains(*genvar_span) {
+                            ranges.push(*genvar_span);
+                        }
+                    }
+                    for sub_item_ref in item.generate_items() {
+                        let sub_item = module_item_arena.get(sub_item_ref);
+                        self.collect_ranges_containing_position(
+                            sub_item,
+                            module_item_arena,
+                            expr_arena,
+                            content,
+                            position,
+                            ranges,
+                        );
+                    }
+                }
+            }
+            ModuleItem::ModportDeclaration { modports, span } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                    for modport in modports.iter().filter(|modport| contains(modport.span)) {
+                        ranges.push(modport.span);
+                        if contains(modport.name_span) {
+                            ranges.push(modport.name_span);
+                        }
+                        for port in &modport.ports {
+                            if contains(port.name_span) {
+                                ranges.push(port.name_span);
+                            }
+                        }
+                    }
+                }
+            }
+            ModuleItem::DefaultClocking {
+                span, name_span, ..
+            }
+            | ModuleItem::ClockingBlock {
+                span, name_span, ..
+            }
+            | ModuleItem::ConfigDeclaration {
+                span, name_span, ..
+            }
+            | ModuleItem::AssertionDeclaration {
+                span, name_span, ..
+            } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                }
+                if contains(*name_span) {
+                    ranges.push(*name_span);
+                }
+            }
+            ModuleItem::DefaultDisableIff { span, .. }
+            | ModuleItem::ElaborationTask { span, .. }
+            | ModuleItem::TimescaleDirective { span, .. }
+            | ModuleItem::DefaultNettypeDirective { span, .. }
+            | ModuleItem::TimeunitsDeclaration { span, .. } => {
+                if contains(*span) {
+                    ranges.push(*span);
+                }
+            }
         }
     }
 
@@ -3141,6 +6552,18 @@ impl Backend {
                 "end interface declaration",
                 CompletionItemKind::KEYWORD,
             ),
+            (
+                "modport",
+                "modport declaration",
+                CompletionItemKind::KEYWORD,
+            ),
+            ("generate", "generate region", CompletionItemKind::KEYWORD),
+            (
+                "endgenerate",
+                "end generate region",
+                CompletionItemKind::KEYWORD,
+            ),
+            ("genvar", "genvar declaration", CompletionItemKind::KEYWORD),
             ("virtual", "virtual keyword", CompletionItemKind::KEYWORD),
             ("extends", "class inheritance", CompletionItemKind::KEYWORD),
             (
@@ -3362,14 +6785,47 @@ impl Backend {
             ),
             (
                 "readmemh",
-                "task $readmemh(filename, memory_array)",
+                "task $readmemh(filename, memory_array[, start[, finish]])",
                 "Reads memory from hex file",
             ),
             (
                 "readmemb",
-                "task $readmemb(filename, memory_array)",
+                "task $readmemb(filename, memory_array[, start[, finish]])",
                 "Reads memory from binary file",
             ),
+            (
+                "writememh",
+                "task $writememh(filename, memory_array[, start[, finish]])",
+                "Writes memory to hex file",
+            ),
+            // File I/O
+            (
+                "fopen",
+                "int $fopen(filename[, type])",
+                "Opens a file and returns its descriptor",
+            ),
+            ("fclose", "task $fclose(fd)", "Closes a file"),
+            (
+                "fdisplay",
+                "task $fdisplay(fd[, list_of_arguments])",
+                "Writes the argument list and a newline to a file",
+            ),
+            (
+                "fwrite",
+                "task $fwrite(fd[, list_of_arguments])",
+                "Writes the argument list to a file without a newline",
+            ),
+            (
+                "fscanf",
+                "int $fscanf(fd, format, args)",
+                "Reads formatted values from a file",
+            ),
+            ("fgets", "int $fgets(str, fd)", "Reads a line from a file"),
+            (
+                "feof",
+                "int $feof(fd)",
+                "Returns nonzero once the end of a file is reached",
+            ),
             (
                 "dumpfile",
                 "task $dumpfile(filename)",
@@ -3518,14 +6974,25 @@ impl Backend {
             if let Some(first_symbol) = symbols.first() {
                 let (kind, detail_prefix) = match first_symbol.symbol_type {
                     SymbolType::Module => (CompletionItemKind::MODULE, "module"),
-                    SymbolType::Class => (CompletionItemKind::CLASS, "class"),
+                    SymbolType::Interface => (CompletionItemKind::INTERFACE, "interface"),
+                    SymbolType::Package => (CompletionItemKind::MODULE, "package"),
+                    SymbolType::Class { .. } => (CompletionItemKind::CLASS, "class"),
                     SymbolType::Function => (CompletionItemKind::FUNCTION, "function"),
                     SymbolType::Task => (CompletionItemKind::FUNCTION, "task"),
                     SymbolType::Variable => (CompletionItemKind::VARIABLE, "variable"),
                     SymbolType::Port => (CompletionItemKind::PROPERTY, "port"),
                     SymbolType::Parameter => (CompletionItemKind::CONSTANT, "parameter"),
+                    SymbolType::Typedef => (CompletionItemKind::TYPE_PARAMETER, "typedef"),
+                    SymbolType::EnumMember => (CompletionItemKind::ENUM_MEMBER, "enum member"),
+                    SymbolType::Event => (CompletionItemKind::EVENT, "event"),
+                    SymbolType::Clocking => (CompletionItemKind::EVENT, "clocking"),
+                    SymbolType::Sequence => (CompletionItemKind::FUNCTION, "sequence"),
+                    SymbolType::Property => (CompletionItemKind::FUNCTION, "property"),
                     SymbolType::Define => (CompletionItemKind::CONSTANT, "define"),
-                    SymbolType::Include => continue, // Skip include symbols
+                    // Skip include symbols, and extern methods, which are
+                    // completed by their own names
+                    SymbolType::Include | SymbolType::ExternMethod => continue,
+                    SymbolType::Instantiation { .. } => (CompletionItemKind::MODULE, "module"),
                 };
 
                 items.push(CompletionItem {
@@ -3765,6 +7232,18 @@ fn get_keyword_completions_impl() -> Vec<CompletionItem> {
             "end interface declaration",
             CompletionItemKind::KEYWORD,
         ),
+        (
+            "modport",
+            "modport declaration",
+            CompletionItemKind::KEYWORD,
+        ),
+        ("generate", "generate region", CompletionItemKind::KEYWORD),
+        (
+            "endgenerate",
+            "end generate region",
+            CompletionItemKind::KEYWORD,
+        ),
+        ("genvar", "genvar declaration", CompletionItemKind::KEYWORD),
         ("virtual", "virtual keyword", CompletionItemKind::KEYWORD),
         ("extends", "class inheritance", CompletionItemKind::KEYWORD),
         (
@@ -3987,6 +7466,19 @@ fn get_member_completions_impl() -> Vec<CompletionItem> {
         .collect()
 }
 
+pub fn create_backend(client: Client) -> Backend {
+    Backend {
+        client,
+        documents: Arc::new(RwLock::new(HashMap::new())),
+        workspace_symbols: Arc::new(RwLock::new(HashMap::new())),
+        config: Arc::new(RwLock::new(ServerConfig::default())),
+        workspace_root: Arc::new(RwLock::new(None)),
+        indexed_files: Arc::new(RwLock::new(HashMap::new())),
+        indexing: Arc::new(IndexingState::default()),
+        register_type_hierarchy: Arc::new(AtomicBool::new(false)),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -4123,13 +7615,13 @@ mod tests {
                 // Clamp to end of text
                 let prefix = text;
                 let line = prefix.matches('\n').count();
-                let column = prefix.split('\n').last().unwrap_or("").len();
+                let column = prefix.split('\n').next_back().unwrap_or("").len();
                 return Some(Position::new(line as u32, column as u32));
             }
 
             let prefix = &text[..offset];
             let line = prefix.matches('\n').count();
-            let column = prefix.split('\n').last().unwrap_or("").len();
+            let column = prefix.split('\n').next_back().unwrap_or("").len();
 
             Some(Position::new(line as u32, column as u32))
         }
@@ -4515,13 +8007,3 @@ mod tests {
         assert_eq!(range.end.line, 1);
     }
 }
-
-pub fn create_backend(client: Client) -> Backend {
-    Backend {
-        client,
-        documents: Arc::new(RwLock::new(HashMap::new())),
-        workspace_symbols: Arc::new(RwLock::new(HashMap::new())),
-        config: Arc::new(RwLock::new(ServerConfig::default())),
-        workspace_root: Arc::new(RwLock::new(None)),
-    }
-}
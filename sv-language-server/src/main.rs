@@ -2,13 +2,20 @@ use anyhow::Result;
 use tokio::io::{stdin, stdout};
 use tower_lsp::{LspService, Server};
 
-#[tokio::main]
-async fn main() -> Result<()> {
+fn main() -> Result<()> {
+    // Documents are parsed on the runtime's threads, which need the parser's stack
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .thread_stack_size(sv_parser::parallel::STACK_SIZE)
+        .build()?;
+    runtime.block_on(serve());
+    Ok(())
+}
+
+async fn serve() {
     let stdin = stdin();
     let stdout = stdout();
 
     let (service, socket) = LspService::new(sv_language_server::create_backend);
     Server::new(stdin, stdout, socket).serve(service).await;
-
-    Ok(())
 }
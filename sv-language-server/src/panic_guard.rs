@@ -0,0 +1,140 @@
+//! Panic recovery
+//!
+//! A bug in the parser or in the analysis of one document must not take the
+//! whole server down. [`catch_panic`] runs a future and turns a panic inside
+//! it into a [`PanicReport`] holding the panic message and a truncated
+//! backtrace, which the server logs, sends as telemetry and reports back to
+//! the client: as a diagnostic when analysis panics, and as an internal error
+//! response when a request handler does.
+
+use std::any::Any;
+use std::backtrace::Backtrace;
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::{Mutex, Once};
+use std::task::{Context, Poll};
+
+/// Number of backtrace lines kept in a report
+pub const MAX_BACKTRACE_LINES: usize = 40;
+
+/// What is known about a caught panic
+#[derive(Debug, Clone, PartialEq)]
+pub struct PanicReport {
+    /// The panic payload, if it was a string
+    pub message: String,
+    /// Where the panic happened (`file:line:column`), if known
+    pub location: Option<String>,
+    /// Backtrace captured when the panic happened, cut to [`MAX_BACKTRACE_LINES`] lines
+    pub backtrace: String,
+}
+
+/// Number of recorded panics kept until a report claims them
+const MAX_RECORDED_PANICS: usize = 16;
+
+/// A panic seen by the hook: its message, location and backtrace
+type RecordedPanic = (String, Option<String>, String);
+
+/// Panics recorded by the hook, oldest first
+///
+/// The parser runs on worker threads that re-raise their panics on the thread
+/// polling the future, so the record can't live in a thread local: it is
+/// shared by the whole process and claimed by message.
+static RECORDED_PANICS: Mutex<Vec<RecordedPanic>> = Mutex::new(Vec::new());
+
+/// Install a panic hook that records the location and backtrace of each panic,
+/// so they can be added to the report once the panic has been caught
+fn install_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            let message = payload_message(info.payload());
+            let location = info.location().map(|location| location.to_string());
+            let backtrace = Backtrace::force_capture().to_string();
+            let mut recorded = RECORDED_PANICS
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            if recorded.len() == MAX_RECORDED_PANICS {
+                recorded.remove(0);
+            }
+            recorded.push((message, location, backtrace));
+            drop(recorded);
+            previous(info);
+        }));
+    });
+}
+
+/// Run `future`, catching any panic raised while polling it
+///
+/// Panics are caught on the thread that polls the future, so the report's
+/// backtrace is that of the panicking task.
+pub async fn catch_panic<F: Future>(future: F) -> Result<F::Output, PanicReport> {
+    install_hook();
+    CatchUnwind {
+        inner: Box::pin(future),
+    }
+    .await
+}
+
+struct CatchUnwind<F> {
+    inner: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for CatchUnwind<F> {
+    type Output = Result<F::Output, PanicReport>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = self.inner.as_mut();
+        match panic::catch_unwind(AssertUnwindSafe(|| inner.poll(cx))) {
+            Ok(Poll::Ready(output)) => Poll::Ready(Ok(output)),
+            Ok(Poll::Pending) => Poll::Pending,
+            Err(payload) => Poll::Ready(Err(report(payload))),
+        }
+    }
+}
+
+fn payload_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic payload".to_string())
+}
+
+fn report(payload: Box<dyn Any + Send>) -> PanicReport {
+    let message = payload_message(payload.as_ref());
+    // The latest panic with this message is the one being caught, even when
+    // it was raised on a worker thread and resumed here
+    let (location, backtrace) = {
+        let mut recorded = RECORDED_PANICS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        recorded
+            .iter()
+            .rposition(|(recorded, _, _)| *recorded == message)
+            .map(|index| {
+                let (_, location, backtrace) = recorded.remove(index);
+                (location, backtrace)
+            })
+            .unwrap_or_default()
+    };
+
+    PanicReport {
+        message,
+        location,
+        backtrace: truncate_lines(&backtrace, MAX_BACKTRACE_LINES),
+    }
+}
+
+/// Keep the first `max_lines` lines of `text`, noting how many were dropped
+pub fn truncate_lines(text: &str, max_lines: usize) -> String {
+    let total = text.lines().count();
+    let mut kept: Vec<&str> = text.lines().take(max_lines).collect();
+    let note;
+    if total > max_lines {
+        note = format!("... {} more lines", total - max_lines);
+        kept.push(&note);
+    }
+    kept.join("\n")
+}
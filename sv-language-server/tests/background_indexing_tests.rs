@@ -0,0 +1,123 @@
+pub mod common;
+
+use std::fs;
+use std::path::Path;
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+/// Create a backend with `root` as the workspace root, without starting the
+/// indexing run that `initialized` would spawn
+async fn backend_for(root: &Path) -> std::sync::Arc<sv_language_server::Backend> {
+    let backend = common::create_test_backend();
+    backend
+        .initialize(InitializeParams {
+            root_uri: Some(Url::from_directory_path(root).unwrap()),
+            ..InitializeParams::default()
+        })
+        .await
+        .unwrap();
+    backend
+}
+
+#[tokio::test]
+async fn test_indexes_files_that_were_never_opened() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("rtl")).unwrap();
+    fs::write(dir.path().join("rtl/alu.sv"), "module alu;\nendmodule\n").unwrap();
+    fs::write(dir.path().join("top.sv"), "module top;\nendmodule\n").unwrap();
+    fs::write(dir.path().join("notes.txt"), "module not_sv;\nendmodule\n").unwrap();
+
+    let backend = backend_for(dir.path()).await;
+    backend.index_workspace().await;
+
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(workspace_symbols.contains_key("alu"));
+    assert!(workspace_symbols.contains_key("top"));
+    assert!(!workspace_symbols.contains_key("not_sv"));
+    assert_eq!(backend.indexed_files.read().await.len(), 2);
+}
+
+#[tokio::test]
+async fn test_skips_hidden_directories() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join(".git")).unwrap();
+    fs::write(
+        dir.path().join(".git/hidden.sv"),
+        "module hidden;\nendmodule\n",
+    )
+    .unwrap();
+
+    let backend = backend_for(dir.path()).await;
+    backend.index_workspace().await;
+
+    assert!(!backend
+        .workspace_symbols
+        .read()
+        .await
+        .contains_key("hidden"));
+}
+
+#[tokio::test]
+async fn test_open_document_wins_over_disk() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("edited.sv");
+    fs::write(&file, "module on_disk;\nendmodule\n").unwrap();
+    let uri = Url::from_file_path(&file).unwrap();
+
+    let backend = backend_for(dir.path()).await;
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: "module in_editor;\nendmodule\n".to_string(),
+            },
+        })
+        .await;
+    backend.index_workspace().await;
+
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert_eq!(workspace_symbols["in_editor"].len(), 1);
+    assert!(!workspace_symbols.contains_key("on_disk"));
+}
+
+#[tokio::test]
+async fn test_reindexing_replaces_changed_file_symbols() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("renamed.sv");
+    fs::write(&file, "module before;\nendmodule\n").unwrap();
+
+    let backend = backend_for(dir.path()).await;
+    backend.index_workspace().await;
+    fs::write(&file, "module after;\nendmodule\n").unwrap();
+    backend.index_workspace().await;
+
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(!workspace_symbols.contains_key("before"));
+    assert_eq!(workspace_symbols["after"].len(), 1);
+}
+
+#[tokio::test]
+async fn test_configured_source_directories_limit_indexing() {
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("rtl")).unwrap();
+    fs::create_dir(dir.path().join("sim")).unwrap();
+    fs::write(dir.path().join("rtl/core.sv"), "module core;\nendmodule\n").unwrap();
+    fs::write(dir.path().join("sim/tb.sv"), "module tb;\nendmodule\n").unwrap();
+
+    let backend = common::create_test_backend();
+    backend
+        .initialize(InitializeParams {
+            root_uri: Some(Url::from_directory_path(dir.path()).unwrap()),
+            initialization_options: Some(serde_json::json!({ "source_directories": ["rtl"] })),
+            ..InitializeParams::default()
+        })
+        .await
+        .unwrap();
+    backend.index_workspace().await;
+
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(workspace_symbols.contains_key("core"));
+    assert!(!workspace_symbols.contains_key("tb"));
+}
@@ -0,0 +1,66 @@
+pub mod common;
+
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+fn code_action_params(uri: &Url, position: Position) -> CodeActionParams {
+    CodeActionParams {
+        text_document: TextDocumentIdentifier { uri: uri.clone() },
+        range: Range::new(position, position),
+        context: CodeActionContext::default(),
+        work_done_progress_params: WorkDoneProgressParams::default(),
+        partial_result_params: PartialResultParams::default(),
+    }
+}
+
+#[tokio::test]
+async fn test_split_always_block_code_action() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/split.sv");
+    let content = "module top(input logic a);
+    logic x, y;
+    always_comb begin
+        x = a;
+        y = a;
+    end
+endmodule
+";
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let actions = backend
+        .code_action(code_action_params(&uri, common::test_position(3, 8)))
+        .await
+        .unwrap()
+        .expect("Expected a code action inside the always block");
+    assert_eq!(actions.len(), 1);
+    let CodeActionOrCommand::CodeAction(action) = &actions[0] else {
+        panic!("Expected a code action");
+    };
+    assert_eq!(action.kind, Some(CodeActionKind::REFACTOR_REWRITE));
+    let edits = &action.edit.as_ref().unwrap().changes.as_ref().unwrap()[&uri];
+    assert_eq!(edits.len(), 1);
+    assert_eq!(
+        edits[0].range,
+        Range::new(common::test_position(2, 4), common::test_position(5, 7))
+    );
+    assert_eq!(
+        edits[0].new_text,
+        "always_comb begin\n        x = a;\n    end\n\n    always_comb begin\n        y = a;\n    end"
+    );
+
+    // Outside any always block there is nothing to offer
+    let actions = backend
+        .code_action(code_action_params(&uri, common::test_position(1, 4)))
+        .await
+        .unwrap();
+    assert!(actions.is_none());
+}
@@ -1,13 +1,13 @@
 use std::sync::Arc;
 use sv_language_server::Backend;
 use tower_lsp::lsp_types::*;
-use tower_lsp::LspService;
+use tower_lsp::{LanguageServer, LspService};
 
 /// Create a test backend for direct testing
 /// Returns an Arc-wrapped backend so it can be shared across tests
 pub fn create_test_backend() -> Arc<Backend> {
     // Create a service to get a valid Client
-    let (service, _socket) = LspService::new(|client| sv_language_server::create_backend(client));
+    let (service, _socket) = LspService::new(sv_language_server::create_backend);
 
     // Get a reference to the inner backend and wrap it in Arc
     // We need to leak it to get a 'static reference, then wrap in Arc
@@ -25,6 +25,9 @@ pub fn create_test_backend() -> Arc<Backend> {
             workspace_symbols: Arc::clone(&original.workspace_symbols),
             config: Arc::clone(&original.config),
             workspace_root: Arc::clone(&original.workspace_root),
+            indexed_files: Arc::clone(&original.indexed_files),
+            indexing: Arc::clone(&original.indexing),
+            register_type_hierarchy: Arc::clone(&original.register_type_hierarchy),
         })
     }
 }
@@ -41,3 +44,17 @@ pub fn test_position(row: u32, col: u32) -> Position {
         character: col,
     }
 }
+
+/// Open a document in the editor, at version 1
+pub async fn open(backend: &Backend, uri: &Url, text: &str) {
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: text.to_string(),
+            },
+        })
+        .await;
+}
@@ -111,3 +111,50 @@ endmodule"#;
         panic!("Should return keyword completions");
     }
 }
+
+#[tokio::test]
+async fn test_completion_typedefs_and_enum_members() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/enums.sv");
+    let content = r#"module test;
+    typedef enum logic [1:0] {IDLE, BUSY = 2} state_t;
+    state_t state;
+
+endmodule"#;
+
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    let result = backend
+        .completion(CompletionParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position: common::test_position(3, 4),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+            context: None,
+        })
+        .await;
+
+    let Ok(Some(CompletionResponse::Array(items))) = result else {
+        panic!("Should return completions");
+    };
+    let kind_of = |label: &str| {
+        items
+            .iter()
+            .find(|item| item.label == label)
+            .and_then(|item| item.kind)
+    };
+    assert_eq!(kind_of("IDLE"), Some(CompletionItemKind::ENUM_MEMBER));
+    assert_eq!(kind_of("BUSY"), Some(CompletionItemKind::ENUM_MEMBER));
+    assert_eq!(kind_of("state_t"), Some(CompletionItemKind::TYPE_PARAMETER));
+}
@@ -0,0 +1,167 @@
+pub mod common;
+
+use std::fs;
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+#[tokio::test]
+async fn test_definition_in_unopened_include_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let include_dir = dir.path().join("inc");
+    let project_dir = dir.path().join("proj");
+    fs::create_dir(&include_dir).unwrap();
+    fs::create_dir(&project_dir).unwrap();
+    let header = include_dir.join("defs.svh");
+    fs::write(&header, "module helper;\nendmodule\n").unwrap();
+
+    let backend = common::create_test_backend();
+    backend
+        .initialize(InitializeParams {
+            root_uri: Some(Url::from_directory_path(&project_dir).unwrap()),
+            initialization_options: Some(serde_json::json!({
+                "include_directories": [include_dir.to_str().unwrap()],
+            })),
+            ..InitializeParams::default()
+        })
+        .await
+        .unwrap();
+
+    let uri = Url::from_file_path(project_dir.join("top.sv")).unwrap();
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: "`include \"defs.svh\"\nmodule top;\n  helper u_helper ();\nendmodule\n"
+                    .to_string(),
+            },
+        })
+        .await;
+
+    let response = backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: Position::new(2, 4),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+
+    let Some(GotoDefinitionResponse::Scalar(location)) = response else {
+        panic!("expected a single definition, got {:?}", response);
+    };
+    assert_eq!(location.uri, Url::from_file_path(&header).unwrap());
+    assert_eq!(location.range.start.line, 0);
+}
+
+#[tokio::test]
+async fn test_definition_links_extern_method_and_its_body() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/packet.sv");
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: "class packet;\n  extern function void send(int n);\nendclass\n\n\
+                       function void packet::send(int n);\n  $display(n);\nendfunction\n"
+                    .to_string(),
+            },
+        })
+        .await;
+
+    let definition = |position: Position| {
+        let backend = backend.clone();
+        let uri = uri.clone();
+        async move {
+            backend
+                .goto_definition(GotoDefinitionParams {
+                    text_document_position_params: TextDocumentPositionParams {
+                        text_document: TextDocumentIdentifier { uri },
+                        position,
+                    },
+                    work_done_progress_params: WorkDoneProgressParams::default(),
+                    partial_result_params: PartialResultParams::default(),
+                })
+                .await
+                .unwrap()
+        }
+    };
+
+    // From the prototype to the body
+    let Some(GotoDefinitionResponse::Scalar(location)) = definition(Position::new(1, 24)).await
+    else {
+        panic!("expected the body of the method");
+    };
+    assert_eq!(
+        location.range,
+        Range::new(Position::new(4, 22), Position::new(4, 26))
+    );
+
+    // And back
+    let Some(GotoDefinitionResponse::Scalar(location)) = definition(Position::new(4, 23)).await
+    else {
+        panic!("expected the prototype of the method");
+    };
+    assert_eq!(
+        location.range,
+        Range::new(Position::new(1, 23), Position::new(1, 27))
+    );
+}
+
+#[tokio::test]
+async fn test_definition_of_imported_name_follows_the_imports() {
+    let backend = common::create_test_backend();
+    let packages = common::test_uri("/test/packages.sv");
+    let top = common::test_uri("/test/top.sv");
+    for (uri, text) in [
+        (
+            &packages,
+            "package bus_pkg;\n  parameter WIDTH = 8;\nendpackage\n\n\
+             package cpu_pkg;\n  parameter WIDTH = 16;\nendpackage\n",
+        ),
+        (
+            &top,
+            "module top;\n  import bus_pkg::*;\n  import cpu_pkg::WIDTH;\n\
+             \x20 logic [7:0] a;\n  assign a = WIDTH;\nendmodule\n",
+        ),
+    ] {
+        backend
+            .did_open(DidOpenTextDocumentParams {
+                text_document: TextDocumentItem {
+                    uri: uri.clone(),
+                    language_id: "systemverilog".to_string(),
+                    version: 1,
+                    text: text.to_string(),
+                },
+            })
+            .await;
+    }
+
+    let response = backend
+        .goto_definition(GotoDefinitionParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: top },
+                position: Position::new(4, 14),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+
+    // The explicit import wins over the wildcard one
+    let Some(GotoDefinitionResponse::Scalar(location)) = response else {
+        panic!("expected a single definition, got {:?}", response);
+    };
+    assert_eq!(location.uri, packages);
+    assert_eq!(
+        location.range,
+        Range::new(Position::new(5, 12), Position::new(5, 17))
+    );
+}
@@ -0,0 +1,178 @@
+pub mod common;
+
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+async fn close(backend: &sv_language_server::Backend, uri: &Url) {
+    backend
+        .did_close(DidCloseTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri: uri.clone() },
+        })
+        .await;
+}
+
+async fn has_ast(backend: &sv_language_server::Backend, uri: &Url) -> bool {
+    backend.documents.read().await[uri].ast.is_some()
+}
+
+async fn is_cached(backend: &sv_language_server::Backend, uri: &Url) -> bool {
+    backend.documents.read().await.contains_key(uri)
+}
+
+#[tokio::test]
+async fn test_closed_document_keeps_symbols() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/closed.sv");
+
+    common::open(&backend, &uri, "module closed_module;\nendmodule").await;
+    close(&backend, &uri).await;
+
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(workspace_symbols.contains_key("closed_module"));
+}
+
+#[tokio::test]
+async fn test_least_recently_used_closed_document_is_evicted() {
+    let backend = common::create_test_backend();
+    backend
+        .did_change_configuration(DidChangeConfigurationParams {
+            settings: serde_json::json!({ "max_cached_documents": 1 }),
+        })
+        .await;
+
+    let first = common::test_uri("/test/first.sv");
+    let second = common::test_uri("/test/second.sv");
+    common::open(&backend, &first, "module first;\nendmodule").await;
+    common::open(&backend, &second, "module second;\nendmodule").await;
+
+    close(&backend, &first).await;
+    close(&backend, &second).await;
+    assert!(!is_cached(&backend, &first).await);
+    assert!(has_ast(&backend, &second).await);
+
+    // The evicted document's symbols stay in the workspace index
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(workspace_symbols.contains_key("first"));
+}
+
+#[tokio::test]
+async fn test_evicted_document_is_parsed_again_from_disk() {
+    let backend = common::create_test_backend();
+    backend
+        .did_change_configuration(DidChangeConfigurationParams {
+            settings: serde_json::json!({ "max_cached_documents": 1 }),
+        })
+        .await;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("evicted.sv");
+    let text = "module evicted;\nendmodule";
+    std::fs::write(&path, text).unwrap();
+    let evicted = Url::from_file_path(&path).unwrap();
+    let other = common::test_uri("/test/other.sv");
+    common::open(&backend, &evicted, text).await;
+    common::open(&backend, &other, "module other;\nendmodule").await;
+    close(&backend, &evicted).await;
+    close(&backend, &other).await;
+    assert!(!is_cached(&backend, &evicted).await);
+
+    let symbols = backend
+        .document_symbol(DocumentSymbolParams {
+            text_document: TextDocumentIdentifier {
+                uri: evicted.clone(),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    let Some(DocumentSymbolResponse::Nested(symbols)) = symbols else {
+        panic!("Expected the symbols of the evicted document");
+    };
+    assert_eq!(symbols[0].name, "evicted");
+    assert!(has_ast(&backend, &evicted).await);
+}
+
+#[tokio::test]
+async fn test_open_documents_are_never_evicted() {
+    let backend = common::create_test_backend();
+    backend
+        .did_change_configuration(DidChangeConfigurationParams {
+            settings: serde_json::json!({ "max_cached_documents": 0 }),
+        })
+        .await;
+
+    let uri = common::test_uri("/test/open.sv");
+    common::open(&backend, &uri, "module still_open;\nendmodule").await;
+    let other = common::test_uri("/test/other.sv");
+    common::open(&backend, &other, "module other;\nendmodule").await;
+    close(&backend, &other).await;
+
+    assert!(has_ast(&backend, &uri).await);
+    assert!(!is_cached(&backend, &other).await);
+}
+
+#[tokio::test]
+async fn test_document_over_parse_time_budget_has_no_ast() {
+    let backend = common::create_test_backend();
+    backend
+        .did_change_configuration(DidChangeConfigurationParams {
+            settings: serde_json::json!({ "max_parse_time_ms": 1 }),
+        })
+        .await;
+
+    let assignments: String = (0..5000)
+        .map(|i| format!("    assign y{} = a{} + b{};\n", i, i, i))
+        .collect();
+    let uri = common::test_uri("/test/generated.sv");
+    common::open(
+        &backend,
+        &uri,
+        &format!("module generated;\n{}endmodule", assignments),
+    )
+    .await;
+    assert!(!has_ast(&backend, &uri).await);
+
+    let small = common::test_uri("/test/small.sv");
+    common::open(&backend, &small, "module small;\nendmodule").await;
+    assert!(has_ast(&backend, &small).await);
+}
+
+#[tokio::test]
+async fn test_superseded_edit_is_dropped() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/edited.sv");
+    common::open(&backend, &uri, "module original;\nendmodule").await;
+
+    let change = |version: i32, text: &str| {
+        backend.did_change(DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier {
+                uri: uri.clone(),
+                version,
+            },
+            content_changes: vec![TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: text.to_string(),
+            }],
+        })
+    };
+    tokio::join!(
+        change(2, "module stale;\nendmodule"),
+        change(3, "module latest;\nendmodule")
+    );
+
+    let docs = backend.documents.read().await;
+    let doc_state = &docs[&uri];
+    assert_eq!(doc_state.version, 3);
+    let names: Vec<&str> = doc_state
+        .symbols
+        .iter()
+        .map(|symbol| symbol.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["latest"]);
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(workspace_symbols.contains_key("latest"));
+    assert!(!workspace_symbols.contains_key("stale"));
+    assert!(!workspace_symbols.contains_key("original"));
+}
@@ -176,3 +176,333 @@ endclass"#;
         assert!(has_class, "Should have MyClass as a CLASS symbol");
     }
 }
+
+async fn outline(content: &str) -> Vec<DocumentSymbol> {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/outline.sv");
+
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    match backend
+        .document_symbol(DocumentSymbolParams {
+            text_document: TextDocumentIdentifier { uri },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap()
+    {
+        Some(DocumentSymbolResponse::Nested(symbols)) => symbols,
+        other => panic!("Expected nested document symbols, got {:?}", other),
+    }
+}
+
+fn child<'a>(symbol: &'a DocumentSymbol, name: &str) -> &'a DocumentSymbol {
+    symbol
+        .children
+        .as_ref()
+        .and_then(|children| children.iter().find(|c| c.name == name))
+        .unwrap_or_else(|| panic!("{} should have a child named {}", symbol.name, name))
+}
+
+#[tokio::test]
+async fn test_document_symbol_module_children_and_details() {
+    let content = r#"module top(input logic [7:0] data, output valid);
+    parameter int WIDTH = 8;
+    logic [3:0] count;
+    sub u_sub (.a(count));
+endmodule"#;
+
+    let symbols = outline(content).await;
+    assert_eq!(
+        symbols.len(),
+        1,
+        "Only the module should be at the top level"
+    );
+    let module = &symbols[0];
+    assert_eq!(module.name, "top");
+    assert_eq!(module.detail.as_deref(), Some("(data, valid)"));
+
+    let data = child(module, "data");
+    assert_eq!(data.kind, SymbolKind::PROPERTY);
+    assert_eq!(data.detail.as_deref(), Some("input [7:0]"));
+    assert_eq!(child(module, "valid").detail.as_deref(), Some("output"));
+
+    let width = child(module, "WIDTH");
+    assert_eq!(width.kind, SymbolKind::CONSTANT);
+    assert_eq!(width.detail.as_deref(), Some("parameter int = 8"));
+
+    assert_eq!(
+        child(module, "count").detail.as_deref(),
+        Some("logic [3:0]")
+    );
+    assert_eq!(child(module, "u_sub").detail.as_deref(), Some("sub"));
+}
+
+#[tokio::test]
+async fn test_document_symbol_non_ansi_ports_listed_once() {
+    let content = r#"module legacy(clk, data);
+    input wire clk;
+    output reg data;
+endmodule"#;
+
+    let symbols = outline(content).await;
+    let children = symbols[0].children.as_ref().unwrap();
+    let clk: Vec<_> = children.iter().filter(|c| c.name == "clk").collect();
+    assert_eq!(clk.len(), 1);
+    assert_eq!(clk[0].detail.as_deref(), Some("input wire"));
+}
+
+#[tokio::test]
+async fn test_document_symbol_nested_class_members() {
+    let content = r#"module top;
+    class Packet extends Base;
+        int len;
+        static int count;
+        function new(string name);
+        endfunction
+        function int size(a, b);
+        endfunction
+        static function int total();
+        endfunction
+    endclass
+endmodule"#;
+
+    let symbols = outline(content).await;
+    let class = child(&symbols[0], "Packet");
+    assert_eq!(class.kind, SymbolKind::CLASS);
+    assert_eq!(class.detail.as_deref(), Some("extends Base"));
+
+    let len = child(class, "len");
+    assert_eq!(len.kind, SymbolKind::FIELD);
+    assert_eq!(len.detail.as_deref(), Some("int"));
+
+    let size = child(class, "size");
+    assert_eq!(size.kind, SymbolKind::METHOD);
+    assert_eq!(size.detail.as_deref(), Some("function int(a, b)"));
+
+    let count = child(class, "count");
+    assert_eq!(count.detail.as_deref(), Some("static int"));
+
+    let new = child(class, "new");
+    assert_eq!(new.kind, SymbolKind::CONSTRUCTOR);
+    assert_eq!(new.detail.as_deref(), Some("function void(name)"));
+
+    let total = child(class, "total");
+    assert_eq!(total.kind, SymbolKind::METHOD);
+    assert_eq!(total.detail.as_deref(), Some("static function int()"));
+}
+
+#[tokio::test]
+async fn test_document_symbol_interface_classes() {
+    let content = r#"interface class printable extends has_id, has_name;
+endclass
+
+class Packet extends Base implements printable;
+    virtual bus_if.monitor vif;
+endclass"#;
+
+    let symbols = outline(content).await;
+    let printable = symbols.iter().find(|s| s.name == "printable").unwrap();
+    assert_eq!(printable.kind, SymbolKind::INTERFACE);
+    assert_eq!(
+        printable.detail.as_deref(),
+        Some("extends has_id, has_name")
+    );
+
+    let packet = symbols.iter().find(|s| s.name == "Packet").unwrap();
+    assert_eq!(
+        packet.detail.as_deref(),
+        Some("extends Base implements printable")
+    );
+    let vif = child(packet, "vif");
+    assert_eq!(vif.detail.as_deref(), Some("virtual bus_if.monitor"));
+}
+
+#[tokio::test]
+async fn test_document_symbol_interfaces_and_modports() {
+    let content = r#"interface bus_if(input logic clk);
+    logic req;
+    modport master(output req, input clk), slave(input req);
+endinterface
+
+module producer(bus_if.master bus);
+endmodule"#;
+
+    let symbols = outline(content).await;
+    let bus_if = symbols.iter().find(|s| s.name == "bus_if").unwrap();
+    assert_eq!(bus_if.kind, SymbolKind::INTERFACE);
+    assert_eq!(bus_if.detail.as_deref(), Some("(clk)"));
+
+    let master = child(bus_if, "master");
+    assert_eq!(master.kind, SymbolKind::INTERFACE);
+    assert_eq!(master.detail.as_deref(), Some("modport"));
+    assert_eq!(child(master, "clk").detail.as_deref(), Some("input"));
+    assert_eq!(child(bus_if, "slave").children.as_ref().unwrap().len(), 1);
+
+    let producer = symbols.iter().find(|s| s.name == "producer").unwrap();
+    assert_eq!(
+        child(producer, "bus").detail.as_deref(),
+        Some("bus_if.master")
+    );
+}
+
+#[tokio::test]
+async fn test_document_symbol_packages_hide_imports() {
+    let content = r#"package cfg_pkg;
+    parameter WIDTH = 8;
+endpackage
+
+import cfg_pkg::*;
+
+module top import cfg_pkg::WIDTH; ;
+    logic ready;
+endmodule"#;
+
+    let symbols = outline(content).await;
+    let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["cfg_pkg", "top"]);
+    assert_eq!(symbols[0].kind, SymbolKind::PACKAGE);
+    assert_eq!(child(&symbols[0], "WIDTH").kind, SymbolKind::CONSTANT);
+    assert_eq!(symbols[1].children.as_ref().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_document_symbol_generate_blocks() {
+    let content = r#"module top;
+    genvar i;
+    generate
+        for (i = 0; i < 4; i++) begin : lane
+            logic bit_q;
+        end
+    endgenerate
+    if (1) begin
+        logic spare;
+    end
+endmodule"#;
+
+    let symbols = outline(content).await;
+    let names: Vec<&str> = symbols[0]
+        .children
+        .as_ref()
+        .unwrap()
+        .iter()
+        .map(|s| s.name.as_str())
+        .collect();
+    // Only named blocks get an entry of their own
+    assert_eq!(names, vec!["i", "lane", "spare"]);
+    assert_eq!(child(&symbols[0], "i").detail.as_deref(), Some("genvar"));
+    let lane = child(&symbols[0], "lane");
+    assert_eq!(lane.kind, SymbolKind::NAMESPACE);
+    assert_eq!(child(lane, "bit_q").kind, SymbolKind::VARIABLE);
+}
+
+#[tokio::test]
+async fn test_document_symbol_selection_range_is_name() {
+    let content = "module top;\n    logic [3:0] count;\nendmodule";
+
+    let symbols = outline(content).await;
+    let count = child(&symbols[0], "count");
+    assert_eq!(count.selection_range.start, Position::new(1, 16));
+    assert_eq!(count.selection_range.end, Position::new(1, 21));
+}
+
+#[tokio::test]
+async fn test_document_symbol_range_covers_item_and_selection_covers_name() {
+    let content = r#"// Packet class
+class Packet;
+    int len;
+    function int size();
+    endfunction
+endclass
+
+global clocking sys_clk @(posedge clk); endclocking"#;
+
+    let symbols = outline(content).await;
+    let class = symbols.iter().find(|s| s.name == "Packet").unwrap();
+    // The leading comment is not part of the class
+    assert_eq!(class.range.start, Position::new(1, 0));
+    assert_eq!(class.range.end, Position::new(5, 8));
+    assert_eq!(class.selection_range.start, Position::new(1, 6));
+    assert_eq!(class.selection_range.end, Position::new(1, 12));
+
+    let len = child(class, "len");
+    assert_eq!(
+        len.range,
+        Range::new(Position::new(2, 4), Position::new(2, 12))
+    );
+    assert_eq!(
+        len.selection_range,
+        Range::new(Position::new(2, 8), Position::new(2, 11))
+    );
+
+    let size = child(class, "size");
+    assert_eq!(size.range.start, Position::new(3, 4));
+    assert_eq!(size.range.end, Position::new(4, 15));
+    assert_eq!(
+        size.selection_range,
+        Range::new(Position::new(3, 17), Position::new(3, 21))
+    );
+
+    let clocking = symbols.iter().find(|s| s.name == "sys_clk").unwrap();
+    assert_eq!(clocking.range.start, Position::new(7, 0));
+    assert_eq!(
+        clocking.selection_range,
+        Range::new(Position::new(7, 16), Position::new(7, 23))
+    );
+}
+
+#[tokio::test]
+async fn test_document_symbol_events_and_clocking_blocks() {
+    let content = r#"module top(input logic clk);
+    event done;
+    default clocking cb @(posedge clk);
+        default input #1 output #2;
+        input req, ack;
+        output #0 grant;
+    endclocking : cb
+    sequence handshake;
+        req ##1 ack;
+    endsequence
+endmodule"#;
+
+    let symbols = outline(content).await;
+    let done = child(&symbols[0], "done");
+    assert_eq!(done.kind, SymbolKind::EVENT);
+    assert_eq!(done.detail.as_deref(), Some("event"));
+
+    let cb = child(&symbols[0], "cb");
+    assert_eq!(cb.kind, SymbolKind::EVENT);
+    assert_eq!(cb.detail.as_deref(), Some("default clocking"));
+    let signals: Vec<_> = cb
+        .children
+        .iter()
+        .flatten()
+        .map(|signal| (signal.name.as_str(), signal.detail.as_deref()))
+        .collect();
+    assert_eq!(
+        signals,
+        vec![
+            ("req", Some("input")),
+            ("ack", Some("input")),
+            ("grant", Some("output #0"))
+        ]
+    );
+    assert_eq!(
+        cb.selection_range,
+        Range::new(Position::new(2, 21), Position::new(2, 23))
+    );
+
+    let handshake = child(&symbols[0], "handshake");
+    assert_eq!(handshake.kind, SymbolKind::FUNCTION);
+    assert_eq!(handshake.detail.as_deref(), Some("sequence"));
+}
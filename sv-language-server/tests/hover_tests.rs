@@ -350,3 +350,169 @@ endmodule"#;
 
     assert!(result.is_ok());
 }
+
+async fn hover_at(content: &str, line: u32, character: u32) -> Option<Hover> {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/hover_number.sv");
+
+    backend
+        .did_open(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "systemverilog".to_string(),
+                version: 1,
+                text: content.to_string(),
+            },
+        })
+        .await;
+
+    backend
+        .hover(HoverParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri },
+                position: common::test_position(line, character),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        })
+        .await
+        .unwrap()
+}
+
+fn markup(hover: Hover) -> String {
+    match hover.contents {
+        HoverContents::Markup(markup) => markup.value,
+        other => panic!("Expected markup content, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+/// Hovering a sized literal shows its conversions, width and signedness
+async fn test_hover_number_literal_conversions() {
+    let content = "module test;\n    logic [7:0] data = 8'hA5;\nendmodule";
+
+    let hover = hover_at(content, 1, 25).await.expect("Expected hover");
+    let text = markup(hover);
+    assert!(text.contains("8 bits, unsigned"));
+    assert!(text.contains("Decimal: `165`"));
+    assert!(text.contains("Hex: `8'hA5`"));
+    assert!(text.contains("Binary: `8'b10100101`"));
+}
+
+#[tokio::test]
+/// Hovering a parameter name shows the value computed from earlier parameters
+async fn test_hover_parameter_constant_value() {
+    let content =
+        "module test;\n    parameter WIDTH = 8;\n    localparam MSB = (WIDTH * 2) - 1;\nendmodule";
+
+    let hover = hover_at(content, 2, 16).await.expect("Expected hover");
+    let text = markup(hover);
+    assert!(text.contains("localparam MSB"));
+    assert!(text.contains("32 bits (unsized), signed"));
+    assert!(text.contains("Decimal: `15`"));
+}
+
+#[tokio::test]
+/// Literals with x/z bits have no numeric value to show
+async fn test_hover_number_with_unknown_bits() {
+    let content = "module test;\n    logic [3:0] data = 4'b1x0z;\nendmodule";
+
+    let hover = hover_at(content, 1, 25).await.expect("Expected hover");
+    assert!(markup(hover).contains("x/z"));
+}
+
+#[tokio::test]
+/// A parameter that is not a constant shows its value in simplified form
+async fn test_hover_parameter_simplified_value() {
+    let content = "module test;\n    parameter DEPTH = (N + 2) + 3;\nendmodule";
+
+    let hover = hover_at(content, 1, 16).await.expect("Expected hover");
+    assert!(markup(hover).contains("parameter DEPTH = N + 5"));
+}
+
+#[tokio::test]
+/// Hovering a macro name shows the macro's definition as it stands at the end of the file
+async fn test_hover_macro_shows_definition() {
+    let content =
+        "`define WIDTH 8\n`define MAX(a, b) ((a) > (b) ? (a) : (b))\nmodule test;\nendmodule\n`define WIDTH 16";
+
+    let hover = hover_at(content, 0, 9).await.expect("Expected hover");
+    assert_eq!(markup(hover), "```systemverilog\n`define WIDTH 16\n```");
+
+    let hover = hover_at(content, 1, 9).await.expect("Expected hover");
+    assert!(markup(hover).contains("`define MAX(a, b) ((a) > (b) ? (a) : (b))"));
+}
+
+#[tokio::test]
+/// Hovering a delay shows its length in the time unit of its module
+async fn test_hover_delay_in_time_unit() {
+    let content = "`timescale 1ns/1ps\nmodule test;\n    timeunit 100ps;\n    initial #15 $display(\"done\");\nendmodule";
+
+    let hover = hover_at(content, 3, 13).await.expect("Expected hover");
+    assert_eq!(
+        markup(hover),
+        "```systemverilog\n#15\n```\n\n15 × 100ps = 1.5ns\n\n\
+         - Time unit: 100ps, from its own declaration\n\
+         - Time precision: 1ps, from `timescale"
+    );
+}
+
+#[tokio::test]
+/// A delay finer than the precision is shown rounded
+async fn test_hover_delay_rounded_to_precision() {
+    let content =
+        "`timescale 1ns/1ns\nmodule test;\n    timeunit 100ps;\n    initial #15 $display(\"done\");\nendmodule";
+
+    let hover = hover_at(content, 3, 13).await.expect("Expected hover");
+    assert!(markup(hover).contains("15 × 100ps = 1.5ns, rounded to 2ns by the precision"));
+}
+
+#[tokio::test]
+/// Without a `timescale or timeunit the delay has no known length
+async fn test_hover_delay_without_time_unit() {
+    let content = "module test;\n    initial #15 $display(\"done\");\nendmodule";
+
+    let hover = hover_at(content, 1, 13).await.expect("Expected hover");
+    assert!(markup(hover).contains("simulator's default time unit"));
+}
+
+#[tokio::test]
+/// Hovering the name of an event or a clocking block shows what it declares
+async fn test_hover_event_and_clocking_block() {
+    let content = "module test(input logic clk);\n    event done;\n    clocking cb @(posedge clk);\n        input done_q;\n    endclocking\n    initial -> done;\nendmodule";
+
+    let hover = hover_at(content, 1, 11).await.expect("Expected hover");
+    assert!(markup(hover).contains("event done"));
+    let hover = hover_at(content, 2, 14).await.expect("Expected hover");
+    assert!(markup(hover).contains("clocking cb"));
+}
+
+#[tokio::test]
+/// System calls in the branches, loop bodies and action blocks of a statement have hovers
+async fn test_hover_system_calls_in_nested_statements() {
+    let content = "module test(input logic en);\n    initial if (en) begin\n        repeat (2) $display(\"on\");\n    end else $finish;\n    always assert (en) else $display(\"off\");\nendmodule";
+
+    let hover = hover_at(content, 2, 20).await.expect("Expected hover");
+    assert!(markup(hover).contains("$display"));
+    let hover = hover_at(content, 3, 15).await.expect("Expected hover");
+    assert!(markup(hover).contains("$finish"));
+    let hover = hover_at(content, 4, 30).await.expect("Expected hover");
+    assert!(markup(hover).contains("$display"));
+}
+
+#[tokio::test]
+/// A name imported from a package shows the declaration the imports choose
+async fn test_hover_imported_name_shows_winning_package() {
+    let content = "package bus_pkg;\n    parameter WIDTH = 8;\nendpackage\n\
+                   package cpu_pkg;\n    parameter WIDTH = 16;\nendpackage\n\
+                   module top;\n    import bus_pkg::*;\n    import cpu_pkg::WIDTH;\n\
+                   \x20   logic [7:0] a;\n    assign a = WIDTH;\nendmodule";
+
+    let hover = hover_at(content, 10, 16).await.expect("Expected hover");
+    let text = markup(hover);
+    assert!(text.contains("parameter WIDTH"), "got: {}", text);
+    assert!(
+        text.contains("Imported from package `cpu_pkg`"),
+        "got: {}",
+        text
+    );
+}
@@ -0,0 +1,87 @@
+pub mod common;
+
+use std::fs;
+use std::path::Path;
+use sv_language_server::index_cache::content_hash;
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+const CONTENT: &str = r#"module cached_module;
+    logic cached_signal;
+endmodule"#;
+
+/// Start a backend with `root` as the workspace root
+async fn start_backend(root: &Path) -> std::sync::Arc<sv_language_server::Backend> {
+    let backend = common::create_test_backend();
+    backend
+        .initialize(InitializeParams {
+            root_uri: Some(Url::from_directory_path(root).unwrap()),
+            ..InitializeParams::default()
+        })
+        .await
+        .unwrap();
+    backend.initialized(InitializedParams {}).await;
+    backend
+}
+
+#[tokio::test]
+async fn test_index_cache_restores_symbols_on_startup() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("cached.sv");
+    fs::write(&file, CONTENT).unwrap();
+    let uri = Url::from_file_path(&file).unwrap();
+
+    let backend = start_backend(dir.path()).await;
+    common::open(&backend, &uri, CONTENT).await;
+    backend.shutdown().await.unwrap();
+    assert!(dir.path().join(".sv-lsp-cache/index.json").exists());
+
+    // A new session knows the symbols without opening the file
+    let backend = start_backend(dir.path()).await;
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    let locations = workspace_symbols
+        .get("cached_module")
+        .expect("cached_module should be loaded from the index cache");
+    assert_eq!(locations[0].uri, uri);
+}
+
+#[tokio::test]
+async fn test_index_cache_skips_changed_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("changed.sv");
+    fs::write(&file, CONTENT).unwrap();
+    let uri = Url::from_file_path(&file).unwrap();
+
+    let backend = start_backend(dir.path()).await;
+    common::open(&backend, &uri, CONTENT).await;
+    backend.shutdown().await.unwrap();
+
+    let renamed = "module renamed_module;\nendmodule\n";
+    fs::write(&file, renamed).unwrap();
+
+    let backend = start_backend(dir.path()).await;
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert!(!workspace_symbols.contains_key("cached_module"));
+    // Background indexing may already have re-indexed the file from disk
+    let indexed_files = backend.indexed_files.read().await;
+    assert!(indexed_files
+        .get(&uri)
+        .is_none_or(|indexed| indexed.hash == content_hash(renamed)));
+}
+
+#[tokio::test]
+async fn test_reopening_cached_file_does_not_duplicate_symbols() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("reopened.sv");
+    fs::write(&file, CONTENT).unwrap();
+    let uri = Url::from_file_path(&file).unwrap();
+
+    let backend = start_backend(dir.path()).await;
+    common::open(&backend, &uri, CONTENT).await;
+    backend.shutdown().await.unwrap();
+
+    let backend = start_backend(dir.path()).await;
+    common::open(&backend, &uri, CONTENT).await;
+    let workspace_symbols = backend.workspace_symbols.read().await;
+    assert_eq!(workspace_symbols["cached_module"].len(), 1);
+}
@@ -0,0 +1,59 @@
+use sv_language_server::panic_guard::{catch_panic, truncate_lines, MAX_BACKTRACE_LINES};
+
+#[tokio::test]
+/// A panic inside the future becomes a report with its message and location
+async fn test_panic_is_caught_and_reported() {
+    let result = catch_panic(async {
+        tokio::task::yield_now().await;
+        let content: &[u8] = &[];
+        if content.is_empty() {
+            panic!("unexpected end of {} tokens", content.len());
+        }
+    })
+    .await;
+
+    let report = result.expect_err("Expected the panic to be caught");
+    assert_eq!(report.message, "unexpected end of 0 tokens");
+    assert!(report
+        .location
+        .as_deref()
+        .is_some_and(|location| location.contains("panic_guard_tests.rs")));
+    assert!(!report.backtrace.is_empty());
+    assert!(report.backtrace.lines().count() <= MAX_BACKTRACE_LINES + 1);
+}
+
+#[tokio::test]
+/// Futures that complete normally pass their output through
+async fn test_completed_future_passes_through() {
+    let result = catch_panic(async { 42 }).await;
+    assert_eq!(result, Ok(42));
+}
+
+#[test]
+fn test_truncate_lines_notes_dropped_lines() {
+    let text = "a\nb\nc\nd";
+    assert_eq!(truncate_lines(text, 2), "a\nb\n... 2 more lines");
+    assert_eq!(truncate_lines(text, 4), text);
+}
+
+#[tokio::test]
+/// A panic resumed from a parser worker thread keeps its location and backtrace
+async fn test_panic_on_worker_thread_is_reported() {
+    let result = catch_panic(async {
+        sv_parser::parallel::with_stack(|| {
+            let depth: usize = std::hint::black_box(0);
+            if depth == 0 {
+                panic!("parser worker failed at depth {}", depth);
+            }
+        })
+    })
+    .await;
+
+    let report = result.expect_err("Expected the panic to be caught");
+    assert_eq!(report.message, "parser worker failed at depth 0");
+    assert!(report
+        .location
+        .as_deref()
+        .is_some_and(|location| location.contains("panic_guard_tests.rs")));
+    assert!(!report.backtrace.is_empty());
+}
@@ -0,0 +1,100 @@
+pub mod common;
+
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+async fn references(
+    backend: &sv_language_server::Backend,
+    uri: &Url,
+    position: Position,
+    include_declaration: bool,
+) -> Vec<Location> {
+    backend
+        .references(ReferenceParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+            context: ReferenceContext {
+                include_declaration,
+            },
+        })
+        .await
+        .unwrap()
+        .unwrap_or_default()
+}
+
+#[tokio::test]
+async fn test_module_references_are_instantiation_sites() {
+    let backend = common::create_test_backend();
+
+    let alu_uri = common::test_uri("/test/alu.sv");
+    common::open(&backend, &alu_uri, "module alu;\nendmodule").await;
+
+    let top_uri = common::test_uri("/test/top.sv");
+    let top = r#"module top;
+    alu u_alu0 ();
+    alu u_alu1 ();
+endmodule"#;
+    common::open(&backend, &top_uri, top).await;
+
+    let wrapper_uri = common::test_uri("/test/wrapper.sv");
+    let wrapper = r#"module wrapper;
+    logic alu;
+    alu u_core ();
+endmodule"#;
+    common::open(&backend, &wrapper_uri, wrapper).await;
+
+    // On the declaration name: the three instantiation sites, not the `alu` signal
+    let locations = references(&backend, &alu_uri, common::test_position(0, 8), false).await;
+    let sites: Vec<(&str, u32, u32)> = locations
+        .iter()
+        .map(|l| (l.uri.path(), l.range.start.line, l.range.start.character))
+        .collect();
+    assert_eq!(
+        sites,
+        vec![
+            ("/test/top.sv", 1, 4),
+            ("/test/top.sv", 2, 4),
+            ("/test/wrapper.sv", 2, 4),
+        ]
+    );
+
+    // The declaration is reported only when asked for, and from an instantiation too
+    let locations = references(&backend, &top_uri, common::test_position(1, 5), true).await;
+    assert_eq!(locations.len(), 4);
+    assert!(locations
+        .iter()
+        .any(|l| l.uri == alu_uri && l.range.start.line == 0));
+}
+
+#[tokio::test]
+async fn test_instantiation_sites_know_their_parent_module() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/design.sv");
+    let content = r#"module leaf;
+endmodule
+module top;
+    leaf u_leaf ();
+endmodule"#;
+    common::open(&backend, &uri, content).await;
+
+    let result = backend
+        .symbol(WorkspaceSymbolParams {
+            query: "leaf".to_string(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap()
+        .unwrap();
+
+    let instance = result
+        .iter()
+        .find(|s| s.name == "instance of leaf")
+        .expect("Should list the instantiation of leaf");
+    assert_eq!(instance.container_name.as_deref(), Some("top"));
+    assert_eq!(instance.location.range.start.line, 3);
+}
@@ -0,0 +1,98 @@
+pub mod common;
+
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+async fn rename(
+    backend: &sv_language_server::Backend,
+    uri: &Url,
+    position: Position,
+    new_name: &str,
+) -> WorkspaceEdit {
+    backend
+        .rename(RenameParams {
+            text_document_position: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier { uri: uri.clone() },
+                position,
+            },
+            new_name: new_name.to_string(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        })
+        .await
+        .unwrap()
+        .expect("Expected a rename edit")
+}
+
+const ALU: &str = "module alu(input logic clk, input logic [7:0] a, output logic [7:0] y);
+    assign y = a;
+endmodule
+";
+
+const TOP: &str = "module top(input logic clk);
+    logic [7:0] a, y;
+    alu u_explicit (.clk(clk), .a(a), .y(y));
+    alu u_implicit (.clk, .a(a), .y);
+endmodule
+";
+
+#[tokio::test]
+async fn test_renaming_port_renames_named_connections() {
+    let backend = common::create_test_backend();
+    let alu_uri = common::test_uri("/test/alu.sv");
+    let top_uri = common::test_uri("/test/top.sv");
+    common::open(&backend, &alu_uri, ALU).await;
+    common::open(&backend, &top_uri, TOP).await;
+
+    let edit = rename(&backend, &alu_uri, common::test_position(0, 68), "result").await;
+
+    assert!(edit.changes.is_none());
+    let Some(DocumentChanges::Edits(documents)) = edit.document_changes else {
+        panic!("Expected document edits");
+    };
+    let top = documents
+        .iter()
+        .find(|document| document.text_document.uri == top_uri)
+        .expect("Expected edits in top.sv");
+    let connections: Vec<Range> = top
+        .edits
+        .iter()
+        .filter_map(|edit| match edit {
+            OneOf::Right(annotated) => {
+                assert_eq!(annotated.annotation_id, "port-connections");
+                assert_eq!(annotated.text_edit.new_text, "result");
+                Some(annotated.text_edit.range)
+            }
+            OneOf::Left(_) => None,
+        })
+        .collect();
+    // `.y` in u_implicit is also a reference to the variable, so it is renamed
+    // with the references instead
+    assert_eq!(
+        connections,
+        vec![Range::new(
+            common::test_position(2, 39),
+            common::test_position(2, 40)
+        )]
+    );
+
+    let annotations = edit.change_annotations.expect("Expected annotations");
+    assert_eq!(
+        annotations["port-connections"].needs_confirmation,
+        Some(true)
+    );
+}
+
+#[tokio::test]
+async fn test_renaming_variable_has_no_connection_edits() {
+    let backend = common::create_test_backend();
+    let uri = common::test_uri("/test/top.sv");
+    common::open(&backend, &uri, TOP).await;
+
+    let edit = rename(&backend, &uri, common::test_position(1, 16), "operand").await;
+
+    assert!(edit.document_changes.is_none());
+    assert!(edit.change_annotations.is_none());
+    assert!(edit
+        .changes
+        .is_some_and(|changes| changes.contains_key(&uri)));
+}
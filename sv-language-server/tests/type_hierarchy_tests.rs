@@ -0,0 +1,87 @@
+pub mod common;
+
+use tower_lsp::lsp_types::*;
+use tower_lsp::LanguageServer;
+
+fn names(items: Option<Vec<TypeHierarchyItem>>) -> Vec<String> {
+    items
+        .unwrap_or_default()
+        .into_iter()
+        .map(|item| item.name)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_type_hierarchy_across_files() {
+    let backend = common::create_test_backend();
+
+    let base_uri = common::test_uri("/test/base.sv");
+    common::open(
+        &backend,
+        &base_uri,
+        "class base;\n  virtual function void show();\n  endfunction\nendclass\n",
+    )
+    .await;
+    let derived_uri = common::test_uri("/test/derived.sv");
+    common::open(
+        &backend,
+        &derived_uri,
+        "class derived extends base;\nendclass\nclass other extends base;\nendclass\n",
+    )
+    .await;
+
+    let items = backend
+        .prepare_type_hierarchy(TypeHierarchyPrepareParams {
+            text_document_position_params: TextDocumentPositionParams {
+                text_document: TextDocumentIdentifier {
+                    uri: derived_uri.clone(),
+                },
+                position: common::test_position(0, 8),
+            },
+            work_done_progress_params: WorkDoneProgressParams::default(),
+        })
+        .await
+        .unwrap()
+        .expect("Expected a type hierarchy item for 'derived'");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].name, "derived");
+    assert_eq!(items[0].kind, SymbolKind::CLASS);
+    assert_eq!(items[0].detail.as_deref(), Some("base"));
+
+    let supertypes = backend
+        .supertypes(TypeHierarchySupertypesParams {
+            item: items[0].clone(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    let supertypes = supertypes.unwrap();
+    assert_eq!(supertypes.len(), 1);
+    assert_eq!(supertypes[0].name, "base");
+    assert_eq!(supertypes[0].uri, base_uri);
+    assert_eq!(
+        supertypes[0].selection_range.start,
+        common::test_position(0, 6)
+    );
+
+    let subtypes = backend
+        .subtypes(TypeHierarchySubtypesParams {
+            item: supertypes[0].clone(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(names(subtypes), vec!["derived", "other"]);
+
+    let root_supertypes = backend
+        .supertypes(TypeHierarchySupertypesParams {
+            item: supertypes[0].clone(),
+            work_done_progress_params: WorkDoneProgressParams::default(),
+            partial_result_params: PartialResultParams::default(),
+        })
+        .await
+        .unwrap();
+    assert!(names(root_supertypes).is_empty());
+}
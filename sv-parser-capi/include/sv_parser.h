@@ -0,0 +1,158 @@
+#ifndef SV_PARSER_H
+#define SV_PARSER_H
+
+/* Generated by cbindgen from sv-parser-capi; do not edit by hand. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+// Phase that reported a diagnostic
+typedef enum SvDiagnosticKind {
+  // The source could not be read, preprocessed or parsed
+  SV_DIAGNOSTIC_KIND_SYNTAX,
+  // The source parsed but is not valid SystemVerilog
+  SV_DIAGNOSTIC_KIND_SEMANTIC,
+} SvDiagnosticKind;
+
+// Kind of a top-level design unit
+typedef enum SvUnitKind {
+  SV_UNIT_KIND_MODULE,
+  SV_UNIT_KIND_INTERFACE,
+  SV_UNIT_KIND_PACKAGE,
+  SV_UNIT_KIND_CLASS,
+  SV_UNIT_KIND_INTERFACE_CLASS,
+  SV_UNIT_KIND_CHECKER,
+} SvUnitKind;
+
+// The diagnostics and design units of a parsed source; opaque to C
+typedef struct SvParseResult SvParseResult;
+
+// Byte range of the source text, end exclusive
+typedef struct SvSpan {
+  size_t start;
+  size_t end;
+} SvSpan;
+
+// Zero-based line and byte column
+typedef struct SvPosition {
+  uint32_t line;
+  uint32_t column;
+} SvPosition;
+
+// An error found in the source
+typedef struct SvDiagnostic {
+  SvDiagnosticKind kind;
+  // Borrowed from the parse result
+  const char *message;
+  SvSpan span;
+  SvPosition start;
+  SvPosition end;
+} SvDiagnostic;
+
+// A design unit defined at the top level of the source
+typedef struct SvUnit {
+  SvUnitKind kind;
+  // Borrowed from the parse result
+  const char *name;
+  SvSpan span;
+  SvSpan name_span;
+} SvUnit;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Parse and analyze SystemVerilog source text
+//
+// Includes are resolved relative to the current directory. Returns NULL if
+// `text` is NULL or not UTF-8; otherwise the caller owns the result and
+// frees it with `sv_parse_result_free`.
+//
+// # Safety
+//
+// `text` must be NULL or point to a NUL-terminated string.
+SvParseResult *sv_parse(const char *text);
+
+// Parse and analyze a SystemVerilog file and the files it includes
+//
+// Includes are searched for next to the file, then in the
+// `include_dir_count` directories of `include_dirs`. Spans point into the
+// text of the file itself. A file that cannot be read gives a result with a
+// single syntax diagnostic. Returns NULL if `path` or one of the include
+// directories is NULL or not UTF-8; otherwise the caller owns the result
+// and frees it with `sv_parse_result_free`.
+//
+// # Safety
+//
+// `path` must be NULL or point to a NUL-terminated string, and
+// `include_dirs` must be NULL or point to `include_dir_count` pointers that
+// are each NULL or point to a NUL-terminated string.
+SvParseResult *sv_parse_file(const char *path,
+                             const char *const *include_dirs,
+                             size_t include_dir_count);
+
+// Release a parse result and the strings borrowed from it
+//
+// Does nothing for NULL.
+//
+// # Safety
+//
+// `result` must be NULL or a handle returned by `sv_parse` or
+// `sv_parse_file` that has not been freed yet.
+void sv_parse_result_free(SvParseResult *result);
+
+// Whether the source parsed, even if it has semantic diagnostics; design
+// units are only reported for sources that parsed
+//
+// # Safety
+//
+// `result` must be NULL or a live handle.
+bool sv_parse_result_parsed(const SvParseResult *result);
+
+// Number of diagnostics of a parse result; 0 for NULL
+//
+// # Safety
+//
+// `result` must be NULL or a live handle.
+size_t sv_diagnostic_count(const SvParseResult *result);
+
+// Fill in `out` with diagnostic `index` of a parse result
+//
+// Returns false, leaving `out` untouched, for a NULL argument or an index
+// past the last diagnostic.
+//
+// # Safety
+//
+// `result` must be NULL or a live handle, and `out` NULL or valid for
+// writes.
+bool sv_diagnostic_get(const SvParseResult *result, size_t index, SvDiagnostic *out);
+
+// Number of top-level design units of a parse result; 0 for NULL
+//
+// # Safety
+//
+// `result` must be NULL or a live handle.
+size_t sv_unit_count(const SvParseResult *result);
+
+// Fill in `out` with design unit `index` of a parse result, in source order
+//
+// Returns false, leaving `out` untouched, for a NULL argument or an index
+// past the last unit.
+//
+// # Safety
+//
+// `result` must be NULL or a live handle, and `out` NULL or valid for
+// writes.
+bool sv_unit_get(const SvParseResult *result, size_t index, SvUnit *out);
+
+// Version of the library, as a static string the caller must not free
+const char *sv_version(void);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* SV_PARSER_H */
@@ -0,0 +1,456 @@
+//! C API for the parser
+//!
+//! Lets tools written in C, C++ or Python (through `ctypes` or `cffi`) embed
+//! the parser over a stable C ABI. The declarations are in
+//! `include/sv_parser.h`, generated with cbindgen from this file.
+//!
+//! A parse returns an opaque [`SvParseResult`] handle that holds the
+//! diagnostics and design units found in the source. Memory ownership
+//! follows three rules:
+//!
+//! - Strings passed in are NUL-terminated UTF-8 and stay owned by the caller;
+//!   the library copies what it keeps.
+//! - Every handle returned by `sv_parse` or `sv_parse_file` is owned by the
+//!   caller and must be released with exactly one `sv_parse_result_free`.
+//! - Strings in the structs filled in by the query functions are borrowed
+//!   from the handle: they stay valid until the handle is freed and must not
+//!   be freed by the caller.
+//!
+//! Spans are byte offsets into the UTF-8 source text, and positions are
+//! zero-based lines and byte columns. A handle may be read from several
+//! threads at once, but not freed while another thread reads it.
+
+use std::collections::HashMap;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
+use std::ptr;
+
+use sv_parser::position::{offset_to_line_column, PositionEncoding};
+use sv_parser::{ModuleItem, ParseError, SourceUnit, Span, SystemVerilogParser};
+
+/// Byte range of the source text, end exclusive
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SvSpan {
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Zero-based line and byte column
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SvPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Phase that reported a diagnostic
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvDiagnosticKind {
+    /// The source could not be read, preprocessed or parsed
+    Syntax,
+    /// The source parsed but is not valid SystemVerilog
+    Semantic,
+}
+
+/// An error found in the source
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SvDiagnostic {
+    pub kind: SvDiagnosticKind,
+    /// Borrowed from the parse result
+    pub message: *const c_char,
+    pub span: SvSpan,
+    pub start: SvPosition,
+    pub end: SvPosition,
+}
+
+/// Kind of a top-level design unit
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SvUnitKind {
+    Module,
+    Interface,
+    Package,
+    Class,
+    InterfaceClass,
+    Checker,
+}
+
+/// A design unit defined at the top level of the source
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct SvUnit {
+    pub kind: SvUnitKind,
+    /// Borrowed from the parse result
+    pub name: *const c_char,
+    pub span: SvSpan,
+    pub name_span: SvSpan,
+}
+
+/// The diagnostics and design units of a parsed source; opaque to C
+pub struct SvParseResult {
+    parsed: bool,
+    diagnostics: Vec<Diagnostic>,
+    units: Vec<Unit>,
+}
+
+/// A diagnostic with the message its [`SvDiagnostic`] borrows
+struct Diagnostic {
+    kind: SvDiagnosticKind,
+    message: CString,
+    span: SvSpan,
+    start: SvPosition,
+    end: SvPosition,
+}
+
+/// A design unit with the name its [`SvUnit`] borrows
+struct Unit {
+    kind: SvUnitKind,
+    name: CString,
+    span: SvSpan,
+    name_span: SvSpan,
+}
+
+/// Converts the parser's character spans to byte spans and positions
+struct SourceText<'a> {
+    text: &'a str,
+    /// Byte offset of each character, and of the end of the text
+    byte_offsets: Vec<usize>,
+}
+
+impl<'a> SourceText<'a> {
+    fn new(text: &'a str) -> Self {
+        let byte_offsets = text
+            .char_indices()
+            .map(|(offset, _)| offset)
+            .chain(std::iter::once(text.len()))
+            .collect();
+        Self { text, byte_offsets }
+    }
+
+    fn span(&self, span: Span) -> SvSpan {
+        let byte = |offset: usize| self.byte_offsets[offset.min(self.byte_offsets.len() - 1)];
+        SvSpan {
+            start: byte(span.0),
+            end: byte(span.1),
+        }
+    }
+
+    fn position(&self, offset: usize) -> SvPosition {
+        let position = offset_to_line_column(self.text, offset, PositionEncoding::Utf8);
+        SvPosition {
+            line: position.line,
+            column: position.column,
+        }
+    }
+}
+
+/// A C string of `text`, without the NUL characters C cannot hold
+fn c_string(text: &str) -> CString {
+    CString::new(text.replace('\0', "")).expect("NUL characters were removed")
+}
+
+impl SvParseResult {
+    fn new(
+        parser: &SystemVerilogParser,
+        result: Result<SourceUnit, ParseError>,
+        text: &str,
+    ) -> Self {
+        let source = SourceText::new(text);
+        match result {
+            Ok(source_unit) => {
+                let diagnostics = parser
+                    .analyze_semantics(&source_unit)
+                    .into_iter()
+                    .map(|error| Diagnostic {
+                        kind: SvDiagnosticKind::Semantic,
+                        message: c_string(&error.message),
+                        span: source.span(error.span),
+                        start: source.position(error.span.0),
+                        end: source.position(error.span.1),
+                    })
+                    .collect();
+                let units = source_unit
+                    .items
+                    .iter()
+                    .filter_map(|item_ref| {
+                        let (kind, name, name_span, span) =
+                            match source_unit.module_item_arena.get(*item_ref) {
+                                ModuleItem::ModuleDeclaration {
+                                    name,
+                                    name_span,
+                                    span,
+                                    ..
+                                } => (SvUnitKind::Module, name, name_span, span),
+                                ModuleItem::InterfaceDeclaration {
+                                    name,
+                                    name_span,
+                                    span,
+                                    ..
+                                } => (SvUnitKind::Interface, name, name_span, span),
+                                ModuleItem::PackageDeclaration {
+                                    name,
+                                    name_span,
+                                    span,
+                                    ..
+                                } => (SvUnitKind::Package, name, name_span, span),
+                                ModuleItem::ClassDeclaration {
+                                    name,
+                                    name_span,
+                                    span,
+                                    ..
+                                } => (SvUnitKind::Class, name, name_span, span),
+                                ModuleItem::InterfaceClassDeclaration {
+                                    name,
+                                    name_span,
+                                    span,
+                                    ..
+                                } => (SvUnitKind::InterfaceClass, name, name_span, span),
+                                ModuleItem::CheckerDeclaration {
+                                    name,
+                                    name_span,
+                                    span,
+                                    ..
+                                } => (SvUnitKind::Checker, name, name_span, span),
+                                _ => return None,
+                            };
+                        Some(Unit {
+                            kind,
+                            name: c_string(name),
+                            span: source.span(*span),
+                            name_span: source.span(*name_span),
+                        })
+                    })
+                    .collect();
+                Self {
+                    parsed: true,
+                    diagnostics,
+                    units,
+                }
+            }
+            Err(error) => {
+                let diagnostics = error
+                    .errors
+                    .iter()
+                    .map(|error| {
+                        let span = error
+                            .location
+                            .as_ref()
+                            .and_then(|location| location.span)
+                            .unwrap_or_default();
+                        Diagnostic {
+                            kind: SvDiagnosticKind::Syntax,
+                            message: c_string(&error.message),
+                            span: source.span(span),
+                            start: source.position(span.0),
+                            end: source.position(span.1),
+                        }
+                    })
+                    .collect();
+                Self {
+                    parsed: false,
+                    diagnostics,
+                    units: Vec::new(),
+                }
+            }
+        }
+    }
+}
+
+/// Run `parse` and box its result, or return NULL if it panics
+fn guarded(parse: impl FnOnce() -> SvParseResult) -> *mut SvParseResult {
+    match panic::catch_unwind(AssertUnwindSafe(parse)) {
+        Ok(result) => Box::into_raw(Box::new(result)),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+/// The UTF-8 string at `text`, or None for NULL or invalid UTF-8
+///
+/// # Safety
+///
+/// `text` must be NULL or point to a NUL-terminated string.
+unsafe fn str_arg<'a>(text: *const c_char) -> Option<&'a str> {
+    if text.is_null() {
+        return None;
+    }
+    CStr::from_ptr(text).to_str().ok()
+}
+
+/// Parse and analyze SystemVerilog source text
+///
+/// Includes are resolved relative to the current directory. Returns NULL if
+/// `text` is NULL or not UTF-8; otherwise the caller owns the result and
+/// frees it with `sv_parse_result_free`.
+///
+/// # Safety
+///
+/// `text` must be NULL or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn sv_parse(text: *const c_char) -> *mut SvParseResult {
+    let Some(text) = str_arg(text) else {
+        return ptr::null_mut();
+    };
+    guarded(|| {
+        let parser = SystemVerilogParser::new(Vec::new(), HashMap::new());
+        let result = parser.parse_content(text);
+        SvParseResult::new(&parser, result, text)
+    })
+}
+
+/// Parse and analyze a SystemVerilog file and the files it includes
+///
+/// Includes are searched for next to the file, then in the
+/// `include_dir_count` directories of `include_dirs`. Spans point into the
+/// text of the file itself. A file that cannot be read gives a result with a
+/// single syntax diagnostic. Returns NULL if `path` or one of the include
+/// directories is NULL or not UTF-8; otherwise the caller owns the result
+/// and frees it with `sv_parse_result_free`.
+///
+/// # Safety
+///
+/// `path` must be NULL or point to a NUL-terminated string, and
+/// `include_dirs` must be NULL or point to `include_dir_count` pointers that
+/// are each NULL or point to a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn sv_parse_file(
+    path: *const c_char,
+    include_dirs: *const *const c_char,
+    include_dir_count: usize,
+) -> *mut SvParseResult {
+    let Some(path) = str_arg(path) else {
+        return ptr::null_mut();
+    };
+    let mut dirs = Vec::with_capacity(include_dir_count);
+    if !include_dirs.is_null() {
+        for index in 0..include_dir_count {
+            let Some(dir) = str_arg(*include_dirs.add(index)) else {
+                return ptr::null_mut();
+            };
+            dirs.push(PathBuf::from(dir));
+        }
+    }
+    guarded(|| {
+        let path = Path::new(path);
+        let mut parser = SystemVerilogParser::new(dirs, HashMap::new());
+        let result = parser.parse_file(path);
+        let text = std::fs::read_to_string(path).unwrap_or_default();
+        SvParseResult::new(&parser, result, &text)
+    })
+}
+
+/// Release a parse result and the strings borrowed from it
+///
+/// Does nothing for NULL.
+///
+/// # Safety
+///
+/// `result` must be NULL or a handle returned by `sv_parse` or
+/// `sv_parse_file` that has not been freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn sv_parse_result_free(result: *mut SvParseResult) {
+    if !result.is_null() {
+        drop(Box::from_raw(result));
+    }
+}
+
+/// Whether the source parsed, even if it has semantic diagnostics; design
+/// units are only reported for sources that parsed
+///
+/// # Safety
+///
+/// `result` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sv_parse_result_parsed(result: *const SvParseResult) -> bool {
+    result.as_ref().is_some_and(|result| result.parsed)
+}
+
+/// Number of diagnostics of a parse result; 0 for NULL
+///
+/// # Safety
+///
+/// `result` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sv_diagnostic_count(result: *const SvParseResult) -> usize {
+    result.as_ref().map_or(0, |result| result.diagnostics.len())
+}
+
+/// Fill in `out` with diagnostic `index` of a parse result
+///
+/// Returns false, leaving `out` untouched, for a NULL argument or an index
+/// past the last diagnostic.
+///
+/// # Safety
+///
+/// `result` must be NULL or a live handle, and `out` NULL or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn sv_diagnostic_get(
+    result: *const SvParseResult,
+    index: usize,
+    out: *mut SvDiagnostic,
+) -> bool {
+    let (Some(result), false) = (result.as_ref(), out.is_null()) else {
+        return false;
+    };
+    let Some(diagnostic) = result.diagnostics.get(index) else {
+        return false;
+    };
+    out.write(SvDiagnostic {
+        kind: diagnostic.kind,
+        message: diagnostic.message.as_ptr(),
+        span: diagnostic.span,
+        start: diagnostic.start,
+        end: diagnostic.end,
+    });
+    true
+}
+
+/// Number of top-level design units of a parse result; 0 for NULL
+///
+/// # Safety
+///
+/// `result` must be NULL or a live handle.
+#[no_mangle]
+pub unsafe extern "C" fn sv_unit_count(result: *const SvParseResult) -> usize {
+    result.as_ref().map_or(0, |result| result.units.len())
+}
+
+/// Fill in `out` with design unit `index` of a parse result, in source order
+///
+/// Returns false, leaving `out` untouched, for a NULL argument or an index
+/// past the last unit.
+///
+/// # Safety
+///
+/// `result` must be NULL or a live handle, and `out` NULL or valid for
+/// writes.
+#[no_mangle]
+pub unsafe extern "C" fn sv_unit_get(
+    result: *const SvParseResult,
+    index: usize,
+    out: *mut SvUnit,
+) -> bool {
+    let (Some(result), false) = (result.as_ref(), out.is_null()) else {
+        return false;
+    };
+    let Some(unit) = result.units.get(index) else {
+        return false;
+    };
+    out.write(SvUnit {
+        kind: unit.kind,
+        name: unit.name.as_ptr(),
+        span: unit.span,
+        name_span: unit.name_span,
+    });
+    true
+}
+
+/// Version of the library, as a static string the caller must not free
+#[no_mangle]
+pub extern "C" fn sv_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
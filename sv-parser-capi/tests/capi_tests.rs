@@ -0,0 +1,134 @@
+//! C API tests, calling the exported functions the way C code would.
+
+use std::ffi::{CStr, CString};
+use std::fs;
+use std::mem::MaybeUninit;
+use std::ptr;
+
+use sv_parser_capi::*;
+use tempfile::TempDir;
+
+fn parse(text: &str) -> *mut SvParseResult {
+    let text = CString::new(text).unwrap();
+    unsafe { sv_parse(text.as_ptr()) }
+}
+
+fn diagnostic(result: *const SvParseResult, index: usize) -> Option<SvDiagnostic> {
+    let mut out = MaybeUninit::uninit();
+    unsafe { sv_diagnostic_get(result, index, out.as_mut_ptr()).then(|| out.assume_init()) }
+}
+
+fn unit(result: *const SvParseResult, index: usize) -> Option<SvUnit> {
+    let mut out = MaybeUninit::uninit();
+    unsafe { sv_unit_get(result, index, out.as_mut_ptr()).then(|| out.assume_init()) }
+}
+
+fn string(text: *const std::ffi::c_char) -> &'static str {
+    unsafe { CStr::from_ptr(text) }.to_str().unwrap()
+}
+
+#[test]
+fn test_units_of_a_valid_source() {
+    let result = parse("// é\nmodule top;\nendmodule\npackage pkg;\nendpackage\n");
+    assert!(!result.is_null());
+
+    unsafe {
+        assert!(sv_parse_result_parsed(result));
+        assert_eq!(sv_diagnostic_count(result), 0);
+        assert_eq!(sv_unit_count(result), 2);
+    }
+    let top = unit(result, 0).unwrap();
+    assert_eq!(top.kind, SvUnitKind::Module);
+    assert_eq!(string(top.name), "top");
+    // Spans count bytes, and `é` takes two
+    assert_eq!(top.name_span, SvSpan { start: 13, end: 16 });
+    assert_eq!(unit(result, 1).unwrap().kind, SvUnitKind::Package);
+    assert!(unit(result, 2).is_none());
+
+    unsafe { sv_parse_result_free(result) };
+}
+
+#[test]
+fn test_semantic_diagnostics() {
+    let result = parse("module top(input logic a);\n    assign a = 1'b0;\nendmodule\n");
+
+    unsafe {
+        assert!(sv_parse_result_parsed(result));
+        assert_eq!(sv_diagnostic_count(result), 1);
+    }
+    let error = diagnostic(result, 0).unwrap();
+    assert_eq!(error.kind, SvDiagnosticKind::Semantic);
+    assert_eq!(string(error.message), "Cannot assign to input port 'a'");
+    assert_eq!(error.start.line, 1);
+
+    unsafe { sv_parse_result_free(result) };
+}
+
+#[test]
+fn test_syntax_diagnostics() {
+    let result = parse("module top;\n    logic a\nendmodule\n");
+
+    unsafe {
+        assert!(!sv_parse_result_parsed(result));
+        assert!(sv_diagnostic_count(result) > 0);
+        assert_eq!(sv_unit_count(result), 0);
+    }
+    assert_eq!(
+        diagnostic(result, 0).unwrap().kind,
+        SvDiagnosticKind::Syntax
+    );
+
+    unsafe { sv_parse_result_free(result) };
+}
+
+#[test]
+fn test_parse_file_with_include_dirs() {
+    let dir = TempDir::new().unwrap();
+    let include_dir = dir.path().join("include");
+    fs::create_dir(&include_dir).unwrap();
+    fs::write(include_dir.join("defs.svh"), "`define WIDTH 8\n").unwrap();
+    let path = dir.path().join("top.sv");
+    fs::write(
+        &path,
+        "`include \"defs.svh\"\nmodule top;\n    logic [`WIDTH:0] a;\nendmodule\n",
+    )
+    .unwrap();
+
+    let path = CString::new(path.to_str().unwrap()).unwrap();
+    let include_dir = CString::new(include_dir.to_str().unwrap()).unwrap();
+    let include_dirs = [include_dir.as_ptr()];
+    let result = unsafe { sv_parse_file(path.as_ptr(), include_dirs.as_ptr(), 1) };
+
+    unsafe {
+        assert!(sv_parse_result_parsed(result));
+        assert_eq!(sv_unit_count(result), 1);
+    }
+    assert_eq!(string(unit(result, 0).unwrap().name), "top");
+
+    unsafe { sv_parse_result_free(result) };
+}
+
+#[test]
+fn test_unreadable_file_is_a_diagnostic() {
+    let path = CString::new("/no/such/file.sv").unwrap();
+    let result = unsafe { sv_parse_file(path.as_ptr(), ptr::null(), 0) };
+
+    assert!(!result.is_null());
+    let error = diagnostic(result, 0).unwrap();
+    assert!(string(error.message).starts_with("Failed to read file"));
+
+    unsafe { sv_parse_result_free(result) };
+}
+
+#[test]
+fn test_null_arguments() {
+    unsafe {
+        assert!(sv_parse(ptr::null()).is_null());
+        assert_eq!(sv_diagnostic_count(ptr::null()), 0);
+        assert!(!sv_parse_result_parsed(ptr::null()));
+        sv_parse_result_free(ptr::null_mut());
+    }
+    let bad_utf8 = [0xffu8, 0];
+    assert!(unsafe { sv_parse(bad_utf8.as_ptr().cast()) }.is_null());
+    assert_eq!(string(sv_version()), env!("CARGO_PKG_VERSION"));
+}
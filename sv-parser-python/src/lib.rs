@@ -0,0 +1,391 @@
+//! Python bindings
+//!
+//! The `sv_parser` extension module, so analysis scripts see the same parse
+//! as the language server. Build it into the active Python environment with
+//! maturin:
+//!
+//! ```text
+//! maturin develop -m sv-parser-python/pyproject.toml
+//! ```
+//!
+//! ```python
+//! import sv_parser
+//!
+//! result = sv_parser.parse_file("top.sv", include_dirs=["include"])
+//! for diagnostic in result.diagnostics:
+//!     print(diagnostic.start, diagnostic.message)
+//! for module in result.modules:
+//!     print(module.name, [port.name for port in module.ports])
+//! ```
+//!
+//! Spans are `(start, end)` character offsets, so they slice Python strings
+//! directly, and positions are zero-based `(line, column)` pairs counted in
+//! characters.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use pyo3::prelude::*;
+
+use sv_parser::position::{offset_to_line_column, PositionEncoding};
+use sv_parser::printer::print_expression;
+use sv_parser::{
+    ExprRef, ModuleItem, ParseError, PortDirection, SourceUnit, Span, SystemVerilogParser,
+};
+
+/// The modules and diagnostics of a parsed source
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct ParseResult {
+    /// Whether the source parsed, even if it has semantic diagnostics
+    parsed: bool,
+    /// Top-level modules in source order; empty if the source did not parse
+    modules: Vec<Module>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+/// A syntax or semantic error in the source
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    /// `"syntax"` or `"semantic"`
+    kind: String,
+    message: String,
+    /// None for a syntax error the parser could not place
+    span: Option<Span>,
+    start: (u32, u32),
+    end: (u32, u32),
+}
+
+/// A module declaration
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Module {
+    name: String,
+    name_span: Span,
+    span: Span,
+    ports: Vec<Port>,
+    /// Continuous assignments, including net declaration assignments
+    assignments: Vec<Assignment>,
+    instances: Vec<Instance>,
+}
+
+/// A port of a module header
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Port {
+    name: String,
+    name_span: Span,
+    /// `"input"`, `"output"` or `"inout"`; None for a non-ANSI or interface port
+    direction: Option<String>,
+    /// Type keyword, like `logic` in `input logic [7:0] a`
+    data_type: Option<String>,
+    /// Interface of an interface port, like `bus_if` in `bus_if.master m`
+    interface: Option<String>,
+    span: Span,
+}
+
+/// `assign target = value;`
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    target: Expression,
+    value: Expression,
+    span: Span,
+}
+
+/// An instance of another module
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Instance {
+    module_name: String,
+    instance_name: String,
+    span: Span,
+}
+
+/// An expression and the expressions it is made of
+#[pyclass(module = "sv_parser", frozen, get_all)]
+#[derive(Debug, Clone)]
+pub struct Expression {
+    /// Node type, like `"identifier"`, `"binary"` or `"call"`
+    kind: String,
+    /// The expression as SystemVerilog, in the layout of the pretty printer
+    text: String,
+    /// Operands, arguments or values, in source order
+    operands: Vec<Expression>,
+}
+
+#[pymethods]
+impl Diagnostic {
+    fn __repr__(&self) -> String {
+        format!(
+            "<Diagnostic {} {}:{}: {}>",
+            self.kind,
+            self.start.0 + 1,
+            self.start.1 + 1,
+            self.message
+        )
+    }
+}
+
+#[pymethods]
+impl Module {
+    fn __repr__(&self) -> String {
+        format!("<Module {}>", self.name)
+    }
+}
+
+#[pymethods]
+impl Port {
+    fn __repr__(&self) -> String {
+        format!("<Port {}>", self.name)
+    }
+}
+
+#[pymethods]
+impl Expression {
+    fn __repr__(&self) -> String {
+        format!("<Expression {} {}>", self.kind, self.text)
+    }
+}
+
+impl Expression {
+    fn new(source_unit: &SourceUnit, expr_ref: ExprRef) -> Self {
+        use sv_parser::Expression as E;
+
+        let (kind, operands): (&str, Vec<ExprRef>) = match source_unit.expr_arena.get(expr_ref) {
+            E::Identifier(..) => ("identifier", Vec::new()),
+            E::Number(..) => ("number", Vec::new()),
+            E::StringLiteral(..) => ("string", Vec::new()),
+            E::This(..) => ("this", Vec::new()),
+            E::Super(..) => ("super", Vec::new()),
+            E::ScopedIdentifier { .. } => ("scoped_identifier", Vec::new()),
+            E::Binary { left, right, .. } => ("binary", vec![*left, *right]),
+            E::Unary { operand, .. } => ("unary", vec![*operand]),
+            E::IncDec { operand, .. } => ("inc_dec", vec![*operand]),
+            E::MacroUsage { arguments, .. } => ("macro", arguments.clone()),
+            E::SystemFunctionCall { arguments, .. } => ("system_call", arguments.clone()),
+            E::New { arguments, .. } => ("new", arguments.clone()),
+            E::MemberAccess { object, .. } => ("member_access", vec![*object]),
+            E::FunctionCall {
+                function,
+                arguments,
+                ..
+            } => (
+                "call",
+                std::iter::once(*function)
+                    .chain(arguments.iter().copied())
+                    .collect(),
+            ),
+            E::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => ("conditional", vec![*condition, *then_expr, *else_expr]),
+            E::TaggedUnion { value, .. } => ("tagged", value.iter().copied().collect()),
+            E::AssignmentPattern { items, .. } => (
+                "assignment_pattern",
+                items.iter().map(|item| item.value).collect(),
+            ),
+            E::ValueRange { low, high, .. } => ("value_range", vec![*low, *high]),
+            E::RandomizeWith {
+                call, constraints, ..
+            } => (
+                "randomize_with",
+                std::iter::once(*call)
+                    .chain(
+                        constraints
+                            .iter()
+                            .flat_map(|constraint| constraint.expressions()),
+                    )
+                    .collect(),
+            ),
+        };
+        Self {
+            kind: kind.to_string(),
+            text: print_expression(source_unit, expr_ref),
+            operands: operands
+                .into_iter()
+                .map(|operand| Self::new(source_unit, operand))
+                .collect(),
+        }
+    }
+}
+
+impl Module {
+    fn new(source_unit: &SourceUnit, item: &ModuleItem) -> Option<Self> {
+        let ModuleItem::ModuleDeclaration {
+            name,
+            name_span,
+            ports,
+            items,
+            span,
+        } = item
+        else {
+            return None;
+        };
+        let arena = &source_unit.module_item_arena;
+        let ports = ports
+            .iter()
+            .map(|port| Port {
+                name: port.name.clone(),
+                name_span: port.name_span,
+                direction: port.direction.as_ref().map(|direction| {
+                    match direction {
+                        PortDirection::Input => "input",
+                        PortDirection::Output => "output",
+                        PortDirection::Inout => "inout",
+                    }
+                    .to_string()
+                }),
+                data_type: port.data_type.clone(),
+                interface: port.interface.clone(),
+                span: port.span,
+            })
+            .collect();
+        let assignments = items
+            .iter()
+            .filter_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::Assignment {
+                    target, expr, span, ..
+                } => Some(Assignment {
+                    target: Expression::new(source_unit, *target),
+                    value: Expression::new(source_unit, *expr),
+                    span: *span,
+                }),
+                _ => None,
+            })
+            .collect();
+        let instances = items
+            .iter()
+            .filter_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::ModuleInstantiation {
+                    module_name,
+                    instance_name,
+                    span,
+                    ..
+                } => Some(Instance {
+                    module_name: module_name.clone(),
+                    instance_name: instance_name.clone(),
+                    span: *span,
+                }),
+                _ => None,
+            })
+            .collect();
+        Some(Self {
+            name: name.clone(),
+            name_span: *name_span,
+            span: *span,
+            ports,
+            assignments,
+            instances,
+        })
+    }
+}
+
+impl ParseResult {
+    fn new(
+        parser: &SystemVerilogParser,
+        result: Result<SourceUnit, ParseError>,
+        text: &str,
+    ) -> Self {
+        let position = |offset| {
+            let position = offset_to_line_column(text, offset, PositionEncoding::Utf32);
+            (position.line, position.column)
+        };
+        match result {
+            Ok(source_unit) => {
+                let diagnostics = parser
+                    .analyze_semantics(&source_unit)
+                    .into_iter()
+                    .map(|error| Diagnostic {
+                        kind: "semantic".to_string(),
+                        message: error.message,
+                        span: Some(error.span),
+                        start: position(error.span.0),
+                        end: position(error.span.1),
+                    })
+                    .collect();
+                let modules = source_unit
+                    .items
+                    .iter()
+                    .filter_map(|item_ref| {
+                        Module::new(&source_unit, source_unit.module_item_arena.get(*item_ref))
+                    })
+                    .collect();
+                Self {
+                    parsed: true,
+                    modules,
+                    diagnostics,
+                }
+            }
+            Err(error) => {
+                let diagnostics = error
+                    .errors
+                    .into_iter()
+                    .map(|error| {
+                        let span = error.location.as_ref().and_then(|location| location.span);
+                        let (start, end) = match (&error.location, span) {
+                            (_, Some((start, end))) => (position(start), position(end)),
+                            (Some(location), None) => {
+                                let start = (location.line as u32, location.column as u32);
+                                (start, start)
+                            }
+                            (None, None) => ((0, 0), (0, 0)),
+                        };
+                        Diagnostic {
+                            kind: "syntax".to_string(),
+                            message: error.message,
+                            span,
+                            start,
+                            end,
+                        }
+                    })
+                    .collect();
+                Self {
+                    parsed: false,
+                    modules: Vec::new(),
+                    diagnostics,
+                }
+            }
+        }
+    }
+}
+
+/// Parse and analyze SystemVerilog source text
+///
+/// Includes are resolved relative to the current directory.
+#[pyfunction]
+fn parse_content(text: &str) -> ParseResult {
+    let parser = SystemVerilogParser::new(Vec::new(), HashMap::new());
+    let result = parser.parse_content(text);
+    ParseResult::new(&parser, result, text)
+}
+
+/// Parse and analyze a SystemVerilog file and the files it includes
+///
+/// Includes are searched for next to the file, then in `include_dirs`. A
+/// file that cannot be read gives a result with a single syntax diagnostic.
+#[pyfunction]
+#[pyo3(signature = (path, include_dirs = Vec::new()))]
+fn parse_file(path: PathBuf, include_dirs: Vec<PathBuf>) -> ParseResult {
+    let mut parser = SystemVerilogParser::new(include_dirs, HashMap::new());
+    let result = parser.parse_file(&path);
+    let text = std::fs::read_to_string(&path).unwrap_or_default();
+    ParseResult::new(&parser, result, &text)
+}
+
+#[pymodule]
+fn sv_parser(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(parse_content, module)?)?;
+    module.add_function(wrap_pyfunction!(parse_file, module)?)?;
+    module.add_class::<ParseResult>()?;
+    module.add_class::<Diagnostic>()?;
+    module.add_class::<Module>()?;
+    module.add_class::<Port>()?;
+    module.add_class::<Assignment>()?;
+    module.add_class::<Instance>()?;
+    module.add_class::<Expression>()?;
+    Ok(())
+}
@@ -0,0 +1,26 @@
+//! JavaScript bindings
+//!
+//! Exports the browser-facing API of [`sv_parser::web`] through
+//! `wasm-bindgen`. The parser is built without its `fs` feature, since
+//! `wasm32-unknown-unknown` has no file system:
+//!
+//! ```text
+//! wasm-pack build sv-parser-wasm
+//! ```
+
+use sv_parser::web;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+/// Parse and analyze `text`, and return its errors as a JSON array; see
+/// [`web::parse`]
+#[wasm_bindgen]
+pub fn parse(text: &str) -> String {
+    web::parse(text)
+}
+
+/// Reformat `text` in the layout of the pretty printer, or return the
+/// message of its first syntax error; see [`web::format`]
+#[wasm_bindgen]
+pub fn format(text: &str) -> Result<String, String> {
+    web::format(text)
+}
@@ -1,7 +1,26 @@
 #!/usr/bin/env rust
 use clap::Parser;
+use std::path::{Path, PathBuf};
 use std::process;
-use sv_parser::{parse_vcs_style_args, SystemVerilogParser};
+use std::time::{Duration, Instant};
+use sv_parser::deps;
+use sv_parser::doc;
+use sv_parser::explain;
+use sv_parser::library::{library_name, WORK_LIBRARY};
+use sv_parser::lint::builtin_rules;
+use sv_parser::metrics;
+use sv_parser::parser::is_header_file;
+use sv_parser::position::{offset_to_line_column, PositionEncoding};
+use sv_parser::printer;
+use sv_parser::refactor::extract_package;
+use sv_parser::stats::unit_name;
+use sv_parser::{
+    apply_edits, parse_vcs_style_args, ClassDiagnosticKind, ClassHierarchy, DepsFormat,
+    DesignLibraries, DuplicateDefinition, FileDependencies, LintDiagnostic, LintEngine, LintFile,
+    LintSelection, MetricsFormat, ModuleDoc, ModuleGraph, ModuleItemRef, ModuleMetrics,
+    ParameterOverride, ParseError, ParsedArgs, PhaseTimes, ProjectConfig, Provenance,
+    SemanticAnalyzer, SemanticError, SourceMap, SourceUnit, Stats, SystemVerilogParser, TextEdit,
+};
 
 #[derive(Parser)]
 #[command(name = "sv_parser")]
@@ -9,7 +28,7 @@ use sv_parser::{parse_vcs_style_args, SystemVerilogParser};
 #[command(version)]
 #[command(disable_help_flag = true)]
 struct Cli {
-    /// All arguments (mix of +incdir+ options and files)
+    /// All arguments (mix of +incdir+ options, lint options and files)
     #[arg(allow_hyphen_values = true)]
     args: Vec<String>,
 
@@ -18,7 +37,7 @@ struct Cli {
     help: Option<bool>,
 
     /// Verbose output (show parsed AST)
-    #[arg(short = 'v', long = "verbose")]
+    #[arg(long = "verbose")]
     verbose: bool,
 
     /// Only check syntax without elaboration
@@ -30,6 +49,148 @@ struct Cli {
     fail_fast: bool,
 }
 
+/// A file of the compilation unit, parsed and waiting to be analyzed
+struct ParsedFile<'a> {
+    path: &'a PathBuf,
+    result: Result<SourceUnit, ParseError>,
+    phase_times: PhaseTimes,
+    /// Definitions the file repeats with a different body
+    duplicates: Vec<DuplicateDefinition>,
+}
+
+/// What semantic analysis and the lint rules found in a parsed file
+#[derive(Default)]
+struct Analysis {
+    semantic_errors: Vec<SemanticError>,
+    item_timings: Vec<(ModuleItemRef, Duration)>,
+    semantic: Duration,
+    /// The text of the file, read for the lint rules
+    content: String,
+    lint_diagnostics: Vec<LintDiagnostic>,
+    lint: Duration,
+}
+
+/// Print the package of the declarations the files share, followed by the
+/// edits to each file as SystemVerilog comments
+fn print_package_extraction(name: &str, sources: &[(PathBuf, SourceUnit, String)]) {
+    let files: Vec<(&Path, &SourceUnit, &str)> = sources
+        .iter()
+        .map(|(path, ast, content)| (path.as_path(), ast, content.as_str()))
+        .collect();
+    let Some(extraction) = extract_package(name, &files) else {
+        eprintln!("No localparam is declared the same way in more than one module");
+        return;
+    };
+    print!("{}", extraction.package);
+    println!();
+    for (path, edit) in &extraction.edits {
+        let content = files
+            .iter()
+            .find(|(file, _, _)| *file == path)
+            .map_or("", |(_, _, content)| *content);
+        let position = offset_to_line_column(content, edit.span.0, PositionEncoding::Utf32);
+        let location = format!(
+            "{}:{}:{}",
+            path.display(),
+            position.line + 1,
+            position.column + 1
+        );
+        if edit.new_text.is_empty() {
+            let removed: String = content
+                .chars()
+                .skip(edit.span.0)
+                .take(edit.span.1 - edit.span.0)
+                .collect();
+            println!("// {}: delete '{}'", location, removed.trim());
+        } else {
+            println!("// {}: replace with '{}'", location, edit.new_text);
+        }
+    }
+}
+
+/// Print where the text at `span` comes from, when it was produced by a macro
+/// or is in a conditional branch
+fn print_provenance(source_map: &SourceMap, span: (usize, usize), indent: &str) {
+    for provenance in source_map.provenance(span) {
+        let (Provenance::Expansion { span, .. } | Provenance::Branch { span, .. }) = &provenance;
+        eprintln!("{}Note at {}:{}: {}", indent, span.0, span.1, provenance);
+    }
+}
+
+/// Rewrite a file with the fixes of its lint findings, and return the
+/// findings that are left: those without a fix, and those whose fix overlaps
+/// another and was skipped, which a second run can fix
+fn apply_lint_fixes(
+    file_path: &Path,
+    content: &str,
+    diagnostics: Vec<LintDiagnostic>,
+) -> Vec<LintDiagnostic> {
+    let (fixable, mut left): (Vec<_>, Vec<_>) = diagnostics
+        .into_iter()
+        .partition(|diagnostic| diagnostic.fix.is_some());
+    if fixable.is_empty() {
+        return left;
+    }
+    let fixes: Vec<TextEdit> = fixable
+        .iter()
+        .filter_map(|diagnostic| diagnostic.fix.clone())
+        .collect();
+    let (fixed, applied) = apply_edits(content, &fixes);
+    if let Err(err) = std::fs::write(file_path, fixed) {
+        eprintln!("Error writing {}: {}", file_path.display(), err);
+        left.extend(fixable);
+        return left;
+    }
+    eprintln!(
+        "Fixed {} lint finding(s) in {}",
+        applied.len(),
+        file_path.display()
+    );
+    left.extend(
+        fixable
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !applied.contains(index))
+            .map(|(_, diagnostic)| diagnostic),
+    );
+    left
+}
+
+/// Give the parameters overridden on the command line their new values in a
+/// parsed file, and mark in `found_modules` the overrides whose module it
+/// defines, whether or not the override succeeds
+///
+/// Returns false if an override names a parameter its module doesn't have.
+fn override_parameters(
+    file_path: &Path,
+    ast: &mut SourceUnit,
+    parameters: &[ParameterOverride],
+    found_modules: &mut [bool],
+) -> bool {
+    let mut ok = true;
+    for (parameter, found_module) in parameters.iter().zip(found_modules.iter_mut()) {
+        *found_module |= match parameter.apply(ast) {
+            Ok(found) => found,
+            // Only a module the file defines can lack the parameter
+            Err(err) => {
+                eprintln!("Error in {}: {}", file_path.display(), err);
+                ok = false;
+                true
+            }
+        };
+    }
+    ok
+}
+
+/// Apply the parsing options shared by every mode
+fn configure_parser(parser: &mut SystemVerilogParser, parsed_args: &ParsedArgs) {
+    parser.set_header_mode(parsed_args.header);
+    parser.set_parse_timeout(parsed_args.parse_timeout);
+    for (extension, version) in &parsed_args.extension_versions {
+        parser.set_extension_version(extension, *version);
+    }
+}
+
 fn main() {
     let cli_args = Cli::parse();
 
@@ -46,14 +207,55 @@ fn main() {
             eprintln!("Usage: sv-parser [OPTIONS] [+incdir+<path>]... [+define+<macro>[=<value>]]... <file>...");
             eprintln!();
             eprintln!("Options:");
-            eprintln!("  -v, --verbose        Verbose output (show parsed AST)");
+            eprintln!("      --verbose        Verbose output (show parsed AST and configuration bindings)");
             eprintln!("  -s, --syntax-only    Only check syntax without elaboration");
             eprintln!("      --fail-fast      Stop parsing after the first error");
+            eprintln!(
+                "      --lint[=<rules>] Run the default lint rules, or only the comma-separated <rules>"
+            );
+            eprintln!("      --no-lint        Disable lint rules (overrides an earlier --lint)");
+            eprintln!("      --fix            Rewrite the files with the fixes of the lint findings that have one");
+            eprintln!("      --list-rules     List available lint rules and exit");
+            eprintln!("      --explain <code> Explain a diagnostic code, like SV0002 or no-casex, and exit");
+            eprintln!(
+                "      --deps[=json|make] Print each file's includes and the units it defines and uses"
+            );
+            eprintln!("      --compile-order  Print the files reordered so definitions come before their users");
+            eprintln!(
+                "      --report metrics[=json|csv] Print the lines, statements, expressions, branches and instances of each module"
+            );
+            eprintln!(
+                "      --stats[=<n>]    Report time per phase and the <n> slowest files and units (default 5)"
+            );
+            eprintln!("      --graph <file>   Write the module and package graph as DOT, or JSON for a .json file");
+            eprintln!(
+                "      --graph-depth=<n> Only graph modules up to <n> levels below the top modules"
+            );
+            eprintln!("      --collapse-cells Graph modules that aren't defined in the sources as one node");
+            eprintln!("      --extract-package <name> Print a package of the localparams several modules repeat");
+            eprintln!("      --doc <dir>      Write a Markdown page per module, with its ports, parameters and hierarchy");
+            eprintln!("      --round-trip     Print each file, parse the output again and report where the trees differ");
+            eprintln!("      --header         Parse every file as a header (.svh and .vh files always are)");
+            eprintln!(
+                "      --parse-timeout=<seconds> Give up on a file that takes longer to parse"
+            );
+            eprintln!(
+                "      --param <module>.<name>=<value> Override a parameter of a top-level module"
+            );
             eprintln!("  -h, --help           Show this help message");
             eprintln!();
             eprintln!("VCS-style options:");
             eprintln!("  +incdir+<path>       Add include directory for `include directives");
             eprintln!("  +define+<macro>=<val> Define preprocessor macro");
+            eprintln!("  -v <file>            Library file; its modules form a library named after the file");
+            eprintln!("  -pvalue+<module>.<name>=<value> Same as --param");
+            eprintln!(
+                "  -sverilog            Parse .v and .vh files as SystemVerilog, not Verilog-2005"
+            );
+            eprintln!("  +systemverilogext+<ext>[+<ext>...] Parse files with these extensions as SystemVerilog");
+            eprintln!(
+                "  +verilog2001ext+<ext>, +verilog1995ext+<ext> Same for Verilog-2001 and 1995"
+            );
             eprintln!();
             eprintln!("Examples:");
             eprintln!("  sv-parser design.sv");
@@ -63,6 +265,68 @@ fn main() {
         }
     };
 
+    if parsed_args.list_rules {
+        for rule in builtin_rules() {
+            let default = if rule.enabled_by_default() {
+                ""
+            } else {
+                " (only when named)"
+            };
+            println!("{:<24} {}{}", rule.name(), rule.description(), default);
+        }
+        process::exit(0);
+    }
+
+    if let Some(code) = &parsed_args.explain {
+        match explain::explain(code) {
+            Some(page) => {
+                print!("{}", page);
+                process::exit(0);
+            }
+            None => {
+                eprintln!("Error: Unknown diagnostic code: {}", code);
+                eprintln!("Use --list-rules to see the lint rules; other codes look like SV0002");
+                process::exit(1);
+            }
+        }
+    }
+
+    let lint_engine = match &parsed_args.lint {
+        LintSelection::Disabled => None,
+        selection => {
+            let config = match std::env::current_dir()
+                .map_err(|err| err.to_string())
+                .and_then(|dir| ProjectConfig::find(&dir))
+            {
+                Ok(Some((path, config))) => {
+                    if parsed_args.verbose {
+                        eprintln!("Project configuration: {}", path.display());
+                    }
+                    config
+                }
+                Ok(None) => ProjectConfig::default(),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    process::exit(1);
+                }
+            };
+            let rules = match selection {
+                LintSelection::Only(rules) => Some(rules.as_slice()),
+                _ => None,
+            };
+            match LintEngine::configured(&config.lint, rules) {
+                Ok(engine) => Some(engine),
+                Err(err) => {
+                    eprintln!("Error: {}", err);
+                    if rules.is_some() {
+                        eprintln!("Use --list-rules to see the available rules");
+                    }
+                    process::exit(1);
+                }
+            }
+        }
+    };
+
     if parsed_args.verbose {
         if !parsed_args.include_dirs.is_empty() {
             eprintln!("Include directories: {:?}", parsed_args.include_dirs);
@@ -70,6 +334,12 @@ fn main() {
         if !parsed_args.defines.is_empty() {
             eprintln!("Macro defines: {:?}", parsed_args.defines);
         }
+        for parameter in &parsed_args.parameters {
+            eprintln!(
+                "Parameter override: {}.{}={}",
+                parameter.module, parameter.name, parameter.value
+            );
+        }
         eprintln!("Files to parse: {:?}", parsed_args.files);
     }
 
@@ -91,36 +361,349 @@ fn main() {
         }
     }
 
+    if parsed_args.deps.is_some() || parsed_args.compile_order {
+        let mut all_deps = Vec::new();
+
+        for file_path in &parsed_args.files {
+            let mut parser =
+                SystemVerilogParser::new(include_paths.clone(), initial_macros.clone());
+            configure_parser(&mut parser, &parsed_args);
+            match parser.parse_file(file_path) {
+                Ok(ast) => all_deps.push(FileDependencies::collect(
+                    file_path,
+                    parser.resolved_includes(),
+                    &ast,
+                )),
+                Err(parse_err) => {
+                    eprintln!("Error parsing {}: {}", file_path.display(), parse_err);
+                    had_errors = true;
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+
+        match parsed_args.deps {
+            Some(DepsFormat::Json) => println!("{}", deps::to_json(&all_deps)),
+            Some(DepsFormat::Make) => print!("{}", deps::to_makefile(&all_deps)),
+            None => {
+                let order = deps::compile_order(&all_deps);
+                if !order.cycles.is_empty() {
+                    eprintln!("Warning: dependency cycle, keeping the given order for:");
+                    for file in &order.cycles {
+                        eprintln!("  {}", file.display());
+                    }
+                }
+                for file in &order.files {
+                    println!("{}", file.display());
+                }
+            }
+        }
+        process::exit(if had_errors { 1 } else { 0 });
+    }
+
+    if let Some(format) = parsed_args.metrics {
+        let mut all_metrics = Vec::new();
+
+        for file_path in &parsed_args.files {
+            let mut parser =
+                SystemVerilogParser::new(include_paths.clone(), initial_macros.clone());
+            configure_parser(&mut parser, &parsed_args);
+            let parsed = parser.parse_file(file_path).map(|ast| {
+                let content = std::fs::read_to_string(file_path).unwrap_or_default();
+                ModuleMetrics::collect(file_path, &ast, &content)
+            });
+            match parsed {
+                Ok(metrics) => all_metrics.extend(metrics),
+                Err(parse_err) => {
+                    eprintln!("Error parsing {}: {}", file_path.display(), parse_err);
+                    had_errors = true;
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+
+        match format {
+            MetricsFormat::Json => println!("{}", metrics::to_json(&all_metrics)),
+            MetricsFormat::Csv => print!("{}", metrics::to_csv(&all_metrics)),
+        }
+        process::exit(if had_errors { 1 } else { 0 });
+    }
+
+    if let Some(dir) = &parsed_args.doc {
+        let mut modules = Vec::new();
+
+        for file_path in &parsed_args.files {
+            let mut parser =
+                SystemVerilogParser::new(include_paths.clone(), initial_macros.clone());
+            configure_parser(&mut parser, &parsed_args);
+            let parsed = parser.parse_file(file_path).map(|ast| {
+                let content = std::fs::read_to_string(file_path).unwrap_or_default();
+                ModuleDoc::collect(file_path, &ast, &content)
+            });
+            match parsed {
+                Ok(docs) => modules.extend(docs),
+                Err(parse_err) => {
+                    eprintln!("Error parsing {}: {}", file_path.display(), parse_err);
+                    had_errors = true;
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+
+        if let Err(err) = std::fs::create_dir_all(dir) {
+            eprintln!("Error creating {}: {}", dir.display(), err);
+            process::exit(1);
+        }
+        for module in &modules {
+            let page = dir.join(module.page_name());
+            match std::fs::write(&page, doc::to_markdown(module, &modules)) {
+                Ok(()) => println!("{}", page.display()),
+                Err(err) => {
+                    eprintln!("Error writing {}: {}", page.display(), err);
+                    had_errors = true;
+                }
+            }
+        }
+        process::exit(if had_errors { 1 } else { 0 });
+    }
+
+    if parsed_args.round_trip {
+        for file_path in &parsed_args.files {
+            let mut parser =
+                SystemVerilogParser::new(include_paths.clone(), initial_macros.clone());
+            configure_parser(&mut parser, &parsed_args);
+            parser.set_language_version(parser.language_version_for(file_path));
+            // Includes stay directives on both sides rather than being expanded
+            let header = parsed_args.header || is_header_file(file_path);
+            let parsed = std::fs::read_to_string(file_path)
+                .map_err(|err| err.to_string())
+                .and_then(|content| {
+                    if header {
+                        parser.parse_header_content(&content)
+                    } else {
+                        parser.parse_content(&content)
+                    }
+                    .map_err(|err| err.to_string())
+                });
+            let result = match parsed {
+                Ok(ast) => printer::round_trip(&parser, &ast, header),
+                Err(err) => {
+                    eprintln!("Error parsing {}: {}", file_path.display(), err);
+                    had_errors = true;
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
+                    continue;
+                }
+            };
+            match result {
+                Ok(()) => println!("{}: round trip OK", file_path.display()),
+                Err(err) => {
+                    println!("{}: round trip failed", file_path.display());
+                    for line in err.to_string().lines() {
+                        println!("  {}", line);
+                    }
+                    had_errors = true;
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
+                }
+            }
+        }
+        process::exit(if had_errors { 1 } else { 0 });
+    }
+
+    let mut stats = Stats::new();
+    let mut graph = ModuleGraph::new();
+    let mut classes = ClassHierarchy::new();
+    let mut libraries = DesignLibraries::new();
+    let mut package_sources = Vec::new();
+    let mut found_modules = vec![false; parsed_args.parameters.len()];
+    // All files form one compilation unit, so a header included by several
+    // of them is read once and conflicting redefinitions are caught
+    let mut parser = if parsed_args.fail_fast {
+        SystemVerilogParser::with_config(include_paths.clone(), initial_macros.clone(), true)
+    } else {
+        SystemVerilogParser::new(include_paths.clone(), initial_macros.clone())
+    };
+    configure_parser(&mut parser, &parsed_args);
+
+    // Files are parsed in order, since they share the compilation unit, then
+    // analyzed several at a time and reported in order again
+    let mut parsed_files = Vec::new();
     for file_path in &parsed_args.files {
         if parsed_args.verbose {
             eprintln!("Parsing file: {}", file_path.display());
         }
 
-        // Create a new parser instance for each file
-        let mut parser = if parsed_args.fail_fast {
-            SystemVerilogParser::with_config(include_paths.clone(), initial_macros.clone(), true)
-        } else {
-            SystemVerilogParser::new(include_paths.clone(), initial_macros.clone())
+        let result = parser.parse_file(file_path);
+        let timings = parser.timings();
+        let phase_times = PhaseTimes {
+            preprocess: timings.preprocess,
+            parse: timings.parse,
+            flatten: timings.flatten,
+            ..PhaseTimes::default()
         };
+        for (name, time) in &timings.units {
+            stats.record_unit(file_path, name, *time);
+        }
+
+        let result = result.map(|mut ast| {
+            if !override_parameters(
+                file_path,
+                &mut ast,
+                &parsed_args.parameters,
+                &mut found_modules,
+            ) {
+                had_errors = true;
+            }
+            graph.add_source_unit(file_path, &ast);
+            classes.add_source_unit(file_path, &ast);
+            libraries.add_source_unit(WORK_LIBRARY, file_path, &ast);
+            ast
+        });
+        let failed = result.is_err();
+        parsed_files.push(ParsedFile {
+            path: file_path,
+            result,
+            phase_times,
+            duplicates: parser.duplicate_definitions().to_vec(),
+        });
+        // Reporting stops at the file that doesn't parse
+        if failed && parsed_args.fail_fast {
+            break;
+        }
+    }
+
+    // Perform semantic analysis and linting unless only syntax was requested
+    let mut analyses = sv_parser::parallel::map(&parsed_files, |file| {
+        let mut analysis = Analysis::default();
+        let Ok(ast) = &file.result else {
+            return analysis;
+        };
+        if parsed_args.syntax_only {
+            return analysis;
+        }
+        let start = Instant::now();
+        let mut analyzer = SemanticAnalyzer::new();
+        analysis.semantic_errors = analyzer.analyze(ast);
+        analysis.semantic = start.elapsed();
+        analysis.item_timings = analyzer.item_timings().to_vec();
+        if let Some(engine) = &lint_engine {
+            let start = Instant::now();
+            analysis.content = std::fs::read_to_string(file.path).unwrap_or_default();
+            analysis.lint_diagnostics = engine.run_file(file.path, ast, &analysis.content);
+            analysis.lint = start.elapsed();
+        }
+        analysis
+    });
+    // Cross-file rules see every file that parsed
+    if let Some(engine) = lint_engine.as_ref().filter(|_| !parsed_args.syntax_only) {
+        let (lint_files, mut diagnostics): (Vec<LintFile>, Vec<Vec<LintDiagnostic>>) = parsed_files
+            .iter()
+            .zip(&mut analyses)
+            .filter_map(|(file, analysis)| {
+                let ast = file.result.as_ref().ok()?;
+                let lint_file = LintFile {
+                    path: file.path,
+                    source_unit: ast,
+                    text: &analysis.content,
+                };
+                Some((lint_file, std::mem::take(&mut analysis.lint_diagnostics)))
+            })
+            .unzip();
+        engine.run_cross_file(&lint_files, &mut diagnostics);
+        let mut diagnostics = diagnostics.into_iter();
+        for (file, analysis) in parsed_files.iter().zip(&mut analyses) {
+            if file.result.is_ok() {
+                analysis.lint_diagnostics = diagnostics.next().unwrap_or_default();
+            }
+        }
+    }
 
-        match parser.parse_file(file_path) {
+    for (file, analysis) in parsed_files.into_iter().zip(analyses) {
+        let file_path = file.path;
+        let mut phase_times = file.phase_times;
+        phase_times.semantic = analysis.semantic;
+        phase_times.lint = analysis.lint;
+        stats.record_file(file_path, phase_times);
+
+        match file.result {
             Ok(ast) => {
-                // Perform semantic analysis
-                let semantic_errors = parser.analyze_semantics(&ast);
+                for (item_ref, time) in &analysis.item_timings {
+                    if let Some(name) = unit_name(ast.module_item_arena.get(*item_ref)) {
+                        stats.record_unit(file_path, name, *time);
+                    }
+                }
+                let semantic_errors = analysis.semantic_errors;
+                let lint_diagnostics = if parsed_args.fix {
+                    apply_lint_fixes(file_path, &analysis.content, analysis.lint_diagnostics)
+                } else {
+                    analysis.lint_diagnostics
+                };
 
-                if !semantic_errors.is_empty() {
+                if let Some(engine) = lint_engine
+                    .as_ref()
+                    .filter(|_| !lint_diagnostics.is_empty())
+                {
+                    eprintln!("Lint findings in {}:", file_path.display());
+                    for diagnostic in &lint_diagnostics {
+                        eprintln!(
+                            "  {} at {}:{}: [{}] {}",
+                            engine.severity(diagnostic.rule),
+                            diagnostic.span.0,
+                            diagnostic.span.1,
+                            diagnostic.rule,
+                            diagnostic.message
+                        );
+                        print_provenance(&ast.source_map, diagnostic.span, "    ");
+                    }
+                    had_errors = true;
+                }
+
+                let duplicates = &file.duplicates;
+                for duplicate in duplicates {
+                    eprintln!("Error: {}", duplicate);
+                }
+
+                if !duplicates.is_empty() && semantic_errors.is_empty() {
+                    had_errors = true;
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
+                } else if !semantic_errors.is_empty() {
                     // Report semantic errors
                     eprintln!("Semantic errors in {}:", file_path.display());
                     for error in &semantic_errors {
                         eprintln!(
-                            "  Error at {}:{}: {}",
-                            error.span.0, error.span.1, error.message
+                            "  Error at {}:{}: [{}] {}",
+                            error.span.0,
+                            error.span.1,
+                            error.error_type.code(),
+                            error.message
                         );
+                        if let Some(related) = &error.related {
+                            eprintln!(
+                                "    Note at {}:{}: {}",
+                                related.span.0, related.span.1, related.message
+                            );
+                        }
+                        print_provenance(&ast.source_map, error.span, "    ");
                     }
                     had_errors = true;
                     if parsed_args.fail_fast {
                         process::exit(1);
                     }
+                } else if !lint_diagnostics.is_empty() {
+                    if parsed_args.fail_fast {
+                        process::exit(1);
+                    }
                 } else if parsed_args.verbose {
                     println!("Successfully parsed {}", file_path.display());
                     println!("AST: {:#?}", ast);
@@ -130,9 +713,22 @@ fn main() {
                         println!("{}: OK", file_path.display());
                     }
                 }
+
+                if parsed_args.extract_package.is_some() {
+                    if let Ok(content) = std::fs::read_to_string(file_path) {
+                        package_sources.push((file_path.clone(), ast, content));
+                    }
+                }
             }
             Err(parse_err) => {
                 eprintln!("Error parsing {}: {}", file_path.display(), parse_err);
+                for span in parse_err
+                    .errors
+                    .iter()
+                    .filter_map(|error| error.location.as_ref()?.span)
+                {
+                    print_provenance(&parse_err.source_map, span, "    ");
+                }
                 had_errors = true;
                 if parsed_args.fail_fast {
                     process::exit(1);
@@ -141,6 +737,105 @@ fn main() {
         }
     }
 
+    for (parameter, _) in parsed_args
+        .parameters
+        .iter()
+        .zip(&found_modules)
+        .filter(|(_, found)| !**found)
+    {
+        eprintln!(
+            "Error: cannot override {}.{}: no module '{}' in the sources",
+            parameter.module, parameter.name, parameter.module
+        );
+        had_errors = true;
+    }
+
+    // Library files only provide cells, so they are not analyzed themselves
+    for library_file in &parsed_args.library_files {
+        match parser.parse_file(library_file) {
+            Ok(ast) => libraries.add_source_unit(&library_name(library_file), library_file, &ast),
+            Err(parse_err) => {
+                eprintln!("Error parsing {}: {}", library_file.display(), parse_err);
+                had_errors = true;
+            }
+        }
+    }
+    for config in libraries.configs() {
+        let Some(hierarchy) = libraries.elaborate(&config.name) else {
+            continue;
+        };
+        for diagnostic in &hierarchy.diagnostics {
+            eprintln!(
+                "Error in {} at {}:{}: {}",
+                diagnostic.file.display(),
+                diagnostic.span.0,
+                diagnostic.span.1,
+                diagnostic.message
+            );
+            had_errors = true;
+        }
+        if parsed_args.verbose {
+            println!("Configuration {}:", config.name);
+            for binding in &hierarchy.bindings {
+                println!("  {} -> {}.{}", binding.path, binding.library, binding.cell);
+            }
+        }
+    }
+
+    // Classes may extend classes of other files, so overrides are checked
+    // once all files are parsed
+    if !parsed_args.syntax_only {
+        for diagnostic in classes.check() {
+            let severity = if diagnostic.kind == ClassDiagnosticKind::NonVirtualOverride {
+                "Warning"
+            } else {
+                had_errors = true;
+                "Error"
+            };
+            eprintln!(
+                "{} in {} at {}:{}: [{}] {}",
+                severity,
+                diagnostic.file.display(),
+                diagnostic.span.0,
+                diagnostic.span.1,
+                diagnostic.kind.code(),
+                diagnostic.message
+            );
+            if let Some((file, span)) = &diagnostic.related {
+                eprintln!(
+                    "  Note at {}:{}:{}: {}",
+                    file.display(),
+                    span.0,
+                    span.1,
+                    diagnostic.kind.related_note()
+                );
+            }
+        }
+    }
+
+    if let Some(name) = &parsed_args.extract_package {
+        print_package_extraction(name, &package_sources);
+    }
+
+    if let Some(graph_file) = &parsed_args.graph {
+        let output = if graph_file
+            .extension()
+            .is_some_and(|extension| extension == "json")
+        {
+            graph.to_json(&parsed_args.graph_options)
+        } else {
+            graph.to_dot(&parsed_args.graph_options)
+        };
+        if let Err(err) = std::fs::write(graph_file, output) {
+            eprintln!("Error writing {}: {}", graph_file.display(), err);
+            had_errors = true;
+        }
+    }
+
+    if let Some(slowest) = parsed_args.stats {
+        eprint!("{}", stats.report(slowest));
+    }
+
     if had_errors {
         process::exit(1);
     } else {
@@ -0,0 +1,486 @@
+//! Class inheritance across files
+//!
+//! [`ClassHierarchy`] records every class of the parsed files with its base
+//! class and method signatures, answers base/derived queries for navigation,
+//! and checks method overrides: an override of a virtual method must keep its
+//! signature, and redefining a method that is not virtual only hides it,
+//! which is usually a mistake. It also records the interfaces of the design,
+//! so that the interface classes a class implements and the interfaces its
+//! virtual interface handles name can be checked across files.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::{ClassItem, DataType, ModuleItem, ModuleItemArena, ModuleItemRef, SourceUnit, Span};
+
+/// A method as far as overriding is concerned
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodSignature {
+    pub name: String,
+    pub name_span: Span,
+    pub is_virtual: bool,
+    /// A `pure virtual` prototype, which classes that are not virtual
+    /// themselves have to implement
+    pub is_pure: bool,
+    /// None for void
+    pub return_type: Option<String>,
+    /// Argument names; their types are not recorded
+    pub parameters: Vec<String>,
+}
+
+impl MethodSignature {
+    /// `function int get(a, b)`
+    pub fn to_prototype(&self) -> String {
+        format!(
+            "function {} {}({})",
+            self.return_type.as_deref().unwrap_or("void"),
+            self.name,
+            self.parameters.join(", ")
+        )
+    }
+}
+
+/// Whether a method named `name` is a function with a return type, rather
+/// than a void function or the constructor
+pub fn returns_value(name: &str, return_type: Option<&str>) -> bool {
+    name != "new" && !matches!(return_type, None | Some("void"))
+}
+
+/// A class and where it is defined
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassNode {
+    pub name: String,
+    pub name_span: Span,
+    pub file: PathBuf,
+    /// An `interface class`
+    pub is_interface: bool,
+    /// A `virtual class`, which may leave pure virtual methods unimplemented
+    pub is_virtual: bool,
+    pub extends: Option<String>,
+    /// Interface classes the class implements, or that an interface class extends
+    pub implements: Vec<String>,
+    pub methods: Vec<MethodSignature>,
+}
+
+impl ClassNode {
+    fn method(&self, name: &str) -> Option<&MethodSignature> {
+        self.methods.iter().find(|method| method.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassDiagnosticKind {
+    /// An override of a virtual method with a different return type or arguments
+    SignatureMismatch,
+    /// A method with the name of a base class method that is not virtual
+    NonVirtualOverride,
+    /// A class that is its own ancestor
+    InheritanceCycle,
+    /// An `implements` or interface class `extends` clause naming an
+    /// interface class no file declares
+    UnknownInterfaceClass,
+    /// A virtual interface handle of an interface no file declares
+    UnknownInterface,
+    /// A class that is not virtual leaving a pure virtual method of a base
+    /// class or interface class unimplemented
+    UnimplementedMethod,
+}
+
+impl ClassDiagnosticKind {
+    /// Every kind of problem, in declaration order
+    pub const ALL: [ClassDiagnosticKind; 6] = [
+        ClassDiagnosticKind::SignatureMismatch,
+        ClassDiagnosticKind::NonVirtualOverride,
+        ClassDiagnosticKind::InheritanceCycle,
+        ClassDiagnosticKind::UnknownInterfaceClass,
+        ClassDiagnosticKind::UnknownInterface,
+        ClassDiagnosticKind::UnimplementedMethod,
+    ];
+
+    /// Stable code of the problem, like `SV0101`, which `--explain` describes
+    pub fn code(self) -> &'static str {
+        match self {
+            ClassDiagnosticKind::SignatureMismatch => "SV0101",
+            ClassDiagnosticKind::NonVirtualOverride => "SV0102",
+            ClassDiagnosticKind::InheritanceCycle => "SV0103",
+            ClassDiagnosticKind::UnknownInterfaceClass => "SV0104",
+            ClassDiagnosticKind::UnknownInterface => "SV0105",
+            ClassDiagnosticKind::UnimplementedMethod => "SV0106",
+        }
+    }
+
+    /// What the related location of the problem is
+    pub fn related_note(self) -> &'static str {
+        match self {
+            ClassDiagnosticKind::UnimplementedMethod => "pure virtual method",
+            _ => "overridden method",
+        }
+    }
+}
+
+/// A problem found by [`ClassHierarchy::check`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassDiagnostic {
+    pub kind: ClassDiagnosticKind,
+    pub file: PathBuf,
+    pub span: Span,
+    pub message: String,
+    /// The base class method involved, and its file
+    pub related: Option<(PathBuf, Span)>,
+}
+
+/// A virtual interface handle: `virtual bus_if.monitor vif;`
+#[derive(Debug, Clone, PartialEq)]
+struct InterfaceHandle {
+    interface: String,
+    file: PathBuf,
+    span: Span,
+}
+
+/// Classes of the design, by name, with the interfaces of the design and
+/// the virtual interface handles of its classes and modules
+#[derive(Debug, Clone, Default)]
+pub struct ClassHierarchy {
+    classes: BTreeMap<String, ClassNode>,
+    interfaces: BTreeSet<String>,
+    handles: Vec<InterfaceHandle>,
+}
+
+impl ClassHierarchy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the classes defined in a parsed file, including those inside modules
+    pub fn add_source_unit(&mut self, file: &Path, source_unit: &SourceUnit) {
+        self.add_items(file, &source_unit.module_item_arena, &source_unit.items);
+    }
+
+    fn add_items(&mut self, file: &Path, arena: &ModuleItemArena, items: &[ModuleItemRef]) {
+        for item_ref in items {
+            match arena.get(*item_ref) {
+                ModuleItem::ClassDeclaration {
+                    is_virtual,
+                    name,
+                    name_span,
+                    extends,
+                    implements,
+                    items,
+                    ..
+                } => {
+                    self.add_handles(file, items);
+                    self.classes.insert(
+                        name.clone(),
+                        ClassNode {
+                            name: name.clone(),
+                            name_span: *name_span,
+                            file: file.to_path_buf(),
+                            is_interface: false,
+                            is_virtual: *is_virtual,
+                            extends: extends.clone(),
+                            implements: implements.clone(),
+                            methods: methods(items),
+                        },
+                    );
+                }
+                ModuleItem::InterfaceClassDeclaration {
+                    name,
+                    name_span,
+                    extends,
+                    items,
+                    ..
+                } => {
+                    self.classes.insert(
+                        name.clone(),
+                        ClassNode {
+                            name: name.clone(),
+                            name_span: *name_span,
+                            file: file.to_path_buf(),
+                            is_interface: true,
+                            is_virtual: true,
+                            extends: None,
+                            implements: extends.clone(),
+                            methods: methods(items),
+                        },
+                    );
+                }
+                ModuleItem::InterfaceDeclaration { name, items, .. } => {
+                    self.interfaces.insert(name.clone());
+                    self.add_items(file, arena, items);
+                }
+                ModuleItem::VariableDeclaration {
+                    data_type: DataType::Named(data_type),
+                    name_span,
+                    ..
+                } => {
+                    if let Some(interface) = virtual_interface(data_type) {
+                        self.handles.push(InterfaceHandle {
+                            interface: interface.to_string(),
+                            file: file.to_path_buf(),
+                            span: *name_span,
+                        });
+                    }
+                }
+                ModuleItem::ModuleDeclaration { items, .. }
+                | ModuleItem::PackageDeclaration { items, .. } => {
+                    self.add_items(file, arena, items)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record the virtual interface handles among the properties of a class
+    fn add_handles(&mut self, file: &Path, items: &[ClassItem]) {
+        for item in items {
+            if let ClassItem::Property {
+                data_type: DataType::Named(data_type),
+                name_span,
+                ..
+            } = item
+            {
+                if let Some(interface) = virtual_interface(data_type) {
+                    self.handles.push(InterfaceHandle {
+                        interface: interface.to_string(),
+                        file: file.to_path_buf(),
+                        span: *name_span,
+                    });
+                }
+            }
+        }
+    }
+
+    pub fn class(&self, name: &str) -> Option<&ClassNode> {
+        self.classes.get(name)
+    }
+
+    /// The class `name` extends, if it is defined
+    pub fn base(&self, name: &str) -> Option<&ClassNode> {
+        self.classes
+            .get(self.classes.get(name)?.extends.as_deref()?)
+    }
+
+    /// Classes that extend `name` directly, sorted by name
+    pub fn derived(&self, name: &str) -> Vec<&ClassNode> {
+        self.classes
+            .values()
+            .filter(|class| class.extends.as_deref() == Some(name))
+            .collect()
+    }
+
+    /// Base classes of `name`, nearest first, stopping at an undefined class or a cycle
+    pub fn ancestors(&self, name: &str) -> Vec<&ClassNode> {
+        let mut seen = HashSet::from([name]);
+        let mut ancestors = Vec::new();
+        let mut current = name;
+        while let Some(base) = self.base(current) {
+            if !seen.insert(base.name.as_str()) {
+                break;
+            }
+            ancestors.push(base);
+            current = &base.name;
+        }
+        ancestors
+    }
+
+    /// Check method overrides and inheritance cycles in every class
+    ///
+    /// A method is virtual if any base class declares it virtual. Constructors
+    /// (`new`) are not overrides.
+    pub fn check(&self) -> Vec<ClassDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for class in self.classes.values() {
+            let ancestors = self.ancestors(&class.name);
+            let last = ancestors.last().copied().unwrap_or(class);
+            // The walk stops where it comes back to a class already seen
+            if self
+                .base(&last.name)
+                .is_some_and(|base| base.name == class.name)
+            {
+                diagnostics.push(ClassDiagnostic {
+                    kind: ClassDiagnosticKind::InheritanceCycle,
+                    file: class.file.clone(),
+                    span: class.name_span,
+                    message: format!("Class '{}' inherits from itself", class.name),
+                    related: None,
+                });
+                continue;
+            }
+
+            for method in class.methods.iter().filter(|method| method.name != "new") {
+                let overridden: Vec<(&ClassNode, &MethodSignature)> = ancestors
+                    .iter()
+                    .filter_map(|base| Some((*base, base.method(&method.name)?)))
+                    .collect();
+                let Some((base, base_method)) = overridden.first() else {
+                    continue;
+                };
+                let related = Some((base.file.clone(), base_method.name_span));
+
+                if !overridden.iter().any(|(_, method)| method.is_virtual) {
+                    diagnostics.push(ClassDiagnostic {
+                        kind: ClassDiagnosticKind::NonVirtualOverride,
+                        file: class.file.clone(),
+                        span: method.name_span,
+                        message: format!(
+                            "'{}::{}' hides the non-virtual method '{}::{}'",
+                            class.name, method.name, base.name, base_method.name
+                        ),
+                        related,
+                    });
+                } else if method.return_type != base_method.return_type
+                    || method.parameters != base_method.parameters
+                {
+                    diagnostics.push(ClassDiagnostic {
+                        kind: ClassDiagnosticKind::SignatureMismatch,
+                        file: class.file.clone(),
+                        span: method.name_span,
+                        message: format!(
+                            "'{}::{}' does not match the virtual method it overrides: expected '{}', found '{}'",
+                            class.name,
+                            method.name,
+                            base_method.to_prototype(),
+                            method.to_prototype()
+                        ),
+                        related,
+                    });
+                }
+            }
+        }
+        diagnostics.extend(self.check_interfaces());
+        diagnostics
+    }
+
+    /// Check the interface classes classes implement, the interfaces of
+    /// virtual interface handles, and that every class that is not virtual
+    /// implements the pure virtual methods it inherits
+    fn check_interfaces(&self) -> Vec<ClassDiagnostic> {
+        let mut diagnostics = Vec::new();
+        for class in self.classes.values() {
+            for interface in &class.implements {
+                if !self.classes.contains_key(interface) {
+                    diagnostics.push(ClassDiagnostic {
+                        kind: ClassDiagnosticKind::UnknownInterfaceClass,
+                        file: class.file.clone(),
+                        span: class.name_span,
+                        message: format!(
+                            "Class '{}' names the interface class '{}', which is not declared",
+                            class.name, interface
+                        ),
+                        related: None,
+                    });
+                }
+            }
+            if class.is_virtual {
+                continue;
+            }
+            for (owner, method) in self.unimplemented_methods(class) {
+                diagnostics.push(ClassDiagnostic {
+                    kind: ClassDiagnosticKind::UnimplementedMethod,
+                    file: class.file.clone(),
+                    span: class.name_span,
+                    message: format!(
+                        "Class '{}' does not implement the pure virtual method '{}::{}'",
+                        class.name, owner.name, method.name
+                    ),
+                    related: Some((owner.file.clone(), method.name_span)),
+                });
+            }
+        }
+        for handle in &self.handles {
+            if !self.interfaces.contains(&handle.interface) {
+                diagnostics.push(ClassDiagnostic {
+                    kind: ClassDiagnosticKind::UnknownInterface,
+                    file: handle.file.clone(),
+                    span: handle.span,
+                    message: format!(
+                        "Virtual interface of '{}', which is not declared",
+                        handle.interface
+                    ),
+                    related: None,
+                });
+            }
+        }
+        diagnostics
+    }
+
+    /// Pure virtual methods of the base classes and interface classes of
+    /// `class` that neither it nor a base class implements, with the class
+    /// or interface class declaring each, nearest first
+    fn unimplemented_methods<'a>(
+        &'a self,
+        class: &'a ClassNode,
+    ) -> Vec<(&'a ClassNode, &'a MethodSignature)> {
+        let lineage: Vec<&ClassNode> = std::iter::once(class)
+            .chain(self.ancestors(&class.name))
+            .collect();
+        let implemented: HashSet<&str> = lineage
+            .iter()
+            .flat_map(|class| &class.methods)
+            .filter(|method| !method.is_pure)
+            .map(|method| method.name.as_str())
+            .collect();
+
+        // The interface classes implemented along the way, and the ones they extend
+        let mut pending: Vec<&str> = lineage
+            .iter()
+            .flat_map(|class| &class.implements)
+            .map(String::as_str)
+            .collect();
+        let mut seen = HashSet::new();
+        let mut owners = lineage;
+        while let Some(name) = pending.pop() {
+            let Some(interface) = self.classes.get(name) else {
+                continue;
+            };
+            if seen.insert(name) {
+                pending.extend(interface.implements.iter().map(String::as_str));
+                owners.push(interface);
+            }
+        }
+
+        let mut reported = HashSet::new();
+        owners
+            .into_iter()
+            .flat_map(|owner| owner.methods.iter().map(move |method| (owner, method)))
+            .filter(|(_, method)| {
+                method.is_pure
+                    && !implemented.contains(method.name.as_str())
+                    && reported.insert(method.name.as_str())
+            })
+            .collect()
+    }
+}
+
+/// The signatures of the methods among class items
+fn methods(items: &[ClassItem]) -> Vec<MethodSignature> {
+    items
+        .iter()
+        .filter_map(|item| match item {
+            ClassItem::Method {
+                is_virtual,
+                is_pure,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                ..
+            } => Some(MethodSignature {
+                name: name.clone(),
+                name_span: *name_span,
+                is_virtual: *is_virtual,
+                is_pure: *is_pure,
+                return_type: return_type.clone(),
+                parameters: parameters.clone(),
+            }),
+            ClassItem::Property { .. } | ClassItem::Constraint(_) => None,
+        })
+        .collect()
+}
+
+/// The interface of a virtual interface type, as its text reads:
+/// `virtual bus_if` or `virtual bus_if.monitor`
+fn virtual_interface(data_type: &str) -> Option<&str> {
+    let interface = data_type.strip_prefix("virtual ")?;
+    Some(interface.split('.').next().unwrap_or(interface))
+}
@@ -1,15 +1,197 @@
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::graph::GraphOptions;
+use crate::lexer::KeywordVersion;
+use crate::{Expression, ModuleItem, NumberLiteral, SourceUnit};
+
+/// Which lint rules to run after semantic analysis
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum LintSelection {
+    /// No lint rules (the default)
+    #[default]
+    Disabled,
+    /// Every rule enabled by default (`--lint`)
+    All,
+    /// Only the named rules (`--lint=rule1,rule2`)
+    Only(Vec<String>),
+}
+
+/// Number of slowest files and design units listed by `--stats` without a count
+pub const DEFAULT_STATS_SLOWEST: usize = 5;
+
+/// Output format for `--deps`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepsFormat {
+    /// JSON array with one object per file (`--deps` or `--deps=json`)
+    Json,
+    /// Makefile dependency rules (`--deps=make`)
+    Make,
+}
+
+/// Output format for `--report metrics`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    /// JSON array with one object per module (`--report metrics` or `--report metrics=json`)
+    Json,
+    /// CSV with one row per module (`--report metrics=csv`)
+    Csv,
+}
+
+/// A new value for a parameter of a top-level module (`--param top.WIDTH=64`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterOverride {
+    pub module: String,
+    pub name: String,
+    /// Integer literal, as written
+    pub value: String,
+}
+
+impl ParameterOverride {
+    /// Parse `<module>.<parameter>=<value>`
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let usage = || {
+            format!(
+                "Invalid parameter override: {} (expected <module>.<parameter>=<value>)",
+                text
+            )
+        };
+        let (path, value) = text.split_once('=').ok_or_else(usage)?;
+        let (module, name) = path.split_once('.').ok_or_else(usage)?;
+        if module.is_empty() || name.is_empty() || value.is_empty() {
+            return Err(usage());
+        }
+        if name.contains('.') {
+            return Err(format!(
+                "Only parameters of top-level modules can be overridden: {}",
+                path
+            ));
+        }
+        if NumberLiteral::parse(value).is_none() {
+            return Err(format!(
+                "Invalid value for parameter {}: {} (expected an integer literal)",
+                path, value
+            ));
+        }
+        Ok(Self {
+            module: module.to_string(),
+            name: name.to_string(),
+            value: value.to_string(),
+        })
+    }
+
+    /// Give the parameter its new value in a parsed file, as a `defparam`
+    /// would, so that everything evaluating it afterwards sees that value
+    ///
+    /// Returns false when the file doesn't define the module, and an error
+    /// when the module has no such parameter or it is a `localparam`.
+    pub fn apply(&self, source_unit: &mut SourceUnit) -> Result<bool, String> {
+        let arena = &source_unit.module_item_arena;
+        let Some(items) =
+            source_unit
+                .items
+                .iter()
+                .find_map(|item_ref| match arena.get(*item_ref) {
+                    ModuleItem::ModuleDeclaration { name, items, .. } if *name == self.module => {
+                        Some(items)
+                    }
+                    _ => None,
+                })
+        else {
+            return Ok(false);
+        };
+
+        let parameter = items
+            .iter()
+            .find_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::ParameterDeclaration {
+                    local,
+                    name,
+                    value,
+                    span,
+                    ..
+                } if *name == self.name => Some((*local, *value, *span)),
+                _ => None,
+            });
+        match parameter {
+            // The new value points at the declaration it replaces
+            Some((false, value, span)) => {
+                *source_unit.expr_arena.get_mut(value) =
+                    Expression::Number(self.value.clone(), span);
+                Ok(true)
+            }
+            Some((true, ..)) => Err(format!(
+                "Cannot override {}.{}: it is a localparam",
+                self.module, self.name
+            )),
+            None => Err(format!(
+                "Module '{}' has no parameter '{}'",
+                self.module, self.name
+            )),
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct ParsedArgs {
     pub files: Vec<PathBuf>,
+    /// Library files (`-v <file>`), each compiled into a library named after
+    /// the file, where the sources and configurations look for cells
+    pub library_files: Vec<PathBuf>,
     pub include_dirs: Vec<PathBuf>,
     pub defines: Vec<String>,
     pub verbose: bool,
     pub syntax_only: bool,
     pub fail_fast: bool,
+    pub lint: LintSelection,
+    /// Rewrite the files with the fixes of the lint findings that have one (`--fix`)
+    pub fix: bool,
+    pub list_rules: bool,
+    /// Print the page of this diagnostic code, like `SV0002` or `no-casex`,
+    /// and exit (`--explain <code>`)
+    pub explain: Option<String>,
+    pub deps: Option<DepsFormat>,
+    pub compile_order: bool,
+    /// Print the size and complexity of each module (`--report metrics[=json|csv]`)
+    pub metrics: Option<MetricsFormat>,
+    /// Report phase timings and this many of the slowest files and design units (`--stats[=N]`)
+    pub stats: Option<usize>,
+    /// Parse every file as a header, accepting declarations outside modules (`--header`)
+    pub header: bool,
+    /// Write the module instantiation and package import graph to this file
+    /// (`--graph <file>`), as JSON if it ends in `.json` and as Graphviz DOT
+    /// otherwise
+    pub graph: Option<PathBuf>,
+    /// `--graph-depth=<n>` and `--collapse-cells`
+    pub graph_options: GraphOptions,
+    /// Print a package with this name holding the `localparam`s that several
+    /// modules repeat, and the edits that import it (`--extract-package <name>`)
+    pub extract_package: Option<String>,
+    /// Language version of files by extension, in the order to apply them:
+    /// `-sverilog` makes `.v` and `.vh` files SystemVerilog, and
+    /// `+systemverilogext+`, `+verilog2001ext+` and `+verilog1995ext+` name
+    /// extensions for each version
+    pub extension_versions: Vec<(String, KeywordVersion)>,
+    /// New values for parameters of top-level modules (`--param top.WIDTH=64`
+    /// or `-pvalue+top.WIDTH=64`)
+    pub parameters: Vec<ParameterOverride>,
+    /// Write a Markdown page per module into this directory (`--doc <dir>`)
+    pub doc: Option<PathBuf>,
+    /// Print each file, parse the printed text again and report where the
+    /// two trees differ (`--round-trip`)
+    pub round_trip: bool,
+    /// Give up on a file that takes longer than this to parse
+    /// (`--parse-timeout=<seconds>`)
+    pub parse_timeout: Option<Duration>,
 }
 
+/// `+<option>+.ext1+.ext2` options naming the file extensions of a language version
+const EXTENSION_OPTIONS: &[(&str, KeywordVersion)] = &[
+    ("+systemverilogext+", KeywordVersion::LATEST),
+    ("+verilog2001ext+", KeywordVersion::V1364_2001),
+    ("+verilog1995ext+", KeywordVersion::V1364_1995),
+];
+
 pub fn parse_vcs_style_args(
     raw_args: Vec<String>,
     verbose: bool,
@@ -17,10 +199,30 @@ pub fn parse_vcs_style_args(
     fail_fast: bool,
 ) -> Result<ParsedArgs, String> {
     let mut files = Vec::new();
+    let mut library_files = Vec::new();
     let mut include_dirs = Vec::new();
     let mut defines = Vec::new();
+    let mut lint = LintSelection::Disabled;
+    let mut fix = false;
+    let mut list_rules = false;
+    let mut explain = None;
+    let mut deps = None;
+    let mut compile_order = false;
+    let mut metrics = None;
+    let mut stats = None;
+    let mut header = false;
+    let mut graph = None;
+    let mut graph_options = GraphOptions::default();
+    let mut extract_package = None;
+    let mut sverilog = false;
+    let mut extension_versions = Vec::new();
+    let mut parameters = Vec::new();
+    let mut doc = None;
+    let mut round_trip = false;
+    let mut parse_timeout = None;
 
-    for arg in raw_args {
+    let mut raw_args = raw_args.into_iter();
+    while let Some(arg) = raw_args.next() {
         if let Some(incdir_path) = arg.strip_prefix("+incdir+") {
             if incdir_path.is_empty() {
                 return Err("Empty path in +incdir+ directive".to_string());
@@ -31,17 +233,184 @@ pub fn parse_vcs_style_args(
                 return Err("Empty define in +define+ directive".to_string());
             }
             defines.push(define_str.to_string());
+        } else if let Some((extensions, version)) =
+            EXTENSION_OPTIONS.iter().find_map(|(option, version)| {
+                arg.strip_prefix(option)
+                    .map(|extensions| (extensions, *version))
+            })
+        {
+            let extensions: Vec<&str> = extensions
+                .split('+')
+                .map(|extension| extension.trim_start_matches('.'))
+                .filter(|extension| !extension.is_empty())
+                .collect();
+            if extensions.is_empty() {
+                return Err(format!("No file extensions in {} option", arg));
+            }
+            for extension in extensions {
+                extension_versions.push((extension.to_string(), version));
+            }
+        } else if let Some(assignment) = arg.strip_prefix("-pvalue+") {
+            parameters.push(ParameterOverride::parse(assignment)?);
         } else if arg.starts_with('+') {
             // Other VCS-style options that we don't support yet
             eprintln!("Warning: Unsupported VCS option: {}", arg);
+        } else if arg == "--lint" {
+            lint = LintSelection::All;
+        } else if let Some(rules) = arg.strip_prefix("--lint=") {
+            let rules: Vec<String> = rules
+                .split(',')
+                .map(str::trim)
+                .filter(|rule| !rule.is_empty())
+                .map(str::to_string)
+                .collect();
+            if rules.is_empty() {
+                return Err("Empty rule list in --lint= option".to_string());
+            }
+            lint = LintSelection::Only(rules);
+        } else if arg == "--no-lint" {
+            lint = LintSelection::Disabled;
+        } else if arg == "--fix" {
+            fix = true;
+        } else if arg == "--list-rules" {
+            list_rules = true;
+        } else if arg == "--explain" {
+            match raw_args.next() {
+                Some(code) if !code.starts_with('-') => explain = Some(code),
+                _ => return Err("--explain needs a diagnostic code".to_string()),
+            }
+        } else if let Some(code) = arg.strip_prefix("--explain=") {
+            if code.is_empty() {
+                return Err("--explain needs a diagnostic code".to_string());
+            }
+            explain = Some(code.to_string());
+        } else if arg == "--stats" {
+            stats = Some(DEFAULT_STATS_SLOWEST);
+        } else if let Some(count) = arg.strip_prefix("--stats=") {
+            match count.parse() {
+                Ok(count) => stats = Some(count),
+                Err(_) => {
+                    return Err(format!(
+                        "Invalid --stats count: {} (expected a number)",
+                        count
+                    ))
+                }
+            }
+        } else if arg == "--graph" {
+            match raw_args.next() {
+                Some(file) if !file.starts_with('-') => graph = Some(PathBuf::from(file)),
+                _ => return Err("--graph needs an output file".to_string()),
+            }
+        } else if let Some(file) = arg.strip_prefix("--graph=") {
+            if file.is_empty() {
+                return Err("--graph needs an output file".to_string());
+            }
+            graph = Some(PathBuf::from(file));
+        } else if let Some(depth) = arg.strip_prefix("--graph-depth=") {
+            match depth.parse() {
+                Ok(depth) => graph_options.max_depth = Some(depth),
+                Err(_) => {
+                    return Err(format!(
+                        "Invalid --graph-depth: {} (expected a number)",
+                        depth
+                    ))
+                }
+            }
+        } else if arg == "--doc" {
+            match raw_args.next() {
+                Some(dir) if !dir.starts_with('-') => doc = Some(PathBuf::from(dir)),
+                _ => return Err("--doc needs an output directory".to_string()),
+            }
+        } else if let Some(dir) = arg.strip_prefix("--doc=") {
+            if dir.is_empty() {
+                return Err("--doc needs an output directory".to_string());
+            }
+            doc = Some(PathBuf::from(dir));
+        } else if arg == "--round-trip" {
+            round_trip = true;
+        } else if let Some(seconds) = arg.strip_prefix("--parse-timeout=") {
+            match seconds.parse::<f64>() {
+                Ok(seconds) if seconds.is_finite() && seconds > 0.0 => {
+                    parse_timeout = Some(Duration::from_secs_f64(seconds))
+                }
+                _ => {
+                    return Err(format!(
+                        "Invalid --parse-timeout: {} (expected a positive number of seconds)",
+                        seconds
+                    ))
+                }
+            }
+        } else if arg == "--collapse-cells" {
+            graph_options.collapse_library_cells = true;
+        } else if arg == "--extract-package" {
+            match raw_args.next() {
+                Some(name) if !name.starts_with('-') => extract_package = Some(name),
+                _ => return Err("--extract-package needs a package name".to_string()),
+            }
+        } else if let Some(name) = arg.strip_prefix("--extract-package=") {
+            if name.is_empty() {
+                return Err("--extract-package needs a package name".to_string());
+            }
+            extract_package = Some(name.to_string());
+        } else if arg == "--param" {
+            match raw_args.next() {
+                Some(assignment) if !assignment.starts_with('-') => {
+                    parameters.push(ParameterOverride::parse(&assignment)?)
+                }
+                _ => return Err("--param needs <module>.<parameter>=<value>".to_string()),
+            }
+        } else if let Some(assignment) = arg.strip_prefix("--param=") {
+            parameters.push(ParameterOverride::parse(assignment)?);
+        } else if arg == "-sverilog" {
+            sverilog = true;
+        } else if arg == "--header" {
+            header = true;
+        } else if arg == "--compile-order" {
+            compile_order = true;
+        } else if arg == "--deps" {
+            deps = Some(DepsFormat::Json);
+        } else if let Some(format) = arg.strip_prefix("--deps=") {
+            deps = Some(match format {
+                "json" => DepsFormat::Json,
+                "make" => DepsFormat::Make,
+                _ => {
+                    return Err(format!(
+                        "Unknown --deps format: {} (expected json or make)",
+                        format
+                    ))
+                }
+            });
+        } else if arg == "--report" || arg.starts_with("--report=") {
+            let report = match arg.strip_prefix("--report=") {
+                Some(report) => report.to_string(),
+                None => raw_args
+                    .next()
+                    .ok_or_else(|| "--report needs a report name".to_string())?,
+            };
+            let (name, format) = report.split_once('=').unwrap_or((&report, "json"));
+            if name != "metrics" {
+                return Err(format!("Unknown report: {} (expected metrics)", name));
+            }
+            metrics = Some(match format {
+                "json" => MetricsFormat::Json,
+                "csv" => MetricsFormat::Csv,
+                _ => {
+                    return Err(format!(
+                        "Unknown --report metrics format: {} (expected json or csv)",
+                        format
+                    ))
+                }
+            });
+        } else if arg == "-v" {
+            match raw_args.next() {
+                Some(file) if !file.starts_with('-') && !file.starts_with('+') => {
+                    library_files.push(PathBuf::from(file))
+                }
+                _ => return Err("-v needs a library file".to_string()),
+            }
         } else if arg.starts_with('-') {
             // Skip clap flags that might have been passed through
-            if arg == "-v"
-                || arg == "--verbose"
-                || arg == "-s"
-                || arg == "--syntax-only"
-                || arg == "--fail-fast"
-            {
+            if arg == "--verbose" || arg == "-s" || arg == "--syntax-only" || arg == "--fail-fast" {
                 continue;
             }
             return Err(format!("Unknown option: {}", arg));
@@ -51,16 +420,60 @@ pub fn parse_vcs_style_args(
         }
     }
 
-    if files.is_empty() {
+    if deps.is_some() && compile_order {
+        return Err("--deps and --compile-order cannot be used together".to_string());
+    }
+    if fix && lint == LintSelection::Disabled {
+        return Err("--fix needs --lint or --lint=<rules>".to_string());
+    }
+    if metrics.is_some() && (deps.is_some() || compile_order) {
+        return Err("--report cannot be used with --deps or --compile-order".to_string());
+    }
+    if doc.is_some() && (metrics.is_some() || deps.is_some() || compile_order) {
+        return Err("--doc cannot be used with --report, --deps or --compile-order".to_string());
+    }
+    if round_trip && (doc.is_some() || metrics.is_some() || deps.is_some() || compile_order) {
+        return Err(
+            "--round-trip cannot be used with --doc, --report, --deps or --compile-order"
+                .to_string(),
+        );
+    }
+
+    // Extensions named explicitly win over -sverilog, wherever it appears
+    if sverilog {
+        for extension in ["vh", "v"] {
+            extension_versions.insert(0, (extension.to_string(), KeywordVersion::LATEST));
+        }
+    }
+
+    if files.is_empty() && !list_rules && explain.is_none() {
         return Err("No input files specified".to_string());
     }
 
     Ok(ParsedArgs {
         files,
+        library_files,
         include_dirs,
         defines,
         verbose,
         syntax_only,
         fail_fast,
+        lint,
+        fix,
+        list_rules,
+        explain,
+        deps,
+        compile_order,
+        metrics,
+        stats,
+        header,
+        graph,
+        graph_options,
+        extract_package,
+        extension_versions,
+        parameters,
+        doc,
+        round_trip,
+        parse_timeout,
     })
 }
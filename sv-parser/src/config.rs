@@ -0,0 +1,120 @@
+//! Project configuration
+//!
+//! Settings that belong to a project rather than to one run of a tool live in
+//! [`CONFIG_FILE`] at the root of the project, such as the naming conventions
+//! of the `naming-*` lint rules, the layout checked by the style rules and
+//! the header every file must start with:
+//!
+//! ```toml
+//! [lint.naming]
+//! parameter = "^[A-Z][A-Z0-9_]*$"
+//! clock = "^clk(_[a-z0-9_]+)?$"
+//!
+//! [lint.style]
+//! max_line_length = 100
+//! trailing_whitespace = true
+//!
+//! [lint.file]
+//! header = "// Copyright \\(c\\) \\d{4} Acme"
+//! name_matches_unit = true
+//!
+//! [lint.severity]
+//! no-casex = "error"
+//! line-length = "info"
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::files;
+use crate::lint::LintSeverity;
+
+/// Name of the configuration file looked for in a project
+pub const CONFIG_FILE: &str = ".very.toml";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct ProjectConfig {
+    #[serde(default)]
+    pub lint: LintConfig,
+}
+
+/// The `[lint]` table
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct LintConfig {
+    #[serde(default)]
+    pub naming: NamingConfig,
+    #[serde(default)]
+    pub style: StyleConfig,
+    #[serde(default)]
+    pub file: FileConfig,
+    /// The `[lint.severity]` table: severities that replace the defaults of
+    /// the named rules
+    #[serde(default)]
+    pub severity: BTreeMap<String, LintSeverity>,
+}
+
+/// The `[lint.naming]` table: a regular expression each name of a kind of
+/// symbol must match; kinds left out keep their default pattern
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct NamingConfig {
+    pub module: Option<String>,
+    pub parameter: Option<String>,
+    pub signal: Option<String>,
+    pub clock: Option<String>,
+    pub reset: Option<String>,
+    pub class: Option<String>,
+}
+
+/// The `[lint.style]` table: the layout rules to run with `--lint`, and
+/// their settings
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct StyleConfig {
+    /// Longest line allowed, in characters (`line-length`)
+    pub max_line_length: Option<usize>,
+    /// Columns between tab stops, used to replace tabs with spaces (`no-tabs`)
+    pub tab_width: Option<usize>,
+    /// Flag whitespace at the end of lines (`trailing-whitespace`)
+    pub trailing_whitespace: Option<bool>,
+    /// Flag files not ending in a newline (`final-newline`)
+    pub final_newline: Option<bool>,
+}
+
+/// The `[lint.file]` table: what the header and name of each file must be
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FileConfig {
+    /// Regular expression the start of each file must match (`file-header`)
+    pub header: Option<String>,
+    /// Text `--fix` inserts at the start of a file without the header
+    pub header_template: Option<String>,
+    /// Flag files not named after the single design unit they define
+    /// (`file-name`)
+    pub name_matches_unit: Option<bool>,
+}
+
+impl ProjectConfig {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        toml::from_str(text).map_err(|err| err.message().to_string())
+    }
+
+    /// The configuration in `dir` or in the nearest directory above it that
+    /// has one, with the path of its file
+    pub fn find(dir: &Path) -> Result<Option<(PathBuf, Self)>, String> {
+        let Some(path) = dir
+            .ancestors()
+            .map(|dir| dir.join(CONFIG_FILE))
+            .find(|path| files::is_file(path))
+        else {
+            return Ok(None);
+        };
+        let text = files::read_to_string(&path)
+            .map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        let config =
+            Self::parse(&text).map_err(|err| format!("Invalid {}: {}", path.display(), err))?;
+        Ok(Some((path, config)))
+    }
+}
@@ -0,0 +1,336 @@
+//! Dependency analysis
+//!
+//! Collects, per source file, the files it includes and the design units it
+//! defines and references. Build systems use this (through `--deps`) to work
+//! out which files must be recompiled when another one changes, and in which
+//! order files need to be compiled.
+
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{ClassItem, ModuleItem, ModuleItemArena, ModuleItemRef, Port, SourceUnit};
+
+/// Built-in data types; any other type name in a declaration refers to a user-defined unit
+const BUILTIN_TYPES: &[&str] = &[
+    "bit", "byte", "enum", "int", "integer", "logic", "longint", "real", "realtime", "reg",
+    "shortint", "string", "struct", "supply0", "supply1", "time", "tri", "tri0", "tri1", "triand",
+    "trior", "trireg", "union", "uwire", "wand", "wire", "wor",
+];
+
+/// Kind of a design unit defined in a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DesignUnitKind {
+    Module,
+    Interface,
+    Package,
+    Checker,
+    Class,
+    #[serde(rename = "interface_class")]
+    InterfaceClass,
+}
+
+/// A design unit defined in a file
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DesignUnit {
+    pub name: String,
+    pub kind: DesignUnitKind,
+}
+
+/// Dependency information for a single source file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FileDependencies {
+    pub file: PathBuf,
+    /// Resolved include files, including nested includes, in the order they were found
+    pub includes: Vec<PathBuf>,
+    /// Design units defined in the file (or in the files it includes)
+    pub definitions: Vec<DesignUnit>,
+    /// Names of design units used by the file but not defined in it, sorted
+    pub references: Vec<String>,
+}
+
+impl FileDependencies {
+    /// Collect the dependencies of a parsed file
+    ///
+    /// `includes` are the include files resolved while parsing it, as returned by
+    /// [`SystemVerilogParser::resolved_includes`](crate::SystemVerilogParser::resolved_includes).
+    pub fn collect(file: &Path, includes: &[PathBuf], source_unit: &SourceUnit) -> Self {
+        let mut definitions = BTreeSet::new();
+        let mut types = BTreeSet::new();
+        let mut references = BTreeSet::new();
+        collect_items(
+            &source_unit.module_item_arena,
+            &source_unit.items,
+            &mut definitions,
+            &mut types,
+            &mut references,
+        );
+
+        for unit in &definitions {
+            references.remove(&unit.name);
+        }
+        // Types defined with `typedef` in the file are not design units
+        for name in &types {
+            references.remove(name);
+        }
+
+        Self {
+            file: file.to_path_buf(),
+            includes: includes.to_vec(),
+            definitions: definitions.into_iter().collect(),
+            references: references.into_iter().collect(),
+        }
+    }
+}
+
+fn collect_items(
+    arena: &ModuleItemArena,
+    items: &[ModuleItemRef],
+    definitions: &mut BTreeSet<DesignUnit>,
+    types: &mut BTreeSet<String>,
+    references: &mut BTreeSet<String>,
+) {
+    for item_ref in items {
+        match arena.get(*item_ref) {
+            ModuleItem::ModuleDeclaration {
+                name, ports, items, ..
+            } => {
+                definitions.insert(DesignUnit {
+                    name: name.clone(),
+                    kind: DesignUnitKind::Module,
+                });
+                add_port_references(ports, references);
+                collect_items(arena, items, definitions, types, references);
+            }
+            ModuleItem::InterfaceDeclaration {
+                name, ports, items, ..
+            } => {
+                definitions.insert(DesignUnit {
+                    name: name.clone(),
+                    kind: DesignUnitKind::Interface,
+                });
+                add_port_references(ports, references);
+                collect_items(arena, items, definitions, types, references);
+            }
+            ModuleItem::CheckerDeclaration {
+                name, ports, items, ..
+            } => {
+                definitions.insert(DesignUnit {
+                    name: name.clone(),
+                    kind: DesignUnitKind::Checker,
+                });
+                add_port_references(ports, references);
+                collect_items(arena, items, definitions, types, references);
+            }
+            ModuleItem::PackageDeclaration { name, items, .. } => {
+                definitions.insert(DesignUnit {
+                    name: name.clone(),
+                    kind: DesignUnitKind::Package,
+                });
+                collect_items(arena, items, definitions, types, references);
+            }
+            ModuleItem::ImportDeclaration { imports, .. } => {
+                references.extend(imports.iter().map(|import| import.package.clone()));
+            }
+            ModuleItem::ExportDeclaration { exports, .. } => {
+                references.extend(
+                    exports
+                        .iter()
+                        .filter(|export| export.package != "*")
+                        .map(|export| export.package.clone()),
+                );
+            }
+            ModuleItem::ClassDeclaration {
+                name,
+                extends,
+                implements,
+                items,
+                ..
+            } => {
+                definitions.insert(DesignUnit {
+                    name: name.clone(),
+                    kind: DesignUnitKind::Class,
+                });
+                references.extend(extends.iter().cloned());
+                references.extend(implements.iter().cloned());
+                collect_class_items(items, references);
+            }
+            ModuleItem::InterfaceClassDeclaration {
+                name,
+                extends,
+                items,
+                ..
+            } => {
+                definitions.insert(DesignUnit {
+                    name: name.clone(),
+                    kind: DesignUnitKind::InterfaceClass,
+                });
+                references.extend(extends.iter().cloned());
+                collect_class_items(items, references);
+            }
+            // The body of an extern method needs the class that declares it
+            ModuleItem::OutOfBlockMethod { class_name, .. } => {
+                references.insert(class_name.clone());
+            }
+            ModuleItem::ModuleInstantiation { module_name, .. } => {
+                references.insert(module_name.clone());
+            }
+            ModuleItem::VariableDeclaration { data_type, .. } => {
+                add_type_reference(data_type.name(), references);
+            }
+            ModuleItem::TypedefDeclaration {
+                data_type, name, ..
+            } => {
+                types.insert(name.clone());
+                add_type_reference(data_type.name(), references);
+            }
+            // Instances inside generate constructs are elaborated like any other
+            item => collect_items(
+                arena,
+                &item.generate_items(),
+                definitions,
+                types,
+                references,
+            ),
+        }
+    }
+}
+
+fn add_port_references(ports: &[Port], references: &mut BTreeSet<String>) {
+    // `bus_if.master m` refers to the interface `bus_if`; a generic `interface m` to none
+    for port in ports {
+        match &port.interface {
+            Some(interface) if interface != "interface" => {
+                references.insert(interface.clone());
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect_class_items(items: &[ClassItem], references: &mut BTreeSet<String>) {
+    for class_item in items {
+        if let ClassItem::Property { data_type, .. } = class_item {
+            add_type_reference(data_type.name(), references);
+        }
+    }
+}
+
+fn add_type_reference(data_type: &str, references: &mut BTreeSet<String>) {
+    // `virtual my_if.modport` refers to the interface `my_if`
+    if let Some(interface) = data_type.strip_prefix("virtual ") {
+        let interface = interface.split('.').next().unwrap_or(interface);
+        references.insert(interface.to_string());
+    } else if let Some((package, _)) = data_type.split_once("::") {
+        // `my_pkg::packet_t` refers to the package `my_pkg`
+        references.insert(package.to_string());
+    } else if !BUILTIN_TYPES.contains(&data_type) {
+        references.insert(data_type.to_string());
+    }
+}
+
+/// Render dependencies as a pretty-printed JSON array
+pub fn to_json(deps: &[FileDependencies]) -> String {
+    serde_json::to_string_pretty(deps).expect("dependency data is always serializable")
+}
+
+/// Render dependencies as Makefile rules
+///
+/// Each file depends on its includes and on the files (from `deps`) that define
+/// the units it references. References to units defined outside `deps` are left
+/// out, since there is no file to point at.
+pub fn to_makefile(deps: &[FileDependencies]) -> String {
+    let edges = file_edges(deps);
+
+    let mut output = String::new();
+    for (file_deps, prerequisites) in deps.iter().zip(edges) {
+        output.push_str(&make_escape(&file_deps.file));
+        output.push(':');
+        let includes = file_deps.includes.iter().map(PathBuf::as_path);
+        let files = prerequisites
+            .into_iter()
+            .map(|index| deps[index].file.as_path());
+        for prerequisite in includes.chain(files) {
+            output.push(' ');
+            output.push_str(&make_escape(prerequisite));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+/// Result of ordering a file list for compilation
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileOrder {
+    /// Files in an order where every file comes after the files defining the units it uses
+    pub files: Vec<PathBuf>,
+    /// Files that could not be ordered because they are part of (or depend on) a
+    /// dependency cycle. These are also at the end of `files`, in their original order.
+    pub cycles: Vec<PathBuf>,
+}
+
+/// Order files so that definitions come before their users
+///
+/// Files with no ordering constraint between them keep their relative order from
+/// `deps`, so an already-correct file list is returned unchanged.
+pub fn compile_order(deps: &[FileDependencies]) -> CompileOrder {
+    let edges = file_edges(deps);
+    let mut placed = vec![false; deps.len()];
+    let mut files = Vec::with_capacity(deps.len());
+
+    // Repeatedly take the first file whose prerequisites have all been placed.
+    // File lists are small enough that the quadratic scan does not matter, and
+    // it keeps the original order wherever possible.
+    while let Some(next) =
+        (0..deps.len()).find(|&i| !placed[i] && edges[i].iter().all(|&dep| placed[dep]))
+    {
+        placed[next] = true;
+        files.push(deps[next].file.clone());
+    }
+
+    let cycles: Vec<PathBuf> = deps
+        .iter()
+        .zip(&placed)
+        .filter(|(_, placed)| !**placed)
+        .map(|(file_deps, _)| file_deps.file.clone())
+        .collect();
+    files.extend(cycles.iter().cloned());
+
+    CompileOrder { files, cycles }
+}
+
+/// For each file, the indices of the other files defining the units it references
+fn file_edges(deps: &[FileDependencies]) -> Vec<Vec<usize>> {
+    let mut defining_file: HashMap<&str, usize> = HashMap::new();
+    for (index, file_deps) in deps.iter().enumerate() {
+        for unit in &file_deps.definitions {
+            defining_file.entry(unit.name.as_str()).or_insert(index);
+        }
+    }
+
+    deps.iter()
+        .enumerate()
+        .map(|(index, file_deps)| {
+            let mut edges = Vec::new();
+            for reference in &file_deps.references {
+                if let Some(&file) = defining_file.get(reference.as_str()) {
+                    if file != index && !edges.contains(&file) {
+                        edges.push(file);
+                    }
+                }
+            }
+            edges
+        })
+        .collect()
+}
+
+/// Escape a path for use in a Makefile rule
+fn make_escape(path: &Path) -> String {
+    path.display()
+        .to_string()
+        .replace('$', "$$")
+        .replace(' ', "\\ ")
+        .replace('#', "\\#")
+}
@@ -0,0 +1,365 @@
+//! Markdown documentation of modules
+//!
+//! With `--doc <dir>` the command line tool writes one Markdown page per
+//! module, with tables of its parameters and ports and the place of the
+//! module in the instantiation hierarchy. The comment lines right above a
+//! declaration, or a `//` comment after it on the same line, describe it.
+
+use std::path::{Path, PathBuf};
+
+use crate::position::{offset_to_line_column, PositionEncoding};
+use crate::{ModuleItem, ModuleItemRef, PortDirection, Range, SourceUnit, Span};
+
+/// Documentation of one module
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModuleDoc {
+    pub file: PathBuf,
+    pub name: String,
+    pub description: String,
+    /// Parameters that can be overridden; `localparam`s are left out
+    pub parameters: Vec<ParameterDoc>,
+    pub ports: Vec<PortDoc>,
+    /// Instance name and module of each instance, in source order
+    pub instances: Vec<(String, String)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParameterDoc {
+    pub name: String,
+    pub data_type: String,
+    /// Source text of the default value
+    pub default: String,
+    pub description: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PortDoc {
+    pub name: String,
+    /// `input`, `output` or `inout`; empty when the port is never declared
+    pub direction: String,
+    pub data_type: String,
+    /// Number of bits when the range is constant, the range itself otherwise
+    pub width: String,
+    pub description: String,
+}
+
+impl ModuleDoc {
+    /// Collect the documentation of every module in a parsed file, nested
+    /// modules included, in source order
+    ///
+    /// `content` is the text of the file, which the spans point into.
+    pub fn collect(file: &Path, source_unit: &SourceUnit, content: &str) -> Vec<Self> {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut docs = Vec::new();
+        let collector = Collector {
+            file,
+            source_unit,
+            content,
+            lines: &lines,
+        };
+        collector.modules(&source_unit.items, &mut docs);
+        docs
+    }
+
+    /// File name of the page of the module
+    pub fn page_name(&self) -> String {
+        format!("{}.md", self.name)
+    }
+}
+
+/// Render the page of `module`; `design` holds all the modules, from which
+/// the modules instantiating this one are found
+pub fn to_markdown(module: &ModuleDoc, design: &[ModuleDoc]) -> String {
+    let mut page = format!("# {}\n\n", module.name);
+    if !module.description.is_empty() {
+        page.push_str(&format!("{}\n\n", module.description));
+    }
+    page.push_str(&format!("Defined in `{}`.\n", module.file.display()));
+
+    if !module.parameters.is_empty() {
+        page.push_str("\n## Parameters\n\n");
+        page.push_str("| Name | Type | Default | Description |\n");
+        page.push_str("|------|------|---------|-------------|\n");
+        for parameter in &module.parameters {
+            page.push_str(&format!(
+                "| `{}` | {} | `{}` | {} |\n",
+                parameter.name,
+                table_cell(&parameter.data_type),
+                table_cell(&parameter.default),
+                table_cell(&parameter.description)
+            ));
+        }
+    }
+
+    if !module.ports.is_empty() {
+        page.push_str("\n## Ports\n\n");
+        page.push_str("| Name | Direction | Type | Width | Description |\n");
+        page.push_str("|------|-----------|------|-------|-------------|\n");
+        for port in &module.ports {
+            page.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                port.name,
+                port.direction,
+                table_cell(&port.data_type),
+                table_cell(&port.width),
+                table_cell(&port.description)
+            ));
+        }
+    }
+
+    let parents: Vec<(&str, &str)> = design
+        .iter()
+        .flat_map(|parent| {
+            parent
+                .instances
+                .iter()
+                .filter(|(_, child)| *child == module.name)
+                .map(|(instance, _)| (parent.name.as_str(), instance.as_str()))
+        })
+        .collect();
+    // One tree per place the module is instantiated, with its own instances below it
+    if !parents.is_empty() || !module.instances.is_empty() {
+        page.push_str("\n## Hierarchy\n\n```text\n");
+        let mut trees: Vec<(String, &str)> = parents
+            .iter()
+            .map(|(parent, instance)| {
+                (
+                    format!("{}\n└── {}: {}\n", parent, instance, module.name),
+                    "    ",
+                )
+            })
+            .collect();
+        if trees.is_empty() {
+            trees.push((format!("{}\n", module.name), ""));
+        }
+        for (tree, indent) in trees {
+            page.push_str(&tree);
+            for (index, (instance, child)) in module.instances.iter().enumerate() {
+                let branch = if index + 1 == module.instances.len() {
+                    "└──"
+                } else {
+                    "├──"
+                };
+                page.push_str(&format!("{}{} {}: {}\n", indent, branch, instance, child));
+            }
+        }
+        page.push_str("```\n");
+    }
+    page
+}
+
+/// Text safe to put in a Markdown table cell
+fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+struct Collector<'a> {
+    file: &'a Path,
+    source_unit: &'a SourceUnit,
+    content: &'a str,
+    lines: &'a [&'a str],
+}
+
+impl Collector<'_> {
+    fn modules(&self, items: &[ModuleItemRef], docs: &mut Vec<ModuleDoc>) {
+        for item_ref in items {
+            let ModuleItem::ModuleDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                ..
+            } = self.source_unit.module_item_arena.get(*item_ref)
+            else {
+                continue;
+            };
+            let mut module = ModuleDoc {
+                file: self.file.to_path_buf(),
+                name: name.clone(),
+                description: self.comment_above(self.line(name_span.0)),
+                ..ModuleDoc::default()
+            };
+
+            for port in ports {
+                let mut doc = PortDoc {
+                    name: port.name.clone(),
+                    direction: port.direction.as_ref().map_or("", direction).to_string(),
+                    data_type: port.data_type.clone().unwrap_or_default(),
+                    width: width(port.range.as_ref()),
+                    description: String::new(),
+                };
+                // A port sharing its line with what comes before it has no comment above
+                let line = self.line(port.span.0);
+                let starts_line = self.lines.get(line).is_some_and(|text| {
+                    text.chars()
+                        .take(self.column(port.span.0))
+                        .all(char::is_whitespace)
+                });
+                if starts_line {
+                    doc.description = self.comment_above(line);
+                }
+                if doc.description.is_empty() {
+                    doc.description = self.comment_after(port.span.1);
+                }
+                module.ports.push(doc);
+            }
+
+            for item_ref in items {
+                match self.source_unit.module_item_arena.get(*item_ref) {
+                    ModuleItem::ParameterDeclaration {
+                        local: false,
+                        data_type,
+                        range,
+                        name,
+                        name_span,
+                        span,
+                        ..
+                    } => {
+                        let declaration = self.text((name_span.1, span.1));
+                        let mut data_type = data_type.clone().unwrap_or_default();
+                        if let Some(range) = range {
+                            data_type = format!("{} [{}:{}]", data_type, range.msb, range.lsb)
+                                .trim()
+                                .to_string();
+                        }
+                        module.parameters.push(ParameterDoc {
+                            name: name.clone(),
+                            data_type,
+                            default: declaration
+                                .trim()
+                                .trim_start_matches('=')
+                                .trim_end_matches(';')
+                                .trim()
+                                .to_string(),
+                            description: self.describe(*name_span, span.1),
+                        });
+                    }
+                    // Non-ANSI ports get their direction and type from the body
+                    ModuleItem::PortDeclaration {
+                        direction: port_direction,
+                        port_type,
+                        name,
+                        name_span,
+                        span,
+                    } => {
+                        if let Some(port) = module.ports.iter_mut().find(|p| p.name == *name) {
+                            port.direction = direction(port_direction).to_string();
+                            port.data_type = port_type.clone();
+                            if port.description.is_empty() {
+                                port.description = self.describe(*name_span, span.1);
+                            }
+                        }
+                    }
+                    ModuleItem::ModuleInstantiation {
+                        module_name,
+                        instance_name,
+                        ..
+                    } => module
+                        .instances
+                        .push((instance_name.clone(), module_name.clone())),
+                    _ => {}
+                }
+            }
+
+            docs.push(module);
+            self.modules(items, docs);
+        }
+    }
+
+    /// Comment above the line of `name_span`, or after the declaration ending at `end`
+    fn describe(&self, name_span: Span, end: usize) -> String {
+        let description = self.comment_above(self.line(name_span.0));
+        if description.is_empty() {
+            self.comment_after(end)
+        } else {
+            description
+        }
+    }
+
+    /// The comment lines right above line `line`, without a blank line in between
+    fn comment_above(&self, line: usize) -> String {
+        let mut comment = Vec::new();
+        let mut in_block = false;
+        for text in self.lines[..line.min(self.lines.len())].iter().rev() {
+            let text = text.trim();
+            if in_block || text.ends_with("*/") {
+                in_block = !text.starts_with("/*");
+            } else if !text.starts_with("//") {
+                break;
+            }
+            comment.push(text);
+        }
+        comment.reverse();
+        comment_text(&comment)
+    }
+
+    /// A `//` comment following the declaration ending at `end` on its line
+    fn comment_after(&self, end: usize) -> String {
+        let Some(text) = self.lines.get(self.line(end)) else {
+            return String::new();
+        };
+        let rest: String = text.chars().skip(self.column(end)).collect();
+        let rest = rest.trim_start_matches(|c: char| c.is_whitespace() || ",;)".contains(c));
+        match rest.strip_prefix("//") {
+            Some(comment) => comment_text(&[comment]),
+            None => String::new(),
+        }
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        offset_to_line_column(self.content, offset, PositionEncoding::Utf32).line as usize
+    }
+
+    fn column(&self, offset: usize) -> usize {
+        offset_to_line_column(self.content, offset, PositionEncoding::Utf32).column as usize
+    }
+
+    fn text(&self, span: Span) -> String {
+        self.content
+            .chars()
+            .skip(span.0)
+            .take(span.1.saturating_sub(span.0))
+            .collect()
+    }
+}
+
+/// Text of comment lines without their markers, as one paragraph; lines
+/// without words, like banners, are dropped
+fn comment_text(lines: &[&str]) -> String {
+    lines
+        .iter()
+        .map(|line| {
+            line.trim()
+                .trim_start_matches('/')
+                .trim_start_matches(['*', '!', '<'])
+                .trim_end_matches('/')
+                .trim_end_matches('*')
+                .trim()
+        })
+        .filter(|line| line.chars().any(char::is_alphanumeric))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn direction(direction: &PortDirection) -> &'static str {
+    match direction {
+        PortDirection::Input => "input",
+        PortDirection::Output => "output",
+        PortDirection::Inout => "inout",
+    }
+}
+
+/// Width of a port: the number of bits of a constant range, the range itself
+/// otherwise, and 1 without a range
+fn width(range: Option<&Range>) -> String {
+    let Some(range) = range else {
+        return "1".to_string();
+    };
+    match (
+        range.msb.trim().parse::<i64>(),
+        range.lsb.trim().parse::<i64>(),
+    ) {
+        (Ok(msb), Ok(lsb)) => ((msb - lsb).abs() + 1).to_string(),
+        _ => format!("[{}:{}]", range.msb, range.lsb),
+    }
+}
@@ -0,0 +1,274 @@
+//! Pages explaining each diagnostic
+//!
+//! Every kind of diagnostic has a code: `SV00xx` for the semantic errors of
+//! a file, `SV01xx` for the problems of a class hierarchy found once all
+//! files are parsed, and the rule name, like `no-casex`, for a lint finding.
+//! [`explain`] builds a short Markdown page for a code from the metadata of
+//! its check, which is what `--explain` prints and what the pages under
+//! [`DOCUMENTATION_URL`] that the language server links hold.
+
+use crate::lint::{builtin_rules, LintRule};
+use crate::{ClassDiagnosticKind, SemanticErrorType};
+
+/// Where the page of each code is published, as `<code>.md`
+pub const DOCUMENTATION_URL: &str =
+    "https://github.com/SeanMcLoughlin/very/blob/main/docs/diagnostics/";
+
+/// Link to the page of `code`
+pub fn documentation_url(code: &str) -> String {
+    format!("{}{}.md", DOCUMENTATION_URL, code)
+}
+
+/// Every code, semantic errors first, then class problems, then lint rules
+pub fn codes() -> Vec<String> {
+    SemanticErrorType::ALL
+        .iter()
+        .map(|kind| kind.code().to_string())
+        .chain(
+            ClassDiagnosticKind::ALL
+                .iter()
+                .map(|kind| kind.code().to_string()),
+        )
+        .chain(builtin_rules().iter().map(|rule| rule.name().to_string()))
+        .collect()
+}
+
+/// The page of `code`, or None for a code no check reports
+///
+/// Codes are matched without regard to case, so `sv0002` finds `SV0002`.
+pub fn explain(code: &str) -> Option<String> {
+    if let Some(kind) = SemanticErrorType::ALL
+        .into_iter()
+        .find(|kind| kind.code().eq_ignore_ascii_case(code))
+    {
+        let (title, explanation) = semantic_page(kind);
+        return Some(format!(
+            "# {}: {}\n\n{}\n\nReported by semantic analysis as an error; it cannot be \
+             turned off or given another severity.\n",
+            kind.code(),
+            title,
+            explanation
+        ));
+    }
+    if let Some(kind) = ClassDiagnosticKind::ALL
+        .into_iter()
+        .find(|kind| kind.code().eq_ignore_ascii_case(code))
+    {
+        let (title, explanation) = class_page(kind);
+        let severity = if kind == ClassDiagnosticKind::NonVirtualOverride {
+            "a warning"
+        } else {
+            "an error"
+        };
+        return Some(format!(
+            "# {}: {}\n\n{}\n\nReported as {} once every file is parsed, since a class \
+             may extend a class of another file.\n",
+            kind.code(),
+            title,
+            explanation,
+            severity
+        ));
+    }
+    builtin_rules()
+        .into_iter()
+        .find(|rule| rule.name().eq_ignore_ascii_case(code))
+        .map(|rule| lint_page(rule.as_ref()))
+}
+
+fn lint_page(rule: &dyn LintRule) -> String {
+    let mut page = format!("# {}: {}\n\n", rule.name(), rule.description());
+    if !rule.explanation().is_empty() {
+        page.push_str(rule.explanation());
+        page.push_str("\n\n");
+    }
+    let selection = if rule.enabled_by_default() {
+        "runs with `--lint`"
+    } else {
+        "runs only when named, as in `--lint=<rules>`"
+    };
+    page.push_str(&format!(
+        "Lint rule; {}. Its default severity is {}, which the \
+         `[lint.severity]` table of `.very.toml` can change:\n\n\
+         ```toml\n[lint.severity]\n{} = \"{}\"\n```\n",
+        selection,
+        rule.default_severity().to_string().to_lowercase(),
+        rule.name(),
+        rule.default_severity().to_string().to_lowercase()
+    ));
+    page
+}
+
+/// Title and explanation of a semantic error
+fn semantic_page(kind: SemanticErrorType) -> (&'static str, &'static str) {
+    match kind {
+        SemanticErrorType::UnknownSystemFunction => (
+            "Unknown system function",
+            "A call to a system task or function that the standard doesn't define, \
+             usually a misspelling like `$fel` for `$fell`.",
+        ),
+        SemanticErrorType::UndeclaredIdentifier => (
+            "Undeclared identifier",
+            "A name used in an expression that no declaration in scope defines: not a \
+             port, variable, parameter, function, enum member or imported package \
+             item.",
+        ),
+        SemanticErrorType::TypeMismatch => (
+            "Type mismatch",
+            "An operand whose type cannot be used where it appears.",
+        ),
+        SemanticErrorType::InvalidOperation => (
+            "Invalid operation",
+            "An operator applied to operands it is not defined for.",
+        ),
+        SemanticErrorType::LabelMismatch => (
+            "Mismatched end label",
+            "The label after the `end` or `join` of a block has to repeat the name \
+             of the block, as in `begin : a ... end : a`, and an unnamed block \
+             cannot have one. A different name usually means a block was closed in \
+             the wrong place.",
+        ),
+        SemanticErrorType::InvalidInheritance => (
+            "Invalid inheritance",
+            "A class can `extend` one class and `implement` interface classes; an \
+             interface class can only `extend` interface classes. Naming the other \
+             kind of class in either clause is an error.",
+        ),
+        SemanticErrorType::DuplicateDeclaration => (
+            "Duplicate declaration",
+            "A declaration repeated where only one is allowed, like two \
+             `default clocking` or `default disable iff` items in the same module.",
+        ),
+        SemanticErrorType::AssignmentToLiteral => (
+            "Assignment to a literal",
+            "The left-hand side of an assignment is a number or string, like \
+             `5 = a`, which has no storage to assign to. The operands were probably \
+             swapped.",
+        ),
+        SemanticErrorType::AssignmentToCall => (
+            "Assignment to a function call",
+            "The left-hand side of an assignment is the result of a function call, \
+             like `f(x) = a`, which is a value and not a variable.",
+        ),
+        SemanticErrorType::AssignmentToInput => (
+            "Assignment to an input port",
+            "An input port is driven from outside its module, so assigning to it \
+             from inside gives it two drivers. Declare it `output` or `inout`, or \
+             assign to a local variable instead.",
+        ),
+        SemanticErrorType::AssignmentToParameter => (
+            "Assignment to a constant",
+            "Parameters, localparams and enum members are constants fixed at \
+             elaboration, and cannot be assigned to at run time.",
+        ),
+        SemanticErrorType::IncDecOnNet => (
+            "Increment or decrement of a net",
+            "`++` and `--` are procedural assignments, and a net like a `wire` can \
+             only be driven by continuous assignments and ports. Declare the signal \
+             as a variable, like `logic`, to update it in a procedural block.",
+        ),
+        SemanticErrorType::InvalidClassReference => (
+            "Invalid `this` or `super`",
+            "`this` and `super` refer to the object a method runs on, so they are \
+             only allowed in the methods of a class, and `super` only in a class \
+             with a base class. A call to `super.new` has to be the first statement \
+             of a constructor.",
+        ),
+        SemanticErrorType::UnknownMember => (
+            "Unknown struct member",
+            "A member access or a key of an assignment pattern names a member the \
+             struct does not declare.",
+        ),
+        SemanticErrorType::ElaborationFailure => (
+            "Elaboration failure",
+            "A `$error` or `$fatal` module item, usually in a generate branch that \
+             checks parameters, stops elaboration of its module. The message of the \
+             task is reported as the error.",
+        ),
+        SemanticErrorType::InvalidReturn => (
+            "Invalid return",
+            "A `return` outside a function or task, a `return` with a value in a \
+             `void` function, task or constructor, or a `return` without one in a \
+             function with a return type.",
+        ),
+        SemanticErrorType::InvalidTimeUnit => (
+            "Invalid time unit",
+            "The time literals of a `` `timescale `` directive and of `timeunit` and \
+             `timeprecision` declarations must be 1, 10 or 100 of `s`, `ms`, `us`, \
+             `ns`, `ps` or `fs`, and the precision of a design element can be no \
+             coarser than its time unit. A module that declares `timeunit 1ps;` \
+             under `` `timescale 1ns/1ns `` keeps the precision of the directive, \
+             so it has to declare a finer `timeprecision` as well.",
+        ),
+        SemanticErrorType::InvalidDriveStrength => (
+            "Invalid drive strength",
+            "A drive strength gives the strength of driving 0 and of driving 1, in \
+             either order, as in `(strong0, weak1)` or `(pull1, highz0)`. Two \
+             strengths for the same value, like `(weak0, strong0)`, leave the other \
+             undefined, and `(highz0, highz1)` would never drive the net at all.",
+        ),
+        SemanticErrorType::InvalidSystemCallArguments => (
+            "Invalid system call arguments",
+            "A file I/O or memory load task or function called with too few or too \
+             many arguments, or with a literal where it can't take one: a number \
+             for a file name, a string for a file descriptor, or a value where the \
+             call writes to a variable, like the memory of `$readmemh`. The mode of \
+             `$fopen` has to be one of `r`, `w` or `a`, optionally followed by `+` \
+             and `b`.",
+        ),
+        SemanticErrorType::InvalidImport => (
+            "Invalid import",
+            "A name declared in a scope wins over an explicit import of it, which \
+             wins over wildcard imports. Importing a name the scope also declares, \
+             or importing the same name explicitly from two packages, is an error. \
+             Two wildcard imports may provide the same name, but only until the \
+             name is used: import it explicitly, as in `import bus_pkg::WIDTH;`, \
+             to choose the package.",
+        ),
+    }
+}
+
+/// Title and explanation of a class hierarchy problem
+fn class_page(kind: ClassDiagnosticKind) -> (&'static str, &'static str) {
+    match kind {
+        ClassDiagnosticKind::SignatureMismatch => (
+            "Override with a different signature",
+            "A method that overrides a virtual method of a base class must have the \
+             same return type and the same arguments, with the same names, types and \
+             directions, so that a call through a handle of the base class passes \
+             what the override expects.",
+        ),
+        ClassDiagnosticKind::NonVirtualOverride => (
+            "Method hiding a non-virtual method",
+            "A method with the name of a base class method that is not `virtual` \
+             hides it instead of overriding it: a call through a handle of the base \
+             class still runs the base class method. Declare the base class method \
+             `virtual` if the derived one should replace it.",
+        ),
+        ClassDiagnosticKind::InheritanceCycle => (
+            "Inheritance cycle",
+            "A class that extends itself, directly or through other classes, has no \
+             base to start from. One of the `extends` clauses in the cycle names the \
+             wrong class.",
+        ),
+        ClassDiagnosticKind::UnknownInterfaceClass => (
+            "Unknown interface class",
+            "The `implements` clause of a class, or the `extends` clause of an \
+             interface class, names an interface class that no file of the design \
+             declares. Usually the name is misspelled or the file declaring it is \
+             not among the files given.",
+        ),
+        ClassDiagnosticKind::UnknownInterface => (
+            "Unknown interface",
+            "A virtual interface handle, like `virtual bus_if vif;`, names an \
+             interface that no file of the design declares, so the handle can \
+             never be connected.",
+        ),
+        ClassDiagnosticKind::UnimplementedMethod => (
+            "Unimplemented pure virtual method",
+            "A class that is not `virtual` has to implement every `pure virtual` \
+             method of its base classes and of the interface classes it \
+             implements, since objects of it can be constructed and the method \
+             called. Implement the method, or declare the class `virtual`.",
+        ),
+    }
+}
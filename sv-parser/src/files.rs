@@ -0,0 +1,50 @@
+//! File system access
+//!
+//! Everything the crate reads from disk goes through here. Without the `fs`
+//! feature, as in builds for the browser, no file exists and every read
+//! fails, so a parse sees only the text it is given.
+
+use std::io;
+use std::path::Path;
+
+/// The contents of the file at `path`
+pub(crate) fn read_to_string(path: &Path) -> io::Result<String> {
+    #[cfg(feature = "fs")]
+    {
+        std::fs::read_to_string(path)
+    }
+    #[cfg(not(feature = "fs"))]
+    {
+        let _ = path;
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "file system access is disabled",
+        ))
+    }
+}
+
+/// Whether `path` names an existing file or directory
+pub(crate) fn exists(path: &Path) -> bool {
+    #[cfg(feature = "fs")]
+    {
+        path.exists()
+    }
+    #[cfg(not(feature = "fs"))]
+    {
+        let _ = path;
+        false
+    }
+}
+
+/// Whether `path` names an existing file
+pub(crate) fn is_file(path: &Path) -> bool {
+    #[cfg(feature = "fs")]
+    {
+        path.is_file()
+    }
+    #[cfg(not(feature = "fs"))]
+    {
+        let _ = path;
+        false
+    }
+}
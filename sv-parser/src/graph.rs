@@ -0,0 +1,419 @@
+//! Module instantiation graph
+//!
+//! [`ModuleGraph`] records which modules instantiate which, and which
+//! packages they import, across all the parsed files, and renders the
+//! hierarchy as Graphviz DOT or JSON (through `--graph`). Modules that are instantiated but not defined in the sources,
+//! such as vendor library cells, can be collapsed into a single node, and the
+//! graph can be cut off at a given depth below the top modules. The JSON
+//! form also lists the value of each module's parameters, after any
+//! `--param` overrides.
+
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::number::parameter_values;
+use crate::{ModuleItem, ModuleItemRef, SourceUnit};
+
+/// Name of the node standing for all library cells when they are collapsed
+pub const LIBRARY_CELLS_NODE: &str = "(library cells)";
+
+/// How much of the graph to render
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GraphOptions {
+    /// Draw all modules that aren't defined in the sources as one node
+    pub collapse_library_cells: bool,
+    /// Only show modules at most this many instantiation levels below a top module
+    pub max_depth: Option<usize>,
+}
+
+/// An instance inside a module
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct Instance {
+    pub name: String,
+    pub module: String,
+}
+
+/// A module of the design
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ModuleNode {
+    pub name: String,
+    /// File defining the module; None for modules that are only instantiated
+    pub file: Option<PathBuf>,
+    pub instances: Vec<Instance>,
+    /// Packages the module imports, sorted by name
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
+    /// Value of each parameter that evaluates to a constant, in decimal
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+}
+
+/// A package of the design
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PackageNode {
+    pub name: String,
+    /// File defining the package; None for packages that are only imported
+    pub file: Option<PathBuf>,
+    /// Packages this one imports, sorted by name
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub imports: Vec<String>,
+}
+
+/// Modules and packages of the design, with the instances and imports
+/// connecting them
+#[derive(Debug, Clone, Default)]
+pub struct ModuleGraph {
+    modules: BTreeMap<String, ModuleNode>,
+    packages: BTreeMap<String, PackageNode>,
+}
+
+#[derive(Serialize)]
+struct GraphJson<'a> {
+    top: Vec<&'a str>,
+    modules: Vec<ModuleNode>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    packages: Vec<PackageNode>,
+}
+
+impl ModuleGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the modules, interfaces and packages defined in a parsed file
+    pub fn add_source_unit(&mut self, file: &Path, source_unit: &SourceUnit) {
+        self.add_items(file, source_unit, &source_unit.items, &BTreeSet::new());
+    }
+
+    /// Add the design units among `items`, which see the packages in
+    /// `outer_imports` and those imported by earlier items
+    fn add_items(
+        &mut self,
+        file: &Path,
+        source_unit: &SourceUnit,
+        items: &[ModuleItemRef],
+        outer_imports: &BTreeSet<String>,
+    ) {
+        let arena = &source_unit.module_item_arena;
+        let mut scope_imports = outer_imports.clone();
+        for item_ref in items {
+            let (name, items) = match arena.get(*item_ref) {
+                ModuleItem::ImportDeclaration { imports, .. } => {
+                    scope_imports.extend(imports.iter().map(|import| import.package.clone()));
+                    continue;
+                }
+                ModuleItem::PackageDeclaration { name, items, .. } => {
+                    let mut package_imports = scope_imports.clone();
+                    package_imports.extend(imports(source_unit, items));
+                    self.packages.insert(
+                        name.clone(),
+                        PackageNode {
+                            name: name.clone(),
+                            file: Some(file.to_path_buf()),
+                            imports: package_imports.into_iter().collect(),
+                        },
+                    );
+                    continue;
+                }
+                // Interfaces and checkers are instantiated like modules, so
+                // they are nodes too
+                ModuleItem::ModuleDeclaration { name, items, .. }
+                | ModuleItem::InterfaceDeclaration { name, items, .. }
+                | ModuleItem::CheckerDeclaration { name, items, .. } => (name, items),
+                _ => continue,
+            };
+            let instances = instances(source_unit, items);
+            let mut module_imports = scope_imports.clone();
+            module_imports.extend(imports(source_unit, items));
+            let parameters = parameter_values(items, arena, &source_unit.expr_arena)
+                .into_iter()
+                .filter_map(|(name, value)| Some((name, value.to_decimal_string()?)))
+                .collect();
+            self.modules.insert(
+                name.clone(),
+                ModuleNode {
+                    name: name.clone(),
+                    file: Some(file.to_path_buf()),
+                    instances,
+                    imports: module_imports.iter().cloned().collect(),
+                    parameters,
+                },
+            );
+            self.add_items(file, source_unit, items, &module_imports);
+        }
+    }
+
+    /// A defined module by name
+    pub fn module(&self, name: &str) -> Option<&ModuleNode> {
+        self.modules.get(name)
+    }
+
+    /// A defined package by name
+    pub fn package(&self, name: &str) -> Option<&PackageNode> {
+        self.packages.get(name)
+    }
+
+    /// Whether `name` is instantiated but not defined in the sources
+    pub fn is_library_cell(&self, name: &str) -> bool {
+        !self.modules.contains_key(name)
+    }
+
+    /// Defined modules that no other module instantiates, sorted by name
+    pub fn top_modules(&self) -> Vec<&str> {
+        let instantiated: BTreeSet<&str> = self
+            .modules
+            .values()
+            .flat_map(|module| {
+                module
+                    .instances
+                    .iter()
+                    .map(|instance| instance.module.as_str())
+            })
+            .collect();
+        self.modules
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !instantiated.contains(name))
+            .collect()
+    }
+
+    /// The modules to render with their depth below a top module, library cells included
+    ///
+    /// Without a depth limit every defined module is shown, including those
+    /// only reachable through an instantiation cycle.
+    fn visible(&self, options: &GraphOptions) -> BTreeMap<&str, usize> {
+        let mut depths = BTreeMap::new();
+        let mut queue: VecDeque<(&str, usize)> =
+            self.top_modules().into_iter().map(|top| (top, 0)).collect();
+        if options.max_depth.is_none() {
+            queue.extend(self.modules.keys().map(|name| (name.as_str(), 0)));
+        }
+        while let Some((name, depth)) = queue.pop_front() {
+            if depths.contains_key(name) || options.max_depth.is_some_and(|max| depth > max) {
+                continue;
+            }
+            depths.insert(name, depth);
+            if let Some(module) = self.modules.get(name) {
+                for instance in &module.instances {
+                    queue.push_back((instance.module.as_str(), depth + 1));
+                }
+            }
+        }
+        depths
+    }
+
+    /// The packages to render: those imported by the visible modules, and the
+    /// packages they import in turn; without a depth limit, every defined
+    /// package as well
+    fn visible_packages(&self, options: &GraphOptions) -> BTreeSet<&str> {
+        let mut pending: Vec<&str> = self
+            .visible(options)
+            .into_keys()
+            .filter_map(|name| self.modules.get(name))
+            .flat_map(|module| module.imports.iter().map(String::as_str))
+            .collect();
+        if options.max_depth.is_none() {
+            pending.extend(self.packages.keys().map(String::as_str));
+        }
+        let mut packages = BTreeSet::new();
+        while let Some(name) = pending.pop() {
+            if packages.insert(name) {
+                if let Some(package) = self.packages.get(name) {
+                    pending.extend(package.imports.iter().map(String::as_str));
+                }
+            }
+        }
+        packages
+    }
+
+    /// Visible import edges from a module or package to the packages it imports
+    fn import_edges(&self, options: &GraphOptions) -> Vec<(&str, &str)> {
+        let visible = self.visible(options);
+        let modules = visible
+            .keys()
+            .filter_map(|name| self.modules.get(*name))
+            .map(|module| (module.name.as_str(), &module.imports));
+        let packages = self
+            .visible_packages(options)
+            .into_iter()
+            .filter_map(|name| self.packages.get(name))
+            .map(|package| (package.name.as_str(), &package.imports));
+        modules
+            .chain(packages)
+            .flat_map(|(name, imports)| imports.iter().map(move |import| (name, import.as_str())))
+            .collect()
+    }
+
+    /// Name of the node drawn for module `name`
+    fn node_name<'a>(&self, name: &'a str, options: &GraphOptions) -> &'a str {
+        if options.collapse_library_cells && self.is_library_cell(name) {
+            LIBRARY_CELLS_NODE
+        } else {
+            name
+        }
+    }
+
+    /// Visible edges from parent node to child node, with the instance names on each
+    fn edges(&self, options: &GraphOptions) -> BTreeMap<(&str, &str), Vec<&str>> {
+        let visible = self.visible(options);
+        let mut edges: BTreeMap<(&str, &str), Vec<&str>> = BTreeMap::new();
+        for name in visible.keys() {
+            let Some(module) = self.modules.get(*name) else {
+                continue;
+            };
+            for instance in &module.instances {
+                if visible.contains_key(instance.module.as_str()) {
+                    edges
+                        .entry((name, self.node_name(&instance.module, options)))
+                        .or_default()
+                        .push(&instance.name);
+                }
+            }
+        }
+        edges
+    }
+
+    /// Render the graph in Graphviz DOT format
+    ///
+    /// Library cells are drawn dashed, and each edge is labelled with the
+    /// names of the instances it stands for. Packages are drawn as folders,
+    /// dashed if they are not defined in the sources, with a dotted edge from
+    /// each importer.
+    pub fn to_dot(&self, options: &GraphOptions) -> String {
+        let nodes: BTreeSet<&str> = self
+            .visible(options)
+            .into_keys()
+            .map(|name| self.node_name(name, options))
+            .collect();
+
+        let mut dot = String::from("digraph modules {\n    node [shape=box];\n");
+        for node in &nodes {
+            if self.is_library_cell(node) {
+                dot.push_str(&format!("    {} [style=dashed];\n", dot_id(node)));
+            } else {
+                dot.push_str(&format!("    {};\n", dot_id(node)));
+            }
+        }
+        for package in self.visible_packages(options) {
+            let style = if self.packages.contains_key(package) {
+                ""
+            } else {
+                ", style=dashed"
+            };
+            dot.push_str(&format!(
+                "    {} [shape=folder{}];\n",
+                dot_id(package),
+                style
+            ));
+        }
+        for ((parent, child), instances) in self.edges(options) {
+            dot.push_str(&format!(
+                "    {} -> {} [label={}];\n",
+                dot_id(parent),
+                dot_id(child),
+                dot_id(&instances.join(", "))
+            ));
+        }
+        for (importer, package) in self.import_edges(options) {
+            dot.push_str(&format!(
+                "    {} -> {} [style=dotted];\n",
+                dot_id(importer),
+                dot_id(package)
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Render the graph as pretty-printed JSON: the top modules, every
+    /// visible module with its file, instances, imports and parameter values,
+    /// and every visible package with its file and imports
+    pub fn to_json(&self, options: &GraphOptions) -> String {
+        let visible = self.visible(options);
+        let mut modules: BTreeMap<&str, ModuleNode> = BTreeMap::new();
+        for name in visible.keys() {
+            let node = self.node_name(name, options);
+            let entry = modules.entry(node).or_insert_with(|| ModuleNode {
+                name: node.to_string(),
+                ..ModuleNode::default()
+            });
+            if let Some(module) = self.modules.get(*name) {
+                entry.file = module.file.clone();
+                entry.imports = module.imports.clone();
+                entry.parameters = module.parameters.clone();
+                entry.instances = module
+                    .instances
+                    .iter()
+                    .filter(|instance| visible.contains_key(instance.module.as_str()))
+                    .map(|instance| Instance {
+                        name: instance.name.clone(),
+                        module: self.node_name(&instance.module, options).to_string(),
+                    })
+                    .collect();
+            }
+        }
+
+        let packages = self
+            .visible_packages(options)
+            .into_iter()
+            .map(|name| {
+                self.packages
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| PackageNode {
+                        name: name.to_string(),
+                        ..PackageNode::default()
+                    })
+            })
+            .collect();
+
+        let json = GraphJson {
+            top: self.top_modules(),
+            modules: modules.into_values().collect(),
+            packages,
+        };
+        serde_json::to_string_pretty(&json).expect("graph data is always serializable")
+    }
+}
+
+/// Instances inside a module, including those inside its generate constructs
+pub(crate) fn instances(source_unit: &SourceUnit, items: &[ModuleItemRef]) -> Vec<Instance> {
+    let mut found = Vec::new();
+    for item_ref in items {
+        match source_unit.module_item_arena.get(*item_ref) {
+            ModuleItem::ModuleInstantiation {
+                module_name,
+                instance_name,
+                ..
+            } => found.push(Instance {
+                name: instance_name.clone(),
+                module: module_name.clone(),
+            }),
+            item => found.extend(instances(source_unit, &item.generate_items())),
+        }
+    }
+    found
+}
+
+/// Packages imported by the items of a module or package, not counting
+/// those of the design units nested in it
+fn imports(source_unit: &SourceUnit, items: &[ModuleItemRef]) -> BTreeSet<String> {
+    items
+        .iter()
+        .filter_map(
+            |item_ref| match source_unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ImportDeclaration { imports, .. } => Some(imports),
+                _ => None,
+            },
+        )
+        .flatten()
+        .map(|import| import.package.clone())
+        .collect()
+}
+
+/// A quoted DOT identifier
+fn dot_id(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
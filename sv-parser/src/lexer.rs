@@ -0,0 +1,552 @@
+//! Tokenizer
+//!
+//! [`lex`] splits source text into [`Token`]s that borrow their text from the
+//! source instead of collecting it into new strings. Whitespace and comments
+//! are skipped. Spans are character offsets, like every other span in the
+//! AST, so a token's span can be compared directly with the spans of the
+//! nodes built from it.
+//!
+//! Which words are keywords depends on the language version in effect:
+//! `` `begin_keywords "1364-2001" `` ... `` `end_keywords `` regions select an
+//! older keyword set, so `logic` is an ordinary identifier inside them, and
+//! a whole file may be lexed under an older version, as Verilog files in a
+//! mixed file list are ([`Lexer::with_version`]). A word right after `.` or
+//! `::` names a member or a scoped item, like `new` in `item.new()` or `and`
+//! in `bits.and()`, so it is an identifier there whatever the version. Each
+//! token records the version it was lexed under, and the parser asks the
+//! token stream rather than its own table whether a word is reserved.
+//! Each token also records what separated it from the token before it, for
+//! the few places where that matters, like the end of a directive's line.
+
+use crate::Span;
+
+/// Reserved words the grammar recognises, in the latest language version
+pub const KEYWORDS: &[&str] = &[
+    "module",
+    "endmodule",
+    "input",
+    "output",
+    "inout",
+    "wire",
+    "assign",
+    "initial",
+    "always",
+    "always_comb",
+    "always_ff",
+    "final",
+    "begin",
+    "end",
+    "if",
+    "else",
+    "case",
+    "casex",
+    "casez",
+    "endcase",
+    "for",
+    "foreach",
+    "forever",
+    "while",
+    "do",
+    "repeat",
+    "posedge",
+    "negedge",
+    "edge",
+    "or",
+    "generate",
+    "endgenerate",
+    "genvar",
+    "int",
+    "logic",
+    "bit",
+    "byte",
+    "reg",
+    "signed",
+    "unsigned",
+    "integer",
+    "time",
+    "event",
+    "shortint",
+    "longint",
+    "class",
+    "endclass",
+    "extends",
+    "function",
+    "endfunction",
+    "task",
+    "endtask",
+    "local",
+    "protected",
+    "new",
+    "this",
+    "super",
+    "static",
+    "extern",
+    "timeunit",
+    "timeprecision",
+    "assert",
+    "assume",
+    "cover",
+    "restrict",
+    "property",
+    "endproperty",
+    "sequence",
+    "endsequence",
+    "and",
+    "not",
+    "intersect",
+    "within",
+    "throughout",
+    "unique",
+    "unique0",
+    "priority",
+    "global",
+    "checker",
+    "endchecker",
+    "clocking",
+    "endclocking",
+    "struct",
+    "union",
+    "packed",
+    "typedef",
+    "type",
+    "enum",
+    "soft",
+    "rand",
+    "randc",
+    "constraint",
+    "dist",
+    "with",
+    "tagged",
+    "supply0",
+    "supply1",
+    "tri",
+    "triand",
+    "trior",
+    "trireg",
+    "vectored",
+    "scalared",
+    "parameter",
+    "localparam",
+    "wait",
+    "fork",
+    "disable",
+    "virtual",
+    "pure",
+    "interface",
+    "endinterface",
+    "modport",
+    "package",
+    "endpackage",
+    "import",
+    "export",
+    "implements",
+    "default",
+    "iff",
+    "matches",
+    "inside",
+    "config",
+    "endconfig",
+    "design",
+    "liblist",
+    "instance",
+    "cell",
+    "use",
+];
+
+/// Operators and punctuation, longest first so that the first match wins
+const OPERATORS: &[&str] = &[
+    "<<<=", ">>>=", "<->", "===", "!==", "==?", "!=?", "<<<", ">>>", "<<=", ">>=", "&&&", "|->",
+    "|=>", "::", "==", "!=", "<=", ">=", "&&", "||", "**", "<<", ">>", "->", "+=", "-=", "*=",
+    "/=", "%=", "&=", "|=", "^=", "~&", "~|", "~^", "^~", "++", "--", "##", ".*", "'{", ":=", ":/",
+    "+", "-", "*", "/", "%", "&", "|", "^", "~", "!", "<", ">", "=", "?", ":", ";", ",", ".", "(",
+    ")", "[", "]", "{", "}", "@", "#", "'",
+];
+
+/// A keyword set selected by `` `begin_keywords ``, oldest first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KeywordVersion {
+    V1364_1995,
+    V1364_2001Noconfig,
+    V1364_2001,
+    V1364_2005,
+    V1800_2005,
+    V1800_2009,
+    V1800_2012,
+    V1800_2017,
+}
+
+impl KeywordVersion {
+    /// The newest version, in effect outside any `` `begin_keywords `` region
+    /// unless the file selects another
+    pub const LATEST: KeywordVersion = KeywordVersion::V1800_2017;
+
+    /// Parse a `` `begin_keywords `` version specifier such as `1800-2009`
+    pub fn from_specifier(specifier: &str) -> Option<Self> {
+        Some(match specifier {
+            "1364-1995" => KeywordVersion::V1364_1995,
+            "1364-2001-noconfig" => KeywordVersion::V1364_2001Noconfig,
+            "1364-2001" => KeywordVersion::V1364_2001,
+            "1364-2005" => KeywordVersion::V1364_2005,
+            "1800-2005" => KeywordVersion::V1800_2005,
+            "1800-2009" => KeywordVersion::V1800_2009,
+            "1800-2012" => KeywordVersion::V1800_2012,
+            "1800-2017" => KeywordVersion::V1800_2017,
+            _ => return None,
+        })
+    }
+
+    pub fn specifier(self) -> &'static str {
+        match self {
+            KeywordVersion::V1364_1995 => "1364-1995",
+            KeywordVersion::V1364_2001Noconfig => "1364-2001-noconfig",
+            KeywordVersion::V1364_2001 => "1364-2001",
+            KeywordVersion::V1364_2005 => "1364-2005",
+            KeywordVersion::V1800_2005 => "1800-2005",
+            KeywordVersion::V1800_2009 => "1800-2009",
+            KeywordVersion::V1800_2012 => "1800-2012",
+            KeywordVersion::V1800_2017 => "1800-2017",
+        }
+    }
+
+    /// Whether `word` is reserved in this version
+    pub fn is_keyword(self, word: &str) -> bool {
+        introduced_in(word).is_some_and(|introduced| introduced <= self)
+    }
+}
+
+/// The first version in which `word` is a keyword, if it is one at all
+fn introduced_in(word: &str) -> Option<KeywordVersion> {
+    if !KEYWORDS.contains(&word) {
+        return None;
+    }
+    Some(match word {
+        "signed" | "unsigned" | "localparam" | "generate" | "endgenerate" | "genvar" => {
+            KeywordVersion::V1364_2001Noconfig
+        }
+        "config" | "endconfig" | "design" | "liblist" | "instance" | "cell" | "use" => {
+            KeywordVersion::V1364_2001
+        }
+        "always_comb" | "always_ff" | "final" | "int" | "logic" | "bit" | "byte" | "shortint"
+        | "longint" | "class" | "endclass" | "extends" | "local" | "protected" | "new" | "this"
+        | "super" | "static" | "extern" | "timeunit" | "timeprecision" | "assert" | "property"
+        | "assume" | "cover" | "endproperty" | "sequence" | "endsequence" | "intersect"
+        | "within" | "throughout" | "unique" | "priority" | "clocking" | "endclocking"
+        | "struct" | "union" | "packed" | "typedef" | "type" | "enum" | "tagged" | "virtual"
+        | "pure" | "interface" | "endinterface" | "modport" | "package" | "endpackage"
+        | "import" | "export" | "iff" | "matches" | "inside" | "foreach" | "rand" | "randc"
+        | "constraint" | "dist" | "with" => KeywordVersion::V1800_2005,
+        "unique0" | "global" | "checker" | "endchecker" | "restrict" => KeywordVersion::V1800_2009,
+        "implements" | "soft" => KeywordVersion::V1800_2012,
+        _ => KeywordVersion::V1364_1995,
+    })
+}
+
+/// Whether `word` is reserved in the latest language version
+pub fn is_keyword(word: &str) -> bool {
+    KeywordVersion::LATEST.is_keyword(word)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    Identifier,
+    Keyword,
+    /// `$display`, `$clog2`
+    SystemIdentifier,
+    /// `` `define ``, `` `WIDTH ``
+    Directive,
+    /// Decimal, based (`8'hA5`) or unbased unsized (`'1`) number, as written
+    Number,
+    /// String literal including its quotes
+    StringLiteral,
+    Operator,
+    /// A character that doesn't start any token, or an unterminated string or comment
+    Unknown,
+}
+
+/// What separates a token from the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Spacing {
+    /// Nothing: the tokens touch, like `#` and `2` in `#2`
+    Joined,
+    /// Whitespace or comments on the same line
+    Space,
+    /// A line break, or nothing at all before the first token
+    LineBreak,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Token<'src> {
+    pub kind: TokenKind,
+    pub text: &'src str,
+    pub span: Span,
+    /// Keyword set in effect where the token appears
+    pub version: KeywordVersion,
+    pub spacing: Spacing,
+}
+
+impl<'src> Token<'src> {
+    /// The identifier named by this token; escaped identifiers (`\bus+index`)
+    /// lose their leading backslash
+    pub fn name(&self) -> &'src str {
+        self.text.strip_prefix('\\').unwrap_or(self.text)
+    }
+}
+
+/// Split `source` into tokens
+pub fn lex(source: &str) -> Vec<Token<'_>> {
+    Lexer::new(source).collect()
+}
+
+/// Iterator over the tokens of a source text
+pub struct Lexer<'src> {
+    source: &'src str,
+    /// Byte offset of the next character
+    pos: usize,
+    /// Character offset of the next character
+    char_pos: usize,
+    /// Version in effect outside any `` `begin_keywords `` region
+    default_version: KeywordVersion,
+    /// Versions selected by enclosing `` `begin_keywords `` directives
+    versions: Vec<KeywordVersion>,
+    /// Whether the last token was `` `begin_keywords ``, so the next string names a version
+    begin_keywords: bool,
+    /// Whether the last token was `.` or `::`, so the next word is a name
+    /// even if it is reserved
+    member: bool,
+}
+
+impl<'src> Lexer<'src> {
+    pub fn new(source: &'src str) -> Self {
+        Self::with_version(source, KeywordVersion::LATEST)
+    }
+
+    /// Lex `source` with `version` in effect outside `` `begin_keywords `` regions,
+    /// as for a Verilog file in a mixed-language file list
+    pub fn with_version(source: &'src str, version: KeywordVersion) -> Self {
+        Self {
+            source,
+            pos: 0,
+            char_pos: 0,
+            default_version: version,
+            versions: Vec::new(),
+            begin_keywords: false,
+            member: false,
+        }
+    }
+
+    /// Keyword set in effect at the current position
+    pub fn version(&self) -> KeywordVersion {
+        self.versions
+            .last()
+            .copied()
+            .unwrap_or(self.default_version)
+    }
+
+    fn rest(&self) -> &'src str {
+        &self.source[self.pos..]
+    }
+
+    /// Whether the operator `op` starts here; a `:/` whose slash starts a
+    /// comment is a `:`
+    fn at_operator(&self, op: &str) -> bool {
+        let rest = self.rest();
+        rest.starts_with(op)
+            && !(op == ":/" && (rest[2..].starts_with('/') || rest[2..].starts_with('*')))
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn peek_second(&self) -> Option<char> {
+        self.rest().chars().nth(1)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        self.char_pos += 1;
+        Some(c)
+    }
+
+    fn bump_while(&mut self, accept: impl Fn(char) -> bool) {
+        while self.peek().is_some_and(&accept) {
+            self.bump();
+        }
+    }
+
+    /// Skip whitespace and comments. Returns false when an unterminated block
+    /// comment ran to the end of the input.
+    fn skip_trivia(&mut self) -> bool {
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("//") {
+                self.bump_while(|c| c != '\n');
+            } else if let Some(body) = rest.strip_prefix("/*") {
+                match body.find("*/") {
+                    Some(end) => {
+                        let comment = &rest[..end + 4];
+                        self.pos += comment.len();
+                        self.char_pos += comment.chars().count();
+                    }
+                    None => return false,
+                }
+            } else if self.peek().is_some_and(char::is_whitespace) {
+                self.bump_while(char::is_whitespace);
+            } else {
+                return true;
+            }
+        }
+    }
+
+    fn is_identifier_char(c: char) -> bool {
+        c.is_alphanumeric() || c == '_' || c == '$'
+    }
+
+    /// Base and digits of a based number, after the `'`
+    fn based_digits(&mut self) {
+        if matches!(self.peek(), Some('s' | 'S')) {
+            self.bump();
+        }
+        self.bump();
+        self.bump_while(|c| {
+            c.is_ascii_hexdigit() || matches!(c, 'x' | 'X' | 'z' | 'Z' | '?' | '_')
+        });
+    }
+
+    /// Whether the text after a `'` continues a number: a base, optionally signed
+    fn at_base(&self) -> bool {
+        let mut chars = self.rest().chars().skip(1);
+        let mut next = chars.next();
+        if matches!(next, Some('s' | 'S')) {
+            next = chars.next();
+        }
+        matches!(next, Some('b' | 'B' | 'o' | 'O' | 'd' | 'D' | 'h' | 'H'))
+    }
+
+    fn number(&mut self) {
+        self.bump_while(|c| c.is_ascii_digit() || c == '_');
+        if self.peek() == Some('.') && self.peek_second().is_some_and(|c| c.is_ascii_digit()) {
+            self.bump();
+            self.bump_while(|c| c.is_ascii_digit() || c == '_');
+        }
+        if self.peek() == Some('\'') && self.at_base() {
+            self.bump();
+            self.based_digits();
+        }
+    }
+
+    /// Lex a string literal, returning false if it isn't terminated on its line
+    fn string(&mut self) -> bool {
+        self.bump();
+        while let Some(c) = self.bump() {
+            match c {
+                '"' => return true,
+                '\\' => {
+                    self.bump();
+                }
+                '\n' => return false,
+                _ => {}
+            }
+        }
+        false
+    }
+}
+
+impl<'src> Iterator for Lexer<'src> {
+    type Item = Token<'src>;
+
+    fn next(&mut self) -> Option<Token<'src>> {
+        let trivia_start = self.pos;
+        let terminated = self.skip_trivia();
+        let spacing = if trivia_start == 0 || self.source[trivia_start..self.pos].contains('\n') {
+            Spacing::LineBreak
+        } else if trivia_start == self.pos {
+            Spacing::Joined
+        } else {
+            Spacing::Space
+        };
+        let (start, char_start) = (self.pos, self.char_pos);
+        let c = self.peek()?;
+        let version = self.version();
+
+        let kind = if !terminated {
+            // Unterminated block comment: the rest of the input is one bad token
+            self.pos = self.source.len();
+            self.char_pos += self.source[start..].chars().count();
+            TokenKind::Unknown
+        } else if c.is_alphabetic() || c == '_' {
+            self.bump_while(Self::is_identifier_char);
+            if !self.member && version.is_keyword(&self.source[start..self.pos]) {
+                TokenKind::Keyword
+            } else {
+                TokenKind::Identifier
+            }
+        } else if (c == '$' || c == '`')
+            && self
+                .peek_second()
+                .is_some_and(|c| c.is_alphabetic() || c == '_')
+        {
+            self.bump();
+            self.bump_while(Self::is_identifier_char);
+            if c == '$' {
+                TokenKind::SystemIdentifier
+            } else {
+                TokenKind::Directive
+            }
+        } else if c == '\\' && self.peek_second().is_some_and(|c| !c.is_whitespace()) {
+            // Escaped identifier: everything up to the next whitespace
+            self.bump_while(|c| !c.is_whitespace());
+            TokenKind::Identifier
+        } else if c.is_ascii_digit() {
+            self.number();
+            TokenKind::Number
+        } else if c == '\'' && self.at_base() {
+            self.bump();
+            self.based_digits();
+            TokenKind::Number
+        } else if c == '\'' && matches!(self.peek_second(), Some('0' | '1' | 'x' | 'X' | 'z' | 'Z'))
+        {
+            self.bump();
+            self.bump();
+            TokenKind::Number
+        } else if c == '"' {
+            if self.string() {
+                TokenKind::StringLiteral
+            } else {
+                TokenKind::Unknown
+            }
+        } else if let Some(op) = OPERATORS.iter().find(|op| self.at_operator(op)) {
+            self.pos += op.len();
+            self.char_pos += op.chars().count();
+            TokenKind::Operator
+        } else {
+            self.bump();
+            TokenKind::Unknown
+        };
+
+        let text = &self.source[start..self.pos];
+        let begin_keywords = std::mem::take(&mut self.begin_keywords);
+        self.member = kind == TokenKind::Operator && matches!(text, "." | "::");
+        match (kind, text) {
+            (TokenKind::Directive, "`begin_keywords") => self.begin_keywords = true,
+            (TokenKind::Directive, "`end_keywords") => {
+                self.versions.pop();
+            }
+            // An unknown version keeps the current keyword set, but still
+            // opens a region so that the matching `end_keywords balances it
+            (TokenKind::StringLiteral, _) if begin_keywords => self
+                .versions
+                .push(KeywordVersion::from_specifier(&text[1..text.len() - 1]).unwrap_or(version)),
+            _ => {}
+        }
+
+        Some(Token {
+            kind,
+            text,
+            span: (char_start, self.char_pos),
+            version,
+            spacing,
+        })
+    }
+}
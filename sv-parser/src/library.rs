@@ -0,0 +1,364 @@
+//! Libraries and configurations
+//!
+//! Cells (modules) are compiled into libraries: the source files into
+//! [`WORK_LIBRARY`] and each `-v` library file into a library named after the
+//! file. A configuration (`config ... endconfig`, IEEE 1800-2017 33.4) picks
+//! the library each instance of a design takes its cell from, so a project
+//! with several implementations of a module binds the same one everywhere
+//! the configuration says. [`DesignLibraries`] stores the cells and
+//! configurations of all files and elaborates a configuration into the
+//! hierarchy of instances with the cell bound to each.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::graph::{instances, Instance};
+use crate::{CellReference, ConfigRule, LibrarySelection, ModuleItem, SourceUnit, Span};
+
+/// Library of the source files given on the command line
+pub const WORK_LIBRARY: &str = "work";
+
+/// Library of a `-v` library file: the file name without its extension
+pub fn library_name(file: &Path) -> String {
+    file.file_stem().map_or_else(
+        || file.display().to_string(),
+        |stem| stem.to_string_lossy().to_string(),
+    )
+}
+
+/// A module compiled into a library
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub library: String,
+    pub name: String,
+    pub file: PathBuf,
+    pub name_span: Span,
+    pub instances: Vec<Instance>,
+}
+
+/// A configuration and the file declaring it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub name: String,
+    pub file: PathBuf,
+    pub design: Vec<CellReference>,
+    pub rules: Vec<ConfigRule>,
+}
+
+/// An instance of the elaborated design and the cell bound to it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    /// Hierarchical path, starting with the top cell: `top.u1.u2`
+    pub path: String,
+    pub library: String,
+    pub cell: String,
+}
+
+/// A configuration that cannot be applied as written
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryDiagnostic {
+    pub file: PathBuf,
+    pub span: Span,
+    pub message: String,
+}
+
+/// The design elaborated under a configuration
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Hierarchy {
+    /// Instances in depth-first order, top cells included
+    pub bindings: Vec<Binding>,
+    pub diagnostics: Vec<LibraryDiagnostic>,
+}
+
+impl Hierarchy {
+    /// The binding of the instance at `path`
+    pub fn binding(&self, path: &str) -> Option<&Binding> {
+        self.bindings.iter().find(|binding| binding.path == path)
+    }
+}
+
+/// Cells and configurations of every library
+#[derive(Debug, Clone, Default)]
+pub struct DesignLibraries {
+    /// Library names in the order they were first added
+    libraries: Vec<String>,
+    cells: BTreeMap<(String, String), Cell>,
+    configs: BTreeMap<String, Config>,
+}
+
+impl DesignLibraries {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compile the modules of a parsed file into `library`, and record its
+    /// configurations
+    ///
+    /// A module already in the library is replaced, as a later definition
+    /// replaces an earlier one when compiling.
+    pub fn add_source_unit(&mut self, library: &str, file: &Path, source_unit: &SourceUnit) {
+        if !self.libraries.iter().any(|name| name == library) {
+            self.libraries.push(library.to_string());
+        }
+        let arena = &source_unit.module_item_arena;
+        for item_ref in &source_unit.items {
+            match arena.get(*item_ref) {
+                ModuleItem::ModuleDeclaration {
+                    name,
+                    name_span,
+                    items,
+                    ..
+                }
+                | ModuleItem::InterfaceDeclaration {
+                    name,
+                    name_span,
+                    items,
+                    ..
+                }
+                | ModuleItem::CheckerDeclaration {
+                    name,
+                    name_span,
+                    items,
+                    ..
+                } => {
+                    let cell = Cell {
+                        library: library.to_string(),
+                        name: name.clone(),
+                        file: file.to_path_buf(),
+                        name_span: *name_span,
+                        instances: instances(source_unit, items),
+                    };
+                    self.cells.insert((library.to_string(), name.clone()), cell);
+                }
+                ModuleItem::ConfigDeclaration {
+                    name,
+                    design,
+                    rules,
+                    ..
+                } => {
+                    let config = Config {
+                        name: name.clone(),
+                        file: file.to_path_buf(),
+                        design: design.clone(),
+                        rules: rules.clone(),
+                    };
+                    self.configs.insert(name.clone(), config);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Library names, in the order they were added
+    pub fn libraries(&self) -> &[String] {
+        &self.libraries
+    }
+
+    pub fn cell(&self, library: &str, name: &str) -> Option<&Cell> {
+        self.cells.get(&(library.to_string(), name.to_string()))
+    }
+
+    pub fn config(&self, name: &str) -> Option<&Config> {
+        self.configs.get(name)
+    }
+
+    /// Configurations, sorted by name
+    pub fn configs(&self) -> impl Iterator<Item = &Config> {
+        self.configs.values()
+    }
+
+    /// The first library of `liblist` that holds a cell named `name`
+    fn find<'a>(&'a self, liblist: &[String], name: &str) -> Option<&'a Cell> {
+        liblist.iter().find_map(|library| self.cell(library, name))
+    }
+
+    /// Elaborate the design of configuration `name`
+    ///
+    /// Each instance takes its cell from, in order of precedence, the
+    /// `instance` rule naming its path, the `cell` rule naming its module, or
+    /// the first library of the liblist in effect that defines the module. The
+    /// liblist in effect is the one of the nearest enclosing `instance` or
+    /// `cell` rule with a liblist, else the `default liblist`, else every
+    /// library in the order it was added. Instances of modules no library
+    /// defines are left out, as are instances that would recurse into a cell
+    /// already on their path.
+    pub fn elaborate(&self, name: &str) -> Option<Hierarchy> {
+        let config = self.configs.get(name)?;
+        let mut hierarchy = Hierarchy::default();
+        let mut report = |span: Span, message: String| {
+            hierarchy.diagnostics.push(LibraryDiagnostic {
+                file: config.file.clone(),
+                span,
+                message,
+            });
+        };
+
+        let mut liblist = self.libraries.clone();
+        for rule in &config.rules {
+            let (libraries, span) = match rule {
+                ConfigRule::Default { liblist, span } => (liblist, span),
+                ConfigRule::Instance {
+                    selection: LibrarySelection::Liblist(liblist),
+                    span,
+                    ..
+                }
+                | ConfigRule::Cell {
+                    selection: LibrarySelection::Liblist(liblist),
+                    span,
+                    ..
+                } => (liblist, span),
+                ConfigRule::Instance {
+                    selection: LibrarySelection::Use(cell),
+                    span,
+                    ..
+                }
+                | ConfigRule::Cell {
+                    selection: LibrarySelection::Use(cell),
+                    span,
+                    ..
+                } => {
+                    if let Some(library) = &cell.library {
+                        if !self.libraries.contains(library) {
+                            report(*span, format!("Unknown library '{}'", library));
+                        } else if self.cell(library, &cell.cell).is_none() {
+                            report(
+                                *span,
+                                format!(
+                                    "Cell '{}.{}' is not defined in library '{}'",
+                                    library, cell.cell, library
+                                ),
+                            );
+                        }
+                    }
+                    continue;
+                }
+            };
+            for library in libraries {
+                if !self.libraries.contains(library) {
+                    report(*span, format!("Unknown library '{}'", library));
+                }
+            }
+            if let ConfigRule::Default {
+                liblist: default, ..
+            } = rule
+            {
+                liblist = default.clone();
+            }
+        }
+
+        let mut elaboration = Elaboration {
+            libraries: self,
+            config,
+            bindings: Vec::new(),
+        };
+        for top in &config.design {
+            let cell = match &top.library {
+                Some(library) => self.cell(library, &top.cell),
+                None => self.find(&liblist, &top.cell),
+            };
+            match cell {
+                Some(cell) => elaboration.bind(top.cell.clone(), cell, &liblist, &mut Vec::new()),
+                None => report(
+                    top.span,
+                    format!("Design cell '{}' is not defined", cell_reference_name(top)),
+                ),
+            }
+        }
+        hierarchy.bindings = elaboration.bindings;
+        Some(hierarchy)
+    }
+}
+
+/// State of [`DesignLibraries::elaborate`] while walking the hierarchy
+struct Elaboration<'a> {
+    libraries: &'a DesignLibraries,
+    config: &'a Config,
+    bindings: Vec<Binding>,
+}
+
+impl<'a> Elaboration<'a> {
+    /// Bind `cell` at `path`, then the instances inside it
+    fn bind(
+        &mut self,
+        path: String,
+        cell: &'a Cell,
+        liblist: &[String],
+        ancestors: &mut Vec<&'a Cell>,
+    ) {
+        if ancestors
+            .iter()
+            .any(|ancestor| std::ptr::eq(*ancestor, cell))
+        {
+            return;
+        }
+        self.bindings.push(Binding {
+            path: path.clone(),
+            library: cell.library.clone(),
+            cell: cell.name.clone(),
+        });
+        ancestors.push(cell);
+        for instance in &cell.instances {
+            let path = format!("{}.{}", path, instance.name);
+            if let Some((child, liblist)) = self.select(&path, &instance.module, liblist) {
+                self.bind(path, child, &liblist, ancestors);
+            }
+        }
+        ancestors.pop();
+    }
+
+    /// The cell for an instance of `module` at `path`, and the liblist for
+    /// the instances inside it
+    fn select(
+        &self,
+        path: &str,
+        module: &str,
+        liblist: &[String],
+    ) -> Option<(&'a Cell, Vec<String>)> {
+        let instance_rule = self.config.rules.iter().find_map(|rule| match rule {
+            ConfigRule::Instance {
+                path: rule_path,
+                selection,
+                ..
+            } if rule_path == path => Some(selection),
+            _ => None,
+        });
+        let selection = instance_rule.or_else(|| {
+            // A cell rule naming a library only applies to the cell from that library
+            let default = self.libraries.find(liblist, module);
+            self.config.rules.iter().find_map(|rule| match rule {
+                ConfigRule::Cell {
+                    cell, selection, ..
+                } if cell.cell == module
+                    && cell.library.as_ref().is_none_or(|library| {
+                        default.is_some_and(|default| &default.library == library)
+                    }) =>
+                {
+                    Some(selection)
+                }
+                _ => None,
+            })
+        });
+
+        match selection {
+            Some(LibrarySelection::Liblist(libraries)) => {
+                Some((self.libraries.find(libraries, module)?, libraries.clone()))
+            }
+            Some(LibrarySelection::Use(cell)) => {
+                let used = match &cell.library {
+                    Some(library) => self.libraries.cell(library, &cell.cell),
+                    None => self.libraries.find(liblist, &cell.cell),
+                }?;
+                Some((used, liblist.to_vec()))
+            }
+            None => Some((self.libraries.find(liblist, module)?, liblist.to_vec())),
+        }
+    }
+}
+
+/// `lib.cell`, or `cell` without a library
+fn cell_reference_name(reference: &CellReference) -> String {
+    match &reference.library {
+        Some(library) => format!("{}.{}", library, reference.cell),
+        None => reference.cell.clone(),
+    }
+}
@@ -0,0 +1,2929 @@
+//! Lint rule engine
+//!
+//! Lint rules are optional checks that run after a file has been parsed and
+//! semantically analyzed. Unlike semantic errors, lint findings flag code that
+//! is legal but suspicious or against common style guides. Every rule has a
+//! stable kebab-case name so that users can select rules individually
+//! (e.g. `--lint=blocking-in-always-ff`).
+//!
+//! Crates that depend on the parser can add rules of their own by
+//! implementing [`LintRule`] and passing them to
+//! [`LintEngine::configured_with`] or [`LintEngine::register`]. A rule can
+//! walk the source unit itself in [`LintRule::check`], or leave the walk to
+//! the engine and handle the items and statements it visits.
+//!
+//! A project-wide run with [`LintEngine::run_files`] checks several files at
+//! once on separate threads. Rules that compare files with each other say so
+//! with [`LintRule::cross_file`], and see every file together once the
+//! per-file checks are done.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::classes::returns_value;
+use crate::config::LintConfig;
+use crate::naming::NamingConventions;
+use crate::number::{evaluate_constant, mask, parameter_values};
+use crate::parallel;
+use crate::{policy, style};
+use crate::{
+    simplify, AssignmentOp, BinaryOp, BitPattern, CaseItem, CaseMatchesItem, ClassItem,
+    EventControl, ExprArena, ExprRef, Expression, Label, ModuleItem, ModuleItemArena,
+    ModuleItemRef, NumberLiteral, Pattern, Port, PortConnection, ProceduralBlockType, PropertyExpr,
+    Range, SourceUnit, Span, Statement, StmtRef, TextEdit, TimingControl, UnpackedDimension,
+};
+
+/// A single finding reported by a lint rule
+#[derive(Debug, Clone, PartialEq)]
+pub struct LintDiagnostic {
+    /// Name of the rule that produced this diagnostic
+    pub rule: &'static str,
+    pub message: String,
+    pub span: Span,
+    /// The edit that fixes the finding, for rules with a mechanical fix
+    pub fix: Option<TextEdit>,
+}
+
+/// How serious the findings of a lint rule are
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl fmt::Display for LintSeverity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LintSeverity::Error => write!(f, "Error"),
+            LintSeverity::Warning => write!(f, "Warning"),
+            LintSeverity::Info => write!(f, "Info"),
+        }
+    }
+}
+
+/// A parsed file of a project-wide lint run
+#[derive(Debug, Clone, Copy)]
+pub struct LintFile<'a> {
+    pub path: &'a Path,
+    pub source_unit: &'a SourceUnit,
+    /// The text the file was parsed from
+    pub text: &'a str,
+}
+
+/// A lint check over a parsed source unit
+///
+/// Rules are shared between the threads of a project-wide run, so they have
+/// to be `Send` and `Sync`.
+pub trait LintRule: Send + Sync {
+    /// Stable, kebab-case rule name used for selection on the command line
+    fn name(&self) -> &'static str;
+
+    /// One-line description shown by `--list-rules`
+    fn description(&self) -> &'static str;
+
+    /// What the rule finds and why it matters, in a paragraph or two of
+    /// Markdown for the page `--explain` prints; empty if the description
+    /// says it all
+    fn explanation(&self) -> &'static str {
+        ""
+    }
+
+    /// Severity of the rule's findings unless the `[lint.severity]` table
+    /// says otherwise
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Warning
+    }
+
+    /// Run the rule and append any findings to `diagnostics`
+    fn check(&self, _source_unit: &SourceUnit, _diagnostics: &mut Vec<LintDiagnostic>) {}
+
+    /// Called by the engine with every module item of the source unit,
+    /// including those nested in modules, interfaces, packages and generate
+    /// constructs, parents before their children
+    fn check_item(
+        &self,
+        _source_unit: &SourceUnit,
+        _item: &ModuleItem,
+        _diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+    }
+
+    /// Called by the engine with every statement of the procedural blocks,
+    /// class methods and assertions of the source unit, including nested
+    /// ones, parents before their children
+    fn check_statement(
+        &self,
+        _source_unit: &SourceUnit,
+        _statement: &Statement,
+        _diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+    }
+
+    /// Run the rule over the text of the file, for rules about its layout
+    /// rather than its syntax
+    fn check_text(&self, _text: &str, _diagnostics: &mut Vec<LintDiagnostic>) {}
+
+    /// Run the rule over a file, for rules about the file itself, like its
+    /// name
+    fn check_file(
+        &self,
+        _path: &Path,
+        _source_unit: &SourceUnit,
+        _text: &str,
+        _diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+    }
+
+    /// Whether the rule needs every file of a project-wide run at once, in
+    /// [`check_project`](Self::check_project); the engine only calls that
+    /// for rules that return true
+    fn cross_file(&self) -> bool {
+        false
+    }
+
+    /// Run the rule over all the files of a project-wide run, after the
+    /// per-file checks, appending the findings for `files[i]` to
+    /// `diagnostics[i]`
+    fn check_project(&self, _files: &[LintFile], _diagnostics: &mut [Vec<LintDiagnostic>]) {}
+
+    /// Whether `--lint` runs the rule; style rules that only suit some
+    /// projects return false and run only when selected by name
+    fn enabled_by_default(&self) -> bool {
+        true
+    }
+}
+
+/// Runs a set of lint rules over source units
+pub struct LintEngine {
+    rules: Vec<Box<dyn LintRule>>,
+    /// Severities set by the project, overriding those of the rules
+    severities: HashMap<String, LintSeverity>,
+}
+
+impl LintEngine {
+    /// Create an engine with every built-in rule that is enabled by default
+    pub fn new() -> Self {
+        Self::enabled_by_default(builtin_rules())
+    }
+
+    /// Create an engine with only the named built-in rules enabled
+    ///
+    /// Returns an error naming the first rule that does not exist.
+    pub fn with_rules(names: &[String]) -> Result<Self, String> {
+        Self::selected(builtin_rules(), names)
+    }
+
+    /// Create an engine with the rules of a project configuration: the named
+    /// ones, or every rule enabled by default when `names` is None
+    ///
+    /// Returns an error for an invalid pattern or an unknown rule.
+    pub fn configured(config: &LintConfig, names: Option<&[String]>) -> Result<Self, String> {
+        Self::configured_with(config, names, Vec::new())
+    }
+
+    /// Create an engine like [`configured`](Self::configured), choosing from
+    /// `extra` rules defined outside the parser as well as the built-in ones
+    ///
+    /// Returns an error for an extra rule with the name of another rule, and
+    /// for a severity set for a rule that does not exist.
+    pub fn configured_with(
+        config: &LintConfig,
+        names: Option<&[String]>,
+        extra: Vec<Box<dyn LintRule>>,
+    ) -> Result<Self, String> {
+        let mut rules = configured_rules(config)?;
+        for rule in extra {
+            if rules.iter().any(|existing| existing.name() == rule.name()) {
+                return Err(format!("Lint rule {} is defined twice", rule.name()));
+            }
+            rules.push(rule);
+        }
+        if let Some(name) = config
+            .severity
+            .keys()
+            .find(|name| !rules.iter().any(|rule| rule.name() == name.as_str()))
+        {
+            return Err(format!("Unknown lint rule in [lint.severity]: {}", name));
+        }
+
+        let mut engine = match names {
+            Some(names) => Self::selected(rules, names)?,
+            None => Self::enabled_by_default(rules),
+        };
+        engine.severities = config
+            .severity
+            .iter()
+            .map(|(name, severity)| (name.clone(), *severity))
+            .collect();
+        Ok(engine)
+    }
+
+    /// Enable another rule in this engine
+    ///
+    /// Returns an error if a rule of the same name is already enabled.
+    pub fn register(&mut self, rule: Box<dyn LintRule>) -> Result<(), String> {
+        if self
+            .rules
+            .iter()
+            .any(|existing| existing.name() == rule.name())
+        {
+            return Err(format!("Lint rule {} is defined twice", rule.name()));
+        }
+        self.rules.push(rule);
+        Ok(())
+    }
+
+    fn enabled_by_default(available: Vec<Box<dyn LintRule>>) -> Self {
+        Self {
+            rules: available
+                .into_iter()
+                .filter(|rule| rule.enabled_by_default())
+                .collect(),
+            severities: HashMap::new(),
+        }
+    }
+
+    fn selected(mut available: Vec<Box<dyn LintRule>>, names: &[String]) -> Result<Self, String> {
+        let mut rules = Vec::new();
+
+        for name in names {
+            match available.iter().position(|rule| rule.name() == name) {
+                Some(index) => rules.push(available.remove(index)),
+                None if rules.iter().any(|rule| rule.name() == name) => {}
+                None => return Err(format!("Unknown lint rule: {}", name)),
+            }
+        }
+
+        Ok(Self {
+            rules,
+            severities: HashMap::new(),
+        })
+    }
+
+    /// The rules enabled in this engine
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(|rule| rule.as_ref())
+    }
+
+    /// Severity of the findings of the named rule: the one the project sets,
+    /// or the rule's default
+    pub fn severity(&self, rule: &str) -> LintSeverity {
+        self.severities.get(rule).copied().unwrap_or_else(|| {
+            self.rules
+                .iter()
+                .find(|enabled| enabled.name() == rule)
+                .map_or(LintSeverity::Warning, |enabled| enabled.default_severity())
+        })
+    }
+
+    /// Run all enabled rules over a source unit
+    ///
+    /// Findings are ordered by span, then rule name and message, whatever the
+    /// order of the rules.
+    pub fn run(&self, source_unit: &SourceUnit) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        self.check(source_unit, &mut diagnostics);
+        Self::sorted(diagnostics)
+    }
+
+    /// Run all enabled rules over a source unit and the text it was parsed
+    /// from, including the rules about the layout of the text
+    pub fn run_with_text(&self, source_unit: &SourceUnit, text: &str) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        self.check(source_unit, &mut diagnostics);
+        for rule in &self.rules {
+            rule.check_text(text, &mut diagnostics);
+        }
+        Self::sorted(diagnostics)
+    }
+
+    /// Run all enabled rules over a file: its source unit, the text it was
+    /// parsed from and its path
+    pub fn run_file(
+        &self,
+        path: &Path,
+        source_unit: &SourceUnit,
+        text: &str,
+    ) -> Vec<LintDiagnostic> {
+        let mut diagnostics = Vec::new();
+        self.check(source_unit, &mut diagnostics);
+        for rule in &self.rules {
+            rule.check_text(text, &mut diagnostics);
+            rule.check_file(path, source_unit, text, &mut diagnostics);
+        }
+        Self::sorted(diagnostics)
+    }
+
+    /// Run all enabled rules over the files of a project, several files at a
+    /// time, then run the cross-file rules over all of them
+    ///
+    /// Returns the findings of each file in the order of `files`, ordered as
+    /// by [`run_file`](Self::run_file) whatever the number of threads.
+    pub fn run_files(&self, files: &[LintFile]) -> Vec<Vec<LintDiagnostic>> {
+        let mut diagnostics = parallel::map(files, |file| {
+            self.run_file(file.path, file.source_unit, file.text)
+        });
+        self.run_cross_file(files, &mut diagnostics);
+        diagnostics
+    }
+
+    /// Run the cross-file rules over the files of a project, adding to the
+    /// per-file findings in `diagnostics`, which stay ordered as by
+    /// [`run_file`](Self::run_file)
+    pub fn run_cross_file(&self, files: &[LintFile], diagnostics: &mut [Vec<LintDiagnostic>]) {
+        let mut ran = false;
+        for rule in self.rules.iter().filter(|rule| rule.cross_file()) {
+            rule.check_project(files, diagnostics);
+            ran = true;
+        }
+        if ran {
+            for file_diagnostics in diagnostics {
+                *file_diagnostics = Self::sorted(std::mem::take(file_diagnostics));
+            }
+        }
+    }
+
+    /// Run the rules over a source unit, then walk it once, handing each item
+    /// and statement to every rule
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for rule in &self.rules {
+            rule.check(source_unit, diagnostics);
+        }
+        self.visit_items(source_unit, &source_unit.items, diagnostics);
+    }
+
+    fn visit_items(
+        &self,
+        source_unit: &SourceUnit,
+        items: &[ModuleItemRef],
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        for item_ref in items {
+            let item = source_unit.module_item_arena.get(*item_ref);
+            for rule in &self.rules {
+                rule.check_item(source_unit, item, diagnostics);
+            }
+
+            match item {
+                ModuleItem::ModuleDeclaration { items, .. }
+                | ModuleItem::InterfaceDeclaration { items, .. }
+                | ModuleItem::CheckerDeclaration { items, .. }
+                | ModuleItem::PackageDeclaration { items, .. } => {
+                    self.visit_items(source_unit, items, diagnostics);
+                }
+                ModuleItem::ProceduralBlock { statements, .. } => {
+                    self.visit_statements(source_unit, statements, diagnostics);
+                }
+                ModuleItem::ConcurrentAssertion {
+                    pass_action,
+                    fail_action,
+                    ..
+                } => {
+                    let actions: Vec<StmtRef> =
+                        pass_action.iter().chain(fail_action).copied().collect();
+                    self.visit_statements(source_unit, &actions, diagnostics);
+                }
+                ModuleItem::ClassDeclaration { items, .. }
+                | ModuleItem::InterfaceClassDeclaration { items, .. } => {
+                    for class_item in items {
+                        if let ClassItem::Method { body, .. } = class_item {
+                            self.visit_statements(source_unit, body, diagnostics);
+                        }
+                    }
+                }
+                ModuleItem::OutOfBlockMethod { body, .. } => {
+                    self.visit_statements(source_unit, body, diagnostics);
+                }
+                _ => self.visit_items(source_unit, &item.generate_items(), diagnostics),
+            }
+        }
+    }
+
+    fn visit_statements(
+        &self,
+        source_unit: &SourceUnit,
+        statements: &[StmtRef],
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        for_each_statement(source_unit, statements, &mut |statement| {
+            for rule in &self.rules {
+                rule.check_statement(source_unit, statement, diagnostics);
+            }
+        });
+    }
+
+    fn sorted(mut diagnostics: Vec<LintDiagnostic>) -> Vec<LintDiagnostic> {
+        diagnostics.sort_by(|a, b| (a.span, a.rule, &a.message).cmp(&(b.span, b.rule, &b.message)));
+        diagnostics
+    }
+}
+
+impl Default for LintEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// All rules shipped with the parser, with their default settings
+pub fn builtin_rules() -> Vec<Box<dyn LintRule>> {
+    configured_rules(&LintConfig::default()).expect("default settings are valid")
+}
+
+/// All rules shipped with the parser, with the settings of a `[lint]` table
+///
+/// Returns an error for a pattern in the table that is not a valid regular
+/// expression.
+pub fn configured_rules(config: &LintConfig) -> Result<Vec<Box<dyn LintRule>>, String> {
+    let mut rules: Vec<Box<dyn LintRule>> = vec![
+        Box::new(BlockingInAlwaysFF),
+        Box::new(DuplicateDeclaration),
+        Box::new(ConstantCondition),
+        Box::new(InvalidDimension),
+        Box::new(AscendingRange),
+        Box::new(DeadCode),
+        Box::new(MissingReturn),
+        Box::new(CaseOverlap),
+        Box::new(IfOverlap),
+        Box::new(IncompleteCase),
+        Box::new(NoCasex),
+        Box::new(AssertionClocking),
+        Box::new(ImplicitNet),
+        Box::new(UnmatchedEvent),
+    ];
+    rules.extend(NamingConventions::from_config(&config.naming)?.rules());
+    rules.extend(style::rules(&config.style));
+    rules.extend(policy::rules(&config.file)?);
+    Ok(rules)
+}
+
+/// Call `visit` with the ports and items of every module declaration in the source
+/// unit, including nested ones
+fn for_each_module<'a, F>(source_unit: &'a SourceUnit, mut visit: F)
+where
+    F: FnMut(&'a [Port], &'a [ModuleItemRef]),
+{
+    fn walk<'a, F>(arena: &'a ModuleItemArena, items: &'a [ModuleItemRef], visit: &mut F)
+    where
+        F: FnMut(&'a [Port], &'a [ModuleItemRef]),
+    {
+        for item_ref in items {
+            if let ModuleItem::ModuleDeclaration { ports, items, .. } = arena.get(*item_ref) {
+                visit(ports, items);
+                walk(arena, items, visit);
+            }
+        }
+    }
+
+    walk(
+        &source_unit.module_item_arena,
+        &source_unit.items,
+        &mut visit,
+    );
+}
+
+/// A class method and its statements; the body of an `extern` method is the
+/// one defined outside its class
+struct Method<'a> {
+    class: &'a str,
+    name: &'a str,
+    name_span: Span,
+    return_type: Option<&'a str>,
+    body: &'a [StmtRef],
+}
+
+/// Call `visit` with every class method that has a body in the source unit,
+/// including those of classes inside modules and packages
+fn for_each_method<'a, F>(source_unit: &'a SourceUnit, mut visit: F)
+where
+    F: FnMut(Method<'a>),
+{
+    fn walk<'a, F>(arena: &'a ModuleItemArena, items: &'a [ModuleItemRef], visit: &mut F)
+    where
+        F: FnMut(Method<'a>),
+    {
+        for item_ref in items {
+            match arena.get(*item_ref) {
+                ModuleItem::ModuleDeclaration { items, .. }
+                | ModuleItem::PackageDeclaration { items, .. } => walk(arena, items, visit),
+                ModuleItem::ClassDeclaration { name, items, .. } => {
+                    for item in items {
+                        if let ClassItem::Method {
+                            is_extern: false,
+                            is_pure: false,
+                            name: method,
+                            name_span,
+                            return_type,
+                            body,
+                            ..
+                        } = item
+                        {
+                            visit(Method {
+                                class: name,
+                                name: method,
+                                name_span: *name_span,
+                                return_type: return_type.as_deref(),
+                                body,
+                            });
+                        }
+                    }
+                }
+                ModuleItem::OutOfBlockMethod {
+                    class_name,
+                    name,
+                    name_span,
+                    return_type,
+                    body,
+                    ..
+                } => visit(Method {
+                    class: class_name,
+                    name,
+                    name_span: *name_span,
+                    return_type: return_type.as_deref(),
+                    body,
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    walk(
+        &source_unit.module_item_arena,
+        &source_unit.items,
+        &mut visit,
+    );
+}
+
+/// Call `visit` with each of `statements` and every statement nested in them
+fn for_each_statement<'a, F>(source_unit: &'a SourceUnit, statements: &[StmtRef], visit: &mut F)
+where
+    F: FnMut(&'a Statement),
+{
+    for stmt_ref in statements {
+        let statement = source_unit.stmt_arena.get(*stmt_ref);
+        visit(statement);
+        for_each_statement(source_unit, &statement.children(), visit);
+    }
+}
+
+/// Flags blocking assignments (`=`) inside `always_ff` blocks
+struct BlockingInAlwaysFF;
+
+impl LintRule for BlockingInAlwaysFF {
+    fn name(&self) -> &'static str {
+        "blocking-in-always-ff"
+    }
+
+    fn description(&self) -> &'static str {
+        "Blocking assignment used inside an always_ff block"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "An `always_ff` block models flip-flops, which all take their new value at \
+         the same clock edge. A blocking assignment (`=`) updates its target at \
+         once, so statements after it see the new value, and simulation no longer \
+         matches the hardware synthesized from the block. Use a nonblocking \
+         assignment (`<=`) instead."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_module(source_unit, |_, items| {
+            for item_ref in items {
+                let ModuleItem::ProceduralBlock {
+                    block_type: ProceduralBlockType::AlwaysFF,
+                    statements,
+                    span,
+                    ..
+                } = source_unit.module_item_arena.get(*item_ref)
+                else {
+                    continue;
+                };
+
+                for stmt_ref in statements {
+                    if let Statement::Assignment {
+                        op: AssignmentOp::Assign,
+                        ..
+                    } = source_unit.stmt_arena.get(*stmt_ref)
+                    {
+                        diagnostics.push(LintDiagnostic {
+                            rule: self.name(),
+                            message: "Blocking assignment in always_ff; use '<=' instead"
+                                .to_string(),
+                            span: *span,
+                            fix: None,
+                        });
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Flags names, enum members among them, declared more than once in the same module
+struct DuplicateDeclaration;
+
+impl LintRule for DuplicateDeclaration {
+    fn name(&self) -> &'static str {
+        "duplicate-declaration"
+    }
+
+    fn description(&self) -> &'static str {
+        "Name declared more than once in the same module"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A module declares the same name twice, for example two variables, a port \
+         and a variable, or an enum member and a parameter. Only one of them can be \
+         referred to, and tools disagree about which; rename one of them."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_module(source_unit, |_, items| {
+            // A non-ANSI port declaration may legally be followed by a net
+            // declaration of the same name (`input a; wire a;`), so ports and
+            // variables are tracked separately
+            let mut seen: HashSet<(bool, &str)> = HashSet::new();
+
+            for item_ref in items {
+                let item = source_unit.module_item_arena.get(*item_ref);
+                let (is_port, name, name_span) = match item {
+                    ModuleItem::VariableDeclaration {
+                        name, name_span, ..
+                    }
+                    | ModuleItem::TypedefDeclaration {
+                        name, name_span, ..
+                    } => (false, name.as_str(), *name_span),
+                    ModuleItem::PortDeclaration {
+                        name, name_span, ..
+                    } => (true, name.as_str(), *name_span),
+                    _ => continue,
+                };
+                // The members of an inline enum come before the name they are declared with
+                let members = item
+                    .enum_members()
+                    .iter()
+                    .map(|member| (false, member.name.as_str(), member.name_span));
+
+                for (is_port, name, name_span) in members.chain([(is_port, name, name_span)]) {
+                    if !seen.insert((is_port, name)) {
+                        diagnostics.push(LintDiagnostic {
+                            rule: self.name(),
+                            message: format!("'{}' is already declared in this module", name),
+                            span: name_span,
+                            fix: None,
+                        });
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Flags `case`, case guard and `?:` conditions that are always true or always false
+///
+/// Conditions are folded with [`simplify`](crate::simplify), and comparisons of an
+/// unsigned signal against a constant it is too narrow to hold (`sel == 5` with a
+/// 2-bit `sel`) are caught using the declared widths of the module's signals.
+struct ConstantCondition;
+
+impl LintRule for ConstantCondition {
+    fn name(&self) -> &'static str {
+        "constant-condition"
+    }
+
+    fn description(&self) -> &'static str {
+        "Condition that is always true or always false"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A `case` expression, `case ... matches` guard, `?:` condition or `if` \
+         condition that folds to a constant, so one branch is always taken and the \
+         other never is. This includes comparisons of an unsigned signal with a \
+         constant it is too narrow to hold, like `sel == 5` with a 2-bit `sel`, \
+         which are always false."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_module(source_unit, |ports, items| {
+            let mut walker = ConditionWalker {
+                rule: self.name(),
+                source_unit,
+                widths: unsigned_widths(source_unit, ports, items),
+                diagnostics: &mut *diagnostics,
+            };
+            for item_ref in items {
+                walker.item(source_unit.module_item_arena.get(*item_ref));
+            }
+        });
+    }
+}
+
+/// Bit widths of the module's unsigned scalar and packed-vector signals
+fn unsigned_widths<'a>(
+    source_unit: &'a SourceUnit,
+    ports: &'a [Port],
+    items: &'a [ModuleItemRef],
+) -> HashMap<&'a str, u32> {
+    let arena = &source_unit.module_item_arena;
+    let parameters = parameter_values(items, arena, &source_unit.expr_arena);
+    let bound = |text: &str| {
+        NumberLiteral::parse(text)
+            .or_else(|| parameters.get(text).copied())
+            .and_then(|literal| literal.to_i128())
+    };
+    let width = |range: &Range| {
+        let (msb, lsb) = (bound(&range.msb)?, bound(&range.lsb)?);
+        u32::try_from((msb - lsb).abs() + 1).ok()
+    };
+
+    let mut widths = HashMap::new();
+    // Port types are not recorded, so only ports with an explicit range are known
+    for port in ports {
+        if let Some(width) = port.range.as_ref().and_then(width) {
+            widths.insert(port.name.as_str(), width);
+        }
+    }
+    for item_ref in items {
+        if let ModuleItem::VariableDeclaration {
+            data_type,
+            signing,
+            range,
+            name,
+            unpacked_dimensions,
+            ..
+        } = arena.get(*item_ref)
+        {
+            let integral = matches!(data_type.name(), "logic" | "bit" | "reg" | "wire");
+            if !integral || signing.as_deref() == Some("signed") || !unpacked_dimensions.is_empty()
+            {
+                continue;
+            }
+            let width = match range {
+                Some(range) => width(range),
+                None => Some(1),
+            };
+            if let Some(width) = width {
+                widths.insert(name.as_str(), width);
+            }
+        }
+    }
+    widths
+}
+
+/// Packed ranges declared in a module, with the span of the declared name
+///
+/// Covers ports, variables and parameters; unpacked dimensions are left to the callers.
+fn packed_ranges<'a>(
+    arena: &'a ModuleItemArena,
+    ports: &'a [Port],
+    items: &'a [ModuleItemRef],
+) -> Vec<(&'a Range, Span)> {
+    let mut ranges: Vec<(&Range, Span)> = ports
+        .iter()
+        .filter_map(|port| Some((port.range.as_ref()?, port.name_span)))
+        .collect();
+    for item_ref in items {
+        match arena.get(*item_ref) {
+            ModuleItem::VariableDeclaration {
+                range: Some(range),
+                name_span,
+                ..
+            }
+            | ModuleItem::ParameterDeclaration {
+                range: Some(range),
+                name_span,
+                ..
+            } => ranges.push((range, *name_span)),
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// A range bound or array size, as written and as evaluated with the module's parameters
+fn dimension_value(
+    text: &str,
+    parameters: &HashMap<String, NumberLiteral>,
+) -> Option<NumberLiteral> {
+    NumberLiteral::parse(text).or_else(|| parameters.get(text).copied())
+}
+
+/// `text`, followed by its value when it is a parameter name
+fn describe_dimension(text: &str, value: i128) -> String {
+    if NumberLiteral::parse(text).is_some() {
+        text.to_string()
+    } else {
+        format!("{} ({})", text, value)
+    }
+}
+
+/// Flags range bounds with x or z bits and array sizes that are not positive
+///
+/// Bounds are literals or parameters of the module; anything that does not
+/// evaluate to a constant is not checked.
+struct InvalidDimension;
+
+impl InvalidDimension {
+    fn bound(
+        &self,
+        text: &str,
+        parameters: &HashMap<String, NumberLiteral>,
+        span: Span,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        if dimension_value(text, parameters).is_some_and(|value| value.value.is_none()) {
+            diagnostics.push(LintDiagnostic {
+                rule: self.name(),
+                message: format!("Range bound {} has x or z bits", text),
+                span,
+                fix: None,
+            });
+        }
+    }
+
+    fn size(
+        &self,
+        text: &str,
+        parameters: &HashMap<String, NumberLiteral>,
+        span: Span,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        let Some(value) = dimension_value(text, parameters) else {
+            return;
+        };
+        let message = match value.to_i128() {
+            None => format!("Array size {} has x or z bits", text),
+            Some(size) if size <= 0 => {
+                format!(
+                    "Array size {} must be positive",
+                    describe_dimension(text, size)
+                )
+            }
+            Some(_) => return,
+        };
+        diagnostics.push(LintDiagnostic {
+            rule: self.name(),
+            message,
+            span,
+            fix: None,
+        });
+    }
+}
+
+impl LintRule for InvalidDimension {
+    fn name(&self) -> &'static str {
+        "invalid-dimension"
+    }
+
+    fn description(&self) -> &'static str {
+        "Range bound with x/z bits or array size that is not positive"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A range bound with x or z bits, like `[4'bx:0]`, has no value, and an \
+         array size of zero or less, like `[0]`, declares no elements. Bounds \
+         written as literals or module parameters are checked; other expressions \
+         are not."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let arena = &source_unit.module_item_arena;
+        for_each_module(source_unit, |ports, items| {
+            let parameters = parameter_values(items, arena, &source_unit.expr_arena);
+            for (range, span) in packed_ranges(arena, ports, items) {
+                self.bound(&range.msb, &parameters, span, diagnostics);
+                self.bound(&range.lsb, &parameters, span, diagnostics);
+            }
+            for item_ref in items {
+                let ModuleItem::VariableDeclaration {
+                    unpacked_dimensions,
+                    name_span,
+                    ..
+                } = arena.get(*item_ref)
+                else {
+                    continue;
+                };
+                for dimension in unpacked_dimensions {
+                    match dimension {
+                        UnpackedDimension::Dynamic => {}
+                        UnpackedDimension::FixedSize(size) => {
+                            self.size(size, &parameters, *name_span, diagnostics)
+                        }
+                        UnpackedDimension::Range(msb, lsb) => {
+                            self.bound(msb, &parameters, *name_span, diagnostics);
+                            self.bound(lsb, &parameters, *name_span, diagnostics);
+                        }
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Flags packed ranges written in ascending order (`[0:7]`)
+///
+/// Off by default, for projects whose style asks for descending packed
+/// ranges. Unpacked dimensions are not checked, since `mem[0:DEPTH-1]` is the
+/// usual way to declare a memory.
+struct AscendingRange;
+
+impl LintRule for AscendingRange {
+    fn name(&self) -> &'static str {
+        "ascending-range"
+    }
+
+    fn description(&self) -> &'static str {
+        "Packed range with its msb below its lsb"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A packed range written low to high, like `[0:7]`, makes bit 0 the most \
+         significant bit, the opposite of the usual convention. The rule is off by \
+         default and suits projects whose style asks for descending packed ranges. \
+         Unpacked dimensions are not checked, since `mem[0:DEPTH-1]` is the usual \
+         way to declare a memory."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let arena = &source_unit.module_item_arena;
+        for_each_module(source_unit, |ports, items| {
+            let parameters = parameter_values(items, arena, &source_unit.expr_arena);
+            let bound = |text: &str| dimension_value(text, &parameters)?.to_i128();
+            for (range, span) in packed_ranges(arena, ports, items) {
+                let (Some(msb), Some(lsb)) = (bound(&range.msb), bound(&range.lsb)) else {
+                    continue;
+                };
+                if msb < lsb {
+                    diagnostics.push(LintDiagnostic {
+                        rule: self.name(),
+                        message: format!(
+                            "Ascending range [{}:{}]; declare it as [{}:{}]",
+                            range.msb, range.lsb, range.lsb, range.msb
+                        ),
+                        span,
+                        fix: None,
+                    });
+                }
+            }
+        });
+    }
+}
+
+/// Flags code that can never run: the statement after a `return`, `$finish`,
+/// `$exit` or `$fatal`, or after a `disable` of a block it is in, and
+/// `case ... matches` arms whose guard is always false or that follow an arm
+/// matching any value
+///
+/// Off by default, since code left unreachable while debugging is common.
+struct DeadCode;
+
+impl LintRule for DeadCode {
+    fn name(&self) -> &'static str {
+        "dead-code"
+    }
+
+    fn description(&self) -> &'static str {
+        "Statement or case arm that can never run"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "Code that can never run: statements after a `return`, `$finish`, `$exit` \
+         or `$fatal`, or after a `disable` of a block they are in, and \
+         `case ... matches` arms whose guard is always false or that follow an arm \
+         matching any value. The rule is off by default, since code left \
+         unreachable while debugging is common."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        false
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_module(source_unit, |_, items| {
+            let mut walker = DeadCodeWalker {
+                rule: self.name(),
+                source_unit,
+                diagnostics: &mut *diagnostics,
+            };
+            for item_ref in items {
+                if let ModuleItem::ProceduralBlock {
+                    statements, label, ..
+                } = source_unit.module_item_arena.get(*item_ref)
+                {
+                    walker.block(label.as_ref(), statements, &mut Vec::new());
+                }
+            }
+        });
+        for_each_method(source_unit, |method| {
+            let mut walker = DeadCodeWalker {
+                rule: self.name(),
+                source_unit,
+                diagnostics: &mut *diagnostics,
+            };
+            walker.statements(method.body, &mut Vec::new());
+        });
+    }
+}
+
+/// Flags functions with a return type that can reach `endfunction` without
+/// assigning to the function name or returning a value
+struct MissingReturn;
+
+impl LintRule for MissingReturn {
+    fn name(&self) -> &'static str {
+        "missing-return"
+    }
+
+    fn description(&self) -> &'static str {
+        "Function that can end without returning a value"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A function with a return type that can reach `endfunction` without \
+         returning a value or assigning to the function name returns an undefined \
+         value on that path. Every branch of an `if` or `case` has to return for the \
+         statement to count, and a `case` needs a `default`."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_method(source_unit, |method| {
+            if !returns_value(method.name, method.return_type) {
+                return;
+            }
+            let returns = method
+                .body
+                .iter()
+                .any(|stmt_ref| always_returns(source_unit, *stmt_ref, method.name));
+            if !returns {
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: format!(
+                        "Function '{}::{}' can end without returning a value",
+                        method.class, method.name
+                    ),
+                    span: method.name_span,
+                    fix: None,
+                });
+            }
+        });
+    }
+}
+
+/// Whether every path through a statement sets the return value of function
+/// `function`, or ends the simulation
+fn always_returns(source_unit: &SourceUnit, stmt_ref: StmtRef, function: &str) -> bool {
+    match source_unit.stmt_arena.get(stmt_ref) {
+        Statement::Return { value, .. } => value.is_some(),
+        Statement::Assignment {
+            target,
+            op: AssignmentOp::Assign,
+            ..
+        } => matches!(
+            source_unit.expr_arena.get(*target),
+            Expression::Identifier(name, _) if name == function
+        ),
+        Statement::SystemCall { name, .. } => matches!(name.as_str(), "finish" | "exit" | "fatal"),
+        Statement::Block { statements, .. } => statements
+            .iter()
+            .any(|stmt_ref| always_returns(source_unit, *stmt_ref, function)),
+        Statement::Labeled { statement, .. }
+        | Statement::Timed {
+            statement: Some(statement),
+            ..
+        } => always_returns(source_unit, *statement, function),
+        // Both branches have to return, so there has to be an else
+        Statement::Conditional {
+            then_statement,
+            else_statement: Some(else_statement),
+            ..
+        } => {
+            always_returns(source_unit, *then_statement, function)
+                && always_returns(source_unit, *else_statement, function)
+        }
+        // Every item has to return, and without a default none may be taken
+        Statement::CaseStatement { items, .. } => {
+            items.iter().any(|item| item.values.is_empty())
+                && items
+                    .iter()
+                    .all(|item| always_returns(source_unit, item.statement, function))
+        }
+        // Every arm has to return, and one has to be taken whatever the value
+        Statement::CaseMatches { items, .. } => {
+            let exhaustive = items.iter().any(|item| {
+                item.guard.is_none()
+                    && matches!(
+                        item.pattern,
+                        None | Some(Pattern::Wildcard(_) | Pattern::Variable(..))
+                    )
+            });
+            exhaustive
+                && items
+                    .iter()
+                    .all(|item| always_returns(source_unit, item.statement, function))
+        }
+        _ => false,
+    }
+}
+
+/// Walks one module looking for constant conditions
+struct ConditionWalker<'a, 'd> {
+    rule: &'static str,
+    source_unit: &'a SourceUnit,
+    widths: HashMap<&'a str, u32>,
+    diagnostics: &'d mut Vec<LintDiagnostic>,
+}
+
+impl ConditionWalker<'_, '_> {
+    fn item(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::ProceduralBlock { statements, .. } => {
+                for stmt_ref in statements {
+                    self.statement(self.source_unit.stmt_arena.get(*stmt_ref));
+                }
+            }
+            ModuleItem::Assignment { expr, span, .. }
+            | ModuleItem::VariableDeclaration {
+                initial_value: Some(expr),
+                span,
+                ..
+            } => self.expression(*expr, *span),
+            _ => {}
+        }
+    }
+
+    fn statement(&mut self, statement: &Statement) {
+        let stmt_arena = &self.source_unit.stmt_arena;
+        match statement {
+            Statement::Assignment { expr, span, .. }
+            | Statement::ExpressionStatement { expr, span }
+            | Statement::VariableDeclaration {
+                initial_value: Some(expr),
+                span,
+                ..
+            } => self.expression(*expr, *span),
+            Statement::SystemCall { args, span, .. } => {
+                for arg in args {
+                    self.expression(*arg, *span);
+                }
+            }
+            Statement::Conditional {
+                condition,
+                then_statement,
+                else_statement,
+                span,
+                ..
+            } => {
+                self.condition(*condition, "If condition", *span);
+                self.expression(*condition, *span);
+                self.statement(stmt_arena.get(*then_statement));
+                if let Some(else_statement) = else_statement {
+                    self.statement(stmt_arena.get(*else_statement));
+                }
+            }
+            Statement::CaseStatement {
+                expr, items, span, ..
+            } => {
+                // `case (1'b1)` is the usual way to write a priority "reverse case"
+                if !matches!(
+                    self.source_unit.expr_arena.get(*expr),
+                    Expression::Number(..)
+                ) {
+                    self.condition(*expr, "Case expression", *span);
+                }
+                self.expression(*expr, *span);
+                for item in items {
+                    self.statement(stmt_arena.get(item.statement));
+                }
+            }
+            Statement::CaseMatches {
+                expr, items, span, ..
+            } => {
+                self.expression(*expr, *span);
+                for item in items {
+                    if let Some(guard) = item.guard {
+                        self.condition(guard, "Case guard", item.span);
+                        self.expression(guard, item.span);
+                    }
+                    self.statement(stmt_arena.get(item.statement));
+                }
+            }
+            Statement::Block { statements, .. } => {
+                for stmt_ref in statements {
+                    self.statement(stmt_arena.get(*stmt_ref));
+                }
+            }
+            Statement::Labeled { statement, .. } => self.statement(stmt_arena.get(*statement)),
+            // `assert (0)` marks code that should never run, so assertion
+            // conditions are not checked
+            Statement::ImmediateAssertion {
+                condition,
+                pass_action,
+                fail_action,
+                span,
+                ..
+            } => {
+                self.expression(*condition, *span);
+                for action in pass_action.iter().chain(fail_action) {
+                    self.statement(stmt_arena.get(*action));
+                }
+            }
+            // `while (1)` and `for (;;)` loop on purpose, so loop conditions
+            // are not checked
+            Statement::ForLoop {
+                init,
+                condition,
+                step,
+                body,
+                span,
+            } => {
+                if let Some(condition) = condition {
+                    self.expression(*condition, *span);
+                }
+                for stmt_ref in init.iter().chain(step).chain(std::iter::once(body)) {
+                    self.statement(stmt_arena.get(*stmt_ref));
+                }
+            }
+            Statement::While {
+                condition,
+                body,
+                span,
+            }
+            | Statement::DoWhile {
+                body,
+                condition,
+                span,
+            }
+            | Statement::Repeat {
+                count: condition,
+                body,
+                span,
+            } => {
+                self.expression(*condition, *span);
+                self.statement(stmt_arena.get(*body));
+            }
+            Statement::Forever { body, .. }
+            | Statement::Foreach { body, .. }
+            | Statement::Timed {
+                statement: Some(body),
+                ..
+            } => self.statement(stmt_arena.get(*body)),
+            _ => {}
+        }
+    }
+
+    /// Check the `?:` conditions inside an expression; `span` is that of the enclosing
+    /// statement, since expressions don't carry reliable spans
+    fn expression(&mut self, expr: ExprRef, span: Span) {
+        match self.source_unit.expr_arena.get(expr) {
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                self.condition(*condition, "Condition", span);
+                for operand in [condition, then_expr, else_expr] {
+                    self.expression(*operand, span);
+                }
+            }
+            Expression::Binary { left, right, .. }
+            | Expression::ValueRange {
+                low: left,
+                high: right,
+                ..
+            } => {
+                self.expression(*left, span);
+                self.expression(*right, span);
+            }
+            Expression::Unary { operand: inner, .. }
+            | Expression::IncDec { operand: inner, .. }
+            | Expression::MemberAccess { object: inner, .. }
+            | Expression::TaggedUnion {
+                value: Some(inner), ..
+            } => self.expression(*inner, span),
+            Expression::MacroUsage { arguments, .. }
+            | Expression::SystemFunctionCall { arguments, .. }
+            | Expression::New { arguments, .. }
+            | Expression::FunctionCall { arguments, .. } => {
+                for argument in arguments {
+                    self.expression(*argument, span);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn condition(&mut self, expr: ExprRef, what: &str, span: Span) {
+        let message = if let Some(message) = self.out_of_range_comparison(expr, what) {
+            message
+        } else {
+            let simplified = simplify(expr, &self.source_unit.expr_arena);
+            let Some(value) = simplified.truth_value() else {
+                return;
+            };
+            format!("{} is always {} (folds to {})", what, value, simplified)
+        };
+        self.diagnostics.push(LintDiagnostic {
+            rule: self.rule,
+            message,
+            span,
+            fix: None,
+        });
+    }
+
+    /// A comparison between an unsigned signal and a constant wider than it, whose
+    /// outcome is therefore fixed
+    fn out_of_range_comparison(&self, expr: ExprRef, what: &str) -> Option<String> {
+        let arena = &self.source_unit.expr_arena;
+        let Expression::Binary {
+            op, left, right, ..
+        } = arena.get(expr)
+        else {
+            return None;
+        };
+
+        // Normalize to `signal <op> constant`, mirroring the operator if needed
+        let (name, constant, op) = match (arena.get(*left), arena.get(*right)) {
+            (Expression::Identifier(name, _), _) => (name, *right, op.clone()),
+            (_, Expression::Identifier(name, _)) => {
+                let mirrored = match op {
+                    BinaryOp::LessThan => BinaryOp::GreaterThan,
+                    BinaryOp::GreaterThan => BinaryOp::LessThan,
+                    BinaryOp::LessEqual => BinaryOp::GreaterEqual,
+                    BinaryOp::GreaterEqual => BinaryOp::LessEqual,
+                    other => other.clone(),
+                };
+                (name, *left, mirrored)
+            }
+            _ => return None,
+        };
+
+        let width = *self.widths.get(name.as_str())?;
+        let value = simplify(constant, arena).constant()?.to_i128()?;
+        if width >= 127 || value < 0 || value < 1 << width {
+            return None;
+        }
+
+        let result = match op {
+            BinaryOp::Equal | BinaryOp::CaseEqual | BinaryOp::WildcardEqual => false,
+            BinaryOp::NotEqual | BinaryOp::CaseNotEqual | BinaryOp::WildcardNotEqual => true,
+            BinaryOp::LessThan | BinaryOp::LessEqual => true,
+            BinaryOp::GreaterThan | BinaryOp::GreaterEqual => false,
+            _ => return None,
+        };
+        Some(format!(
+            "{} is always {}: '{}' is {} bits wide and can never hold {}",
+            what, result, name, width, value
+        ))
+    }
+}
+
+/// A statement after which the rest of its block never runs
+enum Exit<'a> {
+    /// `return` from a function
+    Return,
+    /// `$finish`, `$exit` or `$fatal`, with the task name
+    Simulation(&'a str),
+    /// `disable name` of an enclosing block
+    Disable(&'a str),
+}
+
+impl std::fmt::Display for Exit<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Exit::Return => write!(f, "return"),
+            Exit::Simulation(name) => write!(f, "${}", name),
+            Exit::Disable(target) => write!(f, "disable {}", target),
+        }
+    }
+}
+
+/// Walks the procedural code of one module looking for code that can never run
+struct DeadCodeWalker<'a, 'd> {
+    rule: &'static str,
+    source_unit: &'a SourceUnit,
+    diagnostics: &'d mut Vec<LintDiagnostic>,
+}
+
+impl<'a> DeadCodeWalker<'a, '_> {
+    /// Check a sequence of statements inside the blocks named `labels`, and
+    /// return how it exits if it always does
+    fn statements(
+        &mut self,
+        statements: &'a [StmtRef],
+        labels: &mut Vec<&'a str>,
+    ) -> Option<Exit<'a>> {
+        let source_unit = self.source_unit;
+        let stmt_arena = &source_unit.stmt_arena;
+        for (index, stmt_ref) in statements.iter().enumerate() {
+            let Some(exit) = self.statement(stmt_arena.get(*stmt_ref), labels) else {
+                continue;
+            };
+            if let Some(next) = statements.get(index + 1) {
+                self.push(
+                    stmt_arena.get(*next).span(),
+                    format!("Statement is never reached after {}", exit),
+                );
+            }
+            return Some(exit);
+        }
+        None
+    }
+
+    fn statement(
+        &mut self,
+        statement: &'a Statement,
+        labels: &mut Vec<&'a str>,
+    ) -> Option<Exit<'a>> {
+        let source_unit = self.source_unit;
+        let stmt_arena = &source_unit.stmt_arena;
+        match statement {
+            Statement::Return { .. } => Some(Exit::Return),
+            Statement::SystemCall { name, .. }
+                if matches!(name.as_str(), "finish" | "exit" | "fatal") =>
+            {
+                Some(Exit::Simulation(name))
+            }
+            // Disabling a task or a block elsewhere doesn't end this one
+            Statement::Disable { target, .. } if labels.contains(&target.as_str()) => {
+                Some(Exit::Disable(target))
+            }
+            Statement::Block {
+                label, statements, ..
+            } => self.block(label.as_ref(), statements, labels),
+            Statement::Labeled {
+                label, statement, ..
+            } => match stmt_arena.get(*statement) {
+                Statement::Block {
+                    label: None,
+                    statements,
+                    ..
+                } => self.block(Some(label), statements, labels),
+                statement => self.statement(statement, labels),
+            },
+            Statement::Timed {
+                statement: Some(statement),
+                ..
+            } => self.statement(stmt_arena.get(*statement), labels),
+            Statement::Conditional {
+                then_statement,
+                else_statement,
+                ..
+            } => {
+                self.statement(stmt_arena.get(*then_statement), labels);
+                if let Some(else_statement) = else_statement {
+                    self.statement(stmt_arena.get(*else_statement), labels);
+                }
+                None
+            }
+            Statement::CaseStatement { items, .. } => {
+                for item in items {
+                    self.statement(stmt_arena.get(item.statement), labels);
+                }
+                None
+            }
+            Statement::CaseMatches { items, .. } => {
+                self.case_arms(items, labels);
+                None
+            }
+            Statement::AssertProperty {
+                pass_action,
+                fail_action,
+                ..
+            }
+            | Statement::ImmediateAssertion {
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                for action in pass_action.iter().chain(fail_action) {
+                    self.statement(stmt_arena.get(*action), labels);
+                }
+                None
+            }
+            // A loop body is checked on its own, not whether the loop itself ends
+            Statement::ForLoop { body, .. }
+            | Statement::While { body, .. }
+            | Statement::DoWhile { body, .. }
+            | Statement::Repeat { body, .. }
+            | Statement::Forever { body, .. }
+            | Statement::Foreach { body, .. } => {
+                self.statement(stmt_arena.get(*body), labels);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn block(
+        &mut self,
+        label: Option<&'a Label>,
+        statements: &'a [StmtRef],
+        labels: &mut Vec<&'a str>,
+    ) -> Option<Exit<'a>> {
+        if let Some(label) = label {
+            labels.push(&label.name);
+        }
+        let exit = self.statements(statements, labels);
+        if label.is_some() {
+            labels.pop();
+        }
+        match exit {
+            // Disabling the block itself carries on after it
+            Some(Exit::Disable(target)) if label.is_some_and(|label| label.name == target) => None,
+            exit => exit,
+        }
+    }
+
+    /// Check the arms of a `case ... matches`, which are tried in order, so an
+    /// arm after one matching any value without a guard is never taken; the
+    /// `default` arm is tried last wherever it is written
+    fn case_arms(&mut self, items: &'a [CaseMatchesItem], labels: &mut Vec<&'a str>) {
+        let source_unit = self.source_unit;
+        let mut matched_all = false;
+        for item in items {
+            if matched_all {
+                self.push(
+                    item.span,
+                    "Case arm is never taken: an earlier arm matches any value".to_string(),
+                );
+                continue;
+            }
+            let guard = item
+                .guard
+                .map(|guard| simplify(guard, &source_unit.expr_arena).truth_value());
+            if guard == Some(Some(false)) {
+                self.push(
+                    item.span,
+                    "Case arm is never taken: its guard is always false".to_string(),
+                );
+                continue;
+            }
+            matched_all = matches!(
+                item.pattern,
+                Some(Pattern::Wildcard(_) | Pattern::Variable(..))
+            ) && matches!(guard, None | Some(Some(true)));
+            self.statement(source_unit.stmt_arena.get(item.statement), labels);
+        }
+    }
+
+    fn push(&mut self, span: Span, message: String) {
+        self.diagnostics.push(LintDiagnostic {
+            rule: self.rule,
+            message,
+            span,
+            fix: None,
+        });
+    }
+}
+
+/// Splits allowed when working out which values the items of one case
+/// statement cover, before giving up on it
+const MAX_COVERAGE_STEPS: usize = 10_000;
+
+/// A `case`, `casez` or `casex` statement found by [`for_each_case`]
+struct Case<'a> {
+    modifier: Option<&'a str>,
+    case_type: &'a str,
+    inside: bool,
+    expr: ExprRef,
+    items: &'a [CaseItem],
+    span: Span,
+}
+
+impl Case<'_> {
+    /// The case type whose wildcards the item values have: a `case ... inside`
+    /// compares with `==?`, where x and z bits of the item match anything, as
+    /// in a `casex`
+    fn wildcard_type(&self) -> &str {
+        if self.inside {
+            "casex"
+        } else {
+            self.case_type
+        }
+    }
+}
+
+/// What the enclosing module tells about the values in a case statement
+#[derive(Default)]
+struct CaseContext<'a> {
+    widths: HashMap<&'a str, u32>,
+    parameters: HashMap<String, NumberLiteral>,
+}
+
+impl CaseContext<'_> {
+    /// The name and width of a case expression that is an unsigned signal of
+    /// known width, up to 128 bits
+    fn signal<'e>(&self, arena: &'e ExprArena, expr: ExprRef) -> Option<(&'e str, u32)> {
+        let Expression::Identifier(name, _) = arena.get(expr) else {
+            return None;
+        };
+        let width = *self.widths.get(name.as_str())?;
+        (width <= 128).then_some((name.as_str(), width))
+    }
+
+    /// The bits of a case item value; None if it is not a constant
+    fn pattern(&self, arena: &ExprArena, value: ExprRef) -> Option<BitPattern> {
+        match arena.get(value) {
+            Expression::Number(text, _) => BitPattern::parse(text),
+            _ => BitPattern::from_literal(&evaluate_constant(value, arena, &self.parameters)?),
+        }
+    }
+}
+
+/// Call `visit` with the statements of every procedural block of a module and
+/// every class method, and what the enclosing module tells about their values
+fn for_each_procedure<'a, F>(source_unit: &'a SourceUnit, mut visit: F)
+where
+    F: FnMut(&CaseContext<'a>, &'a [StmtRef]),
+{
+    for_each_module(source_unit, |ports, items| {
+        let arena = &source_unit.module_item_arena;
+        let context = CaseContext {
+            widths: unsigned_widths(source_unit, ports, items),
+            parameters: parameter_values(items, arena, &source_unit.expr_arena),
+        };
+        for item_ref in items {
+            if let ModuleItem::ProceduralBlock { statements, .. } = arena.get(*item_ref) {
+                visit(&context, statements);
+            }
+        }
+    });
+    let context = CaseContext::default();
+    for_each_method(source_unit, |method| visit(&context, method.body));
+}
+
+/// Call `visit` with every `case`, `casez` and `casex` statement in the
+/// procedural blocks of modules and in class methods
+fn for_each_case<'a, F>(source_unit: &'a SourceUnit, mut visit: F)
+where
+    F: FnMut(&CaseContext<'a>, Case<'a>),
+{
+    for_each_procedure(source_unit, |context, statements| {
+        for_each_statement(source_unit, statements, &mut |statement| {
+            if let Statement::CaseStatement {
+                modifier,
+                case_type,
+                inside,
+                expr,
+                items,
+                span,
+            } = statement
+            {
+                let case = Case {
+                    modifier: modifier.as_deref(),
+                    case_type,
+                    inside: *inside,
+                    expr: *expr,
+                    items,
+                    span: *span,
+                };
+                visit(context, case);
+            }
+        });
+    });
+}
+
+/// An `if` statement and the `else if` statements after it, found by [`for_each_if`]
+struct IfChain<'a> {
+    modifier: Option<&'a str>,
+    /// The condition of each `if`, with the span of its statement
+    branches: Vec<(ExprRef, Span)>,
+}
+
+/// Call `visit` with every chain of `if` and `else if` statements in the
+/// procedural blocks of modules and in class methods
+///
+/// An `else if` with a modifier of its own starts a new chain.
+fn for_each_if<'a, F>(source_unit: &'a SourceUnit, mut visit: F)
+where
+    F: FnMut(&CaseContext<'a>, IfChain<'a>),
+{
+    let stmt_arena = &source_unit.stmt_arena;
+    for_each_procedure(source_unit, |context, statements| {
+        // Statements already visited as an `else if` of the chain before them
+        let mut else_ifs = HashSet::new();
+        for_each_statement(source_unit, statements, &mut |statement| {
+            let Statement::Conditional {
+                modifier,
+                condition,
+                else_statement,
+                span,
+                ..
+            } = statement
+            else {
+                return;
+            };
+            if else_ifs.contains(span) {
+                return;
+            }
+            let mut branches = vec![(*condition, *span)];
+            let mut next = *else_statement;
+            while let Some(Statement::Conditional {
+                modifier: None,
+                condition,
+                else_statement,
+                span,
+                ..
+            }) = next.map(|stmt_ref| stmt_arena.get(stmt_ref))
+            {
+                else_ifs.insert(*span);
+                branches.push((*condition, *span));
+                next = *else_statement;
+            }
+            let chain = IfChain {
+                modifier: modifier.as_deref(),
+                branches,
+            };
+            visit(context, chain);
+        });
+    });
+}
+
+/// The values of a case expression that a case item value matches: those
+/// equal to `value` in the bits of `care`
+#[derive(Debug, Clone, Copy)]
+struct Cube {
+    care: u128,
+    value: u128,
+}
+
+impl Cube {
+    /// The values of a `width`-bit case expression that an item value matches
+    /// in a case statement of type `case_type`; None if it matches none
+    fn new(pattern: &BitPattern, case_type: &str, width: u32) -> Option<Self> {
+        let wildcards = pattern.wildcards(case_type);
+        // An x or z bit that is compared only matches an x or z, never a 0 or 1
+        if (pattern.x | pattern.z) & !wildcards != 0 {
+            return None;
+        }
+        let care = !wildcards & mask(pattern.width.max(width));
+        // The case expression is extended with zeros, so a 1 above it never matches
+        if width < 128 && (pattern.value & care) >> width != 0 {
+            return None;
+        }
+        Some(Self {
+            care: care & mask(width),
+            value: pattern.value & care & mask(width),
+        })
+    }
+
+    /// Whether some value matches both cubes
+    fn intersects(&self, other: &Cube) -> bool {
+        (self.value ^ other.value) & self.care & other.care == 0
+    }
+
+    /// Whether every value `other` matches, this one matches too
+    fn contains(&self, other: &Cube) -> bool {
+        self.care & !other.care == 0 && self.intersects(other)
+    }
+}
+
+/// Whether the values of a cube are all matched by a set of cubes
+enum Coverage {
+    Covered,
+    /// One of the values none of them match
+    Missing(u128),
+    /// Too many splits were needed to tell
+    Unknown,
+}
+
+/// Which values of `cube` `cubes` match
+///
+/// Splits `cube` on the bits the others compare until each part is inside one
+/// of them or outside all of them, counting the splits down in `steps`.
+fn coverage(cube: Cube, cubes: &[Cube], steps: &mut usize) -> Coverage {
+    let cubes: Vec<Cube> = cubes
+        .iter()
+        .copied()
+        .filter(|other| other.intersects(&cube))
+        .collect();
+    let Some(first) = cubes.first() else {
+        return Coverage::Missing(cube.value);
+    };
+    if cubes.iter().any(|other| other.contains(&cube)) {
+        return Coverage::Covered;
+    }
+    if *steps == 0 {
+        return Coverage::Unknown;
+    }
+    *steps -= 1;
+
+    // `first` compares a bit that `cube` doesn't, or it would contain it
+    let split = first.care & !cube.care;
+    let bit = split & split.wrapping_neg();
+    for value in [cube.value, cube.value | bit] {
+        let half = Cube {
+            care: cube.care | bit,
+            value,
+        };
+        match coverage(half, &cubes, steps) {
+            Coverage::Covered => {}
+            other => return other,
+        }
+    }
+    Coverage::Covered
+}
+
+/// A value as a sized binary literal, e.g. `4'b0110`
+fn binary(value: u128, width: u32) -> String {
+    format!("{}'b{:0digits$b}", width, value, digits = width as usize)
+}
+
+/// Flags case items that can never be taken, because the items before them
+/// match every value they match, and items of `unique` and `unique0` cases
+/// that match a value an earlier item also matches
+///
+/// Item values are compared bit by bit, treating z and `?` bits as wildcards
+/// in a `casez` and x and z bits as wildcards in a `casex`. Values that are
+/// not constants are not checked.
+struct CaseOverlap;
+
+impl LintRule for CaseOverlap {
+    fn name(&self) -> &'static str {
+        "case-overlap"
+    }
+
+    fn description(&self) -> &'static str {
+        "Case item that overlaps an earlier one"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A case item that can never be taken, because the items before it match \
+         every value it matches, or an item of a `unique` or `unique0` case that \
+         matches a value an earlier item also matches, which makes the `unique` \
+         check fail in simulation. Item values are compared bit by bit, with the \
+         wildcards of `casez`, `casex` and `case ... inside`; values that aren't \
+         constants are not checked."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let arena = &source_unit.expr_arena;
+        for_each_case(source_unit, |context, case| {
+            let patterns: Vec<Vec<Option<BitPattern>>> = case
+                .items
+                .iter()
+                .map(|item| {
+                    item.values
+                        .iter()
+                        .map(|value| context.pattern(arena, *value))
+                        .collect()
+                })
+                .collect();
+            let width = match context.signal(arena, case.expr) {
+                Some((_, width)) => width,
+                None => patterns
+                    .iter()
+                    .flatten()
+                    .flatten()
+                    .map(|pattern| pattern.width)
+                    .max()
+                    .unwrap_or(1),
+            };
+
+            let mut steps = MAX_COVERAGE_STEPS;
+            let mut earlier: Vec<Cube> = Vec::new();
+            for (item, patterns) in case.items.iter().zip(&patterns) {
+                let cubes: Vec<Option<Cube>> = patterns
+                    .iter()
+                    .map(|pattern| Cube::new(pattern.as_ref()?, case.wildcard_type(), width))
+                    .collect();
+                let never_taken = !cubes.is_empty()
+                    && cubes.iter().all(|cube| {
+                        cube.is_some_and(|cube| {
+                            matches!(coverage(cube, &earlier, &mut steps), Coverage::Covered)
+                        })
+                    });
+                let overlap = cubes.iter().flatten().find_map(|cube| {
+                    earlier
+                        .iter()
+                        .find(|other| other.intersects(cube))
+                        .map(|other| other.value | cube.value)
+                });
+
+                let message = match (case.modifier, overlap) {
+                    _ if never_taken => Some(
+                        "Case item is never taken: earlier items match every value it matches"
+                            .to_string(),
+                    ),
+                    (Some(modifier @ ("unique" | "unique0")), Some(value)) => Some(format!(
+                        "Case item overlaps an earlier item of this {} {}: both match {}",
+                        modifier,
+                        case.case_type,
+                        binary(value, width)
+                    )),
+                    _ => None,
+                };
+                if let Some(message) = message {
+                    diagnostics.push(LintDiagnostic {
+                        rule: self.name(),
+                        message,
+                        span: item.span,
+                        fix: None,
+                    });
+                }
+                earlier.extend(cubes.into_iter().flatten());
+            }
+        });
+    }
+}
+
+/// The signal, value and case type an `if` condition tests the way a case item
+/// does: `sel == 2'b01` like an item of a `case` on `sel`, and `sel ==? 2'b1x`
+/// like one of a `casex`
+fn case_like<'e>(
+    context: &CaseContext<'_>,
+    arena: &'e ExprArena,
+    condition: ExprRef,
+) -> Option<(&'e str, u32, BitPattern, &'static str)> {
+    let Expression::Binary {
+        op, left, right, ..
+    } = arena.get(condition)
+    else {
+        return None;
+    };
+    let case_type = match op {
+        BinaryOp::Equal | BinaryOp::CaseEqual => "case",
+        // Only the x and z bits of the right operand are wildcards
+        BinaryOp::WildcardEqual => "casex",
+        _ => return None,
+    };
+    let ((name, width), value) = match context.signal(arena, *left) {
+        Some(signal) => (signal, *right),
+        None if case_type == "case" => (context.signal(arena, *right)?, *left),
+        None => return None,
+    };
+    Some((name, width, context.pattern(arena, value)?, case_type))
+}
+
+/// Flags branches of an `if` and `else if` chain that can never be taken,
+/// because the branches before them match every value they match, and
+/// branches of `unique` and `unique0` ifs that match a value an earlier branch
+/// also matches
+///
+/// Only chains whose conditions all compare one signal with a constant are
+/// checked, reading each comparison as a case item: `==` and `===` as items of
+/// a `case`, `==?` as items of a `casex`.
+struct IfOverlap;
+
+impl LintRule for IfOverlap {
+    fn name(&self) -> &'static str {
+        "if-overlap"
+    }
+
+    fn description(&self) -> &'static str {
+        "If branch that overlaps an earlier one"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A branch of an `if` and `else if` chain that can never be taken, because \
+         the conditions before it match every value it matches, or a branch of a \
+         `unique` or `unique0` if that overlaps an earlier one. Only chains whose \
+         conditions all compare one signal with a constant are checked, reading \
+         `==` and `===` as items of a `case` and `==?` as items of a `casex`."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let arena = &source_unit.expr_arena;
+        for_each_if(source_unit, |context, chain| {
+            let mut tests = Vec::new();
+            for (condition, span) in &chain.branches {
+                let Some(test) = case_like(context, arena, *condition) else {
+                    return;
+                };
+                tests.push((test, *span));
+            }
+            let Some(((signal, width, ..), _)) = tests.first() else {
+                return;
+            };
+            let (signal, width) = (*signal, *width);
+            if tests.iter().any(|((name, ..), _)| *name != signal) {
+                return;
+            }
+
+            let mut steps = MAX_COVERAGE_STEPS;
+            let mut earlier: Vec<Cube> = Vec::new();
+            for ((_, _, pattern, case_type), span) in &tests {
+                let cube = Cube::new(pattern, case_type, width);
+                let never_taken = cube.is_some_and(|cube| {
+                    matches!(coverage(cube, &earlier, &mut steps), Coverage::Covered)
+                });
+                let overlap = cube.and_then(|cube| {
+                    earlier
+                        .iter()
+                        .find(|other| other.intersects(&cube))
+                        .map(|other| other.value | cube.value)
+                });
+
+                let message = match (chain.modifier, overlap) {
+                    _ if never_taken => Some(
+                        "Branch is never taken: earlier conditions match every value it matches"
+                            .to_string(),
+                    ),
+                    (Some(modifier @ ("unique" | "unique0")), Some(value)) => Some(format!(
+                        "Branch overlaps an earlier branch of this {} if: both match {}",
+                        modifier,
+                        binary(value, width)
+                    )),
+                    _ => None,
+                };
+                if let Some(message) = message {
+                    diagnostics.push(LintDiagnostic {
+                        rule: self.name(),
+                        message,
+                        span: *span,
+                        fix: None,
+                    });
+                }
+                earlier.extend(cube);
+            }
+        });
+    }
+}
+
+/// Flags case statements without a `default` item whose items don't match
+/// every value of the case expression, which leaves a latch in combinational
+/// logic
+///
+/// Only cases on a signal of known width whose item values are all constants
+/// are checked, with the wildcards of `casez` and `casex` items taken into
+/// account. A `unique`, `unique0` or `priority` case states which values
+/// occur and is not flagged.
+struct IncompleteCase;
+
+impl LintRule for IncompleteCase {
+    fn name(&self) -> &'static str {
+        "case-incomplete"
+    }
+
+    fn description(&self) -> &'static str {
+        "Case statement without a default that misses values"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A case statement without a `default` item whose items don't match every \
+         value of the case expression leaves its targets unassigned for the other \
+         values, which infers a latch in combinational logic. Add a `default` item, \
+         or state that the other values don't occur with `unique`, `unique0` or \
+         `priority`. Only cases on a signal of known width whose items are all \
+         constants are checked."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let arena = &source_unit.expr_arena;
+        for_each_case(source_unit, |context, case| {
+            if case.modifier.is_some() || case.items.iter().any(|item| item.values.is_empty()) {
+                return;
+            }
+            let Some((name, width)) = context.signal(arena, case.expr) else {
+                return;
+            };
+            let mut cubes = Vec::new();
+            for value in case.items.iter().flat_map(|item| &item.values) {
+                // A value that isn't a constant could match anything
+                let Some(pattern) = context.pattern(arena, *value) else {
+                    return;
+                };
+                cubes.extend(Cube::new(&pattern, case.wildcard_type(), width));
+            }
+
+            let all = Cube { care: 0, value: 0 };
+            let mut steps = MAX_COVERAGE_STEPS;
+            if let Coverage::Missing(value) = coverage(all, &cubes, &mut steps) {
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: format!(
+                        "Case on '{}' has no default and misses values such as {}",
+                        name,
+                        binary(value, width)
+                    ),
+                    span: case.span,
+                    fix: None,
+                });
+            }
+        });
+    }
+}
+
+/// Flags `casex` statements
+///
+/// A `casex` also treats x bits of the case expression as wildcards, so an
+/// unknown value silently matches an item; style guides ask for `casez`.
+struct NoCasex;
+
+impl LintRule for NoCasex {
+    fn name(&self) -> &'static str {
+        "no-casex"
+    }
+
+    fn description(&self) -> &'static str {
+        "casex statement; use casez"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A `casex` treats x and z bits of the case expression, not only of the \
+         items, as wildcards, so an unknown value silently matches an item and \
+         hides the bug that produced it. Style guides ask for `casez`, whose \
+         wildcards are only the z and `?` bits, or `case ... inside`."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_case(source_unit, |_, case| {
+            if case.case_type == "casex" {
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: "casex matches x bits of the case expression as wildcards; use casez"
+                        .to_string(),
+                    span: case.span,
+                    fix: None,
+                });
+            }
+        });
+    }
+}
+
+/// Sampled value functions that sample on a clock, with the position of the
+/// argument that names one explicitly
+const SAMPLED_VALUE_FUNCTIONS: &[(&str, usize)] = &[
+    ("rose", 1),
+    ("fell", 1),
+    ("stable", 1),
+    ("changed", 1),
+    ("past", 3),
+];
+
+/// Flags concurrent assertions and sampled value functions with no clock
+///
+/// An assertion without a clocking event of its own takes the module's
+/// `default clocking`, and one in an `always` block the edge its block waits
+/// for; `$rose`, `$fell`, `$stable`, `$changed` and `$past` sample on the
+/// same clock unless given one as an argument. With none of these there is
+/// nothing to sample on.
+struct AssertionClocking;
+
+impl LintRule for AssertionClocking {
+    fn name(&self) -> &'static str {
+        "assertion-clock"
+    }
+
+    fn description(&self) -> &'static str {
+        "Assertion or sampled value function with no clock"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A concurrent assertion is checked at each tick of its clock: the \
+         clocking event of its property, like `@(posedge clk)`, the edge the \
+         `always` block it is in waits for, or else the `default clocking` of \
+         its module. `$rose`, `$fell`, `$stable`, `$changed` and `$past` compare \
+         values sampled at the ticks of the same clock, unless given one as an \
+         argument. In a module without a default clocking, an assertion with no \
+         clocking event, or one of these functions in a continuous assignment, \
+         an `always_comb` block or another block that waits for no edge, has no \
+         clock, and tools reject it or sample it somewhere unexpected."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_module(source_unit, |_, items| {
+            let arena = &source_unit.module_item_arena;
+            // The default clocking applies to the whole module, wherever it is declared
+            if items.iter().any(|item_ref| {
+                matches!(
+                    arena.get(*item_ref),
+                    ModuleItem::DefaultClocking { .. }
+                        | ModuleItem::ClockingBlock { default: true, .. }
+                )
+            }) {
+                return;
+            }
+            // A sequence or property declared with a clock clocks the
+            // assertions that use it
+            let clocked_declarations = items
+                .iter()
+                .filter_map(|item_ref| match arena.get(*item_ref) {
+                    ModuleItem::AssertionDeclaration {
+                        name,
+                        clocking_event,
+                        body,
+                        ..
+                    } if clocking_event.is_some()
+                        || matches!(body, PropertyExpr::Clocked { .. }) =>
+                    {
+                        Some(name.as_str())
+                    }
+                    _ => None,
+                })
+                .collect();
+            let mut walker = ClockWalker {
+                rule: self.name(),
+                source_unit,
+                clocked_declarations,
+                diagnostics,
+            };
+            for item_ref in items {
+                walker.item(arena.get(*item_ref));
+            }
+        });
+    }
+}
+
+struct ClockWalker<'a, 'd> {
+    rule: &'static str,
+    source_unit: &'a SourceUnit,
+    /// Sequences and properties of the module declared with a clock
+    clocked_declarations: Vec<&'a str>,
+    diagnostics: &'d mut Vec<LintDiagnostic>,
+}
+
+impl ClockWalker<'_, '_> {
+    fn item(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::ConcurrentAssertion {
+                clocking_event,
+                property,
+                span,
+                ..
+            } if !self.has_clock(*clocking_event, property) => self.unclocked_assertion(*span),
+            ModuleItem::ProceduralBlock {
+                event_control,
+                statements,
+                ..
+            } => {
+                let clocked = event_control.as_ref().is_some_and(is_clock);
+                self.statements(statements, clocked);
+            }
+            ModuleItem::Assignment { expr, .. }
+            | ModuleItem::VariableDeclaration {
+                initial_value: Some(expr),
+                ..
+            } => self.expression(*expr),
+            _ => {}
+        }
+    }
+
+    /// Check statements run one after another, on a clock edge if `clocked`
+    /// or once one of them has waited for an edge, and return whether the
+    /// statements after them are
+    fn statements(&mut self, stmt_refs: &[StmtRef], mut clocked: bool) -> bool {
+        for stmt_ref in stmt_refs {
+            clocked = self.statement(*stmt_ref, clocked);
+        }
+        clocked
+    }
+
+    /// Check a statement run on a clock edge if `clocked`, and return whether
+    /// the statements after it are
+    fn statement(&mut self, stmt_ref: StmtRef, clocked: bool) -> bool {
+        let statement = self.source_unit.stmt_arena.get(stmt_ref);
+        let clocked = clocked
+            || matches!(
+                statement,
+                Statement::Timed {
+                    control: TimingControl::Event(control),
+                    ..
+                } if is_clock(control)
+            );
+        if !clocked {
+            match statement {
+                Statement::AssertProperty {
+                    clocking_event,
+                    property,
+                    span,
+                    ..
+                } => {
+                    if !self.has_clock(*clocking_event, property) {
+                        self.unclocked_assertion(*span);
+                    }
+                }
+                _ => {
+                    for expr in statement_expressions(statement) {
+                        self.expression(expr);
+                    }
+                }
+            }
+        }
+        match statement {
+            Statement::Block { statements, .. } => self.statements(statements, clocked),
+            // A wait in a branch or loop may never happen
+            _ => {
+                for child in statement.children() {
+                    self.statement(child, clocked);
+                }
+                clocked
+            }
+        }
+    }
+
+    /// Whether an assertion gives its property a clock: a clocking event of
+    /// its own, or a sequence or property declared with one
+    fn has_clock(&self, clocking_event: Option<ExprRef>, property: &PropertyExpr) -> bool {
+        if clocking_event.is_some() {
+            return true;
+        }
+        let arena = &self.source_unit.expr_arena;
+        match property {
+            PropertyExpr::Clocked { .. } => true,
+            PropertyExpr::Expression { expr, .. } => {
+                let instance = match arena.get(*expr) {
+                    Expression::FunctionCall { function, .. } => arena.get(*function),
+                    expr => expr,
+                };
+                matches!(instance, Expression::Identifier(name, _)
+                    if self.clocked_declarations.contains(&name.as_str()))
+            }
+            _ => false,
+        }
+    }
+
+    /// Flag the sampled value functions in an expression evaluated with no clock
+    fn expression(&mut self, expr: ExprRef) {
+        match self.source_unit.expr_arena.get(expr) {
+            Expression::SystemFunctionCall {
+                name,
+                arguments,
+                span,
+            } => {
+                let explicit_clock = SAMPLED_VALUE_FUNCTIONS
+                    .iter()
+                    .find(|(function, _)| *function == name.as_str())
+                    .map(|(_, clock)| arguments.len() > *clock);
+                if explicit_clock == Some(false) {
+                    self.diagnostics.push(LintDiagnostic {
+                        rule: self.rule,
+                        message: format!(
+                            "${} has no clock to sample on: call it where an edge is \
+                             awaited, give it a clocking event or declare a default clocking",
+                            name
+                        ),
+                        span: *span,
+                        fix: None,
+                    });
+                }
+                for argument in arguments {
+                    self.expression(*argument);
+                }
+            }
+            Expression::Binary { left, right, .. }
+            | Expression::ValueRange {
+                low: left,
+                high: right,
+                ..
+            } => {
+                self.expression(*left);
+                self.expression(*right);
+            }
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                for operand in [condition, then_expr, else_expr] {
+                    self.expression(*operand);
+                }
+            }
+            Expression::Unary { operand: inner, .. }
+            | Expression::IncDec { operand: inner, .. }
+            | Expression::MemberAccess { object: inner, .. }
+            | Expression::TaggedUnion {
+                value: Some(inner), ..
+            } => self.expression(*inner),
+            Expression::MacroUsage { arguments, .. }
+            | Expression::New { arguments, .. }
+            | Expression::FunctionCall { arguments, .. } => {
+                for argument in arguments {
+                    self.expression(*argument);
+                }
+            }
+            Expression::AssignmentPattern { items, .. } => {
+                for item in items {
+                    self.expression(item.value);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn unclocked_assertion(&mut self, span: Span) {
+        self.diagnostics.push(LintDiagnostic {
+            rule: self.rule,
+            message: "Concurrent assertion has no clock: give the property a clocking event \
+                      like @(posedge clk) or declare a default clocking"
+                .to_string(),
+            span,
+            fix: None,
+        });
+    }
+}
+
+/// Whether an event control waits for a clock edge, which assertions and
+/// sampled value functions under it take as their clock
+fn is_clock(control: &EventControl) -> bool {
+    match control {
+        EventControl::Implicit { .. } => false,
+        EventControl::Events { events, .. } => events.iter().any(|event| event.edge.is_some()),
+    }
+}
+
+/// The expressions a statement evaluates itself, not counting those of the
+/// statements nested in it
+fn statement_expressions(statement: &Statement) -> Vec<ExprRef> {
+    match statement {
+        Statement::Assignment { target, expr, .. } => vec![*target, *expr],
+        Statement::SystemCall { args, .. } => args.clone(),
+        Statement::CaseStatement { expr, items, .. } => std::iter::once(*expr)
+            .chain(items.iter().flat_map(|item| item.values.iter().copied()))
+            .collect(),
+        Statement::CaseMatches { expr, items, .. } => std::iter::once(*expr)
+            .chain(items.iter().filter_map(|item| item.guard))
+            .collect(),
+        Statement::ExpressionStatement { expr, .. } => vec![*expr],
+        Statement::Conditional { condition, .. }
+        | Statement::ImmediateAssertion { condition, .. }
+        | Statement::EventTrigger {
+            event: condition, ..
+        }
+        | Statement::While { condition, .. }
+        | Statement::DoWhile { condition, .. }
+        | Statement::Repeat {
+            count: condition, ..
+        } => vec![*condition],
+        Statement::ForLoop { condition, .. } => condition.iter().copied().collect(),
+        Statement::AssertProperty {
+            disable_iff,
+            property,
+            ..
+        } => disable_iff
+            .iter()
+            .copied()
+            .chain(property.expressions())
+            .collect(),
+        Statement::VariableDeclaration { initial_value, .. }
+        | Statement::Return {
+            value: initial_value,
+            ..
+        } => initial_value.iter().copied().collect(),
+        Statement::Timed { control, .. } => control.expressions(),
+        _ => Vec::new(),
+    }
+}
+
+/// Flags undeclared names that create implicit nets
+///
+/// Unless `` `default_nettype none `` is in effect, a name that is not
+/// declared becomes a net of the default net type where it is connected to
+/// a port of an instance or assigned by a continuous assignment (IEEE
+/// 1800-2017 6.10), so a misspelled signal compiles as a new one that
+/// nothing else drives or reads. Each such net is reported once, at its
+/// first use.
+struct ImplicitNet;
+
+impl LintRule for ImplicitNet {
+    fn name(&self) -> &'static str {
+        "implicit-net"
+    }
+
+    fn description(&self) -> &'static str {
+        "Undeclared name that creates an implicit net"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "An undeclared name in a port connection of an instance, or on the left \
+         of a continuous assignment, doesn't fail to compile: it declares a \
+         scalar net of the default net type, a `wire` unless a \
+         `` `default_nettype `` directive says otherwise. A misspelled signal \
+         name then becomes a new one-bit net that nothing else drives or reads. \
+         Declare every net, and put `` `default_nettype none `` before the \
+         modules so that tools reject undeclared names; set the rule's severity \
+         to `error` to treat each implicit net as one."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let arena = &source_unit.module_item_arena;
+        let packages: HashMap<&str, &[ModuleItemRef]> = source_unit
+            .items
+            .iter()
+            .filter_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::PackageDeclaration { name, items, .. } => {
+                    Some((name.as_str(), items.as_slice()))
+                }
+                _ => None,
+            })
+            .collect();
+        // Compilation-unit declarations are visible in every module
+        let Some(unit_names) = declared_names(source_unit, &[], &source_unit.items, &packages)
+        else {
+            return;
+        };
+
+        let mut net_type = "wire";
+        for item_ref in &source_unit.items {
+            match arena.get(*item_ref) {
+                ModuleItem::DefaultNettypeDirective {
+                    net_type: directive_type,
+                    ..
+                } => net_type = directive_type.as_str(),
+                item @ (ModuleItem::ModuleDeclaration { .. }
+                | ModuleItem::InterfaceDeclaration { .. })
+                    if net_type != "none" =>
+                {
+                    let mut walker = ImplicitNetWalker {
+                        rule: self.name(),
+                        source_unit,
+                        packages: &packages,
+                        net_type,
+                        diagnostics,
+                    };
+                    walker.design_element(item, &unit_names);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+struct ImplicitNetWalker<'a, 'd> {
+    rule: &'static str,
+    source_unit: &'a SourceUnit,
+    packages: &'d HashMap<&'a str, &'a [ModuleItemRef]>,
+    net_type: &'a str,
+    diagnostics: &'d mut Vec<LintDiagnostic>,
+}
+
+impl<'a> ImplicitNetWalker<'a, '_> {
+    /// Check a module or interface, which also sees the names of the scopes
+    /// around it in `outer`, and the modules nested in it
+    fn design_element(&mut self, item: &'a ModuleItem, outer: &HashSet<&'a str>) {
+        let (ports, items) = match item {
+            ModuleItem::ModuleDeclaration { ports, items, .. }
+            | ModuleItem::InterfaceDeclaration { ports, items, .. } => (ports, items),
+            _ => return,
+        };
+        // Names imported from a package outside the file can't be known
+        let Some(mut declared) = declared_names(self.source_unit, ports, items, self.packages)
+        else {
+            return;
+        };
+        declared.extend(outer.iter().copied());
+
+        let mut reported = HashSet::new();
+        let mut nested = Vec::new();
+        self.items(items, &declared, &mut reported, &mut nested);
+        for item in nested {
+            self.design_element(item, &declared);
+        }
+    }
+
+    fn items(
+        &mut self,
+        items: &[ModuleItemRef],
+        declared: &HashSet<&'a str>,
+        reported: &mut HashSet<&'a str>,
+        nested: &mut Vec<&'a ModuleItem>,
+    ) {
+        let arena = &self.source_unit.module_item_arena;
+        for item_ref in items {
+            let item = arena.get(*item_ref);
+            match item {
+                ModuleItem::Assignment {
+                    implicit: false,
+                    target,
+                    span,
+                    ..
+                } => self.net_use(*target, "continuous assignment", *span, declared, reported),
+                ModuleItem::ModuleInstantiation {
+                    connections, span, ..
+                } => {
+                    for connection in connections {
+                        let expr = match connection {
+                            PortConnection::Positional(expr) => *expr,
+                            PortConnection::Named {
+                                name_span,
+                                expr: Some(expr),
+                                ..
+                            } => {
+                                // `.port` connects a declared name and never creates a net
+                                if matches!(
+                                    self.source_unit.expr_arena.get(*expr),
+                                    Expression::Identifier(_, span) if span == name_span
+                                ) {
+                                    continue;
+                                }
+                                *expr
+                            }
+                            _ => continue,
+                        };
+                        self.net_use(expr, "port connection", *span, declared, reported);
+                    }
+                }
+                ModuleItem::ModuleDeclaration { .. } | ModuleItem::InterfaceDeclaration { .. } => {
+                    nested.push(item)
+                }
+                _ => self.items(&item.generate_items(), declared, reported, nested),
+            }
+        }
+    }
+
+    /// Report `expr` if it is an undeclared name not reported before;
+    /// `item_span` stands in for the span of a name that has none
+    fn net_use(
+        &mut self,
+        expr: ExprRef,
+        usage: &str,
+        item_span: Span,
+        declared: &HashSet<&'a str>,
+        reported: &mut HashSet<&'a str>,
+    ) {
+        let Expression::Identifier(name, span) = self.source_unit.expr_arena.get(expr) else {
+            return;
+        };
+        if declared.contains(name.as_str()) || !reported.insert(name.as_str()) {
+            return;
+        }
+        self.diagnostics.push(LintDiagnostic {
+            rule: self.rule,
+            message: format!(
+                "'{}' is not declared, so this {} creates an implicit {} net",
+                name, usage, self.net_type
+            ),
+            span: if *span == (0, 0) { item_span } else { *span },
+            fix: None,
+        });
+    }
+}
+
+/// Flags named events that are triggered but never waited on, or waited on
+/// but never triggered
+///
+/// Only events declared in a module are checked. One that is assigned to
+/// another event or connected to a port may be triggered or waited on
+/// elsewhere, so it is left alone.
+struct UnmatchedEvent;
+
+impl LintRule for UnmatchedEvent {
+    fn name(&self) -> &'static str {
+        "unmatched-event"
+    }
+
+    fn description(&self) -> &'static str {
+        "Event that is triggered but never waited on, or waited on but never triggered"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A named `event` synchronizes processes: one triggers it with `->` or \
+         `->>`, and others wait for it with `@(e)` or `wait (e.triggered)`. A \
+         trigger that no process waits for does nothing, and a process that \
+         waits for an event nothing triggers blocks forever. Either usually \
+         means a misspelled event name, or a trigger or wait that was removed \
+         from one side only."
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        for_each_module(source_unit, |_, items| {
+            let mut walker = EventWalker {
+                source_unit,
+                events: Vec::new(),
+                triggered: HashSet::new(),
+                waited: HashSet::new(),
+                escaped: HashSet::new(),
+            };
+            walker.items(items);
+            for (name, name_span) in walker.events {
+                if walker.escaped.contains(name) {
+                    continue;
+                }
+                let message = match (
+                    walker.triggered.contains(name),
+                    walker.waited.contains(name),
+                ) {
+                    (true, false) => format!("Event '{}' is triggered but never waited on", name),
+                    (false, true) => format!(
+                        "Event '{}' is waited on but never triggered, so the wait never ends",
+                        name
+                    ),
+                    _ => continue,
+                };
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message,
+                    span: name_span,
+                    fix: None,
+                });
+            }
+        });
+    }
+}
+
+struct EventWalker<'a> {
+    source_unit: &'a SourceUnit,
+    /// Events declared in the module, with the spans of their names
+    events: Vec<(&'a str, Span)>,
+    triggered: HashSet<&'a str>,
+    waited: HashSet<&'a str>,
+    /// Names assigned or connected to a port, which other code may trigger
+    /// or wait on
+    escaped: HashSet<&'a str>,
+}
+
+impl<'a> EventWalker<'a> {
+    fn items(&mut self, items: &[ModuleItemRef]) {
+        let arena = &self.source_unit.module_item_arena;
+        for item_ref in items {
+            let item = arena.get(*item_ref);
+            match item {
+                ModuleItem::VariableDeclaration {
+                    data_type,
+                    name,
+                    name_span,
+                    ..
+                } if data_type.name() == "event" => self.events.push((name, *name_span)),
+                ModuleItem::ProceduralBlock {
+                    event_control,
+                    statements,
+                    ..
+                } => {
+                    if let Some(control) = event_control {
+                        self.event_control(control);
+                    }
+                    self.statements(statements);
+                }
+                ModuleItem::ModuleInstantiation { connections, .. } => {
+                    for connection in connections {
+                        if let PortConnection::Positional(expr)
+                        | PortConnection::Named {
+                            expr: Some(expr), ..
+                        } = connection
+                        {
+                            self.escape(*expr);
+                        }
+                    }
+                }
+                ModuleItem::ModuleDeclaration { .. } => {}
+                _ => self.items(&item.generate_items()),
+            }
+        }
+    }
+
+    fn statements(&mut self, stmt_refs: &[StmtRef]) {
+        for stmt_ref in stmt_refs {
+            let statement = self.source_unit.stmt_arena.get(*stmt_ref);
+            match statement {
+                Statement::EventTrigger { event, .. } => {
+                    if let Expression::Identifier(name, _) = self.source_unit.expr_arena.get(*event)
+                    {
+                        self.triggered.insert(name);
+                    }
+                }
+                Statement::Timed {
+                    control: TimingControl::Event(control),
+                    ..
+                } => self.event_control(control),
+                Statement::Assignment { target, expr, .. } => {
+                    self.escape(*target);
+                    self.escape(*expr);
+                }
+                _ => {
+                    for expr in statement_expressions(statement) {
+                        self.expression(expr);
+                    }
+                }
+            }
+            self.statements(&statement.children());
+        }
+    }
+
+    /// Record the events an `@(...)` waits for: any change of a name
+    fn event_control(&mut self, control: &EventControl) {
+        if let EventControl::Events { events, .. } = control {
+            for event in events.iter().filter(|event| event.edge.is_none()) {
+                if let Expression::Identifier(name, _) =
+                    self.source_unit.expr_arena.get(event.signal)
+                {
+                    self.waited.insert(name);
+                }
+            }
+        }
+    }
+
+    /// Record the `e.triggered` reads in an expression, which wait on `e`
+    /// in a `wait` or a condition
+    fn expression(&mut self, expr: ExprRef) {
+        let arena = &self.source_unit.expr_arena;
+        match arena.get(expr) {
+            Expression::MemberAccess { object, member, .. } => match arena.get(*object) {
+                Expression::Identifier(name, _) if member == "triggered" => {
+                    self.waited.insert(name);
+                }
+                _ => self.expression(*object),
+            },
+            Expression::Binary { left, right, .. } => {
+                self.expression(*left);
+                self.expression(*right);
+            }
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                for operand in [condition, then_expr, else_expr] {
+                    self.expression(*operand);
+                }
+            }
+            Expression::Unary { operand, .. } => self.expression(*operand),
+            Expression::FunctionCall { arguments, .. }
+            | Expression::SystemFunctionCall { arguments, .. } => {
+                for argument in arguments {
+                    self.escape(*argument);
+                    self.expression(*argument);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn escape(&mut self, expr: ExprRef) {
+        if let Expression::Identifier(name, _) = self.source_unit.expr_arena.get(expr) {
+            self.escaped.insert(name);
+        }
+    }
+}
+
+/// The names declared by `ports` and `items`, including those in generate
+/// constructs and those imported from the packages of the file; None when
+/// a wildcard import names a package the file doesn't declare
+fn declared_names<'a>(
+    source_unit: &'a SourceUnit,
+    ports: &'a [Port],
+    items: &[ModuleItemRef],
+    packages: &HashMap<&'a str, &'a [ModuleItemRef]>,
+) -> Option<HashSet<&'a str>> {
+    fn walk<'a>(
+        source_unit: &'a SourceUnit,
+        items: &[ModuleItemRef],
+        packages: &HashMap<&'a str, &'a [ModuleItemRef]>,
+        names: &mut HashSet<&'a str>,
+    ) -> Option<()> {
+        let arena = &source_unit.module_item_arena;
+        for item_ref in items {
+            let item = arena.get(*item_ref);
+            names.extend(
+                item.enum_members()
+                    .iter()
+                    .map(|member| member.name.as_str()),
+            );
+            match item {
+                ModuleItem::PortDeclaration { name, .. }
+                | ModuleItem::VariableDeclaration { name, .. }
+                | ModuleItem::TypedefDeclaration { name, .. }
+                | ModuleItem::ParameterDeclaration { name, .. }
+                | ModuleItem::DefaultClocking { name, .. }
+                | ModuleItem::ClockingBlock { name, .. }
+                | ModuleItem::AssertionDeclaration { name, .. }
+                | ModuleItem::ClassDeclaration { name, .. }
+                | ModuleItem::ModuleDeclaration { name, .. }
+                | ModuleItem::InterfaceDeclaration { name, .. } => {
+                    names.insert(name);
+                }
+                ModuleItem::ModuleInstantiation { instance_name, .. } => {
+                    names.insert(instance_name);
+                }
+                ModuleItem::GenvarDeclaration { genvars, .. } => {
+                    names.extend(genvars.iter().map(|genvar| genvar.name.as_str()));
+                }
+                ModuleItem::GenerateFor { genvar, .. } => {
+                    names.insert(genvar);
+                }
+                ModuleItem::ImportDeclaration { imports, .. } => {
+                    for import in imports {
+                        match &import.name {
+                            Some(name) => {
+                                names.insert(name);
+                            }
+                            None => walk(
+                                source_unit,
+                                packages.get(import.package.as_str())?,
+                                packages,
+                                names,
+                            )?,
+                        }
+                    }
+                }
+                _ => {}
+            }
+            walk(source_unit, &item.generate_items(), packages, names)?;
+        }
+        Some(())
+    }
+
+    let mut names: HashSet<&str> = ports.iter().map(|port| port.name.as_str()).collect();
+    walk(source_unit, items, packages, &mut names)?;
+    Some(names)
+}
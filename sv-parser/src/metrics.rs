@@ -0,0 +1,461 @@
+//! Design complexity metrics
+//!
+//! With `--report metrics` the command line tool reports, for every module,
+//! its size and how much logic it holds, so that teams can track how the
+//! complexity of a design grows over time.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+    BinaryOp, ConstraintExpr, ExprRef, Expression, ModuleItem, ModuleItemRef, Pattern,
+    PortConnection, SourceUnit, Statement, StmtRef,
+};
+
+/// Statistics of one module
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ModuleMetrics {
+    pub file: PathBuf,
+    pub module: String,
+    /// Source lines from `module` to `endmodule`
+    pub lines: usize,
+    /// Procedural statements, counting those inside blocks
+    pub statements: usize,
+    /// Expression nodes: operands, operators and calls
+    pub expressions: usize,
+    /// Decision points: conditional operators, `&&`, `||`, `if`s, loops other
+    /// than `forever`, and case items and arms other than `default`
+    pub branches: usize,
+    /// Module instances
+    pub instances: usize,
+}
+
+impl ModuleMetrics {
+    /// Collect the metrics of every module in a parsed file, nested modules
+    /// included, in source order
+    ///
+    /// `content` is the text of the file, which the module spans point into.
+    pub fn collect(file: &Path, source_unit: &SourceUnit, content: &str) -> Vec<Self> {
+        let mut metrics = Vec::new();
+        let mut counter = Counter { source_unit };
+        counter.modules(file, content, &source_unit.items, &mut metrics);
+        metrics
+    }
+}
+
+/// Render metrics as a pretty-printed JSON array
+pub fn to_json(metrics: &[ModuleMetrics]) -> String {
+    serde_json::to_string_pretty(metrics).expect("metrics are always serializable")
+}
+
+/// Render metrics as CSV with a header row
+pub fn to_csv(metrics: &[ModuleMetrics]) -> String {
+    let mut output = String::from("file,module,lines,statements,expressions,branches,instances\n");
+    for module in metrics {
+        output.push_str(&format!(
+            "{},{},{},{},{},{},{}\n",
+            csv_field(&module.file.display().to_string()),
+            csv_field(&module.module),
+            module.lines,
+            module.statements,
+            module.expressions,
+            module.branches,
+            module.instances
+        ));
+    }
+    output
+}
+
+/// Quote a CSV field holding a separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+struct Counter<'a> {
+    source_unit: &'a SourceUnit,
+}
+
+impl Counter<'_> {
+    fn modules(
+        &mut self,
+        file: &Path,
+        content: &str,
+        items: &[ModuleItemRef],
+        metrics: &mut Vec<ModuleMetrics>,
+    ) {
+        for item_ref in items {
+            let ModuleItem::ModuleDeclaration {
+                name, items, span, ..
+            } = self.source_unit.module_item_arena.get(*item_ref)
+            else {
+                continue;
+            };
+            let text: String = content
+                .chars()
+                .skip(span.0)
+                .take(span.1.saturating_sub(span.0))
+                .collect();
+            let mut module = ModuleMetrics {
+                file: file.to_path_buf(),
+                module: name.clone(),
+                lines: text.trim_end().lines().count(),
+                ..ModuleMetrics::default()
+            };
+            for item_ref in items {
+                self.item(*item_ref, &mut module);
+            }
+            metrics.push(module);
+            self.modules(file, content, items, metrics);
+        }
+    }
+
+    fn item(&mut self, item_ref: ModuleItemRef, module: &mut ModuleMetrics) {
+        match self.source_unit.module_item_arena.get(item_ref) {
+            ModuleItem::VariableDeclaration {
+                initial_value: Some(expr),
+                ..
+            }
+            | ModuleItem::DefaultDisableIff {
+                condition: expr, ..
+            }
+            | ModuleItem::GlobalClocking {
+                clocking_event: expr,
+                ..
+            }
+            | ModuleItem::ClockingBlock {
+                clocking_event: expr,
+                ..
+            }
+            | ModuleItem::ParameterDeclaration { value: expr, .. } => {
+                self.expression(*expr, module)
+            }
+            ModuleItem::Assignment { target, expr, .. } => {
+                self.expression(*target, module);
+                self.expression(*expr, module);
+            }
+            ModuleItem::ProceduralBlock { statements, .. } => {
+                for stmt_ref in statements {
+                    self.statement(*stmt_ref, module);
+                }
+            }
+            ModuleItem::ConcurrentAssertion {
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                for expr in clocking_event.iter().chain(disable_iff) {
+                    self.expression(*expr, module);
+                }
+                for expr in property.expressions() {
+                    self.expression(expr, module);
+                }
+                for action in pass_action.iter().chain(fail_action) {
+                    self.statement(*action, module);
+                }
+            }
+            ModuleItem::AssertionDeclaration {
+                clocking_event,
+                disable_iff,
+                body,
+                ..
+            } => {
+                for expr in clocking_event.iter().chain(disable_iff) {
+                    self.expression(*expr, module);
+                }
+                for expr in body.expressions() {
+                    self.expression(expr, module);
+                }
+            }
+            ModuleItem::ModuleInstantiation {
+                parameters,
+                connections,
+                ..
+            } => {
+                module.instances += 1;
+                for connection in parameters.iter().chain(connections) {
+                    match connection {
+                        PortConnection::Positional(expr)
+                        | PortConnection::Named {
+                            expr: Some(expr), ..
+                        } => self.expression(*expr, module),
+                        PortConnection::Named { expr: None, .. } | PortConnection::Wildcard => {}
+                    }
+                }
+            }
+            ModuleItem::ElaborationTask { arguments, .. } => {
+                for expr in arguments {
+                    self.expression(*expr, module);
+                }
+            }
+            ModuleItem::GenerateFor {
+                init,
+                condition,
+                step,
+                body,
+                ..
+            } => {
+                for expr in [init, condition, step] {
+                    self.expression(*expr, module);
+                }
+                self.item(*body, module);
+            }
+            ModuleItem::GenerateIf {
+                condition,
+                then_item,
+                else_item,
+                ..
+            } => {
+                self.expression(*condition, module);
+                for nested in std::iter::once(then_item).chain(else_item) {
+                    self.item(*nested, module);
+                }
+            }
+            ModuleItem::GenerateCase { expr, items, .. } => {
+                self.expression(*expr, module);
+                for case_item in items {
+                    for value in &case_item.values {
+                        self.expression(*value, module);
+                    }
+                    self.item(case_item.body, module);
+                }
+            }
+            ModuleItem::GenerateRegion { items, .. } | ModuleItem::GenerateBlock { items, .. } => {
+                for nested in items {
+                    self.item(*nested, module);
+                }
+            }
+            // Nested modules are reported on their own
+            _ => {}
+        }
+    }
+
+    fn statement(&mut self, stmt_ref: StmtRef, module: &mut ModuleMetrics) {
+        let statement = self.source_unit.stmt_arena.get(stmt_ref);
+        // A block groups statements; it is not one itself
+        if !matches!(
+            statement,
+            Statement::Block { .. } | Statement::Fork { .. } | Statement::Labeled { .. }
+        ) {
+            module.statements += 1;
+        }
+        match statement {
+            Statement::Assignment { target, expr, .. } => {
+                self.expression(*target, module);
+                self.expression(*expr, module);
+            }
+            Statement::SystemCall { args, .. } => {
+                for expr in args {
+                    self.expression(*expr, module);
+                }
+            }
+            Statement::Conditional {
+                condition,
+                then_statement,
+                else_statement,
+                ..
+            } => {
+                module.branches += 1;
+                self.expression(*condition, module);
+                self.statement(*then_statement, module);
+                if let Some(else_statement) = else_statement {
+                    self.statement(*else_statement, module);
+                }
+            }
+            Statement::CaseStatement { expr, items, .. } => {
+                module.branches += items.iter().filter(|item| !item.values.is_empty()).count();
+                self.expression(*expr, module);
+                for item in items {
+                    for value in &item.values {
+                        self.expression(*value, module);
+                    }
+                    self.statement(item.statement, module);
+                }
+            }
+            Statement::ExpressionStatement { expr, .. }
+            | Statement::EventTrigger { event: expr, .. }
+            | Statement::VariableDeclaration {
+                initial_value: Some(expr),
+                ..
+            }
+            | Statement::Return {
+                value: Some(expr), ..
+            } => self.expression(*expr, module),
+            Statement::AssertProperty {
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                for expr in clocking_event.iter().chain(disable_iff) {
+                    self.expression(*expr, module);
+                }
+                for expr in property.expressions() {
+                    self.expression(expr, module);
+                }
+                for action in pass_action.iter().chain(fail_action) {
+                    self.statement(*action, module);
+                }
+            }
+            Statement::ImmediateAssertion {
+                condition,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                self.expression(*condition, module);
+                for action in pass_action.iter().chain(fail_action) {
+                    self.statement(*action, module);
+                }
+            }
+            Statement::Block { statements, .. } | Statement::Fork { statements, .. } => {
+                for stmt_ref in statements {
+                    self.statement(*stmt_ref, module);
+                }
+            }
+            Statement::Labeled { statement, .. } => self.statement(*statement, module),
+            Statement::CaseMatches { expr, items, .. } => {
+                module.branches += items.iter().filter(|item| item.pattern.is_some()).count();
+                self.expression(*expr, module);
+                for item in items {
+                    if let Some(pattern) = &item.pattern {
+                        self.pattern(pattern, module);
+                    }
+                    if let Some(guard) = item.guard {
+                        self.expression(guard, module);
+                    }
+                    self.statement(item.statement, module);
+                }
+            }
+            Statement::ForLoop {
+                init,
+                condition,
+                step,
+                body,
+                ..
+            } => {
+                module.branches += 1;
+                if let Some(condition) = condition {
+                    self.expression(*condition, module);
+                }
+                for stmt_ref in init.iter().chain(step).chain(std::iter::once(body)) {
+                    self.statement(*stmt_ref, module);
+                }
+            }
+            Statement::While {
+                condition, body, ..
+            }
+            | Statement::DoWhile {
+                body, condition, ..
+            }
+            | Statement::Repeat {
+                count: condition,
+                body,
+                ..
+            } => {
+                module.branches += 1;
+                self.expression(*condition, module);
+                self.statement(*body, module);
+            }
+            Statement::Timed {
+                control, statement, ..
+            } => {
+                for expr_ref in control.expressions() {
+                    self.expression(expr_ref, module);
+                }
+                if let Some(statement) = statement {
+                    self.statement(*statement, module);
+                }
+            }
+            Statement::Forever { body, .. } => self.statement(*body, module),
+            Statement::Foreach { body, .. } => {
+                module.branches += 1;
+                self.statement(*body, module);
+            }
+            Statement::VariableDeclaration {
+                initial_value: None,
+                ..
+            }
+            | Statement::Return { value: None, .. }
+            | Statement::WaitFork { .. }
+            | Statement::DisableFork { .. }
+            | Statement::Disable { .. } => {}
+        }
+    }
+
+    fn pattern(&mut self, pattern: &Pattern, module: &mut ModuleMetrics) {
+        match pattern {
+            Pattern::Constant(expr) => self.expression(*expr, module),
+            Pattern::Tagged {
+                pattern: Some(pattern),
+                ..
+            } => self.pattern(pattern, module),
+            _ => {}
+        }
+    }
+
+    fn expression(&mut self, expr_ref: ExprRef, module: &mut ModuleMetrics) {
+        module.expressions += 1;
+        let children: Vec<ExprRef> = match self.source_unit.expr_arena.get(expr_ref) {
+            Expression::Binary {
+                op, left, right, ..
+            } => {
+                if matches!(op, BinaryOp::LogicalAnd | BinaryOp::LogicalOr) {
+                    module.branches += 1;
+                }
+                vec![*left, *right]
+            }
+            Expression::Unary { operand, .. } | Expression::IncDec { operand, .. } => {
+                vec![*operand]
+            }
+            Expression::MacroUsage { arguments, .. }
+            | Expression::SystemFunctionCall { arguments, .. }
+            | Expression::New { arguments, .. } => arguments.clone(),
+            Expression::MemberAccess { object, .. } => vec![*object],
+            Expression::FunctionCall {
+                function,
+                arguments,
+                ..
+            } => std::iter::once(*function)
+                .chain(arguments.iter().copied())
+                .collect(),
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                module.branches += 1;
+                vec![*condition, *then_expr, *else_expr]
+            }
+            Expression::TaggedUnion { value, .. } => value.iter().copied().collect(),
+            Expression::AssignmentPattern { items, .. } => {
+                items.iter().map(|item| item.value).collect()
+            }
+            Expression::ValueRange { low, high, .. } => vec![*low, *high],
+            Expression::RandomizeWith {
+                call, constraints, ..
+            } => std::iter::once(*call)
+                .chain(constraints.iter().flat_map(ConstraintExpr::expressions))
+                .collect(),
+            Expression::Identifier(..)
+            | Expression::Number(..)
+            | Expression::StringLiteral(..)
+            | Expression::This(..)
+            | Expression::Super(..)
+            | Expression::ScopedIdentifier { .. } => Vec::new(),
+        };
+        for child in children {
+            self.expression(child, module);
+        }
+    }
+}
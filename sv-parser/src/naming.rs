@@ -0,0 +1,297 @@
+//! Naming convention lint rules
+//!
+//! Each kind of symbol has a `naming-*` rule checking that its names match a
+//! regular expression, by default the common SystemVerilog style: lower
+//! snake case for modules, signals and classes, upper snake case for
+//! parameters, and `clk_*` and `rst_*` for clocks and resets. The patterns
+//! come from the `[lint.naming]` table of the project configuration, and a
+//! rule runs with `--lint` only when its pattern is configured there.
+//! Findings point at the name itself, so that an editor can offer to rename
+//! it.
+
+use std::collections::HashSet;
+
+use regex::Regex;
+
+use crate::config::NamingConfig;
+use crate::lint::{LintDiagnostic, LintRule};
+use crate::{ModuleItem, ModuleItemArena, ModuleItemRef, Port, SourceUnit, Span};
+
+/// A kind of symbol with its own naming convention
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NameKind {
+    Module,
+    Parameter,
+    /// Ports and variables that are not clocks or resets
+    Signal,
+    /// Signals whose name mentions `clk` or `clock`
+    Clock,
+    /// Signals whose name mentions `rst` or `reset`
+    Reset,
+    Class,
+}
+
+impl NameKind {
+    pub const ALL: [NameKind; 6] = [
+        NameKind::Module,
+        NameKind::Parameter,
+        NameKind::Signal,
+        NameKind::Clock,
+        NameKind::Reset,
+        NameKind::Class,
+    ];
+
+    fn rule_name(self) -> &'static str {
+        match self {
+            NameKind::Module => "naming-module",
+            NameKind::Parameter => "naming-parameter",
+            NameKind::Signal => "naming-signal",
+            NameKind::Clock => "naming-clock",
+            NameKind::Reset => "naming-reset",
+            NameKind::Class => "naming-class",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            NameKind::Module => "Module name not matching the naming convention",
+            NameKind::Parameter => "Parameter name not matching the naming convention",
+            NameKind::Signal => "Signal name not matching the naming convention",
+            NameKind::Clock => "Clock name not matching the naming convention",
+            NameKind::Reset => "Reset name not matching the naming convention",
+            NameKind::Class => "Class name not matching the naming convention",
+        }
+    }
+
+    fn explanation(self) -> &'static str {
+        match self {
+            NameKind::Module => {
+                "Module names must match the `module` pattern \
+                 of the `[lint.naming]` table, by default lower snake case \
+                 (`^[a-z][a-z0-9_]*$`). The rule runs with `--lint` only when the \
+                 pattern is configured."
+            }
+            NameKind::Parameter => {
+                "Parameter names must match the `parameter` pattern of \
+                 the `[lint.naming]` table, by default upper snake case \
+                 (`^[A-Z][A-Z0-9_]*$`), so constants stand out from signals. The \
+                 rule runs with `--lint` only when the pattern is configured."
+            }
+            NameKind::Signal => {
+                "Port and variable names that don't mention a clock or a reset must \
+                 match the `signal` pattern of the `[lint.naming]` table, by default \
+                 lower snake case (`^[a-z][a-z0-9_]*$`). The rule runs with `--lint` \
+                 only when the pattern is configured."
+            }
+            NameKind::Clock => {
+                "Signals whose name mentions `clk` or `clock` must match the `clock` \
+                 pattern of the `[lint.naming]` table, by default `clk` or \
+                 `clk_<domain>` (`^clk(_[a-z0-9_]+)?$`). The rule runs with `--lint` \
+                 only when the pattern is configured."
+            }
+            NameKind::Reset => {
+                "Signals whose name mentions `rst` or `reset` must match the `reset` \
+                 pattern of the `[lint.naming]` table, by default `rst` or \
+                 `rst_<suffix>` (`^rst(_[a-z0-9_]+)?$`), so the active level can be \
+                 spelled the same way everywhere. The rule runs with `--lint` only \
+                 when the pattern is configured."
+            }
+            NameKind::Class => {
+                "Class and interface class names must match the `class` pattern of \
+                 the `[lint.naming]` table, by default lower snake case \
+                 (`^[a-z][a-z0-9_]*$`). The rule runs with `--lint` only when the \
+                 pattern is configured."
+            }
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            NameKind::Module => "Module",
+            NameKind::Parameter => "Parameter",
+            NameKind::Signal => "Signal",
+            NameKind::Clock => "Clock",
+            NameKind::Reset => "Reset",
+            NameKind::Class => "Class",
+        }
+    }
+
+    fn default_pattern(self) -> &'static str {
+        match self {
+            NameKind::Module | NameKind::Signal | NameKind::Class => "^[a-z][a-z0-9_]*$",
+            NameKind::Parameter => "^[A-Z][A-Z0-9_]*$",
+            NameKind::Clock => "^clk(_[a-z0-9_]+)?$",
+            NameKind::Reset => "^rst(_[a-z0-9_]+)?$",
+        }
+    }
+
+    fn configured(self, config: &NamingConfig) -> Option<&str> {
+        match self {
+            NameKind::Module => config.module.as_deref(),
+            NameKind::Parameter => config.parameter.as_deref(),
+            NameKind::Signal => config.signal.as_deref(),
+            NameKind::Clock => config.clock.as_deref(),
+            NameKind::Reset => config.reset.as_deref(),
+            NameKind::Class => config.class.as_deref(),
+        }
+    }
+
+    /// The kind of a port or variable, guessed from its name, since the
+    /// signals a block is sensitive to are not recorded
+    fn of_signal(name: &str) -> NameKind {
+        let name = name.to_ascii_lowercase();
+        if name.contains("clk") || name.contains("clock") {
+            NameKind::Clock
+        } else if name.contains("rst") || name.contains("reset") {
+            NameKind::Reset
+        } else {
+            NameKind::Signal
+        }
+    }
+}
+
+/// The naming convention of every kind of symbol
+#[derive(Debug, Clone)]
+pub struct NamingConventions {
+    rules: Vec<NamingRule>,
+}
+
+impl NamingConventions {
+    /// The conventions of a `[lint.naming]` table
+    ///
+    /// Returns an error naming the first kind whose pattern is not a valid
+    /// regular expression.
+    pub fn from_config(config: &NamingConfig) -> Result<Self, String> {
+        let rules = NameKind::ALL
+            .into_iter()
+            .map(|kind| {
+                let configured = kind.configured(config);
+                let source = configured.unwrap_or(kind.default_pattern());
+                let pattern = Regex::new(source).map_err(|err| {
+                    format!("Invalid naming pattern for {}: {}", kind.rule_name(), err)
+                })?;
+                Ok(NamingRule {
+                    kind,
+                    pattern,
+                    configured: configured.is_some(),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self { rules })
+    }
+
+    /// One lint rule per kind of symbol
+    pub fn rules(&self) -> Vec<Box<dyn LintRule>> {
+        self.rules
+            .iter()
+            .map(|rule| Box::new(rule.clone()) as Box<dyn LintRule>)
+            .collect()
+    }
+}
+
+impl Default for NamingConventions {
+    fn default() -> Self {
+        Self::from_config(&NamingConfig::default()).expect("default patterns are valid")
+    }
+}
+
+/// Flags names of one kind of symbol that don't match its pattern
+#[derive(Debug, Clone)]
+struct NamingRule {
+    kind: NameKind,
+    pattern: Regex,
+    configured: bool,
+}
+
+impl LintRule for NamingRule {
+    fn name(&self) -> &'static str {
+        self.kind.rule_name()
+    }
+
+    fn description(&self) -> &'static str {
+        self.kind.description()
+    }
+
+    fn explanation(&self) -> &'static str {
+        self.kind.explanation()
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check(&self, source_unit: &SourceUnit, diagnostics: &mut Vec<LintDiagnostic>) {
+        let mut names = Vec::new();
+        collect_names(
+            &source_unit.module_item_arena,
+            &[],
+            &source_unit.items,
+            &mut names,
+        );
+        for (kind, name, name_span) in names {
+            if kind == self.kind && !self.pattern.is_match(name) {
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: format!(
+                        "{} name '{}' does not match the naming convention {}",
+                        kind.label(),
+                        name,
+                        self.pattern
+                    ),
+                    span: name_span,
+                    fix: None,
+                });
+            }
+        }
+    }
+}
+
+/// Names declared by the ports and items of a scope and by the modules
+/// inside it, with their kind
+///
+/// A port declared again in the body (`input a; wire a;`) is listed once.
+fn collect_names<'a>(
+    arena: &'a ModuleItemArena,
+    ports: &'a [Port],
+    items: &'a [ModuleItemRef],
+    names: &mut Vec<(NameKind, &'a str, Span)>,
+) {
+    let mut signals = HashSet::new();
+    for port in ports {
+        if signals.insert(port.name.as_str()) {
+            names.push((NameKind::of_signal(&port.name), &port.name, port.name_span));
+        }
+    }
+    for item_ref in items {
+        match arena.get(*item_ref) {
+            ModuleItem::ModuleDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                ..
+            } => {
+                names.push((NameKind::Module, name, *name_span));
+                collect_names(arena, ports, items, names);
+            }
+            ModuleItem::ParameterDeclaration {
+                name, name_span, ..
+            } => names.push((NameKind::Parameter, name, *name_span)),
+            ModuleItem::PortDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::VariableDeclaration {
+                name, name_span, ..
+            } if signals.insert(name.as_str()) => {
+                names.push((NameKind::of_signal(name), name, *name_span))
+            }
+            ModuleItem::ClassDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::InterfaceClassDeclaration {
+                name, name_span, ..
+            } => names.push((NameKind::Class, name, *name_span)),
+            _ => {}
+        }
+    }
+}
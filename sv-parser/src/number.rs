@@ -0,0 +1,430 @@
+//! Numeric literals and constant evaluation
+//!
+//! The parser keeps number literals as their source text. [`NumberLiteral`]
+//! decodes that text (`8'hA5`, `4'sb1010`, `1_000`) into a width, signedness
+//! and value, and [`evaluate_constant`] folds constant expressions such as
+//! parameter values (`WIDTH * 2 - 1`) into the same representation.
+
+use std::collections::HashMap;
+
+use crate::{
+    BinaryOp, ExprArena, ExprRef, Expression, ModuleItem, ModuleItemArena, ModuleItemRef, UnaryOp,
+};
+
+/// Width of unsized literals and of `integer`/`int` values
+pub const UNSIZED_WIDTH: u32 = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberBase {
+    Binary,
+    Octal,
+    Decimal,
+    Hex,
+}
+
+impl NumberBase {
+    fn radix(self) -> u32 {
+        match self {
+            NumberBase::Binary => 2,
+            NumberBase::Octal => 8,
+            NumberBase::Decimal => 10,
+            NumberBase::Hex => 16,
+        }
+    }
+}
+
+/// A decoded integer literal or constant value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLiteral {
+    /// Declared width (`8` in `8'hA5`); None for unsized literals
+    pub width: Option<u32>,
+    pub signed: bool,
+    /// Base the literal was written in (Decimal for evaluated values)
+    pub base: NumberBase,
+    /// Bit pattern truncated to the width; None if any bit is x or z
+    pub value: Option<u128>,
+}
+
+impl NumberLiteral {
+    /// Decode the text of an integer literal
+    ///
+    /// Returns None for text that is not a well-formed integer literal, for
+    /// unbased unsized literals (`'1`), whose width depends on the context, and
+    /// for literals wider than 128 bits.
+    pub fn parse(text: &str) -> Option<Self> {
+        let text: String = text
+            .chars()
+            .filter(|c| *c != '_' && !c.is_whitespace())
+            .collect();
+
+        let Some((size, rest)) = text.split_once('\'') else {
+            let value = parse_digits(&text, NumberBase::Decimal)?;
+            return Some(Self {
+                width: None,
+                signed: true,
+                base: NumberBase::Decimal,
+                value: Some(value? & mask(UNSIZED_WIDTH)),
+            });
+        };
+
+        let width = if size.is_empty() {
+            None
+        } else {
+            match size.parse::<u32>().ok()? {
+                0 | 129.. => return None,
+                width => Some(width),
+            }
+        };
+
+        let mut chars = rest.chars();
+        let mut base_char = chars.next()?;
+        let signed = matches!(base_char, 's' | 'S');
+        if signed {
+            base_char = chars.next()?;
+        }
+        let base = match base_char.to_ascii_lowercase() {
+            'b' => NumberBase::Binary,
+            'o' => NumberBase::Octal,
+            'd' => NumberBase::Decimal,
+            'h' => NumberBase::Hex,
+            _ => return None,
+        };
+
+        let value = parse_digits(chars.as_str(), base)?;
+        let bits = width.unwrap_or(UNSIZED_WIDTH);
+        Some(Self {
+            width,
+            signed,
+            base,
+            value: value.map(|value| value & mask(bits)),
+        })
+    }
+
+    /// Number of bits, counting unsized values as 32 bits wide
+    pub fn bit_width(&self) -> u32 {
+        self.width.unwrap_or(UNSIZED_WIDTH)
+    }
+
+    /// The value as a (possibly negative) integer, taking signedness into account
+    pub fn to_i128(&self) -> Option<i128> {
+        let value = self.value?;
+        let bits = self.bit_width();
+        if self.signed && bits < 128 && value >> (bits - 1) & 1 == 1 {
+            Some(value as i128 - (1i128 << bits))
+        } else {
+            Some(value as i128)
+        }
+    }
+
+    /// Decimal rendering, negative for signed values with the sign bit set
+    pub fn to_decimal_string(&self) -> Option<String> {
+        if self.signed {
+            self.to_i128().map(|value| value.to_string())
+        } else {
+            self.value.map(|value| value.to_string())
+        }
+    }
+
+    /// Hex rendering, e.g. `8'hA5`
+    pub fn to_hex_string(&self) -> Option<String> {
+        let digits = self.bit_width().div_ceil(4) as usize;
+        self.value
+            .map(|value| format!("{}'h{:0digits$X}", self.bit_width(), value))
+    }
+
+    /// Binary rendering, e.g. `8'b10100101`
+    pub fn to_binary_string(&self) -> Option<String> {
+        let digits = self.bit_width() as usize;
+        self.value
+            .map(|value| format!("{}'b{:0digits$b}", self.bit_width(), value))
+    }
+
+    /// Convert to `width` bits, truncating or sign/zero-extending
+    pub fn resize(&self, width: u32) -> Self {
+        Self {
+            width: Some(width),
+            value: self.to_i128().map(|value| value as u128 & mask(width)),
+            ..*self
+        }
+    }
+
+    fn from_i128(value: i128, width: Option<u32>, signed: bool) -> Self {
+        Self {
+            width,
+            signed,
+            base: NumberBase::Decimal,
+            value: Some(value as u128 & mask(width.unwrap_or(UNSIZED_WIDTH))),
+        }
+    }
+}
+
+/// Parse the digits of a literal; Some(None) means x/z digits were present
+fn parse_digits(digits: &str, base: NumberBase) -> Option<Option<u128>> {
+    if digits.is_empty() {
+        return None;
+    }
+
+    let mut value: u128 = 0;
+    let mut unknown = false;
+    for c in digits.chars() {
+        if matches!(c, 'x' | 'X' | 'z' | 'Z' | '?') {
+            unknown = true;
+            continue;
+        }
+        let digit = c.to_digit(base.radix())?;
+        value = value
+            .checked_mul(u128::from(base.radix()))?
+            .checked_add(u128::from(digit))?;
+    }
+
+    Some((!unknown).then_some(value))
+}
+
+/// The bits of an integer literal, keeping the x, z and `?` digits that the
+/// items of `casez` and `casex` statements use as wildcards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitPattern {
+    /// Number of bits, counting unsized literals as 32 bits wide
+    pub width: u32,
+    /// The 0 and 1 bits; 0 where a bit is x or z
+    pub value: u128,
+    /// Bits written as x
+    pub x: u128,
+    /// Bits written as z or `?`
+    pub z: u128,
+}
+
+impl BitPattern {
+    /// Decode the text of an integer literal bit by bit
+    ///
+    /// An x or z in the leftmost digit fills the bits above it, as in
+    /// `8'bz1` or `4'hx`. Returns None where [`NumberLiteral::parse`] does, and
+    /// for decimal literals with x or z digits other than a lone `4'dx`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let literal = NumberLiteral::parse(text)?;
+        if let Some(pattern) = Self::from_literal(&literal) {
+            return Some(pattern);
+        }
+
+        let text: String = text
+            .chars()
+            .filter(|c| *c != '_' && !c.is_whitespace())
+            .collect();
+        let (_, rest) = text.split_once('\'')?;
+        // Skip the signedness and base letters
+        let digits: Vec<char> = rest
+            .trim_start_matches(['s', 'S'])
+            .chars()
+            .skip(1)
+            .collect();
+        let width = literal.bit_width();
+        let digit_bits = match literal.base {
+            NumberBase::Binary => 1,
+            NumberBase::Octal => 3,
+            NumberBase::Hex => 4,
+            NumberBase::Decimal => width,
+        };
+        if literal.base == NumberBase::Decimal && digits.len() != 1 {
+            return None;
+        }
+
+        let mut pattern = Self {
+            width,
+            value: 0,
+            x: 0,
+            z: 0,
+        };
+        let digit_mask = mask(digit_bits);
+        for c in &digits {
+            pattern.value = pattern.value.checked_shl(digit_bits).unwrap_or(0);
+            pattern.x = pattern.x.checked_shl(digit_bits).unwrap_or(0);
+            pattern.z = pattern.z.checked_shl(digit_bits).unwrap_or(0);
+            match c {
+                'x' | 'X' => pattern.x |= digit_mask,
+                'z' | 'Z' | '?' => pattern.z |= digit_mask,
+                _ => pattern.value |= u128::from(c.to_digit(literal.base.radix())?),
+            }
+        }
+
+        let written = digits.len() as u64 * u64::from(digit_bits);
+        if written < u64::from(width) {
+            let above = mask(width) & !mask(written as u32);
+            match digits[0] {
+                'x' | 'X' => pattern.x |= above,
+                'z' | 'Z' | '?' => pattern.z |= above,
+                _ => {}
+            }
+        }
+        pattern.value &= mask(width);
+        pattern.x &= mask(width);
+        pattern.z &= mask(width);
+        Some(pattern)
+    }
+
+    /// The bits of a value with no x or z bits; None if it has any
+    pub fn from_literal(literal: &NumberLiteral) -> Option<Self> {
+        Some(Self {
+            width: literal.bit_width(),
+            value: literal.value?,
+            x: 0,
+            z: 0,
+        })
+    }
+
+    /// Bits a case item of type `case_type` doesn't compare: z and `?` bits
+    /// in a `casez`, x and z bits in a `casex`, none in a plain `case`
+    pub fn wildcards(&self, case_type: &str) -> u128 {
+        match case_type {
+            "casez" => self.z,
+            "casex" => self.x | self.z,
+            _ => 0,
+        }
+    }
+}
+
+pub(crate) fn mask(bits: u32) -> u128 {
+    if bits >= 128 {
+        u128::MAX
+    } else {
+        (1u128 << bits) - 1
+    }
+}
+
+/// Evaluate a constant integer expression
+///
+/// Identifiers are looked up in `parameters`. Returns None for anything that is
+/// not a constant integer expression, including division by zero.
+pub fn evaluate_constant(
+    expr: ExprRef,
+    arena: &ExprArena,
+    parameters: &HashMap<String, NumberLiteral>,
+) -> Option<NumberLiteral> {
+    match arena.get(expr) {
+        Expression::Number(text, _) => NumberLiteral::parse(text),
+        Expression::Identifier(name, _) => parameters.get(name).copied(),
+        Expression::Unary { op, operand, .. } => {
+            let operand = evaluate_constant(*operand, arena, parameters)?;
+            fold_unary(op, operand)
+        }
+        Expression::Binary {
+            op, left, right, ..
+        } => {
+            let left = evaluate_constant(*left, arena, parameters)?;
+            let right = evaluate_constant(*right, arena, parameters)?;
+            fold_binary(op, left, right)
+        }
+        _ => None,
+    }
+}
+
+/// Apply a unary operator to a constant; None for unsupported operators and x/z values
+pub(crate) fn fold_unary(op: &UnaryOp, operand: NumberLiteral) -> Option<NumberLiteral> {
+    let value = operand.to_i128()?;
+    let result = match op {
+        UnaryOp::Plus => value,
+        UnaryOp::Minus => value.checked_neg()?,
+        UnaryOp::Not => !value,
+        UnaryOp::LogicalNot => return Some(boolean(value == 0)),
+        _ => return None,
+    };
+    Some(NumberLiteral::from_i128(
+        result,
+        operand.width,
+        operand.signed,
+    ))
+}
+
+/// Apply a binary operator to two constants; None for unsupported operators, x/z
+/// values, overflow and division by zero
+pub(crate) fn fold_binary(
+    op: &BinaryOp,
+    left: NumberLiteral,
+    right: NumberLiteral,
+) -> Option<NumberLiteral> {
+    let (l, r) = (left.to_i128()?, right.to_i128()?);
+
+    let width = match (left.width, right.width) {
+        (None, None) => None,
+        (l, r) => Some(l.unwrap_or(UNSIZED_WIDTH).max(r.unwrap_or(UNSIZED_WIDTH))),
+    };
+    let signed = left.signed && right.signed;
+
+    let result = match op {
+        BinaryOp::Add => l.checked_add(r)?,
+        BinaryOp::Sub => l.checked_sub(r)?,
+        BinaryOp::Mul => l.checked_mul(r)?,
+        BinaryOp::Div => l.checked_div(r)?,
+        BinaryOp::Modulo => l.checked_rem(r)?,
+        BinaryOp::Power => l.checked_pow(u32::try_from(r).ok()?)?,
+        BinaryOp::And => l & r,
+        BinaryOp::Or => l | r,
+        BinaryOp::Xor => l ^ r,
+        BinaryOp::BitwiseXnor => !(l ^ r),
+        BinaryOp::LogicalShiftLeft | BinaryOp::ArithmeticShiftLeft => {
+            let shifted = l.checked_shl(u32::try_from(r).ok()?)?;
+            return Some(NumberLiteral::from_i128(shifted, left.width, left.signed));
+        }
+        BinaryOp::LogicalShiftRight => {
+            let value = left.value? >> u32::try_from(r).ok()?.min(127);
+            return Some(NumberLiteral::from_i128(
+                value as i128,
+                left.width,
+                left.signed,
+            ));
+        }
+        BinaryOp::ArithmeticShiftRight => {
+            let shifted = l >> u32::try_from(r).ok()?.min(127);
+            return Some(NumberLiteral::from_i128(shifted, left.width, left.signed));
+        }
+        BinaryOp::Equal | BinaryOp::CaseEqual => return Some(boolean(l == r)),
+        BinaryOp::NotEqual | BinaryOp::CaseNotEqual => return Some(boolean(l != r)),
+        BinaryOp::GreaterThan => return Some(boolean(l > r)),
+        BinaryOp::LessThan => return Some(boolean(l < r)),
+        BinaryOp::GreaterEqual => return Some(boolean(l >= r)),
+        BinaryOp::LessEqual => return Some(boolean(l <= r)),
+        BinaryOp::LogicalAnd => return Some(boolean(l != 0 && r != 0)),
+        BinaryOp::LogicalOr => return Some(boolean(l != 0 || r != 0)),
+        _ => return None,
+    };
+    Some(NumberLiteral::from_i128(result, width, signed))
+}
+
+pub(crate) fn boolean(value: bool) -> NumberLiteral {
+    NumberLiteral::from_i128(i128::from(value), Some(1), false)
+}
+
+/// Evaluate the parameters declared directly in a module body, in declaration order
+///
+/// Each parameter can refer to the ones declared before it. Parameters whose
+/// value is not a constant integer expression are left out.
+pub fn parameter_values(
+    items: &[ModuleItemRef],
+    module_item_arena: &ModuleItemArena,
+    expr_arena: &ExprArena,
+) -> HashMap<String, NumberLiteral> {
+    let mut values = HashMap::new();
+    for item_ref in items {
+        let ModuleItem::ParameterDeclaration {
+            range, name, value, ..
+        } = module_item_arena.get(*item_ref)
+        else {
+            continue;
+        };
+
+        let Some(mut literal) = evaluate_constant(*value, expr_arena, &values) else {
+            continue;
+        };
+        // An explicit packed range fixes the width of the parameter
+        if let Some(range) = range {
+            let msb = NumberLiteral::parse(&range.msb).and_then(|n| n.to_i128());
+            let lsb = NumberLiteral::parse(&range.lsb).and_then(|n| n.to_i128());
+            if let (Some(msb), Some(lsb)) = (msb, lsb) {
+                if let Ok(width @ 1..=128) = u32::try_from((msb - lsb).abs() + 1) {
+                    literal = literal.resize(width);
+                }
+            }
+        }
+        values.insert(name.clone(), literal);
+    }
+    values
+}
@@ -0,0 +1,106 @@
+//! Work spread over threads
+//!
+//! The design units of a file, and the files of a project, are analyzed
+//! independently of each other, so a large run keeps every core busy.
+//! Results always come back in the order of the inputs, so the diagnostics
+//! of a run don't depend on how its work was scheduled. Targets without
+//! threads, like wasm32-unknown-unknown, run everything on the calling
+//! thread. A `map` inside another one runs on the thread that calls it, so
+//! nested work doesn't start a thread per core for every item.
+
+use std::cell::Cell;
+use std::panic;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// Stack size of the threads that parse and analyze: the parser recurses
+/// deeply, and its grammar is built in one large stack frame, so the 2 MiB
+/// default of a spawned thread is not enough in an unoptimized build. The
+/// parser runs the grammar through [`with_stack`], so callers don't need to
+/// size their own threads.
+pub const STACK_SIZE: usize = 16 * 1024 * 1024;
+
+thread_local! {
+    /// Whether this thread is one of the workers of a `map`
+    static IN_WORKER: Cell<bool> = const { Cell::new(false) };
+}
+
+/// `f` applied to every item, on up to one thread per core, in the order of
+/// `items`
+///
+/// Threads take the next item as soon as they finish one, so a few large
+/// items don't hold up the rest. Called from one of those threads, it runs
+/// on that thread alone. A panic in `f` is raised again on the calling
+/// thread.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = thread_count().min(items.len());
+    if threads <= 1 || IN_WORKER.get() {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                let worker = thread::Builder::new().stack_size(STACK_SIZE);
+                worker.spawn_scoped(scope, || {
+                    IN_WORKER.set(true);
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(item) = items.get(index) else {
+                            break;
+                        };
+                        done.push((index, f(item)));
+                    }
+                    done
+                })
+            })
+            .collect::<Result<_, _>>()
+            .expect("failed to start a worker thread");
+        workers
+            .into_iter()
+            .flat_map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|err| panic::resume_unwind(err))
+            })
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// `f` run with a stack of at least [`STACK_SIZE`]: on the calling thread if
+/// it is a worker of a `map` or there are no threads, on a thread of its own
+/// otherwise. A panic in `f` is raised again on the calling thread.
+pub fn with_stack<R, F>(f: F) -> R
+where
+    R: Send,
+    F: FnOnce() -> R + Send,
+{
+    if cfg!(target_arch = "wasm32") || IN_WORKER.get() {
+        return f();
+    }
+    thread::scope(|scope| {
+        thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("failed to start a thread")
+            .join()
+            .unwrap_or_else(|err| panic::resume_unwind(err))
+    })
+}
+
+/// Threads worth starting: one per core
+fn thread_count() -> usize {
+    if cfg!(target_arch = "wasm32") {
+        return 1;
+    }
+    thread::available_parallelism().map_or(1, |count| count.get())
+}
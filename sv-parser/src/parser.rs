@@ -1,13 +1,32 @@
+use chumsky::error::SimpleReason;
 use chumsky::prelude::*;
-use std::collections::HashMap;
+use chumsky::Stream;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
 
-use crate::preprocessor::Preprocessor;
+use crate::files;
+use crate::lexer::{KeywordVersion, Lexer, Spacing, TokenKind};
+use crate::preprocessor::{
+    collect_macro_definitions, preprocess, MacroDefinition, PreprocessLimits, Preprocessor,
+    SourceMap,
+};
+use crate::stats::{unit_name, ParseTimings, Stopwatch};
+use crate::timescale::format_time;
 use crate::{
-    AssignmentOp, BinaryOp, ClassItem, ClassQualifier, Delay, DriveStrength, ExprArena, ExprRef,
-    Expression, ModuleItem, ModuleItemArena, ModuleItemRef, ParseError, ParseErrorType, Port,
-    PortDirection, ProceduralBlockType, Range, SingleParseError, SourceUnit, Span, Statement,
-    StmtArena, StmtRef, UnaryOp, UnpackedDimension,
+    is_net_type, AssertionDeclarationKind, AssertionDeferral, AssertionKind, AssignmentOp,
+    AssignmentPatternItem, BinaryOp, CaseItem, CaseMatchesItem, CellReference, ClassItem,
+    ClassParameter, ClassQualifier, ClockingSignal, ConfigRule, ConstraintBlock, ConstraintExpr,
+    CycleBound, CycleRange, DataType, Delay, DistItem, DriveStrength, Edge, ElaborationSeverity,
+    EnumMember, EnumType, EventControl, EventExpression, ExprArena, ExprRef, Expression,
+    GenerateCaseItem, Genvar, IncDecOp, JoinKind, Label, LibrarySelection, LoopVariable, Modport,
+    ModportPort, ModuleItem, ModuleItemArena, ModuleItemRef, PackageImport, ParameterArgument,
+    ParseError, ParseErrorType, Pattern, Port, PortConnection, PortDirection, ProceduralBlockType,
+    PropertyExpr, PropertyOp, RandQualifier, Range, RepetitionKind, SingleParseError,
+    SourceLocation, SourceUnit, Span, Statement, StmtArena, StmtRef, StructMember, TimeUnit,
+    TimeValue, TimingControl, TypeReference, UnaryOp, UnpackedDimension,
 };
 
 /// Temporary expression type used during parsing with Box-based recursion
@@ -17,6 +36,8 @@ enum ParsedExpression {
     Identifier(String, Span),
     Number(String, Span),
     StringLiteral(String, Span),
+    This(Span),
+    Super(Span),
     Binary {
         op: BinaryOp,
         left: Box<ParsedExpression>,
@@ -28,6 +49,12 @@ enum ParsedExpression {
         operand: Box<ParsedExpression>,
         span: Span,
     },
+    IncDec {
+        op: IncDecOp,
+        prefix: bool,
+        operand: Box<ParsedExpression>,
+        span: Span,
+    },
     #[allow(dead_code)]
     MacroUsage {
         name: String,
@@ -55,6 +82,39 @@ enum ParsedExpression {
         arguments: Vec<ParsedExpression>,
         span: Span,
     },
+    Conditional {
+        condition: Box<ParsedExpression>,
+        then_expr: Box<ParsedExpression>,
+        else_expr: Box<ParsedExpression>,
+        span: Span,
+    },
+    TaggedUnion {
+        member: String,
+        member_span: Span,
+        value: Option<Box<ParsedExpression>>,
+        span: Span,
+    },
+    ScopedIdentifier {
+        scope: String,
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    AssignmentPattern {
+        /// Member name or `default` with its span, and the value
+        items: Vec<(Option<(String, Span)>, ParsedExpression)>,
+        span: Span,
+    },
+    ValueRange {
+        low: Box<ParsedExpression>,
+        high: Box<ParsedExpression>,
+        span: Span,
+    },
+    RandomizeWith {
+        call: Box<ParsedExpression>,
+        constraints: Vec<ParsedConstraint>,
+        span: Span,
+    },
 }
 
 impl ParsedExpression {
@@ -68,6 +128,8 @@ impl ParsedExpression {
             ParsedExpression::StringLiteral(s, span) => {
                 arena.alloc(Expression::StringLiteral(s, span))
             }
+            ParsedExpression::This(span) => arena.alloc(Expression::This(span)),
+            ParsedExpression::Super(span) => arena.alloc(Expression::Super(span)),
             ParsedExpression::Binary {
                 op,
                 left,
@@ -91,6 +153,20 @@ impl ParsedExpression {
                     span,
                 })
             }
+            ParsedExpression::IncDec {
+                op,
+                prefix,
+                operand,
+                span,
+            } => {
+                let operand_ref = operand.flatten(arena);
+                arena.alloc(Expression::IncDec {
+                    op,
+                    prefix,
+                    operand: operand_ref,
+                    span,
+                })
+            }
             ParsedExpression::MacroUsage {
                 name,
                 name_span,
@@ -155,8 +231,444 @@ impl ParsedExpression {
                     span,
                 })
             }
+            ParsedExpression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                span,
+            } => {
+                let condition_ref = condition.flatten(arena);
+                let then_ref = then_expr.flatten(arena);
+                let else_ref = else_expr.flatten(arena);
+                arena.alloc(Expression::Conditional {
+                    condition: condition_ref,
+                    then_expr: then_ref,
+                    else_expr: else_ref,
+                    span,
+                })
+            }
+            ParsedExpression::TaggedUnion {
+                member,
+                member_span,
+                value,
+                span,
+            } => {
+                let value_ref = value.map(|v| v.flatten(arena));
+                arena.alloc(Expression::TaggedUnion {
+                    member,
+                    member_span,
+                    value: value_ref,
+                    span,
+                })
+            }
+            ParsedExpression::ScopedIdentifier {
+                scope,
+                name,
+                name_span,
+                span,
+            } => arena.alloc(Expression::ScopedIdentifier {
+                scope,
+                name,
+                name_span,
+                span,
+            }),
+            ParsedExpression::AssignmentPattern { items, span } => {
+                let items = items
+                    .into_iter()
+                    .map(|(key, value)| {
+                        let (key, key_span) = match key {
+                            Some((key, key_span)) => (Some(key), key_span),
+                            None => (None, (0, 0)),
+                        };
+                        AssignmentPatternItem {
+                            key,
+                            key_span,
+                            value: value.flatten(arena),
+                        }
+                    })
+                    .collect();
+                arena.alloc(Expression::AssignmentPattern { items, span })
+            }
+            ParsedExpression::ValueRange { low, high, span } => {
+                let low_ref = low.flatten(arena);
+                let high_ref = high.flatten(arena);
+                arena.alloc(Expression::ValueRange {
+                    low: low_ref,
+                    high: high_ref,
+                    span,
+                })
+            }
+            ParsedExpression::RandomizeWith {
+                call,
+                constraints,
+                span,
+            } => {
+                let call_ref = call.flatten(arena);
+                let constraints = constraints
+                    .into_iter()
+                    .map(|constraint| constraint.flatten(arena))
+                    .collect();
+                arena.alloc(Expression::RandomizeWith {
+                    call: call_ref,
+                    constraints,
+                    span,
+                })
+            }
+        }
+    }
+}
+
+/// Temporary constraint that holds ParsedExpressions during parsing
+#[derive(Clone, PartialEq)]
+enum ParsedConstraint {
+    Expression {
+        soft: bool,
+        expr: ParsedExpression,
+        span: Span,
+    },
+    Inside {
+        soft: bool,
+        expr: ParsedExpression,
+        set: Vec<ParsedExpression>,
+        span: Span,
+    },
+    Dist {
+        soft: bool,
+        expr: ParsedExpression,
+        /// Value, weight, and whether the weight is shared by a range (`:/`)
+        items: Vec<(ParsedExpression, ParsedExpression, bool)>,
+        span: Span,
+    },
+    Implication {
+        condition: ParsedExpression,
+        constraints: Vec<ParsedConstraint>,
+        span: Span,
+    },
+    IfElse {
+        condition: ParsedExpression,
+        then_constraints: Vec<ParsedConstraint>,
+        else_constraints: Vec<ParsedConstraint>,
+        span: Span,
+    },
+}
+
+/// What follows the expression of a constraint
+#[derive(Clone)]
+enum ParsedConstraintTail {
+    Inside(Vec<ParsedExpression>),
+    Dist(Vec<(ParsedExpression, ParsedExpression, bool)>),
+    Implication(Vec<ParsedConstraint>),
+    None,
+}
+
+impl ParsedConstraint {
+    /// The constraint of `expr` followed by `tail`
+    ///
+    /// `->` binds looser than any other operator, so in `a -> b inside {1, 2}`
+    /// the expression parser reads `a -> b` as one implication; it is taken
+    /// apart here so that the set applies to `b`.
+    fn new(soft: bool, expr: ParsedExpression, tail: ParsedConstraintTail, span: Span) -> Self {
+        if let ParsedExpression::Binary {
+            op: BinaryOp::LogicalImpl,
+            left,
+            right,
+            ..
+        } = expr
+        {
+            return ParsedConstraint::Implication {
+                condition: *left,
+                constraints: vec![Self::new(soft, *right, tail, span)],
+                span,
+            };
+        }
+        match tail {
+            ParsedConstraintTail::Inside(set) => ParsedConstraint::Inside {
+                soft,
+                expr,
+                set,
+                span,
+            },
+            ParsedConstraintTail::Dist(items) => ParsedConstraint::Dist {
+                soft,
+                expr,
+                items,
+                span,
+            },
+            ParsedConstraintTail::Implication(constraints) => ParsedConstraint::Implication {
+                condition: expr,
+                constraints,
+                span,
+            },
+            ParsedConstraintTail::None => ParsedConstraint::Expression { soft, expr, span },
+        }
+    }
+
+    fn flatten(self, arena: &mut ExprArena) -> ConstraintExpr {
+        let flatten_all =
+            |constraints: Vec<ParsedConstraint>, arena: &mut ExprArena| -> Vec<ConstraintExpr> {
+                constraints
+                    .into_iter()
+                    .map(|constraint| constraint.flatten(arena))
+                    .collect()
+            };
+        match self {
+            ParsedConstraint::Expression { soft, expr, span } => ConstraintExpr::Expression {
+                soft,
+                expr: expr.flatten(arena),
+                span,
+            },
+            ParsedConstraint::Inside {
+                soft,
+                expr,
+                set,
+                span,
+            } => ConstraintExpr::Inside {
+                soft,
+                expr: expr.flatten(arena),
+                set: set.into_iter().map(|value| value.flatten(arena)).collect(),
+                span,
+            },
+            ParsedConstraint::Dist {
+                soft,
+                expr,
+                items,
+                span,
+            } => ConstraintExpr::Dist {
+                soft,
+                expr: expr.flatten(arena),
+                items: items
+                    .into_iter()
+                    .map(|(value, weight, per_range)| DistItem {
+                        value: value.flatten(arena),
+                        weight: weight.flatten(arena),
+                        per_range,
+                    })
+                    .collect(),
+                span,
+            },
+            ParsedConstraint::Implication {
+                condition,
+                constraints,
+                span,
+            } => ConstraintExpr::Implication {
+                condition: condition.flatten(arena),
+                constraints: flatten_all(constraints, arena),
+                span,
+            },
+            ParsedConstraint::IfElse {
+                condition,
+                then_constraints,
+                else_constraints,
+                span,
+            } => ConstraintExpr::IfElse {
+                condition: condition.flatten(arena),
+                then_constraints: flatten_all(then_constraints, arena),
+                else_constraints: flatten_all(else_constraints, arena),
+                span,
+            },
+        }
+    }
+}
+
+/// Temporary sequence or property that holds ParsedExpressions during parsing
+#[derive(Clone)]
+enum ParsedPropertyExpr {
+    Expression(ParsedExpression, Span),
+    Delay {
+        left: Option<Box<ParsedPropertyExpr>>,
+        cycles: ParsedCycleRange,
+        right: Box<ParsedPropertyExpr>,
+        span: Span,
+    },
+    Repetition {
+        operand: Box<ParsedPropertyExpr>,
+        kind: RepetitionKind,
+        cycles: ParsedCycleRange,
+        span: Span,
+    },
+    Binary {
+        op: PropertyOp,
+        left: Box<ParsedPropertyExpr>,
+        right: Box<ParsedPropertyExpr>,
+        span: Span,
+    },
+    Not {
+        operand: Box<ParsedPropertyExpr>,
+        span: Span,
+    },
+    Clocked {
+        clocking_event: ParsedExpression,
+        operand: Box<ParsedPropertyExpr>,
+        span: Span,
+    },
+}
+
+/// Temporary number of clock ticks: the count, and the upper end of a
+/// range, which is None for `$`
+#[derive(Clone)]
+struct ParsedCycleRange {
+    low: ParsedExpression,
+    high: Option<Option<ParsedExpression>>,
+}
+
+impl ParsedCycleRange {
+    fn flatten(self, arena: &mut ExprArena) -> CycleRange {
+        CycleRange {
+            low: self.low.flatten(arena),
+            high: self.high.map(|high| match high {
+                Some(high) => CycleBound::Count(high.flatten(arena)),
+                None => CycleBound::Unbounded,
+            }),
+        }
+    }
+}
+
+impl ParsedPropertyExpr {
+    fn span(&self) -> Span {
+        match self {
+            ParsedPropertyExpr::Expression(_, span)
+            | ParsedPropertyExpr::Delay { span, .. }
+            | ParsedPropertyExpr::Repetition { span, .. }
+            | ParsedPropertyExpr::Binary { span, .. }
+            | ParsedPropertyExpr::Not { span, .. }
+            | ParsedPropertyExpr::Clocked { span, .. } => *span,
+        }
+    }
+
+    fn flatten(self, arena: &mut ExprArena) -> PropertyExpr {
+        let boxed = |property: Box<ParsedPropertyExpr>, arena: &mut ExprArena| {
+            Box::new(property.flatten(arena))
+        };
+        match self {
+            ParsedPropertyExpr::Expression(expr, span) => PropertyExpr::Expression {
+                expr: expr.flatten(arena),
+                span,
+            },
+            ParsedPropertyExpr::Delay {
+                left,
+                cycles,
+                right,
+                span,
+            } => PropertyExpr::Delay {
+                left: left.map(|left| boxed(left, arena)),
+                cycles: cycles.flatten(arena),
+                right: boxed(right, arena),
+                span,
+            },
+            ParsedPropertyExpr::Repetition {
+                operand,
+                kind,
+                cycles,
+                span,
+            } => PropertyExpr::Repetition {
+                operand: boxed(operand, arena),
+                kind,
+                cycles: cycles.flatten(arena),
+                span,
+            },
+            ParsedPropertyExpr::Binary {
+                op,
+                left,
+                right,
+                span,
+            } => PropertyExpr::Binary {
+                op,
+                left: boxed(left, arena),
+                right: boxed(right, arena),
+                span,
+            },
+            ParsedPropertyExpr::Not { operand, span } => PropertyExpr::Not {
+                operand: boxed(operand, arena),
+                span,
+            },
+            ParsedPropertyExpr::Clocked {
+                clocking_event,
+                operand,
+                span,
+            } => PropertyExpr::Clocked {
+                clocking_event: clocking_event.flatten(arena),
+                operand: boxed(operand, arena),
+                span,
+            },
+        }
+    }
+}
+
+/// Group `first op operand op operand ...`, where every operator has the
+/// same precedence, from the right for `throughout` and the implications and
+/// from the left for the rest
+fn group_property(
+    first: ParsedPropertyExpr,
+    rest: Vec<(PropertyOp, ParsedPropertyExpr)>,
+) -> ParsedPropertyExpr {
+    let binary =
+        |op, left: ParsedPropertyExpr, right: ParsedPropertyExpr| ParsedPropertyExpr::Binary {
+            op,
+            span: (left.span().0, right.span().1),
+            left: Box::new(left),
+            right: Box::new(right),
+        };
+    let right_associative = rest.first().is_some_and(|(op, _)| {
+        matches!(
+            op,
+            PropertyOp::Throughout
+                | PropertyOp::OverlappedImplication
+                | PropertyOp::NonOverlappedImplication
+        )
+    });
+    if !right_associative {
+        return rest
+            .into_iter()
+            .fold(first, |left, (op, right)| binary(op, left, right));
+    }
+    // a op b op c is a op (b op c): each operator takes the operand on its
+    // left and everything after it
+    let mut operands = vec![first];
+    let mut operators = Vec::new();
+    for (op, operand) in rest {
+        operators.push(op);
+        operands.push(operand);
+    }
+    let mut right = operands.pop().expect("a chain has an operand");
+    while let Some(op) = operators.pop() {
+        let left = operands.pop().expect("an operator has two operands");
+        right = binary(op, left, right);
+    }
+    right
+}
+
+/// Group `first op operand op operand ...` by operator precedence, with
+/// operators of the same precedence taking the operand on their left first
+fn group_binary(
+    first: ParsedExpression,
+    rest: Vec<(BinaryOp, ParsedExpression)>,
+) -> ParsedExpression {
+    fn reduce(operands: &mut Vec<ParsedExpression>, op: BinaryOp) {
+        let right = operands.pop().expect("an operator has two operands");
+        let left = operands.pop().expect("an operator has two operands");
+        operands.push(ParsedExpression::Binary {
+            op,
+            left: Box::new(left),
+            right: Box::new(right),
+            span: (0, 0),
+        });
+    }
+
+    let mut operands = vec![first];
+    let mut operators: Vec<BinaryOp> = Vec::new();
+    for (op, operand) in rest {
+        while let Some(pending) =
+            operators.pop_if(|pending| pending.precedence() >= op.precedence())
+        {
+            reduce(&mut operands, pending);
         }
+        operators.push(op);
+        operands.push(operand);
+    }
+    while let Some(op) = operators.pop() {
+        reduce(&mut operands, op);
     }
+    operands.pop().expect("a chain has an operand")
 }
 
 /// Temporary statement that holds ParsedExpressions during parsing
@@ -165,7 +677,9 @@ enum ParsedStatement {
     Assignment {
         target: ParsedExpression,
         op: AssignmentOp,
+        delay: Option<Delay>,
         expr: ParsedExpression,
+        span: Span,
     },
     SystemCall {
         name: String,
@@ -175,14 +689,38 @@ enum ParsedStatement {
     CaseStatement {
         modifier: Option<String>,
         case_type: String,
+        inside: bool,
         expr: ParsedExpression,
+        items: Vec<ParsedCaseItem>,
+        span: Span,
     },
     AssertProperty {
-        property_expr: ParsedExpression,
-        action_block: Option<Box<ParsedStatement>>,
+        kind: AssertionKind,
+        clocking_event: Option<ParsedExpression>,
+        disable_iff: Option<ParsedExpression>,
+        property: ParsedPropertyExpr,
+        pass_action: Option<Box<ParsedStatement>>,
+        fail_action: Option<Box<ParsedStatement>>,
+        span: Span,
+    },
+    ImmediateAssertion {
+        kind: AssertionKind,
+        deferral: Option<AssertionDeferral>,
+        condition: ParsedExpression,
+        pass_action: Option<Box<ParsedStatement>>,
+        fail_action: Option<Box<ParsedStatement>>,
+        span: Span,
     },
     ExpressionStatement {
         expr: ParsedExpression,
+        span: Span,
+    },
+    Conditional {
+        modifier: Option<String>,
+        condition: ParsedExpression,
+        then_statement: Box<ParsedStatement>,
+        else_statement: Option<Box<ParsedStatement>>,
+        span: Span,
     },
     VariableDeclaration {
         data_type: String,
@@ -191,23 +729,363 @@ enum ParsedStatement {
         initial_value: Option<ParsedExpression>,
         span: Span,
     },
+    Block {
+        label: Option<Label>,
+        statements: Vec<ParsedStatement>,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    Fork {
+        label: Option<Label>,
+        statements: Vec<ParsedStatement>,
+        join: JoinKind,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    Labeled {
+        label: Label,
+        statement: Box<ParsedStatement>,
+        span: Span,
+    },
+    EventTrigger {
+        nonblocking: bool,
+        event: ParsedExpression,
+        span: Span,
+    },
+    WaitFork {
+        span: Span,
+    },
+    DisableFork {
+        span: Span,
+    },
+    Disable {
+        target: String,
+        target_span: Span,
+        span: Span,
+    },
+    CaseMatches {
+        modifier: Option<String>,
+        case_type: String,
+        expr: ParsedExpression,
+        items: Vec<ParsedCaseMatchesItem>,
+        span: Span,
+    },
+    Return {
+        value: Option<ParsedExpression>,
+        span: Span,
+    },
+    ForLoop {
+        init: Vec<ParsedStatement>,
+        condition: Option<ParsedExpression>,
+        step: Vec<ParsedStatement>,
+        body: Box<ParsedStatement>,
+        span: Span,
+    },
+    While {
+        condition: ParsedExpression,
+        body: Box<ParsedStatement>,
+        span: Span,
+    },
+    DoWhile {
+        body: Box<ParsedStatement>,
+        condition: ParsedExpression,
+        span: Span,
+    },
+    Repeat {
+        count: ParsedExpression,
+        body: Box<ParsedStatement>,
+        span: Span,
+    },
+    Timed {
+        control: ParsedTimingControl,
+        statement: Option<Box<ParsedStatement>>,
+        span: Span,
+    },
+    Forever {
+        body: Box<ParsedStatement>,
+        span: Span,
+    },
+    Foreach {
+        array: String,
+        array_span: Span,
+        indices: Vec<Option<LoopVariable>>,
+        body: Box<ParsedStatement>,
+        span: Span,
+    },
+    /// `int i = 0, j = 1;`: one declaration per variable, which become
+    /// statements of their own when flattened
+    Declarations {
+        declarations: Vec<ParsedStatement>,
+        span: Span,
+    },
 }
 
-impl ParsedStatement {
-    fn flatten(self, expr_arena: &mut ExprArena, _stmt_arena: &mut StmtArena) -> Statement {
+/// Temporary event control that holds ParsedExpressions during parsing
+#[derive(Clone)]
+enum ParsedEventControl {
+    Implicit(Span),
+    Events(Vec<ParsedEventExpression>, Span),
+}
+
+/// Temporary timing control that holds ParsedExpressions during parsing
+#[derive(Clone)]
+enum ParsedTimingControl {
+    Delay(Delay),
+    Event(ParsedEventControl),
+    Wait(ParsedExpression),
+}
+
+impl ParsedTimingControl {
+    fn flatten(self, expr_arena: &mut ExprArena) -> TimingControl {
         match self {
-            ParsedStatement::Assignment { target, op, expr } => {
-                let target_ref = target.flatten(expr_arena);
-                let expr_ref = expr.flatten(expr_arena);
-                Statement::Assignment {
-                    target: target_ref,
-                    op,
-                    expr: expr_ref,
-                    span: (0, 0),
-                }
+            ParsedTimingControl::Delay(delay) => TimingControl::Delay(delay),
+            ParsedTimingControl::Event(control) => {
+                TimingControl::Event(control.flatten(expr_arena))
             }
-            ParsedStatement::SystemCall { name, args, span } => {
-                let arg_refs = args.into_iter().map(|a| a.flatten(expr_arena)).collect();
+            ParsedTimingControl::Wait(condition) => {
+                TimingControl::Wait(condition.flatten(expr_arena))
+            }
+        }
+    }
+}
+
+/// Temporary event of an event control that holds ParsedExpressions during parsing
+#[derive(Clone)]
+struct ParsedEventExpression {
+    edge: Option<Edge>,
+    signal: ParsedExpression,
+    iff: Option<ParsedExpression>,
+    span: Span,
+}
+
+impl ParsedEventControl {
+    fn flatten(self, expr_arena: &mut ExprArena) -> EventControl {
+        match self {
+            ParsedEventControl::Implicit(span) => EventControl::Implicit { span },
+            ParsedEventControl::Events(events, span) => EventControl::Events {
+                events: events
+                    .into_iter()
+                    .map(|event| EventExpression {
+                        edge: event.edge,
+                        signal: event.signal.flatten(expr_arena),
+                        iff: event.iff.map(|iff| iff.flatten(expr_arena)),
+                        span: event.span,
+                    })
+                    .collect(),
+                span,
+            },
+        }
+    }
+}
+
+/// Temporary case item that holds ParsedExpressions during parsing
+#[derive(Clone)]
+struct ParsedCaseItem {
+    values: Vec<ParsedExpression>,
+    statement: ParsedStatement,
+    span: Span,
+}
+
+/// Temporary generate `case` item that holds ParsedExpressions during parsing
+#[derive(Clone)]
+struct ParsedGenerateCaseItem {
+    values: Vec<ParsedExpression>,
+    body: ParsedModuleItem,
+    span: Span,
+}
+
+/// Temporary enum type whose member values are ParsedExpressions during parsing
+#[derive(Clone)]
+struct ParsedEnumType {
+    base_type: Option<String>,
+    signing: Option<String>,
+    range: Option<Range>,
+    members: Vec<(String, Span, Option<ParsedExpression>)>,
+}
+
+/// Temporary data type whose enum member values are ParsedExpressions during parsing
+#[derive(Clone)]
+enum ParsedDataType {
+    Named(String),
+    Struct {
+        packed: bool,
+        members: Vec<StructMember>,
+    },
+    Union {
+        tagged: bool,
+        packed: bool,
+        members: Vec<StructMember>,
+    },
+    Enum(ParsedEnumType),
+    /// `fifo#(int, 16)`: name, its span and the parameter values
+    Specialized(String, Span, Vec<ParsedParameterArgument>),
+}
+
+impl ParsedDataType {
+    fn flatten(self, expr_arena: &mut ExprArena) -> DataType {
+        match self {
+            ParsedDataType::Named(name) => DataType::Named(name),
+            ParsedDataType::Struct { packed, members } => DataType::Struct { packed, members },
+            ParsedDataType::Union {
+                tagged,
+                packed,
+                members,
+            } => DataType::Union {
+                tagged,
+                packed,
+                members,
+            },
+            ParsedDataType::Enum(enum_type) => DataType::Enum(enum_type.flatten(expr_arena)),
+            ParsedDataType::Specialized(name, name_span, arguments) => {
+                DataType::Specialized(TypeReference {
+                    name,
+                    name_span,
+                    arguments: arguments
+                        .into_iter()
+                        .map(|argument| argument.flatten(expr_arena))
+                        .collect(),
+                })
+            }
+        }
+    }
+}
+
+/// Temporary class parameter value that holds a ParsedExpression during parsing
+#[derive(Clone)]
+enum ParsedParameterArgument {
+    Type(String),
+    Value(ParsedExpression),
+}
+
+impl ParsedParameterArgument {
+    fn flatten(self, expr_arena: &mut ExprArena) -> ParameterArgument {
+        match self {
+            ParsedParameterArgument::Type(data_type) => ParameterArgument::Type(data_type),
+            ParsedParameterArgument::Value(expr) => {
+                ParameterArgument::Value(expr.flatten(expr_arena))
+            }
+        }
+    }
+}
+
+/// Temporary class parameter port whose default holds a ParsedExpression during parsing
+#[derive(Clone)]
+struct ParsedClassParameter {
+    is_type: bool,
+    data_type: Option<String>,
+    name: String,
+    name_span: Span,
+    default: Option<ParsedParameterArgument>,
+    span: Span,
+}
+
+impl ParsedClassParameter {
+    fn flatten(self, expr_arena: &mut ExprArena) -> ClassParameter {
+        ClassParameter {
+            is_type: self.is_type,
+            data_type: self.data_type,
+            name: self.name,
+            name_span: self.name_span,
+            default: self.default.map(|argument| argument.flatten(expr_arena)),
+            span: self.span,
+        }
+    }
+}
+
+impl ParsedEnumType {
+    fn flatten(self, expr_arena: &mut ExprArena) -> EnumType {
+        EnumType {
+            base_type: self.base_type,
+            signing: self.signing,
+            range: self.range,
+            members: self
+                .members
+                .into_iter()
+                .map(|(name, name_span, value)| EnumMember {
+                    name,
+                    name_span,
+                    value: value.map(|e| e.flatten(expr_arena)),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Temporary `case ... matches` arm that holds ParsedExpressions during parsing
+#[derive(Clone)]
+struct ParsedCaseMatchesItem {
+    pattern: Option<ParsedPattern>,
+    guard: Option<ParsedExpression>,
+    statement: ParsedStatement,
+    span: Span,
+}
+
+/// Temporary pattern that holds ParsedExpressions during parsing
+#[derive(Clone)]
+enum ParsedPattern {
+    Variable(String, Span),
+    Wildcard(Span),
+    Tagged {
+        member: String,
+        member_span: Span,
+        pattern: Option<Box<ParsedPattern>>,
+        span: Span,
+    },
+    Constant(ParsedExpression),
+}
+
+impl ParsedPattern {
+    fn flatten(self, expr_arena: &mut ExprArena) -> Pattern {
+        match self {
+            ParsedPattern::Variable(name, span) => Pattern::Variable(name, span),
+            ParsedPattern::Wildcard(span) => Pattern::Wildcard(span),
+            ParsedPattern::Tagged {
+                member,
+                member_span,
+                pattern,
+                span,
+            } => Pattern::Tagged {
+                member,
+                member_span,
+                pattern: pattern.map(|p| Box::new(p.flatten(expr_arena))),
+                span,
+            },
+            ParsedPattern::Constant(expr) => Pattern::Constant(expr.flatten(expr_arena)),
+        }
+    }
+}
+
+impl ParsedStatement {
+    /// The statements this one stands for in a list of statements: the
+    /// declarations of a declaration of several variables, else itself
+    fn declarations(self) -> Vec<ParsedStatement> {
+        match self {
+            ParsedStatement::Declarations { declarations, .. } => declarations,
+            statement => vec![statement],
+        }
+    }
+
+    fn flatten(self, expr_arena: &mut ExprArena, stmt_arena: &mut StmtArena) -> Statement {
+        match self {
+            ParsedStatement::Assignment {
+                target,
+                op,
+                delay,
+                expr,
+                span,
+            } => {
+                let target_ref = target.flatten(expr_arena);
+                let expr_ref = expr.flatten(expr_arena);
+                Statement::Assignment {
+                    target: target_ref,
+                    op,
+                    delay,
+                    expr: expr_ref,
+                    span,
+                }
+            }
+            ParsedStatement::SystemCall { name, args, span } => {
+                let arg_refs = args.into_iter().map(|a| a.flatten(expr_arena)).collect();
                 Statement::SystemCall {
                     name,
                     args: arg_refs,
@@ -217,36 +1095,116 @@ impl ParsedStatement {
             ParsedStatement::CaseStatement {
                 modifier,
                 case_type,
+                inside,
                 expr,
+                items,
+                span,
             } => {
                 let expr_ref = expr.flatten(expr_arena);
+                let items = items
+                    .into_iter()
+                    .map(|item| {
+                        let values = item
+                            .values
+                            .into_iter()
+                            .map(|value| value.flatten(expr_arena))
+                            .collect();
+                        let stmt = item.statement.flatten(expr_arena, stmt_arena);
+                        CaseItem {
+                            values,
+                            statement: stmt_arena.alloc(stmt),
+                            span: item.span,
+                        }
+                    })
+                    .collect();
                 Statement::CaseStatement {
                     modifier,
                     case_type,
+                    inside,
                     expr: expr_ref,
-                    span: (0, 0),
+                    items,
+                    span,
                 }
             }
             ParsedStatement::AssertProperty {
-                property_expr,
-                action_block,
+                kind,
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                span,
             } => {
-                let property_ref = property_expr.flatten(expr_arena);
-                let action_ref = action_block.map(|stmt| {
-                    let flattened = stmt.flatten(expr_arena, _stmt_arena);
-                    _stmt_arena.alloc(flattened)
-                });
+                let clocking_event = clocking_event.map(|e| e.flatten(expr_arena));
+                let disable_iff = disable_iff.map(|e| e.flatten(expr_arena));
+                let property = property.flatten(expr_arena);
+                let mut action = |stmt: Option<Box<ParsedStatement>>| {
+                    stmt.map(|stmt| {
+                        let flattened = stmt.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(flattened)
+                    })
+                };
                 Statement::AssertProperty {
-                    property_expr: property_ref,
-                    action_block: action_ref,
-                    span: (0, 0),
+                    kind,
+                    clocking_event,
+                    disable_iff,
+                    property,
+                    pass_action: action(pass_action),
+                    fail_action: action(fail_action),
+                    span,
+                }
+            }
+            ParsedStatement::ImmediateAssertion {
+                kind,
+                deferral,
+                condition,
+                pass_action,
+                fail_action,
+                span,
+            } => {
+                let condition = condition.flatten(expr_arena);
+                let mut action = |stmt: Option<Box<ParsedStatement>>| {
+                    stmt.map(|stmt| {
+                        let flattened = stmt.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(flattened)
+                    })
+                };
+                Statement::ImmediateAssertion {
+                    kind,
+                    deferral,
+                    condition,
+                    pass_action: action(pass_action),
+                    fail_action: action(fail_action),
+                    span,
                 }
             }
-            ParsedStatement::ExpressionStatement { expr } => {
+            ParsedStatement::ExpressionStatement { expr, span } => {
                 let expr_ref = expr.flatten(expr_arena);
                 Statement::ExpressionStatement {
                     expr: expr_ref,
-                    span: (0, 0),
+                    span,
+                }
+            }
+            ParsedStatement::Conditional {
+                modifier,
+                condition,
+                then_statement,
+                else_statement,
+                span,
+            } => {
+                let condition_ref = condition.flatten(expr_arena);
+                let then_stmt = then_statement.flatten(expr_arena, stmt_arena);
+                let then_ref = stmt_arena.alloc(then_stmt);
+                let else_ref = else_statement.map(|stmt| {
+                    let flattened = stmt.flatten(expr_arena, stmt_arena);
+                    stmt_arena.alloc(flattened)
+                });
+                Statement::Conditional {
+                    modifier,
+                    condition: condition_ref,
+                    then_statement: then_ref,
+                    else_statement: else_ref,
+                    span,
                 }
             }
             ParsedStatement::VariableDeclaration {
@@ -258,13 +1216,239 @@ impl ParsedStatement {
             } => {
                 let initial_value_ref = initial_value.map(|expr| expr.flatten(expr_arena));
                 Statement::VariableDeclaration {
-                    data_type,
+                    data_type: DataType::Named(data_type),
                     name,
                     name_span,
                     initial_value: initial_value_ref,
                     span,
                 }
             }
+            ParsedStatement::Block {
+                label,
+                statements,
+                end_label,
+                span,
+            } => {
+                let statement_refs = statements
+                    .into_iter()
+                    .flat_map(ParsedStatement::declarations)
+                    .map(|s| {
+                        let stmt = s.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(stmt)
+                    })
+                    .collect();
+                Statement::Block {
+                    label,
+                    statements: statement_refs,
+                    end_label,
+                    span,
+                }
+            }
+            ParsedStatement::Fork {
+                label,
+                statements,
+                join,
+                end_label,
+                span,
+            } => {
+                let statement_refs = statements
+                    .into_iter()
+                    .flat_map(ParsedStatement::declarations)
+                    .map(|s| {
+                        let stmt = s.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(stmt)
+                    })
+                    .collect();
+                Statement::Fork {
+                    label,
+                    statements: statement_refs,
+                    join,
+                    end_label,
+                    span,
+                }
+            }
+            ParsedStatement::Labeled {
+                label,
+                statement,
+                span,
+            } => {
+                let stmt = statement.flatten(expr_arena, stmt_arena);
+                Statement::Labeled {
+                    label,
+                    statement: stmt_arena.alloc(stmt),
+                    span,
+                }
+            }
+            ParsedStatement::EventTrigger {
+                nonblocking,
+                event,
+                span,
+            } => Statement::EventTrigger {
+                nonblocking,
+                event: event.flatten(expr_arena),
+                span,
+            },
+            ParsedStatement::WaitFork { span } => Statement::WaitFork { span },
+            ParsedStatement::DisableFork { span } => Statement::DisableFork { span },
+            ParsedStatement::Disable {
+                target,
+                target_span,
+                span,
+            } => Statement::Disable {
+                target,
+                target_span,
+                span,
+            },
+            ParsedStatement::CaseMatches {
+                modifier,
+                case_type,
+                expr,
+                items,
+                span,
+            } => {
+                let expr_ref = expr.flatten(expr_arena);
+                let items = items
+                    .into_iter()
+                    .map(|item| {
+                        let pattern = item.pattern.map(|p| p.flatten(expr_arena));
+                        let guard = item.guard.map(|g| g.flatten(expr_arena));
+                        let stmt = item.statement.flatten(expr_arena, stmt_arena);
+                        CaseMatchesItem {
+                            pattern,
+                            guard,
+                            statement: stmt_arena.alloc(stmt),
+                            span: item.span,
+                        }
+                    })
+                    .collect();
+                Statement::CaseMatches {
+                    modifier,
+                    case_type,
+                    expr: expr_ref,
+                    items,
+                    span,
+                }
+            }
+            ParsedStatement::Return { value, span } => Statement::Return {
+                value: value.map(|expr| expr.flatten(expr_arena)),
+                span,
+            },
+            ParsedStatement::ForLoop {
+                init,
+                condition,
+                step,
+                body,
+                span,
+            } => {
+                let mut flatten_all = |statements: Vec<ParsedStatement>| -> Vec<StmtRef> {
+                    statements
+                        .into_iter()
+                        .flat_map(ParsedStatement::declarations)
+                        .map(|s| {
+                            let stmt = s.flatten(expr_arena, stmt_arena);
+                            stmt_arena.alloc(stmt)
+                        })
+                        .collect()
+                };
+                let init = flatten_all(init);
+                let step = flatten_all(step);
+                let condition = condition.map(|expr| expr.flatten(expr_arena));
+                let body = body.flatten(expr_arena, stmt_arena);
+                Statement::ForLoop {
+                    init,
+                    condition,
+                    step,
+                    body: stmt_arena.alloc(body),
+                    span,
+                }
+            }
+            ParsedStatement::While {
+                condition,
+                body,
+                span,
+            } => {
+                let condition = condition.flatten(expr_arena);
+                let body = body.flatten(expr_arena, stmt_arena);
+                Statement::While {
+                    condition,
+                    body: stmt_arena.alloc(body),
+                    span,
+                }
+            }
+            ParsedStatement::DoWhile {
+                body,
+                condition,
+                span,
+            } => {
+                let body = body.flatten(expr_arena, stmt_arena);
+                let body = stmt_arena.alloc(body);
+                Statement::DoWhile {
+                    body,
+                    condition: condition.flatten(expr_arena),
+                    span,
+                }
+            }
+            ParsedStatement::Repeat { count, body, span } => {
+                let count = count.flatten(expr_arena);
+                let body = body.flatten(expr_arena, stmt_arena);
+                Statement::Repeat {
+                    count,
+                    body: stmt_arena.alloc(body),
+                    span,
+                }
+            }
+            ParsedStatement::Timed {
+                control,
+                statement,
+                span,
+            } => {
+                let control = control.flatten(expr_arena);
+                let statement = statement.map(|statement| {
+                    let statement = statement.flatten(expr_arena, stmt_arena);
+                    stmt_arena.alloc(statement)
+                });
+                Statement::Timed {
+                    control,
+                    statement,
+                    span,
+                }
+            }
+            ParsedStatement::Forever { body, span } => {
+                let body = body.flatten(expr_arena, stmt_arena);
+                Statement::Forever {
+                    body: stmt_arena.alloc(body),
+                    span,
+                }
+            }
+            ParsedStatement::Foreach {
+                array,
+                array_span,
+                indices,
+                body,
+                span,
+            } => {
+                let body = body.flatten(expr_arena, stmt_arena);
+                Statement::Foreach {
+                    array,
+                    array_span,
+                    indices,
+                    body: stmt_arena.alloc(body),
+                    span,
+                }
+            }
+            // Where only one statement is allowed, the declarations share a block
+            ParsedStatement::Declarations { declarations, span } => Statement::Block {
+                label: None,
+                statements: declarations
+                    .into_iter()
+                    .map(|declaration| {
+                        let stmt = declaration.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(stmt)
+                    })
+                    .collect(),
+                end_label: None,
+                span,
+            },
         }
     }
 }
@@ -274,47 +1458,106 @@ impl ParsedStatement {
 enum ParsedClassItem {
     Property {
         qualifier: Option<ClassQualifier>,
-        data_type: String,
+        is_static: bool,
+        rand: Option<RandQualifier>,
+        data_type: ParsedDataType,
         name: String,
+        name_span: Span,
         unpacked_dimensions: Vec<UnpackedDimension>,
         initial_value: Option<ParsedExpression>,
+        span: Span,
     },
     Method {
         qualifier: Option<ClassQualifier>,
+        is_virtual: bool,
+        is_pure: bool,
+        is_static: bool,
+        is_extern: bool,
+        is_task: bool,
         return_type: Option<String>,
         name: String,
+        name_span: Span,
         parameters: Vec<String>,
         body: Vec<ParsedStatement>,
+        span: Span,
     },
-}
+    Constraint {
+        is_static: bool,
+        name: String,
+        name_span: Span,
+        constraints: Vec<ParsedConstraint>,
+        span: Span,
+    },
+}
+
+/// A qualifier before a class property or method
+#[derive(Clone, PartialEq)]
+enum ParsedClassQualifier {
+    Virtual,
+    Static,
+    Rand(RandQualifier),
+    Access(ClassQualifier),
+}
+
+impl ParsedClassQualifier {
+    /// The first `local` or `protected` among `qualifiers`
+    fn access(qualifiers: &[ParsedClassQualifier]) -> Option<ClassQualifier> {
+        qualifiers.iter().find_map(|qualifier| match qualifier {
+            ParsedClassQualifier::Access(access) => Some(access.clone()),
+            _ => None,
+        })
+    }
+
+    /// The first `rand` or `randc` among `qualifiers`
+    fn rand(qualifiers: &[ParsedClassQualifier]) -> Option<RandQualifier> {
+        qualifiers.iter().find_map(|qualifier| match qualifier {
+            ParsedClassQualifier::Rand(rand) => Some(*rand),
+            _ => None,
+        })
+    }
+}
 
 impl ParsedClassItem {
     fn flatten(self, expr_arena: &mut ExprArena, stmt_arena: &mut StmtArena) -> ClassItem {
         match self {
             ParsedClassItem::Property {
                 qualifier,
+                is_static,
+                rand,
                 data_type,
                 name,
+                name_span,
                 unpacked_dimensions,
                 initial_value,
+                span,
             } => ClassItem::Property {
                 qualifier,
-                data_type,
+                is_static,
+                rand,
+                data_type: data_type.flatten(expr_arena),
                 name,
-                name_span: (0, 0),
+                name_span,
                 unpacked_dimensions,
                 initial_value: initial_value.map(|e| e.flatten(expr_arena)),
-                span: (0, 0),
+                span,
             },
             ParsedClassItem::Method {
                 qualifier,
+                is_virtual,
+                is_pure,
+                is_static,
+                is_extern,
+                is_task,
                 return_type,
                 name,
+                name_span,
                 parameters,
                 body,
+                span,
             } => {
                 let body_refs: Vec<StmtRef> = body
                     .into_iter()
+                    .flat_map(ParsedStatement::declarations)
                     .map(|s| {
                         let stmt = s.flatten(expr_arena, stmt_arena);
                         stmt_arena.alloc(stmt)
@@ -322,14 +1565,67 @@ impl ParsedClassItem {
                     .collect();
                 ClassItem::Method {
                     qualifier,
+                    is_virtual,
+                    is_pure,
+                    is_static,
+                    is_extern,
+                    is_task,
                     return_type,
                     name,
-                    name_span: (0, 0),
+                    name_span,
                     parameters,
                     body: body_refs,
-                    span: (0, 0),
+                    span,
                 }
             }
+            ParsedClassItem::Constraint {
+                is_static,
+                name,
+                name_span,
+                constraints,
+                span,
+            } => ClassItem::Constraint(ConstraintBlock {
+                is_static,
+                name,
+                name_span,
+                constraints: constraints
+                    .into_iter()
+                    .map(|constraint| constraint.flatten(expr_arena))
+                    .collect(),
+                span,
+            }),
+        }
+    }
+}
+
+/// Temporary port connection that holds ParsedExpressions during parsing
+#[derive(Clone)]
+enum ParsedPortConnection {
+    Positional(ParsedExpression),
+    Named {
+        name: String,
+        name_span: Span,
+        expr: Option<ParsedExpression>,
+    },
+    Wildcard,
+}
+
+impl ParsedPortConnection {
+    fn flatten(self, expr_arena: &mut ExprArena) -> PortConnection {
+        match self {
+            ParsedPortConnection::Positional(expr) => {
+                PortConnection::Positional(expr.flatten(expr_arena))
+            }
+            ParsedPortConnection::Named {
+                name,
+                name_span,
+                expr,
+            } => PortConnection::Named {
+                name,
+                name_span,
+                expr: expr.map(|e| e.flatten(expr_arena)),
+            },
+            ParsedPortConnection::Wildcard => PortConnection::Wildcard,
         }
     }
 }
@@ -344,8 +1640,40 @@ enum ParsedModuleItem {
         items: Vec<ParsedModuleItem>,
         span: Span,
     },
+    InterfaceDeclaration {
+        name: String,
+        name_span: Span,
+        ports: Vec<Port>,
+        items: Vec<ParsedModuleItem>,
+        span: Span,
+    },
+    CheckerDeclaration {
+        name: String,
+        name_span: Span,
+        ports: Vec<Port>,
+        items: Vec<ParsedModuleItem>,
+        span: Span,
+    },
+    ModportDeclaration {
+        modports: Vec<Modport>,
+        span: Span,
+    },
+    PackageDeclaration {
+        name: String,
+        name_span: Span,
+        items: Vec<ParsedModuleItem>,
+        span: Span,
+    },
+    ImportDeclaration {
+        imports: Vec<PackageImport>,
+        span: Span,
+    },
+    ExportDeclaration {
+        exports: Vec<PackageImport>,
+        span: Span,
+    },
     VariableDeclaration {
-        data_type: String,
+        data_type: ParsedDataType,
         signing: Option<String>,
         drive_strength: Option<DriveStrength>,
         delay: Option<Delay>,
@@ -356,7 +1684,17 @@ enum ParsedModuleItem {
         initial_value: Option<ParsedExpression>,
         span: Span,
     },
+    TypedefDeclaration {
+        data_type: ParsedDataType,
+        signing: Option<String>,
+        range: Option<Range>,
+        name: String,
+        name_span: Span,
+        unpacked_dimensions: Vec<UnpackedDimension>,
+        span: Span,
+    },
     Assignment {
+        drive_strength: Option<DriveStrength>,
         delay: Option<Delay>,
         target: ParsedExpression,
         expr: ParsedExpression,
@@ -364,16 +1702,49 @@ enum ParsedModuleItem {
     },
     ProceduralBlock {
         block_type: ProceduralBlockType,
+        event_control: Option<ParsedEventControl>,
         statements: Vec<ParsedStatement>,
+        label: Option<Label>,
+        end_label: Option<Label>,
         span: Span,
     },
     ClassDeclaration {
+        is_virtual: bool,
         name: String,
         name_span: Span,
+        parameters: Vec<ParsedClassParameter>,
         extends: Option<String>,
+        implements: Vec<String>,
+        items: Vec<ParsedClassItem>,
+        span: Span,
+    },
+    InterfaceClassDeclaration {
+        name: String,
+        name_span: Span,
+        extends: Vec<String>,
         items: Vec<ParsedClassItem>,
         span: Span,
     },
+    OutOfBlockMethod {
+        return_type: Option<String>,
+        class_name: String,
+        class_name_span: Span,
+        name: String,
+        name_span: Span,
+        parameters: Vec<String>,
+        body: Vec<ParsedStatement>,
+        span: Span,
+    },
+    SubroutineDeclaration {
+        is_task: bool,
+        is_automatic: bool,
+        return_type: Option<String>,
+        name: String,
+        name_span: Span,
+        parameters: Vec<String>,
+        body: Vec<ParsedStatement>,
+        span: Span,
+    },
     PortDeclaration {
         direction: PortDirection,
         port_type: String,
@@ -394,7 +1765,46 @@ enum ParsedModuleItem {
         span: Span,
     },
     ConcurrentAssertion {
-        statement: ParsedStatement,
+        kind: AssertionKind,
+        clocking_event: Option<ParsedExpression>,
+        disable_iff: Option<ParsedExpression>,
+        property: ParsedPropertyExpr,
+        pass_action: Option<Box<ParsedStatement>>,
+        fail_action: Option<Box<ParsedStatement>>,
+        span: Span,
+    },
+    AssertionDeclaration {
+        kind: AssertionDeclarationKind,
+        name: String,
+        name_span: Span,
+        ports: Vec<Port>,
+        clocking_event: Option<ParsedExpression>,
+        disable_iff: Option<ParsedExpression>,
+        body: ParsedPropertyExpr,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    DefaultClocking {
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    TimescaleDirective {
+        unit: TimeValue,
+        precision: TimeValue,
+        span: Span,
+    },
+    DefaultNettypeDirective {
+        net_type: String,
+        span: Span,
+    },
+    TimeunitsDeclaration {
+        unit: Option<TimeValue>,
+        precision: Option<TimeValue>,
+        span: Span,
+    },
+    DefaultDisableIff {
+        condition: ParsedExpression,
         span: Span,
     },
     GlobalClocking {
@@ -404,9 +1814,158 @@ enum ParsedModuleItem {
         end_label: Option<String>,
         span: Span,
     },
+    ClockingBlock {
+        default: bool,
+        name: String,
+        name_span: Span,
+        clocking_event: ParsedExpression,
+        default_input_skew: Option<String>,
+        default_output_skew: Option<String>,
+        signals: Vec<ClockingSignal>,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    ParameterDeclaration {
+        local: bool,
+        data_type: Option<String>,
+        range: Option<Range>,
+        name: String,
+        name_span: Span,
+        value: ParsedExpression,
+        span: Span,
+    },
+    ModuleInstantiation {
+        module_name: String,
+        module_name_span: Span,
+        parameters: Vec<ParsedPortConnection>,
+        instance_name: String,
+        instance_name_span: Span,
+        connections: Vec<ParsedPortConnection>,
+        span: Span,
+    },
+    ConfigDeclaration {
+        name: String,
+        name_span: Span,
+        design: Vec<CellReference>,
+        rules: Vec<ConfigRule>,
+        span: Span,
+    },
+    ElaborationTask {
+        severity: ElaborationSeverity,
+        arguments: Vec<ParsedExpression>,
+        span: Span,
+    },
+    GenvarDeclaration {
+        genvars: Vec<Genvar>,
+        span: Span,
+    },
+    GenerateRegion {
+        items: Vec<ParsedModuleItem>,
+        span: Span,
+    },
+    GenerateBlock {
+        label: Option<Label>,
+        items: Vec<ParsedModuleItem>,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    GenerateFor {
+        declares_genvar: bool,
+        genvar: String,
+        genvar_span: Span,
+        init: ParsedExpression,
+        condition: ParsedExpression,
+        step_op: AssignmentOp,
+        step: ParsedExpression,
+        body: Box<ParsedModuleItem>,
+        span: Span,
+    },
+    GenerateIf {
+        condition: ParsedExpression,
+        then_item: Box<ParsedModuleItem>,
+        else_item: Option<Box<ParsedModuleItem>>,
+        span: Span,
+    },
+    GenerateCase {
+        expr: ParsedExpression,
+        items: Vec<ParsedGenerateCaseItem>,
+        span: Span,
+    },
+    /// `logic a, b = 1;` or `assign a = b, c = d;`: one declaration or
+    /// assignment per variable, which become items of their own when flattened
+    Declarations {
+        declarations: Vec<ParsedModuleItem>,
+        span: Span,
+    },
 }
 
 impl ParsedModuleItem {
+    /// The items this one stands for in a list of items: the declarations of
+    /// a declaration of several variables, else itself
+    fn declarations(self) -> Vec<ParsedModuleItem> {
+        match self {
+            ParsedModuleItem::Declarations { declarations, .. } => declarations,
+            item => vec![item],
+        }
+    }
+
+    /// Flatten the items of a module or interface body and allocate them in the arena
+    fn flatten_items(
+        items: Vec<ParsedModuleItem>,
+        expr_arena: &mut ExprArena,
+        stmt_arena: &mut StmtArena,
+        module_item_arena: &mut ModuleItemArena,
+    ) -> Vec<ModuleItemRef> {
+        // First flatten all child items into ModuleItems, splitting net
+        // declaration assignments into the net and its continuous assignment
+        let mut flattened_items: Vec<ModuleItem> = Vec::with_capacity(items.len());
+        for item in items.into_iter().flat_map(ParsedModuleItem::declarations) {
+            match item.flatten(expr_arena, stmt_arena, module_item_arena) {
+                ModuleItem::VariableDeclaration {
+                    data_type,
+                    signing,
+                    drive_strength,
+                    delay,
+                    range,
+                    name,
+                    name_span,
+                    unpacked_dimensions,
+                    initial_value: Some(expr),
+                    span,
+                } if is_net_type(data_type.name()) => {
+                    let target = expr_arena.alloc(Expression::Identifier(name.clone(), name_span));
+                    flattened_items.push(ModuleItem::VariableDeclaration {
+                        data_type,
+                        signing,
+                        drive_strength,
+                        delay: delay.clone(),
+                        range,
+                        name,
+                        name_span,
+                        unpacked_dimensions,
+                        initial_value: None,
+                        span,
+                    });
+                    flattened_items.push(ModuleItem::Assignment {
+                        drive_strength: None,
+                        delay,
+                        target,
+                        expr,
+                        implicit: true,
+                        span,
+                    });
+                }
+                item => flattened_items.push(item),
+            }
+        }
+
+        // Then allocate them in the arena and collect their refs
+        flattened_items
+            .into_iter()
+            .map(|item| module_item_arena.alloc(item))
+            .collect()
+    }
+
     /// Flatten this parsed module item into a real ModuleItem + arena
     fn flatten(
         self,
@@ -421,26 +1980,58 @@ impl ParsedModuleItem {
                 ports,
                 items,
                 span,
-            } => {
-                // First flatten all child items into ModuleItems
-                let flattened_items: Vec<ModuleItem> = items
-                    .into_iter()
-                    .map(|item| item.flatten(expr_arena, stmt_arena, module_item_arena))
-                    .collect();
-
-                // Then allocate them in the arena and collect their refs
-                let item_refs: Vec<ModuleItemRef> = flattened_items
-                    .into_iter()
-                    .map(|item| module_item_arena.alloc(item))
-                    .collect();
-
-                ModuleItem::ModuleDeclaration {
-                    name,
-                    name_span,
-                    ports,
-                    items: item_refs,
-                    span,
-                }
+            } => ModuleItem::ModuleDeclaration {
+                name,
+                name_span,
+                ports,
+                items: Self::flatten_items(items, expr_arena, stmt_arena, module_item_arena),
+                span,
+            },
+            ParsedModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            } => ModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                ports,
+                items: Self::flatten_items(items, expr_arena, stmt_arena, module_item_arena),
+                span,
+            },
+            ParsedModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            } => ModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                ports,
+                items: Self::flatten_items(items, expr_arena, stmt_arena, module_item_arena),
+                span,
+            },
+            ParsedModuleItem::ModportDeclaration { modports, span } => {
+                ModuleItem::ModportDeclaration { modports, span }
+            }
+            ParsedModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items,
+                span,
+            } => ModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items: Self::flatten_items(items, expr_arena, stmt_arena, module_item_arena),
+                span,
+            },
+            ParsedModuleItem::ImportDeclaration { imports, span } => {
+                ModuleItem::ImportDeclaration { imports, span }
+            }
+            ParsedModuleItem::ExportDeclaration { exports, span } => {
+                ModuleItem::ExportDeclaration { exports, span }
             }
             ParsedModuleItem::VariableDeclaration {
                 data_type,
@@ -454,7 +2045,7 @@ impl ParsedModuleItem {
                 initial_value,
                 span,
             } => ModuleItem::VariableDeclaration {
-                data_type,
+                data_type: data_type.flatten(expr_arena),
                 signing,
                 drive_strength,
                 delay,
@@ -465,7 +2056,25 @@ impl ParsedModuleItem {
                 initial_value: initial_value.map(|e| e.flatten(expr_arena)),
                 span,
             },
+            ParsedModuleItem::TypedefDeclaration {
+                data_type,
+                signing,
+                range,
+                name,
+                name_span,
+                unpacked_dimensions,
+                span,
+            } => ModuleItem::TypedefDeclaration {
+                data_type: data_type.flatten(expr_arena),
+                signing,
+                range,
+                name,
+                name_span,
+                unpacked_dimensions,
+                span,
+            },
             ParsedModuleItem::Assignment {
+                drive_strength,
                 delay,
                 target,
                 expr,
@@ -474,19 +2083,26 @@ impl ParsedModuleItem {
                 let target_ref = target.flatten(expr_arena);
                 let expr_ref = expr.flatten(expr_arena);
                 ModuleItem::Assignment {
+                    drive_strength,
                     delay,
                     target: target_ref,
                     expr: expr_ref,
+                    implicit: false,
                     span,
                 }
             }
             ParsedModuleItem::ProceduralBlock {
                 block_type,
+                event_control,
                 statements,
+                label,
+                end_label,
                 span,
             } => {
+                let event_control = event_control.map(|control| control.flatten(expr_arena));
                 let statement_refs: Vec<StmtRef> = statements
                     .into_iter()
+                    .flat_map(ParsedStatement::declarations)
                     .map(|s| {
                         let stmt = s.flatten(expr_arena, stmt_arena);
                         stmt_arena.alloc(stmt)
@@ -494,38 +2110,122 @@ impl ParsedModuleItem {
                     .collect();
                 ModuleItem::ProceduralBlock {
                     block_type,
+                    event_control,
                     statements: statement_refs,
+                    label,
+                    end_label,
                     span,
                 }
             }
             ParsedModuleItem::ClassDeclaration {
+                is_virtual,
                 name,
                 name_span,
+                parameters,
                 extends,
+                implements,
                 items,
                 span,
             } => {
+                let parameters = parameters
+                    .into_iter()
+                    .map(|parameter| parameter.flatten(expr_arena))
+                    .collect();
                 let flattened_items: Vec<ClassItem> = items
                     .into_iter()
                     .map(|item| item.flatten(expr_arena, stmt_arena))
                     .collect();
                 ModuleItem::ClassDeclaration {
+                    is_virtual,
                     name,
                     name_span,
+                    parameters,
                     extends,
+                    implements,
                     items: flattened_items,
                     span,
                 }
             }
-            ParsedModuleItem::PortDeclaration {
-                direction,
-                port_type,
+            ParsedModuleItem::InterfaceClassDeclaration {
                 name,
                 name_span,
+                extends,
+                items,
                 span,
-            } => ModuleItem::PortDeclaration {
-                direction,
-                port_type,
+            } => {
+                let flattened_items: Vec<ClassItem> = items
+                    .into_iter()
+                    .map(|item| item.flatten(expr_arena, stmt_arena))
+                    .collect();
+                ModuleItem::InterfaceClassDeclaration {
+                    name,
+                    name_span,
+                    extends,
+                    items: flattened_items,
+                    span,
+                }
+            }
+            ParsedModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                class_name_span,
+                name,
+                name_span,
+                parameters,
+                body,
+                span,
+            } => ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                class_name_span,
+                name,
+                name_span,
+                parameters,
+                body: body
+                    .into_iter()
+                    .flat_map(ParsedStatement::declarations)
+                    .map(|s| {
+                        let stmt = s.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(stmt)
+                    })
+                    .collect(),
+                span,
+            },
+            ParsedModuleItem::SubroutineDeclaration {
+                is_task,
+                is_automatic,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                body,
+                span,
+            } => ModuleItem::SubroutineDeclaration {
+                is_task,
+                is_automatic,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                body: body
+                    .into_iter()
+                    .flat_map(ParsedStatement::declarations)
+                    .map(|s| {
+                        let stmt = s.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(stmt)
+                    })
+                    .collect(),
+                span,
+            },
+            ParsedModuleItem::PortDeclaration {
+                direction,
+                port_type,
+                name,
+                name_span,
+                span,
+            } => ModuleItem::PortDeclaration {
+                direction,
+                port_type,
                 name,
                 name_span,
                 span,
@@ -553,11 +2253,88 @@ impl ParsedModuleItem {
                 resolved_path: None,
                 span,
             },
-            ParsedModuleItem::ConcurrentAssertion { statement, span } => {
-                let stmt = statement.flatten(expr_arena, stmt_arena);
-                let stmt_ref = stmt_arena.alloc(stmt);
+            ParsedModuleItem::ConcurrentAssertion {
+                kind,
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                span,
+            } => {
+                let clocking_event = clocking_event.map(|e| e.flatten(expr_arena));
+                let disable_iff = disable_iff.map(|e| e.flatten(expr_arena));
+                let property = property.flatten(expr_arena);
+                let mut action = |stmt: Option<Box<ParsedStatement>>| {
+                    stmt.map(|stmt| {
+                        let flattened = stmt.flatten(expr_arena, stmt_arena);
+                        stmt_arena.alloc(flattened)
+                    })
+                };
                 ModuleItem::ConcurrentAssertion {
-                    statement: stmt_ref,
+                    kind,
+                    clocking_event,
+                    disable_iff,
+                    property,
+                    pass_action: action(pass_action),
+                    fail_action: action(fail_action),
+                    span,
+                }
+            }
+            ParsedModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                ports,
+                clocking_event,
+                disable_iff,
+                body,
+                end_label,
+                span,
+            } => ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                ports,
+                clocking_event: clocking_event.map(|e| e.flatten(expr_arena)),
+                disable_iff: disable_iff.map(|e| e.flatten(expr_arena)),
+                body: body.flatten(expr_arena),
+                end_label,
+                span,
+            },
+            ParsedModuleItem::DefaultClocking {
+                name,
+                name_span,
+                span,
+            } => ModuleItem::DefaultClocking {
+                name,
+                name_span,
+                span,
+            },
+            ParsedModuleItem::TimescaleDirective {
+                unit,
+                precision,
+                span,
+            } => ModuleItem::TimescaleDirective {
+                unit,
+                precision,
+                span,
+            },
+            ParsedModuleItem::DefaultNettypeDirective { net_type, span } => {
+                ModuleItem::DefaultNettypeDirective { net_type, span }
+            }
+            ParsedModuleItem::TimeunitsDeclaration {
+                unit,
+                precision,
+                span,
+            } => ModuleItem::TimeunitsDeclaration {
+                unit,
+                precision,
+                span,
+            },
+            ParsedModuleItem::DefaultDisableIff { condition, span } => {
+                ModuleItem::DefaultDisableIff {
+                    condition: condition.flatten(expr_arena),
                     span,
                 }
             }
@@ -577,7 +2354,386 @@ impl ParsedModuleItem {
                     span,
                 }
             }
+            ParsedModuleItem::ClockingBlock {
+                default,
+                name,
+                name_span,
+                clocking_event,
+                default_input_skew,
+                default_output_skew,
+                signals,
+                end_label,
+                span,
+            } => ModuleItem::ClockingBlock {
+                default,
+                name,
+                name_span,
+                clocking_event: clocking_event.flatten(expr_arena),
+                default_input_skew,
+                default_output_skew,
+                signals,
+                end_label,
+                span,
+            },
+            ParsedModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                name_span,
+                value,
+                span,
+            } => ModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                name_span,
+                value: value.flatten(expr_arena),
+                span,
+            },
+            ParsedModuleItem::ModuleInstantiation {
+                module_name,
+                module_name_span,
+                parameters,
+                instance_name,
+                instance_name_span,
+                connections,
+                span,
+            } => ModuleItem::ModuleInstantiation {
+                module_name,
+                module_name_span,
+                parameters: parameters
+                    .into_iter()
+                    .map(|c| c.flatten(expr_arena))
+                    .collect(),
+                instance_name,
+                instance_name_span,
+                connections: connections
+                    .into_iter()
+                    .map(|c| c.flatten(expr_arena))
+                    .collect(),
+                span,
+            },
+            ParsedModuleItem::ConfigDeclaration {
+                name,
+                name_span,
+                design,
+                rules,
+                span,
+            } => ModuleItem::ConfigDeclaration {
+                name,
+                name_span,
+                design,
+                rules,
+                span,
+            },
+            ParsedModuleItem::ElaborationTask {
+                severity,
+                arguments,
+                span,
+            } => ModuleItem::ElaborationTask {
+                severity,
+                arguments: arguments
+                    .into_iter()
+                    .map(|arg| arg.flatten(expr_arena))
+                    .collect(),
+                span,
+            },
+            ParsedModuleItem::GenvarDeclaration { genvars, span } => {
+                ModuleItem::GenvarDeclaration { genvars, span }
+            }
+            ParsedModuleItem::GenerateRegion { items, span } => ModuleItem::GenerateRegion {
+                items: Self::flatten_items(items, expr_arena, stmt_arena, module_item_arena),
+                span,
+            },
+            ParsedModuleItem::GenerateBlock {
+                label,
+                items,
+                end_label,
+                span,
+            } => ModuleItem::GenerateBlock {
+                label,
+                items: Self::flatten_items(items, expr_arena, stmt_arena, module_item_arena),
+                end_label,
+                span,
+            },
+            ParsedModuleItem::GenerateFor {
+                declares_genvar,
+                genvar,
+                genvar_span,
+                init,
+                condition,
+                step_op,
+                step,
+                body,
+                span,
+            } => ModuleItem::GenerateFor {
+                declares_genvar,
+                genvar,
+                genvar_span,
+                init: init.flatten(expr_arena),
+                condition: condition.flatten(expr_arena),
+                step_op,
+                step: step.flatten(expr_arena),
+                body: (*body).flatten_body(expr_arena, stmt_arena, module_item_arena),
+                span,
+            },
+            ParsedModuleItem::GenerateIf {
+                condition,
+                then_item,
+                else_item,
+                span,
+            } => ModuleItem::GenerateIf {
+                condition: condition.flatten(expr_arena),
+                then_item: (*then_item).flatten_body(expr_arena, stmt_arena, module_item_arena),
+                else_item: else_item
+                    .map(|item| (*item).flatten_body(expr_arena, stmt_arena, module_item_arena)),
+                span,
+            },
+            // Where only one item is allowed, as the body of a generate
+            // construct, the declarations share an unnamed generate block
+            ParsedModuleItem::Declarations { declarations, span } => ModuleItem::GenerateBlock {
+                label: None,
+                items: Self::flatten_items(declarations, expr_arena, stmt_arena, module_item_arena),
+                end_label: None,
+                span,
+            },
+            ParsedModuleItem::GenerateCase { expr, items, span } => ModuleItem::GenerateCase {
+                expr: expr.flatten(expr_arena),
+                items: items
+                    .into_iter()
+                    .map(|item| GenerateCaseItem {
+                        values: item
+                            .values
+                            .into_iter()
+                            .map(|value| value.flatten(expr_arena))
+                            .collect(),
+                        body: item
+                            .body
+                            .flatten_body(expr_arena, stmt_arena, module_item_arena),
+                        span: item.span,
+                    })
+                    .collect(),
+                span,
+            },
+        }
+    }
+
+    /// Flatten the body of a generate loop or branch and allocate it in the arena
+    fn flatten_body(
+        self,
+        expr_arena: &mut ExprArena,
+        stmt_arena: &mut StmtArena,
+        module_item_arena: &mut ModuleItemArena,
+    ) -> ModuleItemRef {
+        let item = self.flatten(expr_arena, stmt_arena, module_item_arena);
+        module_item_arena.alloc(item)
+    }
+}
+
+/// A token of the lexer as the grammar sees it
+///
+/// The text is shared rather than borrowed from the source: a borrowed text
+/// would put a lifetime in the type of every parser the grammar is built
+/// from, and checking the borrows of that many parsers takes the compiler
+/// far longer than checking the grammar itself.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct GrammarToken {
+    kind: TokenKind,
+    text: Rc<str>,
+    spacing: Spacing,
+}
+
+impl GrammarToken {
+    /// The identifier named by this token, without the backslash of an
+    /// escaped identifier
+    fn name(&self) -> &str {
+        self.text.strip_prefix('\\').unwrap_or(&self.text)
+    }
+}
+
+/// Errors of the grammar, which runs over the tokens of the lexer
+type TokenError = Simple<GrammarToken>;
+
+/// The token written `text` as an expected token, or any token of `kind`,
+/// such as any identifier, when `text` is empty
+fn expected_token(kind: TokenKind, text: &'static str) -> GrammarToken {
+    GrammarToken {
+        kind,
+        text: text.into(),
+        spacing: Spacing::Joined,
+    }
+}
+
+/// A token accepted by `accept`; when another is found, the error names the
+/// token written `text`, or any token of `kind` when `text` is empty
+#[allow(clippy::result_large_err)]
+fn token_where(
+    kind: TokenKind,
+    text: &'static str,
+    accept: impl Fn(&GrammarToken) -> bool + Copy,
+) -> impl Parser<GrammarToken, GrammarToken, Error = TokenError> + Copy {
+    filter_map(move |span, token: GrammarToken| {
+        if accept(&token) {
+            Ok(token)
+        } else {
+            Err(Simple::expected_input_found(
+                span,
+                [Some(expected_token(kind, text))],
+                Some(token),
+            ))
+        }
+    })
+    // At the end of the input the error would otherwise expect nothing
+    .map_err(move |error: TokenError| match error.found() {
+        Some(_) => error,
+        None => {
+            Simple::expected_input_found(error.span(), [Some(expected_token(kind, text))], None)
+        }
+    })
+}
+
+/// Any token of `kind`
+fn token_of(kind: TokenKind) -> impl Parser<GrammarToken, GrammarToken, Error = TokenError> + Copy {
+    token_where(kind, "", move |token| token.kind == kind)
+}
+
+/// The token written `text`, such as `;`, `<<=` or `` `timescale ``
+fn just(text: &'static str) -> impl Parser<GrammarToken, GrammarToken, Error = TokenError> + Copy {
+    token_where(kind_of(text), text, move |token| &*token.text == text)
+}
+
+/// The kind of the token written `text`
+fn kind_of(text: &'static str) -> TokenKind {
+    Lexer::new(text)
+        .next()
+        .filter(|token| token.text == text)
+        .unwrap_or_else(|| panic!("the grammar matches whole tokens, not '{}'", text))
+        .kind
+}
+
+/// The word `word`: a keyword, or a word that only means something where
+/// the grammar expects it, such as `ns` after a time magnitude
+fn keyword(
+    word: &'static str,
+) -> impl Parser<GrammarToken, GrammarToken, Error = TokenError> + Copy {
+    just(word)
+}
+
+/// The text of the tokens up to the next `end`, which is left in the input
+fn until(end: &'static str) -> impl Parser<GrammarToken, String, Error = TokenError> + Copy {
+    token_where(TokenKind::Unknown, "", move |token| &*token.text != end)
+        .repeated()
+        .map(|tokens| source_text(&tokens))
+}
+
+/// The text of the tokens up to the end of the line, as of a directive
+fn rest_of_line() -> impl Parser<GrammarToken, String, Error = TokenError> + Copy {
+    token_where(TokenKind::Unknown, "", |token| {
+        token.spacing != Spacing::LineBreak
+    })
+    .repeated()
+    .map(|tokens| source_text(&tokens))
+}
+
+/// The text of consecutive tokens, with a space wherever anything separated them
+fn source_text(tokens: &[GrammarToken]) -> String {
+    let mut text = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        if i > 0 && token.spacing != Spacing::Joined {
+            text.push(' ');
+        }
+        text.push_str(&token.text);
+    }
+    text
+}
+
+/// Where the grammar starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Entry {
+    SourceText,
+    /// Source text that may also hold module items outside any module
+    Header,
+    Expression,
+    Statement,
+}
+
+/// How long one run of the grammar may take
+///
+/// The grammar backtracks, so some inputs, like huge generated expressions,
+/// take far longer than their size suggests. Every token boundary checks the
+/// budget, and once it is spent every check fails, which brings the run to a
+/// quick end; the error it ends with is replaced by one naming where it was.
+struct ParseBudget {
+    stopwatch: Stopwatch,
+    limit: Option<Duration>,
+    /// Checks since the clock was last read
+    checks: Cell<u32>,
+    /// Where the grammar was when the budget ran out
+    exceeded_at: Cell<Option<usize>>,
+}
+
+impl ParseBudget {
+    /// Checks between readings of the clock, which costs more than a check
+    const CHECKS_PER_READING: u32 = 256;
+
+    fn new(limit: Option<Duration>) -> Self {
+        Self {
+            stopwatch: Stopwatch::start(),
+            limit,
+            checks: Cell::new(0),
+            exceeded_at: Cell::new(None),
+        }
+    }
+
+    /// Whether the budget is spent, with the grammar at `offset`
+    fn spent(&self, offset: usize) -> bool {
+        let Some(limit) = self.limit else {
+            return false;
+        };
+        if self.exceeded_at.get().is_some() {
+            return true;
         }
+        let checks = self.checks.get() + 1;
+        if checks < Self::CHECKS_PER_READING {
+            self.checks.set(checks);
+            return false;
+        }
+        self.checks.set(0);
+        if self.stopwatch.elapsed() < limit {
+            return false;
+        }
+        self.exceeded_at.set(Some(offset));
+        true
+    }
+}
+
+/// What the grammar produced, depending on its [`Entry`]
+enum ParsedFragment {
+    Items(Vec<ParsedModuleItem>),
+    Expression(ParsedExpression),
+    Statement(Box<ParsedStatement>),
+}
+
+/// An expression or statement parsed on its own, with the arenas holding its nodes
+#[derive(Debug, Clone)]
+pub struct Fragment<R> {
+    pub expr_arena: ExprArena,
+    /// Empty for an expression
+    pub stmt_arena: StmtArena,
+    pub root: R,
+}
+
+impl Fragment<ExprRef> {
+    pub fn expression(&self) -> &Expression {
+        self.expr_arena.get(self.root)
+    }
+}
+
+impl Fragment<StmtRef> {
+    pub fn statement(&self) -> &Statement {
+        self.stmt_arena.get(self.root)
     }
 }
 
@@ -586,6 +2742,88 @@ pub struct SystemVerilogParser {
     preprocessor: Preprocessor,
     #[allow(dead_code)]
     fail_fast: bool,
+    /// Include files resolved during the last `parse_file` call, in the order they were found
+    resolved_includes: Vec<PathBuf>,
+    /// Time spent in each phase of the last `parse_file` call, including included files
+    timings: ParseTimings,
+    /// Macros defined at the end of the last `parse_file` call
+    macros: HashMap<String, MacroDefinition>,
+    /// Parse every file as a header, whatever its extension
+    header_mode: bool,
+    /// Language version of content and of files whose extension has none of its own
+    language_version: KeywordVersion,
+    /// Language version of files by extension, without the dot
+    extension_versions: HashMap<String, KeywordVersion>,
+    /// Headers already included in the current compilation unit, which later
+    /// `include directives skip
+    unit_includes: HashSet<PathBuf>,
+    /// Design units defined so far in the current compilation unit
+    unit_definitions: HashMap<String, UnitDefinition>,
+    /// Conflicting definitions found during the last `parse_file` call
+    duplicate_definitions: Vec<DuplicateDefinition>,
+    /// How deep macros and includes may nest, and how much text they may produce
+    preprocess_limits: PreprocessLimits,
+    /// Keep every directive of a file in its source map
+    retain_directives: bool,
+    /// How long the grammar may run over one file before giving up
+    parse_timeout: Option<Duration>,
+}
+
+/// Where a design unit was defined, and its text with whitespace normalised
+#[derive(Debug, Clone)]
+struct UnitDefinition {
+    file: PathBuf,
+    location: SourceLocation,
+    text: String,
+}
+
+/// A module or class defined a second time in the same compilation unit
+/// with a different body
+#[derive(Debug, Clone)]
+pub struct DuplicateDefinition {
+    pub name: String,
+    pub file: PathBuf,
+    pub location: SourceLocation,
+    pub previous_file: PathBuf,
+    pub previous_location: SourceLocation,
+}
+
+impl std::fmt::Display for DuplicateDefinition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: '{}' is already defined differently at {}:{}:{}",
+            self.file.display(),
+            self.location.line + 1,
+            self.location.column + 1,
+            self.name,
+            self.previous_file.display(),
+            self.previous_location.line + 1,
+            self.previous_location.column + 1
+        )
+    }
+}
+
+/// Whether `path` names a header file (`.svh`, `.vh`), which is parsed in
+/// header mode
+pub fn is_header_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .is_some_and(|extension| matches!(extension, "svh" | "vh"))
+}
+
+/// Verilog files (`.v`, `.vh`) use the Verilog-2005 keywords and SystemVerilog
+/// files (`.sv`, `.svh`) the latest ones, as in simulators without `-sverilog`
+fn default_extension_versions() -> HashMap<String, KeywordVersion> {
+    [
+        ("v", KeywordVersion::V1364_2005),
+        ("vh", KeywordVersion::V1364_2005),
+        ("sv", KeywordVersion::LATEST),
+        ("svh", KeywordVersion::LATEST),
+    ]
+    .into_iter()
+    .map(|(extension, version)| (extension.to_string(), version))
+    .collect()
 }
 
 impl SystemVerilogParser {
@@ -601,45 +2839,244 @@ impl SystemVerilogParser {
         Self {
             preprocessor: Preprocessor::new(include_dirs, initial_macros),
             fail_fast,
+            resolved_includes: Vec::new(),
+            timings: ParseTimings::default(),
+            macros: HashMap::new(),
+            header_mode: false,
+            language_version: KeywordVersion::LATEST,
+            extension_versions: default_extension_versions(),
+            unit_includes: HashSet::new(),
+            unit_definitions: HashMap::new(),
+            duplicate_definitions: Vec::new(),
+            preprocess_limits: PreprocessLimits::default(),
+            retain_directives: false,
+            parse_timeout: None,
         }
     }
 
-    pub fn parse_file(&mut self, file_path: &Path) -> Result<SourceUnit, ParseError> {
-        let mut included_files = std::collections::HashSet::new();
-        self.parse_file_with_includes(file_path, &mut included_files)
+    /// Start a new compilation unit
+    ///
+    /// Files parsed with the same parser share a compilation unit: a header
+    /// included by one file is skipped when a later file includes it again,
+    /// and a module or class defined again with a different body is reported
+    /// by [`duplicate_definitions`](Self::duplicate_definitions).
+    pub fn begin_compilation_unit(&mut self) {
+        self.unit_includes.clear();
+        self.unit_definitions.clear();
     }
 
-    fn parse_file_with_includes(
-        &mut self,
-        file_path: &Path,
-        included_files: &mut std::collections::HashSet<std::path::PathBuf>,
-    ) -> Result<SourceUnit, ParseError> {
-        // Canonicalize the file path to detect circular includes
-        let canonical_path = file_path
-            .canonicalize()
-            .unwrap_or_else(|_| file_path.to_path_buf());
+    /// Modules and classes of the last `parse_file` call that conflict with an
+    /// earlier definition in the compilation unit
+    pub fn duplicate_definitions(&self) -> &[DuplicateDefinition] {
+        &self.duplicate_definitions
+    }
 
-        // Check for circular includes
-        if included_files.contains(&canonical_path) {
-            // Already included, return empty AST to avoid infinite recursion
-            return Ok(SourceUnit {
-                items: Vec::new(),
-                expr_arena: ExprArena::new(),
-                stmt_arena: StmtArena::new(),
-                module_item_arena: ModuleItemArena::new(),
-            });
-        }
+    /// Parse all files and content as headers, not only `.svh` and `.vh` files
+    pub fn set_header_mode(&mut self, header_mode: bool) {
+        self.header_mode = header_mode;
+    }
 
-        included_files.insert(canonical_path.clone());
+    /// Set the language version of `parse_content` and of files whose
+    /// extension has no version of its own
+    pub fn set_language_version(&mut self, version: KeywordVersion) {
+        self.language_version = version;
+    }
 
-        let raw_content = std::fs::read_to_string(file_path).map_err(|e| {
-            ParseError::new(SingleParseError::new(
-                format!("Failed to read file {}: {}", file_path.display(), e),
-                ParseErrorType::PreprocessorError,
-            ))
+    /// Fail preprocessing that nests macros and includes deeper, or produces
+    /// more text, than `limits` allow, instead of running on
+    pub fn set_preprocess_limits(&mut self, limits: PreprocessLimits) {
+        self.preprocess_limits = limits;
+    }
+
+    /// Give up parsing a file, or a fragment, that takes longer than
+    /// `timeout`, with an error naming the design unit the parser was in,
+    /// instead of running on; None, the default, sets no limit
+    ///
+    /// Preprocessing is bounded separately, by
+    /// [`set_preprocess_limits`](Self::set_preprocess_limits).
+    pub fn set_parse_timeout(&mut self, timeout: Option<Duration>) {
+        self.parse_timeout = timeout;
+    }
+
+    /// Keep every directive of a file, with its text, span and whether it is
+    /// compiled, in [`SourceMap::directives`], for tools that need the source
+    /// as written; branches not taken and directives the grammar drops are
+    /// included
+    pub fn set_retain_directives(&mut self, retain_directives: bool) {
+        self.retain_directives = retain_directives;
+    }
+
+    /// Parse files ending in `.<extension>` with `version`'s keywords
+    pub fn set_extension_version(&mut self, extension: &str, version: KeywordVersion) {
+        self.extension_versions
+            .insert(extension.trim_start_matches('.').to_string(), version);
+    }
+
+    /// Language version a file is parsed with, chosen by its extension
+    ///
+    /// `` `begin_keywords `` regions inside the file still select their own
+    /// keywords.
+    pub fn language_version_for(&self, path: &Path) -> KeywordVersion {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| self.extension_versions.get(extension))
+            .copied()
+            .unwrap_or(self.language_version)
+    }
+
+    pub fn parse_file(&mut self, file_path: &Path) -> Result<SourceUnit, ParseError> {
+        let start = Stopwatch::start();
+        let mut included_files = std::collections::HashSet::new();
+        self.resolved_includes.clear();
+        self.timings = ParseTimings::default();
+        self.macros = self.predefined_macros();
+        self.duplicate_definitions.clear();
+        included_files.extend(self.unit_includes.iter().cloned());
+        let result = self.parse_file_with_includes(file_path, &mut included_files);
+        // The file itself may be parsed again as part of the unit; its includes may not
+        if let Ok(canonical) = file_path.canonicalize() {
+            included_files.remove(&canonical);
+        }
+        self.unit_includes.extend(included_files);
+        // Whatever wasn't spent in the grammar or flattening went to reading and merging files
+        self.timings.preprocess = start
+            .elapsed()
+            .saturating_sub(self.timings.parse + self.timings.flatten);
+        result
+    }
+
+    /// Include files resolved while parsing the last file, including nested includes
+    pub fn resolved_includes(&self) -> &[PathBuf] {
+        &self.resolved_includes
+    }
+
+    /// Time spent in each phase while parsing the last file, including its includes
+    pub fn timings(&self) -> &ParseTimings {
+        &self.timings
+    }
+
+    /// Macros in effect at the end of the last `parse_file` call: the
+    /// predefined macros plus each `define read from the file and its
+    /// includes, later definitions replacing earlier ones
+    pub fn macros(&self) -> &HashMap<String, MacroDefinition> {
+        &self.macros
+    }
+
+    /// Definition of macro `name` at the end of the last `parse_file` call
+    pub fn macro_definition(&self, name: &str) -> Option<&MacroDefinition> {
+        self.macros.get(name)
+    }
+
+    /// Macros in effect at the end of source text parsed with `parse_content`
+    pub fn macro_definitions(&self, unit: &SourceUnit) -> HashMap<String, MacroDefinition> {
+        let mut macros = self.predefined_macros();
+        collect_macro_definitions(unit, None, &mut macros);
+        macros
+    }
+
+    fn predefined_macros(&self) -> HashMap<String, MacroDefinition> {
+        self.preprocessor
+            .defines
+            .iter()
+            .map(|(name, value)| (name.clone(), MacroDefinition::predefined(name, value)))
+            .collect()
+    }
+
+    /// Add the design units of one file to the compilation unit, noting any
+    /// that were already defined with a different body
+    fn record_definitions(&mut self, ast: &SourceUnit, file: &Path, content: &str) {
+        let chars: Vec<char> = content.chars().collect();
+        for &item_ref in &ast.items {
+            let (name, (start, end)) = match ast.module_item_arena.get(item_ref) {
+                ModuleItem::ModuleDeclaration { name, span, .. }
+                | ModuleItem::InterfaceDeclaration { name, span, .. }
+                | ModuleItem::CheckerDeclaration { name, span, .. }
+                | ModuleItem::PackageDeclaration { name, span, .. }
+                | ModuleItem::ClassDeclaration { name, span, .. }
+                | ModuleItem::InterfaceClassDeclaration { name, span, .. } => (name, *span),
+                _ => continue,
+            };
+            let text = chars[start.min(chars.len())..end.min(chars.len())]
+                .iter()
+                .collect::<String>()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            let definition = UnitDefinition {
+                file: file.to_path_buf(),
+                location: Self::span_to_location(content, start..end),
+                text,
+            };
+            match self.unit_definitions.get(name) {
+                Some(previous) if previous.text != definition.text => {
+                    self.duplicate_definitions.push(DuplicateDefinition {
+                        name: name.to_string(),
+                        file: definition.file,
+                        location: definition.location,
+                        previous_file: previous.file.clone(),
+                        previous_location: previous.location.clone(),
+                    });
+                }
+                Some(_) => {}
+                None => {
+                    self.unit_definitions.insert(name.to_string(), definition);
+                }
+            }
+        }
+    }
+
+    fn record_macro(&mut self, item: &ModuleItem, file: &Path) {
+        if let Some(definition) = MacroDefinition::from_item(item, Some(file)) {
+            self.macros.insert(definition.name.clone(), definition);
+        }
+    }
+
+    fn record_include(&mut self, resolved_path: &Path) {
+        if !self.resolved_includes.iter().any(|p| p == resolved_path) {
+            self.resolved_includes.push(resolved_path.to_path_buf());
+        }
+    }
+
+    fn parse_file_with_includes(
+        &mut self,
+        file_path: &Path,
+        included_files: &mut std::collections::HashSet<std::path::PathBuf>,
+    ) -> Result<SourceUnit, ParseError> {
+        // Canonicalize the file path to detect circular includes
+        let canonical_path = file_path
+            .canonicalize()
+            .unwrap_or_else(|_| file_path.to_path_buf());
+
+        // Check for circular includes
+        if included_files.contains(&canonical_path) {
+            // Already included, return empty AST to avoid infinite recursion
+            return Ok(SourceUnit {
+                items: Vec::new(),
+                expr_arena: ExprArena::new(),
+                stmt_arena: StmtArena::new(),
+                module_item_arena: ModuleItemArena::new(),
+                source_map: SourceMap::default(),
+            });
+        }
+
+        included_files.insert(canonical_path.clone());
+
+        let raw_content = files::read_to_string(file_path).map_err(|e| {
+            ParseError::new(SingleParseError::new(
+                format!("Failed to read file {}: {}", file_path.display(), e),
+                ParseErrorType::PreprocessorError,
+            ))
         })?;
 
-        let mut ast = self.parse_content(&raw_content)?;
+        let header = self.header_mode || is_header_file(file_path);
+        let version = self.language_version_for(file_path);
+        let (result, timings) =
+            self.parse_content_timed(&raw_content, Some(file_path), header, version);
+        self.timings.parse += timings.parse;
+        self.timings.flatten += timings.flatten;
+        self.timings.units.extend(timings.units);
+        let mut ast = result?;
+        self.record_definitions(&ast, file_path, &raw_content);
         self.expand_includes_in_ast(&mut ast, file_path, included_files)?;
         Ok(ast)
     }
@@ -661,6 +3098,7 @@ impl SystemVerilogParser {
 
                 // Resolve the include path
                 let resolved_path = self.resolve_include_path(&include_path, current_file)?;
+                self.record_include(&resolved_path);
 
                 // Parse the included file
                 let included_ast = self.parse_file_with_includes(&resolved_path, included_files)?;
@@ -668,32 +3106,17 @@ impl SystemVerilogParser {
                 // Remove the include directive from the AST
                 ast.items.remove(i);
 
-                // Merge the included AST into the current AST
-                // First, we need to copy the arenas and remap references
-                let item_offset = ast.module_item_arena.nodes.len() as u32;
-                let expr_offset = ast.expr_arena.nodes.len() as u32;
-                let stmt_offset = ast.stmt_arena.nodes.len() as u32;
-
-                // Merge arenas
-                ast.expr_arena.nodes.extend(included_ast.expr_arena.nodes);
-                ast.stmt_arena.nodes.extend(included_ast.stmt_arena.nodes);
-
-                // Copy and remap module items
-                for included_item in included_ast.module_item_arena.nodes {
-                    let remapped_item =
-                        Self::remap_item(included_item, expr_offset, stmt_offset, item_offset);
-                    ast.module_item_arena.nodes.push(remapped_item);
-                }
-
-                // Insert the included items into the current position
-                for included_item_ref in included_ast.items {
-                    ast.items.insert(i, included_item_ref + item_offset);
+                // Merge the included AST into the current AST, inserting its
+                // items at the position of the directive
+                for included_item_ref in ast.absorb(included_ast) {
+                    ast.items.insert(i, included_item_ref);
                     i += 1;
                 }
 
                 // Continue processing from the current position
                 // (don't increment i, as we've already advanced it)
             } else {
+                self.record_macro(item, current_file);
                 // Not an include directive, check if it's a module with nested includes
                 self.expand_includes_in_module(item_ref, current_file, ast, included_files)?;
                 i += 1;
@@ -711,7 +3134,13 @@ impl SystemVerilogParser {
     ) -> Result<(), ParseError> {
         let item = ast.module_item_arena.get(item_ref);
 
-        if let ModuleItem::ModuleDeclaration { items, .. } = item {
+        if let ModuleItem::ModuleDeclaration { items, .. }
+        | ModuleItem::InterfaceDeclaration { items, .. }
+        | ModuleItem::CheckerDeclaration { items, .. }
+        | ModuleItem::PackageDeclaration { items, .. }
+        | ModuleItem::GenerateRegion { items, .. }
+        | ModuleItem::GenerateBlock { items, .. } = item
+        {
             let nested_items = items.clone();
             let _ = item; // Release the borrow
 
@@ -726,34 +3155,27 @@ impl SystemVerilogParser {
 
                     // Resolve and parse the included file
                     let resolved_path = self.resolve_include_path(&include_path, current_file)?;
+                    self.record_include(&resolved_path);
                     let included_ast =
                         self.parse_file_with_includes(&resolved_path, included_files)?;
 
                     // Merge the included AST
-                    let item_offset = ast.module_item_arena.nodes.len() as u32;
-                    let expr_offset = ast.expr_arena.nodes.len() as u32;
-                    let stmt_offset = ast.stmt_arena.nodes.len() as u32;
-
-                    ast.expr_arena.nodes.extend(included_ast.expr_arena.nodes);
-                    ast.stmt_arena.nodes.extend(included_ast.stmt_arena.nodes);
-
-                    for included_item in included_ast.module_item_arena.nodes {
-                        let remapped_item =
-                            Self::remap_item(included_item, expr_offset, stmt_offset, item_offset);
-                        ast.module_item_arena.nodes.push(remapped_item);
-                    }
-
-                    for included_item_ref in included_ast.items {
-                        new_items.push(included_item_ref + item_offset);
-                    }
+                    new_items.extend(ast.absorb(included_ast));
                 } else {
+                    self.record_macro(nested_item, current_file);
                     new_items.push(nested_ref);
                 }
             }
 
             // Update the module's items
             let item_mut = ast.module_item_arena.get_mut(item_ref);
-            if let ModuleItem::ModuleDeclaration { items, .. } = item_mut {
+            if let ModuleItem::ModuleDeclaration { items, .. }
+            | ModuleItem::InterfaceDeclaration { items, .. }
+            | ModuleItem::CheckerDeclaration { items, .. }
+            | ModuleItem::PackageDeclaration { items, .. }
+            | ModuleItem::GenerateRegion { items, .. }
+            | ModuleItem::GenerateBlock { items, .. } = item_mut
+            {
                 *items = new_items.clone();
             }
 
@@ -761,150 +3183,26 @@ impl SystemVerilogParser {
             for &nested_ref in &new_items {
                 self.expand_includes_in_module(nested_ref, current_file, ast, included_files)?;
             }
+        } else {
+            // Loop and branch bodies may be generate blocks with includes of their own
+            for nested_ref in item.generate_items() {
+                self.expand_includes_in_module(nested_ref, current_file, ast, included_files)?;
+            }
         }
         Ok(())
     }
 
-    fn remap_item(
-        item: ModuleItem,
-        expr_offset: u32,
-        stmt_offset: u32,
-        item_offset: u32,
-    ) -> ModuleItem {
-        match item {
-            ModuleItem::ModuleDeclaration {
-                name,
-                name_span,
-                ports,
-                items,
-                span,
-            } => ModuleItem::ModuleDeclaration {
-                name,
-                name_span,
-                ports,
-                items: items.into_iter().map(|r| r + item_offset).collect(),
-                span,
-            },
-            ModuleItem::VariableDeclaration {
-                data_type,
-                signing,
-                drive_strength,
-                delay,
-                range,
-                name,
-                name_span,
-                unpacked_dimensions,
-                initial_value,
-                span,
-            } => ModuleItem::VariableDeclaration {
-                data_type,
-                signing,
-                drive_strength,
-                delay,
-                range,
-                name,
-                name_span,
-                unpacked_dimensions,
-                initial_value: initial_value.map(|r| r + expr_offset),
-                span,
-            },
-            ModuleItem::Assignment {
-                delay,
-                target,
-                expr,
-                span,
-            } => ModuleItem::Assignment {
-                delay,
-                target: target + expr_offset,
-                expr: expr + expr_offset,
-                span,
-            },
-            ModuleItem::ProceduralBlock {
-                block_type,
-                statements,
-                span,
-            } => ModuleItem::ProceduralBlock {
-                block_type,
-                statements: statements.into_iter().map(|r| r + stmt_offset).collect(),
-                span,
-            },
-            ModuleItem::ClassDeclaration {
-                name,
-                name_span,
-                extends,
-                items,
-                span,
-            } => {
-                // Class items may contain expression references too
-                let remapped_items = items
-                    .into_iter()
-                    .map(|class_item| match class_item {
-                        ClassItem::Property {
-                            qualifier,
-                            data_type,
-                            name,
-                            name_span,
-                            unpacked_dimensions,
-                            initial_value,
-                            span,
-                        } => ClassItem::Property {
-                            qualifier,
-                            data_type,
-                            name,
-                            name_span,
-                            unpacked_dimensions,
-                            initial_value: initial_value.map(|r| r + expr_offset),
-                            span,
-                        },
-                        ClassItem::Method {
-                            qualifier,
-                            return_type,
-                            name,
-                            name_span,
-                            parameters,
-                            body,
-                            span,
-                        } => ClassItem::Method {
-                            qualifier,
-                            return_type,
-                            name,
-                            name_span,
-                            parameters,
-                            body: body.into_iter().map(|r| r + stmt_offset).collect(),
-                            span,
-                        },
-                    })
-                    .collect();
-
-                ModuleItem::ClassDeclaration {
-                    name,
-                    name_span,
-                    extends,
-                    items: remapped_items,
-                    span,
-                }
-            }
-            ModuleItem::ConcurrentAssertion { statement, span } => {
-                ModuleItem::ConcurrentAssertion {
-                    statement: statement + stmt_offset,
-                    span,
-                }
+    /// Record the file each include directive names, as found from `file`
+    fn resolve_include_directives(&self, arena: &mut ModuleItemArena, file: &Path) {
+        for item in &mut arena.nodes {
+            if let ModuleItem::IncludeDirective {
+                path,
+                resolved_path,
+                ..
+            } = item
+            {
+                *resolved_path = self.resolve_include_path(path, file).ok();
             }
-            ModuleItem::GlobalClocking {
-                identifier,
-                identifier_span,
-                clocking_event,
-                end_label,
-                span,
-            } => ModuleItem::GlobalClocking {
-                identifier,
-                identifier_span,
-                clocking_event: clocking_event + expr_offset,
-                end_label,
-                span,
-            },
-            // Items that don't need remapping
-            other => other,
         }
     }
 
@@ -917,7 +3215,7 @@ impl SystemVerilogParser {
 
         if let Some(parent) = current_file.parent() {
             let candidate = parent.join(filename);
-            if candidate.exists() {
+            if files::exists(&candidate) {
                 found_path = Some(candidate);
             }
         }
@@ -925,7 +3223,7 @@ impl SystemVerilogParser {
         if found_path.is_none() {
             for include_dir in &self.preprocessor.include_dirs {
                 let candidate = include_dir.join(filename);
-                if candidate.exists() {
+                if files::exists(&candidate) {
                     found_path = Some(candidate);
                     break;
                 }
@@ -941,46 +3239,362 @@ impl SystemVerilogParser {
     }
 
     pub fn parse_content(&self, content: &str) -> Result<SourceUnit, ParseError> {
+        self.parse_content_timed(content, None, self.header_mode, self.language_version)
+            .0
+    }
+
+    /// Parse `content` as the text of `file`, the way an editor holds a file
+    /// that may not be saved: `include files are looked for next to `file`
+    /// first, and each include directive records the file it resolves to.
+    /// Header files are parsed in header mode.
+    pub fn parse_file_content(&self, content: &str, file: &Path) -> Result<SourceUnit, ParseError> {
+        let header = self.header_mode || is_header_file(file);
+        self.parse_content_timed(content, Some(file), header, self.language_version)
+            .0
+    }
+
+    /// Parse the text of a header file, which may hold declarations such as
+    /// variables, parameters and assignments outside any module
+    pub fn parse_header_content(&self, content: &str) -> Result<SourceUnit, ParseError> {
+        self.parse_content_timed(content, None, true, self.language_version)
+            .0
+    }
+
+    /// Parse a single expression, such as `a + 8'hFF` or `$clog2(DEPTH)`
+    pub fn parse_expression(&self, text: &str) -> Result<Fragment<ExprRef>, ParseError> {
+        let ParsedFragment::Expression(expr) =
+            self.run_grammar(text, None, Entry::Expression, self.language_version)?
+        else {
+            unreachable!("the expression grammar produces an expression");
+        };
+        let mut expr_arena = ExprArena::new();
+        let root = expr.flatten(&mut expr_arena);
+        Ok(Fragment {
+            expr_arena,
+            stmt_arena: StmtArena::new(),
+            root,
+        })
+    }
+
+    /// Parse a single procedural statement, such as `count <= count + 1;` or a
+    /// `begin ... end` block
+    pub fn parse_statement(&self, text: &str) -> Result<Fragment<StmtRef>, ParseError> {
+        let ParsedFragment::Statement(statement) =
+            self.run_grammar(text, None, Entry::Statement, self.language_version)?
+        else {
+            unreachable!("the statement grammar produces a statement");
+        };
         let mut expr_arena = ExprArena::new();
         let mut stmt_arena = StmtArena::new();
-        let mut module_item_arena = ModuleItemArena::new();
-        let parser = self.build_parser();
+        let statement = statement.flatten(&mut expr_arena, &mut stmt_arena);
+        let root = stmt_arena.alloc(statement);
+        Ok(Fragment {
+            expr_arena,
+            stmt_arena,
+            root,
+        })
+    }
+
+    /// Run the grammar from `entry` over the tokens of all of `content`, or
+    /// of its preprocessed characters when given
+    fn run_grammar(
+        &self,
+        content: &str,
+        preprocessed: Option<Vec<(char, Span)>>,
+        entry: Entry,
+        version: KeywordVersion,
+    ) -> Result<ParsedFragment, ParseError> {
+        crate::parallel::with_stack(|| self.run_grammar_here(content, preprocessed, entry, version))
+    }
+
+    /// [`Self::run_grammar`] on the calling thread, whatever its stack size
+    fn run_grammar_here(
+        &self,
+        content: &str,
+        preprocessed: Option<Vec<(char, Span)>>,
+        entry: Entry,
+        version: KeywordVersion,
+    ) -> Result<ParsedFragment, ParseError> {
+        // Preprocessed text is lexed on its own, and each token then takes
+        // the span of the source text its characters come from
+        let (text, spans): (String, Option<Vec<Span>>) = match preprocessed {
+            Some(chars) => {
+                let (text, spans) = chars.into_iter().unzip();
+                (text, Some(spans))
+            }
+            None => (content.to_string(), None),
+        };
+        // Tokens with the same text share it, so most take no allocation of their own
+        let mut texts: HashMap<&str, Rc<str>> = HashMap::new();
+        let tokens: Vec<_> = Lexer::with_version(&text, version)
+            .map(|token| {
+                let (start, end) = match &spans {
+                    Some(spans) => (spans[token.span.0].0, spans[token.span.1 - 1].1),
+                    None => token.span,
+                };
+                let grammar_token = GrammarToken {
+                    kind: token.kind,
+                    text: texts
+                        .entry(token.text)
+                        .or_insert_with(|| token.text.into())
+                        .clone(),
+                    spacing: token.spacing,
+                };
+                (grammar_token, start..end)
+            })
+            .collect();
 
-        match parser.parse(content) {
-            Ok(parsed_items) => {
-                // Flatten ParsedModuleItems into ModuleItems + arena, then allocate them
-                let item_refs: Vec<ModuleItemRef> = parsed_items
+        let budget = ParseBudget::new(self.parse_timeout);
+        let parser = self.build_parser(&budget, entry);
+        let end = content.chars().count();
+        let result = parser.parse(Stream::from_iter(end..end, tokens.into_iter()));
+        if let (Some(offset), Some(limit)) = (budget.exceeded_at.get(), self.parse_timeout) {
+            return Err(ParseError::new(Self::timeout_error(content, offset, limit)));
+        }
+        result.map_err(|errors| {
+            ParseError::multiple(
+                errors
                     .into_iter()
-                    .map(|item| {
-                        let module_item =
-                            item.flatten(&mut expr_arena, &mut stmt_arena, &mut module_item_arena);
-                        module_item_arena.alloc(module_item)
-                    })
-                    .collect();
+                    .map(|e| Self::translate_error(content, e))
+                    .collect(),
+            )
+        })
+    }
+
+    /// Parse source text, also returning how long parsing and flattening took
+    fn parse_content_timed(
+        &self,
+        content: &str,
+        file: Option<&Path>,
+        header: bool,
+        version: KeywordVersion,
+    ) -> (Result<SourceUnit, ParseError>, ParseTimings) {
+        let mut timings = ParseTimings::default();
+        let mut expr_arena = ExprArena::new();
+        let mut stmt_arena = StmtArena::new();
+        let mut module_item_arena = ModuleItemArena::new();
+
+        let start = Stopwatch::start();
+        let entry = if header {
+            Entry::Header
+        } else {
+            Entry::SourceText
+        };
+        let mut read_include = |name: &str, current_file: Option<&Path>| {
+            let path = self
+                .resolve_include_path(name, current_file.unwrap_or(Path::new("")))
+                .ok()?;
+            let content = files::read_to_string(&path).ok()?;
+            Some((path, content))
+        };
+        let (chars, mut source_map) = match preprocess(
+            content,
+            file,
+            &self.predefined_macros(),
+            self.preprocess_limits,
+            self.retain_directives,
+            &mut read_include,
+        ) {
+            Ok(Some(preprocessed)) => (Some(preprocessed.chars), preprocessed.source_map),
+            Ok(None) => (None, SourceMap::default()),
+            Err(error) => return (Err(ParseError::new(error)), timings),
+        };
+        source_map.set_file(file.map(Path::to_path_buf));
+        let parsed = self.run_grammar(content, chars, entry, version);
+        timings.parse = start.elapsed();
+        // Keep the source map with the errors, so they can say which macro
+        // or conditional branch the text they point at comes from
+        let parsed = match parsed {
+            Ok(parsed) => Ok(parsed),
+            Err(mut error) => {
+                error.source_map = Box::new(source_map);
+                return (Err(error), timings);
+            }
+        };
 
-                Ok(SourceUnit {
-                    items: item_refs,
-                    expr_arena,
-                    stmt_arena,
-                    module_item_arena,
+        let result = parsed.map(|parsed| {
+            let ParsedFragment::Items(parsed_items) = parsed else {
+                unreachable!("the source text grammar produces items");
+            };
+            let start = Stopwatch::start();
+            // Flatten ParsedModuleItems into ModuleItems + arena, then allocate them
+            let item_refs: Vec<ModuleItemRef> = parsed_items
+                .into_iter()
+                .flat_map(ParsedModuleItem::declarations)
+                .map(|item| {
+                    let item_start = Stopwatch::start();
+                    let module_item =
+                        item.flatten(&mut expr_arena, &mut stmt_arena, &mut module_item_arena);
+                    if let Some(name) = unit_name(&module_item) {
+                        timings.units.push((name.to_string(), item_start.elapsed()));
+                    }
+                    module_item_arena.alloc(module_item)
                 })
+                .collect();
+            timings.flatten = start.elapsed();
+            if let Some(file) = file {
+                self.resolve_include_directives(&mut module_item_arena, file);
             }
-            Err(errors) => {
-                let parse_errors: Vec<SingleParseError> = errors
-                    .into_iter()
-                    .map(|e| {
-                        let span = e.span();
-                        let location = Self::span_to_location(content, span);
-                        SingleParseError::new(
-                            format!("Parse error: {:?}", e),
+
+            SourceUnit {
+                items: item_refs,
+                expr_arena,
+                stmt_arena,
+                module_item_arena,
+                source_map,
+            }
+        });
+        (result, timings)
+    }
+
+    /// The error of a run of the grammar that ran out of time at `offset`,
+    /// naming the design unit it was in
+    fn timeout_error(content: &str, offset: usize, limit: Duration) -> SingleParseError {
+        let mut unit = None;
+        let mut tokens = Lexer::new(content)
+            .take_while(|token| token.span.0 < offset)
+            .peekable();
+        while let Some(token) = tokens.next() {
+            match token.text {
+                "module" | "macromodule" | "interface" | "program" | "package" | "class"
+                | "checker" => {
+                    if let Some(name) = tokens.next_if(|token| token.kind == TokenKind::Identifier)
+                    {
+                        unit = Some((token.text, name.text));
+                    }
+                }
+                "endmodule" | "endinterface" | "endprogram" | "endpackage" | "endclass"
+                | "endchecker" => unit = None,
+                _ => {}
+            }
+        }
+        let region = match unit {
+            Some((keyword, name)) => format!("{} '{}'", keyword, name),
+            None => "the compilation unit".to_string(),
+        };
+        let length = Lexer::new(content)
+            .find(|token| token.span.0 == offset)
+            .map_or(0, |token| token.span.1 - token.span.0);
+        SingleParseError::new(
+            format!(
+                "parsing took longer than {} and was abandoned in {}",
+                format_time(limit.as_secs_f64() * 1e15),
+                region
+            ),
+            ParseErrorType::Timeout,
+        )
+        .with_location(Self::span_to_location(content, offset..offset + length))
+    }
+
+    /// Translate a chumsky error into a parse error naming the expected and found tokens
+    ///
+    /// Keywords and other words the grammar expects are summarised as
+    /// "identifier", giving messages like "expected ';' or ',', found
+    /// 'endmodule'"; a keyword found where an identifier was expected is
+    /// reported as a keyword.
+    fn translate_error(content: &str, error: TokenError) -> SingleParseError {
+        let span = error.span();
+        let found = error.found();
+        let found_text = found.map_or_else(
+            || "end of input".to_string(),
+            |token| Self::quote(&token.text),
+        );
+
+        let (message, error_type) = match error.reason() {
+            SimpleReason::Custom(message) => (message.clone(), ParseErrorType::InvalidSyntax),
+            SimpleReason::Unclosed { delimiter, .. } => {
+                let expected = format!("closing delimiter for {}", Self::quote(&delimiter.text));
+                (
+                    format!("expected {}, found {}", expected, found_text),
+                    ParseErrorType::ExpectedToken(expected),
+                )
+            }
+            SimpleReason::Unexpected => {
+                let identifier_expected = error
+                    .expected()
+                    .flatten()
+                    .any(|token| token.kind == TokenKind::Identifier && token.text.is_empty());
+                match (Self::describe_expected(error.expected()), found) {
+                    (_, Some(token)) if identifier_expected && token.kind == TokenKind::Keyword => {
+                        (
+                            format!("'{}' is a keyword", token.text),
                             ParseErrorType::InvalidSyntax,
                         )
-                        .with_location(location)
-                    })
-                    .collect();
-                Err(ParseError::multiple(parse_errors))
+                    }
+                    (Some(expected), _) => (
+                        format!("expected {}, found {}", expected, found_text),
+                        if found.is_some() {
+                            ParseErrorType::ExpectedToken(expected)
+                        } else {
+                            ParseErrorType::UnexpectedEndOfInput
+                        },
+                    ),
+                    (None, Some(_)) => (
+                        format!("unexpected {}", found_text),
+                        ParseErrorType::UnexpectedToken,
+                    ),
+                    (None, None) => (
+                        "unexpected end of input".to_string(),
+                        ParseErrorType::UnexpectedEndOfInput,
+                    ),
+                }
+            }
+        };
+
+        SingleParseError::new(message, error_type)
+            .with_location(Self::span_to_location(content, span))
+    }
+
+    /// Render an expected-token set as "';', ',' or identifier"; None if the set is empty
+    fn describe_expected<'a>(
+        expected: impl Iterator<Item = &'a Option<GrammarToken>>,
+    ) -> Option<String> {
+        let mut texts = Vec::new();
+        let (mut identifier, mut number, mut string, mut system_name, mut end_of_input) =
+            (false, false, false, false, false);
+        for token in expected {
+            match token {
+                Some(token) => match token.kind {
+                    TokenKind::Identifier | TokenKind::Keyword => identifier = true,
+                    TokenKind::Number => number = true,
+                    TokenKind::StringLiteral => string = true,
+                    TokenKind::SystemIdentifier if token.text.is_empty() => system_name = true,
+                    _ => texts.push(&*token.text),
+                },
+                None => end_of_input = true,
+            }
+        }
+        texts.sort_unstable();
+        texts.dedup();
+
+        let mut names: Vec<String> = texts.iter().map(|text| Self::quote(text)).collect();
+        for (expected, name) in [
+            (identifier, "identifier"),
+            (number, "number"),
+            (string, "string"),
+            (system_name, "system task or function"),
+            (end_of_input, "end of input"),
+        ] {
+            if expected {
+                names.push(name.to_string());
             }
         }
+
+        let last = names.pop()?;
+        if names.is_empty() {
+            Some(last)
+        } else {
+            Some(format!("{} or {}", names.join(", "), last))
+        }
+    }
+
+    /// Token text in quotes for a message: `';'`, or `"'"` for a text with a quote in it
+    fn quote(text: &str) -> String {
+        if text.contains('\'') {
+            format!("\"{}\"", text)
+        } else {
+            format!("'{}'", text)
+        }
     }
 
     /// Convert a character span to a SourceLocation with line/column information
@@ -1018,140 +3632,64 @@ impl SystemVerilogParser {
         analyzer.analyze(source_unit)
     }
 
-    fn build_parser(&self) -> impl Parser<char, Vec<ParsedModuleItem>, Error = Simple<char>> + '_ {
-        // Comments
-        let line_comment = just("//")
-            .then(take_until(text::newline::<char, Simple<char>>().or(end())))
-            .ignored();
-        let block_comment = just("/*").then(take_until(just("*/"))).ignored();
+    // The grammar's closures return chumsky's errors, which are large
+    #[allow(clippy::result_large_err)]
+    fn build_parser<'a>(
+        &'a self,
+        budget: &'a ParseBudget,
+        entry: Entry,
+    ) -> impl Parser<GrammarToken, ParsedFragment, Error = TokenError> + 'a {
+        // The lexer has dropped whitespace and comments and worked out which
+        // words are keywords where they appear, so a name is just an
+        // identifier token. Nearly every alternative the grammar tries comes to
+        // a name or a number, so that is where the time budget is checked
+        let within_budget = move |token: GrammarToken, span: std::ops::Range<usize>| {
+            if budget.spent(span.start) {
+                Err(Simple::custom(span, "parse time budget exceeded"))
+            } else {
+                Ok(token)
+            }
+        };
 
-        // Whitespace and comments - match any combination
-        let ws = choice((
-            filter(|c: &char| c.is_whitespace()).ignored(),
-            line_comment,
-            block_comment,
-        ))
-        .repeated()
-        .ignored();
-
-        // Keywords that should not be identifiers
-        let keywords = [
-            "module",
-            "endmodule",
-            "input",
-            "output",
-            "inout",
-            "wire",
-            "assign",
-            "initial",
-            "always",
-            "always_comb",
-            "always_ff",
-            "final",
-            "begin",
-            "end",
-            "if",
-            "else",
-            "case",
-            "casex",
-            "casez",
-            "endcase",
-            "int",
-            "logic",
-            "bit",
-            "byte",
-            "reg",
-            "signed",
-            "unsigned",
-            "integer",
-            "time",
-            "shortint",
-            "longint",
-            "class",
-            "endclass",
-            "extends",
-            "function",
-            "endfunction",
-            "local",
-            "protected",
-            "new",
-            "assert",
-            "property",
-            "unique",
-            "unique0",
-            "priority",
-            "global",
-            "clocking",
-            "endclocking",
-            "struct",
-            "union",
-            "packed",
-            "soft",
-            "tagged",
-            "supply0",
-            "supply1",
-            "tri",
-            "triand",
-            "trior",
-        ];
-
-        // Identifier: [a-zA-Z_][a-zA-Z0-9_$]* (but not keywords)
-        let identifier = filter(|c: &char| c.is_alphabetic() || *c == '_')
-            .chain::<char, _, _>(
-                filter(|c: &char| c.is_alphanumeric() || *c == '_' || *c == '$').repeated(),
-            )
-            .collect::<String>()
-            .try_map(move |s, span| {
-                if keywords.contains(&s.as_str()) {
-                    Err(Simple::custom(span, format!("'{}' is a keyword", s)))
-                } else {
-                    Ok(s)
-                }
-            });
+        // Identifier, or an escaped identifier \bus+index, which is named
+        // without its backslash
+        let identifier_token = token_of(TokenKind::Identifier).try_map(within_budget);
+        let identifier = identifier_token.map(|token| token.name().to_string());
 
-        // Number: decimal, hex, binary, octal (including z/x for high-Z/unknown)
-        let number = filter(|c: &char| {
-            c.is_ascii_digit()
-                || matches!(
-                    c,
-                    '\'' | 'x'
-                        | 'b'
-                        | 'o'
-                        | 'd'
-                        | 'h'
-                        | 'z'
-                        | '_'
-                        | 'X'
-                        | 'B'
-                        | 'O'
-                        | 'D'
-                        | 'H'
-                        | 'Z'
-                )
-        })
-        .repeated()
-        .at_least(1)
-        .collect::<String>();
+        // Number: decimal (1_000), based (8'hA5, 4'sb1010, 'o17, including z/x for
+        // high-Z/unknown) or unbased unsized ('0, '1, 'x, 'z). The text is kept as written.
+        let number = token_of(TokenKind::Number)
+            .try_map(within_budget)
+            .map(|token| token.text.to_string());
 
-        // String literal: "..."
-        let string_literal = just('"')
-            .ignore_then(
-                filter(|c: &char| *c != '"' && *c != '\\')
-                    .or(just('\\').ignore_then(any()))
-                    .repeated()
-                    .collect::<String>(),
-            )
-            .then_ignore(just('"'));
+        // String literal: "...", without its quotes and with escaped
+        // characters kept as themselves
+        let string_literal = token_of(TokenKind::StringLiteral).map(|token| {
+            let mut chars = token.text[1..token.text.len() - 1].chars();
+            let mut text = String::new();
+            while let Some(c) = chars.next() {
+                text.extend(if c == '\\' { chars.next() } else { Some(c) });
+            }
+            text
+        });
+
+        // System task or function name, without its `$`: $display, $clog2
+        let system_name =
+            token_of(TokenKind::SystemIdentifier).map(|token| token.text[1..].to_string());
+
+        // Constraints of a constraint block or of a `randomize() with` call,
+        // defined once the expressions they hold are
+        let mut constraint_set: Recursive<GrammarToken, Vec<ParsedConstraint>, TokenError> =
+            Recursive::declare();
 
         // Expression parser (recursive)
         let expr = recursive(|expr| {
             // System function call: $display(...), $sin(...), etc.
-            let system_function = just('$')
-                .ignore_then(identifier.clone())
+            let system_function = system_name
                 .then(
                     expr.clone()
-                        .separated_by(just(',').padded_by(ws.clone()))
-                        .delimited_by(just('('), just(')'))
+                        .separated_by(just(","))
+                        .delimited_by(just("("), just(")"))
                         .or_not()
                         .map(|args| args.unwrap_or_default()),
                 )
@@ -1164,12 +3702,11 @@ impl SystemVerilogParser {
                 );
 
             // New expression: new or new(args)
-            let new_expr = text::keyword("new")
+            let new_expr = keyword("new")
                 .then(
-                    just('(')
-                        .padded_by(ws.clone())
-                        .ignore_then(expr.clone().separated_by(just(',').padded_by(ws.clone())))
-                        .then_ignore(just(')').padded_by(ws.clone()))
+                    just("(")
+                        .ignore_then(expr.clone().separated_by(just(",")))
+                        .then_ignore(just(")"))
                         .or_not(),
                 )
                 .map(|(_new, arguments)| ParsedExpression::New {
@@ -1177,25 +3714,118 @@ impl SystemVerilogParser {
                     span: (0, 0),
                 });
 
+            // Scoped name: process::self, pkg::name
+            let scoped_identifier = identifier
+                .then_ignore(just("::"))
+                .then(
+                    identifier.map_with_span(|name, span: std::ops::Range<usize>| {
+                        (name, (span.start, span.end))
+                    }),
+                )
+                .map_with_span(|(scope, (name, name_span)), span| {
+                    ParsedExpression::ScopedIdentifier {
+                        scope,
+                        name,
+                        name_span,
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // Tagged union value: tagged Valid 5, tagged Valid (a + b), tagged Invalid
+            let tagged_union = keyword("tagged")
+                .ignore_then(
+                    identifier.map_with_span(|name, span: std::ops::Range<usize>| {
+                        (name, (span.start, span.end))
+                    }),
+                )
+                .then(
+                    choice((
+                        expr.clone().delimited_by(just("("), just(")")),
+                        number.map_with_span(|num, span: std::ops::Range<usize>| {
+                            ParsedExpression::Number(num, (span.start, span.end))
+                        }),
+                        identifier.map_with_span(|name, span: std::ops::Range<usize>| {
+                            ParsedExpression::Identifier(name, (span.start, span.end))
+                        }),
+                    ))
+                    .or_not(),
+                )
+                .map_with_span(|((member, member_span), value), span| {
+                    ParsedExpression::TaggedUnion {
+                        member,
+                        member_span,
+                        value: value.map(Box::new),
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // Class handles: this, super, and the base class constructor super.new
+            let this_expr = keyword("this").map_with_span(|_, span: std::ops::Range<usize>| {
+                ParsedExpression::This((span.start, span.end))
+            });
+            let super_expr = keyword("super")
+                .map_with_span(|_, span: std::ops::Range<usize>| {
+                    ParsedExpression::Super((span.start, span.end))
+                })
+                .then(
+                    just(".")
+                        .ignore_then(keyword("new").map_with_span(
+                            |_, span: std::ops::Range<usize>| (span.start, span.end),
+                        ))
+                        .or_not(),
+                )
+                .map_with_span(|(object, constructor), span: std::ops::Range<usize>| {
+                    match constructor {
+                        Some(member_span) => ParsedExpression::MemberAccess {
+                            object: Box::new(object),
+                            member: "new".to_string(),
+                            member_span,
+                            span: (span.start, span.end),
+                        },
+                        None => object,
+                    }
+                });
+
+            // Assignment pattern: '{a: 1, b: 0}, '{1, 0}, '{default: 0}
+            let pattern_key = choice((keyword("default").to("default".to_string()), identifier))
+                .map_with_span(|key, span: std::ops::Range<usize>| (key, (span.start, span.end)))
+                .then_ignore(just(":"));
+            let assignment_pattern = just("'{")
+                .ignore_then(
+                    pattern_key
+                        .or_not()
+                        .then(expr.clone())
+                        .separated_by(just(",")),
+                )
+                .then_ignore(just("}"))
+                .map_with_span(|items, span: std::ops::Range<usize>| {
+                    ParsedExpression::AssignmentPattern {
+                        items,
+                        span: (span.start, span.end),
+                    }
+                });
+
             let atom = choice((
                 new_expr,
+                this_expr,
+                super_expr,
+                assignment_pattern,
                 system_function,
-                string_literal
-                    .clone()
-                    .map(|s| ParsedExpression::StringLiteral(s, (0, 0))),
-                identifier
-                    .clone()
-                    .map(|name| ParsedExpression::Identifier(name, (0, 0))),
-                number
-                    .clone()
-                    .map(|num| ParsedExpression::Number(num, (0, 0))),
-                expr.clone().delimited_by(
-                    just('(').padded_by(ws.clone()),
-                    just(')').padded_by(ws.clone()),
-                ),
+                tagged_union,
+                scoped_identifier,
+                string_literal.map_with_span(|s, span: std::ops::Range<usize>| {
+                    ParsedExpression::StringLiteral(s, (span.start, span.end))
+                }),
+                identifier.map_with_span(|name, span: std::ops::Range<usize>| {
+                    ParsedExpression::Identifier(name, (span.start, span.end))
+                }),
+                number.map_with_span(|num, span: std::ops::Range<usize>| {
+                    ParsedExpression::Number(num, (span.start, span.end))
+                }),
+                expr.clone().delimited_by(just("("), just(")")),
             ));
 
-            // Unary operators - order matters for multi-char operators!
+            // Unary operators
             let unary_op = choice((
                 just("~&").to(UnaryOp::ReductionNand),
                 just("~|").to(UnaryOp::ReductionNor),
@@ -1212,7 +3842,6 @@ impl SystemVerilogParser {
             // Unary expression: !a, ~b, +c, -d
             let unary_expr =
                 unary_op
-                    .then_ignore(ws.clone())
                     .then(atom.clone())
                     .map(|(op, operand)| ParsedExpression::Unary {
                         op,
@@ -1220,23 +3849,49 @@ impl SystemVerilogParser {
                         span: (0, 0),
                     });
 
-            // Member access: obj.field, obj.field.subfield
-            let member_access = choice((unary_expr.clone(), atom.clone()))
-                .then(just('.').ignore_then(identifier.clone()).repeated())
-                .foldl(|object, member| ParsedExpression::MemberAccess {
+            // Member names: .field, .field.subfield
+            let members =
+                just(".")
+                    .ignore_then(identifier.map_with_span(
+                        |member, span: std::ops::Range<usize>| (member, (span.start, span.end)),
+                    ))
+                    .repeated();
+            let member_fold =
+                |object, (member, member_span): (String, Span)| ParsedExpression::MemberAccess {
                     object: Box::new(object),
                     member,
-                    member_span: (0, 0),
+                    member_span,
                     span: (0, 0),
+                };
+
+            // Increment and decrement operators
+            let inc_dec_op = choice((
+                just("++").to(IncDecOp::Increment),
+                just("--").to(IncDecOp::Decrement),
+            ));
+
+            // Prefix increment: ++i, --obj.count
+            let prefix_inc_dec = inc_dec_op
+                .then(atom.clone().then(members).foldl(member_fold))
+                .map_with_span(|(op, operand), span| ParsedExpression::IncDec {
+                    op,
+                    prefix: true,
+                    operand: Box::new(operand),
+                    span: (span.start, span.end),
                 });
 
+            // Member access: obj.field, obj.field.subfield
+            let member_access = choice((prefix_inc_dec, unary_expr.clone(), atom.clone()))
+                .then(members)
+                .foldl(member_fold);
+
             // Function call: func(), obj.method()
             let function_call = member_access
                 .clone()
                 .then(
                     expr.clone()
-                        .separated_by(just(',').padded_by(ws.clone()))
-                        .delimited_by(just('('), just(')'))
+                        .separated_by(just(","))
+                        .delimited_by(just("("), just(")"))
                         .or_not(),
                 )
                 .map(|(function, maybe_args)| {
@@ -1251,12 +3906,35 @@ impl SystemVerilogParser {
                     }
                 });
 
-            let primary = function_call;
+            // Inline constraints of a call: obj.randomize() with { len < 8; }
+            let randomize_with = function_call
+                .then(keyword("with").ignore_then(constraint_set.clone()).or_not())
+                .map_with_span(|(call, constraints), span| match constraints {
+                    Some(constraints) => ParsedExpression::RandomizeWith {
+                        call: Box::new(call),
+                        constraints,
+                        span: (span.start, span.end),
+                    },
+                    None => call,
+                });
+
+            // Postfix increment: i++, obj.count--
+            let primary =
+                randomize_with
+                    .then(inc_dec_op.or_not())
+                    .map_with_span(|(operand, op), span| match op {
+                        Some(op) => ParsedExpression::IncDec {
+                            op,
+                            prefix: false,
+                            operand: Box::new(operand),
+                            span: (span.start, span.end),
+                        },
+                        None => operand,
+                    });
 
-            // Binary operators - split into groups to avoid tuple size limits
+            // Binary operators - split into groups to avoid tuple size limits;
+            // the implications bind looser than `?:` and are parsed below it
             let binary_op_multi = choice((
-                just("<->").to(BinaryOp::LogicalEquiv),
-                just("->").to(BinaryOp::LogicalImpl),
                 just("<<<").to(BinaryOp::ArithmeticShiftLeft),
                 just(">>>").to(BinaryOp::ArithmeticShiftRight),
                 just("<<").to(BinaryOp::LogicalShiftLeft),
@@ -1276,6 +3954,7 @@ impl SystemVerilogParser {
             let binary_op_single = choice((
                 just("**").to(BinaryOp::Power),
                 just("~^").to(BinaryOp::BitwiseXnor),
+                just("^~").to(BinaryOp::BitwiseXnor),
                 just("<").to(BinaryOp::LessThan),
                 just(">").to(BinaryOp::GreaterThan),
                 just("+").to(BinaryOp::Add),
@@ -1290,141 +3969,636 @@ impl SystemVerilogParser {
 
             let binary_op = choice((binary_op_multi, binary_op_single));
 
-            primary
+            // Chain of binary operators: a + b * c - d, grouped by precedence
+            let binary = primary
                 .clone()
+                .then(binary_op.then(primary.clone()).repeated())
+                .map(|(first, rest)| group_binary(first, rest));
+
+            // Conditional operator: sel ? a : b, right associative
+            let conditional = recursive(|conditional| {
+                binary
+                    .then(
+                        just("?")
+                            .ignore_then(expr.clone())
+                            .then_ignore(just(":"))
+                            .then(conditional)
+                            .or_not(),
+                    )
+                    .map(|(condition, branches)| match branches {
+                        Some((then_expr, else_expr)) => ParsedExpression::Conditional {
+                            condition: Box::new(condition),
+                            then_expr: Box::new(then_expr),
+                            else_expr: Box::new(else_expr),
+                            span: (0, 0),
+                        },
+                        None => condition,
+                    })
+            });
+
+            // Implications: a -> b, a <-> b, right associative
+            conditional
                 .then(
-                    binary_op
-                        .padded_by(ws.clone())
-                        .then(primary.clone())
-                        .or_not(),
+                    choice((
+                        just("<->").to(BinaryOp::LogicalEquiv),
+                        just("->").to(BinaryOp::LogicalImpl),
+                    ))
+                    .then(expr.clone())
+                    .or_not(),
                 )
-                .map(|(left, maybe_right)| {
-                    if let Some((op, right)) = maybe_right {
-                        ParsedExpression::Binary {
-                            op,
-                            left: Box::new(left),
-                            right: Box::new(right),
-                            span: (0, 0),
-                        }
-                    } else {
-                        left
-                    }
+                .map(|(left, implication)| match implication {
+                    Some((op, right)) => ParsedExpression::Binary {
+                        op,
+                        left: Box::new(left),
+                        right: Box::new(right),
+                        span: (0, 0),
+                    },
+                    None => left,
                 })
         });
 
+        // Value of an `inside` or `dist` set: a value or a range [lo:hi]
+        let set_value = expr
+            .clone()
+            .then_ignore(just(":"))
+            .then(expr.clone())
+            .delimited_by(just("["), just("]"))
+            .map_with_span(|(low, high), span| ParsedExpression::ValueRange {
+                low: Box::new(low),
+                high: Box::new(high),
+                span: (span.start, span.end),
+            })
+            .or(expr.clone());
+        let open_brace = just("{");
+        let close_brace = just("}");
+
+        // Constraint: `len < 8;`, `soft len == 4;`, `x inside {[0:7], 9};`,
+        // `y dist {0 := 5, [1:3] :/ 1};`, `mode -> len < 4;`, `mode -> {...}`
+        // or `if (mode) ... else ...`
+        let constraint = recursive(|constraint| {
+            let constraints = constraint
+                .clone()
+                .repeated()
+                .delimited_by(open_brace, close_brace);
+            let body = choice((
+                constraints.clone(),
+                constraint.map(|constraint| vec![constraint]),
+            ));
+            let inside = keyword("inside").ignore_then(
+                set_value
+                    .clone()
+                    .separated_by(just(","))
+                    .delimited_by(open_brace, close_brace),
+            );
+            let dist_item = set_value
+                .clone()
+                .then(choice((just(":=").to(false), just(":/").to(true))))
+                .then(expr.clone())
+                .map(|((value, per_range), weight)| (value, weight, per_range));
+            let dist = keyword("dist").ignore_then(
+                dist_item
+                    .separated_by(just(","))
+                    .delimited_by(open_brace, close_brace),
+            );
+            let semicolon = just(";");
+            let tail = choice((
+                inside
+                    .map(ParsedConstraintTail::Inside)
+                    .then_ignore(semicolon),
+                dist.map(ParsedConstraintTail::Dist).then_ignore(semicolon),
+                just("->")
+                    .ignore_then(constraints)
+                    .map(ParsedConstraintTail::Implication),
+                semicolon.to(ParsedConstraintTail::None),
+            ));
+            let expression_constraint = keyword("soft")
+                .or_not()
+                .map(|soft| soft.is_some())
+                .then(expr.clone())
+                .then(tail)
+                .map_with_span(|((soft, expr), tail), span| {
+                    ParsedConstraint::new(soft, expr, tail, (span.start, span.end))
+                });
+            let if_else = keyword("if")
+                .ignore_then(expr.clone().delimited_by(just("("), just(")")))
+                .then(body.clone())
+                .then(keyword("else").ignore_then(body).or_not())
+                .map_with_span(|((condition, then_constraints), else_constraints), span| {
+                    ParsedConstraint::IfElse {
+                        condition,
+                        then_constraints,
+                        else_constraints: else_constraints.unwrap_or_default(),
+                        span: (span.start, span.end),
+                    }
+                });
+            choice((if_else, expression_constraint))
+        });
+        constraint_set.define(constraint.repeated().delimited_by(open_brace, close_brace));
+
         // Delay: #number
-        let delay = just('#').ignore_then(number.clone()).map(Delay::Value);
+        let delay = just("#").ignore_then(number).map(Delay::Value);
 
         // Range: [3:0]
-        let range = just('[')
-            .padded_by(ws.clone())
-            .ignore_then(choice((number.clone(), identifier.clone())))
-            .then_ignore(ws.clone())
-            .then_ignore(just(':'))
-            .then_ignore(ws.clone())
-            .then(choice((number.clone(), identifier.clone())))
-            .then_ignore(ws.clone())
-            .then_ignore(just(']'))
+        let range = just("[")
+            .ignore_then(choice((number, identifier)))
+            .then_ignore(just(":"))
+            .then(choice((number, identifier)))
+            .then_ignore(just("]"))
             .map(|(msb, lsb)| Range { msb, lsb });
 
-        // Concurrent assertion
-        let concurrent_assertion = text::keyword("assert")
-            .padded_by(ws.clone())
-            .ignore_then(text::keyword("property"))
-            .then_ignore(ws.clone())
+        // Clocking event: @(posedge clk) or @clk, kept as its source text
+        let clocking_event = just("@")
             .ignore_then(
-                filter(|c| *c != ';')
-                    .repeated()
-                    .then_ignore(just(';').padded_by(ws.clone())),
+                just("(")
+                    .ignore_then(until(")"))
+                    .then_ignore(just(")"))
+                    .map(|s| format!("@({})", s))
+                    .or(identifier.map(|s| format!("@{}", s))),
+            )
+            .map_with_span(|text, span: std::ops::Range<usize>| {
+                ParsedExpression::Identifier(text, (span.start, span.end))
+            });
+
+        // Event control: @(posedge clk or negedge rst_n), @ready, @* or @(*)
+        let edge = choice((
+            keyword("posedge").to(Edge::Posedge),
+            keyword("negedge").to(Edge::Negedge),
+            keyword("edge").to(Edge::Edge),
+        ));
+        let event_expression = edge
+            .or_not()
+            .then(expr.clone())
+            .then(keyword("iff").ignore_then(expr.clone()).or_not())
+            .map_with_span(|((edge, signal), iff), span: std::ops::Range<usize>| {
+                ParsedEventExpression {
+                    edge,
+                    signal,
+                    iff,
+                    span: (span.start, span.end),
+                }
+            });
+        let implicit_event = just("(")
+            .then(just("*"))
+            .then(just(")"))
+            .ignored()
+            .or(just("*").ignored());
+        let event_control = just("@")
+            .ignore_then(choice((
+                implicit_event.to(None),
+                event_expression
+                    .separated_by(keyword("or").ignored().or(just(",").ignored()))
+                    .at_least(1)
+                    .delimited_by(just("("), just(")"))
+                    .map(Some),
+                identifier
+                    .map_with_span(|name, span: std::ops::Range<usize>| {
+                        let span = (span.start, span.end);
+                        vec![ParsedEventExpression {
+                            edge: None,
+                            signal: ParsedExpression::Identifier(name, span),
+                            iff: None,
+                            span,
+                        }]
+                    })
+                    .map(Some),
+            )))
+            .map_with_span(|events, span: std::ops::Range<usize>| {
+                let span = (span.start, span.end);
+                match events {
+                    Some(events) => ParsedEventControl::Events(events, span),
+                    None => ParsedEventControl::Implicit(span),
+                }
+            });
+
+        // disable iff (cond)
+        let disable_iff = keyword("disable")
+            .ignore_then(keyword("iff"))
+            .ignore_then(expr.clone().delimited_by(just("("), just(")")));
+
+        // Number of clock ticks of a delay or repetition: `2`, `N:M` or `1:$`
+        let cycle_range = expr
+            .clone()
+            .then(
+                just(":")
+                    .ignore_then(expr.clone().map(Some).or(just("$").to(None)))
+                    .or_not(),
+            )
+            .map(|(low, high)| ParsedCycleRange { low, high });
+
+        // Cycle delay: ##2, ##N, ##(N + 1) or ##[1:3]
+        let cycle_delay = just("##").ignore_then(choice((
+            cycle_range.clone().delimited_by(just("["), just("]")),
+            choice((
+                number.map_with_span(|num, span: std::ops::Range<usize>| {
+                    ParsedExpression::Number(num, (span.start, span.end))
+                }),
+                identifier.map_with_span(|name, span: std::ops::Range<usize>| {
+                    ParsedExpression::Identifier(name, (span.start, span.end))
+                }),
+                expr.clone().delimited_by(just("("), just(")")),
+            ))
+            .map(|low| ParsedCycleRange { low, high: None }),
+        )));
+
+        // Repetition: [*3], [=1:2], [->1]
+        let repetition = just("[")
+            .ignore_then(choice((
+                just("->").to(RepetitionKind::Goto),
+                just("*").to(RepetitionKind::Consecutive),
+                just("=").to(RepetitionKind::NonConsecutive),
+            )))
+            .then(cycle_range)
+            .then_ignore(just("]"))
+            .map_with_span(|(kind, cycles), span: std::ops::Range<usize>| (kind, cycles, span.end));
+
+        // Sequence and property expressions, from the tightest binding
+        // operators to the loosest: repetition, ##, throughout, within,
+        // intersect, not, and, or, then |-> and |=>
+        let property_expr = recursive(|property| {
+            let atom = choice((
+                expr.clone()
+                    .map_with_span(|expr, span: std::ops::Range<usize>| {
+                        ParsedPropertyExpr::Expression(expr, (span.start, span.end))
+                    }),
+                property.clone().delimited_by(just("("), just(")")),
+                clocking_event.then(property.clone()).map_with_span(
+                    |(clocking_event, operand), span| ParsedPropertyExpr::Clocked {
+                        clocking_event,
+                        operand: Box::new(operand),
+                        span: (span.start, span.end),
+                    },
+                ),
+            ));
+
+            let repeated =
+                atom.then(repetition.repeated())
+                    .foldl(
+                        |operand, (kind, cycles, end)| ParsedPropertyExpr::Repetition {
+                            span: (operand.span().0, end),
+                            operand: Box::new(operand),
+                            kind,
+                            cycles,
+                        },
+                    );
+
+            // a ##1 b ##[0:2] c, or a sequence starting with a delay, ##1 b
+            let delayed = cycle_delay
+                .clone()
+                .then(repeated.clone())
+                .map_with_span(|(cycles, right), span| ParsedPropertyExpr::Delay {
+                    left: None,
+                    cycles,
+                    right: Box::new(right),
+                    span: (span.start, span.end),
+                })
+                .or(repeated.clone())
+                .then(cycle_delay.clone().then(repeated).repeated())
+                .foldl(|left, (cycles, right)| ParsedPropertyExpr::Delay {
+                    span: (left.span().0, right.span().1),
+                    left: Some(Box::new(left)),
+                    cycles,
+                    right: Box::new(right),
+                });
+
+            let chain =
+                |operand: BoxedParser<'a, GrammarToken, ParsedPropertyExpr, TokenError>,
+                 op: BoxedParser<'a, GrammarToken, PropertyOp, TokenError>| {
+                    operand
+                        .clone()
+                        .then(op.then(operand).repeated())
+                        .map(|(first, rest)| group_property(first, rest))
+                        .boxed()
+                };
+            let throughout = chain(
+                delayed.boxed(),
+                keyword("throughout").to(PropertyOp::Throughout).boxed(),
+            );
+            let within = chain(throughout, keyword("within").to(PropertyOp::Within).boxed());
+            let intersect = chain(
+                within,
+                keyword("intersect").to(PropertyOp::Intersect).boxed(),
+            );
+            let negated = keyword("not")
+                .map_with_span(|_, span: std::ops::Range<usize>| span.start)
+                .repeated()
+                .then(intersect)
+                .foldr(|start, operand| ParsedPropertyExpr::Not {
+                    span: (start, operand.span().1),
+                    operand: Box::new(operand),
+                })
+                .boxed();
+            let and = chain(negated, keyword("and").to(PropertyOp::And).boxed());
+            let or = chain(and, keyword("or").to(PropertyOp::Or).boxed());
+            chain(
+                or,
+                choice((
+                    just("|->").to(PropertyOp::OverlappedImplication),
+                    just("|=>").to(PropertyOp::NonOverlappedImplication),
+                ))
+                .boxed(),
             )
-            .map_with_span(|_, span| ParsedModuleItem::ConcurrentAssertion {
-                statement: ParsedStatement::ExpressionStatement {
-                    expr: ParsedExpression::Identifier("placeholder".to_string(), (0, 0)),
+        });
+
+        // Assertion up to its action block: assert property (@(posedge clk)
+        // disable iff (rst) req |=> gnt), or the assume, cover or restrict form
+        let assertion = choice((
+            keyword("assert").to(AssertionKind::Assert),
+            keyword("assume").to(AssertionKind::Assume),
+            keyword("cover").to(AssertionKind::Cover),
+            keyword("restrict").to(AssertionKind::Restrict),
+        ))
+        .then_ignore(keyword("property"))
+        .then_ignore(just("("))
+        .then(clocking_event.or_not())
+        .then(disable_iff.clone().or_not())
+        .then(property_expr.clone())
+        .then_ignore(just(")"));
+
+        // default clocking name;
+        let default_clocking = keyword("default")
+            .ignore_then(keyword("clocking"))
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then_ignore(just(";"))
+            .map_with_span(
+                |(name, name_span), span| ParsedModuleItem::DefaultClocking {
+                    name,
+                    name_span,
+                    span: (span.start, span.end),
+                },
+            );
+
+        // default disable iff (cond);
+        let default_disable_iff = keyword("default")
+            .ignore_then(disable_iff.clone())
+            .then_ignore(just(";"))
+            .map_with_span(|condition, span| ParsedModuleItem::DefaultDisableIff {
+                condition,
+                span: (span.start, span.end),
+            });
+
+        // Time literal of a time unit declaration: 1ns, 100 ps
+        let time_value = token_where(TokenKind::Number, "", |token| {
+            token.kind == TokenKind::Number && token.text.bytes().all(|b| b.is_ascii_digit())
+        })
+        .try_map(|digits, span: std::ops::Range<usize>| {
+            digits
+                .text
+                .parse::<u32>()
+                .map_err(|_| Simple::custom(span, "time magnitude is too large"))
+        })
+        .then(choice((
+            keyword("fs").to(TimeUnit::Fs),
+            keyword("ps").to(TimeUnit::Ps),
+            keyword("ns").to(TimeUnit::Ns),
+            keyword("us").to(TimeUnit::Us),
+            keyword("ms").to(TimeUnit::Ms),
+            keyword("s").to(TimeUnit::S),
+        )))
+        .map_with_span(|(magnitude, unit), span| TimeValue {
+            magnitude,
+            unit,
+            span: (span.start, span.end),
+        });
+        let time_precision = just("/").ignore_then(time_value);
+
+        // `timescale 1ns / 1ps
+        let timescale_directive = just("`timescale")
+            .ignore_then(time_value)
+            .then(time_precision)
+            .map_with_span(
+                |(unit, precision), span| ParsedModuleItem::TimescaleDirective {
+                    unit,
+                    precision,
+                    span: (span.start, span.end),
                 },
+            );
+
+        // `default_nettype none, `default_nettype wire
+        let default_nettype_directive = just("`default_nettype")
+            .ignore_then(
+                token_where(TokenKind::Identifier, "", |token| {
+                    matches!(token.kind, TokenKind::Identifier | TokenKind::Keyword)
+                })
+                .map(|token| token.text.to_string())
+                .try_map(|net_type: String, span: std::ops::Range<usize>| {
+                    // Any net type but the supplies, or none for no implicit nets
+                    if net_type == "none"
+                        || (is_net_type(&net_type) && !net_type.starts_with("supply"))
+                    {
+                        Ok(net_type)
+                    } else {
+                        Err(Simple::custom(
+                            span,
+                            format!("'{}' is not a net type for `default_nettype", net_type),
+                        ))
+                    }
+                }),
+            )
+            .map_with_span(|net_type, span| ParsedModuleItem::DefaultNettypeDirective {
+                net_type,
                 span: (span.start, span.end),
             });
 
-        // Type keywords - order matters! Longer keywords first
+        // timeunit 1ns; timeunit 1ns / 1ps; timeprecision 1ps;
+        let timeunits_decl = choice((
+            keyword("timeunit")
+                .ignore_then(time_value)
+                .then(time_precision.or_not())
+                .map(|(unit, precision)| (Some(unit), precision)),
+            keyword("timeprecision")
+                .ignore_then(time_value)
+                .map(|precision| (None, Some(precision))),
+        ))
+        .then_ignore(just(";"))
+        .map_with_span(
+            |(unit, precision), span| ParsedModuleItem::TimeunitsDeclaration {
+                unit,
+                precision,
+                span: (span.start, span.end),
+            },
+        );
+
+        // Type keywords
         let type_keyword = choice((
-            text::keyword("shortint").to("shortint".to_string()),
-            text::keyword("longint").to("longint".to_string()),
-            text::keyword("integer").to("integer".to_string()),
-            text::keyword("supply0").to("supply0".to_string()),
-            text::keyword("supply1").to("supply1".to_string()),
-            text::keyword("triand").to("triand".to_string()),
-            text::keyword("trior").to("trior".to_string()),
-            text::keyword("logic").to("logic".to_string()),
-            text::keyword("uwire").to("uwire".to_string()),
-            text::keyword("wire").to("wire".to_string()),
-            text::keyword("wand").to("wand".to_string()),
-            text::keyword("wor").to("wor".to_string()),
-            text::keyword("byte").to("byte".to_string()),
-            text::keyword("time").to("time".to_string()),
-            text::keyword("tri0").to("tri0".to_string()),
-            text::keyword("tri1").to("tri1".to_string()),
-            text::keyword("tri").to("tri".to_string()),
-            text::keyword("int").to("int".to_string()),
-            text::keyword("bit").to("bit".to_string()),
-            text::keyword("reg").to("reg".to_string()),
+            keyword("shortint").to("shortint".to_string()),
+            keyword("longint").to("longint".to_string()),
+            keyword("integer").to("integer".to_string()),
+            keyword("supply0").to("supply0".to_string()),
+            keyword("supply1").to("supply1".to_string()),
+            keyword("triand").to("triand".to_string()),
+            keyword("trior").to("trior".to_string()),
+            keyword("logic").to("logic".to_string()),
+            keyword("uwire").to("uwire".to_string()),
+            keyword("wire").to("wire".to_string()),
+            keyword("wand").to("wand".to_string()),
+            keyword("wor").to("wor".to_string()),
+            keyword("byte").to("byte".to_string()),
+            keyword("time").to("time".to_string()),
+            keyword("event").to("event".to_string()),
+            keyword("tri0").to("tri0".to_string()),
+            keyword("tri1").to("tri1".to_string()),
+            keyword("tri").to("tri".to_string()),
+            keyword("int").to("int".to_string()),
+            keyword("bit").to("bit".to_string()),
+            keyword("reg").to("reg".to_string()),
         ));
 
+        // Virtual interface type: virtual interface my_if, virtual my_if.modport
+        let virtual_interface_type = keyword("virtual")
+            .ignore_then(keyword("interface").or_not())
+            .ignore_then(identifier)
+            .then(just(".").ignore_then(identifier).or_not())
+            .map(|(interface, modport)| match modport {
+                Some(modport) => format!("virtual {}.{}", interface, modport),
+                None => format!("virtual {}", interface),
+            });
+
         // Port direction
         let port_direction = choice((
-            text::keyword("input").to(PortDirection::Input),
-            text::keyword("output").to(PortDirection::Output),
-            text::keyword("inout").to(PortDirection::Inout),
+            keyword("input").to(PortDirection::Input),
+            keyword("output").to(PortDirection::Output),
+            keyword("inout").to(PortDirection::Inout),
         ));
 
-        // Preprocessor directives
-        let define_directive = ws
+        // Clocking block: [default] clocking cb @(posedge clk); input a;
+        // output #2 b; endclocking [: cb]. Skews are kept as written, like
+        // #2, #1step or posedge #1
+        let delay_part =
+            |token: &GrammarToken| matches!(token.kind, TokenKind::Number | TokenKind::Identifier);
+        let clocking_delay = just("#")
+            .ignore_then(token_where(TokenKind::Number, "", delay_part))
+            .chain(
+                token_where(TokenKind::Number, "", move |token| {
+                    delay_part(token) && token.spacing == Spacing::Joined
+                })
+                .repeated(),
+            )
+            .map(|delay| format!("#{}", source_text(&delay)));
+        let clocking_skew = choice((
+            choice((
+                keyword("posedge").to("posedge"),
+                keyword("negedge").to("negedge"),
+                keyword("edge").to("edge"),
+            ))
+            .then(clocking_delay.or_not())
+            .map(|(edge, delay)| match delay {
+                Some(delay) => format!("{} {}", edge, delay),
+                None => edge.to_string(),
+            }),
+            clocking_delay,
+        ));
+        // default input #1step output #0;
+        let default_skew = keyword("default")
+            .ignore_then(keyword("input").ignore_then(clocking_skew).or_not())
+            .then(keyword("output").ignore_then(clocking_skew).or_not())
+            .then_ignore(just(";"))
+            .try_map(|skews, span| match skews {
+                (None, None) => Err(Simple::custom(
+                    span,
+                    "default skew must give an input or output skew",
+                )),
+                skews => Ok(skews),
+            });
+        // input #1step a, b;
+        let clocking_signals = port_direction
             .clone()
-            .ignore_then(just('`'))
-            .ignore_then(text::keyword("define"))
-            .ignore_then(ws.clone())
-            .ignore_then(
+            .then(clocking_skew.or_not())
+            .then(
                 identifier
-                    .clone()
-                    .map_with_span(|n, s| (n, (s.start, s.end))),
+                    .map_with_span(|n, s| (n, (s.start, s.end)))
+                    .separated_by(just(","))
+                    .at_least(1),
             )
-            .then_ignore(ws.clone())
+            .then_ignore(just(";"))
+            .map_with_span(|((direction, skew), names), span| {
+                names
+                    .into_iter()
+                    .map(|(name, name_span)| ClockingSignal {
+                        direction: direction.clone(),
+                        skew: skew.clone(),
+                        name,
+                        name_span,
+                        span: (span.start, span.end),
+                    })
+                    .collect::<Vec<_>>()
+            });
+        let clocking_block = keyword("default")
+            .or_not()
+            .map(|default| default.is_some())
+            .then_ignore(keyword("clocking"))
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(clocking_event)
+            .then_ignore(just(";"))
             .then(
-                just('(')
-                    .ignore_then(
-                        identifier
-                            .clone()
-                            .separated_by(just(',').padded_by(ws.clone())),
-                    )
-                    .then_ignore(just(')'))
-                    .then_ignore(ws.clone())
+                choice((
+                    default_skew.map(|skews| (Some(skews), Vec::new())),
+                    clocking_signals.map(|signals| (None, signals)),
+                ))
+                .repeated(),
+            )
+            .then_ignore(keyword("endclocking"))
+            .then(
+                just(":")
+                    .ignore_then(identifier.map_with_span(|name, span| Label {
+                        name,
+                        span: (span.start, span.end),
+                    }))
                     .or_not(),
             )
-            .then(filter(|c: &char| *c != '\n').repeated().collect::<String>())
+            .map_with_span(
+                |((((default, (name, name_span)), clocking_event), items), end_label), span| {
+                    let mut default_skews = (None, None);
+                    let mut signals = Vec::new();
+                    for (skews, item_signals) in items {
+                        if let Some((input, output)) = skews {
+                            default_skews = (input, output);
+                        }
+                        signals.extend(item_signals);
+                    }
+                    ParsedModuleItem::ClockingBlock {
+                        default,
+                        name,
+                        name_span,
+                        clocking_event,
+                        default_input_skew: default_skews.0,
+                        default_output_skew: default_skews.1,
+                        signals,
+                        end_label,
+                        span: (span.start, span.end),
+                    }
+                },
+            );
+
+        // Preprocessor directives
+        let define_directive = just("`define")
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            // Formal arguments follow the name directly; the text ends at the end of the line
+            .then(
+                token_where(TokenKind::Operator, "", |token| {
+                    &*token.text == "(" && token.spacing == Spacing::Joined
+                })
+                .ignore_then(identifier.separated_by(just(",")))
+                .then_ignore(just(")"))
+                .or_not(),
+            )
+            .then(rest_of_line())
             .map_with_span(|(((name, name_span), params), value), span| {
                 ParsedModuleItem::DefineDirective {
                     name,
                     name_span,
                     parameters: params.unwrap_or_default(),
-                    value: value.trim().to_string(),
+                    value,
                     span: (span.start, span.end),
                 }
             });
 
-        let include_directive = ws
-            .clone()
-            .ignore_then(just('`'))
-            .ignore_then(text::keyword("include"))
-            .then_ignore(ws.clone())
+        let include_directive = just("`include")
             .ignore_then(
                 // Parse "filename" or <filename>
                 choice((
-                    filter(|c: &char| *c != '\n' && *c != '"' && *c != '<' && *c != '>')
-                        .repeated()
-                        .collect::<String>()
-                        .delimited_by(just('"'), just('"')),
-                    filter(|c: &char| *c != '\n' && *c != '"' && *c != '<' && *c != '>')
-                        .repeated()
-                        .collect::<String>()
-                        .delimited_by(just('<'), just('>')),
+                    token_of(TokenKind::StringLiteral)
+                        .map(|token| token.text[1..token.text.len() - 1].to_string()),
+                    until(">").delimited_by(just("<"), just(">")),
                 )),
             )
             .map_with_span(|path, span| ParsedModuleItem::IncludeDirective {
@@ -1433,20 +4607,65 @@ impl SystemVerilogParser {
                 span: (span.start, span.end),
             });
 
+        // Import declaration: import pkg::*; or import pkg::name, other::*;
+        let package_import = identifier
+            .map_with_span(|n, s| (n, (s.start, s.end)))
+            .then_ignore(just("::"))
+            .then(
+                just("*")
+                    .to(None)
+                    .or(identifier.map(Some))
+                    .map_with_span(|n, s| (n, (s.start, s.end))),
+            )
+            .map_with_span(
+                |((package, package_span), (name, name_span)), span| PackageImport {
+                    package,
+                    package_span,
+                    name,
+                    name_span,
+                    span: (span.start, span.end),
+                },
+            );
+        let import_decl = keyword("import")
+            .ignore_then(package_import.clone().separated_by(just(",")).at_least(1))
+            .then_ignore(just(";"))
+            .map_with_span(|imports, span| ParsedModuleItem::ImportDeclaration {
+                imports,
+                span: (span.start, span.end),
+            });
+
+        // Export declaration in a package: export pkg::name; export pkg::*;
+        // export *::*;
+        let all_packages = just("*")
+            .map_with_span(|_, s: std::ops::Range<usize>| (s.start, s.end))
+            .then_ignore(just("::"))
+            .then(just("*").map_with_span(|_, s: std::ops::Range<usize>| (s.start, s.end)))
+            .map_with_span(|(package_span, name_span), span| PackageImport {
+                package: "*".to_string(),
+                package_span,
+                name: None,
+                name_span,
+                span: (span.start, span.end),
+            });
+        let export_decl = keyword("export")
+            .ignore_then(
+                all_packages
+                    .or(package_import)
+                    .separated_by(just(","))
+                    .at_least(1),
+            )
+            .then_ignore(just(";"))
+            .map_with_span(|exports, span| ParsedModuleItem::ExportDeclaration {
+                exports,
+                span: (span.start, span.end),
+            });
+
         // Port declaration
-        let port_decl = ws
+        let port_decl = port_direction
             .clone()
-            .ignore_then(port_direction.clone())
-            .then_ignore(ws.clone())
             .then(type_keyword.clone()) // port type (wire, reg, logic, etc.)
-            .then_ignore(ws.clone())
-            .then(
-                identifier
-                    .clone()
-                    .map_with_span(|n, s| (n, (s.start, s.end))),
-            ) // port name
-            .then_ignore(ws.clone())
-            .then_ignore(just(';'))
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end)))) // port name
+            .then_ignore(just(";"))
             .map_with_span(|((direction, port_type), (name, name_span)), span| {
                 ParsedModuleItem::PortDeclaration {
                     direction,
@@ -1460,344 +4679,1385 @@ impl SystemVerilogParser {
         // Port: input [3:0] a, output b, output reg data, or just "clk" (non-ANSI)
         let port = port_direction
             .clone()
-            .then_ignore(ws.clone())
             .then(
                 // Optional type keyword (e.g., 'reg', 'wire')
-                type_keyword.clone().then_ignore(ws.clone()).or_not(),
+                type_keyword.clone().or_not(),
             )
-            .then(range.clone().or_not())
-            .then_ignore(ws.clone())
-            .then(identifier.clone())
-            .map(|(((direction, _type), range), name)| Port {
-                name: name.clone(),
-                name_span: (0, 0),
-                direction: Some(direction),
-                range,
-                span: (0, 0),
-            })
-            .or(
-                // Non-ANSI style: just port name without direction
-                identifier.clone().map(|name| Port {
-                    name: name.clone(),
-                    name_span: (0, 0),
-                    direction: None,
+            .then(range.or_not())
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .map_with_span(
+                |(((direction, data_type), range), (name, name_span)), span| Port {
+                    name,
+                    name_span,
+                    direction: Some(direction),
+                    data_type,
+                    range,
+                    interface: None,
+                    modport: None,
+                    span: (span.start, span.end),
+                },
+            )
+            .or(
+                // Interface port: bus_if m, bus_if.master m or interface m
+                identifier
+                    .or(keyword("interface").to("interface".to_string()))
+                    .then(just(".").ignore_then(identifier).or_not())
+                    .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                    .map_with_span(|((interface, modport), (name, name_span)), span| Port {
+                        name,
+                        name_span,
+                        direction: None,
+                        data_type: None,
+                        range: None,
+                        interface: Some(interface),
+                        modport,
+                        span: (span.start, span.end),
+                    }),
+            )
+            .or(
+                // Non-ANSI style: just port name without direction
+                identifier.map_with_span(|name, span| Port {
+                    name,
+                    name_span: (span.start, span.end),
+                    direction: None,
+                    data_type: None,
                     range: None,
-                    span: (0, 0),
+                    interface: None,
+                    modport: None,
+                    span: (span.start, span.end),
                 }),
             );
 
+        // Checker port: a module port, or a formal typed without a
+        // direction, like `logic [7:0] a`, `sequence s` or `untyped x`
+        let checker_port = choice((
+            type_keyword.clone(),
+            keyword("sequence").to("sequence".to_string()),
+            keyword("property").to("property".to_string()),
+            keyword("event").to("event".to_string()),
+            keyword("untyped").to("untyped".to_string()),
+        ))
+        .then(range.or_not())
+        .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+        .map_with_span(|((data_type, range), (name, name_span)), span| Port {
+            name,
+            name_span,
+            direction: None,
+            data_type: Some(data_type),
+            range,
+            interface: None,
+            modport: None,
+            span: (span.start, span.end),
+        })
+        .or(port.clone());
+        let checker_port_list = checker_port
+            .separated_by(just(","))
+            .allow_trailing()
+            .delimited_by(just("("), just(")"));
+
+        // Sequence or property declaration:
+        // property name(ports); @(posedge clk) body; endproperty : name
+        let assertion_declaration_kind = choice((
+            keyword("sequence").to(AssertionDeclarationKind::Sequence),
+            keyword("property").to(AssertionDeclarationKind::Property),
+        ));
+        let assertion_decl = assertion_declaration_kind
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(checker_port_list.clone().or_not())
+            .then_ignore(just(";"))
+            .then(clocking_event.or_not())
+            .then(disable_iff.clone().or_not())
+            .then(property_expr.clone())
+            .then_ignore(just(";").or_not())
+            .then(
+                choice((
+                    keyword("endsequence").to(AssertionDeclarationKind::Sequence),
+                    keyword("endproperty").to(AssertionDeclarationKind::Property),
+                ))
+                .then(
+                    just(":")
+                        .ignore_then(identifier.map_with_span(|name, span| Label {
+                            name,
+                            span: (span.start, span.end),
+                        }))
+                        .or_not(),
+                ),
+            )
+            .try_map(
+                |(
+                    (((((kind, (name, name_span)), ports), clocking_event), disable_iff), body),
+                    (end_kind, end_label),
+                ),
+                 span: std::ops::Range<usize>| {
+                    if end_kind != kind {
+                        return Err(Simple::custom(
+                            span,
+                            format!(
+                                "{} '{}' must end with 'end{}'",
+                                kind.keyword(),
+                                name,
+                                kind.keyword()
+                            ),
+                        ));
+                    }
+                    if kind == AssertionDeclarationKind::Sequence && disable_iff.is_some() {
+                        return Err(Simple::custom(
+                            span,
+                            format!("sequence '{}' cannot have a 'disable iff'", name),
+                        ));
+                    }
+                    Ok(ParsedModuleItem::AssertionDeclaration {
+                        kind,
+                        name,
+                        name_span,
+                        ports: ports.unwrap_or_default(),
+                        clocking_event,
+                        disable_iff,
+                        body,
+                        end_label,
+                        span: (span.start, span.end),
+                    })
+                },
+            );
+
         // Port list: (input a, input b) or ()
         let port_list = port
-            .separated_by(just(',').padded_by(ws.clone()))
+            .separated_by(just(","))
             .allow_trailing()
-            .delimited_by(
-                just('(').padded_by(ws.clone()),
-                just(')').padded_by(ws.clone()),
-            );
+            .delimited_by(just("("), just(")"));
 
         // Statement parser (for inside initial/always blocks)
-        let statement = recursive(|_statement| {
-            // Assignment operators - order matters! Longest first
-            let assign_op = choice((
-                just(">>>=").to(AssignmentOp::AShrAssign),
-                just("<<<=").to(AssignmentOp::AShlAssign),
-                just(">>=").to(AssignmentOp::ShrAssign),
-                just("<<=").to(AssignmentOp::ShlAssign),
-                just("^=").to(AssignmentOp::XorAssign),
-                just("+=").to(AssignmentOp::AddAssign),
-                just("-=").to(AssignmentOp::SubAssign),
-                just("*=").to(AssignmentOp::MulAssign),
-                just("/=").to(AssignmentOp::DivAssign),
-                just("%=").to(AssignmentOp::ModAssign),
-                just("&=").to(AssignmentOp::AndAssign),
-                just("|=").to(AssignmentOp::OrAssign),
-                just("=").to(AssignmentOp::Assign),
-            ));
+        // Block name after begin/end: `begin : name`, `end : name`
+        let block_name = just(":").ignore_then(identifier.map_with_span(|name, span| Label {
+            name,
+            span: (span.start, span.end),
+        }));
 
-            // Statement-level assignment: a ^= b;
-            let stmt_assignment = ws
+        // Assignment operators
+        let assign_op = choice((
+            just(">>>=").to(AssignmentOp::AShrAssign),
+            just("<<<=").to(AssignmentOp::AShlAssign),
+            just(">>=").to(AssignmentOp::ShrAssign),
+            just("<<=").to(AssignmentOp::ShlAssign),
+            just("^=").to(AssignmentOp::XorAssign),
+            just("+=").to(AssignmentOp::AddAssign),
+            just("-=").to(AssignmentOp::SubAssign),
+            just("*=").to(AssignmentOp::MulAssign),
+            just("/=").to(AssignmentOp::DivAssign),
+            just("%=").to(AssignmentOp::ModAssign),
+            just("&=").to(AssignmentOp::AndAssign),
+            just("|=").to(AssignmentOp::OrAssign),
+            just("=").to(AssignmentOp::Assign),
+        ));
+
+        let statement = recursive(|statement| {
+            // Statement-level assignment: a ^= b; a = #5 b;
+            let stmt_assignment = expr
                 .clone()
-                .ignore_then(expr.clone())
-                .then_ignore(ws.clone())
-                .then(assign_op)
-                .then_ignore(ws.clone())
+                .then(assign_op.clone())
+                .then(delay.or_not())
                 .then(expr.clone())
-                .then_ignore(ws.clone())
-                .then_ignore(just(';'))
-                .map(|((target, op), expr)| ParsedStatement::Assignment { target, op, expr });
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |(((target, op), delay), expr), span| ParsedStatement::Assignment {
+                        target,
+                        op,
+                        delay,
+                        expr,
+                        span: (span.start, span.end),
+                    },
+                );
+
+            // Nonblocking assignment: q <= d; q <= #2 d;
+            // The target is a variable rather than an expression, so its `<=`
+            // is never read as less-than-or-equal
+            let variable_lvalue = identifier
+                .map_with_span(|name, span: std::ops::Range<usize>| {
+                    ParsedExpression::Identifier(name, (span.start, span.end))
+                })
+                .then(
+                    just(".")
+                        .ignore_then(identifier.map_with_span(
+                            |member, span: std::ops::Range<usize>| (member, (span.start, span.end)),
+                        ))
+                        .repeated(),
+                )
+                .foldl(
+                    |object, (member, member_span)| ParsedExpression::MemberAccess {
+                        object: Box::new(object),
+                        member,
+                        member_span,
+                        span: (0, 0),
+                    },
+                );
+            let nonblocking_assignment = variable_lvalue
+                .then_ignore(just("<="))
+                .then(delay.or_not())
+                .then(expr.clone())
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |((target, delay), expr), span| ParsedStatement::Assignment {
+                        target,
+                        op: AssignmentOp::NonBlocking,
+                        delay,
+                        expr,
+                        span: (span.start, span.end),
+                    },
+                );
 
             // System call: $display(...);
-            let system_call = ws
-                .clone()
-                .ignore_then(just('$'))
-                .ignore_then(identifier.clone())
+            let system_call = system_name
                 .then(
                     expr.clone()
-                        .separated_by(just(',').padded_by(ws.clone()))
-                        .delimited_by(
-                            just('(').padded_by(ws.clone()),
-                            just(')').padded_by(ws.clone()),
-                        )
+                        .separated_by(just(","))
+                        .delimited_by(just("("), just(")"))
                         .or_not()
                         .map(|args| args.unwrap_or_default()),
                 )
-                .then_ignore(just(';').padded_by(ws.clone()))
+                .then_ignore(just(";"))
                 .map_with_span(|(name, args), span| ParsedStatement::SystemCall {
                     name,
                     args,
                     span: (span.start, span.end),
                 });
 
-            // Case statement modifiers
+            // Case and conditional statement modifiers
             let case_modifier = choice((
-                text::keyword("unique0").to("unique0".to_string()),
-                text::keyword("unique").to("unique".to_string()),
-                text::keyword("priority").to("priority".to_string()),
+                keyword("unique0").to("unique0".to_string()),
+                keyword("unique").to("unique".to_string()),
+                keyword("priority").to("priority".to_string()),
             ))
-            .padded_by(ws.clone())
             .or_not();
 
+            // Conditional statement: [unique|unique0|priority] if (cond) stmt [else stmt]
+            let if_stmt = case_modifier
+                .clone()
+                .then_ignore(keyword("if"))
+                .then(expr.clone().delimited_by(just("("), just(")")))
+                .then(statement.clone())
+                .then(keyword("else").ignore_then(statement.clone()).or_not())
+                .map_with_span(
+                    |(((modifier, condition), then_statement), else_statement), span| {
+                        ParsedStatement::Conditional {
+                            modifier,
+                            condition,
+                            then_statement: Box::new(then_statement),
+                            else_statement: else_statement.map(Box::new),
+                            span: (span.start, span.end),
+                        }
+                    },
+                );
+
             // Case type
             let case_type = choice((
-                text::keyword("casez").to("casez".to_string()),
-                text::keyword("casex").to("casex".to_string()),
-                text::keyword("case").to("case".to_string()),
-            ))
-            .padded_by(ws.clone());
+                keyword("casez").to("casez".to_string()),
+                keyword("casex").to("casex".to_string()),
+                keyword("case").to("case".to_string()),
+            ));
+
+            // Value range of a `case ... inside` item: [lo:hi]
+            let value_range = expr
+                .clone()
+                .then_ignore(just(":"))
+                .then(expr.clone())
+                .delimited_by(just("["), just("]"))
+                .map_with_span(|(low, high), span| ParsedExpression::ValueRange {
+                    low: Box::new(low),
+                    high: Box::new(high),
+                    span: (span.start, span.end),
+                });
+
+            // Case item: value, value: statement or default[:] statement
+            let case_item = keyword("default")
+                .then(just(":").or_not())
+                .to(Vec::new())
+                .or(value_range
+                    .or(expr.clone())
+                    .separated_by(just(","))
+                    .at_least(1)
+                    .then_ignore(just(":")))
+                .then(statement.clone())
+                .map_with_span(|(values, statement), span| ParsedCaseItem {
+                    values,
+                    statement,
+                    span: (span.start, span.end),
+                });
 
-            // Case statement (simplified - just skip to endcase)
+            // Case statement: case (expr) [inside] item ... endcase
             let case_stmt = case_modifier
-                .then(case_type)
-                .then(expr.clone().delimited_by(
-                    just('(').padded_by(ws.clone()),
-                    just(')').padded_by(ws.clone()),
-                ))
-                .then_ignore(
-                    filter(|c| *c != 'e')
-                        .repeated()
-                        .then(text::keyword("endcase"))
-                        .padded_by(ws.clone()),
-                )
-                .map(
-                    |((modifier, case_type), case_expr)| ParsedStatement::CaseStatement {
-                        modifier,
-                        case_type,
-                        expr: case_expr,
+                .clone()
+                .then(case_type.clone())
+                .then(expr.clone().delimited_by(just("("), just(")")))
+                .then(keyword("inside").or_not())
+                .then(case_item.repeated())
+                .then_ignore(keyword("endcase"))
+                .try_map(
+                    |((((modifier, case_type), case_expr), inside), items),
+                     span: std::ops::Range<usize>| {
+                        // Only the items of a `case ... inside` hold value ranges
+                        let range = items
+                            .iter()
+                            .flat_map(|item: &ParsedCaseItem| &item.values)
+                            .find_map(|value| match value {
+                                ParsedExpression::ValueRange { span, .. } => Some(*span),
+                                _ => None,
+                            });
+                        if let (None, Some((start, end))) = (&inside, range) {
+                            return Err(Simple::custom(
+                                start..end,
+                                "Value ranges are only allowed in a 'case ... inside'",
+                            ));
+                        }
+                        Ok(ParsedStatement::CaseStatement {
+                            modifier,
+                            case_type,
+                            inside: inside.is_some(),
+                            expr: case_expr,
+                            items,
+                            span: (span.start, span.end),
+                        })
                     },
                 );
 
-            // Assert property statement
-            let assert_property = text::keyword("assert")
-                .padded_by(ws.clone())
-                .ignore_then(text::keyword("property").padded_by(ws.clone()))
-                .ignore_then(expr.clone().delimited_by(
-                    just('(').padded_by(ws.clone()),
-                    just(')').padded_by(ws.clone()),
+            // Pattern for case ... matches: .name, .*, tagged Member [pattern], constant
+            let pattern = recursive(|pattern| {
+                choice((
+                    just(".*").map_with_span(|_, span: std::ops::Range<usize>| {
+                        ParsedPattern::Wildcard((span.start, span.end))
+                    }),
+                    just(".").ignore_then(identifier).map_with_span(
+                        |name, span: std::ops::Range<usize>| {
+                            ParsedPattern::Variable(name, (span.start, span.end))
+                        },
+                    ),
+                    keyword("tagged")
+                        .ignore_then(identifier.map_with_span(
+                            |name, span: std::ops::Range<usize>| (name, (span.start, span.end)),
+                        ))
+                        .then(pattern.or_not())
+                        .map_with_span(|((member, member_span), pattern), span| {
+                            ParsedPattern::Tagged {
+                                member,
+                                member_span,
+                                pattern: pattern.map(Box::new),
+                                span: (span.start, span.end),
+                            }
+                        })
+                        .boxed(),
+                    expr.clone().map(ParsedPattern::Constant),
                 ))
-                .then(
-                    text::keyword("else")
-                        .padded_by(ws.clone())
-                        .ignore_then(
-                            just('$')
-                                .ignore_then(identifier.clone())
-                                .then(
-                                    expr.clone()
-                                        .separated_by(just(',').padded_by(ws.clone()))
-                                        .delimited_by(just('('), just(')'))
-                                        .or_not()
-                                        .map(|args| args.unwrap_or_default()),
-                                )
-                                .map_with_span(|(name, args), span| ParsedStatement::SystemCall {
-                                    name,
-                                    args,
-                                    span: (span.start, span.end),
-                                }),
-                        )
-                        .or_not(),
-                )
-                .then_ignore(just(';').padded_by(ws.clone()))
-                .map(
-                    |(property_expr, action_block)| ParsedStatement::AssertProperty {
-                        property_expr,
-                        action_block: action_block.map(Box::new),
+            });
+
+            // case (expr) matches pattern &&& guard: statement ... endcase
+            let case_matches_item = keyword("default")
+                .to(None)
+                .or(pattern.map(Some))
+                .then(just("&&&").ignore_then(expr.clone()).or_not())
+                .then_ignore(just(":"))
+                .then(statement.clone())
+                .map_with_span(
+                    |((pattern, guard), statement), span| ParsedCaseMatchesItem {
+                        pattern,
+                        guard,
+                        statement,
+                        span: (span.start, span.end),
                     },
                 );
 
-            // Variable declaration statement: logic a = $tan(1);
-            let var_decl_stmt = choice((
-                text::keyword("logic").to("logic".to_string()),
-                text::keyword("bit").to("bit".to_string()),
-                text::keyword("int").to("int".to_string()),
-                text::keyword("byte").to("byte".to_string()),
-                text::keyword("reg").to("reg".to_string()),
-                text::keyword("integer").to("integer".to_string()),
-                text::keyword("time").to("time".to_string()),
-                text::keyword("shortint").to("shortint".to_string()),
-                text::keyword("longint").to("longint".to_string()),
-                text::keyword("real").to("real".to_string()),
-                text::keyword("realtime").to("realtime".to_string()),
-            ))
-            .padded_by(ws.clone())
-            .then(
-                identifier
+            let case_matches_stmt = case_modifier
+                .clone()
+                .then(case_type.clone())
+                .then(expr.clone().delimited_by(just("("), just(")")))
+                .then_ignore(keyword("matches"))
+                .then(case_matches_item.repeated())
+                .then_ignore(keyword("endcase"))
+                .map_with_span(|(((modifier, case_type), case_expr), items), span| {
+                    ParsedStatement::CaseMatches {
+                        modifier,
+                        case_type,
+                        expr: case_expr,
+                        items,
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // Assert, assume, cover or restrict property statement with its
+            // action block: `;`, `else fail`, or `pass [else fail]`
+            let else_action = keyword("else").ignore_then(statement.clone());
+            let action_block = choice((
+                else_action.clone().map(|fail| (None, Some(fail))),
+                just(";")
+                    .ignore_then(else_action.clone().or_not())
+                    .map(|fail| (None, fail)),
+                statement
                     .clone()
-                    .map_with_span(|name, span| (name, (span.start, span.end))),
-            )
-            .then(
-                just('=')
-                    .padded_by(ws.clone())
-                    .ignore_then(expr.clone())
-                    .or_not(),
-            )
-            .then_ignore(just(';').padded_by(ws.clone()))
-            .map_with_span(|((data_type, (name, name_span)), initial_value), span| {
-                ParsedStatement::VariableDeclaration {
-                    data_type,
-                    name,
-                    name_span,
-                    initial_value,
+                    .then(else_action.or_not())
+                    .map(|(pass, fail)| (Some(pass), fail)),
+            ));
+            let assert_property = assertion.clone().then(action_block.clone()).map_with_span(
+                |((((kind, clocking_event), disable_iff), property), (pass, fail)), span| {
+                    ParsedStatement::AssertProperty {
+                        kind,
+                        clocking_event,
+                        disable_iff,
+                        property,
+                        pass_action: pass.map(Box::new),
+                        fail_action: fail.map(Box::new),
+                        span: (span.start, span.end),
+                    }
+                },
+            );
+
+            // Immediate assertion: assert (cond) pass; else fail;, deferred
+            // with `#0` or `final` after the keyword
+            let deferral = choice((
+                just("#").then(just("0")).to(AssertionDeferral::Observed),
+                keyword("final").to(AssertionDeferral::Final),
+            ));
+            let immediate_assertion = choice((
+                keyword("assert").to(AssertionKind::Assert),
+                keyword("assume").to(AssertionKind::Assume),
+                keyword("cover").to(AssertionKind::Cover),
+            ))
+            .then(deferral.or_not())
+            .then(expr.clone().delimited_by(just("("), just(")")))
+            .then(action_block)
+            .map_with_span(|(((kind, deferral), condition), (pass, fail)), span| {
+                ParsedStatement::ImmediateAssertion {
+                    kind,
+                    deferral,
+                    condition,
+                    pass_action: pass.map(Box::new),
+                    fail_action: fail.map(Box::new),
                     span: (span.start, span.end),
                 }
             });
 
+            // Type of a variable declared in a procedure
+            let var_type = choice((
+                keyword("logic").to("logic".to_string()),
+                keyword("bit").to("bit".to_string()),
+                keyword("int").to("int".to_string()),
+                keyword("byte").to("byte".to_string()),
+                keyword("reg").to("reg".to_string()),
+                keyword("integer").to("integer".to_string()),
+                keyword("time").to("time".to_string()),
+                keyword("shortint").to("shortint".to_string()),
+                keyword("longint").to("longint".to_string()),
+                keyword("real").to("real".to_string()),
+                keyword("realtime").to("realtime".to_string()),
+                keyword("process").to("process".to_string()),
+                keyword("event").to("event".to_string()),
+            ));
+
+            // Variable declaration statement: logic a = $tan(1); or int i = 0, j = 1;
+            let var_decl_stmt = var_type
+                .clone()
+                .then(
+                    identifier
+                        .map_with_span(|name, span| (name, (span.start, span.end)))
+                        .then(just("=").ignore_then(expr.clone()).or_not())
+                        .separated_by(just(","))
+                        .at_least(1),
+                )
+                .then_ignore(just(";"))
+                .map_with_span(|(data_type, variables), span| {
+                    let span = (span.start, span.end);
+                    let mut declarations: Vec<ParsedStatement> = variables
+                        .into_iter()
+                        .map(|((name, name_span), initial_value)| {
+                            ParsedStatement::VariableDeclaration {
+                                data_type: data_type.clone(),
+                                name,
+                                name_span,
+                                initial_value,
+                                span,
+                            }
+                        })
+                        .collect();
+                    if declarations.len() == 1 {
+                        declarations.remove(0)
+                    } else {
+                        ParsedStatement::Declarations { declarations, span }
+                    }
+                });
+
             // Expression statement (for function calls)
             let expr_stmt = expr
                 .clone()
-                .then_ignore(just(';').padded_by(ws.clone()))
-                .map(|expr| ParsedStatement::ExpressionStatement { expr });
+                .then_ignore(just(";"))
+                .map_with_span(|expr, span| ParsedStatement::ExpressionStatement {
+                    expr,
+                    span: (span.start, span.end),
+                });
+
+            // Sequential block: begin : name ... end : name
+            let block_stmt = keyword("begin")
+                .ignore_then(block_name.or_not())
+                .then(statement.clone().repeated())
+                .then_ignore(keyword("end"))
+                .then(block_name.or_not())
+                .map_with_span(
+                    |((label, statements), end_label), span| ParsedStatement::Block {
+                        label,
+                        statements,
+                        end_label,
+                        span: (span.start, span.end),
+                    },
+                );
+
+            // Parallel block: fork : name ... join_any : name
+            let join_keyword = choice((
+                keyword("join_any").to(JoinKind::JoinAny),
+                keyword("join_none").to(JoinKind::JoinNone),
+                keyword("join").to(JoinKind::Join),
+            ));
+            let fork_stmt = keyword("fork")
+                .ignore_then(block_name.or_not())
+                .then(statement.clone().repeated())
+                .then(join_keyword)
+                .then(block_name.or_not())
+                .map_with_span(|(((label, statements), join), end_label), span| {
+                    ParsedStatement::Fork {
+                        label,
+                        statements,
+                        join,
+                        end_label,
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // Labeled statement: name: statement (but not a `pkg::` scope)
+            let labeled_stmt = identifier
+                .map_with_span(|name, span| Label {
+                    name,
+                    span: (span.start, span.end),
+                })
+                .then_ignore(just(":"))
+                .then_ignore(just(":").not().rewind())
+                .then(statement.clone())
+                .map_with_span(|(label, statement), span| ParsedStatement::Labeled {
+                    label,
+                    statement: Box::new(statement),
+                    span: (span.start, span.end),
+                });
+
+            // Event trigger: -> done; or ->> done;
+            let event_trigger = just("->")
+                .then(just(">"))
+                .to(true)
+                .or(just("->").to(false))
+                .then(expr.clone())
+                .then_ignore(just(";"))
+                .map_with_span(|(nonblocking, event), span| ParsedStatement::EventTrigger {
+                    nonblocking,
+                    event,
+                    span: (span.start, span.end),
+                });
+
+            // Process control: wait fork; disable fork; disable name;
+            let wait_fork = keyword("wait")
+                .ignore_then(keyword("fork"))
+                .then_ignore(just(";"))
+                .map_with_span(|_, span| ParsedStatement::WaitFork {
+                    span: (span.start, span.end),
+                });
+
+            let disable_stmt = keyword("disable")
+                .ignore_then(keyword("fork").to(None).or(identifier.map_with_span(
+                    |name, span: std::ops::Range<usize>| Some((name, (span.start, span.end))),
+                )))
+                .then_ignore(just(";"))
+                .map_with_span(|target, span| match target {
+                    None => ParsedStatement::DisableFork {
+                        span: (span.start, span.end),
+                    },
+                    Some((target, target_span)) => ParsedStatement::Disable {
+                        target,
+                        target_span,
+                        span: (span.start, span.end),
+                    },
+                });
+
+            // return; or return value;
+            let return_stmt = keyword("return")
+                .ignore_then(expr.clone().or_not())
+                .then_ignore(just(";"))
+                .map_with_span(|value, span| ParsedStatement::Return {
+                    value,
+                    span: (span.start, span.end),
+                });
+
+            // A parenthesized loop condition or count
+            let loop_expr = expr.clone().delimited_by(just("("), just(")"));
+
+            // for (int i = 0, j = 0; i < n; i++, j += 2) body; the variables
+            // after the first share its type
+            let for_init = var_type
+                .clone()
+                .then(
+                    identifier
+                        .map_with_span(|name, span| (name, (span.start, span.end)))
+                        .then_ignore(just("="))
+                        .then(expr.clone())
+                        .separated_by(just(","))
+                        .at_least(1),
+                )
+                .map_with_span(|(data_type, variables), span| {
+                    let span = (span.start, span.end);
+                    variables
+                        .into_iter()
+                        .map(|((name, name_span), initial_value)| {
+                            ParsedStatement::VariableDeclaration {
+                                data_type: data_type.clone(),
+                                name,
+                                name_span,
+                                initial_value: Some(initial_value),
+                                span,
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                });
+            let for_assignment = expr
+                .clone()
+                .then(assign_op.clone())
+                .then(expr.clone())
+                .map_with_span(|((target, op), expr), span| ParsedStatement::Assignment {
+                    target,
+                    op,
+                    delay: None,
+                    expr,
+                    span: (span.start, span.end),
+                });
+            let for_step = for_assignment
+                .clone()
+                .or(expr.clone().map_with_span(|expr, span| {
+                    ParsedStatement::ExpressionStatement {
+                        expr,
+                        span: (span.start, span.end),
+                    }
+                }));
+            let for_stmt = keyword("for")
+                .ignore_then(just("("))
+                .ignore_then(
+                    for_init
+                        .or(for_assignment.map(|assignment| vec![assignment]))
+                        .separated_by(just(","))
+                        .flatten(),
+                )
+                .then_ignore(just(";"))
+                .then(expr.clone().or_not())
+                .then_ignore(just(";"))
+                .then(for_step.separated_by(just(",")))
+                .then_ignore(just(")"))
+                .then(statement.clone())
+                .map_with_span(|(((init, condition), step), body), span| {
+                    ParsedStatement::ForLoop {
+                        init,
+                        condition,
+                        step,
+                        body: Box::new(body),
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // while (cond) body
+            let while_stmt = keyword("while")
+                .ignore_then(loop_expr.clone())
+                .then(statement.clone())
+                .map_with_span(|(condition, body), span| ParsedStatement::While {
+                    condition,
+                    body: Box::new(body),
+                    span: (span.start, span.end),
+                });
+
+            // do body while (cond);
+            let do_while_stmt = keyword("do")
+                .ignore_then(statement.clone())
+                .then_ignore(keyword("while"))
+                .then(loop_expr.clone())
+                .then_ignore(just(";"))
+                .map_with_span(|(body, condition), span| ParsedStatement::DoWhile {
+                    body: Box::new(body),
+                    condition,
+                    span: (span.start, span.end),
+                });
+
+            // repeat (count) body
+            let repeat_stmt = keyword("repeat")
+                .ignore_then(loop_expr.clone())
+                .then(statement.clone())
+                .map_with_span(|(count, body), span| ParsedStatement::Repeat {
+                    count,
+                    body: Box::new(body),
+                    span: (span.start, span.end),
+                });
+
+            // #10 a = b; @(posedge clk) q <= d; wait (ready); - wait, then run
+            // the statement, if any
+            let timing_control = choice((
+                delay.map(ParsedTimingControl::Delay),
+                event_control.clone().map(ParsedTimingControl::Event),
+                keyword("wait")
+                    .ignore_then(loop_expr)
+                    .map(ParsedTimingControl::Wait),
+            ));
+            let timed_stmt = timing_control
+                .then(
+                    just(";")
+                        .to(None)
+                        .or(statement.clone().map(|statement| Some(Box::new(statement)))),
+                )
+                .map_with_span(|(control, statement), span| ParsedStatement::Timed {
+                    control,
+                    statement,
+                    span: (span.start, span.end),
+                });
+
+            // forever body
+            let forever_stmt = keyword("forever")
+                .ignore_then(statement.clone())
+                .map_with_span(|body, span| ParsedStatement::Forever {
+                    body: Box::new(body),
+                    span: (span.start, span.end),
+                });
+
+            // foreach (array[i, j]) body; a dimension's variable may be left out
+            let loop_variable = identifier
+                .map_with_span(|name, span: std::ops::Range<usize>| LoopVariable {
+                    name,
+                    span: (span.start, span.end),
+                })
+                .or_not();
+            let foreach_stmt = keyword("foreach")
+                .ignore_then(just("("))
+                .ignore_then(
+                    identifier
+                        .separated_by(just("."))
+                        .at_least(1)
+                        .map_with_span(|path, span: std::ops::Range<usize>| {
+                            (path.join("."), (span.start, span.end))
+                        }),
+                )
+                .then(
+                    loop_variable
+                        .separated_by(just(","))
+                        .delimited_by(just("["), just("]")),
+                )
+                .then_ignore(just(")"))
+                .then(statement.clone())
+                .map_with_span(|(((array, array_span), indices), body), span| {
+                    ParsedStatement::Foreach {
+                        array,
+                        array_span,
+                        indices,
+                        body: Box::new(body),
+                        span: (span.start, span.end),
+                    }
+                });
 
             choice((
+                block_stmt,
+                fork_stmt,
+                wait_fork,
+                disable_stmt,
+                event_trigger,
+                return_stmt,
+                for_stmt,
+                foreach_stmt,
+                forever_stmt,
+                timed_stmt,
+                while_stmt,
+                do_while_stmt,
+                repeat_stmt,
+                labeled_stmt,
                 assert_property,
+                immediate_assertion,
+                if_stmt,
+                case_matches_stmt,
                 case_stmt,
                 system_call,
                 var_decl_stmt,
+                nonblocking_assignment,
                 stmt_assignment,
                 expr_stmt,
             ))
         });
 
+        // Concurrent assertion module item, with the action block of the
+        // statement form
+        let else_action = keyword("else").ignore_then(statement.clone());
+        let concurrent_assertion = assertion
+            .then(choice((
+                else_action.clone().map(|fail| (None, Some(fail))),
+                just(";")
+                    .ignore_then(else_action.clone().or_not())
+                    .map(|fail| (None, fail)),
+                statement
+                    .clone()
+                    .then(else_action.or_not())
+                    .map(|(pass, fail)| (Some(pass), fail)),
+            )))
+            .map_with_span(
+                |(
+                    (((kind, clocking_event), disable_iff), property),
+                    (pass_action, fail_action),
+                ),
+                 span| ParsedModuleItem::ConcurrentAssertion {
+                    kind,
+                    clocking_event,
+                    disable_iff,
+                    property,
+                    pass_action: pass_action.map(Box::new),
+                    fail_action: fail_action.map(Box::new),
+                    span: (span.start, span.end),
+                },
+            );
+
         // Unpacked dimension: [10] or []
-        let unpacked_dim = just('[')
-            .padded_by(ws.clone())
-            .ignore_then(choice((number.clone(), identifier.clone())).or_not())
-            .then_ignore(ws.clone())
-            .then_ignore(just(']'))
+        let unpacked_dim = just("[")
+            .ignore_then(
+                choice((number, identifier))
+                    .then(just(":").ignore_then(choice((number, identifier))).or_not())
+                    .or_not(),
+            )
+            .then_ignore(just("]"))
             .map(|dim| match dim {
                 None => UnpackedDimension::Dynamic,
-                Some(size) => UnpackedDimension::FixedSize(size),
+                Some((size, None)) => UnpackedDimension::FixedSize(size),
+                Some((msb, Some(lsb))) => UnpackedDimension::Range(msb, lsb),
+            });
+
+        // Signing keyword
+        let signing = choice((
+            keyword("signed").to("signed"),
+            keyword("unsigned").to("unsigned"),
+        ));
+
+        // Union/struct type; a union may be `tagged`. Yields None for a struct
+        // and whether it is tagged for a union
+        let union_struct_type = choice((
+            keyword("union")
+                .ignore_then(keyword("tagged").or_not())
+                .map(|tagged| Some(tagged.is_some())),
+            keyword("struct").to(None),
+        ))
+        .then(keyword("packed").or_not().map(|packed| packed.is_some()))
+        .then_ignore(just("{"))
+        .then(
+            // Parse struct/union members: type name;
+            type_keyword
+                .clone()
+                .or(identifier)
+                .map(DataType::Named)
+                .then(range.or_not())
+                .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |((data_type, range), (name, name_span)), span| StructMember {
+                        data_type,
+                        range,
+                        name,
+                        name_span,
+                        span: (span.start, span.end),
+                    },
+                )
+                .repeated()
+                .at_least(1),
+        )
+        .then_ignore(just("}"))
+        .map(|((union_tagged, packed), members)| match union_tagged {
+            Some(tagged) => ParsedDataType::Union {
+                tagged,
+                packed,
+                members,
+            },
+            None => ParsedDataType::Struct { packed, members },
+        });
+
+        // Enum type: enum logic [1:0] { IDLE, BUSY = 2 }
+        let enum_member = identifier
+            .map_with_span(|n, s| (n, (s.start, s.end)))
+            .then(just("=").ignore_then(expr.clone()).or_not())
+            .map(|((name, name_span), value)| (name, name_span, value));
+        let enum_type = keyword("enum")
+            .ignore_then(type_keyword.clone().or_not())
+            .then(signing.or_not())
+            .then(range.or_not())
+            .then(
+                enum_member
+                    .separated_by(just(","))
+                    .at_least(1)
+                    .delimited_by(just("{"), just("}")),
+            )
+            .map(|(((base_type, signing), range), members)| ParsedEnumType {
+                base_type,
+                signing: signing.map(|s| s.to_string()),
+                range,
+                members,
+            });
+
+        // Data type of a typedef or variable declaration that may declare
+        // struct, union or enum members inline
+        let inline_type = choice((union_struct_type, enum_type.map(ParsedDataType::Enum)));
+
+        // Data type written with a type keyword, as text: `bit signed [7:0]`
+        let keyword_type = type_keyword
+            .clone()
+            .then(signing.or_not())
+            .then(range.or_not())
+            .map(|((data_type, signing), range)| {
+                let mut text = data_type;
+                if let Some(signing) = signing {
+                    text.push(' ');
+                    text.push_str(signing);
+                }
+                if let Some(range) = range {
+                    text.push_str(&format!(" [{}:{}]", range.msb, range.lsb));
+                }
+                text
+            });
+
+        // Value of a class parameter: a data type or an expression
+        let parameter_argument = choice((
+            keyword_type.clone().map(ParsedParameterArgument::Type),
+            expr.clone().map(ParsedParameterArgument::Value),
+        ));
+
+        // Specialization of a parameterized class: fifo#(bit [7:0], 16)
+        let specialized_type = identifier
+            .map_with_span(|name, span| (name, (span.start, span.end)))
+            .then_ignore(just("#"))
+            .then(
+                parameter_argument
+                    .clone()
+                    .separated_by(just(","))
+                    .delimited_by(just("("), just(")")),
+            )
+            .map(|((name, name_span), arguments)| {
+                ParsedDataType::Specialized(name, name_span, arguments)
             });
 
+        // Parameter port of a class: `type T = int`, `int DEPTH = 8` or `parameter W`
+        let class_parameter = choice((
+            keyword("parameter").ignored(),
+            keyword("localparam").ignored(),
+        ))
+        .or_not()
+        .ignore_then(choice((
+            keyword("type")
+                .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                .then(just("=").ignore_then(parameter_argument.clone()).or_not())
+                .map(|(name, default)| (true, None, name, default)),
+            choice((
+                keyword_type
+                    .clone()
+                    .map(Some)
+                    .then(identifier.map_with_span(|n, s| (n, (s.start, s.end)))),
+                // A named type needs a second name after it: `addr_t BASE`
+                identifier
+                    .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                    .map(|(data_type, name)| (Some(data_type), name)),
+                identifier
+                    .map_with_span(|n, s| (n, (s.start, s.end)))
+                    .map(|name| (None, name)),
+            ))
+            .then(
+                just("=")
+                    .ignore_then(expr.clone().map(ParsedParameterArgument::Value))
+                    .or_not(),
+            )
+            .map(|((data_type, name), default)| (false, data_type, name, default)),
+        )))
+        .map_with_span(|(is_type, data_type, (name, name_span), default), span| {
+            ParsedClassParameter {
+                is_type,
+                data_type,
+                name,
+                name_span,
+                default,
+                span: (span.start, span.end),
+            }
+        });
+
+        // Type definition: typedef logic [7:0] byte_t; or typedef enum { A, B } state_t;
+        let typedef_decl = keyword("typedef")
+            .ignore_then(choice((
+                inline_type.clone(),
+                specialized_type.clone(),
+                choice((
+                    type_keyword.clone(),
+                    identifier
+                        .then_ignore(just("::"))
+                        .then(identifier)
+                        .map(|(package, name)| format!("{}::{}", package, name)),
+                    identifier,
+                ))
+                .map(ParsedDataType::Named),
+            )))
+            .then(signing.or_not())
+            .then(range.or_not())
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(unpacked_dim.repeated())
+            .then_ignore(just(";"))
+            .map_with_span(
+                |((((data_type, signing), range), (name, name_span)), unpacked_dimensions),
+                 span| {
+                    ParsedModuleItem::TypedefDeclaration {
+                        data_type,
+                        signing: signing.map(|s| s.to_string()),
+                        range,
+                        name,
+                        name_span,
+                        unpacked_dimensions,
+                        span: (span.start, span.end),
+                    }
+                },
+            );
+
         // Class qualifier
         let class_qualifier = choice((
-            text::keyword("local").to(ClassQualifier::Local),
-            text::keyword("protected").to(ClassQualifier::Protected),
+            keyword("local").to(ClassQualifier::Local),
+            keyword("protected").to(ClassQualifier::Protected),
         ));
 
+        // Method qualifiers in any order: `protected virtual`, `static local`
+        let method_qualifiers = choice((
+            keyword("virtual").to(ParsedClassQualifier::Virtual),
+            keyword("static").to(ParsedClassQualifier::Static),
+            class_qualifier.clone().map(ParsedClassQualifier::Access),
+        ))
+        .repeated()
+        .map(|qualifiers| {
+            (
+                ParsedClassQualifier::access(&qualifiers),
+                qualifiers.contains(&ParsedClassQualifier::Virtual),
+                qualifiers.contains(&ParsedClassQualifier::Static),
+            )
+        });
+
+        // Method argument: `a`, `int a`, `input logic [7:0] a` or `string name = "x"`.
+        // Only the name is kept
+        let method_argument = port_direction
+            .clone()
+            .or_not()
+            .ignore_then(
+                choice((type_keyword.clone(), identifier))
+                    .then_ignore(range.or_not())
+                    .ignore_then(identifier)
+                    .or(identifier),
+            )
+            .then_ignore(unpacked_dim.repeated())
+            .then_ignore(just("=").ignore_then(expr.clone()).or_not());
+        let method_arguments = just("(")
+            .ignore_then(method_argument.separated_by(just(",")))
+            .then_ignore(just(")"));
+
+        // Method name, or new for a constructor
+        let method_name = choice((identifier, keyword("new").to("new".to_string())))
+            .map_with_span(|n, s| (n, (s.start, s.end)));
+
+        // Method header up to its `;`: `function int name(int a)` or `task
+        // run`, whose arguments may be left out. Yields whether it is a task
+        let function_header = keyword("function")
+            .ignore_then(choice((type_keyword.clone(), identifier)).or_not()) // return type (optional)
+            .then(method_name.clone())
+            .then(method_arguments.clone().or_not())
+            .map(|((return_type, name), parameters)| {
+                (false, return_type, name, parameters.unwrap_or_default())
+            });
+        let task_header = keyword("task")
+            .ignore_then(method_name.clone())
+            .then(method_arguments.clone().or_not())
+            .map(|(name, parameters)| (true, None, name, parameters.unwrap_or_default()));
+        let method_header = choice((function_header, task_header));
+
+        // Body of an extern method outside its class: `function void
+        // packet::send(int n); ... endfunction`. The class name could also be
+        // read as the return type, so the form without one is tried second
+        let scoped_method_name = identifier
+            .map_with_span(|n, s| (n, (s.start, s.end)))
+            .then_ignore(just("::"))
+            .then(method_name.clone());
+        let out_of_block_method = keyword("function")
+            .ignore_then(choice((
+                choice((type_keyword.clone(), identifier))
+                    .then(scoped_method_name.clone())
+                    .map(|(return_type, name)| (Some(return_type), name)),
+                scoped_method_name.map(|name| (None, name)),
+            )))
+            .then(method_arguments.clone())
+            .then_ignore(just(";"))
+            .then(statement.clone().repeated())
+            .then_ignore(keyword("endfunction"))
+            .map_with_span(
+                |(
+                    ((return_type, ((class_name, class_name_span), (name, name_span))), parameters),
+                    body,
+                ),
+                 span| ParsedModuleItem::OutOfBlockMethod {
+                    return_type,
+                    class_name,
+                    class_name_span,
+                    name,
+                    name_span,
+                    parameters,
+                    body,
+                    span: (span.start, span.end),
+                },
+            );
+
+        // Function or task outside a class: `function automatic int f(int
+        // a); ... endfunction`. `static` is the default lifetime
+        let lifetime = choice((keyword("automatic").to(true), keyword("static").to(false)))
+            .or_not()
+            .map(|lifetime| lifetime.unwrap_or(false));
+        let subroutine_name = identifier.map_with_span(|n, s| (n, (s.start, s.end)));
+        let subroutine_decl = keyword("function")
+            .ignore_then(lifetime)
+            .then(choice((type_keyword.clone(), identifier)).or_not())
+            .then(subroutine_name)
+            .then(method_arguments.clone().or_not())
+            .then_ignore(just(";"))
+            .then(statement.clone().repeated())
+            .then_ignore(keyword("endfunction"))
+            .map(
+                |((((is_automatic, return_type), name), parameters), body)| {
+                    (false, is_automatic, return_type, name, parameters, body)
+                },
+            )
+            .or(keyword("task")
+                .ignore_then(lifetime)
+                .then(subroutine_name)
+                .then(method_arguments.clone().or_not())
+                .then_ignore(just(";"))
+                .then(statement.clone().repeated())
+                .then_ignore(keyword("endtask"))
+                .map(|(((is_automatic, name), parameters), body)| {
+                    (true, is_automatic, None, name, parameters, body)
+                }))
+            .map_with_span(
+                |(is_task, is_automatic, return_type, (name, name_span), parameters, body),
+                 span| ParsedModuleItem::SubroutineDeclaration {
+                    is_task,
+                    is_automatic,
+                    return_type,
+                    name,
+                    name_span,
+                    parameters: parameters.unwrap_or_default(),
+                    body,
+                    span: (span.start, span.end),
+                },
+            );
+
         // Class item parser
         let class_item = recursive(|_class_item| {
             // Class property
-            let class_property = ws
-                .clone()
-                .ignore_then(class_qualifier.clone().or_not())
-                .then_ignore(ws.clone())
-                .then(choice((type_keyword.clone(), identifier.clone())))
-                .then_ignore(ws.clone())
-                .then(identifier.clone())
-                .then_ignore(ws.clone())
-                .then(unpacked_dim.clone().repeated())
-                .then_ignore(ws.clone())
-                .then(
-                    just('=')
-                        .padded_by(ws.clone())
-                        .ignore_then(expr.clone())
-                        .or_not(),
-                )
-                .then_ignore(ws.clone())
-                .then_ignore(just(';'))
-                .map(
-                    |((((qualifier, data_type), name), unpacked), initial_value)| {
-                        ParsedClassItem::Property {
+            let class_property = choice((
+                keyword("static").to(ParsedClassQualifier::Static),
+                keyword("randc").to(ParsedClassQualifier::Rand(RandQualifier::Randc)),
+                keyword("rand").to(ParsedClassQualifier::Rand(RandQualifier::Rand)),
+                class_qualifier.clone().map(ParsedClassQualifier::Access),
+            ))
+            .repeated()
+            .then(choice((
+                specialized_type.clone(),
+                choice((virtual_interface_type, keyword_type.clone(), identifier))
+                    .map(ParsedDataType::Named),
+            )))
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(unpacked_dim.repeated())
+            .then(just("=").ignore_then(expr.clone()).or_not())
+            .then_ignore(just(";"))
+            .map_with_span(
+                |((((qualifiers, data_type), (name, name_span)), unpacked), initial_value),
+                 span| {
+                    ParsedClassItem::Property {
+                        qualifier: ParsedClassQualifier::access(&qualifiers),
+                        is_static: qualifiers.contains(&ParsedClassQualifier::Static),
+                        rand: ParsedClassQualifier::rand(&qualifiers),
+                        data_type,
+                        name,
+                        name_span,
+                        unpacked_dimensions: unpacked,
+                        initial_value,
+                        span: (span.start, span.end),
+                    }
+                },
+            );
+
+            // Class method, or the prototype of an `extern` method, whose body
+            // follows the class
+            let extern_method = keyword("extern")
+                .ignore_then(method_qualifiers.clone())
+                .then(method_header.clone())
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |(
+                        (qualifier, is_virtual, is_static),
+                        (is_task, return_type, name, parameters),
+                    ),
+                     span| {
+                        ParsedClassItem::Method {
                             qualifier,
-                            data_type,
-                            name,
-                            unpacked_dimensions: unpacked,
-                            initial_value,
+                            is_virtual,
+                            is_pure: false,
+                            is_static,
+                            is_extern: true,
+                            is_task,
+                            return_type,
+                            name: name.0,
+                            name_span: name.1,
+                            parameters,
+                            body: Vec::new(),
+                            span: (span.start, span.end),
                         }
                     },
                 );
-
-            // Class method
-            let class_method = ws
-                .clone()
-                .ignore_then(class_qualifier.clone().or_not())
-                .then_ignore(ws.clone())
-                .then_ignore(text::keyword("function"))
-                .then_ignore(ws.clone())
-                .then(choice((type_keyword.clone(), identifier.clone())).or_not()) // return type (optional)
-                .then_ignore(ws.clone())
-                .then(identifier.clone()) // method name
-                .then_ignore(ws.clone())
-                .then(
-                    // parameter list
-                    just('(')
-                        .padded_by(ws.clone())
-                        .ignore_then(just(')'))
-                        .to(Vec::new())
-                        .or(just('(')
-                            .padded_by(ws.clone())
-                            .ignore_then(
-                                identifier
-                                    .clone()
-                                    .separated_by(just(',').padded_by(ws.clone())),
-                            )
-                            .then_ignore(just(')').padded_by(ws.clone()))),
-                )
-                .then_ignore(just(';').padded_by(ws.clone()))
+            // `pure virtual function int size();`, a prototype without a body
+            let pure_method = keyword("pure")
+                .ignore_then(method_qualifiers.clone())
+                .then(method_header.clone())
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |((qualifier, _, is_static), (is_task, return_type, name, parameters)),
+                     span| {
+                        ParsedClassItem::Method {
+                            qualifier,
+                            is_virtual: true,
+                            is_pure: true,
+                            is_static,
+                            is_extern: false,
+                            is_task,
+                            return_type,
+                            name: name.0,
+                            name_span: name.1,
+                            parameters,
+                            body: Vec::new(),
+                            span: (span.start, span.end),
+                        }
+                    },
+                );
+            let class_method = method_qualifiers
+                .then(method_header.clone())
+                .then_ignore(just(";"))
                 .then(
-                    // function body - statements until endfunction
+                    // method body - statements until endfunction or endtask
                     statement.clone().repeated(),
                 )
-                .then_ignore(ws.clone())
-                .then_ignore(text::keyword("endfunction"))
-                .map(|((((qualifier, return_type), name), parameters), body)| {
-                    ParsedClassItem::Method {
-                        qualifier,
-                        return_type,
+                .then_ignore(choice((keyword("endfunction"), keyword("endtask"))))
+                .map_with_span(
+                    |(
+                        (
+                            (qualifier, is_virtual, is_static),
+                            (is_task, return_type, name, parameters),
+                        ),
+                        body,
+                    ),
+                     span| {
+                        ParsedClassItem::Method {
+                            qualifier,
+                            is_virtual,
+                            is_pure: false,
+                            is_static,
+                            is_extern: false,
+                            is_task,
+                            return_type,
+                            name: name.0,
+                            name_span: name.1,
+                            parameters,
+                            body,
+                            span: (span.start, span.end),
+                        }
+                    },
+                );
+
+            // Constraint block: `constraint c { ... }`, `static constraint c { ... }`
+            let constraint_block = keyword("static")
+                .or_not()
+                .map(|keyword| keyword.is_some())
+                .then_ignore(keyword("constraint"))
+                .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                .then(constraint_set.clone())
+                .map_with_span(|((is_static, (name, name_span)), constraints), span| {
+                    ParsedClassItem::Constraint {
+                        is_static,
                         name,
-                        parameters,
-                        body,
+                        name_span,
+                        constraints,
+                        span: (span.start, span.end),
                     }
                 });
 
-            choice((class_property, class_method))
+            choice((
+                class_property,
+                constraint_block,
+                extern_method,
+                pure_method,
+                class_method,
+            ))
         });
 
-        // Class declaration
-        let class_decl = ws
-            .clone()
-            .ignore_then(text::keyword("class"))
-            .then_ignore(ws.clone())
-            .ignore_then(
-                identifier
-                    .clone()
-                    .map_with_span(|n, s| (n, (s.start, s.end))),
+        // Class declaration, `virtual class` for an abstract one
+        let class_decl = keyword("virtual")
+            .or_not()
+            .map(|keyword| keyword.is_some())
+            .then_ignore(keyword("class"))
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(
+                just("#")
+                    .ignore_then(
+                        class_parameter
+                            .separated_by(just(","))
+                            .delimited_by(just("("), just(")")),
+                    )
+                    .or_not()
+                    .map(|parameters| parameters.unwrap_or_default()),
             )
-            .then_ignore(ws.clone())
+            .then(keyword("extends").ignore_then(identifier).or_not())
             .then(
-                text::keyword("extends")
-                    .ignore_then(ws.clone())
-                    .ignore_then(identifier.clone())
-                    .or_not(),
+                keyword("implements")
+                    .ignore_then(identifier.separated_by(just(",")))
+                    .or_not()
+                    .map(|implements| implements.unwrap_or_default()),
+            )
+            .then_ignore(just(";"))
+            .then(class_item.clone().repeated())
+            .then_ignore(keyword("endclass"))
+            .map_with_span(
+                |(
+                    ((((is_virtual, (name, name_span)), parameters), extends), implements),
+                    items,
+                ),
+                 span| {
+                    ParsedModuleItem::ClassDeclaration {
+                        is_virtual,
+                        name,
+                        name_span,
+                        parameters,
+                        extends,
+                        implements,
+                        items,
+                        span: (span.start, span.end),
+                    }
+                },
+            );
+
+        // Interface class declaration: interface class name extends a, b; ... endclass
+        let interface_class_decl = keyword("interface")
+            .ignore_then(keyword("class"))
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(
+                keyword("extends")
+                    .ignore_then(identifier.separated_by(just(",")))
+                    .or_not()
+                    .map(|extends| extends.unwrap_or_default()),
             )
-            .then_ignore(ws.clone())
-            .then_ignore(just(';'))
-            .then_ignore(ws.clone())
+            .then_ignore(just(";"))
             .then(class_item.repeated())
-            .then_ignore(ws.clone())
-            .then_ignore(text::keyword("endclass"))
-            .then_ignore(ws.clone())
+            .then_ignore(keyword("endclass"))
             .map_with_span(|(((name, name_span), extends), items), span| {
-                ParsedModuleItem::ClassDeclaration {
+                ParsedModuleItem::InterfaceClassDeclaration {
                     name,
                     name_span,
                     extends,
@@ -1807,232 +6067,221 @@ impl SystemVerilogParser {
             });
 
         // Module item parser (recursive for module body)
-        let module_item = recursive(|_module_item| {
-            // Signing keyword
-            let signing = choice((
-                text::keyword("signed").to("signed"),
-                text::keyword("unsigned").to("unsigned"),
-            ));
-
+        let module_item = recursive(|module_item| {
             // Drive strength: (supply0, supply1), (strong0, strong1), etc.
             let strength_keyword = choice((
-                text::keyword("supply0").to("supply0"),
-                text::keyword("supply1").to("supply1"),
-                text::keyword("strong0").to("strong0"),
-                text::keyword("strong1").to("strong1"),
-                text::keyword("pull0").to("pull0"),
-                text::keyword("pull1").to("pull1"),
-                text::keyword("weak0").to("weak0"),
-                text::keyword("weak1").to("weak1"),
-                text::keyword("highz0").to("highz0"),
-                text::keyword("highz1").to("highz1"),
+                keyword("supply0").to("supply0"),
+                keyword("supply1").to("supply1"),
+                keyword("strong0").to("strong0"),
+                keyword("strong1").to("strong1"),
+                keyword("pull0").to("pull0"),
+                keyword("pull1").to("pull1"),
+                keyword("weak0").to("weak0"),
+                keyword("weak1").to("weak1"),
+                keyword("highz0").to("highz0"),
+                keyword("highz1").to("highz1"),
             ));
 
-            let drive_strength = just('(')
-                .padded_by(ws.clone())
-                .ignore_then(strength_keyword.clone())
-                .then_ignore(just(',').padded_by(ws.clone()))
-                .then(strength_keyword.clone())
-                .then_ignore(just(')').padded_by(ws.clone()))
-                .map(|(s0, s1)| DriveStrength {
+            let drive_strength = just("(")
+                .ignore_then(strength_keyword)
+                .then_ignore(just(","))
+                .then(strength_keyword)
+                .then_ignore(just(")"))
+                .map_with_span(|(s0, s1), span| DriveStrength {
                     strength0: s0.to_string(),
                     strength1: s1.to_string(),
+                    span: (span.start, span.end),
                 });
 
-            // Union/struct type
-            let union_struct_type = choice((
-                text::keyword("union").to("union".to_string()),
-                text::keyword("struct").to("struct".to_string()),
+            // Net type with what may follow it before the signing:
+            // wire (strong0, weak1) vectored logic
+            let net_type = choice((
+                keyword("supply0").to("supply0"),
+                keyword("supply1").to("supply1"),
+                keyword("triand").to("triand"),
+                keyword("trior").to("trior"),
+                keyword("trireg").to("trireg"),
+                keyword("tri0").to("tri0"),
+                keyword("tri1").to("tri1"),
+                keyword("tri").to("tri"),
+                keyword("uwire").to("uwire"),
+                keyword("wire").to("wire"),
+                keyword("wand").to("wand"),
+                keyword("wor").to("wor"),
             ))
-            .then_ignore(ws.clone())
-            .then(text::keyword("packed").or_not())
-            .then_ignore(ws.clone())
-            .then_ignore(just('{'))
-            .then_ignore(ws.clone())
-            .then(
-                // Parse struct/union members: type name;
-                type_keyword
-                    .clone()
-                    .or(identifier.clone())
-                    .then_ignore(ws.clone())
-                    .then(range.clone().or_not())
-                    .then_ignore(ws.clone())
-                    .then(identifier.clone())
-                    .then_ignore(ws.clone())
-                    .then_ignore(just(';'))
-                    .then_ignore(ws.clone())
-                    .repeated()
-                    .at_least(1),
+            .then(drive_strength.or_not())
+            .then_ignore(choice((keyword("vectored"), keyword("scalared"))).or_not())
+            // The data type of a net is logic whether or not it is written out,
+            // unless `begin_keywords makes `logic` the net's name
+            .then_ignore(
+                token_where(TokenKind::Keyword, "logic", |token| {
+                    &*token.text == "logic" && token.kind == TokenKind::Keyword
+                })
+                .then(token_where(TokenKind::Unknown, "", |_| true).rewind())
+                // In `wire logic;` it is a misused name rather than the data type
+                .try_map(|(_, next), span| {
+                    if matches!(&*next.text, ";" | "," | "=") {
+                        Err(Simple::custom(span, "'logic' is a keyword"))
+                    } else {
+                        Ok(())
+                    }
+                })
+                .or_not(),
             )
-            .then_ignore(ws.clone())
-            .then_ignore(just('}'))
-            .map(|((union_or_struct, _packed), _members)| {
-                // For now, just return "union" or "struct" as the type name
-                // A full implementation would store the member information
-                union_or_struct
-            });
+            .map(|(net_type, drive_strength)| (net_type.to_string(), drive_strength));
 
             // Variable declaration: wire w; or int unsigned a = 12; or bit [7:0] arr[10]; or logic a, b, c;
             // or union { ... } un;
-            let var_decl = ws
-                .clone()
-                .ignore_then(choice((
-                    union_struct_type.clone(),
+            let var_decl = choice((
+                net_type.map(|(data_type, strength)| (ParsedDataType::Named(data_type), strength)),
+                inline_type.clone().map(|data_type| (data_type, None)),
+                specialized_type.clone().map(|data_type| (data_type, None)),
+                choice((
+                    virtual_interface_type,
                     type_keyword.clone(),
-                    identifier.clone(),
-                )))
-                .then_ignore(ws.clone())
-                .then(signing.or_not())
-                .then_ignore(ws.clone())
-                .then(drive_strength.or_not())
-                .then_ignore(ws.clone())
-                .then(range.clone().or_not()) // Packed dimension [7:0]
-                .then_ignore(ws.clone())
-                .then(delay.clone().or_not())
-                .then_ignore(ws.clone())
-                .then(
+                    // Type from a package: my_pkg::packet_t
                     identifier
-                        .clone()
-                        .map_with_span(|n, s| (n, (s.start, s.end)))
-                        .then_ignore(ws.clone())
-                        .then(unpacked_dim.clone().repeated()) // Unpacked dimensions [10][20]
-                        .then_ignore(ws.clone())
-                        .then(
-                            just('=')
-                                .padded_by(ws.clone())
-                                .ignore_then(expr.clone())
-                                .or_not(),
+                        .then_ignore(just("::"))
+                        .then(identifier)
+                        .map(|(package, name)| format!("{}::{}", package, name)),
+                    identifier,
+                ))
+                .map(|data_type| (ParsedDataType::Named(data_type), None)),
+            ))
+            .then(signing.or_not())
+            .then(drive_strength.or_not())
+            .then(range.or_not()) // Packed dimension [7:0]
+            .then(delay.or_not())
+            .then(
+                identifier
+                    .map_with_span(|n, s| (n, (s.start, s.end)))
+                    .then(unpacked_dim.repeated()) // Unpacked dimensions [10][20]
+                    .then(just("=").ignore_then(expr.clone()).or_not())
+                    .separated_by(just(","))
+                    .at_least(1),
+            )
+            .then_ignore(just(";"))
+            .map_with_span(
+                |(
+                    (((((data_type, net_strength), signing), drive_strength), packed_range), delay),
+                    variables,
+                ),
+                 span| {
+                    let span = (span.start, span.end);
+                    let mut declarations: Vec<ParsedModuleItem> = variables
+                        .into_iter()
+                        .map(
+                            |(((name, name_span), unpacked_dimensions), initial_value)| {
+                                ParsedModuleItem::VariableDeclaration {
+                                    data_type: data_type.clone(),
+                                    signing: signing.map(|s| s.to_string()),
+                                    drive_strength: net_strength.clone().or(drive_strength.clone()),
+                                    delay: delay.clone(),
+                                    range: packed_range.clone(),
+                                    name,
+                                    name_span,
+                                    unpacked_dimensions,
+                                    initial_value,
+                                    span,
+                                }
+                            },
                         )
-                        .separated_by(just(',').padded_by(ws.clone()))
+                        .collect();
+                    if declarations.len() == 1 {
+                        declarations.remove(0)
+                    } else {
+                        ParsedModuleItem::Declarations { declarations, span }
+                    }
+                },
+            );
+
+            // Continuous assignment: assign (strong0, weak1)? #delay? a = b, c = d;
+            let assignment = keyword("assign")
+                .ignore_then(drive_strength.or_not())
+                .then(delay.or_not())
+                .then(
+                    expr.clone()
+                        .then_ignore(just("="))
+                        .then(expr.clone())
+                        .separated_by(just(","))
                         .at_least(1),
                 )
-                .then_ignore(ws.clone())
-                .then_ignore(just(';'))
-                .map_with_span(
-                    |(
-                        ((((data_type, signing), drive_strength), packed_range), delay),
-                        variables,
-                    ),
-                     span| {
-                        // For now, return only the first variable as VariableDeclaration
-                        // In a real implementation, we'd need to handle multiple declarations
-                        let (((name, name_span), unpacked), initial_value) = &variables[0];
-                        ParsedModuleItem::VariableDeclaration {
-                            data_type: data_type.to_string(),
-                            signing: signing.map(|s| s.to_string()),
-                            drive_strength,
-                            delay,
-                            range: packed_range,
-                            name: name.clone(),
-                            name_span: *name_span,
-                            unpacked_dimensions: unpacked.clone(),
-                            initial_value: initial_value.clone(),
-                            span: (span.start, span.end),
+                .then_ignore(just(";"))
+                .map_with_span(|((drive_strength, delay), assignments), span| {
+                    let span = (span.start, span.end);
+                    let mut assignments: Vec<ParsedModuleItem> = assignments
+                        .into_iter()
+                        .map(|(target, expr)| ParsedModuleItem::Assignment {
+                            drive_strength: drive_strength.clone(),
+                            delay: delay.clone(),
+                            target,
+                            expr,
+                            span,
+                        })
+                        .collect();
+                    if assignments.len() == 1 {
+                        assignments.remove(0)
+                    } else {
+                        ParsedModuleItem::Declarations {
+                            declarations: assignments,
+                            span,
                         }
-                    },
-                );
-
-            // Continuous assignment: assign #delay? target = expr;
-            let assignment = ws
-                .clone()
-                .ignore_then(text::keyword("assign"))
-                .then_ignore(ws.clone())
-                .ignore_then(delay.clone().or_not())
-                .then_ignore(ws.clone())
-                .then(expr.clone())
-                .then_ignore(ws.clone())
-                .then_ignore(just('='))
-                .then_ignore(ws.clone())
-                .then(expr.clone())
-                .then_ignore(ws.clone())
-                .then_ignore(just(';'))
-                .map_with_span(
-                    |((delay, target), expr), span| ParsedModuleItem::Assignment {
-                        delay,
-                        target,
-                        expr,
-                        span: (span.start, span.end),
-                    },
-                );
+                    }
+                });
 
             // Procedural block type
             let block_type = choice((
-                text::keyword("always_comb").to(ProceduralBlockType::AlwaysComb),
-                text::keyword("always_ff").to(ProceduralBlockType::AlwaysFF),
-                text::keyword("always").to(ProceduralBlockType::Always),
-                text::keyword("initial").to(ProceduralBlockType::Initial),
-                text::keyword("final").to(ProceduralBlockType::Final),
+                keyword("always_comb").to(ProceduralBlockType::AlwaysComb),
+                keyword("always_ff").to(ProceduralBlockType::AlwaysFF),
+                keyword("always").to(ProceduralBlockType::Always),
+                keyword("initial").to(ProceduralBlockType::Initial),
+                keyword("final").to(ProceduralBlockType::Final),
             ));
 
             // Procedural block: initial/always/always_comb/always_ff/final begin...end
-            let procedural_block = ws
-                .clone()
-                .ignore_then(block_type)
-                .then_ignore(ws.clone())
-                .then_ignore(
-                    // Optional event control like @(posedge clk)
-                    just('@')
-                        .padded_by(ws.clone())
-                        .ignore_then(
-                            just('(')
-                                .ignore_then(filter(|c| *c != ')').repeated())
-                                .then_ignore(just(')')),
-                        )
-                        .or_not(),
-                )
-                .then_ignore(ws.clone())
+            let procedural_block = block_type
+                .then(event_control.clone().or_not())
                 .then(choice((
-                    // Multiple statements with begin/end
-                    text::keyword("begin")
-                        .ignore_then(ws.clone())
-                        .ignore_then(statement.clone().repeated())
-                        .then_ignore(ws.clone())
-                        .then_ignore(text::keyword("end")),
+                    // Multiple statements with begin/end, optionally named
+                    keyword("begin")
+                        .ignore_then(block_name.or_not())
+                        .then(statement.clone().repeated())
+                        .then_ignore(keyword("end"))
+                        .then(block_name.or_not())
+                        .map(|((label, statements), end_label)| (statements, label, end_label)),
                     // Single statement without begin/end
-                    statement.clone().map(|s| vec![s]),
+                    statement.clone().map(|s| (vec![s], None, None)),
                 )))
-                .map_with_span(|(block_type, statements), span| {
-                    ParsedModuleItem::ProceduralBlock {
-                        block_type,
-                        statements,
-                        span: (span.start, span.end),
-                    }
-                });
+                .map_with_span(
+                    |((block_type, event_control), (statements, label, end_label)), span| {
+                        ParsedModuleItem::ProceduralBlock {
+                            block_type,
+                            event_control,
+                            statements,
+                            label,
+                            end_label,
+                            span: (span.start, span.end),
+                        }
+                    },
+                );
 
             // Global clocking (needs to be before var_decl to avoid conflicts)
-            let global_clocking_item = text::keyword("global")
-                .padded_by(ws.clone())
-                .ignore_then(text::keyword("clocking"))
-                .then_ignore(ws.clone())
+            let global_clocking_item = keyword("global")
+                .ignore_then(keyword("clocking"))
                 .ignore_then(
                     identifier
-                        .clone()
                         .map_with_span(|n, s| (n, (s.start, s.end)))
                         .or_not(),
                 )
-                .then_ignore(ws.clone())
                 .then(
                     // Event control @(...)
-                    just('@')
-                        .padded_by(ws.clone())
-                        .ignore_then(
-                            just('(')
-                                .ignore_then(filter(|c| *c != ')').repeated().collect::<String>())
-                                .then_ignore(just(')')),
-                        )
+                    just("@")
+                        .ignore_then(just("(").ignore_then(until(")")).then_ignore(just(")")))
                         .map(|s| ParsedExpression::Identifier(format!("@({})", s), (0, 0))),
                 )
-                .then_ignore(ws.clone())
-                .then_ignore(just(';'))
-                .then_ignore(ws.clone())
-                .then_ignore(text::keyword("endclocking"))
-                .then_ignore(ws.clone())
-                .then(
-                    just(':')
-                        .padded_by(ws.clone())
-                        .ignore_then(identifier.clone())
-                        .or_not(),
-                )
+                .then_ignore(just(";"))
+                .then_ignore(keyword("endclocking"))
+                .then(just(":").ignore_then(identifier).or_not())
                 .map_with_span(|((identifier, clocking_event), end_label), span| {
                     ParsedModuleItem::GlobalClocking {
                         identifier: identifier.as_ref().map(|(n, _)| n.clone()),
@@ -2043,53 +6292,365 @@ impl SystemVerilogParser {
                     }
                 });
 
+            // Port connection: .name(expr), .name(), .name, .* or a positional expression
+            let port_connection = choice((
+                just(".*").to(ParsedPortConnection::Wildcard),
+                just(".")
+                    .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                    .then(
+                        expr.clone()
+                            .or_not()
+                            .delimited_by(just("("), just(")"))
+                            .or_not(),
+                    )
+                    .map(|((name, name_span), connected)| {
+                        // `.name` is shorthand for `.name(name)`
+                        let expr = match connected {
+                            Some(expr) => expr,
+                            None => Some(ParsedExpression::Identifier(name.clone(), name_span)),
+                        };
+                        ParsedPortConnection::Named {
+                            name,
+                            name_span,
+                            expr,
+                        }
+                    }),
+                expr.clone().map(ParsedPortConnection::Positional),
+            ));
+
+            let connection_list = port_connection
+                .separated_by(just(","))
+                .delimited_by(just("("), just(")"));
+
+            // Module instantiation: sub #(.W(8)) u_sub (.a(x), .b(y));
+            // (needs to be before var_decl, which would otherwise claim `sub u_sub`)
+            let module_instantiation = identifier
+                .map_with_span(|n, s| (n, (s.start, s.end)))
+                .then(just("#").ignore_then(connection_list.clone()).or_not())
+                .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                .then(connection_list)
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |((((module_name, module_name_span), parameters), instance), connections),
+                     span| {
+                        let (instance_name, instance_name_span) = instance;
+                        ParsedModuleItem::ModuleInstantiation {
+                            module_name,
+                            module_name_span,
+                            parameters: parameters.unwrap_or_default(),
+                            instance_name,
+                            instance_name_span,
+                            connections,
+                            span: (span.start, span.end),
+                        }
+                    },
+                );
+
+            // Parameter declaration: parameter WIDTH = 8; or localparam logic [3:0] MASK = 4'hF;
+            let parameter_decl = choice((
+                keyword("parameter").to(false),
+                keyword("localparam").to(true),
+            ))
+            .then(type_keyword.clone().or_not())
+            .then(range.or_not())
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then_ignore(just("="))
+            .then(expr.clone())
+            .then_ignore(just(";"))
+            .map_with_span(
+                |((((local, data_type), range), (name, name_span)), value), span| {
+                    ParsedModuleItem::ParameterDeclaration {
+                        local,
+                        data_type,
+                        range,
+                        name,
+                        name_span,
+                        value,
+                        span: (span.start, span.end),
+                    }
+                },
+            );
+
+            // Elaboration system task: $error("message");
+            let elaboration_task = choice((
+                just("$fatal").to(ElaborationSeverity::Fatal),
+                just("$error").to(ElaborationSeverity::Error),
+                just("$warning").to(ElaborationSeverity::Warning),
+                just("$info").to(ElaborationSeverity::Info),
+            ))
+            .then(
+                expr.clone()
+                    .separated_by(just(","))
+                    .delimited_by(just("("), just(")"))
+                    .or_not()
+                    .map(|args| args.unwrap_or_default()),
+            )
+            .then_ignore(just(";"))
+            .map_with_span(|(severity, arguments), span| {
+                ParsedModuleItem::ElaborationTask {
+                    severity,
+                    arguments,
+                    span: (span.start, span.end),
+                }
+            });
+
+            // Genvar declaration: genvar i, j;
+            let genvar_decl = keyword("genvar")
+                .ignore_then(
+                    identifier
+                        .map_with_span(|name, span| Genvar {
+                            name,
+                            name_span: (span.start, span.end),
+                        })
+                        .separated_by(just(","))
+                        .at_least(1),
+                )
+                .then_ignore(just(";"))
+                .map_with_span(|genvars, span| ParsedModuleItem::GenvarDeclaration {
+                    genvars,
+                    span: (span.start, span.end),
+                });
+
+            // Generate block: `begin : name ... end : name` or `name : begin ... end`
+            let generate_block = identifier
+                .map_with_span(|name, span| Label {
+                    name,
+                    span: (span.start, span.end),
+                })
+                .then_ignore(just(":"))
+                .or_not()
+                .then_ignore(keyword("begin"))
+                .then(block_name.or_not())
+                .then(module_item.clone().repeated())
+                .then_ignore(keyword("end"))
+                .then(block_name.or_not())
+                .map_with_span(|(((prefix, label), items), end_label), span| {
+                    ParsedModuleItem::GenerateBlock {
+                        label: label.or(prefix),
+                        items,
+                        end_label,
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // The body of a generate loop or branch: a block, or a single item
+            let generate_body = generate_block.or(module_item.clone()).map(Box::new);
+
+            // Genvar step: i++, i--, or i += 2
+            let genvar_step = identifier.then(choice((
+                just("++").map_with_span(|_, span: std::ops::Range<usize>| {
+                    (
+                        AssignmentOp::AddAssign,
+                        ParsedExpression::Number("1".to_string(), (span.start, span.end)),
+                    )
+                }),
+                just("--").map_with_span(|_, span: std::ops::Range<usize>| {
+                    (
+                        AssignmentOp::SubAssign,
+                        ParsedExpression::Number("1".to_string(), (span.start, span.end)),
+                    )
+                }),
+                assign_op.clone().then(expr.clone()),
+            )));
+
+            // Generate loop: for (genvar i = 0; i < N; i++) body
+            let generate_for = keyword("for")
+                .ignore_then(just("("))
+                .ignore_then(keyword("genvar").or_not().map(|genvar| genvar.is_some()))
+                .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                .then_ignore(just("="))
+                .then(expr.clone())
+                .then_ignore(just(";"))
+                .then(expr.clone())
+                .then_ignore(just(";"))
+                .then(genvar_step)
+                .then_ignore(just(")"))
+                .then(generate_body.clone())
+                .try_map(
+                    |(
+                        ((((declares_genvar, (genvar, genvar_span)), init), condition), step),
+                        body,
+                    ),
+                     span: std::ops::Range<usize>| {
+                        let (step_genvar, (step_op, step)) = step;
+                        if step_genvar != genvar {
+                            return Err(Simple::custom(
+                                span,
+                                format!(
+                                    "Generate loop over '{}' cannot step '{}'",
+                                    genvar, step_genvar
+                                ),
+                            ));
+                        }
+                        Ok(ParsedModuleItem::GenerateFor {
+                            declares_genvar,
+                            genvar,
+                            genvar_span,
+                            init,
+                            condition,
+                            step_op,
+                            step,
+                            body,
+                            span: (span.start, span.end),
+                        })
+                    },
+                );
+
+            // Generate branch: if (cond) body else body
+            let generate_if = keyword("if")
+                .ignore_then(expr.clone().delimited_by(just("("), just(")")))
+                .then(generate_body.clone())
+                .then(keyword("else").ignore_then(generate_body.clone()).or_not())
+                .map_with_span(|((condition, then_item), else_item), span| {
+                    ParsedModuleItem::GenerateIf {
+                        condition,
+                        then_item,
+                        else_item,
+                        span: (span.start, span.end),
+                    }
+                });
+
+            // Generate case item: `1, 2: body` or `default: body`
+            let generate_case_item = keyword("default")
+                .then_ignore(just(":").or_not())
+                .to(Vec::new())
+                .or(expr
+                    .clone()
+                    .separated_by(just(","))
+                    .at_least(1)
+                    .then_ignore(just(":")))
+                .then(generate_body.clone())
+                .map_with_span(|(values, body), span| ParsedGenerateCaseItem {
+                    values,
+                    body: *body,
+                    span: (span.start, span.end),
+                });
+
+            // Generate case: case (expr) ... endcase
+            let generate_case = keyword("case")
+                .ignore_then(expr.clone().delimited_by(just("("), just(")")))
+                .then(generate_case_item.repeated())
+                .then_ignore(keyword("endcase"))
+                .map_with_span(|(expr, items), span| ParsedModuleItem::GenerateCase {
+                    expr,
+                    items,
+                    span: (span.start, span.end),
+                });
+
+            // Generate region: generate ... endgenerate
+            let generate_region = keyword("generate")
+                .ignore_then(module_item.clone().repeated())
+                .then_ignore(keyword("endgenerate"))
+                .map_with_span(|items, span| ParsedModuleItem::GenerateRegion {
+                    items,
+                    span: (span.start, span.end),
+                });
+
+            // Generate constructs (needs to be before module_instantiation)
+            let generate_item = choice((
+                genvar_decl,
+                generate_region,
+                generate_for,
+                generate_if,
+                generate_case,
+            ));
+
             choice((
-                define_directive.clone(),
+                define_directive,
                 include_directive.clone(),
+                timescale_directive,
+                default_nettype_directive,
+                timeunits_decl,
+                parameter_decl,
+                typedef_decl.clone(),
+                elaboration_task,
                 global_clocking_item,
+                clocking_block.clone(),
+                default_clocking,
+                default_disable_iff,
+                assertion_decl.clone(),
                 concurrent_assertion.clone(),
+                import_decl.clone(),
                 port_decl.clone(),
                 class_decl.clone(),
+                interface_class_decl.clone(),
+                out_of_block_method.clone(),
+                subroutine_decl.clone(),
+                generate_item,
+                module_instantiation,
                 var_decl,
                 assignment,
                 procedural_block,
             ))
         });
 
+        // Modport declaration: modport master(output req, input gnt), slave(...);
+        let modport_port = port_direction
+            .clone()
+            .or_not()
+            .then(identifier.map_with_span(|n, s| (n, (s.start, s.end))));
+        let modport = identifier
+            .map_with_span(|n, s| (n, (s.start, s.end)))
+            .then(
+                modport_port
+                    .separated_by(just(","))
+                    .delimited_by(just("("), just(")")),
+            )
+            .try_map(
+                |((name, name_span), signals), span: std::ops::Range<usize>| {
+                    // A signal without a direction takes the one of the signal before it
+                    let mut ports = Vec::with_capacity(signals.len());
+                    let mut previous = None;
+                    for (direction, (port_name, port_name_span)) in signals {
+                        let direction = match direction.or(previous) {
+                            Some(direction) => direction,
+                            None => {
+                                return Err(Simple::custom(
+                                    span,
+                                    format!("modport port '{}' has no direction", port_name),
+                                ))
+                            }
+                        };
+                        previous = Some(direction.clone());
+                        ports.push(ModportPort {
+                            direction,
+                            name: port_name,
+                            name_span: port_name_span,
+                        });
+                    }
+                    Ok(Modport {
+                        name,
+                        name_span,
+                        ports,
+                        span: (span.start, span.end),
+                    })
+                },
+            );
+        let modport_decl = keyword("modport")
+            .ignore_then(modport.separated_by(just(",")).at_least(1))
+            .then_ignore(just(";"))
+            .map_with_span(|modports, span| ParsedModuleItem::ModportDeclaration {
+                modports,
+                span: (span.start, span.end),
+            });
+
         // Global clocking (for top-level)
-        let global_clocking = text::keyword("global")
-            .padded_by(ws.clone())
-            .ignore_then(text::keyword("clocking"))
-            .then_ignore(ws.clone())
+        let global_clocking = keyword("global")
+            .ignore_then(keyword("clocking"))
             .ignore_then(
                 identifier
-                    .clone()
                     .map_with_span(|n, s| (n, (s.start, s.end)))
                     .or_not(),
             )
-            .then_ignore(ws.clone())
             .then(
                 // Event control @(...)
-                just('@')
-                    .padded_by(ws.clone())
-                    .ignore_then(
-                        just('(')
-                            .ignore_then(filter(|c| *c != ')').repeated().collect::<String>())
-                            .then_ignore(just(')')),
-                    )
+                just("@")
+                    .ignore_then(just("(").ignore_then(until(")")).then_ignore(just(")")))
                     .map(|s| ParsedExpression::Identifier(format!("@({})", s), (0, 0))),
             )
-            .then_ignore(ws.clone())
-            .then_ignore(just(';'))
-            .then_ignore(ws.clone())
-            .then_ignore(text::keyword("endclocking"))
-            .then_ignore(ws.clone())
-            .then(
-                just(':')
-                    .padded_by(ws.clone())
-                    .ignore_then(identifier.clone())
-                    .or_not(),
-            )
+            .then_ignore(just(";"))
+            .then_ignore(keyword("endclocking"))
+            .then(just(":").ignore_then(identifier).or_not())
             .map_with_span(|((identifier, clocking_event), end_label), span| {
                 ParsedModuleItem::GlobalClocking {
                     identifier: identifier.as_ref().map(|(n, _)| n.clone()),
@@ -2100,27 +6661,56 @@ impl SystemVerilogParser {
                 }
             });
 
-        // Module declaration: module <name> (ports); items endmodule
-        let module_decl = ws
-            .clone()
-            .ignore_then(text::keyword("module"))
-            .then_ignore(ws.clone())
-            .ignore_then(
-                identifier
-                    .clone()
-                    .map_with_span(|n, s| (n, (s.start, s.end))),
-            )
-            .then_ignore(ws.clone())
+        // Checker declaration: checker <name> (ports); items endchecker
+        let checker_decl = keyword("checker")
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then(checker_port_list.or_not())
+            .then_ignore(just(";"))
+            .then(module_item.clone().repeated())
+            .then_ignore(keyword("endchecker"))
+            .map_with_span(|(((name, name_span), ports), items), span| {
+                ParsedModuleItem::CheckerDeclaration {
+                    name,
+                    name_span,
+                    ports: ports.unwrap_or_default(),
+                    items,
+                    span: (span.start, span.end),
+                }
+            });
+
+        // Module declaration: module <name> (ports); items endmodule, where
+        // the items may be nested module and checker declarations
+        let module_decl = recursive(|module_decl| {
+            keyword("module")
+                .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+                // Imports in the header, which the ports can already use
+                .then(import_decl.clone().repeated())
+                .then(port_list.clone().or_not())
+                .then_ignore(just(";"))
+                .then(choice((module_decl, checker_decl.clone(), module_item.clone())).repeated())
+                .then_ignore(keyword("endmodule"))
+                .map_with_span(|((((name, name_span), mut imports), ports), items), span| {
+                    // Header imports come first among the items of the module
+                    imports.extend(items);
+                    ParsedModuleItem::ModuleDeclaration {
+                        name,
+                        name_span,
+                        ports: ports.unwrap_or_default(),
+                        items: imports,
+                        span: (span.start, span.end),
+                    }
+                })
+        });
+
+        // Interface declaration: interface <name> (ports); items endinterface
+        let interface_decl = keyword("interface")
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
             .then(port_list.or_not())
-            .then_ignore(ws.clone())
-            .then_ignore(just(';'))
-            .then_ignore(ws.clone())
-            .then(module_item.repeated())
-            .then_ignore(ws.clone())
-            .then_ignore(text::keyword("endmodule"))
-            .then_ignore(ws.clone())
+            .then_ignore(just(";"))
+            .then(choice((modport_decl, module_item.clone())).repeated())
+            .then_ignore(keyword("endinterface"))
             .map_with_span(|(((name, name_span), ports), items), span| {
-                ParsedModuleItem::ModuleDeclaration {
+                ParsedModuleItem::InterfaceDeclaration {
                     name,
                     name_span,
                     ports: ports.unwrap_or_default(),
@@ -2129,21 +6719,161 @@ impl SystemVerilogParser {
                 }
             });
 
-        // Top-level items (modules, classes, preprocessor directives)
+        // Package declaration: package <name>; items endpackage
+        let package_decl = keyword("package")
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then_ignore(just(";"))
+            .then(choice((export_decl, checker_decl.clone(), module_item.clone())).repeated())
+            .then_ignore(keyword("endpackage"))
+            .map_with_span(|((name, name_span), items), span| {
+                ParsedModuleItem::PackageDeclaration {
+                    name,
+                    name_span,
+                    items,
+                    span: (span.start, span.end),
+                }
+            });
+
+        // Configuration: config name; design lib.top; rules... endconfig
+        let cell_reference = identifier
+            .then(just(".").ignore_then(identifier).or_not())
+            .map_with_span(|(first, second), span| {
+                let span = (span.start, span.end);
+                match second {
+                    Some(cell) => CellReference {
+                        library: Some(first),
+                        cell,
+                        span,
+                    },
+                    None => CellReference {
+                        library: None,
+                        cell: first,
+                        span,
+                    },
+                }
+            });
+        let library_names = identifier.repeated();
+        let library_selection = choice((
+            keyword("liblist")
+                .ignore_then(library_names)
+                .map(LibrarySelection::Liblist),
+            keyword("use")
+                .ignore_then(cell_reference)
+                .map(LibrarySelection::Use),
+        ));
+        let config_rule = choice((
+            keyword("default")
+                .ignore_then(keyword("liblist"))
+                .ignore_then(library_names)
+                .then_ignore(just(";"))
+                .map_with_span(
+                    |liblist, span: std::ops::Range<usize>| ConfigRule::Default {
+                        liblist,
+                        span: (span.start, span.end),
+                    },
+                ),
+            keyword("instance")
+                .ignore_then(
+                    identifier
+                        .separated_by(just("."))
+                        .at_least(1)
+                        .map(|names| names.join(".")),
+                )
+                .then(library_selection)
+                .then_ignore(just(";"))
+                .map_with_span(|(path, selection), span| ConfigRule::Instance {
+                    path,
+                    selection,
+                    span: (span.start, span.end),
+                }),
+            keyword("cell")
+                .ignore_then(cell_reference)
+                .then(library_selection)
+                .then_ignore(just(";"))
+                .map_with_span(|(cell, selection), span| ConfigRule::Cell {
+                    cell,
+                    selection,
+                    span: (span.start, span.end),
+                }),
+        ));
+        let config_decl = keyword("config")
+            .ignore_then(identifier.map_with_span(|n, s| (n, (s.start, s.end))))
+            .then_ignore(just(";"))
+            .then_ignore(keyword("design"))
+            .then(cell_reference.repeated())
+            .then_ignore(just(";"))
+            .then(config_rule.repeated())
+            .then_ignore(keyword("endconfig"))
+            .map_with_span(|(((name, name_span), design), rules), span| {
+                ParsedModuleItem::ConfigDeclaration {
+                    name,
+                    name_span,
+                    design,
+                    rules,
+                    span: (span.start, span.end),
+                }
+            });
+
+        // Top-level items (modules, classes, configurations, preprocessor directives)
         let top_level = choice((
             define_directive,
             include_directive,
+            timescale_directive,
+            default_nettype_directive,
+            timeunits_decl,
             class_decl,
+            interface_class_decl,
+            out_of_block_method,
+            subroutine_decl,
+            interface_decl,
+            package_decl,
             module_decl,
+            checker_decl,
+            config_decl,
+            import_decl,
+            typedef_decl,
             global_clocking,
+            assertion_decl,
             concurrent_assertion,
             port_decl,
         ));
 
-        ws.clone()
-            .ignore_then(top_level.repeated())
-            .then_ignore(ws.clone())
+        // Header files may also hold declarations that otherwise only appear
+        // inside a module
+        let top_level = if entry == Entry::Header {
+            choice((top_level, module_item)).boxed()
+        } else {
+            top_level.boxed()
+        };
+
+        // `begin_keywords "1800-2005" and `end_keywords between top-level
+        // items; the lexer has already worked out which words they turn into
+        // identifiers
+        let keywords_directive = just("`begin_keywords")
+            .or(just("`end_keywords"))
+            .then(rest_of_line())
+            .ignored();
+
+        let keywords_directives = keywords_directive.repeated();
+
+        let source_text = keywords_directives
+            .ignore_then(top_level)
+            .repeated()
+            .then_ignore(keywords_directives)
             .then_ignore(end())
+            .map(ParsedFragment::Items);
+
+        match entry {
+            Entry::SourceText | Entry::Header => source_text.boxed(),
+            Entry::Expression => expr
+                .then_ignore(end())
+                .map(ParsedFragment::Expression)
+                .boxed(),
+            Entry::Statement => statement
+                .then_ignore(end())
+                .map(|statement| ParsedFragment::Statement(Box::new(statement)))
+                .boxed(),
+        }
     }
 }
 
@@ -2195,7 +6925,7 @@ mod tests {
                     ModuleItem::VariableDeclaration {
                         data_type, name, ..
                     } => {
-                        assert_eq!(data_type, "wire");
+                        assert_eq!(data_type.name(), "wire");
                         assert_eq!(name, "w");
                     }
                     _ => panic!("Expected VariableDeclaration"),
@@ -0,0 +1,185 @@
+//! File policy lint rules
+//!
+//! Teams often require every file to start with a copyright or license
+//! header, and to be named after the one design unit it defines, so that a
+//! unit can be found from its name. The `[lint.file]` table of the project
+//! configuration sets the header pattern, the text `--fix` inserts when it is
+//! missing, and whether file names are checked; each rule runs with `--lint`
+//! only when it is configured there.
+
+use std::path::Path;
+
+use regex::Regex;
+
+use crate::config::FileConfig;
+use crate::lint::{LintDiagnostic, LintRule};
+use crate::{ModuleItem, SourceUnit, Span, TextEdit};
+
+/// Header pattern when `file-header` is named but not configured: the file
+/// starts with a comment
+pub const DEFAULT_HEADER: &str = r"//|/\*";
+
+/// The file policy rules, with the settings of a `[lint.file]` table
+///
+/// Returns an error when the header pattern is not a valid regular
+/// expression.
+pub fn rules(config: &FileConfig) -> Result<Vec<Box<dyn LintRule>>, String> {
+    let source = config.header.as_deref().unwrap_or(DEFAULT_HEADER);
+    // Anchored, so the header has to be at the very start of the file
+    let pattern = Regex::new(&format!(r"\A(?:{})", source))
+        .map_err(|err| format!("Invalid header pattern for file-header: {}", err))?;
+    Ok(vec![
+        Box::new(FileHeader {
+            pattern,
+            source: source.to_string(),
+            template: config.header_template.clone(),
+            configured: config.header.is_some(),
+        }),
+        Box::new(FileName {
+            configured: config.name_matches_unit == Some(true),
+        }),
+    ])
+}
+
+/// Flags files that don't start with the required header
+struct FileHeader {
+    pattern: Regex,
+    source: String,
+    template: Option<String>,
+    configured: bool,
+}
+
+impl LintRule for FileHeader {
+    fn name(&self) -> &'static str {
+        "file-header"
+    }
+
+    fn description(&self) -> &'static str {
+        "File not starting with the required header"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "Every file must start with text matching the `header` regular \
+         expression of the `[lint.file]` table, like a copyright or license notice; \
+         by default any comment. `--fix` inserts `header_template` when it is set. \
+         The rule runs with `--lint` only when the header is configured."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check_text(&self, text: &str, diagnostics: &mut Vec<LintDiagnostic>) {
+        if self.pattern.is_match(text) {
+            return;
+        }
+        let first_line = text.lines().next().unwrap_or("").chars().count();
+        diagnostics.push(LintDiagnostic {
+            rule: self.name(),
+            message: format!("File does not start with a header matching {}", self.source),
+            span: (0, first_line),
+            fix: self.template.as_ref().map(|template| {
+                let mut new_text = template.clone();
+                if !new_text.ends_with('\n') {
+                    new_text.push('\n');
+                }
+                TextEdit {
+                    span: (0, 0),
+                    new_text,
+                }
+            }),
+        });
+    }
+}
+
+/// Flags a file defining a single design unit whose name differs from the
+/// file's, like `module fifo` in `buffer.sv`
+struct FileName {
+    configured: bool,
+}
+
+impl LintRule for FileName {
+    fn name(&self) -> &'static str {
+        "file-name"
+    }
+
+    fn description(&self) -> &'static str {
+        "File not named after the design unit it defines"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A file that defines a single module, interface, package, class or other \
+         design unit must be named after it, like `fifo.sv` for `module fifo`, so \
+         that a unit can be found from its name. Files with several units or none \
+         are not checked. The rule runs with `--lint` only when \
+         `name_matches_unit = true` is set in the `[lint.file]` table."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check_file(
+        &self,
+        path: &Path,
+        source_unit: &SourceUnit,
+        _text: &str,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        let units: Vec<(&str, &str, Span)> = source_unit
+            .items
+            .iter()
+            .filter_map(|item_ref| design_unit(source_unit.module_item_arena.get(*item_ref)))
+            .collect();
+        let [(kind, name, name_span)] = units[..] else {
+            return;
+        };
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            return;
+        };
+        // The name ends at the first dot, as in `fifo.pkg.sv`
+        let (stem, extension) = match file_name.split_once('.') {
+            Some((stem, extension)) => (stem, format!(".{}", extension)),
+            None => (file_name, String::new()),
+        };
+        if stem != name {
+            diagnostics.push(LintDiagnostic {
+                rule: self.name(),
+                message: format!(
+                    "File '{}' defines {} '{}'; rename it to '{}{}'",
+                    file_name, kind, name, name, extension
+                ),
+                span: name_span,
+                fix: None,
+            });
+        }
+    }
+}
+
+/// The kind and name of a design unit declared at the top of a file
+fn design_unit(item: &ModuleItem) -> Option<(&'static str, &str, Span)> {
+    match item {
+        ModuleItem::ModuleDeclaration {
+            name, name_span, ..
+        } => Some(("module", name, *name_span)),
+        ModuleItem::InterfaceDeclaration {
+            name, name_span, ..
+        } => Some(("interface", name, *name_span)),
+        ModuleItem::CheckerDeclaration {
+            name, name_span, ..
+        } => Some(("checker", name, *name_span)),
+        ModuleItem::PackageDeclaration {
+            name, name_span, ..
+        } => Some(("package", name, *name_span)),
+        ModuleItem::ClassDeclaration {
+            name, name_span, ..
+        }
+        | ModuleItem::InterfaceClassDeclaration {
+            name, name_span, ..
+        } => Some(("class", name, *name_span)),
+        ModuleItem::ConfigDeclaration {
+            name, name_span, ..
+        } => Some(("config", name, *name_span)),
+        _ => None,
+    }
+}
@@ -1,8 +1,892 @@
 use std::collections::HashMap;
-use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::{ParseError, ParseErrorType, SingleParseError, SourceLocation};
+pub use very_ast::source_map::{DefinitionSite, Provenance, SourceMap};
+
+use crate::files;
+use crate::lexer::KeywordVersion;
+use crate::{
+    Directive, DirectiveKind, ModuleItem, ModuleItemRef, ParseError, ParseErrorType,
+    SingleParseError, SourceLocation, SourceUnit, Span,
+};
+
+/// A macro as it stands after parsing, for `ifdef questions and hover text
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MacroDefinition {
+    pub name: String,
+    /// Formal arguments of a function-like macro
+    pub parameters: Vec<String>,
+    /// Replacement text
+    pub value: String,
+    /// File holding the `define; None for macros given on the command line
+    /// or in the configuration, and for text parsed with `parse_content`
+    pub file: Option<PathBuf>,
+    /// Span of the `define in `file`; None for predefined macros
+    pub span: Option<Span>,
+}
+
+impl MacroDefinition {
+    /// A macro defined outside the sources, e.g. with +define+
+    pub fn predefined(name: &str, value: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            parameters: Vec::new(),
+            value: value.to_string(),
+            file: None,
+            span: None,
+        }
+    }
+
+    /// The definition made by a `define item
+    pub fn from_item(item: &ModuleItem, file: Option<&Path>) -> Option<Self> {
+        let ModuleItem::DefineDirective {
+            name,
+            parameters,
+            value,
+            span,
+            ..
+        } = item
+        else {
+            return None;
+        };
+        Some(Self {
+            name: name.clone(),
+            parameters: parameters.clone(),
+            value: value.clone(),
+            file: file.map(Path::to_path_buf),
+            span: Some(*span),
+        })
+    }
+}
+
+/// Add the `define directives of `unit`, top-level and inside modules, to
+/// `macros` in source order, so later definitions replace earlier ones
+pub fn collect_macro_definitions(
+    unit: &SourceUnit,
+    file: Option<&Path>,
+    macros: &mut HashMap<String, MacroDefinition>,
+) {
+    fn visit(
+        unit: &SourceUnit,
+        item_ref: ModuleItemRef,
+        file: Option<&Path>,
+        macros: &mut HashMap<String, MacroDefinition>,
+    ) {
+        let item = unit.module_item_arena.get(item_ref);
+        if let Some(definition) = MacroDefinition::from_item(item, file) {
+            macros.insert(definition.name.clone(), definition);
+        } else if let ModuleItem::ModuleDeclaration { items, .. }
+        | ModuleItem::InterfaceDeclaration { items, .. }
+        | ModuleItem::CheckerDeclaration { items, .. }
+        | ModuleItem::PackageDeclaration { items, .. } = item
+        {
+            for &nested in items {
+                visit(unit, nested, file, macros);
+            }
+        } else {
+            for nested in item.generate_items() {
+                visit(unit, nested, file, macros);
+            }
+        }
+    }
+
+    for &item_ref in &unit.items {
+        visit(unit, item_ref, file, macros);
+    }
+}
+
+/// How far preprocessing may go before it gives up with an error, so that a
+/// runaway macro or a long `include chain can't stall the parser
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PreprocessLimits {
+    /// Deepest nesting of macro expansions and `include files
+    pub max_depth: usize,
+    /// Most characters the preprocessed text of one file may hold
+    pub max_output: usize,
+}
+
+impl Default for PreprocessLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            max_output: 16 * 1024 * 1024,
+        }
+    }
+}
+
+/// Source text after conditional compilation and macro expansion
+#[derive(Debug, Clone, Default)]
+pub struct Preprocessed {
+    /// Each character with the span of the source text it comes from: itself,
+    /// or the whole macro usage for expanded text
+    pub chars: Vec<(char, Span)>,
+    pub source_map: SourceMap,
+}
+
+/// Reads the file named by an `include in a file, or None if it can't be found
+pub type IncludeReader<'a> = dyn FnMut(&str, Option<&Path>) -> Option<(PathBuf, String)> + 'a;
+
+/// Apply conditional directives and expand macro usages in `content`
+///
+/// `ifdef, `ifndef, `elsif, `else and `endif select the text that is kept,
+/// and `undef removes a macro; these directives and the text of the branches
+/// not taken are dropped. Usages of macros defined in `macros`, earlier in
+/// the file or in its `include files are replaced with their text. `define
+/// and `include lines, other directives and usages of unknown macros are
+/// kept for the grammar. `macros` are those defined before the text, such as
+/// on the command line. Going past `limits` is an error naming the macros and
+/// `include files that led there. With `retain_directives`, every directive
+/// of the text, compiled or not, is recorded in the source map. Returns None
+/// when the text needs no change and no directive was recorded.
+pub fn preprocess(
+    content: &str,
+    file: Option<&Path>,
+    macros: &HashMap<String, MacroDefinition>,
+    limits: PreprocessLimits,
+    retain_directives: bool,
+    read_include: &mut IncludeReader<'_>,
+) -> Result<Option<Preprocessed>, SingleParseError> {
+    let text: Vec<char> = content.chars().collect();
+    let mut expander = Expander {
+        macros: macros
+            .iter()
+            .map(|(name, definition)| {
+                let definition = Macro {
+                    parameters: definition.parameters.clone(),
+                    value: definition.value.clone(),
+                    site: None,
+                };
+                (name.clone(), definition)
+            })
+            .collect(),
+        output: Preprocessed::default(),
+        changed: false,
+        limits,
+        chain: Vec::new(),
+        limit_exceeded: false,
+        reading: file.map(canonical).into_iter().collect(),
+        retain_directives,
+        read_include,
+    };
+    match expander.run(&text, Origin::Source { file, emit: true }, 0) {
+        Ok(()) if expander.changed || !expander.output.source_map.is_empty() => {
+            Ok(Some(expander.output))
+        }
+        Ok(()) => Ok(None),
+        Err((message, (start, end))) => {
+            let line = text[..start.min(text.len())]
+                .iter()
+                .filter(|c| **c == '\n')
+                .count();
+            let column = text[..start.min(text.len())]
+                .iter()
+                .rev()
+                .take_while(|c| **c != '\n')
+                .count();
+            Err(
+                SingleParseError::new(message, ParseErrorType::PreprocessorError).with_location(
+                    SourceLocation {
+                        line,
+                        column,
+                        span: Some((start, end)),
+                    },
+                ),
+            )
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Macro {
+    parameters: Vec<String>,
+    value: String,
+    site: Option<DefinitionSite>,
+}
+
+/// Where the text being preprocessed comes from
+#[derive(Clone, Copy)]
+enum Origin<'p> {
+    /// A source file; the text of `include files is only read for its
+    /// macros, and not emitted
+    Source { file: Option<&'p Path>, emit: bool },
+    /// The text of a macro, with the span of the source text each character
+    /// comes from: the macro usage, or the actual argument it was part of
+    Expansion(&'p [Span]),
+}
+
+impl Origin<'_> {
+    /// The span of the source text that `text[start..end]` comes from
+    fn span(&self, start: usize, end: usize) -> Span {
+        match self {
+            Origin::Source { .. } => (start, end),
+            Origin::Expansion(spans) => spans[start..end.max(start + 1)]
+                .iter()
+                .fold((usize::MAX, 0), |(from, to), span| {
+                    (from.min(span.0), to.max(span.1))
+                }),
+        }
+    }
+}
+
+/// An open `ifdef or `ifndef
+struct Conditional {
+    opening: String,
+    opening_span: Span,
+    /// Whether the text around the conditional is compiled
+    enclosing: bool,
+    /// Whether a branch has been compiled
+    taken: bool,
+    /// Whether the current branch is compiled
+    active: bool,
+    branch: String,
+    branch_span: Span,
+    body_start: usize,
+}
+
+type ExpandResult<T> = Result<T, (String, Span)>;
+
+struct Expander<'r, 'a> {
+    macros: HashMap<String, Macro>,
+    output: Preprocessed,
+    changed: bool,
+    limits: PreprocessLimits,
+    /// The macros being expanded and the `include files being read, outermost first
+    chain: Vec<String>,
+    /// Whether the error being returned is a limit, which an `include file
+    /// can't leave to be reported when the file is parsed on its own
+    limit_exceeded: bool,
+    /// The file and the `include files being read, so that a file including
+    /// itself again is skipped as the parser does
+    reading: Vec<PathBuf>,
+    /// Record the directives of the file in the source map
+    retain_directives: bool,
+    read_include: &'r mut IncludeReader<'a>,
+}
+
+impl Expander<'_, '_> {
+    fn run(&mut self, text: &[char], origin: Origin, depth: usize) -> ExpandResult<()> {
+        let emit = match origin {
+            Origin::Source { emit, .. } => emit,
+            Origin::Expansion(_) => true,
+        };
+        let span_at = |start: usize, end: usize| origin.span(start, end);
+        let mut conditionals: Vec<Conditional> = Vec::new();
+        // Open `begin_keywords regions of the file
+        let mut keyword_regions = 0;
+        let mut line = 0;
+        let mut line_counted = 0;
+        let mut i = 0;
+
+        while i < text.len() {
+            let active = conditionals.last().is_none_or(|open| open.active);
+            let c = text[i];
+            let end = if c == '/' && text.get(i + 1) == Some(&'/') {
+                line_end(text, i)
+            } else if c == '/' && text.get(i + 1) == Some(&'*') {
+                block_comment_end(text, i)
+            } else if c == '"' {
+                string_end(text, i)
+            } else if c == '`' && text.get(i + 1).is_some_and(|c| is_identifier_start(*c)) {
+                let name_end = identifier_end(text, i + 1);
+                let name: String = text[i + 1..name_end].iter().collect();
+                if self.retain_directives
+                    && matches!(origin, Origin::Source { emit: true, .. })
+                    && !self.macros.contains_key(&name)
+                {
+                    let compiled = match name.as_str() {
+                        "elsif" | "else" | "endif" => {
+                            conditionals.last().is_none_or(|open| open.enclosing)
+                        }
+                        _ => active,
+                    };
+                    let end = directive_end(text, &name, name_end);
+                    self.output.source_map.record_directive(Directive {
+                        kind: DirectiveKind::from_name(&name),
+                        text: text[i..end].iter().collect(),
+                        span: (i, end),
+                        active: compiled,
+                    });
+                }
+                match name.as_str() {
+                    "ifdef" | "ifndef" | "elsif" => {
+                        let (macro_name, end) = directive_argument(text, name_end);
+                        let span = span_at(i, end);
+                        if macro_name.is_empty() {
+                            return Err((format!("`{} needs a macro name", name), span));
+                        }
+                        let defined = self.macros.contains_key(&macro_name);
+                        let directive = format!("`{} {}", name, macro_name);
+                        if name == "elsif" {
+                            let Some(open) = conditionals.last_mut() else {
+                                return Err(("`elsif without `ifdef".to_string(), span));
+                            };
+                            self.close_branch(open, i, origin);
+                            open.active = open.enclosing && !open.taken && defined;
+                            open.taken |= open.active;
+                            open.branch = directive;
+                            open.branch_span = span;
+                            open.body_start = end;
+                        } else {
+                            let compiled = active && defined == (name == "ifdef");
+                            conditionals.push(Conditional {
+                                opening: directive.clone(),
+                                opening_span: span,
+                                enclosing: active,
+                                taken: compiled,
+                                active: compiled,
+                                branch: directive,
+                                branch_span: span,
+                                body_start: end,
+                            });
+                        }
+                        self.changed = true;
+                        i = end;
+                        continue;
+                    }
+                    "else" | "endif" => {
+                        let span = span_at(i, name_end);
+                        let Some(open) = conditionals.last_mut() else {
+                            return Err((format!("`{} without `ifdef", name), span));
+                        };
+                        self.close_branch(open, i, origin);
+                        if name == "else" {
+                            open.active = open.enclosing && !open.taken;
+                            open.taken = true;
+                            open.branch = format!("`else of {}", open.opening);
+                            open.branch_span = span;
+                            open.body_start = name_end;
+                        } else {
+                            conditionals.pop();
+                        }
+                        self.changed = true;
+                        i = name_end;
+                        continue;
+                    }
+                    _ if !active => name_end,
+                    // The directives are kept for the lexer, which switches
+                    // keyword sets; only their versions and nesting are checked
+                    "begin_keywords" if matches!(origin, Origin::Source { emit: true, .. }) => {
+                        let (specifier, end) = directive_string(text, name_end);
+                        let span = span_at(i, end);
+                        match specifier {
+                            Some(specifier)
+                                if KeywordVersion::from_specifier(&specifier).is_some() =>
+                            {
+                                keyword_regions += 1
+                            }
+                            Some(specifier) => {
+                                return Err((
+                                    format!("unknown `begin_keywords version \"{}\"", specifier),
+                                    span,
+                                ))
+                            }
+                            None => {
+                                return Err((
+                                    "`begin_keywords needs a version in quotes, like \"1800-2017\""
+                                        .to_string(),
+                                    span,
+                                ))
+                            }
+                        }
+                        name_end
+                    }
+                    "end_keywords" if matches!(origin, Origin::Source { emit: true, .. }) => {
+                        if keyword_regions == 0 {
+                            return Err((
+                                "`end_keywords without `begin_keywords".to_string(),
+                                span_at(i, name_end),
+                            ));
+                        }
+                        keyword_regions -= 1;
+                        name_end
+                    }
+                    "undef" => {
+                        let (macro_name, end) = directive_argument(text, name_end);
+                        self.macros.remove(&macro_name);
+                        self.changed = true;
+                        i = end;
+                        continue;
+                    }
+                    "define" => {
+                        let end = line_end(text, i);
+                        line += text[line_counted..i].iter().filter(|c| **c == '\n').count();
+                        line_counted = i;
+                        if let Some((name, definition)) = parse_define(&text[name_end..end]) {
+                            let site = match origin {
+                                Origin::Source { file, .. } => Some(DefinitionSite {
+                                    file: file.map(Path::to_path_buf),
+                                    span: (i, end),
+                                    line,
+                                }),
+                                Origin::Expansion(_) => None,
+                            };
+                            self.macros.insert(name, Macro { site, ..definition });
+                        }
+                        end
+                    }
+                    "include" => {
+                        let end = line_end(text, i);
+                        if let Origin::Source { file, .. } = origin {
+                            let directive = &text[name_end..end];
+                            self.read_include_macros(directive, file, (i, end), depth)?;
+                        }
+                        end
+                    }
+                    _ if emit && self.macros.contains_key(&name) => {
+                        i = self.expand(&name, text, i, name_end, origin, depth)?;
+                        continue;
+                    }
+                    _ => name_end,
+                }
+            } else {
+                i + 1
+            };
+
+            if emit && active {
+                for (k, c) in text.iter().enumerate().take(end).skip(i) {
+                    self.output.chars.push((*c, span_at(k, k + 1)));
+                }
+                if self.output.chars.len() > self.limits.max_output {
+                    let mut message = format!(
+                        "preprocessed text exceeds {} characters",
+                        self.limits.max_output
+                    );
+                    if !self.chain.is_empty() {
+                        message.push_str(&format!(" while expanding {}", chain_text(&self.chain)));
+                    }
+                    self.limit_exceeded = true;
+                    return Err((message, span_at(i, end)));
+                }
+            } else if emit {
+                self.changed = true;
+            }
+            i = end;
+        }
+
+        match conditionals.last() {
+            Some(open) => Err((
+                format!("missing `endif for {}", open.opening),
+                open.opening_span,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Record the branch of `open` ending at `end` if it was compiled
+    fn close_branch(&mut self, open: &Conditional, end: usize, origin: Origin) {
+        if open.active && matches!(origin, Origin::Source { emit: true, .. }) {
+            self.output.source_map.record_branch(
+                open.branch.clone(),
+                open.branch_span,
+                (open.body_start, end),
+            );
+        }
+    }
+
+    /// Replace the usage of macro `name` starting at `start` with its text,
+    /// returning the end of the usage
+    fn expand(
+        &mut self,
+        name: &str,
+        text: &[char],
+        start: usize,
+        name_end: usize,
+        origin: Origin,
+        depth: usize,
+    ) -> ExpandResult<usize> {
+        let definition = self.macros[name].clone();
+        let usage_span = |end: usize| origin.span(start, end);
+
+        let mut arguments = Vec::new();
+        let mut end = name_end;
+        if !definition.parameters.is_empty() {
+            let open = name_end
+                + text[name_end..]
+                    .iter()
+                    .take_while(|c| **c == ' ' || **c == '\t')
+                    .count();
+            if text.get(open) != Some(&'(') {
+                return Err((
+                    format!(
+                        "`{} expects {} arguments",
+                        name,
+                        definition.parameters.len()
+                    ),
+                    usage_span(name_end),
+                ));
+            }
+            let Some((found, close)) = macro_arguments(text, open) else {
+                return Err((
+                    format!("unterminated arguments to `{}", name),
+                    usage_span(text.len()),
+                ));
+            };
+            end = close + 1;
+            if found.len() != definition.parameters.len() {
+                return Err((
+                    format!(
+                        "`{} expects {} arguments, found {}",
+                        name,
+                        definition.parameters.len(),
+                        found.len()
+                    ),
+                    usage_span(end),
+                ));
+            }
+            arguments = found
+                .into_iter()
+                .map(|(from, to)| {
+                    (from..to)
+                        .map(|k| (text[k], origin.span(k, k + 1)))
+                        .collect()
+                })
+                .collect();
+        }
+
+        let span = usage_span(end);
+        self.chain.push(format!("`{}", name));
+        if depth >= self.limits.max_depth {
+            return Err((self.depth_error(&format!("expansion of `{}", name)), span));
+        }
+        if let Origin::Source { .. } = origin {
+            self.output.source_map.record_expansion(
+                name.to_string(),
+                span,
+                definition.site.clone(),
+            );
+        }
+        let (body, spans): (Vec<char>, Vec<Span>) =
+            substitute(&definition.value, &definition.parameters, &arguments, span)
+                .into_iter()
+                .unzip();
+        self.run(&body, Origin::Expansion(&spans), depth + 1)?;
+        self.chain.pop();
+        self.changed = true;
+        Ok(end)
+    }
+
+    /// Read the macros defined by the file an `include line names
+    ///
+    /// The file is parsed on its own later, which reports its errors, except
+    /// for a limit that reading it went past: that is reported at `span`, the
+    /// `include line, since parsing the file on its own starts afresh.
+    fn read_include_macros(
+        &mut self,
+        directive: &[char],
+        file: Option<&Path>,
+        span: Span,
+        depth: usize,
+    ) -> ExpandResult<()> {
+        let argument: String = directive.iter().collect();
+        let argument = argument.trim();
+        let Some(name) = argument
+            .strip_prefix('"')
+            .and_then(|rest| rest.split('"').next())
+            .or_else(|| {
+                argument
+                    .strip_prefix('<')
+                    .and_then(|rest| rest.split('>').next())
+            })
+        else {
+            return Ok(());
+        };
+        let Some((path, content)) = (self.read_include)(name, file) else {
+            return Ok(());
+        };
+        let canonical_path = canonical(&path);
+        if self.reading.contains(&canonical_path) {
+            return Ok(());
+        }
+        self.chain.push(format!("\"{}\"", name));
+        if depth >= self.limits.max_depth {
+            return Err((self.depth_error(&format!("`include \"{}\"", name)), span));
+        }
+
+        self.reading.push(canonical_path);
+        let text: Vec<char> = content.chars().collect();
+        let origin = Origin::Source {
+            file: Some(&path),
+            emit: false,
+        };
+        if let Err((message, _)) = self.run(&text, origin, depth + 1) {
+            if self.limit_exceeded {
+                return Err((message, span));
+            }
+        }
+        // An error in the file leaves what it was expanding on the chain
+        self.chain.truncate(depth);
+        self.reading.pop();
+        Ok(())
+    }
+
+    /// The error for going past the nesting limit with `what`, the last entry of the chain
+    fn depth_error(&mut self, what: &str) -> String {
+        self.limit_exceeded = true;
+        format!(
+            "{} is nested more than {} deep: {}",
+            what,
+            self.limits.max_depth,
+            chain_text(&self.chain)
+        )
+    }
+}
+
+/// `path` with symbolic links and relative parts resolved, if it exists
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// The macros and `include files of `chain`, outermost first, leaving out
+/// the middle of a long chain
+fn chain_text(chain: &[String]) -> String {
+    const SHOWN: usize = 3;
+    if chain.len() <= 2 * SHOWN + 1 {
+        return chain.join(" -> ");
+    }
+    format!(
+        "{} -> ... {} more -> {}",
+        chain[..SHOWN].join(" -> "),
+        chain.len() - 2 * SHOWN,
+        chain[chain.len() - SHOWN..].join(" -> ")
+    )
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '$'
+}
+
+fn identifier_end(text: &[char], start: usize) -> usize {
+    start
+        + text[start..]
+            .iter()
+            .take_while(|c| is_identifier_char(**c))
+            .count()
+}
+
+/// The end of the line holding `start`, before the newline
+fn line_end(text: &[char], start: usize) -> usize {
+    start + text[start..].iter().take_while(|c| **c != '\n').count()
+}
+
+/// The end of the text of directive `name`, whose name ends at `name_end`:
+/// its macro name, the rest of its line, or nothing more for a directive
+/// without arguments or one the standard does not define
+fn directive_end(text: &[char], name: &str, name_end: usize) -> usize {
+    match name {
+        "ifdef" | "ifndef" | "elsif" | "undef" => directive_argument(text, name_end).1,
+        "define" | "include" | "timescale" | "default_nettype" | "unconnected_drive" | "line"
+        | "pragma" | "begin_keywords" => {
+            let end = line_end(text, name_end);
+            end - text[name_end..end]
+                .iter()
+                .rev()
+                .take_while(|c| c.is_whitespace())
+                .count()
+        }
+        _ => name_end,
+    }
+}
+
+fn block_comment_end(text: &[char], start: usize) -> usize {
+    (start + 2..text.len())
+        .find(|&k| text[k] == '*' && text.get(k + 1) == Some(&'/'))
+        .map_or(text.len(), |k| k + 2)
+}
+
+/// The end of the string literal starting at `start`; an unterminated string
+/// ends at the end of its line
+fn string_end(text: &[char], start: usize) -> usize {
+    let mut k = start + 1;
+    while k < text.len() {
+        match text[k] {
+            '\\' => k += 2,
+            '"' => return k + 1,
+            '\n' => return k,
+            _ => k += 1,
+        }
+    }
+    text.len()
+}
+
+/// The identifier following a directive on the same line, and its end
+fn directive_argument(text: &[char], start: usize) -> (String, usize) {
+    let name_start = start
+        + text[start..]
+            .iter()
+            .take_while(|c| **c == ' ' || **c == '\t')
+            .count();
+    let end = identifier_end(text, name_start);
+    (text[name_start..end].iter().collect(), end)
+}
+
+/// The string literal following a directive on the same line, without its
+/// quotes, and its end; None if no string follows
+fn directive_string(text: &[char], start: usize) -> (Option<String>, usize) {
+    let string_start = start
+        + text[start..]
+            .iter()
+            .take_while(|c| **c == ' ' || **c == '\t')
+            .count();
+    if text.get(string_start) != Some(&'"') {
+        return (None, string_start);
+    }
+    let end = string_end(text, string_start);
+    let closed = end > string_start + 1 && text[end - 1] == '"';
+    let contents = text[string_start + 1..end - usize::from(closed)]
+        .iter()
+        .collect();
+    (Some(contents), end)
+}
+
+/// The name and macro of a `define line, from just after `define
+///
+/// A macro has formal arguments when a `(` follows its name directly.
+fn parse_define(text: &[char]) -> Option<(String, Macro)> {
+    let name_start = text.iter().take_while(|c| c.is_whitespace()).count();
+    let name_end = identifier_end(text, name_start);
+    if name_end == name_start {
+        return None;
+    }
+    let name: String = text[name_start..name_end].iter().collect();
+    let mut parameters = Vec::new();
+    let mut value_start = name_end;
+    if text.get(name_end) == Some(&'(') {
+        let close = name_end + text[name_end..].iter().position(|c| *c == ')')?;
+        let list: String = text[name_end + 1..close].iter().collect();
+        parameters = list
+            .split(',')
+            .map(|parameter| {
+                let parameter = parameter.split('=').next().unwrap_or_default();
+                parameter.trim().to_string()
+            })
+            .collect();
+        value_start = close + 1;
+    }
+    let value: String = text[value_start..].iter().collect();
+    Some((
+        name,
+        Macro {
+            parameters,
+            value: value.trim().to_string(),
+            site: None,
+        },
+    ))
+}
+
+/// The actual arguments of a macro usage whose `(` is at `open`, as the
+/// ranges of `text` they cover without surrounding whitespace, and the
+/// position of the `)`
+///
+/// Arguments are split at the commas outside nested parentheses, brackets,
+/// braces and strings, so `` `MAX(f(a, b), {c, d}) `` has two.
+fn macro_arguments(text: &[char], open: usize) -> Option<(Vec<(usize, usize)>, usize)> {
+    let trimmed = |from: usize, to: usize| {
+        let from = from
+            + text[from..to]
+                .iter()
+                .take_while(|c| c.is_whitespace())
+                .count();
+        let to = to
+            - text[from..to]
+                .iter()
+                .rev()
+                .take_while(|c| c.is_whitespace())
+                .count();
+        (from, to)
+    };
+    let mut arguments = Vec::new();
+    let mut argument_start = open + 1;
+    let mut nesting = 0;
+    let mut k = open + 1;
+    while k < text.len() {
+        match text[k] {
+            '"' => {
+                k = string_end(text, k);
+                continue;
+            }
+            '(' | '[' | '{' => nesting += 1,
+            ')' if nesting == 0 => {
+                arguments.push(trimmed(argument_start, k));
+                return Some((arguments, k));
+            }
+            ')' | ']' | '}' => nesting -= 1,
+            ',' if nesting == 0 => {
+                arguments.push(trimmed(argument_start, k));
+                argument_start = k + 1;
+            }
+            _ => {}
+        }
+        k += 1;
+    }
+    None
+}
+
+/// The text of a macro used at `usage` with its formal arguments replaced by
+/// the actual ones, each character with the span of the source text it comes from
+///
+/// ``` `` ``` joins the text on either side, and ``` `" ``` is a quote. Text
+/// substituted from an argument keeps the spans of the argument, so errors
+/// in it point at the code that was passed; the rest has the usage's span.
+/// The spans of arguments only ever move forward through the result: an
+/// argument used twice, or after one that follows it in the usage, gets the
+/// usage's span, so that the span of any run of the text is well-formed.
+fn substitute(
+    value: &str,
+    parameters: &[String],
+    arguments: &[Vec<(char, Span)>],
+    usage: Span,
+) -> Vec<(char, Span)> {
+    let text: Vec<char> = value.chars().collect();
+    let mut result = Vec::new();
+    let from_usage = |chars: &[char], result: &mut Vec<(char, Span)>| {
+        result.extend(chars.iter().map(|c| (*c, usage)));
+    };
+    let mut argument_floor = 0;
+    let mut k = 0;
+    while k < text.len() {
+        let c = text[k];
+        if c == '`' && text.get(k + 1) == Some(&'`') {
+            k += 2;
+        } else if c == '`' && text.get(k + 1) == Some(&'"') {
+            from_usage(&['"'], &mut result);
+            k += 2;
+        } else if c == '`' && text.get(k + 1).is_some_and(|c| is_identifier_start(*c)) {
+            // A macro usage; its name is not a formal argument
+            let end = identifier_end(text.as_slice(), k + 1);
+            from_usage(&text[k..end], &mut result);
+            k = end;
+        } else if c == '"' {
+            let end = string_end(&text, k);
+            from_usage(&text[k..end], &mut result);
+            k = end;
+        } else if is_identifier_start(c) {
+            let end = identifier_end(&text, k);
+            let word: String = text[k..end].iter().collect();
+            match parameters.iter().position(|parameter| *parameter == word) {
+                Some(index) => {
+                    for &(c, span) in &arguments[index] {
+                        if span.0 >= argument_floor {
+                            argument_floor = span.0;
+                            result.push((c, span));
+                        } else {
+                            result.push((c, usage));
+                        }
+                    }
+                }
+                None => from_usage(&text[k..end], &mut result),
+            }
+            k = end;
+        } else {
+            from_usage(&[c], &mut result);
+            k += 1;
+        }
+    }
+    result
+}
 
 #[derive(Debug, Clone)]
 pub struct Preprocessor {
@@ -19,7 +903,7 @@ impl Preprocessor {
     }
 
     pub fn preprocess_file(&mut self, file_path: &Path) -> Result<String, ParseError> {
-        let content = fs::read_to_string(file_path).map_err(|e| {
+        let content = files::read_to_string(file_path).map_err(|e| {
             ParseError::new(SingleParseError::new(
                 format!("Failed to read file {}: {}", file_path.display(), e),
                 ParseErrorType::PreprocessorError,
@@ -102,9 +986,9 @@ impl Preprocessor {
     ) -> Result<String, ParseError> {
         // Parse `include "filename" or `include <filename>
         let filename = include_content.trim();
-        let filename = if filename.starts_with('"') && filename.ends_with('"') {
-            &filename[1..filename.len() - 1]
-        } else if filename.starts_with('<') && filename.ends_with('>') {
+        let filename = if (filename.starts_with('"') && filename.ends_with('"'))
+            || (filename.starts_with('<') && filename.ends_with('>'))
+        {
             &filename[1..filename.len() - 1]
         } else {
             filename
@@ -117,7 +1001,7 @@ impl Preprocessor {
         if let Some(current) = current_file {
             if let Some(parent) = current.parent() {
                 let candidate = parent.join(filename);
-                if candidate.exists() {
+                if files::exists(&candidate) {
                     found_path = Some(candidate);
                 }
             }
@@ -127,7 +1011,7 @@ impl Preprocessor {
         if found_path.is_none() {
             for inc_dir in &self.include_dirs {
                 let candidate = inc_dir.join(filename);
-                if candidate.exists() {
+                if files::exists(&candidate) {
                     found_path = Some(candidate);
                     break;
                 }
@@ -173,7 +1057,7 @@ impl Preprocessor {
                 let expanded_words: Vec<String> = words
                     .iter()
                     .map(|word| {
-                        if word == &macro_name {
+                        if *word == macro_name {
                             macro_value.clone()
                         } else {
                             word.to_string()
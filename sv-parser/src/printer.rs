@@ -0,0 +1,2072 @@
+//! Pretty-printer and round-trip check
+//!
+//! [`print`] renders a parsed [`SourceUnit`] back as SystemVerilog, one
+//! declaration or statement per line, with compound operands parenthesized.
+//! [`round_trip`] parses the printed text again and compares the two trees
+//! node by node, ignoring spans, so anything the printer drops or the parser
+//! reads differently the second time shows up as a [`Divergence`]
+//! (`--round-trip` on the command line). What the parser never keeps, such
+//! as comments and event controls, can't be checked this way.
+
+use std::fmt;
+
+use regex::Regex;
+
+use crate::lexer::is_keyword;
+use crate::simplify::{binary_symbol, inc_dec_symbol, unary_symbol};
+use crate::{
+    AssignmentOp, BinaryOp, CellReference, ClassItem, ClassParameter, ClassQualifier, ConfigRule,
+    ConstraintExpr, CycleBound, CycleRange, DataType, Delay, Edge, EventControl, ExprRef,
+    Expression, Label, LibrarySelection, ModuleItem, ModuleItemRef, PackageImport,
+    ParameterArgument, ParseError, Pattern, Port, PortConnection, PortDirection,
+    ProceduralBlockType, PropertyExpr, PropertyOp, RandQualifier, Range, SourceUnit, Statement,
+    StmtRef, SystemVerilogParser, TimingControl, UnpackedDimension,
+};
+
+const INDENT: &str = "    ";
+
+/// Render a parsed file as SystemVerilog
+pub fn print(source_unit: &SourceUnit) -> String {
+    let mut printer = Printer {
+        source_unit,
+        output: String::new(),
+        depth: 0,
+    };
+    printer.items(&source_unit.items);
+    printer.output
+}
+
+/// Render one expression of a parsed file as SystemVerilog
+pub fn print_expression(source_unit: &SourceUnit, expr_ref: ExprRef) -> String {
+    let printer = Printer {
+        source_unit,
+        output: String::new(),
+        depth: 0,
+    };
+    printer.expr(expr_ref)
+}
+
+/// First node of one kind that differs between the parsed and the reparsed tree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    /// Kind and arena index of the node, like "statement 4"
+    pub node: String,
+    /// The node as parsed from the file, None if the reparsed tree has more nodes
+    pub original: Option<String>,
+    /// The node as parsed from the printed text, None if it has fewer nodes
+    pub reparsed: Option<String>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let missing = "(missing)".to_string();
+        write!(
+            f,
+            "{} differs: {} became {}",
+            self.node,
+            self.original.as_ref().unwrap_or(&missing),
+            self.reparsed.as_ref().unwrap_or(&missing)
+        )
+    }
+}
+
+/// Why a file does not survive printing and parsing again
+#[derive(Debug, Clone)]
+pub enum RoundTripError {
+    /// The printed text does not parse
+    Unparsable(ParseError),
+    /// The trees differ
+    Diverged(Vec<Divergence>),
+}
+
+impl fmt::Display for RoundTripError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundTripError::Unparsable(err) => write!(f, "printed text does not parse: {}", err),
+            RoundTripError::Diverged(divergences) => {
+                for (index, divergence) in divergences.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "{}", divergence)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Print `source_unit`, parse the text again with `parser`, as a header if
+/// `header` is set, and compare the two trees
+pub fn round_trip(
+    parser: &SystemVerilogParser,
+    source_unit: &SourceUnit,
+    header: bool,
+) -> Result<(), RoundTripError> {
+    let printed = print(source_unit);
+    let reparsed = if header {
+        parser.parse_header_content(&printed)
+    } else {
+        parser.parse_content(&printed)
+    }
+    .map_err(RoundTripError::Unparsable)?;
+
+    let divergences = compare(source_unit, &reparsed);
+    if divergences.is_empty() {
+        Ok(())
+    } else {
+        Err(RoundTripError::Diverged(divergences))
+    }
+}
+
+/// The first difference in the top-level items and in each arena
+///
+/// Flattening the same tree allocates its nodes in the same order, so equal
+/// trees have equal arenas once spans are left out.
+fn compare(original: &SourceUnit, reparsed: &SourceUnit) -> Vec<Divergence> {
+    let spans = Regex::new(r"\(\d+, \d+\)").expect("valid regex");
+    [
+        first_difference(
+            "top-level item",
+            debug(&original.items, &spans),
+            debug(&reparsed.items, &spans),
+        ),
+        first_difference(
+            "module item",
+            debug(&original.module_item_arena.nodes, &spans),
+            debug(&reparsed.module_item_arena.nodes, &spans),
+        ),
+        first_difference(
+            "statement",
+            debug(&original.stmt_arena.nodes, &spans),
+            debug(&reparsed.stmt_arena.nodes, &spans),
+        ),
+        first_difference(
+            "expression",
+            debug(&original.expr_arena.nodes, &spans),
+            debug(&reparsed.expr_arena.nodes, &spans),
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Debug text of each node with its spans replaced by `_`
+fn debug<T: fmt::Debug>(nodes: &[T], spans: &Regex) -> Vec<String> {
+    nodes
+        .iter()
+        .map(|node| spans.replace_all(&format!("{:?}", node), "_").into_owned())
+        .collect()
+}
+
+fn first_difference(
+    kind: &str,
+    original: Vec<String>,
+    reparsed: Vec<String>,
+) -> Option<Divergence> {
+    (0..original.len().max(reparsed.len()))
+        .find(|index| original.get(*index) != reparsed.get(*index))
+        .map(|index| Divergence {
+            node: format!("{} {}", kind, index),
+            original: original.get(index).cloned(),
+            reparsed: reparsed.get(index).cloned(),
+        })
+}
+
+struct Printer<'a> {
+    source_unit: &'a SourceUnit,
+    output: String,
+    depth: usize,
+}
+
+impl Printer<'_> {
+    fn line(&mut self, text: &str) {
+        for _ in 0..self.depth {
+            self.output.push_str(INDENT);
+        }
+        self.output.push_str(text);
+        self.output.push('\n');
+    }
+
+    fn items(&mut self, items: &[ModuleItemRef]) {
+        let source_unit = self.source_unit;
+        let arena = &source_unit.module_item_arena;
+        let mut index = 0;
+        while index < items.len() {
+            let item = arena.get(items[index]);
+            // A net declaration assignment was split into the net and an
+            // implicit assignment; print them as one declaration again
+            if let (
+                ModuleItem::VariableDeclaration {
+                    initial_value: None,
+                    ..
+                },
+                Some(ModuleItem::Assignment {
+                    expr,
+                    implicit: true,
+                    ..
+                }),
+            ) = (item, items.get(index + 1).map(|next| arena.get(*next)))
+            {
+                let declaration = self.variable_declaration(item, Some(*expr));
+                self.line(&declaration);
+                index += 2;
+                continue;
+            }
+            self.item(item);
+            index += 1;
+        }
+    }
+
+    fn item(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::ModuleDeclaration {
+                name, ports, items, ..
+            } => self.design_unit("module", name, ports, items),
+            ModuleItem::InterfaceDeclaration {
+                name, ports, items, ..
+            } => self.design_unit("interface", name, ports, items),
+            ModuleItem::CheckerDeclaration {
+                name, ports, items, ..
+            } => self.design_unit("checker", name, ports, items),
+            ModuleItem::PackageDeclaration { name, items, .. } => {
+                self.design_unit("package", name, &[], items)
+            }
+            ModuleItem::ImportDeclaration { imports, .. } => {
+                self.line(&format!("import {};", package_imports(imports)));
+            }
+            ModuleItem::ExportDeclaration { exports, .. } => {
+                self.line(&format!("export {};", package_imports(exports)));
+            }
+            ModuleItem::ModportDeclaration { modports, .. } => {
+                let modports: Vec<String> = modports
+                    .iter()
+                    .map(|modport| {
+                        let ports: Vec<String> = modport
+                            .ports
+                            .iter()
+                            .map(|port| {
+                                format!("{} {}", port_direction(&port.direction), ident(&port.name))
+                            })
+                            .collect();
+                        format!("{}({})", ident(&modport.name), ports.join(", "))
+                    })
+                    .collect();
+                self.line(&format!("modport {};", modports.join(", ")));
+            }
+            ModuleItem::PortDeclaration {
+                direction,
+                port_type,
+                name,
+                ..
+            } => self.line(&format!(
+                "{} {} {};",
+                port_direction(direction),
+                port_type,
+                ident(name)
+            )),
+            ModuleItem::VariableDeclaration { initial_value, .. } => {
+                let declaration = self.variable_declaration(item, *initial_value);
+                self.line(&declaration);
+            }
+            ModuleItem::TypedefDeclaration {
+                data_type,
+                signing,
+                range,
+                name,
+                unpacked_dimensions,
+                ..
+            } => {
+                let mut text = format!("typedef {}", self.data_type(data_type));
+                if let Some(signing) = signing {
+                    text.push(' ');
+                    text.push_str(signing);
+                }
+                if let Some(range) = range {
+                    text.push(' ');
+                    text.push_str(&range_text(range));
+                }
+                text.push(' ');
+                text.push_str(&ident(name));
+                for dimension in unpacked_dimensions {
+                    text.push_str(&dimension_text(dimension));
+                }
+                text.push(';');
+                self.line(&text);
+            }
+            ModuleItem::Assignment {
+                drive_strength,
+                delay,
+                target,
+                expr,
+                ..
+            } => {
+                let strength = drive_strength
+                    .as_ref()
+                    .map(|strength| format!("({}, {}) ", strength.strength0, strength.strength1))
+                    .unwrap_or_default();
+                let delay = delay.as_ref().map(delay_text).unwrap_or_default();
+                let assignment = format!(
+                    "assign {}{}{} = {};",
+                    strength,
+                    delay,
+                    self.expr(*target),
+                    self.expr(*expr)
+                );
+                self.line(&assignment);
+            }
+            ModuleItem::ProceduralBlock {
+                block_type,
+                event_control,
+                statements,
+                label,
+                end_label,
+                ..
+            } => {
+                let keyword = match block_type {
+                    ProceduralBlockType::Initial => "initial",
+                    ProceduralBlockType::Final => "final",
+                    ProceduralBlockType::Always => "always",
+                    ProceduralBlockType::AlwaysComb => "always_comb",
+                    ProceduralBlockType::AlwaysFF => "always_ff",
+                };
+                let header = match event_control {
+                    Some(control) => format!("{} {} begin", keyword, self.event_control(control)),
+                    None => format!("{} begin", keyword),
+                };
+                self.block(
+                    &header,
+                    label.as_ref(),
+                    statements,
+                    "end",
+                    end_label.as_ref(),
+                );
+            }
+            ModuleItem::DefineDirective {
+                name,
+                parameters,
+                value,
+                ..
+            } => {
+                let mut text = format!("`define {}", name);
+                if !parameters.is_empty() {
+                    text.push_str(&format!("({})", parameters.join(", ")));
+                }
+                if !value.is_empty() {
+                    text.push(' ');
+                    text.push_str(value);
+                }
+                self.line(&text);
+            }
+            ModuleItem::IncludeDirective { path, .. } => {
+                self.line(&format!("`include \"{}\"", path))
+            }
+            ModuleItem::TimescaleDirective {
+                unit, precision, ..
+            } => self.line(&format!("`timescale {}/{}", unit.text(), precision.text())),
+            ModuleItem::DefaultNettypeDirective { net_type, .. } => {
+                self.line(&format!("`default_nettype {}", net_type))
+            }
+            ModuleItem::TimeunitsDeclaration {
+                unit, precision, ..
+            } => {
+                let text = match (unit, precision) {
+                    (Some(unit), Some(precision)) => {
+                        format!("timeunit {} / {};", unit.text(), precision.text())
+                    }
+                    (Some(unit), None) => format!("timeunit {};", unit.text()),
+                    (None, Some(precision)) => format!("timeprecision {};", precision.text()),
+                    (None, None) => return,
+                };
+                self.line(&text);
+            }
+            ModuleItem::ClassDeclaration {
+                is_virtual,
+                name,
+                parameters,
+                extends,
+                implements,
+                items,
+                ..
+            } => {
+                let mut header = if *is_virtual {
+                    format!("virtual class {}", ident(name))
+                } else {
+                    format!("class {}", ident(name))
+                };
+                if !parameters.is_empty() {
+                    let parameters: Vec<String> = parameters
+                        .iter()
+                        .map(|parameter| self.class_parameter(parameter))
+                        .collect();
+                    header.push_str(&format!(" #({})", parameters.join(", ")));
+                }
+                if let Some(base) = extends {
+                    header.push_str(&format!(" extends {}", ident(base)));
+                }
+                if !implements.is_empty() {
+                    let names: Vec<String> = implements.iter().map(|name| ident(name)).collect();
+                    header.push_str(&format!(" implements {}", names.join(", ")));
+                }
+                self.line(&format!("{};", header));
+                self.class_items(items);
+                self.line("endclass");
+            }
+            ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                name,
+                parameters,
+                body,
+                ..
+            } => {
+                let mut text = "function ".to_string();
+                if let Some(return_type) = return_type {
+                    text.push_str(&type_name(return_type));
+                    text.push(' ');
+                }
+                text.push_str(&format!("{}::", ident(class_name)));
+                if name == "new" {
+                    text.push_str(name);
+                } else {
+                    text.push_str(&ident(name));
+                }
+                let parameters: Vec<String> = parameters.iter().map(|name| ident(name)).collect();
+                self.line(&format!("{}({});", text, parameters.join(", ")));
+                self.statements(body);
+                self.line("endfunction");
+            }
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                is_automatic,
+                return_type,
+                name,
+                parameters,
+                body,
+                ..
+            } => {
+                let mut text = if *is_task { "task " } else { "function " }.to_string();
+                if *is_automatic {
+                    text.push_str("automatic ");
+                }
+                if let Some(return_type) = return_type {
+                    text.push_str(&type_name(return_type));
+                    text.push(' ');
+                }
+                text.push_str(&ident(name));
+                let parameters: Vec<String> = parameters.iter().map(|name| ident(name)).collect();
+                self.line(&format!("{}({});", text, parameters.join(", ")));
+                self.statements(body);
+                self.line(if *is_task { "endtask" } else { "endfunction" });
+            }
+            ModuleItem::InterfaceClassDeclaration {
+                name,
+                extends,
+                items,
+                ..
+            } => {
+                let mut header = format!("interface class {}", ident(name));
+                if !extends.is_empty() {
+                    let names: Vec<String> = extends.iter().map(|name| ident(name)).collect();
+                    header.push_str(&format!(" extends {}", names.join(", ")));
+                }
+                self.line(&format!("{};", header));
+                self.class_items(items);
+                self.line("endclass");
+            }
+            ModuleItem::ConcurrentAssertion {
+                kind,
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => self.assertion(
+                kind.keyword(),
+                *clocking_event,
+                *disable_iff,
+                property,
+                *pass_action,
+                *fail_action,
+            ),
+            ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                ports,
+                clocking_event,
+                disable_iff,
+                body,
+                end_label,
+                ..
+            } => {
+                let keyword = kind.keyword();
+                if ports.is_empty() {
+                    self.line(&format!("{} {};", keyword, ident(name)));
+                } else {
+                    let ports: Vec<String> = ports.iter().map(port_text).collect();
+                    self.line(&format!(
+                        "{} {}({});",
+                        keyword,
+                        ident(name),
+                        ports.join(", ")
+                    ));
+                }
+                self.depth += 1;
+                let body = self.clocked_property(*clocking_event, *disable_iff, body);
+                self.line(&format!("{};", body));
+                self.depth -= 1;
+                match end_label {
+                    Some(label) => self.line(&format!("end{} : {}", keyword, ident(&label.name))),
+                    None => self.line(&format!("end{}", keyword)),
+                }
+            }
+            ModuleItem::DefaultClocking { name, .. } => {
+                self.line(&format!("default clocking {};", ident(name)))
+            }
+            ModuleItem::DefaultDisableIff { condition, .. } => {
+                let condition = self.expr(*condition);
+                self.line(&format!("default disable iff ({});", condition));
+            }
+            ModuleItem::GlobalClocking {
+                identifier,
+                clocking_event,
+                end_label,
+                ..
+            } => {
+                let mut header = "global clocking ".to_string();
+                if let Some(identifier) = identifier {
+                    header.push_str(&ident(identifier));
+                    header.push(' ');
+                }
+                header.push_str(&self.clocking_event(*clocking_event));
+                self.line(&format!("{};", header));
+                match end_label {
+                    Some(label) => self.line(&format!("endclocking : {}", ident(label))),
+                    None => self.line("endclocking"),
+                }
+            }
+            ModuleItem::ClockingBlock {
+                default,
+                name,
+                clocking_event,
+                default_input_skew,
+                default_output_skew,
+                signals,
+                end_label,
+                ..
+            } => {
+                let keyword = if *default {
+                    "default clocking"
+                } else {
+                    "clocking"
+                };
+                let event = self.clocking_event(*clocking_event);
+                self.line(&format!("{} {} {};", keyword, ident(name), event));
+                self.depth += 1;
+                let mut skews = Vec::new();
+                if let Some(skew) = default_input_skew {
+                    skews.push(format!("input {}", skew));
+                }
+                if let Some(skew) = default_output_skew {
+                    skews.push(format!("output {}", skew));
+                }
+                if !skews.is_empty() {
+                    self.line(&format!("default {};", skews.join(" ")));
+                }
+                // Signals declared together share the span of their declaration
+                for declaration in signals.chunk_by(|a, b| a.span == b.span) {
+                    let mut text = port_direction(&declaration[0].direction).to_string();
+                    if let Some(skew) = &declaration[0].skew {
+                        text.push(' ');
+                        text.push_str(skew);
+                    }
+                    let names: Vec<String> = declaration
+                        .iter()
+                        .map(|signal| ident(&signal.name))
+                        .collect();
+                    self.line(&format!("{} {};", text, names.join(", ")));
+                }
+                self.depth -= 1;
+                match end_label {
+                    Some(label) => self.line(&format!("endclocking : {}", ident(&label.name))),
+                    None => self.line("endclocking"),
+                }
+            }
+            ModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                value,
+                ..
+            } => {
+                let mut text = if *local { "localparam " } else { "parameter " }.to_string();
+                if let Some(data_type) = data_type {
+                    text.push_str(data_type);
+                    text.push(' ');
+                }
+                if let Some(range) = range {
+                    text.push_str(&range_text(range));
+                    text.push(' ');
+                }
+                let text = format!("{}{} = {};", text, ident(name), self.expr(*value));
+                self.line(&text);
+            }
+            ModuleItem::ModuleInstantiation {
+                module_name,
+                parameters,
+                instance_name,
+                connections,
+                ..
+            } => {
+                let mut text = ident(module_name);
+                if !parameters.is_empty() {
+                    text.push_str(&format!(" #({})", self.connections(parameters)));
+                }
+                let text = format!(
+                    "{} {} ({});",
+                    text,
+                    ident(instance_name),
+                    self.connections(connections)
+                );
+                self.line(&text);
+            }
+            ModuleItem::ConfigDeclaration {
+                name,
+                design,
+                rules,
+                ..
+            } => {
+                self.line(&format!("config {};", ident(name)));
+                self.depth += 1;
+                let cells: Vec<String> = design.iter().map(cell_text).collect();
+                self.line(&format!("design {};", cells.join(" ")));
+                for rule in rules {
+                    let text = match rule {
+                        ConfigRule::Default { liblist, .. } => {
+                            format!("default {}", liblist_text(liblist))
+                        }
+                        ConfigRule::Instance {
+                            path, selection, ..
+                        } => format!("instance {} {}", path, selection_text(selection)),
+                        ConfigRule::Cell {
+                            cell, selection, ..
+                        } => format!("cell {} {}", cell_text(cell), selection_text(selection)),
+                    };
+                    self.line(&format!("{};", text));
+                }
+                self.depth -= 1;
+                self.line("endconfig");
+            }
+            ModuleItem::ElaborationTask {
+                severity,
+                arguments,
+                ..
+            } => {
+                let call = self.call(&format!("${}", severity.name()), arguments);
+                self.line(&format!("{};", call));
+            }
+            ModuleItem::GenvarDeclaration { genvars, .. } => {
+                let names: Vec<String> = genvars.iter().map(|genvar| ident(&genvar.name)).collect();
+                self.line(&format!("genvar {};", names.join(", ")));
+            }
+            ModuleItem::GenerateRegion { items, .. } => {
+                self.line("generate");
+                self.depth += 1;
+                self.items(items);
+                self.depth -= 1;
+                self.line("endgenerate");
+            }
+            ModuleItem::GenerateBlock {
+                label,
+                items,
+                end_label,
+                ..
+            } => {
+                match label {
+                    Some(label) => self.line(&format!("begin : {}", ident(&label.name))),
+                    None => self.line("begin"),
+                }
+                self.depth += 1;
+                self.items(items);
+                self.depth -= 1;
+                match end_label {
+                    Some(label) => self.line(&format!("end : {}", ident(&label.name))),
+                    None => self.line("end"),
+                }
+            }
+            ModuleItem::GenerateFor {
+                declares_genvar,
+                genvar,
+                init,
+                condition,
+                step_op,
+                step,
+                body,
+                ..
+            } => {
+                let genvar = ident(genvar);
+                let text = format!(
+                    "for ({}{} = {}; {}; {} {} {})",
+                    if *declares_genvar { "genvar " } else { "" },
+                    genvar,
+                    self.expr(*init),
+                    self.expr(*condition),
+                    genvar,
+                    assignment_symbol(step_op),
+                    self.expr(*step)
+                );
+                self.line(&text);
+                self.generate_body(*body);
+            }
+            ModuleItem::GenerateIf {
+                condition,
+                then_item,
+                else_item,
+                ..
+            } => {
+                let text = format!("if ({})", self.expr(*condition));
+                self.line(&text);
+                self.generate_body(*then_item);
+                if let Some(else_item) = else_item {
+                    self.line("else");
+                    self.generate_body(*else_item);
+                }
+            }
+            ModuleItem::GenerateCase { expr, items, .. } => {
+                let text = format!("case ({})", self.expr(*expr));
+                self.line(&text);
+                self.depth += 1;
+                for item in items {
+                    if item.values.is_empty() {
+                        self.line("default:");
+                    } else {
+                        let values: Vec<String> = item
+                            .values
+                            .iter()
+                            .map(|value| self.case_value(*value))
+                            .collect();
+                        self.line(&format!("{}:", values.join(", ")));
+                    }
+                    self.generate_body(item.body);
+                }
+                self.depth -= 1;
+                self.line("endcase");
+            }
+        }
+    }
+
+    /// The body of a generate loop or branch, one level deeper
+    fn generate_body(&mut self, body: ModuleItemRef) {
+        let source_unit = self.source_unit;
+        self.depth += 1;
+        self.item(source_unit.module_item_arena.get(body));
+        self.depth -= 1;
+    }
+
+    /// `module` or `interface` with its ports and items
+    fn design_unit(&mut self, keyword: &str, name: &str, ports: &[Port], items: &[ModuleItemRef]) {
+        if ports.is_empty() {
+            self.line(&format!("{} {};", keyword, ident(name)));
+        } else {
+            let ports: Vec<String> = ports.iter().map(port_text).collect();
+            self.line(&format!(
+                "{} {}({});",
+                keyword,
+                ident(name),
+                ports.join(", ")
+            ));
+        }
+        self.depth += 1;
+        self.items(items);
+        self.depth -= 1;
+        self.line(&format!("end{}", keyword));
+    }
+
+    /// A variable or net declaration, with `initial_value` as its value
+    fn variable_declaration(&self, item: &ModuleItem, initial_value: Option<ExprRef>) -> String {
+        let ModuleItem::VariableDeclaration {
+            data_type,
+            signing,
+            drive_strength,
+            delay,
+            range,
+            name,
+            unpacked_dimensions,
+            ..
+        } = item
+        else {
+            unreachable!("only called with variable declarations");
+        };
+        let mut text = self.data_type(data_type);
+        if let Some(signing) = signing {
+            text.push(' ');
+            text.push_str(signing);
+        }
+        if let Some(strength) = drive_strength {
+            text.push_str(&format!(
+                " ({}, {})",
+                strength.strength0, strength.strength1
+            ));
+        }
+        if let Some(range) = range {
+            text.push(' ');
+            text.push_str(&range_text(range));
+        }
+        if let Some(delay) = delay {
+            text.push(' ');
+            text.push_str(delay_text(delay).trim_end());
+        }
+        text.push(' ');
+        text.push_str(&ident(name));
+        for dimension in unpacked_dimensions {
+            text.push_str(&dimension_text(dimension));
+        }
+        if let Some(value) = initial_value {
+            text.push_str(&format!(" = {}", self.expr(value)));
+        }
+        text.push(';');
+        text
+    }
+
+    /// A parameter port of a class: `type T = int`
+    fn class_parameter(&self, parameter: &ClassParameter) -> String {
+        let mut text = if parameter.is_type {
+            "type ".to_string()
+        } else {
+            parameter
+                .data_type
+                .as_ref()
+                .map(|data_type| format!("{} ", type_name(data_type)))
+                .unwrap_or_default()
+        };
+        text.push_str(&ident(&parameter.name));
+        if let Some(default) = &parameter.default {
+            text.push_str(&format!(" = {}", self.parameter_argument(default)));
+        }
+        text
+    }
+
+    fn parameter_argument(&self, argument: &ParameterArgument) -> String {
+        match argument {
+            ParameterArgument::Type(data_type) => data_type.clone(),
+            ParameterArgument::Value(value) => self.expr(*value),
+        }
+    }
+
+    /// A data type, with the members of an inline struct, union or enum type
+    fn data_type(&self, data_type: &DataType) -> String {
+        match data_type {
+            DataType::Named(name) => type_name(name),
+            DataType::Specialized(reference) => {
+                let arguments: Vec<String> = reference
+                    .arguments
+                    .iter()
+                    .map(|argument| self.parameter_argument(argument))
+                    .collect();
+                format!("{}#({})", ident(&reference.name), arguments.join(", "))
+            }
+            DataType::Struct { packed, members }
+            | DataType::Union {
+                packed, members, ..
+            } => {
+                let members: Vec<String> = members
+                    .iter()
+                    .map(|member| {
+                        let range = member
+                            .range
+                            .as_ref()
+                            .map(|range| format!("{} ", range_text(range)))
+                            .unwrap_or_default();
+                        format!(
+                            "{} {}{};",
+                            self.data_type(&member.data_type),
+                            range,
+                            ident(&member.name)
+                        )
+                    })
+                    .collect();
+                let tagged = match data_type {
+                    DataType::Union { tagged: true, .. } => " tagged",
+                    _ => "",
+                };
+                let packed = if *packed { " packed" } else { "" };
+                format!(
+                    "{}{}{} {{ {} }}",
+                    data_type.name(),
+                    tagged,
+                    packed,
+                    members.join(" ")
+                )
+            }
+            DataType::Enum(enum_type) => {
+                let mut text = "enum".to_string();
+                for part in [
+                    enum_type.base_type.clone(),
+                    enum_type.signing.clone(),
+                    enum_type.range.as_ref().map(range_text),
+                ]
+                .into_iter()
+                .flatten()
+                {
+                    text.push(' ');
+                    text.push_str(&part);
+                }
+                let members: Vec<String> = enum_type
+                    .members
+                    .iter()
+                    .map(|member| match member.value {
+                        Some(value) => format!("{} = {}", ident(&member.name), self.expr(value)),
+                        None => ident(&member.name),
+                    })
+                    .collect();
+                text.push_str(&format!(" {{ {} }}", members.join(", ")));
+                text
+            }
+        }
+    }
+
+    fn class_items(&mut self, items: &[ClassItem]) {
+        self.depth += 1;
+        for item in items {
+            match item {
+                ClassItem::Property {
+                    qualifier,
+                    is_static,
+                    data_type,
+                    name,
+                    unpacked_dimensions,
+                    initial_value,
+                    rand,
+                    ..
+                } => {
+                    let mut text = qualifier_text(qualifier.as_ref());
+                    if *is_static {
+                        text.push_str("static ");
+                    }
+                    match rand {
+                        Some(RandQualifier::Rand) => text.push_str("rand "),
+                        Some(RandQualifier::Randc) => text.push_str("randc "),
+                        None => {}
+                    }
+                    text.push_str(&format!("{} {}", self.data_type(data_type), ident(name)));
+                    for dimension in unpacked_dimensions {
+                        text.push_str(&dimension_text(dimension));
+                    }
+                    if let Some(value) = initial_value {
+                        text.push_str(&format!(" = {}", self.expr(*value)));
+                    }
+                    self.line(&format!("{};", text));
+                }
+                ClassItem::Method {
+                    qualifier,
+                    is_virtual,
+                    is_pure,
+                    is_static,
+                    is_extern,
+                    is_task,
+                    return_type,
+                    name,
+                    parameters,
+                    body,
+                    ..
+                } => {
+                    let mut text = if *is_extern {
+                        "extern ".to_string()
+                    } else {
+                        String::new()
+                    };
+                    if *is_pure {
+                        text.push_str("pure virtual ");
+                    }
+                    text.push_str(&qualifier_text(qualifier.as_ref()));
+                    if *is_virtual && !*is_pure {
+                        text.push_str("virtual ");
+                    }
+                    if *is_static {
+                        text.push_str("static ");
+                    }
+                    text.push_str(if *is_task { "task " } else { "function " });
+                    if let Some(return_type) = return_type {
+                        text.push_str(&type_name(return_type));
+                        text.push(' ');
+                    }
+                    // The constructor is the only method named by a keyword
+                    if item.is_constructor() {
+                        text.push_str(name);
+                    } else {
+                        text.push_str(&ident(name));
+                    }
+                    let parameters: Vec<String> =
+                        parameters.iter().map(|name| ident(name)).collect();
+                    self.line(&format!("{}({});", text, parameters.join(", ")));
+                    if !*is_extern && !*is_pure {
+                        self.statements(body);
+                        self.line(if *is_task { "endtask" } else { "endfunction" });
+                    }
+                }
+                ClassItem::Constraint(block) => {
+                    let keyword = if block.is_static {
+                        "static constraint"
+                    } else {
+                        "constraint"
+                    };
+                    self.line(&format!("{} {} {{", keyword, ident(&block.name)));
+                    self.depth += 1;
+                    for constraint in &block.constraints {
+                        self.line(&self.constraint(constraint));
+                    }
+                    self.depth -= 1;
+                    self.line("}");
+                }
+            }
+        }
+        self.depth -= 1;
+    }
+
+    /// A constraint on one line, nested constraints in braces
+    fn constraint(&self, constraint: &ConstraintExpr) -> String {
+        let soft = |soft: bool| if soft { "soft " } else { "" };
+        match constraint {
+            ConstraintExpr::Expression {
+                soft: is_soft,
+                expr,
+                ..
+            } => {
+                format!("{}{};", soft(*is_soft), self.expr(*expr))
+            }
+            ConstraintExpr::Inside {
+                soft: is_soft,
+                expr,
+                set,
+                ..
+            } => {
+                let set: Vec<String> = set.iter().map(|value| self.expr(*value)).collect();
+                format!(
+                    "{}{} inside {{{}}};",
+                    soft(*is_soft),
+                    self.expr(*expr),
+                    set.join(", ")
+                )
+            }
+            ConstraintExpr::Dist {
+                soft: is_soft,
+                expr,
+                items,
+                ..
+            } => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| {
+                        let op = if item.per_range { ":/" } else { ":=" };
+                        format!(
+                            "{} {} {}",
+                            self.case_value(item.value),
+                            op,
+                            self.expr(item.weight)
+                        )
+                    })
+                    .collect();
+                format!(
+                    "{}{} dist {{{}}};",
+                    soft(*is_soft),
+                    self.expr(*expr),
+                    items.join(", ")
+                )
+            }
+            ConstraintExpr::Implication {
+                condition,
+                constraints,
+                ..
+            } => {
+                // A condition that is itself an implication would take the
+                // constraints with it
+                let condition_text = match self.source_unit.expr_arena.get(*condition) {
+                    expr if expr.precedence() <= BinaryOp::LogicalImpl.precedence() => {
+                        format!("({})", self.expr(*condition))
+                    }
+                    _ => self.expr(*condition),
+                };
+                format!("{} -> {}", condition_text, self.constraint_set(constraints))
+            }
+            ConstraintExpr::IfElse {
+                condition,
+                then_constraints,
+                else_constraints,
+                ..
+            } => {
+                let mut text = format!(
+                    "if ({}) {}",
+                    self.expr(*condition),
+                    self.constraint_set(then_constraints)
+                );
+                if !else_constraints.is_empty() {
+                    text.push_str(&format!(" else {}", self.constraint_set(else_constraints)));
+                }
+                text
+            }
+        }
+    }
+
+    /// Constraints in braces: `{ len < 8; mode -> { len < 4; } }`
+    fn constraint_set(&self, constraints: &[ConstraintExpr]) -> String {
+        if constraints.is_empty() {
+            return "{}".to_string();
+        }
+        let constraints: Vec<String> = constraints
+            .iter()
+            .map(|constraint| self.constraint(constraint))
+            .collect();
+        format!("{{ {} }}", constraints.join(" "))
+    }
+
+    /// `header`, then `statements` one level deeper and `end`
+    fn block(
+        &mut self,
+        header: &str,
+        label: Option<&Label>,
+        statements: &[StmtRef],
+        footer: &str,
+        end_label: Option<&Label>,
+    ) {
+        match label {
+            Some(label) => self.line(&format!("{} : {}", header, ident(&label.name))),
+            None => self.line(header),
+        }
+        self.statements(statements);
+        match end_label {
+            Some(label) => self.line(&format!("{} : {}", footer, ident(&label.name))),
+            None => self.line(footer),
+        }
+    }
+
+    fn statements(&mut self, statements: &[StmtRef]) {
+        self.depth += 1;
+        for statement in statements {
+            self.statement(*statement);
+        }
+        self.depth -= 1;
+    }
+
+    fn statement(&mut self, stmt_ref: StmtRef) {
+        let source_unit = self.source_unit;
+        match source_unit.stmt_arena.get(stmt_ref) {
+            Statement::Assignment {
+                target,
+                op,
+                delay,
+                expr,
+                ..
+            } => {
+                let text = format!(
+                    "{} {} {}{};",
+                    self.expr(*target),
+                    assignment_symbol(op),
+                    delay.as_ref().map(delay_text).unwrap_or_default(),
+                    self.expr(*expr)
+                );
+                self.line(&text);
+            }
+            Statement::SystemCall { name, args, .. } => {
+                let call = self.call(&format!("${}", name), args);
+                self.line(&format!("{};", call));
+            }
+            Statement::ExpressionStatement { expr, .. } => {
+                let text = format!("{};", self.expr(*expr));
+                self.line(&text);
+            }
+            Statement::VariableDeclaration {
+                data_type,
+                name,
+                initial_value,
+                ..
+            } => {
+                let mut text = format!("{} {}", self.data_type(data_type), ident(name));
+                if let Some(value) = initial_value {
+                    text.push_str(&format!(" = {}", self.expr(*value)));
+                }
+                self.line(&format!("{};", text));
+            }
+            Statement::Block {
+                label,
+                statements,
+                end_label,
+                ..
+            } => self.block(
+                "begin",
+                label.as_ref(),
+                statements,
+                "end",
+                end_label.as_ref(),
+            ),
+            Statement::Fork {
+                label,
+                statements,
+                join,
+                end_label,
+                ..
+            } => self.block(
+                "fork",
+                label.as_ref(),
+                statements,
+                join.keyword(),
+                end_label.as_ref(),
+            ),
+            Statement::Labeled {
+                label, statement, ..
+            } => {
+                self.line(&format!("{}:", ident(&label.name)));
+                self.statement(*statement);
+            }
+            Statement::AssertProperty {
+                kind,
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => self.assertion(
+                kind.keyword(),
+                *clocking_event,
+                *disable_iff,
+                property,
+                *pass_action,
+                *fail_action,
+            ),
+            Statement::ImmediateAssertion {
+                kind,
+                deferral,
+                condition,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                let header = match deferral {
+                    Some(deferral) => format!(
+                        "{} {} ({})",
+                        kind.keyword(),
+                        deferral.keyword(),
+                        self.expr(*condition)
+                    ),
+                    None => format!("{} ({})", kind.keyword(), self.expr(*condition)),
+                };
+                self.action_block(&header, *pass_action, *fail_action);
+            }
+            Statement::Conditional { .. } => self.conditional(stmt_ref, ""),
+            Statement::CaseStatement {
+                modifier,
+                case_type,
+                inside,
+                expr,
+                items,
+                ..
+            } => {
+                let header = self.case_header(modifier.as_ref(), case_type, *expr);
+                if *inside {
+                    self.line(&format!("{} inside", header));
+                } else {
+                    self.line(&header);
+                }
+                self.depth += 1;
+                for item in items {
+                    if item.values.is_empty() {
+                        self.line("default:");
+                    } else {
+                        let values: Vec<String> = item
+                            .values
+                            .iter()
+                            .map(|value| self.case_value(*value))
+                            .collect();
+                        self.line(&format!("{}:", values.join(", ")));
+                    }
+                    self.statements(&[item.statement]);
+                }
+                self.depth -= 1;
+                self.line("endcase");
+            }
+            Statement::CaseMatches {
+                modifier,
+                case_type,
+                expr,
+                items,
+                ..
+            } => {
+                let header = self.case_header(modifier.as_ref(), case_type, *expr);
+                self.line(&format!("{} matches", header));
+                self.depth += 1;
+                for item in items {
+                    let mut text = match &item.pattern {
+                        Some(pattern) => self.pattern(pattern),
+                        None => "default".to_string(),
+                    };
+                    if let Some(guard) = item.guard {
+                        text.push_str(&format!(" &&& {}", self.expr(guard)));
+                    }
+                    self.line(&format!("{}:", text));
+                    self.statements(&[item.statement]);
+                }
+                self.depth -= 1;
+                self.line("endcase");
+            }
+            Statement::Return { value, .. } => match value {
+                Some(value) => {
+                    let text = format!("return {};", self.expr(*value));
+                    self.line(&text);
+                }
+                None => self.line("return;"),
+            },
+            Statement::ForLoop {
+                init,
+                condition,
+                step,
+                body,
+                ..
+            } => {
+                let clauses = |statements: &[StmtRef]| -> String {
+                    statements
+                        .iter()
+                        .map(|stmt| self.for_clause(*stmt))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                let condition = condition
+                    .map(|condition| format!(" {}", self.expr(condition)))
+                    .unwrap_or_default();
+                let step = match clauses(step) {
+                    step if step.is_empty() => step,
+                    step => format!(" {}", step),
+                };
+                let header = format!("for ({};{};{})", clauses(init), condition, step);
+                self.line(&header);
+                self.statements(&[*body]);
+            }
+            Statement::While {
+                condition, body, ..
+            } => {
+                let header = format!("while ({})", self.expr(*condition));
+                self.line(&header);
+                self.statements(&[*body]);
+            }
+            Statement::DoWhile {
+                body, condition, ..
+            } => {
+                self.line("do");
+                self.statements(&[*body]);
+                let footer = format!("while ({});", self.expr(*condition));
+                self.line(&footer);
+            }
+            Statement::Repeat { count, body, .. } => {
+                let header = format!("repeat ({})", self.expr(*count));
+                self.line(&header);
+                self.statements(&[*body]);
+            }
+            Statement::Timed {
+                control, statement, ..
+            } => {
+                let control = match control {
+                    TimingControl::Delay(delay) => delay_text(delay).trim_end().to_string(),
+                    TimingControl::Event(control) => self.event_control(control),
+                    TimingControl::Wait(condition) => format!("wait ({})", self.expr(*condition)),
+                };
+                match statement {
+                    Some(statement) => {
+                        self.line(&control);
+                        self.statements(&[*statement]);
+                    }
+                    None => self.line(&format!("{};", control)),
+                }
+            }
+            Statement::Forever { body, .. } => {
+                self.line("forever");
+                self.statements(&[*body]);
+            }
+            Statement::Foreach {
+                array,
+                indices,
+                body,
+                ..
+            } => {
+                let indices: Vec<&str> = indices
+                    .iter()
+                    .map(|index| index.as_ref().map_or("", |index| index.name.as_str()))
+                    .collect();
+                self.line(&format!("foreach ({}[{}])", array, indices.join(", ")));
+                self.statements(&[*body]);
+            }
+            Statement::EventTrigger {
+                nonblocking, event, ..
+            } => {
+                let operator = if *nonblocking { "->>" } else { "->" };
+                let event = self.expr(*event);
+                self.line(&format!("{} {};", operator, event));
+            }
+            Statement::WaitFork { .. } => self.line("wait fork;"),
+            Statement::DisableFork { .. } => self.line("disable fork;"),
+            Statement::Disable { target, .. } => self.line(&format!("disable {};", ident(target))),
+        }
+    }
+
+    /// A declaration, assignment or increment in the header of a `for` loop,
+    /// without its `;`
+    fn for_clause(&self, stmt_ref: StmtRef) -> String {
+        match self.source_unit.stmt_arena.get(stmt_ref) {
+            Statement::VariableDeclaration {
+                data_type,
+                name,
+                initial_value,
+                ..
+            } => {
+                let data_type = self.data_type(data_type);
+                match initial_value {
+                    Some(value) => format!("{} {} = {}", data_type, ident(name), self.expr(*value)),
+                    None => format!("{} {}", data_type, ident(name)),
+                }
+            }
+            Statement::Assignment {
+                target, op, expr, ..
+            } => format!(
+                "{} {} {}",
+                self.expr(*target),
+                assignment_symbol(op),
+                self.expr(*expr)
+            ),
+            Statement::ExpressionStatement { expr, .. } => self.expr(*expr),
+            _ => String::new(),
+        }
+    }
+
+    /// An `if` statement, after `keyword` when it is the else branch of another,
+    /// with its `else if` branches
+    fn conditional(&mut self, stmt_ref: StmtRef, keyword: &str) {
+        let source_unit = self.source_unit;
+        let arena = &source_unit.stmt_arena;
+        let Statement::Conditional {
+            modifier,
+            condition,
+            then_statement,
+            else_statement,
+            ..
+        } = arena.get(stmt_ref)
+        else {
+            return;
+        };
+        let modifier = modifier
+            .as_ref()
+            .map(|modifier| format!("{} ", modifier))
+            .unwrap_or_default();
+        let header = format!("{}{}if ({})", keyword, modifier, self.expr(*condition));
+        self.line(&header);
+        self.statements(&[*then_statement]);
+        match else_statement.map(|else_ref| (else_ref, arena.get(else_ref))) {
+            Some((else_ref, Statement::Conditional { .. })) => self.conditional(else_ref, "else "),
+            Some((else_ref, _)) => {
+                self.line("else");
+                self.statements(&[else_ref]);
+            }
+            None => {}
+        }
+    }
+
+    /// `assert property (...)` and its action block: the pass statement,
+    /// then the fail statement after `else`
+    fn assertion(
+        &mut self,
+        keyword: &str,
+        clocking_event: Option<ExprRef>,
+        disable_iff: Option<ExprRef>,
+        property: &PropertyExpr,
+        pass_action: Option<StmtRef>,
+        fail_action: Option<StmtRef>,
+    ) {
+        let header = format!(
+            "{} property ({})",
+            keyword,
+            self.clocked_property(clocking_event, disable_iff, property)
+        );
+        self.action_block(&header, pass_action, fail_action);
+    }
+
+    /// An assertion header, then its pass and fail statements, or `;`
+    fn action_block(
+        &mut self,
+        header: &str,
+        pass_action: Option<StmtRef>,
+        fail_action: Option<StmtRef>,
+    ) {
+        if pass_action.is_none() && fail_action.is_none() {
+            self.line(&format!("{};", header));
+            return;
+        }
+        self.line(header);
+        self.statements(&pass_action.into_iter().collect::<Vec<_>>());
+        if let Some(fail_action) = fail_action {
+            self.line("else");
+            self.statements(&[fail_action]);
+        }
+    }
+
+    /// A property after its leading clock and `disable iff` condition
+    fn clocked_property(
+        &self,
+        clocking_event: Option<ExprRef>,
+        disable_iff: Option<ExprRef>,
+        property: &PropertyExpr,
+    ) -> String {
+        let mut text = String::new();
+        if let Some(event) = clocking_event {
+            text.push_str(&self.clocking_event(event));
+            text.push(' ');
+        }
+        if let Some(condition) = disable_iff {
+            text.push_str(&format!("disable iff ({}) ", self.expr(condition)));
+        }
+        text.push_str(&self.property(property));
+        text
+    }
+
+    /// A sequence or property, with operands that bind more loosely than
+    /// their operator parenthesized
+    fn property(&self, property: &PropertyExpr) -> String {
+        match property {
+            PropertyExpr::Expression { expr, .. } => self.expr(*expr),
+            PropertyExpr::Delay {
+                left,
+                cycles,
+                right,
+                ..
+            } => {
+                let delay = match cycles.high {
+                    None => match self.source_unit.expr_arena.get(cycles.low) {
+                        Expression::Number(..) | Expression::Identifier(..) => {
+                            format!("##{}", self.expr(cycles.low))
+                        }
+                        _ => format!("##({})", self.expr(cycles.low)),
+                    },
+                    Some(_) => format!("##[{}]", self.cycles(cycles)),
+                };
+                let right = self.property_operand(right, property.precedence() + 1);
+                match left {
+                    Some(left) => format!(
+                        "{} {} {}",
+                        self.property_operand(left, property.precedence()),
+                        delay,
+                        right
+                    ),
+                    None => format!("{} {}", delay, right),
+                }
+            }
+            PropertyExpr::Repetition {
+                operand,
+                kind,
+                cycles,
+                ..
+            } => format!(
+                "{}{}{}]",
+                self.property_operand(operand, property.precedence()),
+                kind.as_str(),
+                self.cycles(cycles)
+            ),
+            PropertyExpr::Binary {
+                op, left, right, ..
+            } => {
+                // The operand on the side the operator groups from may use it
+                // unparenthesized
+                let precedence = op.precedence();
+                let (left_min, right_min) = match op {
+                    PropertyOp::Throughout
+                    | PropertyOp::OverlappedImplication
+                    | PropertyOp::NonOverlappedImplication => (precedence + 1, precedence),
+                    _ => (precedence, precedence + 1),
+                };
+                format!(
+                    "{} {} {}",
+                    self.property_operand(left, left_min),
+                    op.as_str(),
+                    self.property_operand(right, right_min)
+                )
+            }
+            PropertyExpr::Not { operand, .. } => {
+                format!(
+                    "not {}",
+                    self.property_operand(operand, property.precedence())
+                )
+            }
+            PropertyExpr::Clocked {
+                clocking_event,
+                operand,
+                ..
+            } => format!(
+                "{} {}",
+                self.clocking_event(*clocking_event),
+                self.property(operand)
+            ),
+        }
+    }
+
+    /// An operand of a property operator, parenthesized if it binds more
+    /// loosely than `precedence`
+    fn property_operand(&self, operand: &PropertyExpr, precedence: u8) -> String {
+        if operand.precedence() < precedence {
+            format!("({})", self.property(operand))
+        } else {
+            self.property(operand)
+        }
+    }
+
+    /// `2`, `1:3` or `1:$`
+    fn cycles(&self, cycles: &CycleRange) -> String {
+        match &cycles.high {
+            None => self.expr(cycles.low),
+            Some(CycleBound::Count(high)) => {
+                format!("{}:{}", self.expr(cycles.low), self.expr(*high))
+            }
+            Some(CycleBound::Unbounded) => format!("{}:$", self.expr(cycles.low)),
+        }
+    }
+
+    fn case_header(&self, modifier: Option<&String>, case_type: &str, expr: ExprRef) -> String {
+        let modifier = modifier
+            .map(|modifier| format!("{} ", modifier))
+            .unwrap_or_default();
+        format!("{}{} ({})", modifier, case_type, self.expr(expr))
+    }
+
+    /// A case item value, parenthesized if its own `:` would end the value list
+    fn case_value(&self, value: ExprRef) -> String {
+        match self.source_unit.expr_arena.get(value) {
+            Expression::Conditional { .. } => format!("({})", self.expr(value)),
+            _ => self.expr(value),
+        }
+    }
+
+    fn pattern(&self, pattern: &Pattern) -> String {
+        match pattern {
+            Pattern::Variable(name, _) => format!(".{}", ident(name)),
+            Pattern::Wildcard(_) => ".*".to_string(),
+            Pattern::Tagged {
+                member, pattern, ..
+            } => match pattern {
+                Some(pattern) => format!("tagged {} {}", ident(member), self.pattern(pattern)),
+                None => format!("tagged {}", ident(member)),
+            },
+            // Parenthesized so a `tagged` value isn't read as a tagged pattern
+            Pattern::Constant(expr) => format!("({})", self.expr(*expr)),
+        }
+    }
+
+    fn connections(&self, connections: &[PortConnection]) -> String {
+        connections
+            .iter()
+            .map(|connection| match connection {
+                PortConnection::Positional(expr) => self.expr(*expr),
+                PortConnection::Named { name, expr, .. } => match expr {
+                    Some(expr) => format!(".{}({})", ident(name), self.expr(*expr)),
+                    None => format!(".{}()", ident(name)),
+                },
+                PortConnection::Wildcard => ".*".to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// `@(posedge clk or rst)`, or `@*`
+    fn event_control(&self, control: &EventControl) -> String {
+        let EventControl::Events { events, .. } = control else {
+            return "@*".to_string();
+        };
+        let events: Vec<String> = events
+            .iter()
+            .map(|event| {
+                let mut text = match event.edge {
+                    Some(Edge::Posedge) => "posedge ".to_string(),
+                    Some(Edge::Negedge) => "negedge ".to_string(),
+                    Some(Edge::Edge) => "edge ".to_string(),
+                    None => String::new(),
+                };
+                text.push_str(&self.expr(event.signal));
+                if let Some(iff) = event.iff {
+                    text.push_str(&format!(" iff {}", self.expr(iff)));
+                }
+                text
+            })
+            .collect();
+        format!("@({})", events.join(" or "))
+    }
+
+    /// A clock, kept as its source text in an identifier
+    fn clocking_event(&self, event: ExprRef) -> String {
+        match self.source_unit.expr_arena.get(event) {
+            Expression::Identifier(text, _) => text.clone(),
+            _ => self.expr(event),
+        }
+    }
+
+    /// `name(arguments)`, or just `name` without arguments
+    fn call(&self, name: &str, arguments: &[ExprRef]) -> String {
+        if arguments.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}({})", name, self.arguments(arguments))
+        }
+    }
+
+    fn arguments(&self, arguments: &[ExprRef]) -> String {
+        arguments
+            .iter()
+            .map(|argument| self.expr(*argument))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn expr(&self, expr_ref: ExprRef) -> String {
+        match self.source_unit.expr_arena.get(expr_ref) {
+            Expression::Identifier(name, _) => ident(name),
+            Expression::Number(text, _) => text.clone(),
+            Expression::StringLiteral(text, _) => {
+                format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+            }
+            Expression::This(_) => "this".to_string(),
+            Expression::Super(_) => "super".to_string(),
+            Expression::Binary {
+                op, left, right, ..
+            } => format!(
+                "{} {} {}",
+                self.binary_operand(*left, op, false),
+                binary_symbol(op),
+                self.binary_operand(*right, op, true)
+            ),
+            Expression::Unary { op, operand, .. } => {
+                let operand = match self.source_unit.expr_arena.get(*operand) {
+                    expr if is_atom(expr) => self.expr(*operand),
+                    _ => format!("({})", self.expr(*operand)),
+                };
+                format!("{}{}", unary_symbol(op), operand)
+            }
+            Expression::IncDec {
+                op,
+                prefix: true,
+                operand,
+                ..
+            } => {
+                // The operand of a prefix operator takes member accesses but not a call
+                let operand = match self.source_unit.expr_arena.get(*operand) {
+                    expr if is_atom(expr) || matches!(expr, Expression::MemberAccess { .. }) => {
+                        self.expr(*operand)
+                    }
+                    _ => format!("({})", self.expr(*operand)),
+                };
+                format!("{}{}", inc_dec_symbol(op), operand)
+            }
+            Expression::IncDec { op, operand, .. } => {
+                format!("{}{}", self.postfix_operand(*operand), inc_dec_symbol(op))
+            }
+            Expression::MacroUsage {
+                name, arguments, ..
+            } => self.call(&format!("`{}", name), arguments),
+            Expression::SystemFunctionCall {
+                name, arguments, ..
+            } => self.call(&format!("${}", name), arguments),
+            Expression::New { arguments, .. } => self.call("new", arguments),
+            Expression::MemberAccess { object, member, .. } => {
+                if member == "new"
+                    && matches!(
+                        self.source_unit.expr_arena.get(*object),
+                        Expression::Super(_)
+                    )
+                {
+                    return "super.new".to_string();
+                }
+                format!("{}.{}", self.postfix_operand(*object), member_name(member))
+            }
+            Expression::FunctionCall {
+                function,
+                arguments,
+                ..
+            } => {
+                // `new` and a system function would take the arguments as their own
+                let function = match self.source_unit.expr_arena.get(*function) {
+                    Expression::New { .. } | Expression::SystemFunctionCall { .. } => {
+                        format!("({})", self.expr(*function))
+                    }
+                    _ => self.postfix_operand(*function),
+                };
+                format!("{}({})", function, self.arguments(arguments))
+            }
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                // `?:` groups from the right and binds tighter than the implications
+                let precedence = self.source_unit.expr_arena.get(expr_ref).precedence();
+                let condition = match self.source_unit.expr_arena.get(*condition) {
+                    Expression::TaggedUnion { .. } => format!("({})", self.expr(*condition)),
+                    expr if expr.precedence() <= precedence => {
+                        format!("({})", self.expr(*condition))
+                    }
+                    _ => self.expr(*condition),
+                };
+                let else_expr = match self.source_unit.expr_arena.get(*else_expr) {
+                    expr if expr.precedence() < precedence => {
+                        format!("({})", self.expr(*else_expr))
+                    }
+                    _ => self.expr(*else_expr),
+                };
+                format!("{} ? {} : {}", condition, self.expr(*then_expr), else_expr)
+            }
+            Expression::TaggedUnion { member, value, .. } => match value {
+                Some(value) => match self.source_unit.expr_arena.get(*value) {
+                    Expression::Number(..) | Expression::Identifier(..) => {
+                        format!("tagged {} {}", ident(member), self.expr(*value))
+                    }
+                    _ => format!("tagged {} ({})", ident(member), self.expr(*value)),
+                },
+                None => format!("tagged {}", ident(member)),
+            },
+            Expression::ScopedIdentifier { scope, name, .. } => {
+                format!("{}::{}", ident(scope), member_name(name))
+            }
+            Expression::AssignmentPattern { items, .. } => {
+                let items: Vec<String> = items
+                    .iter()
+                    .map(|item| match &item.key {
+                        Some(key) if key == "default" => {
+                            format!("default: {}", self.expr(item.value))
+                        }
+                        Some(key) => format!("{}: {}", ident(key), self.expr(item.value)),
+                        None => self.expr(item.value),
+                    })
+                    .collect();
+                format!("'{{{}}}", items.join(", "))
+            }
+            Expression::ValueRange { low, high, .. } => {
+                // The `:` of a `?:` would end the bound
+                format!("[{}:{}]", self.case_value(*low), self.case_value(*high))
+            }
+            Expression::RandomizeWith {
+                call, constraints, ..
+            } => format!(
+                "{} with {}",
+                self.expr(*call),
+                self.constraint_set(constraints)
+            ),
+        }
+    }
+
+    /// An operand of `op`, in parentheses when it binds more loosely than
+    /// `op`, or as loosely on the side `op` doesn't group from
+    fn binary_operand(&self, expr_ref: ExprRef, op: &BinaryOp, right: bool) -> String {
+        let expr = self.source_unit.expr_arena.get(expr_ref);
+        let parenthesize = match expr {
+            // The value of a tagged union would take the operator with it
+            Expression::TaggedUnion { .. } => true,
+            _ => {
+                expr.precedence() < op.precedence()
+                    || (expr.precedence() == op.precedence() && right != op.is_right_associative())
+            }
+        };
+        if parenthesize {
+            format!("({})", self.expr(expr_ref))
+        } else {
+            self.expr(expr_ref)
+        }
+    }
+
+    /// The object of a member access or the function of a call, which
+    /// follows directly before the `.` or `(`
+    fn postfix_operand(&self, expr_ref: ExprRef) -> String {
+        let expr = self.source_unit.expr_arena.get(expr_ref);
+        let text = self.expr(expr_ref);
+        // An escaped identifier ends at the space after it
+        if (is_atom(expr)
+            || matches!(
+                expr,
+                Expression::Unary { .. } | Expression::MemberAccess { .. }
+            ))
+            && !text.ends_with(' ')
+        {
+            text
+        } else {
+            format!("({})", text)
+        }
+    }
+}
+
+/// Whether `expr` parses as an atom, without parentheses around it
+fn is_atom(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Identifier(..)
+            | Expression::Number(..)
+            | Expression::StringLiteral(..)
+            | Expression::This(_)
+            | Expression::Super(_)
+            | Expression::New { .. }
+            | Expression::SystemFunctionCall { .. }
+            | Expression::ScopedIdentifier { .. }
+            | Expression::AssignmentPattern { .. }
+    )
+}
+
+/// The items of an import or export declaration, like `p::x, q::*`
+fn package_imports(imports: &[PackageImport]) -> String {
+    let imports: Vec<String> = imports
+        .iter()
+        .map(|import| {
+            // The `*::*` of an export names no package
+            let package = match import.package.as_str() {
+                "*" => "*".to_string(),
+                package => ident(package),
+            };
+            let name = import.name.as_deref().map(ident);
+            format!("{}::{}", package, name.unwrap_or_else(|| "*".to_string()))
+        })
+        .collect();
+    imports.join(", ")
+}
+
+/// `name` as written in source: escaped when it isn't a simple identifier or
+/// is a keyword
+fn ident(name: &str) -> String {
+    let mut chars = name.chars();
+    let simple = chars
+        .next()
+        .is_some_and(|first| first.is_alphabetic() || first == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$');
+    if simple && !is_keyword(name) {
+        name.to_string()
+    } else {
+        format!("\\{} ", name)
+    }
+}
+
+/// A name after `.` or `::`, where even a keyword is written as is
+fn member_name(name: &str) -> String {
+    if is_keyword(name) {
+        name.to_string()
+    } else {
+        ident(name)
+    }
+}
+
+/// A data type: a keyword like `logic` or `virtual bus_if`, or a type name
+fn type_name(data_type: &str) -> String {
+    let first_word = data_type.split(' ').next().unwrap_or_default();
+    if is_keyword(first_word) {
+        data_type.to_string()
+    } else {
+        ident(data_type)
+    }
+}
+
+fn port_text(port: &Port) -> String {
+    let mut text = String::new();
+    if let Some(direction) = &port.direction {
+        text.push_str(port_direction(direction));
+        text.push(' ');
+    }
+    // Checker formals may have a type without a direction
+    if let Some(data_type) = &port.data_type {
+        text.push_str(data_type);
+        text.push(' ');
+    }
+    if let Some(range) = &port.range {
+        text.push_str(&range_text(range));
+        text.push(' ');
+    }
+    if let Some(interface) = &port.interface {
+        // A generic `interface` port is the keyword itself
+        if interface == "interface" {
+            text.push_str(interface);
+        } else {
+            text.push_str(&ident(interface));
+        }
+        if let Some(modport) = &port.modport {
+            text.push('.');
+            text.push_str(&ident(modport));
+        }
+        text.push(' ');
+    }
+    text + &ident(&port.name)
+}
+
+fn port_direction(direction: &PortDirection) -> &'static str {
+    match direction {
+        PortDirection::Input => "input",
+        PortDirection::Output => "output",
+        PortDirection::Inout => "inout",
+    }
+}
+
+fn range_text(range: &Range) -> String {
+    format!("[{}:{}]", range.msb, range.lsb)
+}
+
+fn dimension_text(dimension: &UnpackedDimension) -> String {
+    match dimension {
+        UnpackedDimension::Dynamic => "[]".to_string(),
+        UnpackedDimension::FixedSize(size) => format!("[{}]", size),
+        UnpackedDimension::Range(msb, lsb) => format!("[{}:{}]", msb, lsb),
+    }
+}
+
+/// The delay followed by a space
+fn delay_text(delay: &Delay) -> String {
+    match delay {
+        Delay::Value(value) => format!("#{} ", value),
+        Delay::Expression(expr) => format!("#({}) ", expr),
+    }
+}
+
+fn qualifier_text(qualifier: Option<&ClassQualifier>) -> String {
+    match qualifier {
+        Some(ClassQualifier::Local) => "local ".to_string(),
+        Some(ClassQualifier::Protected) => "protected ".to_string(),
+        None => String::new(),
+    }
+}
+
+fn cell_text(cell: &CellReference) -> String {
+    match &cell.library {
+        Some(library) => format!("{}.{}", library, cell.cell),
+        None => cell.cell.clone(),
+    }
+}
+
+fn liblist_text(libraries: &[String]) -> String {
+    if libraries.is_empty() {
+        "liblist".to_string()
+    } else {
+        format!("liblist {}", libraries.join(" "))
+    }
+}
+
+fn selection_text(selection: &LibrarySelection) -> String {
+    match selection {
+        LibrarySelection::Liblist(libraries) => liblist_text(libraries),
+        LibrarySelection::Use(cell) => format!("use {}", cell_text(cell)),
+    }
+}
+
+fn assignment_symbol(op: &AssignmentOp) -> &'static str {
+    match op {
+        AssignmentOp::Assign => "=",
+        AssignmentOp::AddAssign => "+=",
+        AssignmentOp::SubAssign => "-=",
+        AssignmentOp::MulAssign => "*=",
+        AssignmentOp::DivAssign => "/=",
+        AssignmentOp::ModAssign => "%=",
+        AssignmentOp::AndAssign => "&=",
+        AssignmentOp::OrAssign => "|=",
+        AssignmentOp::XorAssign => "^=",
+        AssignmentOp::ShlAssign => "<<=",
+        AssignmentOp::ShrAssign => ">>=",
+        AssignmentOp::AShlAssign => "<<<=",
+        AssignmentOp::AShrAssign => ">>>=",
+        AssignmentOp::NonBlocking => "<=",
+    }
+}
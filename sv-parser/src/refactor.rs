@@ -0,0 +1,783 @@
+//! Source rewrites offered as refactorings
+//!
+//! A refactoring works on the parsed file and its text and returns
+//! [`TextEdit`]s for the editor (or a command-line tool) to apply. Statements
+//! are copied from the source text rather than printed from the AST, so their
+//! formatting and comments are kept.
+
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::{
+    ConstraintExpr, DataType, ExprArena, ExprRef, Expression, ModuleItem, ModuleItemRef,
+    PortConnection, ProceduralBlockType, SourceUnit, Span, Statement, StmtArena, StmtRef,
+    TokenKind,
+};
+
+/// Replace the text at `span` with `new_text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub new_text: String,
+}
+
+/// Apply `edits` to `text`, skipping those that overlap an edit before them
+///
+/// Returns the new text and the positions in `edits` of the edits applied,
+/// in the order they appear in the text.
+pub fn apply_edits(text: &str, edits: &[TextEdit]) -> (String, Vec<usize>) {
+    let mut order: Vec<usize> = (0..edits.len()).collect();
+    order.sort_by_key(|&index| edits[index].span);
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut output = String::with_capacity(text.len());
+    let mut position = 0;
+    let mut applied = Vec::new();
+    for index in order {
+        let edit = &edits[index];
+        if edit.span.0 < position || edit.span.1 > chars.len() {
+            continue;
+        }
+        output.extend(&chars[position..edit.span.0]);
+        output.push_str(&edit.new_text);
+        position = edit.span.1;
+        applied.push(index);
+    }
+    output.extend(&chars[position..]);
+    (output, applied)
+}
+
+/// The innermost procedural block whose text contains `offset`
+pub fn procedural_block_at(source_unit: &SourceUnit, offset: usize) -> Option<ModuleItemRef> {
+    let arena = &source_unit.module_item_arena;
+    let mut items = source_unit.items.clone();
+    while let Some(item_ref) = items.pop() {
+        match arena.get(item_ref) {
+            ModuleItem::ModuleDeclaration { items: nested, .. }
+            | ModuleItem::InterfaceDeclaration { items: nested, .. }
+            | ModuleItem::CheckerDeclaration { items: nested, .. } => items.extend(nested),
+            ModuleItem::ProceduralBlock { span, .. } if span.0 <= offset && offset < span.1 => {
+                return Some(item_ref);
+            }
+            item => items.extend(item.generate_items()),
+        }
+    }
+    None
+}
+
+/// Name of the module or interface around `offset` that has a port `port`
+pub fn port_owner<'a>(source_unit: &'a SourceUnit, offset: usize, port: &str) -> Option<&'a str> {
+    let arena = &source_unit.module_item_arena;
+    let mut items = source_unit.items.clone();
+    while let Some(item_ref) = items.pop() {
+        match arena.get(item_ref) {
+            ModuleItem::ModuleDeclaration {
+                name,
+                ports,
+                items: nested,
+                span,
+                ..
+            }
+            | ModuleItem::InterfaceDeclaration {
+                name,
+                ports,
+                items: nested,
+                span,
+                ..
+            } if span.0 <= offset && offset < span.1 => {
+                if ports.iter().any(|declared| declared.name == port) {
+                    return Some(name);
+                }
+                // A nested module has ports of its own
+                items.extend(nested);
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Edits renaming the port `port` of `module` in the named connections of
+/// its instances
+///
+/// `.port(x)` becomes `.new_name(x)`, and the implicit `.port` becomes
+/// `.new_name(port)` so it stays connected to the same signal. Positional and
+/// `.*` connections need no edit.
+pub fn rename_port_connections(
+    source_unit: &SourceUnit,
+    module: &str,
+    port: &str,
+    new_name: &str,
+) -> Vec<TextEdit> {
+    let arena = &source_unit.module_item_arena;
+    let mut edits = Vec::new();
+    let mut items = source_unit.items.clone();
+    while let Some(item_ref) = items.pop() {
+        match arena.get(item_ref) {
+            ModuleItem::ModuleDeclaration { items: nested, .. }
+            | ModuleItem::InterfaceDeclaration { items: nested, .. } => items.extend(nested),
+            ModuleItem::ModuleInstantiation {
+                module_name,
+                connections,
+                ..
+            } if module_name == module => {
+                for connection in connections {
+                    let PortConnection::Named {
+                        name,
+                        name_span,
+                        expr,
+                    } = connection
+                    else {
+                        continue;
+                    };
+                    if name != port {
+                        continue;
+                    }
+                    let implicit = expr.is_some_and(|expr| {
+                        matches!(
+                            source_unit.expr_arena.get(expr),
+                            Expression::Identifier(_, span) if span == name_span
+                        )
+                    });
+                    edits.push(TextEdit {
+                        span: *name_span,
+                        new_text: if implicit {
+                            format!("{}({})", new_name, port)
+                        } else {
+                            new_name.to_string()
+                        },
+                    });
+                }
+            }
+            item => items.extend(item.generate_items()),
+        }
+    }
+    edits.sort_by_key(|edit| edit.span);
+    edits
+}
+
+/// Split an always block into one always block per group of statements that
+/// drive the same variables
+///
+/// Statements are grouped when they assign a common variable or when one reads
+/// a variable another assigns, so every variable keeps a single driving block
+/// and values computed in the block stay with the statements that use them.
+/// Statements that assign nothing, like `$display`, join the group they read
+/// from, or else the group of the statement before them.
+///
+/// Returns None unless the block is an `always`, `always_comb` or `always_ff`
+/// block with `begin ... end` and at least two groups. Named blocks are left
+/// alone since their name may be referenced, and so are blocks containing
+/// `case ... matches` statements, whose pattern variables are not tracked.
+pub fn split_always_block(
+    source_unit: &SourceUnit,
+    content: &str,
+    block: ModuleItemRef,
+) -> Option<TextEdit> {
+    let ModuleItem::ProceduralBlock {
+        block_type,
+        statements,
+        label: None,
+        span,
+        ..
+    } = source_unit.module_item_arena.get(block)
+    else {
+        return None;
+    };
+    if !matches!(
+        block_type,
+        ProceduralBlockType::Always
+            | ProceduralBlockType::AlwaysComb
+            | ProceduralBlockType::AlwaysFF
+    ) || statements.len() < 2
+    {
+        return None;
+    }
+
+    let chars: Vec<char> = content.chars().collect();
+    let text = |span: Span| -> String { chars[span.0..span.1].iter().collect() };
+
+    let mut accesses = Vec::with_capacity(statements.len());
+    for stmt_ref in statements {
+        let mut access = Access::default();
+        if !access.statement(*stmt_ref, &source_unit.stmt_arena, &source_unit.expr_arena) {
+            return None;
+        }
+        accesses.push(access);
+    }
+    let groups = group_statements(&accesses);
+    if groups.len() < 2 {
+        return None;
+    }
+
+    // The block span may start with the whitespace before the keyword
+    let block_text = text(*span);
+    let start = span.0 + (block_text.chars().count() - block_text.trim_start().chars().count());
+    let first_statement = trimmed_span(&chars, source_unit.stmt_arena.get(statements[0]).span());
+    let head = text((start, first_statement.0));
+    let header = head[..head.rfind("begin")?].trim_end().to_string();
+
+    let block_indent = indentation(&chars, start);
+    let statement_indent = indentation(&chars, first_statement.0);
+    let blocks: Vec<String> = groups
+        .iter()
+        .map(|group| {
+            let body: String = group
+                .iter()
+                .map(|index| {
+                    let span = trimmed_span(
+                        &chars,
+                        source_unit.stmt_arena.get(statements[*index]).span(),
+                    );
+                    format!("{}{}\n", statement_indent, text(span))
+                })
+                .collect();
+            format!("{} begin\n{}{}end", header, body, block_indent)
+        })
+        .collect();
+
+    Some(TextEdit {
+        span: (start, span.1),
+        new_text: blocks.join(&format!("\n\n{}", block_indent)),
+    })
+}
+
+/// A package gathering declarations that several modules repeat, and the
+/// edits that make those modules import it instead
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageExtraction {
+    /// Source text of the package, ending with a newline
+    pub package: String,
+    /// Names moved into the package, in package order
+    pub names: Vec<String>,
+    /// Edits to each file, in source order within a file
+    pub edits: Vec<(PathBuf, TextEdit)>,
+}
+
+/// A `localparam` or typedef of a module, keyed by its name and its normalized text
+struct SharedDeclaration {
+    file: usize,
+    module: usize,
+    name: String,
+    /// The declaration with its whitespace collapsed, for comparison
+    key: String,
+    text: String,
+    span: Span,
+    reads: HashSet<String>,
+}
+
+/// Collect the `localparam`s and typedefs declared identically in two or more
+/// modules into a package named `name`
+///
+/// `files` holds each parsed file with its path and text. A declaration is
+/// shared when modules repeat it with the same text, ignoring whitespace;
+/// where a name has different definitions, the one most modules use is moved
+/// and the others stay where they are, since local declarations win over
+/// wildcard imports. Declarations that read a name that is not moved with
+/// them, as a value or a type, stay too. In each module the first moved
+/// declaration becomes `import <name>::*;` and the others are deleted.
+///
+/// Returns None if no declaration is shared.
+pub fn extract_package(
+    name: &str,
+    files: &[(&Path, &SourceUnit, &str)],
+) -> Option<PackageExtraction> {
+    let contents: Vec<Vec<char>> = files
+        .iter()
+        .map(|(_, _, content)| content.chars().collect())
+        .collect();
+    let mut declarations = Vec::new();
+    let mut module_count = 0;
+    for (file, (_, source_unit, _)) in files.iter().enumerate() {
+        let arena = &source_unit.module_item_arena;
+        for item_ref in &source_unit.items {
+            let ModuleItem::ModuleDeclaration { items, .. } = arena.get(*item_ref) else {
+                continue;
+            };
+            let module = module_count;
+            module_count += 1;
+            for item_ref in items {
+                if let Some(declaration) =
+                    shared_declaration(source_unit, &contents[file], *item_ref)
+                {
+                    declarations.push(SharedDeclaration {
+                        file,
+                        module,
+                        ..declaration
+                    });
+                }
+            }
+        }
+    }
+
+    // The definition of each name that the most modules use, first seen on a tie
+    let mut modules_using: HashMap<(&str, &str), HashSet<usize>> = HashMap::new();
+    for declaration in &declarations {
+        modules_using
+            .entry((&declaration.name, &declaration.key))
+            .or_default()
+            .insert(declaration.module);
+    }
+    let mut chosen: HashMap<&str, (&str, usize)> = HashMap::new();
+    for declaration in &declarations {
+        let count = modules_using[&(declaration.name.as_str(), declaration.key.as_str())].len();
+        let best = chosen
+            .entry(&declaration.name)
+            .or_insert((&declaration.key, count));
+        if count > best.1 {
+            *best = (&declaration.key, count);
+        }
+    }
+    let mut moved: HashMap<&str, &str> = chosen
+        .into_iter()
+        .filter(|(_, (_, count))| *count >= 2)
+        .map(|(name, (key, _))| (name, key))
+        .collect();
+
+    // A moved value may only read other moved names, or names no module declares
+    let declared: HashSet<&str> = declarations.iter().map(|d| d.name.as_str()).collect();
+    loop {
+        let stranded: Vec<&str> = declarations
+            .iter()
+            .filter(|d| moved.get(d.name.as_str()) == Some(&d.key.as_str()))
+            .filter(|d| {
+                d.reads.iter().any(|read| {
+                    declared.contains(read.as_str()) && !moved.contains_key(read.as_str())
+                })
+            })
+            .map(|d| d.name.as_str())
+            .collect();
+        if stranded.is_empty() {
+            break;
+        }
+        for stranded_name in stranded {
+            moved.remove(stranded_name);
+        }
+    }
+    if moved.is_empty() {
+        return None;
+    }
+
+    let is_moved = |declaration: &SharedDeclaration| {
+        moved.get(declaration.name.as_str()) == Some(&declaration.key.as_str())
+    };
+    let mut package = format!("package {};\n", name);
+    let mut package_names = Vec::new();
+    for declaration in declarations.iter().filter(|d| is_moved(d)) {
+        if !package_names.contains(&declaration.name) {
+            package.push_str(&format!("  {}\n", declaration.text));
+            package_names.push(declaration.name.clone());
+        }
+    }
+    package.push_str("endpackage\n");
+
+    let mut edits = Vec::new();
+    let mut imported = HashSet::new();
+    for declaration in declarations.iter().filter(|d| is_moved(d)) {
+        let path = files[declaration.file].0.to_path_buf();
+        let edit = if imported.insert(declaration.module) {
+            TextEdit {
+                span: declaration.span,
+                new_text: format!("import {}::*;", name),
+            }
+        } else {
+            TextEdit {
+                span: line_span(&contents[declaration.file], declaration.span),
+                new_text: String::new(),
+            }
+        };
+        edits.push((path, edit));
+    }
+
+    Some(PackageExtraction {
+        package,
+        names: package_names,
+        edits,
+    })
+}
+
+/// A `localparam` or typedef module item, with its file and module left to fill in
+fn shared_declaration(
+    source_unit: &SourceUnit,
+    chars: &[char],
+    item_ref: ModuleItemRef,
+) -> Option<SharedDeclaration> {
+    let (name, name_span, span) = match source_unit.module_item_arena.get(item_ref) {
+        ModuleItem::ParameterDeclaration {
+            local: true,
+            name,
+            name_span,
+            span,
+            ..
+        }
+        | ModuleItem::TypedefDeclaration {
+            name,
+            name_span,
+            span,
+            ..
+        } => (name, name_span, span),
+        _ => return None,
+    };
+    // Items expanded from an include file have spans in that file
+    if span.1 > chars.len() || chars[name_span.0..name_span.1].iter().collect::<String>() != *name {
+        return None;
+    }
+    let span = trimmed_span(chars, *span);
+    let text: String = chars[span.0..span.1].iter().collect();
+    let reads = match source_unit.module_item_arena.get(item_ref) {
+        ModuleItem::ParameterDeclaration { value, .. } => {
+            let mut access = Access::default();
+            access.expression(*value, &source_unit.expr_arena);
+            access.reads
+        }
+        // Ranges and member types are kept as text, so read the names in it
+        ModuleItem::TypedefDeclaration { data_type, .. } => {
+            let declared: HashSet<&str> = std::iter::once(name.as_str())
+                .chain(enum_member_names(data_type))
+                .collect();
+            crate::lex(&text)
+                .into_iter()
+                .filter(|token| token.kind == TokenKind::Identifier)
+                .map(|token| token.name().to_string())
+                .filter(|read| !declared.contains(read.as_str()))
+                .collect()
+        }
+        _ => HashSet::new(),
+    };
+    Some(SharedDeclaration {
+        file: 0,
+        module: 0,
+        name: name.clone(),
+        key: text.split_whitespace().collect::<Vec<_>>().join(" "),
+        text,
+        span,
+        reads,
+    })
+}
+
+/// Names of the members of an inline enum type, which its typedef declares
+fn enum_member_names(data_type: &DataType) -> impl Iterator<Item = &str> {
+    let members = match data_type {
+        DataType::Enum(enum_type) => enum_type.members.as_slice(),
+        _ => &[],
+    };
+    members.iter().map(|member| member.name.as_str())
+}
+
+/// `span` widened to its whole line when nothing else is on that line
+fn line_span(chars: &[char], span: Span) -> Span {
+    let line_start = chars[..span.0]
+        .iter()
+        .rposition(|c| *c == '\n')
+        .map_or(0, |newline| newline + 1);
+    let line_end = chars[span.1..]
+        .iter()
+        .position(|c| *c == '\n')
+        .map_or(chars.len(), |newline| span.1 + newline);
+    let blank = |range: &[char]| range.iter().all(|c| *c == ' ' || *c == '\t' || *c == '\r');
+    if blank(&chars[line_start..span.0]) && blank(&chars[span.1..line_end]) {
+        (line_start, (line_end + 1).min(chars.len()))
+    } else {
+        span
+    }
+}
+
+/// Variables a statement assigns and reads
+#[derive(Debug, Default)]
+struct Access {
+    drives: HashSet<String>,
+    reads: HashSet<String>,
+}
+
+impl Access {
+    /// Record the accesses of a statement; false if they cannot be known
+    fn statement(&mut self, stmt_ref: StmtRef, stmts: &StmtArena, exprs: &ExprArena) -> bool {
+        match stmts.get(stmt_ref) {
+            Statement::Assignment { target, expr, .. } => {
+                if let Some(name) = root_name(*target, exprs) {
+                    self.drives.insert(name.to_string());
+                }
+                self.expression(*target, exprs);
+                self.expression(*expr, exprs);
+            }
+            Statement::VariableDeclaration {
+                name,
+                initial_value,
+                ..
+            } => {
+                self.drives.insert(name.clone());
+                if let Some(value) = initial_value {
+                    self.expression(*value, exprs);
+                }
+            }
+            Statement::SystemCall { args, .. } => {
+                for arg in args {
+                    self.expression(*arg, exprs);
+                }
+            }
+            Statement::ExpressionStatement { expr, .. } => self.expression(*expr, exprs),
+            // Triggering an event is a write to it
+            Statement::EventTrigger { event, .. } => {
+                if let Some(name) = root_name(*event, exprs) {
+                    self.drives.insert(name.to_string());
+                }
+            }
+            Statement::Return { value, .. } => {
+                if let Some(value) = value {
+                    self.expression(*value, exprs);
+                }
+            }
+            Statement::AssertProperty {
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                for expr in disable_iff.iter().copied().chain(property.expressions()) {
+                    self.expression(expr, exprs);
+                }
+                return pass_action
+                    .iter()
+                    .chain(fail_action)
+                    .all(|action| self.statement(*action, stmts, exprs));
+            }
+            Statement::ImmediateAssertion {
+                condition,
+                pass_action,
+                fail_action,
+                ..
+            } => {
+                self.expression(*condition, exprs);
+                return pass_action
+                    .iter()
+                    .chain(fail_action)
+                    .all(|action| self.statement(*action, stmts, exprs));
+            }
+            Statement::Block { statements, .. } => {
+                return statements
+                    .iter()
+                    .all(|stmt| self.statement(*stmt, stmts, exprs));
+            }
+            Statement::Labeled { statement, .. } => {
+                return self.statement(*statement, stmts, exprs);
+            }
+            Statement::Conditional {
+                condition,
+                then_statement,
+                else_statement,
+                ..
+            } => {
+                self.expression(*condition, exprs);
+                return std::iter::once(then_statement)
+                    .chain(else_statement)
+                    .all(|stmt| self.statement(*stmt, stmts, exprs));
+            }
+            Statement::CaseStatement { expr, items, .. } => {
+                self.expression(*expr, exprs);
+                for item in items {
+                    for value in &item.values {
+                        self.expression(*value, exprs);
+                    }
+                    if !self.statement(item.statement, stmts, exprs) {
+                        return false;
+                    }
+                }
+            }
+            Statement::ForLoop {
+                init,
+                condition,
+                step,
+                body,
+                ..
+            } => {
+                if let Some(condition) = condition {
+                    self.expression(*condition, exprs);
+                }
+                return init
+                    .iter()
+                    .chain(step)
+                    .chain(std::iter::once(body))
+                    .all(|stmt| self.statement(*stmt, stmts, exprs));
+            }
+            Statement::While {
+                condition, body, ..
+            }
+            | Statement::DoWhile {
+                body, condition, ..
+            }
+            | Statement::Repeat {
+                count: condition,
+                body,
+                ..
+            } => {
+                self.expression(*condition, exprs);
+                return self.statement(*body, stmts, exprs);
+            }
+            Statement::Timed {
+                control, statement, ..
+            } => {
+                for expr_ref in control.expressions() {
+                    self.expression(expr_ref, exprs);
+                }
+                return statement.is_none_or(|statement| self.statement(statement, stmts, exprs));
+            }
+            Statement::Forever { body, .. } => return self.statement(*body, stmts, exprs),
+            Statement::Foreach {
+                array,
+                indices,
+                body,
+                ..
+            } => {
+                if let Some(root) = array.split('.').next() {
+                    self.reads.insert(root.to_string());
+                }
+                for index in indices.iter().flatten() {
+                    self.drives.insert(index.name.clone());
+                }
+                return self.statement(*body, stmts, exprs);
+            }
+            // Splitting would change which processes a fork starts
+            Statement::CaseMatches { .. } | Statement::Fork { .. } => return false,
+            Statement::WaitFork { .. }
+            | Statement::DisableFork { .. }
+            | Statement::Disable { .. } => {}
+        }
+        true
+    }
+
+    fn expression(&mut self, expr_ref: ExprRef, exprs: &ExprArena) {
+        let mut pending = vec![expr_ref];
+        while let Some(expr_ref) = pending.pop() {
+            match exprs.get(expr_ref) {
+                Expression::Identifier(name, _) => {
+                    self.reads.insert(name.clone());
+                }
+                Expression::Binary { left, right, .. } => pending.extend([*left, *right]),
+                Expression::Unary { operand, .. } => pending.push(*operand),
+                // `i++` reads `i` as well as driving it
+                Expression::IncDec { operand, .. } => {
+                    if let Some(name) = root_name(*operand, exprs) {
+                        self.drives.insert(name.to_string());
+                    }
+                    pending.push(*operand);
+                }
+                Expression::MemberAccess { object, .. } => pending.push(*object),
+                Expression::Conditional {
+                    condition,
+                    then_expr,
+                    else_expr,
+                    ..
+                } => pending.extend([*condition, *then_expr, *else_expr]),
+                Expression::ValueRange { low, high, .. } => pending.extend([*low, *high]),
+                Expression::RandomizeWith {
+                    call, constraints, ..
+                } => {
+                    pending.push(*call);
+                    pending.extend(constraints.iter().flat_map(ConstraintExpr::expressions));
+                }
+                Expression::FunctionCall {
+                    function,
+                    arguments,
+                    ..
+                } => {
+                    pending.push(*function);
+                    pending.extend(arguments);
+                }
+                Expression::MacroUsage { arguments, .. }
+                | Expression::SystemFunctionCall { arguments, .. }
+                | Expression::New { arguments, .. } => pending.extend(arguments),
+                Expression::TaggedUnion {
+                    value: Some(value), ..
+                } => pending.push(*value),
+                Expression::AssignmentPattern { items, .. } => {
+                    pending.extend(items.iter().map(|item| item.value));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+/// The variable an assignment target writes: `a` in `a`, `a.b` and `a.b.c`
+fn root_name(expr_ref: ExprRef, exprs: &ExprArena) -> Option<&str> {
+    match exprs.get(expr_ref) {
+        Expression::Identifier(name, _) => Some(name),
+        Expression::MemberAccess { object, .. } => root_name(*object, exprs),
+        _ => None,
+    }
+}
+
+/// Statement indices grouped by shared variables, each group and the groups
+/// themselves in source order
+fn group_statements(accesses: &[Access]) -> Vec<Vec<usize>> {
+    let mut parent: Vec<usize> = (0..accesses.len()).collect();
+    fn find(parent: &mut [usize], index: usize) -> usize {
+        let mut root = index;
+        while parent[root] != root {
+            root = parent[root];
+        }
+        parent[index] = root;
+        root
+    }
+    fn union(parent: &mut [usize], a: usize, b: usize) {
+        let (a, b) = (find(parent, a), find(parent, b));
+        // The earlier statement is the root, so groups are ordered by their first statement
+        parent[a.max(b)] = a.min(b);
+    }
+
+    for (i, access) in accesses.iter().enumerate() {
+        let mut connected = false;
+        for (j, other) in accesses.iter().enumerate().take(i) {
+            let shares = !access.drives.is_disjoint(&other.drives)
+                || !access.reads.is_disjoint(&other.drives)
+                || !access.drives.is_disjoint(&other.reads);
+            if shares {
+                union(&mut parent, i, j);
+                connected = true;
+            }
+        }
+        if !connected && access.drives.is_empty() && i > 0 {
+            union(&mut parent, i, i - 1);
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = Vec::new();
+    let mut roots: Vec<usize> = Vec::new();
+    for index in 0..accesses.len() {
+        let root = find(&mut parent, index);
+        match roots.iter().position(|r| *r == root) {
+            Some(group) => groups[group].push(index),
+            None => {
+                roots.push(root);
+                groups.push(vec![index]);
+            }
+        }
+    }
+    groups
+}
+
+/// `span` without the whitespace around it
+fn trimmed_span(chars: &[char], span: Span) -> Span {
+    let mut start = span.0;
+    let mut end = span.1;
+    while start < end && chars[start].is_whitespace() {
+        start += 1;
+    }
+    while end > start && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    (start, end)
+}
+
+/// The whitespace at the start of the line containing `offset`
+fn indentation(chars: &[char], offset: usize) -> String {
+    let line_start = chars[..offset]
+        .iter()
+        .rposition(|c| *c == '\n')
+        .map_or(0, |newline| newline + 1);
+    chars[line_start..offset]
+        .iter()
+        .take_while(|c| **c == ' ' || **c == '\t')
+        .collect()
+}
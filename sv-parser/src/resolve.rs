@@ -0,0 +1,363 @@
+//! Name resolution through package imports
+//!
+//! A scope sees the names it declares itself, the names it imports
+//! explicitly (`import p::x;`) and, for names found nowhere else, the names
+//! of wildcard-imported packages (`import p::*;`). [`ImportScope`] applies
+//! these rules (IEEE 1800-2017 26.3): local declarations win over explicit
+//! imports, which win over wildcard imports, and a name that two wildcard
+//! imports provide is ambiguous unless one of the first two provides it.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::{
+    ModuleItem, ModuleItemArena, ModuleItemRef, PackageImport, Port, SourceUnit, Span, Statement,
+    StmtArena, StmtRef,
+};
+
+/// Names declared in each package, with the span of each declaration
+pub type PackageExports = HashMap<String, HashMap<String, Span>>;
+
+/// The names the items of a package declare, with the spans of the names:
+/// its parameters, variables, types, enum members and classes
+pub fn package_declarations(
+    items: &[ModuleItemRef],
+    arena: &ModuleItemArena,
+) -> Vec<(String, Span)> {
+    let mut declarations = Vec::new();
+    for item_ref in items {
+        let item = arena.get(*item_ref);
+        match item {
+            ModuleItem::ParameterDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::VariableDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::TypedefDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::ClassDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::InterfaceClassDeclaration {
+                name, name_span, ..
+            }
+            | ModuleItem::SubroutineDeclaration {
+                name, name_span, ..
+            } => declarations.push((name.clone(), *name_span)),
+            _ => continue,
+        }
+        // Enum members are visible in the package like its other names
+        declarations.extend(
+            item.enum_members()
+                .iter()
+                .map(|member| (member.name.clone(), member.name_span)),
+        );
+    }
+    declarations
+}
+
+/// The declarations of each package of a source unit, without the names
+/// the packages pass on with `export`
+pub fn package_exports(source_unit: &SourceUnit) -> PackageExports {
+    let arena = &source_unit.module_item_arena;
+    source_unit
+        .items
+        .iter()
+        .filter_map(|item_ref| match arena.get(*item_ref) {
+            ModuleItem::PackageDeclaration { name, items, .. } => Some((
+                name.clone(),
+                package_declarations(items, arena).into_iter().collect(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// An import declaration of a scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    pub package: String,
+    /// The imported name; None for a wildcard import (`p::*`)
+    pub item: Option<String>,
+    pub span: Span,
+}
+
+impl From<&PackageImport> for Import {
+    fn from(import: &PackageImport) -> Self {
+        Import {
+            package: import.package.clone(),
+            item: import.name.clone(),
+            span: import.span,
+        }
+    }
+}
+
+/// Where a name used in a scope comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution<'a> {
+    /// Declared in the scope itself
+    Local,
+    /// Declared in `package` at `declaration`, and imported by `import`
+    Imported {
+        package: &'a str,
+        declaration: Span,
+        import: &'a Import,
+    },
+    /// Provided by several wildcard imports and nothing else, by package name
+    Ambiguous(Vec<&'a str>),
+    Unresolved,
+}
+
+/// A problem with the imports of a scope
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportDiagnostic {
+    pub message: String,
+    pub span: Span,
+}
+
+/// The declarations and imports of one scope, such as a module
+#[derive(Debug, Clone, Default)]
+pub struct ImportScope {
+    imports: Vec<Import>,
+    locals: HashSet<String>,
+}
+
+impl ImportScope {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_import(&mut self, import: Import) {
+        self.imports.push(import);
+    }
+
+    /// Record a name declared in the scope itself
+    pub fn add_local(&mut self, name: &str) {
+        self.locals.insert(name.to_string());
+    }
+
+    /// Record the names the ports and items of a module, interface or package
+    /// declare, with those of its generate constructs and the variables of
+    /// its procedural blocks; nested modules and classes keep theirs
+    pub fn add_declarations(
+        &mut self,
+        ports: &[Port],
+        items: &[ModuleItemRef],
+        arena: &ModuleItemArena,
+        stmt_arena: &StmtArena,
+    ) {
+        for port in ports {
+            self.add_local(&port.name);
+        }
+        for item_ref in items {
+            let item = arena.get(*item_ref);
+            for member in item.enum_members() {
+                self.add_local(&member.name);
+            }
+            match item {
+                ModuleItem::PortDeclaration { name, .. }
+                | ModuleItem::VariableDeclaration { name, .. }
+                | ModuleItem::TypedefDeclaration { name, .. }
+                | ModuleItem::ParameterDeclaration { name, .. }
+                | ModuleItem::ClockingBlock { name, .. }
+                | ModuleItem::AssertionDeclaration { name, .. }
+                | ModuleItem::ClassDeclaration { name, .. }
+                | ModuleItem::InterfaceClassDeclaration { name, .. }
+                | ModuleItem::SubroutineDeclaration { name, .. } => self.add_local(name),
+                ModuleItem::ModuleInstantiation { instance_name, .. } => {
+                    self.add_local(instance_name)
+                }
+                ModuleItem::GenvarDeclaration { genvars, .. } => {
+                    for genvar in genvars {
+                        self.add_local(&genvar.name);
+                    }
+                }
+                ModuleItem::GenerateFor { genvar, .. } => self.add_local(genvar),
+                ModuleItem::ProceduralBlock { statements, .. } => {
+                    self.add_statement_declarations(statements, stmt_arena);
+                }
+                _ => {}
+            }
+            self.add_declarations(&[], &item.generate_items(), arena, stmt_arena);
+        }
+    }
+
+    /// Record the variables and loop variables declared in statements, at any depth
+    fn add_statement_declarations(&mut self, statements: &[StmtRef], arena: &StmtArena) {
+        for stmt_ref in statements {
+            let statement = arena.get(*stmt_ref);
+            match statement {
+                Statement::VariableDeclaration { name, .. } => self.add_local(name),
+                Statement::Foreach { indices, .. } => {
+                    for index in indices.iter().flatten() {
+                        self.add_local(&index.name);
+                    }
+                }
+                _ => {}
+            }
+            self.add_statement_declarations(&statement.children(), arena);
+        }
+    }
+
+    /// The scope names at `offset` of a source unit are resolved in: the
+    /// innermost module, interface, checker or package around it, with the
+    /// names of the scopes around that and the compilation-unit imports
+    /// before it
+    pub fn at(source_unit: &SourceUnit, offset: usize) -> Self {
+        let arena = &source_unit.module_item_arena;
+        let mut scope = ImportScope::new();
+        scope.add_declarations(&[], &source_unit.items, arena, &source_unit.stmt_arena);
+        let mut items = source_unit.items.as_slice();
+        'scopes: loop {
+            for item_ref in items {
+                let item = arena.get(*item_ref);
+                if let ModuleItem::ImportDeclaration { imports, span } = item {
+                    if span.0 < offset {
+                        scope.imports.extend(imports.iter().map(Import::from));
+                    }
+                    continue;
+                }
+                let (ports, inner, span) = match item {
+                    ModuleItem::ModuleDeclaration {
+                        ports, items, span, ..
+                    }
+                    | ModuleItem::InterfaceDeclaration {
+                        ports, items, span, ..
+                    }
+                    | ModuleItem::CheckerDeclaration {
+                        ports, items, span, ..
+                    } => (ports.as_slice(), items, span),
+                    ModuleItem::PackageDeclaration { items, span, .. } => (&[][..], items, span),
+                    _ => continue,
+                };
+                if span.0 <= offset && offset < span.1 {
+                    scope.add_declarations(ports, inner, arena, &source_unit.stmt_arena);
+                    items = inner;
+                    continue 'scopes;
+                }
+            }
+            return scope;
+        }
+    }
+
+    pub fn imports(&self) -> &[Import] {
+        &self.imports
+    }
+
+    /// Resolve a name used in the scope
+    pub fn resolve<'a>(&'a self, name: &str, packages: &'a PackageExports) -> Resolution<'a> {
+        if self.locals.contains(name) {
+            return Resolution::Local;
+        }
+
+        let declared_in = |import: &'a Import| {
+            packages
+                .get_key_value(&import.package)
+                .and_then(|(package, names)| Some((package.as_str(), *names.get(name)?)))
+        };
+
+        if let Some((package, declaration, import)) = self
+            .imports
+            .iter()
+            .filter(|import| import.item.as_deref() == Some(name))
+            .find_map(|import| declared_in(import).map(|(p, d)| (p, d, import)))
+        {
+            return Resolution::Imported {
+                package,
+                declaration,
+                import,
+            };
+        }
+
+        // The same package imported twice with a wildcard is still one candidate
+        let mut candidates: BTreeMap<&str, (Span, &Import)> = BTreeMap::new();
+        for import in self.imports.iter().filter(|import| import.item.is_none()) {
+            if let Some((package, declaration)) = declared_in(import) {
+                candidates.entry(package).or_insert((declaration, import));
+            }
+        }
+        match candidates.len() {
+            0 => Resolution::Unresolved,
+            1 => {
+                let (package, (declaration, import)) = candidates.pop_first().unwrap();
+                Resolution::Imported {
+                    package,
+                    declaration,
+                    import,
+                }
+            }
+            _ => Resolution::Ambiguous(candidates.into_keys().collect()),
+        }
+    }
+
+    /// Check the imports themselves: unknown packages and names, and explicit
+    /// imports that clash with each other or with a local declaration
+    ///
+    /// Ambiguous wildcard imports are only an error where the name is used, so
+    /// they are reported by [`check_reference`](Self::check_reference).
+    pub fn check_imports(&self, packages: &PackageExports) -> Vec<ImportDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut explicit: HashMap<&str, &str> = HashMap::new();
+
+        for import in &self.imports {
+            let Some(names) = packages.get(&import.package) else {
+                diagnostics.push(ImportDiagnostic {
+                    message: format!("Unknown package '{}'", import.package),
+                    span: import.span,
+                });
+                continue;
+            };
+            let Some(item) = import.item.as_deref() else {
+                continue;
+            };
+
+            if !names.contains_key(item) {
+                diagnostics.push(ImportDiagnostic {
+                    message: format!("'{}' is not declared in package '{}'", item, import.package),
+                    span: import.span,
+                });
+            } else if self.locals.contains(item) {
+                diagnostics.push(ImportDiagnostic {
+                    message: format!(
+                        "'{}' is imported from '{}' but also declared in this scope",
+                        item, import.package
+                    ),
+                    span: import.span,
+                });
+            } else if let Some(previous) = explicit.insert(item, &import.package) {
+                if previous != import.package {
+                    diagnostics.push(ImportDiagnostic {
+                        message: format!("'{}' is already imported from '{}'", item, previous),
+                        span: import.span,
+                    });
+                }
+            }
+        }
+        diagnostics
+    }
+
+    /// Report a use of `name` at `span` that wildcard imports make ambiguous
+    pub fn check_reference(
+        &self,
+        name: &str,
+        span: Span,
+        packages: &PackageExports,
+    ) -> Option<ImportDiagnostic> {
+        let Resolution::Ambiguous(candidates) = self.resolve(name, packages) else {
+            return None;
+        };
+        let candidates: Vec<String> = candidates
+            .iter()
+            .map(|package| format!("'{}'", package))
+            .collect();
+        Some(ImportDiagnostic {
+            message: format!(
+                "'{}' is ambiguous: it is imported with wildcards from {}; import it explicitly",
+                name,
+                candidates.join(" and ")
+            ),
+            span,
+        })
+    }
+}
@@ -7,8 +7,20 @@
 //! - Type checking
 //! - Scope resolution
 
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::number::{evaluate_constant, parameter_values, NumberLiteral};
+use crate::parallel;
+use crate::resolve::{package_declarations, Import, ImportDiagnostic, ImportScope, PackageExports};
+use crate::stats::Stopwatch;
+use crate::timescale::{time_scopes, TimeSource};
 use crate::{
-    ExprArena, ExprRef, Expression, ModuleItem, ModuleItemArena, SourceUnit, Statement, StmtArena,
+    is_net_type, scoped_method_name, DataType, DriveStrength, ElaborationSeverity, ExprArena,
+    ExprRef, Expression, GenerateCaseItem, Label, ModuleItem, ModuleItemArena, ModuleItemRef,
+    PackageImport, Pattern, PortConnection, PortDirection, PropertyExpr, SourceUnit, Span,
+    Statement, StmtArena, StmtRef, StructMember, TimeValue,
 };
 
 /// Represents a semantic error found during analysis
@@ -17,10 +29,21 @@ pub struct SemanticError {
     pub error_type: SemanticErrorType,
     pub message: String,
     pub span: (usize, usize),
+    /// Another location that explains the error, such as the declaration it conflicts with
+    pub related: Option<RelatedInformation>,
 }
 
-/// Types of semantic errors
+/// Secondary location attached to a semantic error
 #[derive(Debug, Clone, PartialEq)]
+pub struct RelatedInformation {
+    pub message: String,
+    pub span: (usize, usize),
+}
+
+/// Types of semantic errors
+///
+/// The declaration order doubles as the tie-breaker when sorting errors at the same span.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum SemanticErrorType {
     /// Unknown system function (e.g., $fel instead of $fell)
     UnknownSystemFunction,
@@ -30,37 +53,350 @@ pub enum SemanticErrorType {
     TypeMismatch,
     /// Invalid operation
     InvalidOperation,
+    /// End label that does not match the block name (`begin : a ... end : b`)
+    LabelMismatch,
+    /// Inheritance clause naming the wrong kind of class (`implements` a plain class)
+    InvalidInheritance,
+    /// Declaration repeated where only one is allowed (two `default clocking` items in a module)
+    DuplicateDeclaration,
+    /// Assignment to a literal (`5 = a`)
+    AssignmentToLiteral,
+    /// Assignment to the result of a function call (`f(x) = a`)
+    AssignmentToCall,
+    /// Assignment to an input port from inside its module
+    AssignmentToInput,
+    /// Assignment to a parameter, localparam or enum member
+    AssignmentToParameter,
+    /// `++` or `--` on a net, which only continuous assignments can drive
+    IncDecOnNet,
+    /// `this` or `super` outside a class method, `super` in a class without a base
+    /// class, or `super.new` anywhere but first in a constructor
+    InvalidClassReference,
+    /// Member access or assignment pattern key naming a member the struct does not have
+    UnknownMember,
+    /// `$error` or `$fatal` module item, which fails elaboration of its module
+    ElaborationFailure,
+    /// `return` outside a function, with a value in a void function or
+    /// constructor, or without one in a function with a return type
+    InvalidReturn,
+    /// Time literal that is not 1, 10 or 100 of a unit, or a time precision
+    /// coarser than the time unit it applies to
+    InvalidTimeUnit,
+    /// Drive strength without one strength for each value, like `(weak0, strong0)`,
+    /// or with both high impedance
+    InvalidDriveStrength,
+    /// File I/O or memory load call with the wrong number of arguments, or an
+    /// argument of the wrong kind, like `$readmemh("f.hex", 0)`
+    InvalidSystemCallArguments,
+    /// Import of a name also declared or imported from another package in the
+    /// same scope, or use of a name that several wildcard imports provide
+    InvalidImport,
+}
+
+impl SemanticErrorType {
+    /// Every error type, in declaration order
+    pub const ALL: [SemanticErrorType; 20] = [
+        SemanticErrorType::UnknownSystemFunction,
+        SemanticErrorType::UndeclaredIdentifier,
+        SemanticErrorType::TypeMismatch,
+        SemanticErrorType::InvalidOperation,
+        SemanticErrorType::LabelMismatch,
+        SemanticErrorType::InvalidInheritance,
+        SemanticErrorType::DuplicateDeclaration,
+        SemanticErrorType::AssignmentToLiteral,
+        SemanticErrorType::AssignmentToCall,
+        SemanticErrorType::AssignmentToInput,
+        SemanticErrorType::AssignmentToParameter,
+        SemanticErrorType::IncDecOnNet,
+        SemanticErrorType::InvalidClassReference,
+        SemanticErrorType::UnknownMember,
+        SemanticErrorType::ElaborationFailure,
+        SemanticErrorType::InvalidReturn,
+        SemanticErrorType::InvalidTimeUnit,
+        SemanticErrorType::InvalidDriveStrength,
+        SemanticErrorType::InvalidSystemCallArguments,
+        SemanticErrorType::InvalidImport,
+    ];
+
+    /// Stable code of the error, like `SV0002`, which `--explain` describes
+    pub fn code(self) -> &'static str {
+        match self {
+            SemanticErrorType::UnknownSystemFunction => "SV0001",
+            SemanticErrorType::UndeclaredIdentifier => "SV0002",
+            SemanticErrorType::TypeMismatch => "SV0003",
+            SemanticErrorType::InvalidOperation => "SV0004",
+            SemanticErrorType::LabelMismatch => "SV0005",
+            SemanticErrorType::InvalidInheritance => "SV0006",
+            SemanticErrorType::DuplicateDeclaration => "SV0007",
+            SemanticErrorType::AssignmentToLiteral => "SV0008",
+            SemanticErrorType::AssignmentToCall => "SV0009",
+            SemanticErrorType::AssignmentToInput => "SV0010",
+            SemanticErrorType::AssignmentToParameter => "SV0011",
+            SemanticErrorType::IncDecOnNet => "SV0012",
+            SemanticErrorType::InvalidClassReference => "SV0013",
+            SemanticErrorType::UnknownMember => "SV0014",
+            SemanticErrorType::ElaborationFailure => "SV0015",
+            SemanticErrorType::InvalidReturn => "SV0016",
+            SemanticErrorType::InvalidTimeUnit => "SV0017",
+            SemanticErrorType::InvalidDriveStrength => "SV0018",
+            SemanticErrorType::InvalidSystemCallArguments => "SV0019",
+            SemanticErrorType::InvalidImport => "SV0020",
+        }
+    }
+}
+
+/// Clock and disable condition that apply to a concurrent assertion
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertionContext {
+    /// Span of the assertion
+    pub span: Span,
+    /// Where the assertion's clock comes from; None if it has no clock at all
+    pub clock: Option<AssertionClock>,
+    /// Effective `disable iff` condition: the assertion's own, else the module default
+    pub disable_iff: Option<ExprRef>,
+}
+
+/// Source of a concurrent assertion's clock
+#[derive(Debug, Clone, PartialEq)]
+pub enum AssertionClock {
+    /// Clocking event written in the property itself
+    Explicit(ExprRef),
+    /// Name of the module's `default clocking` block
+    Default(String),
+}
+
+/// `default clocking` and `default disable iff` in effect for the module being analyzed
+#[derive(Debug, Clone, Default)]
+struct ModuleDefaults {
+    clocking: Option<String>,
+    disable_iff: Option<ExprRef>,
+}
+
+/// Module-level name that assignments inside the module must not target
+#[derive(Debug, Clone)]
+struct ReadOnlyDeclaration {
+    kind: ReadOnlyKind,
+    span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReadOnlyKind {
+    Input,
+    Parameter,
+    EnumMember,
+}
+
+/// Names a package declares, as seen from the modules that import it
+#[derive(Debug, Clone, Default)]
+struct PackageScope {
+    /// Every parameter, variable, type, enum member and class of the package,
+    /// with the spans of their names
+    names: BTreeMap<String, Span>,
+    /// The parameters among them, with the spans of their names
+    parameters: HashMap<String, Span>,
+}
+
+/// The class method being analyzed, which `this` and `super` refer to, or
+/// the function or task outside a class
+#[derive(Debug, Clone)]
+struct MethodScope {
+    /// None for a function or task outside a class
+    class: Option<String>,
+    method: String,
+    has_base: bool,
+    /// Declared `static`, so it runs without an object for `this` to refer to
+    is_static: bool,
+    /// Whether the method is a function with a return type, whose `return`
+    /// statements must give a value
+    returns_value: bool,
+    /// The `super.new` that starts the constructor being analyzed, the only one allowed
+    constructor_call: Option<ExprRef>,
+}
+
+impl MethodScope {
+    /// `class::method`, or the bare name of a function or task outside a class
+    fn qualified_name(&self) -> String {
+        match &self.class {
+            Some(class) => scoped_method_name(class, &self.method),
+            None => self.method.clone(),
+        }
+    }
 }
 
 /// Semantic analyzer that validates an AST
 pub struct SemanticAnalyzer {
     errors: Vec<SemanticError>,
+    /// Classes declared in the source unit, mapped to whether they are interface classes
+    class_kinds: Arc<HashMap<String, bool>>,
+    /// Scopes of the `extern` methods of those classes, by
+    /// [`scoped_method_name`], for their bodies outside the class
+    extern_methods: Arc<HashMap<String, MethodScope>>,
+    /// Packages declared in the source unit, by name
+    packages: Arc<HashMap<String, PackageScope>>,
+    /// The names of those packages, as import resolution sees them
+    package_exports: Arc<PackageExports>,
+    /// Imports at compilation-unit scope seen so far, which apply to every later module
+    unit_imports: Vec<PackageImport>,
+    module_defaults: ModuleDefaults,
+    /// Input ports and parameters of the module being analyzed
+    read_only: HashMap<String, ReadOnlyDeclaration>,
+    /// Struct and union typedefs of the compilation unit and of the module being analyzed
+    struct_types: HashMap<String, Vec<StructMember>>,
+    /// Variables of the module being analyzed that have a struct or union type
+    struct_variables: HashMap<String, Vec<StructMember>>,
+    /// Nets of the module being analyzed, with the spans of their names
+    nets: HashMap<String, Span>,
+    method_scope: Option<MethodScope>,
+    /// Declarations and imports that names used in the items being analyzed
+    /// resolve through; None inside a class, whose members are not tracked
+    import_scope: Option<ImportScope>,
+    /// Values of the constant parameters of the module being analyzed
+    parameters: HashMap<String, NumberLiteral>,
+    /// Whether the items being analyzed are the body of a generate loop, or a
+    /// generate branch whose guard is constant false, which may never be
+    /// elaborated
+    in_generate_body: bool,
+    assertion_contexts: Vec<AssertionContext>,
+    item_timings: Vec<(ModuleItemRef, Duration)>,
 }
 
 impl SemanticAnalyzer {
     /// Create a new semantic analyzer
     pub fn new() -> Self {
-        Self { errors: Vec::new() }
+        Self {
+            errors: Vec::new(),
+            class_kinds: Arc::default(),
+            extern_methods: Arc::default(),
+            packages: Arc::default(),
+            package_exports: Arc::default(),
+            unit_imports: Vec::new(),
+            module_defaults: ModuleDefaults::default(),
+            read_only: HashMap::new(),
+            struct_types: HashMap::new(),
+            struct_variables: HashMap::new(),
+            nets: HashMap::new(),
+            method_scope: None,
+            import_scope: None,
+            parameters: HashMap::new(),
+            in_generate_body: false,
+            assertion_contexts: Vec::new(),
+            item_timings: Vec::new(),
+        }
     }
 
     /// Analyze a source unit and return any semantic errors found
+    ///
+    /// Once the classes and packages of the whole unit are known, its
+    /// top-level items are analyzed on several threads; the errors are
+    /// reported in source order either way.
     pub fn analyze(&mut self, source_unit: &SourceUnit) -> Vec<SemanticError> {
         self.errors.clear();
+        self.class_kinds = Arc::default();
+        self.extern_methods = Arc::default();
+        self.packages = Arc::default();
+        self.package_exports = Arc::default();
+        self.unit_imports.clear();
+        self.module_defaults = ModuleDefaults::default();
+        self.read_only.clear();
+        self.struct_types = Self::collect_struct_types(
+            &source_unit.items,
+            &source_unit.module_item_arena,
+            HashMap::new(),
+        );
+        self.struct_variables.clear();
+        self.nets.clear();
+        self.method_scope = None;
+        self.import_scope = None;
+        self.assertion_contexts.clear();
+        self.item_timings.clear();
+        self.collect_class_kinds(&source_unit.items, &source_unit.module_item_arena);
+        self.collect_packages(&source_unit.items, &source_unit.module_item_arena);
+        self.package_exports = Arc::new(
+            self.packages
+                .iter()
+                .map(|(name, scope)| (name.clone(), scope.names.clone().into_iter().collect()))
+                .collect(),
+        );
+        self.check_time_units(source_unit);
+        let mut unit_scope = ImportScope::new();
+        unit_scope.add_declarations(
+            &[],
+            &source_unit.items,
+            &source_unit.module_item_arena,
+            &source_unit.stmt_arena,
+        );
 
-        // Walk the AST and validate - items is now Vec<ModuleItemRef>
-        for item_ref in &source_unit.items {
-            let item = source_unit.module_item_arena.get(*item_ref);
-            self.analyze_module_item(
-                item,
+        // Compilation-unit imports apply to every item after them, so each
+        // item is analyzed with the ones up to and including itself
+        let shards: Vec<(ModuleItemRef, usize)> = source_unit
+            .items
+            .iter()
+            .map(|item_ref| {
+                let item = source_unit.module_item_arena.get(*item_ref);
+                if let ModuleItem::ImportDeclaration { imports, .. } = item {
+                    self.unit_imports.extend(imports.iter().cloned());
+                }
+                (*item_ref, self.unit_imports.len())
+            })
+            .collect();
+        let mut unit_imports = unit_scope.clone();
+        for import in &self.unit_imports {
+            self.add_import(&mut unit_imports, import);
+        }
+        let diagnostics = unit_imports.check_imports(&self.package_exports);
+        self.report_imports(diagnostics);
+        let analyzer = &*self;
+        let results = parallel::map(&shards, |(item_ref, imports)| {
+            let start = Stopwatch::start();
+            let mut import_scope = unit_scope.clone();
+            for import in &analyzer.unit_imports[..*imports] {
+                analyzer.add_import(&mut import_scope, import);
+            }
+            let mut shard = SemanticAnalyzer {
+                class_kinds: Arc::clone(&analyzer.class_kinds),
+                extern_methods: Arc::clone(&analyzer.extern_methods),
+                packages: Arc::clone(&analyzer.packages),
+                package_exports: Arc::clone(&analyzer.package_exports),
+                unit_imports: analyzer.unit_imports[..*imports].to_vec(),
+                import_scope: Some(import_scope),
+                struct_types: analyzer.struct_types.clone(),
+                ..SemanticAnalyzer::new()
+            };
+            shard.analyze_module_item(
+                source_unit.module_item_arena.get(*item_ref),
                 &source_unit.expr_arena,
                 &source_unit.stmt_arena,
                 &source_unit.module_item_arena,
             );
+            (shard.errors, shard.assertion_contexts, start.elapsed())
+        });
+        for ((item_ref, _), (errors, assertion_contexts, elapsed)) in shards.iter().zip(results) {
+            self.errors.extend(errors);
+            self.assertion_contexts.extend(assertion_contexts);
+            self.item_timings.push((*item_ref, elapsed));
         }
 
+        // Checks run in passes, so report in source order instead of discovery order
+        self.errors.sort_by(|a, b| {
+            (a.span, a.error_type)
+                .cmp(&(b.span, b.error_type))
+                .then_with(|| a.message.cmp(&b.message))
+        });
         self.errors.clone()
     }
 
+    /// Effective clock and disable condition of each concurrent assertion seen by the
+    /// last call to [`analyze`](Self::analyze), in source order
+    pub fn assertion_contexts(&self) -> &[AssertionContext] {
+        &self.assertion_contexts
+    }
+
+    /// Time spent analyzing each top-level item during the last call to
+    /// [`analyze`](Self::analyze), in source order
+    pub fn item_timings(&self) -> &[(ModuleItemRef, Duration)] {
+        &self.item_timings
+    }
+
     /// Analyze a module item
     fn analyze_module_item(
         &mut self,
@@ -70,14 +406,41 @@ impl SemanticAnalyzer {
         module_item_arena: &ModuleItemArena,
     ) {
         match item {
-            ModuleItem::ModuleDeclaration { items, .. } => {
-                // Recursively analyze nested items - items are now refs into the arena
-                for item_ref in items {
-                    let sub_item = module_item_arena.get(*item_ref);
-                    self.analyze_module_item(sub_item, expr_arena, stmt_arena, module_item_arena);
+            ModuleItem::ModuleDeclaration { ports, items, .. }
+            | ModuleItem::InterfaceDeclaration { ports, items, .. }
+            | ModuleItem::CheckerDeclaration { ports, items, .. } => {
+                self.analyze_scope(ports, items, expr_arena, stmt_arena, module_item_arena);
+            }
+            // A package is analyzed like a module without ports
+            ModuleItem::PackageDeclaration { items, .. } => {
+                self.analyze_scope(&[], items, expr_arena, stmt_arena, module_item_arena);
+            }
+            ModuleItem::ImportDeclaration {
+                imports: packages, ..
+            }
+            | ModuleItem::ExportDeclaration {
+                exports: packages, ..
+            } => {
+                for import in packages {
+                    if let Some(name) = &import.name {
+                        self.check_package_member(&import.package, name, import.name_span);
+                    }
                 }
             }
-            ModuleItem::ProceduralBlock { statements, .. } => {
+            ModuleItem::ProceduralBlock {
+                event_control,
+                statements,
+                label,
+                end_label,
+                ..
+            } => {
+                self.check_end_label(label.as_ref(), end_label.as_ref());
+                for expr_ref in event_control
+                    .iter()
+                    .flat_map(|control| control.expressions())
+                {
+                    self.analyze_expression_ref(expr_ref, expr_arena);
+                }
                 // statements is now Vec<StmtRef>
                 for stmt_ref in statements {
                     let statement = stmt_arena.get(*stmt_ref);
@@ -85,32 +448,1253 @@ impl SemanticAnalyzer {
                 }
             }
             ModuleItem::VariableDeclaration {
-                initial_value: Some(expr),
+                data_type,
+                drive_strength,
+                name,
+                initial_value,
                 ..
             } => {
-                self.analyze_expression_ref(*expr, expr_arena);
+                if let Some(strength) = drive_strength {
+                    self.check_drive_strength(strength);
+                }
+                for value in item.enum_members().iter().filter_map(|member| member.value) {
+                    self.analyze_expression_ref(value, expr_arena);
+                }
+                if let Some(expr) = initial_value {
+                    let members = Self::resolve_struct_members(data_type, &self.struct_types)
+                        .unwrap_or_default();
+                    self.check_pattern_keys(name, &members, *expr, expr_arena);
+                    self.analyze_expression_ref(*expr, expr_arena);
+                }
+            }
+            ModuleItem::TypedefDeclaration { .. } => {
+                for value in item.enum_members().iter().filter_map(|member| member.value) {
+                    self.analyze_expression_ref(value, expr_arena);
+                }
             }
-            ModuleItem::Assignment { expr, .. } => {
+            ModuleItem::Assignment {
+                drive_strength,
+                target,
+                expr,
+                span,
+                ..
+            } => {
+                if let Some(strength) = drive_strength {
+                    self.check_drive_strength(strength);
+                }
+                self.check_assignment_target(*target, *span, expr_arena);
+                self.check_struct_assignment(*target, *expr, expr_arena);
                 self.analyze_expression_ref(*expr, expr_arena);
             }
-            ModuleItem::ConcurrentAssertion { statement, .. } => {
-                // statement is now StmtRef
-                let stmt = stmt_arena.get(*statement);
-                self.analyze_statement(stmt, expr_arena, stmt_arena);
+            ModuleItem::ParameterDeclaration { value, .. } => {
+                self.analyze_expression_ref(*value, expr_arena);
+            }
+            ModuleItem::ConcurrentAssertion {
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                span,
+                ..
+            } => {
+                let clock = match clocking_event {
+                    Some(event) => Some(AssertionClock::Explicit(*event)),
+                    None => self
+                        .module_defaults
+                        .clocking
+                        .clone()
+                        .map(AssertionClock::Default),
+                };
+                self.assertion_contexts.push(AssertionContext {
+                    span: *span,
+                    clock,
+                    disable_iff: disable_iff.or(self.module_defaults.disable_iff),
+                });
+
+                if let Some(condition) = disable_iff {
+                    self.analyze_expression_ref(*condition, expr_arena);
+                }
+                self.analyze_property(property, expr_arena);
+                for action in pass_action.iter().chain(fail_action) {
+                    let stmt = stmt_arena.get(*action);
+                    self.analyze_statement(stmt, expr_arena, stmt_arena);
+                }
+            }
+            ModuleItem::AssertionDeclaration {
+                name,
+                name_span,
+                disable_iff,
+                body,
+                end_label,
+                ..
+            } => {
+                let label = Label {
+                    name: name.clone(),
+                    span: *name_span,
+                };
+                self.check_end_label(Some(&label), end_label.as_ref());
+                if let Some(condition) = disable_iff {
+                    self.analyze_expression_ref(*condition, expr_arena);
+                }
+                self.analyze_property(body, expr_arena);
             }
-            ModuleItem::ClassDeclaration { items, .. } => {
+            ModuleItem::ClockingBlock {
+                name,
+                name_span,
+                end_label,
+                ..
+            } => {
+                let label = Label {
+                    name: name.clone(),
+                    span: *name_span,
+                };
+                self.check_end_label(Some(&label), end_label.as_ref());
+            }
+            ModuleItem::DefaultDisableIff { condition, .. } => {
+                self.analyze_expression_ref(*condition, expr_arena);
+            }
+            ModuleItem::ClassDeclaration {
+                name,
+                name_span,
+                extends,
+                implements,
+                items,
+                ..
+            } => {
+                if let Some(base) = extends {
+                    if self.class_kinds.get(base) == Some(&true) {
+                        self.errors.push(SemanticError {
+                            error_type: SemanticErrorType::InvalidInheritance,
+                            message: format!(
+                                "Class '{}' cannot extend interface class '{}'; use 'implements'",
+                                name, base
+                            ),
+                            span: *name_span,
+                            related: None,
+                        });
+                    }
+                }
+                for interface in implements {
+                    self.check_interface_class(name, interface, *name_span);
+                }
+                // Class members may shadow module ports, parameters, nets and
+                // imported names, so don't check them
+                let outer_read_only = std::mem::take(&mut self.read_only);
+                let outer_nets = std::mem::take(&mut self.nets);
+                let outer_import_scope = self.import_scope.take();
                 for class_item in items {
-                    self.analyze_class_item(class_item, expr_arena, stmt_arena);
+                    self.analyze_class_item(
+                        class_item,
+                        name,
+                        extends.is_some(),
+                        expr_arena,
+                        stmt_arena,
+                    );
+                }
+                self.read_only = outer_read_only;
+                self.nets = outer_nets;
+                self.import_scope = outer_import_scope;
+            }
+            ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                name,
+                body,
+                ..
+            } => {
+                let returns_value = crate::classes::returns_value(name, return_type.as_deref());
+                let scope = match self
+                    .extern_methods
+                    .get(&scoped_method_name(class_name, name))
+                {
+                    Some(prototype) => MethodScope {
+                        returns_value,
+                        ..prototype.clone()
+                    },
+                    // The class may be declared in another file, and then
+                    // `this` and `super` are taken to be allowed
+                    None => MethodScope {
+                        class: Some(class_name.clone()),
+                        method: name.clone(),
+                        has_base: true,
+                        is_static: false,
+                        returns_value,
+                        constructor_call: None,
+                    },
+                };
+                let outer_read_only = std::mem::take(&mut self.read_only);
+                let outer_nets = std::mem::take(&mut self.nets);
+                let outer_import_scope = self.import_scope.take();
+                self.analyze_method_body(scope, body, expr_arena, stmt_arena);
+                self.read_only = outer_read_only;
+                self.nets = outer_nets;
+                self.import_scope = outer_import_scope;
+            }
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                return_type,
+                name,
+                body,
+                ..
+            } => {
+                let scope = MethodScope {
+                    class: None,
+                    method: name.clone(),
+                    has_base: false,
+                    is_static: false,
+                    returns_value: !*is_task
+                        && !matches!(return_type.as_deref(), None | Some("void")),
+                    constructor_call: None,
+                };
+                self.analyze_method_body(scope, body, expr_arena, stmt_arena);
+            }
+            ModuleItem::InterfaceClassDeclaration {
+                name,
+                name_span,
+                extends,
+                items,
+                ..
+            } => {
+                for interface in extends {
+                    self.check_interface_class(name, interface, *name_span);
+                }
+                let outer_import_scope = self.import_scope.take();
+                for class_item in items {
+                    self.analyze_class_item(class_item, name, false, expr_arena, stmt_arena);
+                }
+                self.import_scope = outer_import_scope;
+            }
+            ModuleItem::ModuleInstantiation {
+                parameters,
+                connections,
+                ..
+            } => {
+                for connection in parameters.iter().chain(connections) {
+                    match connection {
+                        PortConnection::Positional(expr)
+                        | PortConnection::Named {
+                            expr: Some(expr), ..
+                        } => self.analyze_expression_ref(*expr, expr_arena),
+                        _ => {}
+                    }
+                }
+            }
+            ModuleItem::ElaborationTask {
+                severity,
+                arguments,
+                span,
+            } => {
+                for arg in arguments {
+                    self.analyze_expression_ref(*arg, expr_arena);
+                }
+                if !self.in_generate_body {
+                    self.check_elaboration_task(*severity, arguments, *span, expr_arena);
+                }
+            }
+            ModuleItem::GenerateRegion { items, .. } => {
+                for item_ref in items {
+                    let sub_item = module_item_arena.get(*item_ref);
+                    self.analyze_module_item(sub_item, expr_arena, stmt_arena, module_item_arena);
+                }
+            }
+            ModuleItem::GenerateBlock {
+                label,
+                items,
+                end_label,
+                ..
+            } => {
+                self.check_end_label(label.as_ref(), end_label.as_ref());
+                for item_ref in items {
+                    let sub_item = module_item_arena.get(*item_ref);
+                    self.analyze_module_item(sub_item, expr_arena, stmt_arena, module_item_arena);
+                }
+            }
+            ModuleItem::GenerateFor {
+                init,
+                condition,
+                step,
+                ..
+            } => {
+                for expr in [init, condition, step] {
+                    self.analyze_expression_ref(*expr, expr_arena);
+                }
+                self.analyze_generate_body(item, expr_arena, stmt_arena, module_item_arena);
+            }
+            ModuleItem::GenerateIf {
+                condition,
+                then_item,
+                else_item,
+                ..
+            } => {
+                self.analyze_expression_ref(*condition, expr_arena);
+                let taken = self
+                    .constant(*condition, expr_arena)
+                    .map(|value| value != 0);
+                let branches = std::iter::once((*then_item, taken != Some(false)))
+                    .chain(else_item.map(|else_item| (else_item, taken != Some(true))));
+                for (branch, elaborated) in branches {
+                    self.analyze_generate_branch(
+                        branch,
+                        elaborated,
+                        expr_arena,
+                        stmt_arena,
+                        module_item_arena,
+                    );
+                }
+            }
+            ModuleItem::GenerateCase {
+                expr: case_expr,
+                items,
+                ..
+            } => {
+                self.analyze_expression_ref(*case_expr, expr_arena);
+                for value in items.iter().flat_map(|case_item| &case_item.values) {
+                    self.analyze_expression_ref(*value, expr_arena);
+                }
+                let chosen = self.chosen_case_item(*case_expr, items, expr_arena);
+                for (index, case_item) in items.iter().enumerate() {
+                    let elaborated = chosen.is_none_or(|chosen| chosen == Some(index));
+                    self.analyze_generate_branch(
+                        case_item.body,
+                        elaborated,
+                        expr_arena,
+                        stmt_arena,
+                        module_item_arena,
+                    );
                 }
             }
             _ => {}
         }
     }
 
-    /// Analyze a class item
+    /// Analyze the bodies of a generate loop or branch, where an elaboration
+    /// task only runs if its body is elaborated
+    fn analyze_generate_body(
+        &mut self,
+        item: &ModuleItem,
+        expr_arena: &ExprArena,
+        stmt_arena: &StmtArena,
+        module_item_arena: &ModuleItemArena,
+    ) {
+        let outer = std::mem::replace(&mut self.in_generate_body, true);
+        for item_ref in item.generate_items() {
+            let sub_item = module_item_arena.get(item_ref);
+            self.analyze_module_item(sub_item, expr_arena, stmt_arena, module_item_arena);
+        }
+        self.in_generate_body = outer;
+    }
+
+    /// Analyze a branch of a generate `if` or `case`; one that is not
+    /// `elaborated` because its guard is constant false is analyzed like the
+    /// body of a loop
+    fn analyze_generate_branch(
+        &mut self,
+        branch: ModuleItemRef,
+        elaborated: bool,
+        expr_arena: &ExprArena,
+        stmt_arena: &StmtArena,
+        module_item_arena: &ModuleItemArena,
+    ) {
+        let outer = self.in_generate_body;
+        self.in_generate_body |= !elaborated;
+        let sub_item = module_item_arena.get(branch);
+        self.analyze_module_item(sub_item, expr_arena, stmt_arena, module_item_arena);
+        self.in_generate_body = outer;
+    }
+
+    /// The value of a constant expression over the module's parameters; None
+    /// if it is not constant or has x or z bits
+    fn constant(&self, expr: ExprRef, arena: &ExprArena) -> Option<i128> {
+        evaluate_constant(expr, arena, &self.parameters)?.to_i128()
+    }
+
+    /// Which item of a generate `case` is elaborated: the first whose value
+    /// equals the case expression, else the `default`, if any. None if that
+    /// is not known because a value is not constant.
+    fn chosen_case_item(
+        &self,
+        case_expr: ExprRef,
+        items: &[GenerateCaseItem],
+        arena: &ExprArena,
+    ) -> Option<Option<usize>> {
+        let selector = self.constant(case_expr, arena)?;
+        for (index, case_item) in items.iter().enumerate() {
+            for value in &case_item.values {
+                if self.constant(*value, arena)? == selector {
+                    return Some(Some(index));
+                }
+            }
+        }
+        Some(
+            items
+                .iter()
+                .position(|case_item| case_item.values.is_empty()),
+        )
+    }
+
+    /// Analyze the items of a module, interface or package with its own
+    /// defaults, read-only names, struct variables and nets in effect
+    fn analyze_scope(
+        &mut self,
+        ports: &[crate::Port],
+        items: &[ModuleItemRef],
+        expr_arena: &ExprArena,
+        stmt_arena: &StmtArena,
+        module_item_arena: &ModuleItemArena,
+    ) {
+        // Defaults apply to the whole module, wherever they appear in it
+        let defaults = self.collect_module_defaults(items, module_item_arena);
+        let outer_defaults = std::mem::replace(&mut self.module_defaults, defaults);
+        // A nested module sees the names of the module around it, including
+        // those that module imports, unless it declares them again
+        let mut read_only = self.read_only.clone();
+        read_only.extend(self.imported_parameters(items, module_item_arena));
+        for port in ports {
+            read_only.remove(&port.name);
+        }
+        for item_ref in items {
+            if let ModuleItem::VariableDeclaration { name, .. } = module_item_arena.get(*item_ref) {
+                read_only.remove(name);
+            }
+        }
+        read_only.extend(Self::collect_read_only(ports, items, module_item_arena));
+        let outer_read_only = std::mem::replace(&mut self.read_only, read_only);
+        let struct_types =
+            Self::collect_struct_types(items, module_item_arena, self.struct_types.clone());
+        let outer_struct_types = std::mem::replace(&mut self.struct_types, struct_types);
+        let struct_variables =
+            Self::collect_struct_variables(items, module_item_arena, &self.struct_types);
+        let outer_struct_variables =
+            std::mem::replace(&mut self.struct_variables, struct_variables);
+        let nets = Self::collect_nets(ports, items, module_item_arena);
+        let outer_nets = std::mem::replace(&mut self.nets, nets);
+        let mut parameters = self.parameters.clone();
+        parameters.extend(parameter_values(items, module_item_arena, expr_arena));
+        let outer_parameters = std::mem::replace(&mut self.parameters, parameters);
+        // The scope's own imports are checked against its own declarations;
+        // names used in it also see those of the scopes around it
+        let mut own_scope = ImportScope::new();
+        own_scope.add_declarations(ports, items, module_item_arena, stmt_arena);
+        let mut import_scope = self.import_scope.clone().unwrap_or_default();
+        import_scope.add_declarations(ports, items, module_item_arena, stmt_arena);
+        for item_ref in items {
+            if let ModuleItem::ImportDeclaration { imports, .. } = module_item_arena.get(*item_ref)
+            {
+                for import in imports {
+                    self.add_import(&mut own_scope, import);
+                    self.add_import(&mut import_scope, import);
+                }
+            }
+        }
+        let diagnostics = own_scope.check_imports(&self.package_exports);
+        self.report_imports(diagnostics);
+        let outer_import_scope = self.import_scope.replace(import_scope);
+
+        // Recursively analyze nested items - items are now refs into the arena
+        for item_ref in items {
+            let sub_item = module_item_arena.get(*item_ref);
+            self.analyze_module_item(sub_item, expr_arena, stmt_arena, module_item_arena);
+        }
+
+        self.module_defaults = outer_defaults;
+        self.read_only = outer_read_only;
+        self.struct_types = outer_struct_types;
+        self.struct_variables = outer_struct_variables;
+        self.nets = outer_nets;
+        self.parameters = outer_parameters;
+        self.import_scope = outer_import_scope;
+    }
+
+    /// Add an import to a scope when its package is declared in the source
+    /// unit; an explicit import from another package still hides the name
+    /// from wildcard imports. An explicit import of a name its package lacks
+    /// is left out, as [`check_package_member`](Self::check_package_member)
+    /// reports it.
+    fn add_import(&self, scope: &mut ImportScope, import: &PackageImport) {
+        match (self.package_exports.get(&import.package), &import.name) {
+            (Some(names), Some(name)) if !names.contains_key(name) => {}
+            (Some(_), _) => scope.add_import(Import::from(import)),
+            (None, Some(name)) => scope.add_local(name),
+            (None, None) => {}
+        }
+    }
+
+    fn report_imports(&mut self, diagnostics: Vec<ImportDiagnostic>) {
+        self.errors
+            .extend(diagnostics.into_iter().map(|diagnostic| SemanticError {
+                error_type: SemanticErrorType::InvalidImport,
+                message: diagnostic.message,
+                span: diagnostic.span,
+                related: None,
+            }));
+    }
+
+    /// Report a use of a name that several wildcard imports provide
+    fn check_import_reference(&mut self, name: &str, span: Span) {
+        let Some(scope) = &self.import_scope else {
+            return;
+        };
+        if let Some(diagnostic) = scope.check_reference(name, span, &self.package_exports) {
+            self.errors.push(SemanticError {
+                error_type: SemanticErrorType::InvalidImport,
+                message: diagnostic.message,
+                span: diagnostic.span,
+                related: None,
+            });
+        }
+    }
+
+    /// Parameters of known packages brought into a module by its own imports or
+    /// by earlier compilation-unit imports
+    fn imported_parameters(
+        &self,
+        items: &[ModuleItemRef],
+        arena: &ModuleItemArena,
+    ) -> HashMap<String, ReadOnlyDeclaration> {
+        let local_imports = items
+            .iter()
+            .filter_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::ImportDeclaration { imports, .. } => Some(imports),
+                _ => None,
+            });
+        let mut read_only = HashMap::new();
+        for import in self.unit_imports.iter().chain(local_imports.flatten()) {
+            let Some(package) = self.packages.get(&import.package) else {
+                continue;
+            };
+            for (name, span) in &package.parameters {
+                if import.name.as_ref().is_none_or(|imported| imported == name) {
+                    read_only.insert(
+                        name.clone(),
+                        ReadOnlyDeclaration {
+                            kind: ReadOnlyKind::Parameter,
+                            span: *span,
+                        },
+                    );
+                }
+            }
+        }
+        read_only
+    }
+
+    /// Check the time literals of `` `timescale `` directives and time unit
+    /// declarations, repeated declarations in a design element, and the
+    /// time precision each element ends up with against its time unit
+    fn check_time_units(&mut self, source_unit: &SourceUnit) {
+        let arena = &source_unit.module_item_arena;
+        for item_ref in &source_unit.items {
+            let (name, items) = match arena.get(*item_ref) {
+                ModuleItem::ModuleDeclaration { name, items, .. }
+                | ModuleItem::InterfaceDeclaration { name, items, .. }
+                | ModuleItem::CheckerDeclaration { name, items, .. }
+                | ModuleItem::PackageDeclaration { name, items, .. } => {
+                    (Some(name.as_str()), items.as_slice())
+                }
+                _ => (None, std::slice::from_ref(item_ref)),
+            };
+            let mut declared: [Option<TimeValue>; 2] = [None, None];
+            for declaration in items {
+                match arena.get(*declaration) {
+                    ModuleItem::TimescaleDirective {
+                        unit, precision, ..
+                    } => self.check_time_pair(Some(unit), Some(precision)),
+                    ModuleItem::TimeunitsDeclaration {
+                        unit, precision, ..
+                    } => {
+                        self.check_time_pair(unit.as_ref(), precision.as_ref());
+                        let Some(name) = name else {
+                            continue;
+                        };
+                        let times = [(unit, "timeunit"), (precision, "timeprecision")];
+                        for (first, (time, keyword)) in declared.iter_mut().zip(times) {
+                            let Some(time) = time else {
+                                continue;
+                            };
+                            match first {
+                                None => *first = Some(*time),
+                                // A repeated declaration has to give the same time
+                                Some(first) if first.femtoseconds() != time.femtoseconds() => {
+                                    self.errors.push(SemanticError {
+                                        error_type: SemanticErrorType::DuplicateDeclaration,
+                                        message: format!(
+                                            "'{}' already declares {} {}",
+                                            name,
+                                            keyword,
+                                            first.text()
+                                        ),
+                                        span: time.span,
+                                        related: Some(RelatedInformation {
+                                            message: format!("{} declared here", keyword),
+                                            span: first.span,
+                                        }),
+                                    });
+                                }
+                                Some(_) => {}
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        for scope in time_scopes(source_unit) {
+            let (Some(unit), Some(precision)) = (scope.unit, scope.precision) else {
+                continue;
+            };
+            // A pair set by a single item was checked with the item
+            if unit.span == precision.span
+                || precision.value.femtoseconds() <= unit.value.femtoseconds()
+            {
+                continue;
+            }
+            let span = if precision.source == TimeSource::Declaration
+                || unit.source != TimeSource::Declaration
+            {
+                precision.value.span
+            } else {
+                unit.value.span
+            };
+            self.errors.push(SemanticError {
+                error_type: SemanticErrorType::InvalidTimeUnit,
+                message: format!(
+                    "Time precision {} of '{}', from {}, is coarser than its time unit {}, from {}",
+                    precision.value.text(),
+                    scope.name,
+                    precision.source.description(),
+                    unit.value.text(),
+                    unit.source.description()
+                ),
+                span,
+                related: None,
+            });
+        }
+    }
+
+    /// Check the literals of a time unit and precision given together, and
+    /// that the precision is no coarser than the unit
+    fn check_time_pair(&mut self, unit: Option<&TimeValue>, precision: Option<&TimeValue>) {
+        for time in unit.iter().chain(precision.iter()) {
+            if !matches!(time.magnitude, 1 | 10 | 100) {
+                self.errors.push(SemanticError {
+                    error_type: SemanticErrorType::InvalidTimeUnit,
+                    message: format!(
+                        "Time literal '{}' must be 1, 10 or 100 of a unit",
+                        time.text()
+                    ),
+                    span: time.span,
+                    related: None,
+                });
+            }
+        }
+        if let (Some(unit), Some(precision)) = (unit, precision) {
+            if precision.femtoseconds() > unit.femtoseconds() {
+                self.errors.push(SemanticError {
+                    error_type: SemanticErrorType::InvalidTimeUnit,
+                    message: format!(
+                        "Time precision {} is coarser than the time unit {}",
+                        precision.text(),
+                        unit.text()
+                    ),
+                    span: precision.span,
+                    related: None,
+                });
+            }
+        }
+    }
+
+    /// Check that a drive strength gives one strength for driving 0 and one
+    /// for driving 1, and that they are not both high impedance
+    fn check_drive_strength(&mut self, strength: &DriveStrength) {
+        let written = format!("({}, {})", strength.strength0, strength.strength1);
+        let message = if strength.strength0.ends_with('0') == strength.strength1.ends_with('0') {
+            format!(
+                "Drive strength {} needs one strength for 0 and one for 1",
+                written
+            )
+        } else if strength.strength0.starts_with("highz") && strength.strength1.starts_with("highz")
+        {
+            format!(
+                "Drive strength {} cannot make both values high impedance",
+                written
+            )
+        } else {
+            return;
+        };
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::InvalidDriveStrength,
+            message,
+            span: strength.span,
+            related: None,
+        });
+    }
+
+    /// Report `package::name` when `package` is declared in the source unit
+    /// without a member called `name`; other packages are not checked
+    fn check_package_member(&mut self, package: &str, name: &str, span: Span) {
+        let Some(scope) = self.packages.get(package) else {
+            return;
+        };
+        if scope.names.contains_key(name) {
+            return;
+        }
+        let mut message = format!("Package '{}' has no member '{}'", package, name);
+        if let Some(suggestion) = closest_name(name, scope.names.keys().map(String::as_str)) {
+            message.push_str(&format!("; did you mean '{}'?", suggestion));
+        }
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::UndeclaredIdentifier,
+            message,
+            span,
+            related: None,
+        });
+    }
+
+    /// Find the `default clocking` and `default disable iff` of a module, reporting repeats
+    fn collect_module_defaults(
+        &mut self,
+        items: &[crate::ModuleItemRef],
+        arena: &ModuleItemArena,
+    ) -> ModuleDefaults {
+        let mut defaults = ModuleDefaults::default();
+        for item_ref in items {
+            match arena.get(*item_ref) {
+                ModuleItem::DefaultClocking {
+                    name, name_span, ..
+                }
+                | ModuleItem::ClockingBlock {
+                    default: true,
+                    name,
+                    name_span,
+                    ..
+                } => {
+                    if defaults.clocking.is_some() {
+                        self.errors.push(SemanticError {
+                            error_type: SemanticErrorType::DuplicateDeclaration,
+                            message: format!(
+                                "Module already has a default clocking; '{}' is a second one",
+                                name
+                            ),
+                            span: *name_span,
+                            related: None,
+                        });
+                    } else {
+                        defaults.clocking = Some(name.clone());
+                    }
+                }
+                ModuleItem::DefaultDisableIff { condition, span } => {
+                    if defaults.disable_iff.is_some() {
+                        self.errors.push(SemanticError {
+                            error_type: SemanticErrorType::DuplicateDeclaration,
+                            message: "Module already has a default disable iff".to_string(),
+                            span: *span,
+                            related: None,
+                        });
+                    } else {
+                        defaults.disable_iff = Some(*condition);
+                    }
+                }
+                _ => {}
+            }
+        }
+        defaults
+    }
+
+    /// Find the input ports, parameters and enum members of a module, from both the
+    /// header and the body
+    fn collect_read_only(
+        ports: &[crate::Port],
+        items: &[crate::ModuleItemRef],
+        arena: &ModuleItemArena,
+    ) -> HashMap<String, ReadOnlyDeclaration> {
+        let mut read_only = HashMap::new();
+        for port in ports {
+            if port.direction == Some(PortDirection::Input) {
+                read_only.insert(
+                    port.name.clone(),
+                    ReadOnlyDeclaration {
+                        kind: ReadOnlyKind::Input,
+                        span: port.name_span,
+                    },
+                );
+            }
+        }
+        for item_ref in items {
+            for member in arena.get(*item_ref).enum_members() {
+                read_only.insert(
+                    member.name.clone(),
+                    ReadOnlyDeclaration {
+                        kind: ReadOnlyKind::EnumMember,
+                        span: member.name_span,
+                    },
+                );
+            }
+            let (name, kind, span) = match arena.get(*item_ref) {
+                ModuleItem::PortDeclaration {
+                    direction: PortDirection::Input,
+                    name,
+                    name_span,
+                    ..
+                } => (name, ReadOnlyKind::Input, *name_span),
+                ModuleItem::ParameterDeclaration {
+                    name, name_span, ..
+                } => (name, ReadOnlyKind::Parameter, *name_span),
+                _ => continue,
+            };
+            read_only.insert(name.clone(), ReadOnlyDeclaration { kind, span });
+        }
+        read_only
+    }
+
+    /// Check that the left-hand side of an assignment is something that can be written;
+    /// errors point at `assignment_span` when the target has no span of its own
+    fn check_assignment_target(
+        &mut self,
+        target: ExprRef,
+        assignment_span: Span,
+        arena: &ExprArena,
+    ) {
+        let (error_type, message, span, related) = match arena.get(target) {
+            Expression::Number(value, span) | Expression::StringLiteral(value, span) => (
+                SemanticErrorType::AssignmentToLiteral,
+                format!("Cannot assign to literal {}", value),
+                *span,
+                None,
+            ),
+            Expression::FunctionCall { span, .. }
+            | Expression::SystemFunctionCall { span, .. }
+            | Expression::New { span, .. } => (
+                SemanticErrorType::AssignmentToCall,
+                "Cannot assign to the result of a function call".to_string(),
+                *span,
+                None,
+            ),
+            // Writing a member still writes the underlying variable
+            Expression::MemberAccess {
+                object,
+                member,
+                member_span,
+                ..
+            } => {
+                self.check_member_access(*object, member, *member_span, arena);
+                return self.check_assignment_target(*object, assignment_span, arena);
+            }
+            Expression::This(span) => return self.check_class_handle("this", *span),
+            Expression::Super(span) => return self.check_class_handle("super", *span),
+            Expression::ScopedIdentifier {
+                scope,
+                name,
+                name_span,
+                ..
+            } => {
+                let Some(declaration) = self
+                    .packages
+                    .get(scope)
+                    .and_then(|package| package.parameters.get(name))
+                else {
+                    return self.check_package_member(scope, name, *name_span);
+                };
+                let related = RelatedInformation {
+                    message: format!("'{}' is declared as a parameter here", name),
+                    span: *declaration,
+                };
+                (
+                    SemanticErrorType::AssignmentToParameter,
+                    format!("Cannot assign to parameter '{}::{}'", scope, name),
+                    *name_span,
+                    Some(related),
+                )
+            }
+            Expression::Identifier(name, span) => {
+                self.check_import_reference(name, *span);
+                let Some(declaration) = self.read_only.get(name) else {
+                    return;
+                };
+                let (error_type, message, note) = match declaration.kind {
+                    ReadOnlyKind::Input => (
+                        SemanticErrorType::AssignmentToInput,
+                        format!("Cannot assign to input port '{}'", name),
+                        format!("'{}' is declared as an input here", name),
+                    ),
+                    ReadOnlyKind::Parameter => (
+                        SemanticErrorType::AssignmentToParameter,
+                        format!("Cannot assign to parameter '{}'", name),
+                        format!("'{}' is declared as a parameter here", name),
+                    ),
+                    ReadOnlyKind::EnumMember => (
+                        SemanticErrorType::AssignmentToParameter,
+                        format!("Cannot assign to enum member '{}'", name),
+                        format!("'{}' is declared as an enum member here", name),
+                    ),
+                };
+                let related = RelatedInformation {
+                    message: note,
+                    span: declaration.span,
+                };
+                (error_type, message, *span, Some(related))
+            }
+            _ => return,
+        };
+        self.errors.push(SemanticError {
+            error_type,
+            message,
+            span: if span == (0, 0) {
+                assignment_span
+            } else {
+                span
+            },
+            related,
+        });
+    }
+
+    /// Add the typedefs of struct and union types among `items` to `struct_types`
+    fn collect_struct_types(
+        items: &[crate::ModuleItemRef],
+        arena: &ModuleItemArena,
+        mut struct_types: HashMap<String, Vec<StructMember>>,
+    ) -> HashMap<String, Vec<StructMember>> {
+        for item_ref in items {
+            if let ModuleItem::TypedefDeclaration {
+                data_type, name, ..
+            } = arena.get(*item_ref)
+            {
+                if let Some(members) = Self::resolve_struct_members(data_type, &struct_types) {
+                    struct_types.insert(name.clone(), members);
+                }
+            }
+        }
+        struct_types
+    }
+
+    /// Find the variables of a module declared with a struct or union type,
+    /// inline or named by a typedef in `struct_types`
+    fn collect_struct_variables(
+        items: &[crate::ModuleItemRef],
+        arena: &ModuleItemArena,
+        struct_types: &HashMap<String, Vec<StructMember>>,
+    ) -> HashMap<String, Vec<StructMember>> {
+        items
+            .iter()
+            .filter_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::VariableDeclaration {
+                    data_type, name, ..
+                } => Some((
+                    name.clone(),
+                    Self::resolve_struct_members(data_type, struct_types)?,
+                )),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Members of a struct or union type, inline or named by a typedef in `struct_types`
+    fn resolve_struct_members(
+        data_type: &DataType,
+        struct_types: &HashMap<String, Vec<StructMember>>,
+    ) -> Option<Vec<StructMember>> {
+        match data_type {
+            DataType::Struct { members, .. } | DataType::Union { members, .. } => {
+                Some(members.clone())
+            }
+            DataType::Named(name) => struct_types.get(name).cloned(),
+            _ => None,
+        }
+    }
+
+    /// Find the nets of a module: ports and declarations with a net type
+    fn collect_nets(
+        ports: &[crate::Port],
+        items: &[crate::ModuleItemRef],
+        arena: &ModuleItemArena,
+    ) -> HashMap<String, Span> {
+        let ports = ports
+            .iter()
+            .filter(|port| port.data_type.as_deref().is_some_and(is_net_type))
+            .map(|port| (port.name.clone(), port.name_span));
+        let declarations = items
+            .iter()
+            .filter_map(|item_ref| match arena.get(*item_ref) {
+                ModuleItem::PortDeclaration {
+                    port_type,
+                    name,
+                    name_span,
+                    ..
+                } if is_net_type(port_type) => Some((name.clone(), *name_span)),
+                ModuleItem::VariableDeclaration {
+                    data_type,
+                    name,
+                    name_span,
+                    ..
+                } if is_net_type(data_type.name()) => Some((name.clone(), *name_span)),
+                _ => None,
+            });
+        ports.chain(declarations).collect()
+    }
+
+    /// Check that the operand of `++` or `--` is a variable, which it both reads and writes
+    fn check_inc_dec_operand(&mut self, operand: ExprRef, span: Span, arena: &ExprArena) {
+        self.check_assignment_target(operand, span, arena);
+        let mut root = operand;
+        while let Expression::MemberAccess { object, .. } = arena.get(root) {
+            root = *object;
+        }
+        let Expression::Identifier(name, name_span) = arena.get(root) else {
+            return;
+        };
+        let Some(declaration) = self.nets.get(name) else {
+            return;
+        };
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::IncDecOnNet,
+            message: format!("Cannot increment or decrement net '{}'", name),
+            span: if *name_span == (0, 0) {
+                span
+            } else {
+                *name_span
+            },
+            related: Some(RelatedInformation {
+                message: format!("'{}' is declared as a net here", name),
+                span: *declaration,
+            }),
+        });
+    }
+
+    /// Check the member names of an assignment pattern assigned to a struct variable
+    fn check_struct_assignment(&mut self, target: ExprRef, expr: ExprRef, arena: &ExprArena) {
+        let Expression::Identifier(name, _) = arena.get(target) else {
+            return;
+        };
+        if let Some(members) = self.struct_variables.get(name).cloned() {
+            self.check_pattern_keys(name, &members, expr, arena);
+        }
+    }
+
+    /// Check the keys of `expr`, if it is an assignment pattern, against the members
+    /// of the struct variable `variable`
+    fn check_pattern_keys(
+        &mut self,
+        variable: &str,
+        members: &[StructMember],
+        expr: ExprRef,
+        arena: &ExprArena,
+    ) {
+        let Expression::AssignmentPattern { items, .. } = arena.get(expr) else {
+            return;
+        };
+        for item in items {
+            match item.key.as_deref() {
+                None | Some("default") => {}
+                Some(key) => self.check_member(variable, members, key, item.key_span),
+            }
+        }
+    }
+
+    /// Check `object.member` when `object` is a struct variable, or a member
+    /// of one whose type is a struct as well
+    fn check_member_access(
+        &mut self,
+        object: ExprRef,
+        member: &str,
+        member_span: Span,
+        arena: &ExprArena,
+    ) {
+        if let Some((name, members)) = self.struct_members_of(object, arena) {
+            self.check_member(&name, &members, member, member_span);
+        }
+    }
+
+    /// The name and members of a struct variable, or of a member of one
+    /// declared with a struct type, like `p.hdr`
+    fn struct_members_of(
+        &self,
+        expr: ExprRef,
+        arena: &ExprArena,
+    ) -> Option<(String, Vec<StructMember>)> {
+        match arena.get(expr) {
+            Expression::Identifier(name, _) => {
+                Some((name.clone(), self.struct_variables.get(name)?.clone()))
+            }
+            Expression::MemberAccess { object, member, .. } => {
+                let (object, members) = self.struct_members_of(*object, arena)?;
+                // An array of structs has no members of its own
+                let member_type = members
+                    .iter()
+                    .find(|m| &m.name == member && m.range.is_none())?;
+                let members =
+                    Self::resolve_struct_members(&member_type.data_type, &self.struct_types)?;
+                Some((format!("{}.{}", object, member), members))
+            }
+            _ => None,
+        }
+    }
+
+    /// Report `member` if the struct variable `variable` has no member of that name
+    fn check_member(&mut self, variable: &str, members: &[StructMember], member: &str, span: Span) {
+        if members.is_empty() || members.iter().any(|m| m.name == member) {
+            return;
+        }
+        let mut message = format!("'{}' has no member '{}'", variable, member);
+        if let Some(suggestion) = closest_name(member, members.iter().map(|m| m.name.as_str())) {
+            message.push_str(&format!("; did you mean '{}'?", suggestion));
+        }
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::UnknownMember,
+            message,
+            span,
+            related: None,
+        });
+    }
+
+    /// Record the classes declared in the source unit, whether each is an
+    /// interface class, and the scopes of their `extern` methods
+    fn collect_class_kinds(&mut self, items: &[crate::ModuleItemRef], arena: &ModuleItemArena) {
+        for item_ref in items {
+            match arena.get(*item_ref) {
+                ModuleItem::ModuleDeclaration { items, .. }
+                | ModuleItem::InterfaceDeclaration { items, .. }
+                | ModuleItem::CheckerDeclaration { items, .. }
+                | ModuleItem::PackageDeclaration { items, .. } => {
+                    self.collect_class_kinds(items, arena);
+                }
+                ModuleItem::ClassDeclaration {
+                    name,
+                    extends,
+                    items,
+                    ..
+                } => {
+                    Arc::make_mut(&mut self.class_kinds).insert(name.clone(), false);
+                    for item in items {
+                        if let crate::ClassItem::Method {
+                            is_extern: true,
+                            is_static,
+                            return_type,
+                            name: method,
+                            ..
+                        } = item
+                        {
+                            Arc::make_mut(&mut self.extern_methods).insert(
+                                scoped_method_name(name, method),
+                                MethodScope {
+                                    class: Some(name.clone()),
+                                    method: method.clone(),
+                                    has_base: extends.is_some(),
+                                    is_static: *is_static,
+                                    returns_value: crate::classes::returns_value(
+                                        method,
+                                        return_type.as_deref(),
+                                    ),
+                                    constructor_call: None,
+                                },
+                            );
+                        }
+                    }
+                }
+                ModuleItem::InterfaceClassDeclaration { name, .. } => {
+                    Arc::make_mut(&mut self.class_kinds).insert(name.clone(), true);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Record the names each package of the source unit declares
+    fn collect_packages(&mut self, items: &[crate::ModuleItemRef], arena: &ModuleItemArena) {
+        for item_ref in items {
+            let ModuleItem::PackageDeclaration { name, items, .. } = arena.get(*item_ref) else {
+                continue;
+            };
+            let mut scope = PackageScope::default();
+            let mut imports = Vec::new();
+            for item_ref in items {
+                match arena.get(*item_ref) {
+                    ModuleItem::ImportDeclaration {
+                        imports: package_imports,
+                        ..
+                    } => imports.extend(package_imports),
+                    ModuleItem::ExportDeclaration { exports, .. } => {
+                        for export in exports {
+                            self.export_names(&mut scope, export, &imports);
+                        }
+                    }
+                    ModuleItem::ParameterDeclaration {
+                        name, name_span, ..
+                    } => {
+                        scope.parameters.insert(name.clone(), *name_span);
+                    }
+                    _ => {}
+                }
+                scope
+                    .names
+                    .extend(package_declarations(std::slice::from_ref(item_ref), arena));
+            }
+            Arc::make_mut(&mut self.packages).insert(name.clone(), scope);
+        }
+    }
+
+    /// Add to a package the names it exports from the packages it imports,
+    /// of those declared in the source unit: one name, or for `p::*` and
+    /// `*::*` every name it imports from `p` or from any package
+    fn export_names(
+        &self,
+        scope: &mut PackageScope,
+        export: &PackageImport,
+        imports: &[&PackageImport],
+    ) {
+        let imported = imports.iter().filter(|import| {
+            export.package == "*"
+                || (import.package == export.package
+                    && (export.name.is_none()
+                        || import.name.is_none()
+                        || import.name == export.name))
+        });
+        for import in imported {
+            let Some(package) = self.packages.get(&import.package) else {
+                continue;
+            };
+            let wanted = |name: &String| {
+                import.name.as_ref().is_none_or(|imported| imported == name)
+                    && export.name.as_ref().is_none_or(|exported| exported == name)
+            };
+            scope.names.extend(
+                package
+                    .names
+                    .iter()
+                    .filter(|(name, _)| wanted(name))
+                    .map(|(name, span)| (name.clone(), *span)),
+            );
+            scope.parameters.extend(
+                package
+                    .parameters
+                    .iter()
+                    .filter(|(name, _)| wanted(name))
+                    .map(|(name, span)| (name.clone(), *span)),
+            );
+        }
+    }
+
+    /// Check that a name in an `implements` (or interface class `extends`) clause is an
+    /// interface class; names declared in other files are not checked
+    fn check_interface_class(&mut self, class_name: &str, interface: &str, span: (usize, usize)) {
+        if self.class_kinds.get(interface) == Some(&false) {
+            self.errors.push(SemanticError {
+                error_type: SemanticErrorType::InvalidInheritance,
+                message: format!(
+                    "'{}' is not an interface class, so '{}' cannot implement or extend it as one",
+                    interface, class_name
+                ),
+                span,
+                related: None,
+            });
+        }
+    }
+
+    /// Analyze a class item of `class`, which extends another class if `has_base`
     fn analyze_class_item(
         &mut self,
         item: &crate::ClassItem,
+        class: &str,
+        has_base: bool,
         expr_arena: &ExprArena,
         stmt_arena: &StmtArena,
     ) {
@@ -121,17 +1705,144 @@ impl SemanticAnalyzer {
             } => {
                 self.analyze_expression_ref(*expr, expr_arena);
             }
-            crate::ClassItem::Method { body, .. } => {
-                // body is now Vec<StmtRef>
-                for stmt_ref in body {
-                    let statement = stmt_arena.get(*stmt_ref);
-                    self.analyze_statement(statement, expr_arena, stmt_arena);
+            crate::ClassItem::Method {
+                name,
+                is_static,
+                return_type,
+                body,
+                ..
+            } => {
+                let scope = MethodScope {
+                    class: Some(class.to_string()),
+                    method: name.clone(),
+                    has_base,
+                    is_static: *is_static,
+                    returns_value: crate::classes::returns_value(name, return_type.as_deref()),
+                    constructor_call: None,
+                };
+                self.analyze_method_body(scope, body, expr_arena, stmt_arena);
+            }
+            // A constraint holds for each object, so `this` refers to the one randomized
+            crate::ClassItem::Constraint(block) => {
+                self.method_scope = Some(MethodScope {
+                    class: Some(class.to_string()),
+                    method: block.name.clone(),
+                    has_base,
+                    is_static: false,
+                    returns_value: false,
+                    constructor_call: None,
+                });
+                for constraint in &block.constraints {
+                    for expr in constraint.expressions() {
+                        self.analyze_expression_ref(expr, expr_arena);
+                    }
                 }
+                self.method_scope = None;
             }
             _ => {}
         }
     }
 
+    /// Analyze the statements of a method in `scope`
+    fn analyze_method_body(
+        &mut self,
+        mut scope: MethodScope,
+        body: &[StmtRef],
+        expr_arena: &ExprArena,
+        stmt_arena: &StmtArena,
+    ) {
+        if scope.method == "new" {
+            scope.constructor_call = match body.first().map(|first| stmt_arena.get(*first)) {
+                Some(Statement::ExpressionStatement { expr, .. }) => {
+                    Self::super_new_call(*expr, expr_arena)
+                }
+                _ => None,
+            };
+        }
+        self.method_scope = Some(scope);
+        for stmt_ref in body {
+            let statement = stmt_arena.get(*stmt_ref);
+            self.analyze_statement(statement, expr_arena, stmt_arena);
+        }
+        self.method_scope = None;
+    }
+
+    /// The `super.new` member access called by an expression statement, with or
+    /// without arguments
+    fn super_new_call(expr_ref: ExprRef, arena: &ExprArena) -> Option<ExprRef> {
+        let callee = match arena.get(expr_ref) {
+            Expression::FunctionCall { function, .. } => *function,
+            _ => expr_ref,
+        };
+        match arena.get(callee) {
+            Expression::MemberAccess { object, member, .. }
+                if member == "new" && matches!(arena.get(*object), Expression::Super(_)) =>
+            {
+                Some(callee)
+            }
+            _ => None,
+        }
+    }
+
+    /// Check a use of `this` or `super` against the method being analyzed
+    fn check_class_handle(&mut self, keyword: &str, span: Span) {
+        let message = match &self.method_scope {
+            None | Some(MethodScope { class: None, .. }) => {
+                format!("'{}' can only be used inside a class method", keyword)
+            }
+            Some(scope) if scope.is_static => format!(
+                "'{}' cannot be used in the static method '{}'",
+                keyword,
+                scope.qualified_name()
+            ),
+            Some(MethodScope {
+                class: Some(class),
+                has_base: false,
+                ..
+            }) if keyword == "super" => format!(
+                "'super' used in class '{}', which does not extend another class",
+                class
+            ),
+            Some(_) => return,
+        };
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::InvalidClassReference,
+            message,
+            span,
+            related: None,
+        });
+    }
+
+    /// Check a `return` statement, which gives a value if `has_value`, against
+    /// the method being analyzed
+    fn check_return(&mut self, has_value: bool, span: Span) {
+        let message = match &self.method_scope {
+            None => "'return' can only be used inside a function".to_string(),
+            Some(MethodScope {
+                class: Some(class),
+                method,
+                ..
+            }) if method == "new" && has_value => {
+                format!("The constructor of class '{}' cannot return a value", class)
+            }
+            Some(scope) if !scope.returns_value && has_value => format!(
+                "Function '{}' is void, so 'return' cannot give a value",
+                scope.qualified_name()
+            ),
+            Some(scope) if scope.returns_value && !has_value => format!(
+                "Function '{}' has a return type, so 'return' must give a value",
+                scope.qualified_name()
+            ),
+            Some(_) => return,
+        };
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::InvalidReturn,
+            message,
+            span,
+            related: None,
+        });
+    }
+
     /// Analyze a statement
     fn analyze_statement(
         &mut self,
@@ -140,7 +1851,11 @@ impl SemanticAnalyzer {
         stmt_arena: &StmtArena,
     ) {
         match statement {
-            Statement::Assignment { expr, .. } => {
+            Statement::Assignment {
+                target, expr, span, ..
+            } => {
+                self.check_assignment_target(*target, *span, expr_arena);
+                self.check_struct_assignment(*target, *expr, expr_arena);
                 self.analyze_expression_ref(*expr, expr_arena);
             }
             Statement::SystemCall { name, args, span } => {
@@ -150,36 +1865,185 @@ impl SemanticAnalyzer {
                         error_type: SemanticErrorType::UnknownSystemFunction,
                         message: format!("Unknown system task: ${}", name),
                         span: *span,
+                        related: None,
                     });
                 }
+                self.check_system_call_arguments(name, args, *span, expr_arena);
                 // Analyze arguments
                 for arg in args {
                     self.analyze_expression_ref(*arg, expr_arena);
                 }
             }
-            Statement::CaseStatement { expr, .. } => {
+            Statement::Conditional { condition, .. } => {
+                self.analyze_expression_ref(*condition, expr_arena);
+            }
+            Statement::CaseStatement { expr, items, .. } => {
                 self.analyze_expression_ref(*expr, expr_arena);
+                for value in items.iter().flat_map(|item| &item.values) {
+                    self.analyze_expression_ref(*value, expr_arena);
+                }
+            }
+            Statement::ExpressionStatement { expr, .. }
+            | Statement::ImmediateAssertion {
+                condition: expr, ..
             }
-            Statement::ExpressionStatement { expr, .. } => {
+            | Statement::EventTrigger { event: expr, .. } => {
                 self.analyze_expression_ref(*expr, expr_arena);
             }
+            Statement::Return { value, span } => {
+                if let Some(expr_ref) = value {
+                    self.analyze_expression_ref(*expr_ref, expr_arena);
+                }
+                self.check_return(value.is_some(), *span);
+            }
             Statement::AssertProperty {
-                property_expr,
-                action_block,
+                disable_iff,
+                property,
                 ..
             } => {
-                self.analyze_expression_ref(*property_expr, expr_arena);
-                if let Some(action_ref) = action_block {
-                    let action_stmt = stmt_arena.get(*action_ref);
-                    self.analyze_statement(action_stmt, expr_arena, stmt_arena);
+                if let Some(condition) = disable_iff {
+                    self.analyze_expression_ref(*condition, expr_arena);
                 }
+                self.analyze_property(property, expr_arena);
             }
             Statement::VariableDeclaration { initial_value, .. } => {
                 if let Some(expr_ref) = initial_value {
                     self.analyze_expression_ref(*expr_ref, expr_arena);
                 }
             }
+            Statement::Block {
+                label, end_label, ..
+            }
+            | Statement::Fork {
+                label, end_label, ..
+            } => {
+                self.check_end_label(label.as_ref(), end_label.as_ref());
+            }
+            Statement::Labeled {
+                label, statement, ..
+            } => {
+                if let Statement::Block {
+                    label: None,
+                    end_label,
+                    statements,
+                    ..
+                } = stmt_arena.get(*statement)
+                {
+                    // `name: begin ... end : name` - the statement label names the block
+                    self.check_end_label(Some(label), end_label.as_ref());
+                    for stmt_ref in statements {
+                        self.analyze_statement(stmt_arena.get(*stmt_ref), expr_arena, stmt_arena);
+                    }
+                    return;
+                }
+            }
+            Statement::CaseMatches { expr, items, .. } => {
+                self.analyze_expression_ref(*expr, expr_arena);
+                for item in items {
+                    if let Some(pattern) = &item.pattern {
+                        self.analyze_pattern(pattern, expr_arena);
+                    }
+                    if let Some(guard) = item.guard {
+                        self.analyze_expression_ref(guard, expr_arena);
+                    }
+                }
+            }
+            Statement::Timed { control, .. } => {
+                for expr_ref in control.expressions() {
+                    self.analyze_expression_ref(expr_ref, expr_arena);
+                }
+            }
+            Statement::ForLoop {
+                condition: Some(condition),
+                ..
+            }
+            | Statement::While { condition, .. }
+            | Statement::DoWhile { condition, .. }
+            | Statement::Repeat {
+                count: condition, ..
+            } => {
+                self.analyze_expression_ref(*condition, expr_arena);
+            }
+            // Nothing to check besides the nested statements
+            Statement::ForLoop {
+                condition: None, ..
+            }
+            | Statement::Forever { .. }
+            | Statement::Foreach { .. }
+            | Statement::WaitFork { .. }
+            | Statement::DisableFork { .. }
+            | Statement::Disable { .. } => {}
+        }
+
+        for stmt_ref in statement.children() {
+            self.analyze_statement(stmt_arena.get(stmt_ref), expr_arena, stmt_arena);
+        }
+    }
+
+    /// Analyze every expression of a sequence or property
+    fn analyze_property(&mut self, property: &PropertyExpr, expr_arena: &ExprArena) {
+        for expr_ref in property.expressions() {
+            self.analyze_expression_ref(expr_ref, expr_arena);
+        }
+    }
+
+    /// Check that a block's end label, if any, repeats the block name
+    fn check_end_label(&mut self, label: Option<&Label>, end_label: Option<&Label>) {
+        let Some(end_label) = end_label else {
+            return;
+        };
+
+        let message = match label {
+            Some(label) if label.name == end_label.name => return,
+            Some(label) => format!(
+                "End label '{}' does not match block name '{}'",
+                end_label.name, label.name
+            ),
+            None => format!("End label '{}' given for an unnamed block", end_label.name),
+        };
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::LabelMismatch,
+            message,
+            span: end_label.span,
+            related: None,
+        });
+    }
+
+    /// Report an elaboration `$error` or `$fatal` with its message
+    ///
+    /// These tasks run when their module is elaborated, unlike the same tasks
+    /// in a procedural block, which only run in simulation and are not
+    /// reported. In the body of a generate loop they only run if the loop
+    /// runs, so they are not counted as reached; in a generate branch they
+    /// are, unless the branch's guard is constant false for the module's
+    /// parameter values. `$warning` and `$info` do
+    /// not fail elaboration and are left alone.
+    fn check_elaboration_task(
+        &mut self,
+        severity: ElaborationSeverity,
+        arguments: &[ExprRef],
+        span: Span,
+        arena: &ExprArena,
+    ) {
+        let mut arguments = arguments.iter().map(|arg| arena.get(*arg)).peekable();
+        match severity {
+            ElaborationSeverity::Fatal => {
+                // The finish number, if given, comes before the message
+                arguments.next_if(|arg| matches!(arg, Expression::Number(..)));
+            }
+            ElaborationSeverity::Error => {}
+            ElaborationSeverity::Warning | ElaborationSeverity::Info => return,
         }
+        let message = match arguments.next() {
+            Some(Expression::StringLiteral(text, _)) => format!("${}: {}", severity.name(), text),
+            _ => format!("${} reached during elaboration", severity.name()),
+        };
+        self.errors.push(SemanticError {
+            error_type: SemanticErrorType::ElaborationFailure,
+            message,
+            span,
+            related: None,
+        });
     }
 
     /// Analyze an expression reference
@@ -198,8 +2062,10 @@ impl SemanticAnalyzer {
                         error_type: SemanticErrorType::UnknownSystemFunction,
                         message: format!("Unknown system function: ${}", name),
                         span: *span,
+                        related: None,
                     });
                 }
+                self.check_system_call_arguments(name, arguments, *span, arena);
                 // Analyze arguments
                 for arg in arguments {
                     self.analyze_expression_ref(*arg, arena);
@@ -212,6 +2078,9 @@ impl SemanticAnalyzer {
             Expression::Unary { operand, .. } => {
                 self.analyze_expression_ref(*operand, arena);
             }
+            Expression::IncDec { operand, span, .. } => {
+                self.check_inc_dec_operand(*operand, *span, arena);
+            }
             Expression::MacroUsage { arguments, .. } => {
                 for arg in arguments {
                     self.analyze_expression_ref(*arg, arena);
@@ -222,7 +2091,30 @@ impl SemanticAnalyzer {
                     self.analyze_expression_ref(*arg, arena);
                 }
             }
-            Expression::MemberAccess { object, .. } => {
+            Expression::This(span) => self.check_class_handle("this", *span),
+            Expression::Super(span) => self.check_class_handle("super", *span),
+            Expression::MemberAccess {
+                object,
+                member,
+                member_span,
+                ..
+            } => {
+                self.check_member_access(*object, member, *member_span, arena);
+                if let Some(scope) = &self.method_scope {
+                    if member == "new"
+                        && scope.class.is_some()
+                        && matches!(arena.get(*object), Expression::Super(_))
+                        && scope.constructor_call != Some(expr_ref)
+                    {
+                        self.errors.push(SemanticError {
+                            error_type: SemanticErrorType::InvalidClassReference,
+                            message: "'super.new' must be the first statement of a constructor"
+                                .to_string(),
+                            span: *member_span,
+                            related: None,
+                        });
+                    }
+                }
                 self.analyze_expression_ref(*object, arena);
             }
             Expression::FunctionCall {
@@ -235,6 +2127,59 @@ impl SemanticAnalyzer {
                     self.analyze_expression_ref(*arg, arena);
                 }
             }
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                ..
+            } => {
+                self.analyze_expression_ref(*condition, arena);
+                self.analyze_expression_ref(*then_expr, arena);
+                self.analyze_expression_ref(*else_expr, arena);
+            }
+            Expression::ValueRange { low, high, .. } => {
+                self.analyze_expression_ref(*low, arena);
+                self.analyze_expression_ref(*high, arena);
+            }
+            Expression::RandomizeWith {
+                call, constraints, ..
+            } => {
+                self.analyze_expression_ref(*call, arena);
+                for constraint in constraints {
+                    for expr in constraint.expressions() {
+                        self.analyze_expression_ref(expr, arena);
+                    }
+                }
+            }
+            Expression::TaggedUnion {
+                value: Some(value), ..
+            } => {
+                self.analyze_expression_ref(*value, arena);
+            }
+            Expression::AssignmentPattern { items, .. } => {
+                for item in items {
+                    self.analyze_expression_ref(item.value, arena);
+                }
+            }
+            Expression::ScopedIdentifier {
+                scope,
+                name,
+                name_span,
+                ..
+            } => self.check_package_member(scope, name, *name_span),
+            Expression::Identifier(name, span) => self.check_import_reference(name, *span),
+            _ => {}
+        }
+    }
+
+    /// Analyze the constant expressions inside a case pattern
+    fn analyze_pattern(&mut self, pattern: &Pattern, arena: &ExprArena) {
+        match pattern {
+            Pattern::Constant(expr) => self.analyze_expression_ref(*expr, arena),
+            Pattern::Tagged {
+                pattern: Some(inner),
+                ..
+            } => self.analyze_pattern(inner, arena),
             _ => {}
         }
     }
@@ -265,6 +2210,14 @@ impl SemanticAnalyzer {
             "isunknown" | "onehot" | "onehot0" | "countbits" | "countones" |
             // Random functions (18.13)
             "urandom" | "urandom_range" | "random" |
+            // File I/O functions (21.3)
+            "fopen" | "fgetc" | "ungetc" | "fgets" | "fscanf" | "sscanf" |
+            "fread" | "ftell" | "fseek" | "rewind" | "feof" | "ferror" |
+            "sformatf" |
+            // Command line input (21.6)
+            "test$plusargs" | "value$plusargs" |
+            // Root of hierarchical names (23.3.1), as in $root.top.mem
+            "root" |
             // Misc
             "time" | "stime" | "realtime"
         )
@@ -308,6 +2261,160 @@ impl SemanticAnalyzer {
             "assertnonvacuouson" | "assertvacuousoff"
         )
     }
+
+    /// Check the number of arguments of a file I/O or memory load call, and
+    /// that none is a literal of the wrong kind, like a number for a file name
+    fn check_system_call_arguments(
+        &mut self,
+        name: &str,
+        args: &[ExprRef],
+        span: Span,
+        arena: &ExprArena,
+    ) {
+        let Some((kinds, required, rest)) = io_signature(name) else {
+            return;
+        };
+        let count = args.len();
+        let expected = match rest {
+            Some(_) if count < required => Some(format!("at least {}", required)),
+            None if count < required || count > kinds.len() => Some(if required == kinds.len() {
+                required.to_string()
+            } else {
+                format!("{} to {}", required, kinds.len())
+            }),
+            _ => None,
+        };
+        if let Some(expected) = expected {
+            let plural = if matches!(expected.as_str(), "1" | "at least 1") {
+                ""
+            } else {
+                "s"
+            };
+            self.errors.push(SemanticError {
+                error_type: SemanticErrorType::InvalidSystemCallArguments,
+                message: format!(
+                    "${} takes {} argument{}, not {}",
+                    name, expected, plural, count
+                ),
+                span,
+                related: None,
+            });
+            return;
+        }
+        for (index, arg) in args.iter().enumerate() {
+            let Some(kind) = kinds.get(index).copied().or(rest) else {
+                break;
+            };
+            let expr = arena.get(*arg);
+            let problem = match (kind, expr) {
+                (ArgumentKind::FileName, Expression::Number(..)) => "is a file name, not a number",
+                (ArgumentKind::Descriptor, Expression::StringLiteral(..)) => {
+                    "is a file descriptor, not a string"
+                }
+                (
+                    ArgumentKind::Variable,
+                    Expression::Identifier(..)
+                    | Expression::MemberAccess { .. }
+                    | Expression::ScopedIdentifier { .. }
+                    | Expression::MacroUsage { .. },
+                ) => continue,
+                (ArgumentKind::Variable, _) => "must name a variable",
+                _ => continue,
+            };
+            self.errors.push(SemanticError {
+                error_type: SemanticErrorType::InvalidSystemCallArguments,
+                message: format!("Argument {} of ${} {}", index + 1, name, problem),
+                span,
+                related: None,
+            });
+        }
+        if let ("fopen", Some(Expression::StringLiteral(mode, _))) =
+            (name, args.get(1).map(|arg| arena.get(*arg)))
+        {
+            if !FILE_MODES.contains(&mode.as_str()) {
+                self.errors.push(SemanticError {
+                    error_type: SemanticErrorType::InvalidSystemCallArguments,
+                    message: format!("'{}' is not a file mode for $fopen", mode),
+                    span,
+                    related: None,
+                });
+            }
+        }
+    }
+}
+
+/// What an argument of a file I/O or memory load call has to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ArgumentKind {
+    /// Name of a file, which a number cannot be
+    FileName,
+    /// Descriptor returned by `$fopen`, which a string cannot be
+    Descriptor,
+    /// Variable the call writes or memory it reads, which has to be a name
+    Variable,
+    /// Any value, like the format and values of `$fdisplay`
+    Any,
+}
+
+/// Modes `$fopen` opens a file in (21.3.1)
+const FILE_MODES: [&str; 15] = [
+    "r", "rb", "w", "wb", "a", "ab", "r+", "r+b", "rb+", "w+", "w+b", "wb+", "a+", "a+b", "ab+",
+];
+
+/// Arguments of the file I/O and memory load tasks and functions (21.3,
+/// 21.4): the kind of each argument it can take, how many of them it needs,
+/// and the kind of any number of arguments after them
+fn io_signature(name: &str) -> Option<(&'static [ArgumentKind], usize, Option<ArgumentKind>)> {
+    use ArgumentKind::*;
+
+    Some(match name {
+        "fopen" => (&[FileName, Any], 1, None),
+        "fclose" | "fgetc" | "ftell" | "feof" | "rewind" => (&[Descriptor], 1, None),
+        "fflush" => (&[Descriptor], 0, None),
+        "fdisplay" | "fdisplayb" | "fdisplayh" | "fdisplayo" | "fwrite" | "fwriteb" | "fwriteh"
+        | "fwriteo" | "fmonitor" | "fmonitorb" | "fmonitorh" | "fmonitoro" | "fstrobe"
+        | "fstrobeb" | "fstrobeh" | "fstrobeo" => (&[Descriptor], 1, Some(Any)),
+        "swrite" => (&[Variable], 1, Some(Any)),
+        "sformat" => (&[Variable, Any], 2, Some(Any)),
+        "sformatf" => (&[Any], 1, Some(Any)),
+        "ungetc" => (&[Any, Descriptor], 2, None),
+        "fgets" => (&[Variable, Descriptor], 2, None),
+        "fscanf" => (&[Descriptor, Any], 2, Some(Variable)),
+        "sscanf" => (&[Any, Any], 2, Some(Variable)),
+        "fread" => (&[Variable, Descriptor, Any, Any], 2, None),
+        "fseek" => (&[Descriptor, Any, Any], 3, None),
+        "ferror" => (&[Descriptor, Variable], 2, None),
+        "readmemb" | "readmemh" | "writememb" | "writememh" => {
+            (&[FileName, Variable, Any, Any], 2, None)
+        }
+        _ => return None,
+    })
+}
+
+/// The candidate closest to `name` by edit distance, if it is close enough to be a
+/// likely typo
+fn closest_name<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = name.chars().count().div_ceil(3).max(1);
+    candidates
+        .map(|candidate| (edit_distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= limit)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two names
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
 }
 
 impl Default for SemanticAnalyzer {
@@ -0,0 +1,436 @@
+//! Expression simplification
+//!
+//! [`simplify`] turns an expression into a [`SimplifiedExpr`]: constant
+//! subexpressions are folded, double negations are removed and chains of an
+//! associative operator are flattened with their constants folded into one
+//! trailing operand, so `(a + 1) + 2` and `a + (1 + 2)` both become `a + 3`.
+//! Lint rules use it to spot conditions that are always true or false, and the
+//! language server uses its `Display` form to show canonical expressions.
+
+use std::fmt;
+
+use crate::number::{boolean, fold_binary, fold_unary};
+use crate::{
+    BinaryOp, ExprArena, ExprRef, Expression, IncDecOp, NumberBase, NumberLiteral, UnaryOp,
+};
+
+/// Canonical form of an expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum SimplifiedExpr {
+    /// Constant with a known value
+    Constant(NumberLiteral),
+    Identifier(String),
+    /// Anything that is not simplified further (calls, member accesses, literals
+    /// with x/z bits), as source text
+    Opaque(String),
+    Unary {
+        op: UnaryOp,
+        operand: Box<SimplifiedExpr>,
+    },
+    /// Non-associative binary operation
+    Binary {
+        op: BinaryOp,
+        left: Box<SimplifiedExpr>,
+        right: Box<SimplifiedExpr>,
+    },
+    /// Conditional operator whose condition is not constant
+    Conditional {
+        condition: Box<SimplifiedExpr>,
+        then_expr: Box<SimplifiedExpr>,
+        else_expr: Box<SimplifiedExpr>,
+    },
+    /// Flattened chain of an associative operator (`a + b + 3`); any constant
+    /// operands are folded into the last one
+    Chain {
+        op: BinaryOp,
+        operands: Vec<SimplifiedExpr>,
+    },
+}
+
+impl SimplifiedExpr {
+    /// The value if the expression folded to a constant
+    pub fn constant(&self) -> Option<NumberLiteral> {
+        match self {
+            SimplifiedExpr::Constant(literal) => Some(*literal),
+            _ => None,
+        }
+    }
+
+    /// Whether the expression is always true or always false when used as a condition
+    pub fn truth_value(&self) -> Option<bool> {
+        self.constant()?.value.map(|value| value != 0)
+    }
+
+    /// Whether the expression always yields a 1-bit truth value
+    fn is_boolean(&self) -> bool {
+        match self {
+            SimplifiedExpr::Unary { op, .. } => *op == UnaryOp::LogicalNot,
+            SimplifiedExpr::Binary { op, .. } | SimplifiedExpr::Chain { op, .. } => matches!(
+                op,
+                BinaryOp::LogicalAnd
+                    | BinaryOp::LogicalOr
+                    | BinaryOp::LogicalImpl
+                    | BinaryOp::LogicalEquiv
+                    | BinaryOp::Equal
+                    | BinaryOp::NotEqual
+                    | BinaryOp::CaseEqual
+                    | BinaryOp::CaseNotEqual
+                    | BinaryOp::WildcardEqual
+                    | BinaryOp::WildcardNotEqual
+                    | BinaryOp::GreaterThan
+                    | BinaryOp::LessThan
+                    | BinaryOp::GreaterEqual
+                    | BinaryOp::LessEqual
+            ),
+            _ => false,
+        }
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(
+            self,
+            SimplifiedExpr::Unary { .. }
+                | SimplifiedExpr::Binary { .. }
+                | SimplifiedExpr::Chain { .. }
+                | SimplifiedExpr::Conditional { .. }
+        )
+    }
+}
+
+/// Simplify an expression
+pub fn simplify(expr: ExprRef, arena: &ExprArena) -> SimplifiedExpr {
+    match arena.get(expr) {
+        Expression::Number(text, _) => match NumberLiteral::parse(text) {
+            Some(literal) if literal.value.is_some() => SimplifiedExpr::Constant(literal),
+            _ => SimplifiedExpr::Opaque(text.clone()),
+        },
+        Expression::Identifier(name, _) => SimplifiedExpr::Identifier(name.clone()),
+        Expression::Unary { op, operand, .. } => simplify_unary(op, simplify(*operand, arena)),
+        Expression::Binary {
+            op, left, right, ..
+        } => {
+            let left = simplify(*left, arena);
+            let right = simplify(*right, arena);
+            if is_associative(op) {
+                simplify_chain(op, left, right)
+            } else if let Some(folded) = left
+                .constant()
+                .zip(right.constant())
+                .and_then(|(l, r)| fold_binary(op, l, r))
+            {
+                SimplifiedExpr::Constant(folded)
+            } else {
+                SimplifiedExpr::Binary {
+                    op: op.clone(),
+                    left: Box::new(left),
+                    right: Box::new(right),
+                }
+            }
+        }
+        Expression::Conditional {
+            condition,
+            then_expr,
+            else_expr,
+            ..
+        } => {
+            let condition = simplify(*condition, arena);
+            match condition.truth_value() {
+                Some(true) => simplify(*then_expr, arena),
+                Some(false) => simplify(*else_expr, arena),
+                None => SimplifiedExpr::Conditional {
+                    condition: Box::new(condition),
+                    then_expr: Box::new(simplify(*then_expr, arena)),
+                    else_expr: Box::new(simplify(*else_expr, arena)),
+                },
+            }
+        }
+        _ => SimplifiedExpr::Opaque(source_text(expr, arena)),
+    }
+}
+
+fn simplify_unary(op: &UnaryOp, operand: SimplifiedExpr) -> SimplifiedExpr {
+    if let Some(folded) = operand.constant().and_then(|value| fold_unary(op, value)) {
+        return SimplifiedExpr::Constant(folded);
+    }
+
+    match (op, operand) {
+        (UnaryOp::Plus, operand) => operand,
+        // `~~a` and `-(-a)` are `a`; `!!a` is only `a` when `a` is already a truth value
+        (
+            UnaryOp::Not | UnaryOp::Minus | UnaryOp::LogicalNot,
+            SimplifiedExpr::Unary {
+                op: inner_op,
+                operand: inner,
+            },
+        ) if inner_op == *op && (*op != UnaryOp::LogicalNot || inner.is_boolean()) => *inner,
+        (op, operand) => SimplifiedExpr::Unary {
+            op: op.clone(),
+            operand: Box::new(operand),
+        },
+    }
+}
+
+fn is_associative(op: &BinaryOp) -> bool {
+    matches!(
+        op,
+        BinaryOp::Add
+            | BinaryOp::Mul
+            | BinaryOp::And
+            | BinaryOp::Or
+            | BinaryOp::Xor
+            | BinaryOp::LogicalAnd
+            | BinaryOp::LogicalOr
+    )
+}
+
+fn simplify_chain(op: &BinaryOp, left: SimplifiedExpr, right: SimplifiedExpr) -> SimplifiedExpr {
+    let mut operands = Vec::new();
+    let mut constants = Vec::new();
+    for side in [left, right] {
+        let flattened = match side {
+            SimplifiedExpr::Chain {
+                op: inner_op,
+                operands,
+            } if inner_op == *op => operands,
+            other => vec![other],
+        };
+        for operand in flattened {
+            match operand.constant() {
+                Some(constant) => constants.push(constant),
+                None => operands.push(operand),
+            }
+        }
+    }
+
+    // A false operand decides `&&` and a true one decides `||`, whatever the rest is
+    let deciding = match op {
+        BinaryOp::LogicalAnd => Some(false),
+        BinaryOp::LogicalOr => Some(true),
+        _ => None,
+    };
+    if let Some(decided) = deciding {
+        if constants
+            .iter()
+            .any(|constant| constant.value.map(|value| value != 0) == Some(decided))
+        {
+            return SimplifiedExpr::Constant(boolean(decided));
+        }
+    }
+
+    let mut constants = constants.into_iter();
+    if let Some(first) = constants.next() {
+        let mut folded = vec![first];
+        for constant in constants {
+            let last = folded.last_mut().expect("folded is never empty");
+            match fold_binary(op, *last, constant) {
+                Some(value) => *last = value,
+                None => folded.push(constant),
+            }
+        }
+        operands.extend(folded.into_iter().map(SimplifiedExpr::Constant));
+    }
+
+    if operands.len() == 1 {
+        operands.pop().expect("one operand")
+    } else {
+        SimplifiedExpr::Chain {
+            op: op.clone(),
+            operands,
+        }
+    }
+}
+
+/// Render an expression that is not simplified, simplifying its subexpressions
+fn source_text(expr: ExprRef, arena: &ExprArena) -> String {
+    let list = |arguments: &[ExprRef]| {
+        arguments
+            .iter()
+            .map(|argument| simplify(*argument, arena).to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    match arena.get(expr) {
+        Expression::Identifier(text, _) | Expression::Number(text, _) => text.clone(),
+        Expression::StringLiteral(text, _) => format!("\"{}\"", text),
+        Expression::This(_) => "this".to_string(),
+        Expression::Super(_) => "super".to_string(),
+        Expression::Binary { .. } | Expression::Unary { .. } | Expression::Conditional { .. } => {
+            simplify(expr, arena).to_string()
+        }
+        Expression::MacroUsage {
+            name, arguments, ..
+        } => {
+            if arguments.is_empty() {
+                format!("`{}", name)
+            } else {
+                format!("`{}({})", name, list(arguments))
+            }
+        }
+        Expression::SystemFunctionCall {
+            name, arguments, ..
+        } => format!("${}({})", name, list(arguments)),
+        Expression::New { arguments, .. } => format!("new({})", list(arguments)),
+        Expression::MemberAccess { object, member, .. } => {
+            format!("{}.{}", source_text(*object, arena), member)
+        }
+        // Not folded, since it changes its operand
+        Expression::IncDec {
+            op,
+            prefix,
+            operand,
+            ..
+        } => {
+            let operand = source_text(*operand, arena);
+            if *prefix {
+                format!("{}{}", inc_dec_symbol(op), operand)
+            } else {
+                format!("{}{}", operand, inc_dec_symbol(op))
+            }
+        }
+        Expression::FunctionCall {
+            function,
+            arguments,
+            ..
+        } => format!("{}({})", source_text(*function, arena), list(arguments)),
+        Expression::TaggedUnion { member, value, .. } => match value {
+            Some(value) => format!("tagged {} {}", member, simplify(*value, arena)),
+            None => format!("tagged {}", member),
+        },
+        Expression::ScopedIdentifier { scope, name, .. } => format!("{}::{}", scope, name),
+        Expression::AssignmentPattern { items, .. } => {
+            let items: Vec<String> = items
+                .iter()
+                .map(|item| match &item.key {
+                    Some(key) => format!("{}: {}", key, simplify(item.value, arena)),
+                    None => simplify(item.value, arena).to_string(),
+                })
+                .collect();
+            format!("'{{{}}}", items.join(", "))
+        }
+        Expression::ValueRange { low, high, .. } => {
+            format!("[{}:{}]", simplify(*low, arena), simplify(*high, arena))
+        }
+        // The constraints are not values, so only their presence is shown
+        Expression::RandomizeWith { call, .. } => {
+            format!("{} with {{...}}", source_text(*call, arena))
+        }
+    }
+}
+
+impl fmt::Display for SimplifiedExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Compound operands are always parenthesized, so precedence never matters
+        let operand = |f: &mut fmt::Formatter<'_>, expr: &SimplifiedExpr| {
+            if expr.is_compound() {
+                write!(f, "({})", expr)
+            } else {
+                write!(f, "{}", expr)
+            }
+        };
+        match self {
+            SimplifiedExpr::Constant(literal) => write!(f, "{}", constant_text(literal)),
+            SimplifiedExpr::Identifier(text) | SimplifiedExpr::Opaque(text) => {
+                write!(f, "{}", text)
+            }
+            SimplifiedExpr::Unary { op, operand: inner } => {
+                write!(f, "{}", unary_symbol(op))?;
+                operand(f, inner)
+            }
+            SimplifiedExpr::Binary { op, left, right } => {
+                operand(f, left)?;
+                write!(f, " {} ", binary_symbol(op))?;
+                operand(f, right)
+            }
+            SimplifiedExpr::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+            } => {
+                operand(f, condition)?;
+                write!(f, " ? ")?;
+                operand(f, then_expr)?;
+                write!(f, " : ")?;
+                operand(f, else_expr)
+            }
+            SimplifiedExpr::Chain { op, operands } => {
+                for (index, inner) in operands.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, " {} ", binary_symbol(op))?;
+                    }
+                    operand(f, inner)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Constant in the base it was written in; folded values are shown in decimal
+fn constant_text(literal: &NumberLiteral) -> String {
+    let value = literal.value.unwrap_or_default();
+    match (literal.width, literal.base) {
+        (None, _) => literal.to_decimal_string().unwrap_or_default(),
+        (Some(_), NumberBase::Hex) => literal.to_hex_string().unwrap_or_default(),
+        (Some(_), NumberBase::Binary) => literal.to_binary_string().unwrap_or_default(),
+        (Some(width), NumberBase::Octal) => format!("{}'o{:o}", width, value),
+        (Some(width), NumberBase::Decimal) => {
+            let sign = if literal.signed { "s" } else { "" };
+            format!("{}'{}d{}", width, sign, value)
+        }
+    }
+}
+
+pub(crate) fn unary_symbol(op: &UnaryOp) -> &'static str {
+    match op {
+        UnaryOp::Plus => "+",
+        UnaryOp::Minus => "-",
+        UnaryOp::Not => "~",
+        UnaryOp::ReductionAnd => "&",
+        UnaryOp::ReductionOr => "|",
+        UnaryOp::ReductionXor => "^",
+        UnaryOp::ReductionNand => "~&",
+        UnaryOp::ReductionNor => "~|",
+        UnaryOp::ReductionXnor => "~^",
+        UnaryOp::LogicalNot => "!",
+    }
+}
+
+pub(crate) fn inc_dec_symbol(op: &IncDecOp) -> &'static str {
+    match op {
+        IncDecOp::Increment => "++",
+        IncDecOp::Decrement => "--",
+    }
+}
+
+pub(crate) fn binary_symbol(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Modulo => "%",
+        BinaryOp::And => "&",
+        BinaryOp::Or => "|",
+        BinaryOp::Xor => "^",
+        BinaryOp::BitwiseXnor => "~^",
+        BinaryOp::LogicalShiftLeft => "<<",
+        BinaryOp::LogicalShiftRight => ">>",
+        BinaryOp::ArithmeticShiftLeft => "<<<",
+        BinaryOp::ArithmeticShiftRight => ">>>",
+        BinaryOp::LogicalEquiv => "<->",
+        BinaryOp::LogicalImpl => "->",
+        BinaryOp::Equal => "==",
+        BinaryOp::NotEqual => "!=",
+        BinaryOp::CaseEqual => "===",
+        BinaryOp::CaseNotEqual => "!==",
+        BinaryOp::WildcardEqual => "==?",
+        BinaryOp::WildcardNotEqual => "!=?",
+        BinaryOp::LogicalAnd => "&&",
+        BinaryOp::LogicalOr => "||",
+        BinaryOp::GreaterThan => ">",
+        BinaryOp::LessThan => "<",
+        BinaryOp::GreaterEqual => ">=",
+        BinaryOp::LessEqual => "<=",
+        BinaryOp::Power => "**",
+    }
+}
@@ -0,0 +1,196 @@
+//! Timing statistics
+//!
+//! With `--stats` the command line tool reports how long each compilation
+//! phase took, overall and per file, along with the slowest files and design
+//! units, so that pathological sources in a large project are easy to find.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+#[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+use std::time::Instant;
+
+use crate::ModuleItem;
+
+/// Time spent parsing one file, as recorded by [`SystemVerilogParser`](crate::SystemVerilogParser)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseTimings {
+    /// Reading the file and its includes and merging the included units
+    pub preprocess: Duration,
+    /// Running the grammar over the source text
+    pub parse: Duration,
+    /// Flattening the parse tree into the AST arenas
+    pub flatten: Duration,
+    /// Flattening time of each design unit, by name
+    pub units: Vec<(String, Duration)>,
+}
+
+/// Measures the time since it was started, on targets with a clock;
+/// wasm32-unknown-unknown has none, so there every measurement is zero
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Stopwatch {
+    #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+    start: Instant,
+}
+
+impl Stopwatch {
+    pub(crate) fn start() -> Self {
+        Self {
+            #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+            start: Instant::now(),
+        }
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(all(target_arch = "wasm32", target_os = "unknown")))]
+        {
+            self.start.elapsed()
+        }
+        #[cfg(all(target_arch = "wasm32", target_os = "unknown"))]
+        {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Name of a top-level item that counts as a design unit for timing purposes
+pub fn unit_name(item: &ModuleItem) -> Option<&str> {
+    match item {
+        ModuleItem::ModuleDeclaration { name, .. }
+        | ModuleItem::InterfaceDeclaration { name, .. }
+        | ModuleItem::CheckerDeclaration { name, .. }
+        | ModuleItem::PackageDeclaration { name, .. }
+        | ModuleItem::ClassDeclaration { name, .. }
+        | ModuleItem::InterfaceClassDeclaration { name, .. } => Some(name),
+        _ => None,
+    }
+}
+
+/// Time spent on each phase
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PhaseTimes {
+    pub preprocess: Duration,
+    pub parse: Duration,
+    pub flatten: Duration,
+    pub semantic: Duration,
+    pub lint: Duration,
+}
+
+impl PhaseTimes {
+    pub fn total(&self) -> Duration {
+        self.preprocess + self.parse + self.flatten + self.semantic + self.lint
+    }
+
+    fn add(&mut self, other: &PhaseTimes) {
+        self.preprocess += other.preprocess;
+        self.parse += other.parse;
+        self.flatten += other.flatten;
+        self.semantic += other.semantic;
+        self.lint += other.lint;
+    }
+}
+
+/// Timing of one design unit: flattening plus semantic analysis
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitStats {
+    pub name: String,
+    pub file: PathBuf,
+    pub time: Duration,
+}
+
+/// Timings collected over a whole run
+#[derive(Debug, Clone, Default)]
+pub struct Stats {
+    files: Vec<(PathBuf, PhaseTimes)>,
+    units: Vec<UnitStats>,
+}
+
+impl Stats {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record the phase times of a file
+    pub fn record_file(&mut self, file: &Path, times: PhaseTimes) {
+        self.files.push((file.to_path_buf(), times));
+    }
+
+    /// Add `time` to a design unit of `file`, creating the entry on first use
+    pub fn record_unit(&mut self, file: &Path, name: &str, time: Duration) {
+        match self
+            .units
+            .iter_mut()
+            .find(|unit| unit.name == name && unit.file == file)
+        {
+            Some(unit) => unit.time += time,
+            None => self.units.push(UnitStats {
+                name: name.to_string(),
+                file: file.to_path_buf(),
+                time,
+            }),
+        }
+    }
+
+    /// Phase times summed over all files
+    pub fn totals(&self) -> PhaseTimes {
+        let mut totals = PhaseTimes::default();
+        for (_, times) in &self.files {
+            totals.add(times);
+        }
+        totals
+    }
+
+    /// Human-readable report listing up to `slowest` of the slowest files and units
+    pub fn report(&self, slowest: usize) -> String {
+        let totals = self.totals();
+        let mut report = format!(
+            "Timing ({} file{}, {} total):\n",
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" },
+            format_duration(totals.total())
+        );
+        for (phase, time) in [
+            ("preprocess", totals.preprocess),
+            ("parse", totals.parse),
+            ("flatten", totals.flatten),
+            ("semantic", totals.semantic),
+            ("lint", totals.lint),
+        ] {
+            report.push_str(&format!("  {:<12} {:>12}\n", phase, format_duration(time)));
+        }
+
+        let mut files: Vec<_> = self.files.iter().collect();
+        files.sort_by(|a, b| b.1.total().cmp(&a.1.total()).then_with(|| a.0.cmp(&b.0)));
+        if !files.is_empty() {
+            report.push_str("Slowest files:\n");
+        }
+        for (file, times) in files.into_iter().take(slowest) {
+            report.push_str(&format!(
+                "  {:>12}  {} (parse {}, semantic {})\n",
+                format_duration(times.total()),
+                file.display(),
+                format_duration(times.parse),
+                format_duration(times.semantic)
+            ));
+        }
+
+        let mut units: Vec<_> = self.units.iter().collect();
+        units.sort_by(|a, b| b.time.cmp(&a.time).then_with(|| a.name.cmp(&b.name)));
+        if !units.is_empty() {
+            report.push_str("Slowest design units:\n");
+        }
+        for unit in units.into_iter().take(slowest) {
+            report.push_str(&format!(
+                "  {:>12}  {} ({})\n",
+                format_duration(unit.time),
+                unit.name,
+                unit.file.display()
+            ));
+        }
+        report
+    }
+}
+
+/// Milliseconds with microsecond precision, e.g. `12.345ms`
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3}ms", duration.as_secs_f64() * 1000.0)
+}
@@ -0,0 +1,251 @@
+//! Layout lint rules
+//!
+//! These rules look at the text of a file rather than its syntax: lines
+//! longer than a limit, tabs, whitespace at the end of lines and a missing
+//! newline at the end of the file. Each has a setting in the `[lint.style]`
+//! table of the project configuration and runs with `--lint` only when it is
+//! set there. Apart from long lines, which need a person to decide where to
+//! break them, every finding comes with the edit that fixes it.
+
+use crate::config::StyleConfig;
+use crate::lint::{LintDiagnostic, LintRule};
+use crate::TextEdit;
+
+/// Longest line allowed when `line-length` is named but not configured
+pub const DEFAULT_MAX_LINE_LENGTH: usize = 100;
+
+/// Columns between tab stops when `no-tabs` is named but not configured
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// The layout rules, with the settings of a `[lint.style]` table
+pub fn rules(config: &StyleConfig) -> Vec<Box<dyn LintRule>> {
+    vec![
+        Box::new(LineLength {
+            max: config.max_line_length.unwrap_or(DEFAULT_MAX_LINE_LENGTH),
+            configured: config.max_line_length.is_some(),
+        }),
+        Box::new(NoTabs {
+            width: config.tab_width.unwrap_or(DEFAULT_TAB_WIDTH).max(1),
+            configured: config.tab_width.is_some(),
+        }),
+        Box::new(TrailingWhitespace {
+            configured: config.trailing_whitespace == Some(true),
+        }),
+        Box::new(FinalNewline {
+            configured: config.final_newline == Some(true),
+        }),
+    ]
+}
+
+/// A line of the text, without its line terminator
+struct Line {
+    /// Character offset of the first character
+    start: usize,
+    chars: Vec<char>,
+}
+
+/// The lines of `text`; a `\r` before a `\n` belongs to the terminator
+fn lines(text: &str) -> Vec<Line> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in text.split('\n') {
+        let length = line.chars().count();
+        let chars = line.strip_suffix('\r').unwrap_or(line).chars().collect();
+        lines.push(Line { start, chars });
+        start += length + 1;
+    }
+    lines
+}
+
+/// Flags lines longer than a limit
+struct LineLength {
+    max: usize,
+    configured: bool,
+}
+
+impl LintRule for LineLength {
+    fn name(&self) -> &'static str {
+        "line-length"
+    }
+
+    fn description(&self) -> &'static str {
+        "Line longer than the configured maximum"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "Lines longer than `max_line_length` of the `[lint.style]` table, \
+         counted in characters. The finding covers the part of the line past the \
+         limit. The rule runs with `--lint` only when the limit is configured, and \
+         with a limit of 100 when it is named without one."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check_text(&self, text: &str, diagnostics: &mut Vec<LintDiagnostic>) {
+        for line in lines(text) {
+            if line.chars.len() > self.max {
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: format!(
+                        "Line is {} characters long; the maximum is {}",
+                        line.chars.len(),
+                        self.max
+                    ),
+                    span: (line.start + self.max, line.start + line.chars.len()),
+                    fix: None,
+                });
+            }
+        }
+    }
+}
+
+/// Flags tabs, fixed by padding with spaces to the next tab stop
+struct NoTabs {
+    width: usize,
+    configured: bool,
+}
+
+impl LintRule for NoTabs {
+    fn name(&self) -> &'static str {
+        "no-tabs"
+    }
+
+    fn description(&self) -> &'static str {
+        "Tab character; use spaces"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "Tabs show at different widths in different editors, so code indented \
+         with them lines up in one and not in another. `--fix` replaces each tab \
+         with spaces up to the next tab stop, every `tab_width` columns of the \
+         `[lint.style]` table. The rule runs with `--lint` only when the width is \
+         configured, and with a width of 4 when it is named without one."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check_text(&self, text: &str, diagnostics: &mut Vec<LintDiagnostic>) {
+        for line in lines(text) {
+            // Column on screen, with the earlier tabs expanded
+            let mut column = 0;
+            let mut index = 0;
+            while index < line.chars.len() {
+                if line.chars[index] != '\t' {
+                    column += 1;
+                    index += 1;
+                    continue;
+                }
+                let start = index;
+                let start_column = column;
+                while index < line.chars.len() && line.chars[index] == '\t' {
+                    column = (column / self.width + 1) * self.width;
+                    index += 1;
+                }
+                let span = (line.start + start, line.start + index);
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: "Tab character; use spaces".to_string(),
+                    span,
+                    fix: Some(TextEdit {
+                        span,
+                        new_text: " ".repeat(column - start_column),
+                    }),
+                });
+            }
+        }
+    }
+}
+
+/// Flags spaces and tabs at the end of lines
+struct TrailingWhitespace {
+    configured: bool,
+}
+
+impl LintRule for TrailingWhitespace {
+    fn name(&self) -> &'static str {
+        "trailing-whitespace"
+    }
+
+    fn description(&self) -> &'static str {
+        "Whitespace at the end of a line"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "Spaces and tabs at the end of a line are invisible, and show up as noise \
+         in diffs when an editor strips them. `--fix` removes them. The rule runs \
+         with `--lint` only when `trailing_whitespace = true` is set in the \
+         `[lint.style]` table."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check_text(&self, text: &str, diagnostics: &mut Vec<LintDiagnostic>) {
+        for line in lines(text) {
+            let content = line
+                .chars
+                .iter()
+                .rposition(|c| *c != ' ' && *c != '\t')
+                .map_or(0, |last| last + 1);
+            if content < line.chars.len() {
+                let span = (line.start + content, line.start + line.chars.len());
+                diagnostics.push(LintDiagnostic {
+                    rule: self.name(),
+                    message: "Trailing whitespace".to_string(),
+                    span,
+                    fix: Some(TextEdit {
+                        span,
+                        new_text: String::new(),
+                    }),
+                });
+            }
+        }
+    }
+}
+
+/// Flags a file whose last line has no line terminator
+struct FinalNewline {
+    configured: bool,
+}
+
+impl LintRule for FinalNewline {
+    fn name(&self) -> &'static str {
+        "final-newline"
+    }
+
+    fn description(&self) -> &'static str {
+        "File not ending in a newline"
+    }
+
+    fn explanation(&self) -> &'static str {
+        "A file whose last line has no newline is not a text file by the POSIX \
+         definition, and tools that concatenate files join its last line to the \
+         next file's first. `--fix` adds the newline. The rule runs with `--lint` \
+         only when `final_newline = true` is set in the `[lint.style]` table."
+    }
+
+    fn enabled_by_default(&self) -> bool {
+        self.configured
+    }
+
+    fn check_text(&self, text: &str, diagnostics: &mut Vec<LintDiagnostic>) {
+        if text.is_empty() || text.ends_with('\n') {
+            return;
+        }
+        let end = text.chars().count();
+        diagnostics.push(LintDiagnostic {
+            rule: self.name(),
+            message: "No newline at end of file".to_string(),
+            span: (end, end),
+            fix: Some(TextEdit {
+                span: (end, end),
+                new_text: "\n".to_string(),
+            }),
+        });
+    }
+}
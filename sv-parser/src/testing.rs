@@ -0,0 +1,122 @@
+//! Helpers for tests of the syntax tree
+//!
+//! With the `testing` feature, crates building on the AST can check spans
+//! and walk fixture directories the way the parser's own tests do:
+//!
+//! ```
+//! use sv_parser::{assert_span_covers, ModuleItem, SystemVerilogParser};
+//!
+//! let content = "module top;\n    logic a;\nendmodule";
+//! let unit = SystemVerilogParser::new(vec![], Default::default())
+//!     .parse_content(content)
+//!     .unwrap();
+//! if let ModuleItem::ModuleDeclaration { name_span, .. } =
+//!     unit.module_item_arena.get(unit.items[0])
+//! {
+//!     assert_span_covers!(content, *name_span, "top");
+//! }
+//! ```
+//!
+//! Spans count characters, so these slice by character rather than by byte
+//! and work the same on text that isn't ASCII.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::{ParseError, SourceUnit, Span, SystemVerilogParser};
+
+/// The text of `content` that `span` covers
+///
+/// Panics if the span is reversed or ends past the end of `content`, so a
+/// wrong span fails the test rather than comparing against a clamped slice.
+pub fn span_text(content: &str, span: Span) -> &str {
+    let (start, end) = span;
+    assert!(start <= end, "span {:?} ends before it starts", span);
+    let mut offsets = content
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(content.len()));
+    let start_byte = offsets
+        .nth(start)
+        .unwrap_or_else(|| panic!("span {:?} starts past the end of the content", span));
+    let end_byte = if end == start {
+        start_byte
+    } else {
+        offsets
+            .nth(end - start - 1)
+            .unwrap_or_else(|| panic!("span {:?} ends past the end of the content", span))
+    };
+    &content[start_byte..end_byte]
+}
+
+/// Assert that a span covers exactly the given text, as in
+/// `assert_span_covers!(content, name_span, "counter")`
+#[macro_export]
+macro_rules! assert_span_covers {
+    ($content:expr, $span:expr, $text:expr $(,)?) => {{
+        let span: $crate::Span = $span;
+        let covered = $crate::testing::span_text($content, span);
+        assert_eq!(
+            covered, $text,
+            "span {:?} covers {:?}, not {:?}",
+            span, covered, $text
+        );
+    }};
+}
+
+/// Assert that the text a span covers includes the given text, for spans
+/// whose exact extent, like a trailing `;`, doesn't matter to the test
+#[macro_export]
+macro_rules! assert_span_contains {
+    ($content:expr, $span:expr, $text:expr $(,)?) => {{
+        let span: $crate::Span = $span;
+        let covered = $crate::testing::span_text($content, span);
+        assert!(
+            covered.contains($text),
+            "span {:?} covers {:?}, which doesn't contain {:?}",
+            span,
+            covered,
+            $text
+        );
+    }};
+}
+
+/// The `.sv` and `.svh` files under `dir` and its subdirectories, sorted by path
+pub fn sv_files(dir: impl AsRef<Path>) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.as_ref().to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(&dir)
+            .unwrap_or_else(|err| panic!("Failed to read directory {}: {}", dir.display(), err));
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("sv" | "svh")
+            ) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Parse every file of [`sv_files`] with `parser`, passing each path, its
+/// text and the result to `visit`
+///
+/// The text is parsed as given, so `visit` can check the spans of the result
+/// against it with [`assert_span_covers!`](crate::assert_span_covers).
+pub fn for_each_fixture<F>(parser: &SystemVerilogParser, dir: impl AsRef<Path>, mut visit: F)
+where
+    F: FnMut(&Path, &str, Result<SourceUnit, ParseError>),
+{
+    for path in sv_files(dir) {
+        let content = fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("Failed to read fixture {}: {}", path.display(), err));
+        let result = parser.parse_content(&content);
+        visit(&path, &content, result);
+    }
+}
@@ -0,0 +1,162 @@
+//! Time units and precisions of design elements
+//!
+//! Delays in a module are counted in its time unit and rounded to its time
+//! precision (IEEE 1800-2017 3.14.2.3). A `timeunit` or `timeprecision`
+//! declaration in the element sets them; otherwise the last `` `timescale ``
+//! directive before the element in the file does, and otherwise a time unit
+//! declaration at compilation-unit scope. Unit and precision are found
+//! separately, so `timeunit 1ns;` under `` `timescale 1us/1ps `` leaves the
+//! precision at 1ps. [`time_scopes`] works this out for every top-level
+//! module, interface, checker and package.
+
+use crate::{ModuleItem, ModuleItemArena, ModuleItemRef, SourceUnit, Span, TimeValue};
+
+/// Where a time unit or precision comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeSource {
+    /// A `timeunit` or `timeprecision` declaration in the element
+    Declaration,
+    /// The `` `timescale `` directive in effect at the element
+    Timescale,
+    /// A `timeunit` or `timeprecision` declaration outside any element
+    CompilationUnit,
+}
+
+impl TimeSource {
+    /// How hover text and diagnostics name the source
+    pub fn description(self) -> &'static str {
+        match self {
+            TimeSource::Declaration => "its own declaration",
+            TimeSource::Timescale => "`timescale",
+            TimeSource::CompilationUnit => "the compilation unit",
+        }
+    }
+}
+
+/// A time unit or precision in effect and the item that sets it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EffectiveTime {
+    pub value: TimeValue,
+    pub source: TimeSource,
+    /// Span of the declaration or directive
+    pub span: Span,
+}
+
+/// A time value with the span of the declaration that sets it
+type SpannedTimeValue = (TimeValue, Span);
+
+/// Time unit and precision of a design element
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimeScope {
+    pub name: String,
+    /// Span of the whole element
+    pub span: Span,
+    /// None when nothing sets it, leaving the simulator's default
+    pub unit: Option<EffectiveTime>,
+    pub precision: Option<EffectiveTime>,
+}
+
+/// Time unit and precision of each top-level design element, in source order
+pub fn time_scopes(source_unit: &SourceUnit) -> Vec<TimeScope> {
+    let arena = &source_unit.module_item_arena;
+    let mut timescale: Option<(TimeValue, TimeValue, Span)> = None;
+    let mut compilation_unit = (None, None);
+    let mut scopes = Vec::new();
+    for item_ref in &source_unit.items {
+        match arena.get(*item_ref) {
+            ModuleItem::TimescaleDirective {
+                unit,
+                precision,
+                span,
+            } => timescale = Some((*unit, *precision, *span)),
+            ModuleItem::TimeunitsDeclaration { .. } => {
+                let (unit, precision) = declared_time_units(&[*item_ref], arena);
+                compilation_unit = (
+                    compilation_unit
+                        .0
+                        .or(effective(unit, TimeSource::CompilationUnit)),
+                    compilation_unit
+                        .1
+                        .or(effective(precision, TimeSource::CompilationUnit)),
+                );
+            }
+            ModuleItem::ModuleDeclaration {
+                name, items, span, ..
+            }
+            | ModuleItem::InterfaceDeclaration {
+                name, items, span, ..
+            }
+            | ModuleItem::CheckerDeclaration {
+                name, items, span, ..
+            }
+            | ModuleItem::PackageDeclaration {
+                name, items, span, ..
+            } => {
+                let (unit, precision) = declared_time_units(items, arena);
+                let directive_unit = timescale.map(|(unit, _, span)| (unit, span));
+                let directive_precision = timescale.map(|(_, precision, span)| (precision, span));
+                scopes.push(TimeScope {
+                    name: name.clone(),
+                    span: *span,
+                    unit: effective(unit, TimeSource::Declaration)
+                        .or(effective(directive_unit, TimeSource::Timescale))
+                        .or(compilation_unit.0),
+                    precision: effective(precision, TimeSource::Declaration)
+                        .or(effective(directive_precision, TimeSource::Timescale))
+                        .or(compilation_unit.1),
+                });
+            }
+            _ => {}
+        }
+    }
+    scopes
+}
+
+fn effective(time: Option<SpannedTimeValue>, source: TimeSource) -> Option<EffectiveTime> {
+    time.map(|(value, span)| EffectiveTime {
+        value,
+        source,
+        span,
+    })
+}
+
+/// The first time unit and precision that the `timeunit` and
+/// `timeprecision` declarations among `items` set, with the span of the
+/// declaration that sets each
+fn declared_time_units(
+    items: &[ModuleItemRef],
+    arena: &ModuleItemArena,
+) -> (Option<SpannedTimeValue>, Option<SpannedTimeValue>) {
+    let mut declared = (None, None);
+    for item_ref in items {
+        if let ModuleItem::TimeunitsDeclaration {
+            unit,
+            precision,
+            span,
+        } = arena.get(*item_ref)
+        {
+            declared.0 = declared.0.or(unit.map(|unit| (unit, *span)));
+            declared.1 = declared.1.or(precision.map(|precision| (precision, *span)));
+        }
+    }
+    declared
+}
+
+/// `femtoseconds` in the largest unit that keeps the number at least 1: `1.5ns`
+pub fn format_time(femtoseconds: f64) -> String {
+    const UNITS: [(&str, f64); 6] = [
+        ("s", 1e15),
+        ("ms", 1e12),
+        ("us", 1e9),
+        ("ns", 1e6),
+        ("ps", 1e3),
+        ("fs", 1.0),
+    ];
+    let (name, scale) = UNITS
+        .iter()
+        .find(|(_, scale)| femtoseconds.abs() >= *scale)
+        .unwrap_or(&UNITS[5]);
+    let value = format!("{:.3}", femtoseconds / scale);
+    let value = value.trim_end_matches('0').trim_end_matches('.');
+    format!("{}{}", value, name)
+}
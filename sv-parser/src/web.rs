@@ -0,0 +1,85 @@
+//! Browser-facing API
+//!
+//! A small text-in, text-out interface for web playgrounds and browser
+//! editors, which have no file system: sources are parsed on their own, with
+//! no includes. The `sv-parser-wasm` package exports the functions to
+//! JavaScript through `wasm-bindgen`, building the crate for
+//! `wasm32-unknown-unknown` without default features.
+//!
+//! Positions in the diagnostics are zero-based lines and UTF-16 columns, the
+//! way JavaScript strings and most editors count them.
+
+use serde::Serialize;
+
+use crate::position::{offset_to_line_column, LineColumn, PositionEncoding};
+use crate::{printer, SystemVerilogParser};
+
+/// A syntax or semantic error in the source
+#[derive(Debug, Serialize)]
+struct Diagnostic {
+    message: String,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+/// Parse and analyze `text`, and return its errors as a JSON array of
+/// `{"message", "start": {"line", "column"}, "end": {"line", "column"}}`
+/// objects, empty for a valid source
+pub fn parse(text: &str) -> String {
+    let position = |offset| offset_to_line_column(text, offset, PositionEncoding::Utf16);
+    let parser = SystemVerilogParser::new(Vec::new(), Default::default());
+
+    let diagnostics: Vec<Diagnostic> = match parser.parse_content(text) {
+        Ok(source_unit) => parser
+            .analyze_semantics(&source_unit)
+            .into_iter()
+            .map(|error| Diagnostic {
+                message: error.message,
+                start: position(error.span.0),
+                end: position(error.span.1),
+            })
+            .collect(),
+        Err(error) => error
+            .errors
+            .into_iter()
+            .map(|error| {
+                let (start, end) = match &error.location {
+                    Some(location) => match location.span {
+                        Some((start, end)) => (position(start), position(end)),
+                        None => {
+                            let start = LineColumn {
+                                line: location.line as u32,
+                                column: location.column as u32,
+                            };
+                            (start, start)
+                        }
+                    },
+                    None => (LineColumn::default(), LineColumn::default()),
+                };
+                Diagnostic {
+                    message: error.message,
+                    start,
+                    end,
+                }
+            })
+            .collect(),
+    };
+    serde_json::to_string(&diagnostics).expect("diagnostics are always serializable")
+}
+
+/// Reformat `text` in the layout of the pretty printer
+///
+/// Returns the message of the first syntax error for a source that does not
+/// parse.
+pub fn format(text: &str) -> Result<String, String> {
+    let parser = SystemVerilogParser::new(Vec::new(), Default::default());
+    match parser.parse_content(text) {
+        Ok(source_unit) => Ok(printer::print(&source_unit)),
+        Err(error) => Err(error
+            .errors
+            .into_iter()
+            .next()
+            .map(|error| error.message)
+            .unwrap_or_else(|| "Failed to parse".to_string())),
+    }
+}
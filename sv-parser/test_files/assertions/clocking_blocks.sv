@@ -0,0 +1,12 @@
+module top(input logic clk, input logic req, output logic grant);
+logic ack;
+default clocking cb @(posedge clk);
+    default input #1step output #2;
+    input req, ack;
+    output negedge #1 grant;
+endclocking : cb
+clocking mon @(negedge clk);
+    input ack;
+endclocking
+assert property (req |=> ack);
+endmodule
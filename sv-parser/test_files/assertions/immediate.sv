@@ -0,0 +1,20 @@
+module fifo_checks(input logic clk, input logic push, input logic pop, input logic full, input logic empty);
+    int count;
+
+    always @(posedge clk) begin
+        assert (!(push && full));
+        assert (!(pop && empty)) else $error("pop while empty");
+        no_overflow: assert (count <= 8)
+            $display("count ok");
+        else
+            $fatal(1, "count %0d", count);
+        assume (!(push && pop));
+        cover (full) $display("filled");
+    end
+
+    always_comb begin
+        assert #0 (!(full && empty));
+        assert final (count >= 0) else $error("negative count");
+        cover final (empty);
+    end
+endmodule
@@ -0,0 +1,31 @@
+module arbiter(input logic clk, input logic rst, input logic req, input logic gnt, input logic busy, input logic done);
+    property grant_follows_request;
+        @(posedge clk) disable iff (rst) req |=> gnt;
+    endproperty
+
+    property stays_busy(start, finish);
+        start |-> busy throughout (##[1:3] finish);
+    endproperty : stays_busy
+
+    property overlapping;
+        (req ##1 gnt) within (busy[*5]) and not (req intersect done);
+    endproperty
+
+    property either;
+        req |-> gnt or done;
+    endproperty
+
+    assert property (grant_follows_request) else $error("no grant");
+    assert property (@(posedge clk) stays_busy(req, done));
+    assume property (@(posedge clk) not (gnt && !req));
+    cover property (@(posedge clk) req ##1 gnt) $display("granted");
+    restrict property (@(posedge clk) !(req && done));
+
+    always @(posedge clk) begin
+        check_grant: assert property (req |-> ##[0:2] gnt)
+            $display("ok");
+        else
+            $error("late grant");
+        cover property (overlapping);
+    end
+endmodule
@@ -0,0 +1,24 @@
+module handshake(input logic clk, input logic rst, input logic req, input logic gnt, input logic done);
+    sequence request;
+        req ##1 !req;
+    endsequence
+
+    sequence grant_within(n);
+        ##[1:n] gnt;
+    endsequence : grant_within
+
+    sequence burst;
+        @(posedge clk) req ##1 gnt[*2:4] ##1 done;
+    endsequence
+
+    sequence retries;
+        (req ##1 !gnt)[*1:$] ##1 gnt;
+    endsequence
+
+    sequence eventually_done;
+        gnt[->1] ##0 done[=2];
+    endsequence
+
+    assert property (@(posedge clk) req ##2 gnt ##(1 + 1) done);
+    cover property (@(posedge clk) retries);
+endmodule
@@ -0,0 +1,13 @@
+/*
+:name: intra_assignment_delay
+:description: assignments with an intra-assignment delay
+:tags: 9.4.5
+*/
+module top(input clk, input d);
+logic q;
+int a;
+always @(posedge clk) begin
+    q <= #2 d;
+    a = #5 a + 1;
+end
+endmodule
@@ -0,0 +1,14 @@
+/*
+:name: nonblocking_assignment
+:description: <= assignment test
+:tags: 10.4.2
+*/
+module top(input clk, input d);
+logic q, q_n;
+logic ready;
+always_ff @(posedge clk) begin
+    q <= d;
+    q_n <= !d;
+    ready <= q <= d;
+end
+endmodule
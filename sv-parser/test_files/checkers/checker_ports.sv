@@ -0,0 +1,5 @@
+checker bus_check(input logic clk, logic [7:0] data, sequence start, property ok, untyped valid);
+endchecker
+
+checker empty_check;
+endchecker
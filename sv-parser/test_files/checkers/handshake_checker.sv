@@ -0,0 +1,10 @@
+checker handshake_check(logic clk, logic req, logic gnt);
+    logic pending;
+
+    assert property (@(posedge clk) $rose(req)) else $error("no request");
+    assert property (@(posedge clk) $stable(gnt)) else $info;
+endchecker
+
+module arbiter(input logic clk, input logic req, output logic gnt);
+    handshake_check u_check(clk, req, gnt);
+endmodule
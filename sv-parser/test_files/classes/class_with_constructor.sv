@@ -0,0 +1,16 @@
+class base;
+  int x;
+  function new(a);
+    this.x = a;
+  endfunction
+endclass
+
+class derived extends base;
+  function new(a);
+    super.new(a);
+    this.x = a + 1;
+  endfunction
+  function void show();
+    super.show();
+  endfunction
+endclass
@@ -0,0 +1,29 @@
+class transaction;
+  static int count = 0;
+  local static int next_id;
+  static protected int id;
+  string name;
+
+  function new(string name = "txn", input int id = 0);
+    this.name = name;
+    this.id = id;
+    count++;
+  endfunction
+
+  static function int get_count();
+    return count;
+  endfunction
+endclass
+
+class read_txn extends transaction;
+  int addr;
+
+  function new(string name, logic [7:0] addr);
+    super.new(name);
+    this.addr = addr;
+  endfunction
+
+  protected static function void reset();
+    count = 0;
+  endfunction
+endclass
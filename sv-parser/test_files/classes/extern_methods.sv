@@ -0,0 +1,19 @@
+class packet;
+  int length;
+
+  extern function new(int length);
+  extern virtual function int size();
+  extern protected function void send(int n, logic [7:0] data);
+endclass
+
+function packet::new(int length);
+  this.length = length;
+endfunction
+
+function int packet::size();
+  return length;
+endfunction
+
+function void packet::send(int n, logic [7:0] data);
+  $display("%0d %0d", n, data);
+endfunction
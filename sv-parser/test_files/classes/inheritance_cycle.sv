@@ -0,0 +1,5 @@
+class a extends b;
+endclass
+
+class b extends a;
+endclass
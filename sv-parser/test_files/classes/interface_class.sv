@@ -0,0 +1,11 @@
+interface class has_id;
+endclass
+
+interface class printable extends has_id;
+endclass
+
+class packet implements printable, has_id;
+    virtual interface bus_if vif;
+    virtual bus_if.monitor mon_vif;
+    int id;
+endclass
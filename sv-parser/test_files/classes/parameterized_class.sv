@@ -0,0 +1,22 @@
+class fifo #(type T = int, int DEPTH = 8);
+  T items[DEPTH];
+  T last;
+  int count;
+
+  function void push(T item);
+    last = item;
+    count++;
+  endfunction
+endclass
+
+class scoreboard #(parameter type KEY = bit [7:0], WIDTH);
+  fifo#(KEY, 16) pending;
+endclass
+
+typedef fifo#(bit [7:0], 16) byte_fifo;
+
+module top;
+  fifo#(bit [7:0], 16) q;
+  fifo #(logic signed [3:0]) nibbles;
+  byte_fifo bytes;
+endmodule
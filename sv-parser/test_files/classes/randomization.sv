@@ -0,0 +1,20 @@
+class packet;
+    rand bit [7:0] len;
+    randc bit [1:0] kind;
+    local rand int data;
+    bit small;
+
+    constraint len_c { len > 0; len < 64; }
+    constraint kind_c {
+        kind inside {0, [2:3]};
+        soft data dist {0 := 1, [1:15] :/ 4};
+    }
+    static constraint small_c {
+        small -> { len < 8; }
+        if (kind == 0) len < 16; else { len >= 16; }
+    }
+
+    function int fill();
+        return randomize() with { len == 4; };
+    endfunction
+endclass
@@ -0,0 +1,27 @@
+interface bus_if;
+  logic valid;
+  modport monitor(input valid);
+endinterface
+
+interface class printable;
+  pure virtual function void print();
+endclass
+
+interface class named extends printable;
+  pure virtual function string name();
+endclass
+
+virtual class shape;
+  pure virtual function int area();
+endclass
+
+class square extends shape implements named;
+  virtual bus_if.monitor mon_vif;
+  virtual interface no_such_if vif;
+  function string name();
+    return "square";
+  endfunction
+endclass
+
+class logger implements no_such_ic;
+endclass
@@ -0,0 +1,29 @@
+interface class comparable;
+    pure virtual function int compare(comparable other);
+endclass
+
+virtual class component;
+    protected int id;
+    function new(int id);
+        this.id = id;
+    endfunction
+    pure virtual task run();
+    pure virtual protected function void report;
+    virtual task reset;
+        id = 0;
+    endtask
+endclass
+
+class driver extends component implements comparable;
+    function new(int id);
+        super.new(id);
+    endfunction
+    virtual task run();
+        $display("run");
+    endtask
+    virtual function int compare(comparable other);
+        return 0;
+    endfunction
+    protected function void report();
+    endfunction
+endclass
@@ -0,0 +1,31 @@
+interface my_if;
+endinterface
+
+class base;
+  virtual my_if vif;
+  function new();
+  endfunction
+  virtual function int get(a);
+  endfunction
+  protected virtual function void show();
+  endfunction
+  function void plain();
+  endfunction
+endclass
+
+class derived extends base;
+  function new();
+    super.new();
+  endfunction
+  function int get(a, b);
+  endfunction
+  function void show();
+  endfunction
+  function void plain();
+  endfunction
+endclass
+
+class leaf extends derived;
+  function void show();
+  endfunction
+endclass
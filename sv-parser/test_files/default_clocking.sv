@@ -0,0 +1,7 @@
+module top();
+logic a, b, clk, fast_clk, rst;
+default clocking cb;
+default disable iff (rst);
+assert property (a);
+assert property (@(posedge fast_clk) disable iff (b) a);
+endmodule
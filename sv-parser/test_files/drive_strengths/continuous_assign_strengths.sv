@@ -0,0 +1,4 @@
+module netlist(input a, input b, output y, output z, output w);
+  assign (weak0, weak1) y = a;
+  assign (strong1, highz0) #2 z = a & b, w = a | b;
+endmodule
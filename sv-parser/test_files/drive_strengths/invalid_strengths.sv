@@ -0,0 +1,5 @@
+module bad(input a, output y, output z);
+  assign (weak0, strong0) y = a;
+  assign (highz1, highz0) z = a;
+  wire (pull1, supply1) w = a;
+endmodule
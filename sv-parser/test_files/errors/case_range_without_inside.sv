@@ -0,0 +1,10 @@
+module top();
+logic [7:0] opcode;
+logic kind;
+always_comb begin
+    case (opcode)
+        [8'h00:8'h0f]: kind = 0;
+        default: kind = 1;
+    endcase
+end
+endmodule
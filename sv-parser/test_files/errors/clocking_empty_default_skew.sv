@@ -0,0 +1,6 @@
+module top(input logic clk, input logic a);
+    clocking cb @(posedge clk);
+        default;
+        input a;
+    endclocking
+endmodule
@@ -0,0 +1,5 @@
+module top(input logic clk, input logic rst, input logic a);
+    sequence s;
+        @(posedge clk) disable iff (rst) a ##1 a;
+    endsequence
+endmodule
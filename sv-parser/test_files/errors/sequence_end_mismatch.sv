@@ -0,0 +1,5 @@
+module top(input logic clk, input logic a);
+    sequence s;
+        a ##1 a;
+    endproperty
+endmodule
@@ -0,0 +1 @@
+module test; assign y = sel ? a : (b == c) ? d : e; endmodule
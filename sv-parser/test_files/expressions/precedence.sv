@@ -0,0 +1,8 @@
+module precedence;
+    assign sum = b + c * d - e;
+    assign bits = g & h | i ^ j;
+    assign test = l == m && n < o || p;
+    assign power = r ** s ** t;
+    assign implied = v -> w -> x;
+    assign selected = z ? a : b -> c;
+endmodule
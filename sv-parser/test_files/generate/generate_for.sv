@@ -0,0 +1,11 @@
+module shift_chain(input logic clk, input logic d);
+    parameter N = 4;
+    genvar i;
+
+    generate
+        for (i = 0; i < N; i++) begin : stage
+            logic r;
+            dff u_dff (.clk(clk), .d(d), .q(r));
+        end
+    endgenerate
+endmodule
@@ -0,0 +1,21 @@
+module adder_select(input logic [7:0] a, input logic [7:0] b, output logic [7:0] y);
+    parameter MODE = 1;
+
+    if (MODE == 0) begin : gen_add
+        assign y = a + b;
+    end else if (MODE == 1) begin : gen_sub
+        assign y = a - b;
+    end else
+        assign y = a;
+
+    case (MODE)
+        0, 1: begin : gen_valid
+            logic ok;
+        end
+        default: $error("Unsupported MODE");
+    endcase
+
+    for (genvar j = 0; j < 2; j += 1) gen_lane: begin
+        logic [7:0] lane;
+    end
+endmodule
@@ -0,0 +1,23 @@
+interface bus_if(input logic clk);
+    logic req;
+    logic gnt;
+    logic [7:0] data;
+
+    modport master(output req, data, input gnt, clk),
+            slave(input req, data, output gnt);
+    modport monitor(input req, gnt, data, clk);
+endinterface
+
+module producer(bus_if.master bus, input logic rst);
+    assign bus.req = !rst;
+endmodule
+
+module consumer(interface bus);
+    assign bus.gnt = bus.req;
+endmodule
+
+module top(input logic clk, input logic rst);
+    bus_if bus(clk);
+    producer u_producer(.bus(bus), .rst(rst));
+    consumer u_consumer(.bus(bus));
+endmodule
@@ -0,0 +1,2 @@
+interface empty_if;
+endinterface
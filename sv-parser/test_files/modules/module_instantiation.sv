@@ -0,0 +1,15 @@
+module top(input clk, input rst);
+    logic [7:0] data;
+    logic valid;
+
+    counter #(.WIDTH(8)) u_counter (
+        .clk(clk),
+        .rst,
+        .count(data),
+        .overflow()
+    );
+
+    checker_unit u_check (clk, valid);
+
+    sink u_sink (.*);
+endmodule
@@ -0,0 +1,10 @@
+module outer(input logic clk);
+    import cfg_pkg::*;
+    logic ready;
+
+    module inner(input logic clk);
+        logic done;
+    endmodule
+
+    inner u_inner(.clk(clk));
+endmodule
@@ -0,0 +1,7 @@
+module params;
+    parameter WIDTH = 8;
+    parameter int DEPTH = 16;
+    localparam logic [3:0] MASK = 4'hF;
+    localparam MSB = WIDTH - 1;
+    logic [7:0] data = 8'hA5;
+endmodule
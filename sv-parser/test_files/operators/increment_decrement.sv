@@ -0,0 +1,9 @@
+module test;
+    int i;
+    int j;
+    initial begin
+        i++;
+        --i;
+        j = i-- + ++j;
+    end
+endmodule
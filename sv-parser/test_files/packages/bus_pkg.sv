@@ -0,0 +1,20 @@
+package bus_pkg;
+    parameter WIDTH = 8;
+    localparam DEPTH = 16;
+    logic [7:0] last_data;
+
+    class transaction;
+    endclass
+endpackage
+
+import bus_pkg::*;
+
+module fifo import bus_pkg::WIDTH, bus_pkg::DEPTH; (input logic clk, input logic [7:0] din);
+    import bus_pkg::transaction;
+    bus_pkg::transaction tr;
+    logic [7:0] mem;
+
+    always_ff @(posedge clk) begin
+        mem = din;
+    end
+endmodule
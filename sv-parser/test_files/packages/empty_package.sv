@@ -0,0 +1,2 @@
+package empty_pkg;
+endpackage
@@ -0,0 +1,10 @@
+package base_pkg;
+    parameter WIDTH = 8;
+    parameter DEPTH = 4;
+endpackage
+
+package top_pkg;
+    import base_pkg::*;
+    export base_pkg::WIDTH, base_pkg::*;
+    export *::*;
+endpackage
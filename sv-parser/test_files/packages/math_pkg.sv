@@ -0,0 +1,19 @@
+package math_pkg;
+    function automatic int clamp(int value, int limit);
+        if (value > limit)
+            return limit;
+        return value;
+    endfunction
+
+    task static report(int value);
+        $display("value = %0d", value);
+    endtask
+endpackage
+
+module saturating_counter import math_pkg::*; (input logic clk);
+    int count;
+
+    always_ff @(posedge clk) begin
+        count <= clamp(count + 1, 255);
+    end
+endmodule
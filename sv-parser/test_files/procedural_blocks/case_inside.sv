@@ -0,0 +1,14 @@
+module top();
+logic [7:0] opcode;
+logic [1:0] kind;
+always_comb begin
+    unique case (opcode) inside
+        [8'h00:8'h0f]: kind = 0;
+        8'h10, [8'h20:8'h2f]: kind = 1;
+        8'b1???????: begin
+            kind = 2;
+        end
+        default: kind = 3;
+    endcase
+end
+endmodule
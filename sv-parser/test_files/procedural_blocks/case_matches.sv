@@ -0,0 +1,19 @@
+module top();
+typedef union tagged {
+    void Invalid;
+    int Valid;
+} VInt;
+VInt v;
+VInt w;
+logic [7:0] x;
+initial begin
+    v = tagged Valid 5;
+    w = tagged Invalid;
+    case (v) matches
+        tagged Valid .n &&& (n > 0): x = n;
+        tagged Invalid: x = 0;
+        .*: x = 1;
+        default: x = 2;
+    endcase
+end
+endmodule
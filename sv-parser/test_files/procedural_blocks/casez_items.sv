@@ -0,0 +1,14 @@
+module top();
+logic [3:0] req;
+logic [1:0] grant;
+always_comb begin
+    casez (req)
+        4'b1???: grant = 3;
+        4'b01??: grant = 2;
+        4'b001?, 4'b0001: begin
+            grant = 1;
+        end
+        default grant = 0;
+    endcase
+end
+endmodule
@@ -0,0 +1,21 @@
+module top(input logic clk, rst_n, en, d, output logic q);
+logic ready, done;
+always_ff @(posedge clk or negedge rst_n) begin
+    if (!rst_n)
+        q <= 0;
+    else
+        q <= d;
+end
+always @(posedge clk iff en, edge ready) done = 1;
+always @* done = ready;
+always @( * ) begin
+    done = ready & en;
+end
+initial begin
+    @(posedge clk);
+    @ready done = 0;
+    @(negedge clk) begin
+        q <= 1;
+    end
+end
+endmodule
@@ -0,0 +1,11 @@
+module top();
+event start, done;
+initial begin
+    -> start;
+    @(done);
+    wait (done.triggered);
+end
+always @(start) begin
+    ->> done;
+end
+endmodule
@@ -0,0 +1,12 @@
+module top();
+logic clk, rst_n, en;
+logic [7:0] count;
+always_ff @(posedge clk or negedge rst_n) begin
+    if (!rst_n) begin
+        count <= 0;
+    end else if (en) begin
+        count <= count + 1;
+    end else
+        count <= count;
+end
+endmodule
@@ -0,0 +1,16 @@
+module top();
+logic a, b, clk;
+initial begin : init_blk
+    a = 0;
+    begin : inner
+        b = 1;
+    end : inner
+    check_a: assert property (a) else $error("a low");
+    set_b: b = 0;
+end : init_blk
+always_comb begin
+    named: begin
+        b = a;
+    end : named
+end
+endmodule
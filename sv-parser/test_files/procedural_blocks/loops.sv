@@ -0,0 +1,38 @@
+module top();
+logic [7:0] mem [16];
+int matrix [4][4];
+int count;
+logic clk;
+initial begin
+    for (int i = 0; i < 16; i++) begin
+        count = i;
+    end
+    for (int i = 0, j = 15; i < j; i++, j--)
+        count = j;
+    for (count = 0; count < 4; count += 1)
+        $display("%d", count);
+    for (;;)
+        count = 0;
+    while (count > 0) begin
+        count--;
+    end
+    do
+        count++;
+    while (count < 10);
+    do begin
+        count -= 2;
+    end while (count > 0);
+    repeat (4) $display("tick");
+    repeat (count + 1) begin
+        count = count - 1;
+    end
+    foreach (mem[i])
+        count += i;
+    foreach (matrix[i, j]) begin
+        count = i * j;
+    end
+    foreach (matrix[, j])
+        count += j;
+end
+initial forever clk = ~clk;
+endmodule
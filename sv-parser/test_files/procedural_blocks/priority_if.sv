@@ -0,0 +1,12 @@
+module top();
+wire [3:0] a = 3;
+reg [3:0] b = 0;
+initial begin
+    priority if (a == 0)
+        b = 1;
+    else if (a == 1)
+        b = 2;
+    else
+        b = 3;
+end
+endmodule
@@ -0,0 +1,18 @@
+module top();
+logic done;
+initial begin : watchdog
+    process p;
+    p = process::self();
+    fork : workers
+        #10 done = 1;
+        begin
+            p.await();
+            done = 0;
+        end
+    join_none : workers
+    wait fork;
+    disable fork;
+    p.kill();
+    disable watchdog;
+end
+endmodule
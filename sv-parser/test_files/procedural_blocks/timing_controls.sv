@@ -0,0 +1,13 @@
+module tb();
+logic clk, ready, a, b, x, y;
+initial forever #5 clk = ~clk;
+initial begin
+    #10 a = b;
+    @(posedge clk) x = y;
+    wait (ready) $display("ready");
+    wait (a && !b);
+    #1;
+    repeat (2) @(negedge clk);
+    $finish;
+end
+endmodule
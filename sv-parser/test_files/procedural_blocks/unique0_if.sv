@@ -0,0 +1,8 @@
+module top();
+wire [3:0] a = 3;
+reg [3:0] b = 0;
+initial begin
+    unique0 if (a == 0)
+        b = 1;
+end
+endmodule
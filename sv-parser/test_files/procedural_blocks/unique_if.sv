@@ -0,0 +1,16 @@
+module top();
+wire [3:0] a = 3;
+reg [3:0] b = 0;
+initial begin
+    unique if (a == 0)
+        b = 1;
+    else if (a == 1) begin
+        b = 2;
+    end
+    else if (a == 2)
+        if (b == 0)
+            b = 3;
+        else
+            b = 4;
+end
+endmodule
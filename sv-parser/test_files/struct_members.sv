@@ -0,0 +1,7 @@
+module top();
+struct packed {
+    logic [7:0] addr;
+    logic [7:0] data;
+    bit valid;
+} pkt = '{addr: 8'h10, data: 0, default: 0};
+endmodule
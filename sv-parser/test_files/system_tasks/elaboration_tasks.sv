@@ -0,0 +1,5 @@
+module fifo;
+  localparam DEPTH = 16;
+  $info("fifo depth %0d", DEPTH);
+  $warning;
+endmodule
@@ -0,0 +1,31 @@
+module file_io;
+  logic [7:0] mem [0:15];
+  logic [7:0] data;
+  integer fd, status, c;
+  string line;
+
+  initial begin
+    fd = $fopen("data.txt", "r");
+    if (fd == 0) $fatal(1, "cannot open data.txt");
+    while (!$feof(fd)) begin
+      status = $fscanf(fd, "%h\n", data);
+      status = $fgets(line, fd);
+    end
+    c = $fgetc(fd);
+    status = $fseek( fd, 0, 0 );
+    $fclose(fd);
+    $readmemh("init.hex", mem);
+    $readmemb("init.bin", mem, 0, 15);
+    $readmemh("top.hex", file_io.mem);
+    $readmemh("root.hex", $root.file_io.mem);
+  end
+
+  final begin
+    fd = $fopen ("dump.txt", "w");
+    $fdisplay(fd, "data = %h", data);
+    $fwrite (fd, "%0d\n", status);
+    $fflush(fd);
+    $fclose(fd);
+    $writememh("dump.hex", mem);
+  end
+endmodule
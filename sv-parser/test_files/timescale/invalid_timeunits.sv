@@ -0,0 +1,22 @@
+`timescale 1ps/1ns
+
+module bad_literal;
+  timeunit 5ns;
+endmodule
+
+module coarse;
+  timeprecision 1ns;
+  timeunit 10ps;
+endmodule
+
+`timescale 1ns/1ns
+
+module finer_unit;
+  timeunit 100ps;
+endmodule
+
+module repeated;
+  timeunit 1ns;
+  timeunit 1ns;
+  timeunit 10ns;
+endmodule
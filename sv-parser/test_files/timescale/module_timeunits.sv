@@ -0,0 +1,18 @@
+`timescale 1ns/1ps
+
+module counter(input logic clk);
+  timeunit 100ps;
+  timeprecision 10ps;
+
+  initial begin
+    #10 $display("1ns in");
+  end
+endmodule
+
+module driver;
+  timeunit 1us / 1ns;
+endmodule
+
+module follower;
+  initial #5 $display("follows the timescale");
+endmodule
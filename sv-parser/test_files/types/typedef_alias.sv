@@ -0,0 +1,2 @@
+typedef int unsigned count_t;
+typedef byte_t word_t [4];
@@ -0,0 +1,20 @@
+typedef logic [7:0] byte_t;
+
+package state_pkg;
+    typedef enum logic [1:0] {IDLE, BUSY = 2'b10, DONE} state_t;
+endpackage
+
+module fsm(input logic clk);
+    import state_pkg::*;
+    typedef struct packed {
+        byte_t data;
+        bit valid;
+    } packet_t;
+    state_pkg::state_t state;
+    enum {RED, GREEN = 3, BLUE} color = GREEN;
+    byte_t count;
+
+    always_ff @(posedge clk) begin
+        count <= count + 1;
+    end
+endmodule
@@ -0,0 +1,10 @@
+module top;
+logic a, b = 1'b1, c;
+int unsigned count [4], total = 0;
+wire p = 1'b0, q;
+initial begin
+    int i = 0, j = 1;
+    logic k;
+    i = j;
+end
+endmodule
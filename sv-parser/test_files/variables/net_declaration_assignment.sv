@@ -0,0 +1,7 @@
+module top(input a, input b);
+supply0 gnd;
+supply1 vdd;
+wire a_and_b = a & b;
+tri1 [3:0] pulled = 4'b0;
+wand #2 both = a;
+endmodule
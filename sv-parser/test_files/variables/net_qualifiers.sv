@@ -0,0 +1,6 @@
+module top(input a);
+wire (strong0, weak1) signed [3:0] driven = a;
+wire vectored [7:0] bus;
+wire logic [7:0] typed = a;
+trireg stored;
+endmodule
@@ -0,0 +1,421 @@
+//! Sequence and property declaration, concurrent and immediate assertion tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{
+    assert_span_covers, AssertionDeclarationKind, AssertionDeferral, AssertionKind, CycleBound,
+    Expression, ModuleItem, PortDirection, PropertyExpr, PropertyOp, RepetitionKind, SourceUnit,
+    Statement, SystemVerilogParser,
+};
+
+/// Ensure every `assertions` fixture parses successfully.
+#[test]
+fn test_parse_all_assertion_files() {
+    assert_directory_parses("assertions");
+}
+
+sv_ok_tests! {
+    sequence_declarations => "assertions/sequences.sv",
+    property_declarations => "assertions/properties.sv",
+    immediate_assertions => "assertions/immediate.sv",
+    clocking_blocks => "assertions/clocking_blocks.sv",
+}
+
+/// The items of the first module of `unit`
+fn module_items(unit: &SourceUnit) -> Vec<&ModuleItem> {
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    items
+        .iter()
+        .map(|item_ref| unit.module_item_arena.get(*item_ref))
+        .collect()
+}
+
+/// The property of the only assertion in `property`, parsed in a module
+fn parse_property(property: &str) -> (SourceUnit, PropertyExpr) {
+    let content = format!(
+        "module top;\n    assert property (@(posedge clk) {});\nendmodule",
+        property
+    );
+    let unit = SystemVerilogParser::new(vec![], HashMap::new())
+        .parse_content(&content)
+        .unwrap_or_else(|err| panic!("Failed to parse {}: {}", property, err));
+    let property = match module_items(&unit)[..] {
+        [ModuleItem::ConcurrentAssertion { property, .. }] => property.clone(),
+        ref items => panic!("Expected one assertion, got {:?}", items),
+    };
+    (unit, property)
+}
+
+fn identifier(unit: &SourceUnit, property: &PropertyExpr) -> String {
+    match property {
+        PropertyExpr::Expression { expr, .. } => match unit.expr_arena.get(*expr) {
+            Expression::Identifier(name, _) => name.clone(),
+            expr => panic!("Expected identifier, got {:?}", expr),
+        },
+        property => panic!("Expected expression, got {:?}", property),
+    }
+}
+
+fn binary(property: &PropertyExpr) -> (PropertyOp, &PropertyExpr, &PropertyExpr) {
+    match property {
+        PropertyExpr::Binary {
+            op, left, right, ..
+        } => (*op, left.as_ref(), right.as_ref()),
+        property => panic!("Expected binary property, got {:?}", property),
+    }
+}
+
+/// Declarations keep their kind, name, formals, clock and end label.
+#[test]
+fn test_sequence_declarations() {
+    let content =
+        std::fs::read_to_string(common::default_fixtures_root().join("assertions/sequences.sv"))
+            .unwrap();
+    let unit = assert_parse_ok("assertions/sequences.sv");
+    let declarations: Vec<_> = module_items(&unit)
+        .into_iter()
+        .filter_map(|item| match item {
+            ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                ports,
+                clocking_event,
+                end_label,
+                ..
+            } => Some((kind, name, name_span, ports, clocking_event, end_label)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(declarations.len(), 5);
+    for (kind, name, name_span, ..) in &declarations {
+        assert_eq!(**kind, AssertionDeclarationKind::Sequence);
+        assert_span_covers!(&content, **name_span, name.as_str());
+    }
+
+    let (_, name, _, ports, _, end_label) = &declarations[1];
+    assert_eq!(name.as_str(), "grant_within");
+    assert_eq!(ports.len(), 1);
+    assert_eq!(ports[0].name, "n");
+    assert_eq!(
+        end_label.as_ref().map(|label| label.name.as_str()),
+        Some("grant_within")
+    );
+
+    let (_, name, _, _, clocking_event, end_label) = &declarations[2];
+    assert_eq!(name.as_str(), "burst");
+    assert!(matches!(
+        clocking_event.map(|event| unit.expr_arena.get(event)),
+        Some(Expression::Identifier(text, _)) if text == "@(posedge clk)"
+    ));
+    assert!(end_label.is_none());
+}
+
+/// `a ##1 b ##[1:$] c` groups from the left, and a sequence may start with a delay.
+#[test]
+fn test_cycle_delays() {
+    let (unit, property) = parse_property("a ##1 b ##[1:$] c");
+    let PropertyExpr::Delay {
+        left: Some(left),
+        cycles,
+        right,
+        ..
+    } = &property
+    else {
+        panic!("Expected delay, got {:?}", property);
+    };
+    assert_eq!(identifier(&unit, right), "c");
+    assert!(matches!(cycles.high, Some(CycleBound::Unbounded)));
+    let PropertyExpr::Delay {
+        left: Some(first),
+        cycles,
+        right,
+        ..
+    } = left.as_ref()
+    else {
+        panic!("Expected delay, got {:?}", left);
+    };
+    assert_eq!(identifier(&unit, first), "a");
+    assert_eq!(identifier(&unit, right), "b");
+    assert!(cycles.high.is_none());
+
+    let (unit, property) = parse_property("##[2:4] done");
+    let PropertyExpr::Delay {
+        left: None,
+        cycles,
+        right,
+        ..
+    } = &property
+    else {
+        panic!("Expected leading delay, got {:?}", property);
+    };
+    assert_eq!(identifier(&unit, right), "done");
+    assert!(matches!(cycles.high, Some(CycleBound::Count(_))));
+}
+
+#[test]
+fn test_repetitions() {
+    for (text, expected) in [
+        ("a[*3]", RepetitionKind::Consecutive),
+        ("a[=1:2]", RepetitionKind::NonConsecutive),
+        ("a[->1]", RepetitionKind::Goto),
+    ] {
+        let (unit, property) = parse_property(text);
+        let PropertyExpr::Repetition { operand, kind, .. } = &property else {
+            panic!("{} parsed as {:?}", text, property);
+        };
+        assert_eq!(*kind, expected, "{}", text);
+        assert_eq!(identifier(&unit, operand), "a");
+    }
+}
+
+/// Operators bind as in IEEE 1800-2017 Table 16-3, with the implications
+/// grouping from the right.
+#[test]
+fn test_property_operator_precedence() {
+    let (unit, property) = parse_property("a and b or c |-> d |=> e");
+    let (op, antecedent, consequent) = binary(&property);
+    assert_eq!(op, PropertyOp::OverlappedImplication);
+    let (op, or_left, or_right) = binary(antecedent);
+    assert_eq!(op, PropertyOp::Or);
+    assert_eq!(identifier(&unit, or_right), "c");
+    assert_eq!(binary(or_left).0, PropertyOp::And);
+    let (op, left, right) = binary(consequent);
+    assert_eq!(op, PropertyOp::NonOverlappedImplication);
+    assert_eq!(identifier(&unit, left), "d");
+    assert_eq!(identifier(&unit, right), "e");
+
+    let (unit, property) = parse_property("not a intersect b and c");
+    let (op, left, right) = binary(&property);
+    assert_eq!(op, PropertyOp::And);
+    assert_eq!(identifier(&unit, right), "c");
+    let PropertyExpr::Not { operand, .. } = left else {
+        panic!("Expected not, got {:?}", left);
+    };
+    assert_eq!(binary(operand).0, PropertyOp::Intersect);
+
+    let (unit, property) = parse_property("a throughout b ##1 c within d");
+    let (op, left, right) = binary(&property);
+    assert_eq!(op, PropertyOp::Within);
+    assert_eq!(identifier(&unit, right), "d");
+    let (op, left, right) = binary(left);
+    assert_eq!(op, PropertyOp::Throughout);
+    assert_eq!(identifier(&unit, left), "a");
+    assert!(matches!(right, PropertyExpr::Delay { .. }));
+}
+
+/// Assume, cover and restrict parse like assert, with the same action blocks.
+#[test]
+fn test_assertion_kinds_and_actions() {
+    let unit = assert_parse_ok("assertions/properties.sv");
+    let assertions: Vec<_> = module_items(&unit)
+        .into_iter()
+        .filter_map(|item| match item {
+            ModuleItem::ConcurrentAssertion {
+                kind,
+                pass_action,
+                fail_action,
+                ..
+            } => Some((*kind, pass_action.is_some(), fail_action.is_some())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        assertions,
+        vec![
+            (AssertionKind::Assert, false, true),
+            (AssertionKind::Assert, false, false),
+            (AssertionKind::Assume, false, false),
+            (AssertionKind::Cover, true, false),
+            (AssertionKind::Restrict, false, false),
+        ]
+    );
+
+    let Some(ModuleItem::ProceduralBlock { statements, .. }) = module_items(&unit)
+        .into_iter()
+        .find(|item| matches!(item, ModuleItem::ProceduralBlock { .. }))
+    else {
+        panic!("Expected always block");
+    };
+    let Statement::Labeled { statement, .. } = unit.stmt_arena.get(statements[0]) else {
+        panic!("Expected labeled assertion");
+    };
+    let Statement::AssertProperty {
+        kind,
+        pass_action: Some(pass_action),
+        fail_action: Some(fail_action),
+        ..
+    } = unit.stmt_arena.get(*statement)
+    else {
+        panic!("Expected assertion with both actions");
+    };
+    assert_eq!(*kind, AssertionKind::Assert);
+    assert!(matches!(
+        unit.stmt_arena.get(*pass_action),
+        Statement::SystemCall { name, .. } if name == "display"
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(*fail_action),
+        Statement::SystemCall { name, .. } if name == "error"
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(statements[1]),
+        Statement::AssertProperty {
+            kind: AssertionKind::Cover,
+            ..
+        }
+    ));
+}
+
+/// Simple and deferred immediate assertions keep their condition and actions.
+#[test]
+fn test_immediate_assertions() {
+    let unit = assert_parse_ok("assertions/immediate.sv");
+    let assertions: Vec<_> = module_items(&unit)
+        .into_iter()
+        .filter_map(|item| match item {
+            ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+            _ => None,
+        })
+        .flatten()
+        .map(|stmt_ref| match unit.stmt_arena.get(*stmt_ref) {
+            Statement::Labeled { statement, .. } => unit.stmt_arena.get(*statement),
+            statement => statement,
+        })
+        .map(|statement| match statement {
+            Statement::ImmediateAssertion {
+                kind,
+                deferral,
+                pass_action,
+                fail_action,
+                ..
+            } => (
+                *kind,
+                *deferral,
+                pass_action.is_some(),
+                fail_action.is_some(),
+            ),
+            statement => panic!("Expected immediate assertion, got {:?}", statement),
+        })
+        .collect();
+    assert_eq!(
+        assertions,
+        vec![
+            (AssertionKind::Assert, None, false, false),
+            (AssertionKind::Assert, None, false, true),
+            (AssertionKind::Assert, None, true, true),
+            (AssertionKind::Assume, None, false, false),
+            (AssertionKind::Cover, None, true, false),
+            (
+                AssertionKind::Assert,
+                Some(AssertionDeferral::Observed),
+                false,
+                false
+            ),
+            (
+                AssertionKind::Assert,
+                Some(AssertionDeferral::Final),
+                false,
+                true
+            ),
+            (
+                AssertionKind::Cover,
+                Some(AssertionDeferral::Final),
+                false,
+                false
+            ),
+        ]
+    );
+
+    let content = "module top;\n    initial assert (a == b) else $error(\"differ\");\nendmodule";
+    let unit = SystemVerilogParser::new(vec![], HashMap::new())
+        .parse_content(content)
+        .unwrap();
+    let Some(ModuleItem::ProceduralBlock { statements, .. }) = module_items(&unit).first() else {
+        panic!("Expected initial block");
+    };
+    let Statement::ImmediateAssertion { condition, .. } = unit.stmt_arena.get(statements[0]) else {
+        panic!("Expected immediate assertion");
+    };
+    assert!(matches!(
+        unit.expr_arena.get(*condition),
+        Expression::Binary { .. }
+    ));
+}
+
+/// Clocking blocks keep their clock, default skews, signals and end label.
+#[test]
+fn test_clocking_blocks() {
+    let content = std::fs::read_to_string(
+        common::default_fixtures_root().join("assertions/clocking_blocks.sv"),
+    )
+    .unwrap();
+    let unit = assert_parse_ok("assertions/clocking_blocks.sv");
+    let blocks: Vec<_> = module_items(&unit)
+        .into_iter()
+        .filter(|item| matches!(item, ModuleItem::ClockingBlock { .. }))
+        .collect();
+    assert_eq!(blocks.len(), 2);
+
+    let ModuleItem::ClockingBlock {
+        default,
+        name,
+        name_span,
+        clocking_event,
+        default_input_skew,
+        default_output_skew,
+        signals,
+        end_label,
+        ..
+    } = blocks[0]
+    else {
+        unreachable!();
+    };
+    assert!(*default);
+    assert_eq!(name, "cb");
+    assert_span_covers!(&content, *name_span, "cb");
+    assert!(matches!(
+        unit.expr_arena.get(*clocking_event),
+        Expression::Identifier(text, _) if text == "@(posedge clk)"
+    ));
+    assert_eq!(default_input_skew.as_deref(), Some("#1step"));
+    assert_eq!(default_output_skew.as_deref(), Some("#2"));
+    let signals: Vec<_> = signals
+        .iter()
+        .map(|signal| {
+            (
+                &signal.direction,
+                signal.skew.as_deref(),
+                signal.name.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        signals,
+        vec![
+            (&PortDirection::Input, None, "req"),
+            (&PortDirection::Input, None, "ack"),
+            (&PortDirection::Output, Some("negedge #1"), "grant"),
+        ]
+    );
+    assert_eq!(
+        end_label.as_ref().map(|label| label.name.as_str()),
+        Some("cb")
+    );
+
+    assert!(matches!(
+        blocks[1],
+        ModuleItem::ClockingBlock {
+            default: false,
+            end_label: None,
+            ..
+        }
+    ));
+}
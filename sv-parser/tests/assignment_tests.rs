@@ -6,7 +6,7 @@
 mod common;
 
 use common::{assert_directory_parses, assert_parse_ok, ast::module_items};
-use sv_parser::{Delay, Expression, ModuleItem};
+use sv_parser::{AssignmentOp, BinaryOp, Delay, Expression, ModuleItem, SourceUnit, Statement};
 
 /// Smoke test: every assignment fixture should parse successfully.
 #[test]
@@ -22,8 +22,10 @@ sv_ok_tests! {
     assign_cont_delay => "assignments/cont_assignment_delay.sv",
     assign_cont_net_delay => "assignments/cont_assignment_net_delay.sv",
     assign_div => "assignments/div_assign.sv",
+    assign_intra_delay => "assignments/intra_assignment_delay.sv",
     assign_mod => "assignments/mod_assign.sv",
     assign_mul => "assignments/mul_assign.sv",
+    assign_nonblocking => "assignments/nonblocking.sv",
     assign_or => "assignments/or_assign.sv",
     assign_shl => "assignments/shl_assign.sv",
     assign_shr => "assignments/shr_assign.sv",
@@ -84,7 +86,7 @@ fn test_net_declaration_with_delay() {
     else {
         panic!("Expected variable declaration");
     };
-    assert_eq!(data_type, "wire");
+    assert_eq!(data_type.name(), "wire");
     match delay {
         Some(Delay::Value(val)) => assert_eq!(val, "10"),
         other => panic!("Expected Delay::Value(\"10\"), got {:?}", other),
@@ -97,3 +99,85 @@ fn test_net_declaration_with_delay() {
     };
     assert!(delay.is_none(), "Assignment should inherit delay from wire");
 }
+
+/// Statements of the only procedural block of the first module
+fn block_statements(result: &SourceUnit) -> Vec<&Statement> {
+    let statements = module_items(result, 0)
+        .into_iter()
+        .find_map(|item_ref| match result.module_item_arena.get(item_ref) {
+            ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+            _ => None,
+        })
+        .expect("Expected a procedural block");
+    statements
+        .iter()
+        .map(|stmt_ref| result.stmt_arena.get(*stmt_ref))
+        .collect()
+}
+
+/// `q <= d;` is a nonblocking assignment, while a `<=` in its value is still
+/// a comparison.
+#[test]
+fn test_nonblocking_assignment() {
+    let result = assert_parse_ok("assignments/nonblocking.sv");
+    let statements = block_statements(&result);
+    assert_eq!(statements.len(), 3);
+
+    for statement in &statements {
+        assert!(matches!(
+            statement,
+            Statement::Assignment {
+                op: AssignmentOp::NonBlocking,
+                delay: None,
+                ..
+            }
+        ));
+    }
+    let Statement::Assignment { target, expr, .. } = statements[2] else {
+        panic!("Expected assignment");
+    };
+    assert!(matches!(
+        result.expr_arena.get(*target),
+        Expression::Identifier(name, _) if name == "ready"
+    ));
+    assert!(matches!(
+        result.expr_arena.get(*expr),
+        Expression::Binary {
+            op: BinaryOp::LessEqual,
+            ..
+        }
+    ));
+}
+
+/// `q <= #2 d;` and `a = #5 a + 1;` keep their delays.
+#[test]
+fn test_intra_assignment_delay() {
+    let result = assert_parse_ok("assignments/intra_assignment_delay.sv");
+    let statements = block_statements(&result);
+    assert_eq!(statements.len(), 2);
+
+    let Statement::Assignment {
+        op: AssignmentOp::NonBlocking,
+        delay: Some(Delay::Value(delay)),
+        ..
+    } = statements[0]
+    else {
+        panic!("Expected nonblocking assignment with a delay");
+    };
+    assert_eq!(delay, "2");
+
+    let Statement::Assignment {
+        op: AssignmentOp::Assign,
+        delay: Some(Delay::Value(delay)),
+        expr,
+        ..
+    } = statements[1]
+    else {
+        panic!("Expected blocking assignment with a delay");
+    };
+    assert_eq!(delay, "5");
+    assert!(matches!(
+        result.expr_arena.get(*expr),
+        Expression::Binary { .. }
+    ));
+}
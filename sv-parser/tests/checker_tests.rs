@@ -0,0 +1,99 @@
+//! Checker declaration and checker instantiation tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{ModuleItem, PortDirection};
+
+/// Ensure all checker fixtures parse without error.
+#[test]
+fn test_parse_all_checker_files() {
+    assert_directory_parses("checkers");
+}
+
+sv_ok_tests! {
+    checker_handshake => "checkers/handshake_checker.sv",
+    checker_ports => "checkers/checker_ports.sv",
+}
+
+/// A checker holds its formals and the same items as a module, and is
+/// instantiated like one.
+#[test]
+fn test_checker_structure() {
+    let result = assert_parse_ok("checkers/handshake_checker.sv");
+    assert_eq!(result.items.len(), 2);
+
+    let ModuleItem::CheckerDeclaration {
+        name,
+        name_span,
+        ports,
+        items,
+        ..
+    } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected checker declaration");
+    };
+    assert_eq!(name, "handshake_check");
+    assert_eq!(*name_span, (8, 23));
+    let names: Vec<&str> = ports.iter().map(|port| port.name.as_str()).collect();
+    assert_eq!(names, vec!["clk", "req", "gnt"]);
+    assert_eq!(items.len(), 3);
+    assert!(matches!(
+        result.module_item_arena.get(items[1]),
+        ModuleItem::ConcurrentAssertion { .. }
+    ));
+
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[1])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert!(matches!(
+        result.module_item_arena.get(items[0]),
+        ModuleItem::ModuleInstantiation { module_name, connections, .. }
+            if module_name == "handshake_check" && connections.len() == 3
+    ));
+}
+
+/// Checker formals may have a type without a direction, including the
+/// `sequence`, `property` and `untyped` formals modules don't have.
+#[test]
+fn test_checker_ports() {
+    let result = assert_parse_ok("checkers/checker_ports.sv");
+    assert_eq!(result.items.len(), 2);
+
+    let ModuleItem::CheckerDeclaration { ports, .. } =
+        result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected checker declaration");
+    };
+    let formals: Vec<(&str, Option<&PortDirection>, Option<&str>)> = ports
+        .iter()
+        .map(|port| {
+            (
+                port.name.as_str(),
+                port.direction.as_ref(),
+                port.data_type.as_deref(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        formals,
+        vec![
+            ("clk", Some(&PortDirection::Input), Some("logic")),
+            ("data", None, Some("logic")),
+            ("start", None, Some("sequence")),
+            ("ok", None, Some("property")),
+            ("valid", None, Some("untyped")),
+        ]
+    );
+    assert!(ports[1].range.is_some());
+
+    let ModuleItem::CheckerDeclaration { ports, items, .. } =
+        result.module_item_arena.get(result.items[1])
+    else {
+        panic!("Expected checker declaration");
+    };
+    assert!(ports.is_empty());
+    assert!(items.is_empty());
+}
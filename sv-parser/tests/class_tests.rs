@@ -1,6 +1,10 @@
 use std::collections::HashMap;
 use std::path::Path;
-use sv_parser::{ClassItem, ClassQualifier, ModuleItem, SystemVerilogParser};
+use sv_parser::{
+    scoped_method_name, ClassDiagnosticKind, ClassHierarchy, ClassItem, ClassQualifier,
+    ConstraintBlock, ConstraintExpr, DataType, Expression, ModuleItem, ParameterArgument,
+    RandQualifier, Statement, SystemVerilogParser,
+};
 
 #[test]
 fn test_simple_class() {
@@ -25,7 +29,7 @@ fn test_simple_class() {
                 ClassItem::Property {
                     data_type, name, ..
                 } => {
-                    assert_eq!(data_type, "int");
+                    assert_eq!(data_type.name(), "int");
                     assert_eq!(name, "x");
                 }
                 _ => panic!("Expected property"),
@@ -60,7 +64,7 @@ fn test_class_with_local_property() {
                 ..
             } => {
                 assert_eq!(qualifier, &Some(ClassQualifier::Local));
-                assert_eq!(data_type, "int");
+                assert_eq!(data_type.name(), "int");
                 assert_eq!(name, "x");
             }
             _ => panic!("Expected property"),
@@ -94,7 +98,7 @@ fn test_class_with_protected_property() {
                 ..
             } => {
                 assert_eq!(qualifier, &Some(ClassQualifier::Protected));
-                assert_eq!(data_type, "int");
+                assert_eq!(data_type.name(), "int");
                 assert_eq!(name, "x");
             }
             _ => panic!("Expected property"),
@@ -202,7 +206,7 @@ fn test_class_in_module() {
                 ModuleItem::VariableDeclaration {
                     data_type, name, ..
                 } => {
-                    assert_eq!(data_type, "test_cls");
+                    assert_eq!(data_type.name(), "test_cls");
                     assert_eq!(name, "obj");
                 }
                 _ => panic!("Expected variable declaration"),
@@ -457,3 +461,739 @@ fn test_encapsulation_inherited_prot_from_inside() {
         _ => panic!("Expected module declaration"),
     }
 }
+
+#[test]
+fn test_interface_class_and_implements() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/interface_class.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+
+    let ast = parser
+        .parse_content(&content)
+        .expect("Failed to parse interface classes");
+    assert_eq!(ast.items.len(), 3);
+
+    match ast.module_item_arena.get(ast.items[1]) {
+        ModuleItem::InterfaceClassDeclaration { name, extends, .. } => {
+            assert_eq!(name, "printable");
+            assert_eq!(extends, &vec!["has_id".to_string()]);
+        }
+        _ => panic!("Expected interface class declaration"),
+    }
+
+    match ast.module_item_arena.get(ast.items[2]) {
+        ModuleItem::ClassDeclaration {
+            name,
+            extends,
+            implements,
+            items,
+            ..
+        } => {
+            assert_eq!(name, "packet");
+            assert_eq!(extends, &None);
+            assert_eq!(
+                implements,
+                &vec!["printable".to_string(), "has_id".to_string()]
+            );
+
+            let data_types: Vec<&str> = items
+                .iter()
+                .filter_map(|item| match item {
+                    ClassItem::Property { data_type, .. } => Some(data_type.name()),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(
+                data_types,
+                vec!["virtual bus_if", "virtual bus_if.monitor", "int"]
+            );
+        }
+        _ => panic!("Expected class declaration"),
+    }
+}
+
+fn class_hierarchy(file: &str) -> (ClassHierarchy, String) {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file = Path::new(env!("CARGO_MANIFEST_DIR")).join(file);
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser.parse_content(&content).expect("Failed to parse");
+    let mut classes = ClassHierarchy::new();
+    classes.add_source_unit(&test_file, &ast);
+    (classes, content)
+}
+
+#[test]
+fn test_virtual_method_qualifier() {
+    let (classes, _) = class_hierarchy("test_files/classes/virtual_overrides.sv");
+    let base = classes.class("base").unwrap();
+    let virtual_methods: Vec<_> = base
+        .methods
+        .iter()
+        .filter(|method| method.is_virtual)
+        .map(|method| method.name.as_str())
+        .collect();
+    assert_eq!(virtual_methods, vec!["get", "show"]);
+}
+
+#[test]
+fn test_class_hierarchy_navigation() {
+    let (classes, _) = class_hierarchy("test_files/classes/virtual_overrides.sv");
+
+    assert_eq!(classes.base("derived").unwrap().name, "base");
+    assert!(classes.base("base").is_none());
+    let derived: Vec<_> = classes
+        .derived("base")
+        .iter()
+        .map(|class| class.name.as_str())
+        .collect();
+    assert_eq!(derived, vec!["derived"]);
+    let ancestors: Vec<_> = classes
+        .ancestors("leaf")
+        .iter()
+        .map(|class| class.name.as_str())
+        .collect();
+    assert_eq!(ancestors, vec!["derived", "base"]);
+}
+
+#[test]
+fn test_override_checks() {
+    let (classes, content) = class_hierarchy("test_files/classes/virtual_overrides.sv");
+    let diagnostics = classes.check();
+
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let text: String = content
+                .chars()
+                .skip(diagnostic.span.0)
+                .take(diagnostic.span.1 - diagnostic.span.0)
+                .collect();
+            (diagnostic.kind, text, diagnostic.message.as_str())
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                ClassDiagnosticKind::SignatureMismatch,
+                "get".to_string(),
+                "'derived::get' does not match the virtual method it overrides: expected 'function int get(a)', found 'function int get(a, b)'"
+            ),
+            (
+                ClassDiagnosticKind::NonVirtualOverride,
+                "plain".to_string(),
+                "'derived::plain' hides the non-virtual method 'base::plain'"
+            ),
+        ]
+    );
+    // The overridden method is the one of the nearest base class
+    let (_, related) = diagnostics[0].related.clone().unwrap();
+    assert_eq!(classes.class("base").unwrap().methods[1].name_span, related);
+}
+
+#[test]
+fn test_unresolved_names_and_unimplemented_methods() {
+    let (classes, content) = class_hierarchy("test_files/classes/unresolved_names.sv");
+    let diagnostics = classes.check();
+
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|diagnostic| {
+            let text: String = content
+                .chars()
+                .skip(diagnostic.span.0)
+                .take(diagnostic.span.1 - diagnostic.span.0)
+                .collect();
+            (diagnostic.kind, text, diagnostic.message.as_str())
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                ClassDiagnosticKind::UnknownInterfaceClass,
+                "logger".to_string(),
+                "Class 'logger' names the interface class 'no_such_ic', which is not declared"
+            ),
+            (
+                ClassDiagnosticKind::UnimplementedMethod,
+                "square".to_string(),
+                "Class 'square' does not implement the pure virtual method 'shape::area'"
+            ),
+            (
+                ClassDiagnosticKind::UnimplementedMethod,
+                "square".to_string(),
+                "Class 'square' does not implement the pure virtual method 'printable::print'"
+            ),
+            (
+                ClassDiagnosticKind::UnknownInterface,
+                "vif".to_string(),
+                "Virtual interface of 'no_such_if', which is not declared"
+            ),
+        ]
+    );
+    // The pure virtual method is inherited through the interface class 'named'
+    let (_, related) = diagnostics[2].related.clone().unwrap();
+    assert_eq!(
+        classes.class("printable").unwrap().methods[0].name_span,
+        related
+    );
+}
+
+#[test]
+fn test_inheritance_cycle() {
+    let (classes, _) = class_hierarchy("test_files/classes/inheritance_cycle.sv");
+    let diagnostics = classes.check();
+    assert_eq!(diagnostics.len(), 2);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.kind == ClassDiagnosticKind::InheritanceCycle));
+    assert_eq!(diagnostics[0].message, "Class 'a' inherits from itself");
+    assert_eq!(classes.ancestors("a").len(), 1);
+}
+
+#[test]
+fn test_constructor_with_super_new() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/class_with_constructor.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser.parse_content(&content).expect("Failed to parse");
+
+    let ModuleItem::ClassDeclaration { items, .. } = ast.module_item_arena.get(ast.items[1]) else {
+        panic!("Expected class declaration");
+    };
+    let ClassItem::Method {
+        name,
+        return_type,
+        parameters,
+        body,
+        ..
+    } = &items[0]
+    else {
+        panic!("Expected constructor");
+    };
+    assert_eq!(name, "new");
+    assert_eq!(return_type, &None);
+    assert_eq!(parameters, &vec!["a".to_string()]);
+
+    // super.new(a);
+    let Statement::ExpressionStatement { expr, .. } = ast.stmt_arena.get(body[0]) else {
+        panic!("Expected expression statement");
+    };
+    let Expression::FunctionCall {
+        function,
+        arguments,
+        ..
+    } = ast.expr_arena.get(*expr)
+    else {
+        panic!("Expected call");
+    };
+    assert_eq!(arguments.len(), 1);
+    let Expression::MemberAccess {
+        object,
+        member,
+        member_span,
+        ..
+    } = ast.expr_arena.get(*function)
+    else {
+        panic!("Expected member access");
+    };
+    assert_eq!(member, "new");
+    let text = |span: (usize, usize)| -> String {
+        content.chars().skip(span.0).take(span.1 - span.0).collect()
+    };
+    assert_eq!(text(*member_span), "new");
+    let Expression::Super(super_span) = ast.expr_arena.get(*object) else {
+        panic!("Expected super");
+    };
+    assert_eq!(text(*super_span), "super");
+
+    // this.x = a + 1;
+    let Statement::Assignment { target, .. } = ast.stmt_arena.get(body[1]) else {
+        panic!("Expected assignment");
+    };
+    let Expression::MemberAccess { object, member, .. } = ast.expr_arena.get(*target) else {
+        panic!("Expected member access");
+    };
+    assert_eq!(member, "x");
+    assert!(matches!(ast.expr_arena.get(*object), Expression::This(_)));
+}
+
+#[test]
+fn test_this_and_super_are_keywords() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let result = parser.parse_content("module m();\n  logic this;\nendmodule\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_static_members_and_typed_constructor_arguments() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/class_with_static.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser
+        .parse_content(&content)
+        .expect("Failed to parse static members");
+    let errors = parser.analyze_semantics(&ast);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+    let ModuleItem::ClassDeclaration { items, .. } = ast.module_item_arena.get(ast.items[0]) else {
+        panic!("Expected class declaration");
+    };
+    let properties: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            ClassItem::Property {
+                qualifier,
+                is_static,
+                name,
+                ..
+            } => Some((name.as_str(), qualifier.clone(), *is_static)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        properties,
+        vec![
+            ("count", None, true),
+            ("next_id", Some(ClassQualifier::Local), true),
+            ("id", Some(ClassQualifier::Protected), true),
+            ("name", None, false),
+        ]
+    );
+
+    let constructors: Vec<_> = items.iter().filter(|item| item.is_constructor()).collect();
+    assert_eq!(constructors.len(), 1);
+    let ClassItem::Method {
+        is_static,
+        parameters,
+        ..
+    } = constructors[0]
+    else {
+        panic!("Expected constructor");
+    };
+    assert!(!is_static);
+    assert_eq!(parameters, &vec!["name".to_string(), "id".to_string()]);
+
+    let ClassItem::Method {
+        name, is_static, ..
+    } = &items[5]
+    else {
+        panic!("Expected static method");
+    };
+    assert_eq!(name, "get_count");
+    assert!(is_static);
+    assert!(!items[5].is_constructor());
+
+    let ModuleItem::ClassDeclaration { items, .. } = ast.module_item_arena.get(ast.items[1]) else {
+        panic!("Expected class declaration");
+    };
+    let ClassItem::Method { parameters, .. } = &items[1] else {
+        panic!("Expected constructor");
+    };
+    assert_eq!(parameters, &vec!["name".to_string(), "addr".to_string()]);
+    let ClassItem::Method {
+        qualifier,
+        is_static,
+        ..
+    } = &items[2]
+    else {
+        panic!("Expected static method");
+    };
+    assert_eq!(qualifier, &Some(ClassQualifier::Protected));
+    assert!(is_static);
+}
+
+#[test]
+fn test_parameterized_class_and_specializations() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/parameterized_class.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser
+        .parse_content(&content)
+        .expect("Failed to parse parameterized classes");
+    let errors = parser.analyze_semantics(&ast);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+    let ModuleItem::ClassDeclaration {
+        name, parameters, ..
+    } = ast.module_item_arena.get(ast.items[0])
+    else {
+        panic!("Expected class declaration");
+    };
+    assert_eq!(name, "fifo");
+    let ports: Vec<_> = parameters
+        .iter()
+        .map(|parameter| {
+            (
+                parameter.is_type,
+                parameter.data_type.as_deref(),
+                parameter.name.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        ports,
+        vec![(true, None, "T"), (false, Some("int"), "DEPTH")]
+    );
+    assert_eq!(
+        parameters[0].default,
+        Some(ParameterArgument::Type("int".to_string()))
+    );
+    let Some(ParameterArgument::Value(depth)) = parameters[1].default else {
+        panic!("Expected a default value for DEPTH");
+    };
+    assert!(matches!(ast.expr_arena.get(depth), Expression::Number(n, _) if n == "8"));
+
+    // `parameter` may come first, and a parameter needs no default
+    let ModuleItem::ClassDeclaration {
+        parameters, items, ..
+    } = ast.module_item_arena.get(ast.items[1])
+    else {
+        panic!("Expected class declaration");
+    };
+    assert_eq!(
+        parameters[0].default,
+        Some(ParameterArgument::Type("bit [7:0]".to_string()))
+    );
+    assert_eq!(
+        (parameters[1].name.as_str(), &parameters[1].default),
+        ("WIDTH", &None)
+    );
+    let ClassItem::Property {
+        data_type: DataType::Specialized(reference),
+        name,
+        ..
+    } = &items[0]
+    else {
+        panic!("Expected property of a specialized class");
+    };
+    assert_eq!(
+        (reference.name.as_str(), name.as_str()),
+        ("fifo", "pending")
+    );
+    assert!(matches!(
+        reference.arguments.as_slice(),
+        [ParameterArgument::Value(_), ParameterArgument::Value(_)]
+    ));
+
+    let ModuleItem::TypedefDeclaration {
+        data_type: DataType::Specialized(reference),
+        ..
+    } = ast.module_item_arena.get(ast.items[2])
+    else {
+        panic!("Expected typedef of a specialized class");
+    };
+    assert_eq!(reference.name, "fifo");
+    assert_eq!(
+        reference.arguments[0],
+        ParameterArgument::Type("bit [7:0]".to_string())
+    );
+
+    let ModuleItem::ModuleDeclaration { items, .. } = ast.module_item_arena.get(ast.items[3])
+    else {
+        panic!("Expected module declaration");
+    };
+    let declarations: Vec<_> = items
+        .iter()
+        .map(|item| match ast.module_item_arena.get(*item) {
+            ModuleItem::VariableDeclaration {
+                data_type, name, ..
+            } => (
+                data_type.name(),
+                name.as_str(),
+                matches!(data_type, DataType::Specialized(_)),
+            ),
+            other => panic!("Expected variable declaration, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        declarations,
+        vec![
+            ("fifo", "q", true),
+            ("fifo", "nibbles", true),
+            ("byte_fifo", "bytes", false),
+        ]
+    );
+    let ModuleItem::VariableDeclaration {
+        data_type: DataType::Specialized(reference),
+        ..
+    } = ast.module_item_arena.get(items[1])
+    else {
+        panic!("Expected specialized declaration");
+    };
+    assert_eq!(
+        reference.arguments,
+        vec![ParameterArgument::Type("logic signed [3:0]".to_string())]
+    );
+}
+
+#[test]
+fn test_extern_methods_defined_outside_class() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/extern_methods.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser
+        .parse_content(&content)
+        .expect("Failed to parse extern methods");
+    assert_eq!(ast.items.len(), 4);
+
+    let ModuleItem::ClassDeclaration { items, .. } = ast.module_item_arena.get(ast.items[0]) else {
+        panic!("Expected class declaration");
+    };
+    let prototypes: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            ClassItem::Method {
+                qualifier,
+                is_virtual,
+                is_extern,
+                name,
+                body,
+                ..
+            } => {
+                assert!(*is_extern, "{} should be extern", name);
+                assert!(body.is_empty());
+                Some((name.as_str(), qualifier.clone(), *is_virtual))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        prototypes,
+        vec![
+            ("new", None, false),
+            ("size", None, true),
+            ("send", Some(ClassQualifier::Protected), false),
+        ]
+    );
+
+    let definitions: Vec<_> = ast.items[1..]
+        .iter()
+        .map(|item| match ast.module_item_arena.get(*item) {
+            ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                name,
+                name_span,
+                parameters,
+                body,
+                ..
+            } => {
+                assert_eq!(&content[name_span.0..name_span.1], name);
+                assert_eq!(body.len(), 1);
+                (
+                    scoped_method_name(class_name, name),
+                    return_type.as_deref(),
+                    parameters.len(),
+                )
+            }
+            other => panic!("Expected out-of-block method, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        definitions,
+        vec![
+            ("packet::new".to_string(), None, 1),
+            ("packet::size".to_string(), Some("int"), 0),
+            ("packet::send".to_string(), Some("void"), 2),
+        ]
+    );
+
+    assert!(parser.analyze_semantics(&ast).is_empty());
+}
+
+#[test]
+fn test_out_of_block_method_return_checked_against_prototype() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"class counter;
+  int count;
+  extern static function void reset();
+endclass
+
+function void counter::reset();
+  this.count = 0;
+  return 1;
+endfunction
+"#;
+    let ast = parser.parse_content(content).unwrap();
+    let messages: Vec<String> = parser
+        .analyze_semantics(&ast)
+        .into_iter()
+        .map(|error| error.message)
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "'this' cannot be used in the static method 'counter::reset'",
+            "Function 'counter::reset' is void, so 'return' cannot give a value",
+        ]
+    );
+}
+
+#[test]
+fn test_virtual_classes_and_pure_virtual_methods() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/virtual_classes.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser
+        .parse_content(&content)
+        .expect("Failed to parse virtual classes");
+    assert_eq!(ast.items.len(), 3);
+
+    let ModuleItem::InterfaceClassDeclaration { items, .. } =
+        ast.module_item_arena.get(ast.items[0])
+    else {
+        panic!("Expected interface class declaration");
+    };
+    assert!(matches!(
+        &items[..],
+        [ClassItem::Method {
+            is_pure: true,
+            is_virtual: true,
+            body,
+            ..
+        }] if body.is_empty()
+    ));
+
+    let methods = |item: &ModuleItem| -> (bool, Vec<(String, bool, bool, bool)>) {
+        let ModuleItem::ClassDeclaration {
+            is_virtual, items, ..
+        } = item
+        else {
+            panic!("Expected class declaration");
+        };
+        let methods = items
+            .iter()
+            .filter_map(|item| match item {
+                ClassItem::Method {
+                    name,
+                    is_virtual,
+                    is_pure,
+                    is_task,
+                    ..
+                } => Some((name.clone(), *is_virtual, *is_pure, *is_task)),
+                _ => None,
+            })
+            .collect();
+        (*is_virtual, methods)
+    };
+    assert_eq!(
+        methods(ast.module_item_arena.get(ast.items[1])),
+        (
+            true,
+            vec![
+                ("new".to_string(), false, false, false),
+                ("run".to_string(), true, true, true),
+                ("report".to_string(), true, true, false),
+                ("reset".to_string(), true, false, true),
+            ]
+        )
+    );
+    assert_eq!(
+        methods(ast.module_item_arena.get(ast.items[2])),
+        (
+            false,
+            vec![
+                ("new".to_string(), false, false, false),
+                ("run".to_string(), true, false, true),
+                ("compare".to_string(), true, false, false),
+                ("report".to_string(), false, false, false),
+            ]
+        )
+    );
+
+    assert!(parser.analyze_semantics(&ast).is_empty());
+}
+
+#[test]
+fn test_randomization_constructs() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let test_file =
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/classes/randomization.sv");
+    let content = std::fs::read_to_string(&test_file).expect("Failed to read test file");
+    let ast = parser
+        .parse_content(&content)
+        .expect("Failed to parse randomization constructs");
+
+    let ModuleItem::ClassDeclaration { items, .. } = ast.module_item_arena.get(ast.items[0]) else {
+        panic!("Expected class declaration");
+    };
+    let properties: Vec<(&str, Option<RandQualifier>)> = items
+        .iter()
+        .filter_map(|item| match item {
+            ClassItem::Property { name, rand, .. } => Some((name.as_str(), *rand)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        properties,
+        vec![
+            ("len", Some(RandQualifier::Rand)),
+            ("kind", Some(RandQualifier::Randc)),
+            ("data", Some(RandQualifier::Rand)),
+            ("small", None),
+        ]
+    );
+
+    let blocks: Vec<&ConstraintBlock> = items
+        .iter()
+        .filter_map(|item| match item {
+            ClassItem::Constraint(block) => Some(block),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        blocks
+            .iter()
+            .map(|block| (
+                block.name.as_str(),
+                block.is_static,
+                block.constraints.len()
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("len_c", false, 2),
+            ("kind_c", false, 2),
+            ("small_c", true, 2)
+        ]
+    );
+    assert!(matches!(
+        &blocks[1].constraints[..],
+        [
+            ConstraintExpr::Inside { soft: false, set, .. },
+            ConstraintExpr::Dist { soft: true, items, .. },
+        ] if set.len() == 2
+            && matches!(ast.expr_arena.get(set[1]), Expression::ValueRange { .. })
+            && items.iter().map(|item| item.per_range).collect::<Vec<_>>() == [false, true]
+    ));
+    assert!(matches!(
+        &blocks[2].constraints[..],
+        [
+            ConstraintExpr::Implication { constraints, .. },
+            ConstraintExpr::IfElse { then_constraints, else_constraints, .. },
+        ] if constraints.len() == 1 && then_constraints.len() == 1 && else_constraints.len() == 1
+    ));
+
+    let Some(ClassItem::Method { body, .. }) = items.last() else {
+        panic!("Expected method");
+    };
+    let Statement::Return {
+        value: Some(value), ..
+    } = ast.stmt_arena.get(body[0])
+    else {
+        panic!("Expected return statement");
+    };
+    assert!(matches!(
+        ast.expr_arena.get(*value),
+        Expression::RandomizeWith { constraints, .. } if constraints.len() == 1
+    ));
+
+    assert!(parser.analyze_semantics(&ast).is_empty());
+}
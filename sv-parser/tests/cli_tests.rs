@@ -1,5 +1,9 @@
 use std::path::PathBuf;
-use sv_parser::parse_vcs_style_args;
+use std::time::Duration;
+use sv_parser::{
+    parse_vcs_style_args, DepsFormat, KeywordVersion, LintSelection, MetricsFormat,
+    ParameterOverride,
+};
 
 #[test]
 fn test_parse_simple_file() {
@@ -188,8 +192,8 @@ fn test_parse_unsupported_vcs_option_warning() {
 #[test]
 fn test_skip_clap_flags() {
     let args = vec![
-        "-v".to_string(),
         "--verbose".to_string(),
+        "-s".to_string(),
         "test.sv".to_string(),
     ];
     let result = parse_vcs_style_args(args, false, false, false).unwrap();
@@ -197,3 +201,435 @@ fn test_skip_clap_flags() {
     // Should skip the clap flags and just parse the file
     assert_eq!(result.files, vec![PathBuf::from("test.sv")]);
 }
+
+#[test]
+fn test_parse_lint_defaults_to_disabled() {
+    let args = vec!["test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    assert_eq!(result.lint, LintSelection::Disabled);
+    assert!(!result.list_rules);
+}
+
+#[test]
+fn test_parse_lint_all_rules() {
+    let args = vec!["--lint".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    assert_eq!(result.lint, LintSelection::All);
+    assert_eq!(result.files, vec![PathBuf::from("test.sv")]);
+}
+
+#[test]
+fn test_parse_lint_rule_selection() {
+    let args = vec![
+        "test.sv".to_string(),
+        "--lint=blocking-in-always-ff, duplicate-declaration".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    assert_eq!(
+        result.lint,
+        LintSelection::Only(vec![
+            "blocking-in-always-ff".to_string(),
+            "duplicate-declaration".to_string()
+        ])
+    );
+}
+
+#[test]
+fn test_parse_no_lint_overrides_lint() {
+    let args = vec![
+        "--lint".to_string(),
+        "--no-lint".to_string(),
+        "test.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    assert_eq!(result.lint, LintSelection::Disabled);
+}
+
+#[test]
+fn test_parse_empty_lint_rule_list_error() {
+    let args = vec!["--lint=".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+
+    assert_eq!(result.unwrap_err(), "Empty rule list in --lint= option");
+}
+
+#[test]
+fn test_parse_fix_needs_lint() {
+    let args = vec![
+        "--lint".to_string(),
+        "--fix".to_string(),
+        "test.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert!(result.fix);
+
+    let args = vec!["--fix".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(result.unwrap_err(), "--fix needs --lint or --lint=<rules>");
+}
+
+#[test]
+fn test_parse_list_rules_without_files() {
+    let args = vec!["--list-rules".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    assert!(result.list_rules);
+    assert!(result.files.is_empty());
+}
+
+#[test]
+fn test_parse_explain_without_files() {
+    let args = vec!["--explain".to_string(), "SV0002".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.explain.as_deref(), Some("SV0002"));
+    assert!(result.files.is_empty());
+
+    let args = vec!["--explain=no-casex".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.explain.as_deref(), Some("no-casex"));
+
+    let args = vec!["--explain".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(result.unwrap_err(), "--explain needs a diagnostic code");
+}
+
+#[test]
+fn test_parse_deps_formats() {
+    let args = vec!["--deps".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.deps, Some(DepsFormat::Json));
+
+    let args = vec!["--deps=make".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.deps, Some(DepsFormat::Make));
+
+    let args = vec!["test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.deps, None);
+}
+
+#[test]
+fn test_parse_unknown_deps_format_error() {
+    let args = vec!["--deps=ninja".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Unknown --deps format: ninja (expected json or make)"
+    );
+}
+
+#[test]
+fn test_parse_report_metrics() {
+    let args = vec![
+        "--report".to_string(),
+        "metrics".to_string(),
+        "test.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.metrics, Some(MetricsFormat::Json));
+    assert_eq!(result.files, vec![PathBuf::from("test.sv")]);
+
+    let args = vec!["--report=metrics=csv".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.metrics, Some(MetricsFormat::Csv));
+
+    let args = vec!["--report=coverage".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(
+        result.unwrap_err(),
+        "Unknown report: coverage (expected metrics)"
+    );
+
+    let args = vec!["--report=metrics=xml".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(
+        result.unwrap_err(),
+        "Unknown --report metrics format: xml (expected json or csv)"
+    );
+}
+
+#[test]
+fn test_parse_compile_order_flag() {
+    let args = vec!["--compile-order".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert!(result.compile_order);
+
+    let args = vec![
+        "--compile-order".to_string(),
+        "--deps".to_string(),
+        "test.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(
+        result.unwrap_err(),
+        "--deps and --compile-order cannot be used together"
+    );
+}
+
+#[test]
+fn test_parse_stats_option() {
+    let args = vec!["test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.stats, None);
+
+    let args = vec!["--stats".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.stats, Some(5));
+
+    let args = vec!["--stats=10".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.stats, Some(10));
+}
+
+#[test]
+fn test_parse_invalid_stats_count_error() {
+    let args = vec!["--stats=many".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+
+    assert_eq!(
+        result.unwrap_err(),
+        "Invalid --stats count: many (expected a number)"
+    );
+}
+
+#[test]
+fn test_parse_header_option() {
+    let args = vec!["defs.inc".to_string()];
+    assert!(
+        !parse_vcs_style_args(args, false, false, false)
+            .unwrap()
+            .header
+    );
+
+    let args = vec!["--header".to_string(), "defs.inc".to_string()];
+    assert!(
+        parse_vcs_style_args(args, false, false, false)
+            .unwrap()
+            .header
+    );
+}
+
+#[test]
+fn test_parse_graph_options() {
+    let args = vec![
+        "--graph".to_string(),
+        "modules.dot".to_string(),
+        "--graph-depth=2".to_string(),
+        "--collapse-cells".to_string(),
+        "test.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.graph, Some(PathBuf::from("modules.dot")));
+    assert_eq!(result.graph_options.max_depth, Some(2));
+    assert!(result.graph_options.collapse_library_cells);
+    assert_eq!(result.files, vec![PathBuf::from("test.sv")]);
+
+    let args = vec!["--graph=modules.json".to_string(), "test.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.graph, Some(PathBuf::from("modules.json")));
+
+    let args = vec!["test.sv".to_string(), "--graph".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(result.unwrap_err(), "--graph needs an output file");
+}
+
+#[test]
+fn test_parse_library_files() {
+    let args = vec![
+        "-v".to_string(),
+        "cells.v".to_string(),
+        "top.sv".to_string(),
+        "-v".to_string(),
+        "vendor/ram.v".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(
+        result.library_files,
+        vec![PathBuf::from("cells.v"), PathBuf::from("vendor/ram.v")]
+    );
+    assert_eq!(result.files, vec![PathBuf::from("top.sv")]);
+
+    let args = vec!["top.sv".to_string(), "-v".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(result.unwrap_err(), "-v needs a library file");
+}
+
+#[test]
+fn test_parse_extract_package_option() {
+    let args = vec![
+        "--extract-package".to_string(),
+        "shared_pkg".to_string(),
+        "a.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.extract_package.as_deref(), Some("shared_pkg"));
+    assert_eq!(result.files, vec![PathBuf::from("a.sv")]);
+
+    let args = vec!["--extract-package=common".to_string(), "a.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.extract_package.as_deref(), Some("common"));
+
+    let args = vec!["a.sv".to_string(), "--extract-package=".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false);
+    assert_eq!(
+        result.unwrap_err(),
+        "--extract-package needs a package name"
+    );
+}
+
+#[test]
+fn test_parse_language_extension_options() {
+    let args = vec![
+        "+verilog2001ext+.v2k+.vlib".to_string(),
+        "-sverilog".to_string(),
+        "+systemverilogext+.vh".to_string(),
+        "top.v".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    // -sverilog comes first so that explicit extensions override it
+    let versions: Vec<_> = result
+        .extension_versions
+        .iter()
+        .map(|(extension, version)| (extension.as_str(), *version))
+        .collect();
+    assert_eq!(
+        versions,
+        vec![
+            ("v", KeywordVersion::LATEST),
+            ("vh", KeywordVersion::LATEST),
+            ("v2k", KeywordVersion::V1364_2001),
+            ("vlib", KeywordVersion::V1364_2001),
+            ("vh", KeywordVersion::LATEST),
+        ]
+    );
+
+    let args = vec!["+systemverilogext+".to_string(), "top.v".to_string()];
+    assert!(parse_vcs_style_args(args, false, false, false).is_err());
+}
+
+#[test]
+fn test_parse_parameter_overrides() {
+    let args = vec![
+        "--param".to_string(),
+        "top.WIDTH=64".to_string(),
+        "--param=top.DEPTH=8'h10".to_string(),
+        "-pvalue+core.MODE=2".to_string(),
+        "top.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+
+    let overrides: Vec<_> = result
+        .parameters
+        .iter()
+        .map(|p| (p.module.as_str(), p.name.as_str(), p.value.as_str()))
+        .collect();
+    assert_eq!(
+        overrides,
+        vec![
+            ("top", "WIDTH", "64"),
+            ("top", "DEPTH", "8'h10"),
+            ("core", "MODE", "2"),
+        ]
+    );
+    assert_eq!(result.files, vec![PathBuf::from("top.sv")]);
+}
+
+#[test]
+fn test_parse_parameter_override_errors() {
+    assert_eq!(
+        ParameterOverride::parse("WIDTH=64").unwrap_err(),
+        "Invalid parameter override: WIDTH=64 (expected <module>.<parameter>=<value>)"
+    );
+    assert_eq!(
+        ParameterOverride::parse("top.u1.WIDTH=64").unwrap_err(),
+        "Only parameters of top-level modules can be overridden: top.u1.WIDTH"
+    );
+    assert_eq!(
+        ParameterOverride::parse("top.WIDTH=wide").unwrap_err(),
+        "Invalid value for parameter top.WIDTH: wide (expected an integer literal)"
+    );
+
+    let args = vec!["top.sv".to_string(), "--param".to_string()];
+    assert_eq!(
+        parse_vcs_style_args(args, false, false, false).unwrap_err(),
+        "--param needs <module>.<parameter>=<value>"
+    );
+}
+
+#[test]
+fn test_parse_doc_option() {
+    let args = vec![
+        "--doc".to_string(),
+        "docs".to_string(),
+        "top.sv".to_string(),
+    ];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.doc, Some(PathBuf::from("docs")));
+    assert_eq!(result.files, vec![PathBuf::from("top.sv")]);
+
+    let args = vec!["--doc=out/docs".to_string(), "top.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.doc, Some(PathBuf::from("out/docs")));
+
+    let args = vec!["top.sv".to_string(), "--doc".to_string()];
+    assert_eq!(
+        parse_vcs_style_args(args, false, false, false).unwrap_err(),
+        "--doc needs an output directory"
+    );
+
+    let args = vec![
+        "--doc=docs".to_string(),
+        "--compile-order".to_string(),
+        "top.sv".to_string(),
+    ];
+    assert_eq!(
+        parse_vcs_style_args(args, false, false, false).unwrap_err(),
+        "--doc cannot be used with --report, --deps or --compile-order"
+    );
+}
+
+#[test]
+fn test_parse_round_trip_option() {
+    let args = vec!["--round-trip".to_string(), "top.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert!(result.round_trip);
+    assert_eq!(result.files, vec![PathBuf::from("top.sv")]);
+
+    let args = vec![
+        "--round-trip".to_string(),
+        "--deps".to_string(),
+        "top.sv".to_string(),
+    ];
+    assert_eq!(
+        parse_vcs_style_args(args, false, false, false).unwrap_err(),
+        "--round-trip cannot be used with --doc, --report, --deps or --compile-order"
+    );
+}
+
+#[test]
+fn test_parse_timeout_option() {
+    let args = vec!["top.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.parse_timeout, None);
+
+    let args = vec!["--parse-timeout=2.5".to_string(), "top.sv".to_string()];
+    let result = parse_vcs_style_args(args, false, false, false).unwrap();
+    assert_eq!(result.parse_timeout, Some(Duration::from_millis(2500)));
+
+    for seconds in ["0", "-1", "soon"] {
+        let args = vec![format!("--parse-timeout={}", seconds), "top.sv".to_string()];
+        assert_eq!(
+            parse_vcs_style_args(args, false, false, false).unwrap_err(),
+            format!(
+                "Invalid --parse-timeout: {} (expected a positive number of seconds)",
+                seconds
+            )
+        );
+    }
+}
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use sv_parser::testing::for_each_fixture;
 use sv_parser::{ParseError, SourceUnit, SystemVerilogParser};
 
 pub mod ast;
@@ -30,10 +31,6 @@ impl TestHarness {
         self
     }
 
-    pub fn fixtures_root(&self) -> &Path {
-        &self.fixtures_root
-    }
-
     pub fn fixture_path(&self, relative: &str) -> PathBuf {
         self.fixtures_root.join(relative)
     }
@@ -72,41 +69,18 @@ pub fn default_fixtures_root() -> PathBuf {
     Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files")
 }
 
-pub fn iter_sv_files(relative_dir: &str) -> Vec<PathBuf> {
-    let root = default_fixtures_root().join(relative_dir);
-    if !root.exists() {
-        return Vec::new();
-    }
-
-    let mut files: Vec<PathBuf> = fs::read_dir(&root)
-        .unwrap_or_else(|err| panic!("Failed to read directory {}: {}", root.display(), err))
-        .filter_map(|entry| {
-            let path = entry.ok()?.path();
-            match path.extension().and_then(|ext| ext.to_str()) {
-                Some("sv") => Some(path),
-                _ => None,
-            }
-        })
-        .collect();
-    files.sort();
-    files
-}
-
+/// Parse the fixtures under `relative_dir` with the default parser, see
+/// [`for_each_fixture`]
 pub fn for_each_sv_file<F>(relative_dir: &str, mut visit: F)
 where
     F: FnMut(&Path, Result<SourceUnit, ParseError>),
 {
-    let harness = TestHarness::default();
-    let fixtures_root = harness.fixtures_root().to_path_buf();
-
-    for path in iter_sv_files(relative_dir) {
-        let relative = path
-            .strip_prefix(&fixtures_root)
-            .unwrap_or_else(|_| path.as_path());
-        let relative_str = relative.to_string_lossy().replace('\\', "/");
-        let result = harness.parse_fixture(&relative_str);
-        visit(&path, result);
-    }
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    for_each_fixture(
+        &parser,
+        default_fixtures_root().join(relative_dir),
+        |path, _, result| visit(path, result),
+    );
 }
 
 #[allow(dead_code)]
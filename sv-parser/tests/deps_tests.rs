@@ -0,0 +1,299 @@
+//! Dependency analysis tests for `--deps` output.
+
+use std::collections::HashMap;
+use std::fs;
+
+use sv_parser::deps::{self, DesignUnit, DesignUnitKind};
+use sv_parser::{FileDependencies, SystemVerilogParser};
+use tempfile::TempDir;
+
+fn collect(dir: &TempDir, name: &str) -> FileDependencies {
+    let path = dir.path().join(name);
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser
+        .parse_file(&path)
+        .unwrap_or_else(|err| panic!("Failed to parse {}: {}", name, err));
+    FileDependencies::collect(&path, parser.resolved_includes(), &ast)
+}
+
+#[test]
+fn test_deps_includes_definitions_and_references() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("defs.svh"), "`define WIDTH 8\n").unwrap();
+    fs::write(
+        dir.path().join("top.sv"),
+        r#"`include "defs.svh"
+module top(input clk);
+    logic a;
+    packet_t pkt;
+    sub u_sub (.clk(clk), .a(a));
+    leaf u_leaf (.clk);
+endmodule
+
+module leaf(input clk);
+endmodule
+"#,
+    )
+    .unwrap();
+
+    let deps = collect(&dir, "top.sv");
+
+    assert_eq!(deps.includes, vec![dir.path().join("defs.svh")]);
+    assert_eq!(
+        deps.definitions,
+        vec![
+            DesignUnit {
+                name: "leaf".to_string(),
+                kind: DesignUnitKind::Module
+            },
+            DesignUnit {
+                name: "top".to_string(),
+                kind: DesignUnitKind::Module
+            },
+        ]
+    );
+    // `leaf` is defined in the same file, and `logic` is a built-in type
+    assert_eq!(deps.references, vec!["packet_t", "sub"]);
+}
+
+#[test]
+fn test_deps_class_extends_is_a_reference() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("driver.sv"),
+        "class my_driver extends base_driver;\nendclass\n",
+    )
+    .unwrap();
+
+    let deps = collect(&dir, "driver.sv");
+
+    assert_eq!(deps.definitions[0].kind, DesignUnitKind::Class);
+    assert_eq!(deps.references, vec!["base_driver"]);
+}
+
+#[test]
+fn test_deps_interface_classes_and_virtual_interfaces() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("agent.sv"),
+        r#"interface class resettable extends base_if_class;
+endclass
+
+class agent implements resettable, printable;
+    virtual interface bus_if.monitor vif;
+endclass
+
+module top;
+    virtual ctrl_if cif;
+endmodule
+"#,
+    )
+    .unwrap();
+
+    let deps = collect(&dir, "agent.sv");
+
+    assert!(deps.definitions.contains(&DesignUnit {
+        name: "resettable".to_string(),
+        kind: DesignUnitKind::InterfaceClass
+    }));
+    assert_eq!(
+        deps.references,
+        vec!["base_if_class", "bus_if", "ctrl_if", "printable"]
+    );
+}
+
+#[test]
+fn test_deps_interfaces_and_interface_ports() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("bus.sv"),
+        r#"interface bus_if;
+    logic req;
+    modport master(output req);
+endinterface
+
+module producer(bus_if.master bus, cfg_if cfg, interface any);
+endmodule
+"#,
+    )
+    .unwrap();
+
+    let deps = collect(&dir, "bus.sv");
+
+    assert_eq!(
+        deps.definitions,
+        vec![
+            DesignUnit {
+                name: "bus_if".to_string(),
+                kind: DesignUnitKind::Interface
+            },
+            DesignUnit {
+                name: "producer".to_string(),
+                kind: DesignUnitKind::Module
+            },
+        ]
+    );
+    // A generic `interface` port names no interface
+    assert_eq!(deps.references, vec!["cfg_if"]);
+}
+
+#[test]
+fn test_deps_packages_and_imports() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("top.sv"),
+        r#"package top_pkg;
+    import base_pkg::*;
+endpackage
+
+module top import cfg_pkg::WIDTH; ;
+    types_pkg::packet_t pkt;
+endmodule
+"#,
+    )
+    .unwrap();
+
+    let deps = collect(&dir, "top.sv");
+
+    assert_eq!(
+        deps.definitions,
+        vec![
+            DesignUnit {
+                name: "top".to_string(),
+                kind: DesignUnitKind::Module
+            },
+            DesignUnit {
+                name: "top_pkg".to_string(),
+                kind: DesignUnitKind::Package
+            },
+        ]
+    );
+    assert_eq!(deps.references, vec!["base_pkg", "cfg_pkg", "types_pkg"]);
+}
+
+#[test]
+fn test_deps_typedefs_are_not_references() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("top.sv"),
+        r#"typedef logic [7:0] byte_t;
+typedef base_t word_t;
+
+module top;
+    byte_t count;
+    word_t data;
+    enum {IDLE, BUSY} state;
+endmodule
+"#,
+    )
+    .unwrap();
+
+    let deps = collect(&dir, "top.sv");
+
+    // The type a typedef names is still a reference
+    assert_eq!(deps.references, vec!["base_t"]);
+}
+
+#[test]
+fn test_deps_makefile_links_referenced_files() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("defs.svh"), "`define WIDTH 8\n").unwrap();
+    fs::write(
+        dir.path().join("top.sv"),
+        "`include \"defs.svh\"\nmodule top;\n    sub u_sub ();\nendmodule\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("sub.sv"), "module sub;\nendmodule\n").unwrap();
+
+    let all_deps = vec![collect(&dir, "top.sv"), collect(&dir, "sub.sv")];
+    let makefile = deps::to_makefile(&all_deps);
+
+    let top = dir.path().join("top.sv");
+    let sub = dir.path().join("sub.sv");
+    let defs = dir.path().join("defs.svh");
+    assert_eq!(
+        makefile,
+        format!(
+            "{}: {} {}\n{}:\n",
+            top.display(),
+            defs.display(),
+            sub.display(),
+            sub.display()
+        )
+    );
+}
+
+#[test]
+fn test_deps_json_output() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("sub.sv"), "module sub;\nendmodule\n").unwrap();
+
+    let json = deps::to_json(&[collect(&dir, "sub.sv")]);
+
+    assert!(json.contains(r#""includes": []"#), "got: {}", json);
+    assert!(json.contains(r#""name": "sub""#), "got: {}", json);
+    assert!(json.contains(r#""kind": "module""#), "got: {}", json);
+    assert!(json.contains(r#""references": []"#), "got: {}", json);
+}
+
+#[test]
+fn test_compile_order_puts_definitions_first() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("test.sv"),
+        "class my_test extends base_test;\nendclass\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("base.sv"),
+        "class base_test extends uvm_test;\nendclass\n",
+    )
+    .unwrap();
+    fs::write(dir.path().join("other.sv"), "module other;\nendmodule\n").unwrap();
+
+    let all_deps = vec![
+        collect(&dir, "test.sv"),
+        collect(&dir, "other.sv"),
+        collect(&dir, "base.sv"),
+    ];
+    let order = deps::compile_order(&all_deps);
+
+    // `uvm_test` is defined outside the file list, so it places no constraint
+    assert_eq!(
+        order.files,
+        vec![
+            dir.path().join("other.sv"),
+            dir.path().join("base.sv"),
+            dir.path().join("test.sv"),
+        ]
+    );
+    assert!(order.cycles.is_empty());
+}
+
+#[test]
+fn test_compile_order_keeps_cycles_in_original_order() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("a.sv"), "class a extends b;\nendclass\n").unwrap();
+    fs::write(dir.path().join("b.sv"), "class b extends a;\nendclass\n").unwrap();
+    fs::write(dir.path().join("c.sv"), "class c;\nendclass\n").unwrap();
+
+    let all_deps = vec![
+        collect(&dir, "a.sv"),
+        collect(&dir, "b.sv"),
+        collect(&dir, "c.sv"),
+    ];
+    let order = deps::compile_order(&all_deps);
+
+    assert_eq!(
+        order.files,
+        vec![
+            dir.path().join("c.sv"),
+            dir.path().join("a.sv"),
+            dir.path().join("b.sv"),
+        ]
+    );
+    assert_eq!(
+        order.cycles,
+        vec![dir.path().join("a.sv"), dir.path().join("b.sv")]
+    );
+}
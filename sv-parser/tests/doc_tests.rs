@@ -0,0 +1,114 @@
+//! Markdown module documentation tests for `--doc` output.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use sv_parser::doc::{to_markdown, ParameterDoc, PortDoc};
+use sv_parser::{ModuleDoc, SystemVerilogParser};
+
+const DESIGN: &str = r#"// Adds two operands
+// ------------------
+module adder(
+    input logic [7:0] a, // First operand
+    // Second operand
+    input logic [MSB:0] b,
+    output sum
+);
+    // Width of the operands
+    parameter int WIDTH = 8;
+    localparam MSB = 7;
+    parameter DEPTH = 4; // Pipeline | stages
+endmodule
+
+/**
+ * Top of the design
+ */
+module top(clk, y);
+    input wire clk; // System clock
+    adder u_add (.a(), .b(), .sum(y));
+    adder u_spare (.a(), .b(), .sum());
+endmodule
+"#;
+
+fn collect(content: &str) -> Vec<ModuleDoc> {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    ModuleDoc::collect(Path::new("design.sv"), &ast, content)
+}
+
+fn port(name: &str, direction: &str, data_type: &str, width: &str, description: &str) -> PortDoc {
+    PortDoc {
+        name: name.to_string(),
+        direction: direction.to_string(),
+        data_type: data_type.to_string(),
+        width: width.to_string(),
+        description: description.to_string(),
+    }
+}
+
+#[test]
+fn test_module_docs_from_comments() {
+    let docs = collect(DESIGN);
+    assert_eq!(docs.len(), 2);
+
+    let adder = &docs[0];
+    assert_eq!(adder.name, "adder");
+    assert_eq!(adder.description, "Adds two operands");
+    assert_eq!(
+        adder.ports,
+        vec![
+            port("a", "input", "logic", "8", "First operand"),
+            port("b", "input", "logic", "[MSB:0]", "Second operand"),
+            port("sum", "output", "", "1", ""),
+        ]
+    );
+    assert_eq!(
+        adder.parameters,
+        vec![
+            ParameterDoc {
+                name: "WIDTH".to_string(),
+                data_type: "int".to_string(),
+                default: "8".to_string(),
+                description: "Width of the operands".to_string(),
+            },
+            ParameterDoc {
+                name: "DEPTH".to_string(),
+                data_type: String::new(),
+                default: "4".to_string(),
+                description: "Pipeline | stages".to_string(),
+            },
+        ]
+    );
+
+    let top = &docs[1];
+    assert_eq!(top.description, "Top of the design");
+    assert_eq!(
+        top.ports,
+        vec![
+            port("clk", "input", "wire", "1", "System clock"),
+            port("y", "", "", "1", ""),
+        ]
+    );
+    assert_eq!(
+        top.instances,
+        vec![
+            ("u_add".to_string(), "adder".to_string()),
+            ("u_spare".to_string(), "adder".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn test_module_page() {
+    let docs = collect(DESIGN);
+
+    let page = to_markdown(&docs[0], &docs);
+    assert!(page.starts_with("# adder\n\nAdds two operands\n\nDefined in `design.sv`.\n"));
+    assert!(page.contains("| `DEPTH` |  | `4` | Pipeline \\| stages |\n"));
+    assert!(page.contains("| `a` | input | logic | 8 | First operand |\n"));
+    assert!(page.contains("```text\ntop\n└── u_add: adder\ntop\n└── u_spare: adder\n```\n"));
+
+    let page = to_markdown(&docs[1], &docs);
+    assert!(!page.contains("## Parameters"));
+    assert!(page.contains("```text\ntop\n├── u_add: adder\n└── u_spare: adder\n```\n"));
+}
@@ -3,7 +3,10 @@
 #[path = "common/mod.rs"]
 mod common;
 
-use common::{assert_directory_parses, assert_parse_ok};
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok, TestHarness};
+use sv_parser::{ModuleItem, SemanticErrorType, SystemVerilogParser};
 
 /// All drive-strength fixtures should parse.
 #[test]
@@ -15,6 +18,8 @@ sv_ok_tests! {
     drive_strength_strong1_highz0 => "drive_strengths/10.3.4--assignment_strong1_highz0.sv",
     drive_strength_pull1_pull0 => "drive_strengths/10.3.4--assignment_pull1_pull0.sv",
     drive_strength_weak1_weak0 => "drive_strengths/10.3.4--assignment_weak1_weak0.sv",
+    continuous_assign_strengths => "drive_strengths/continuous_assign_strengths.sv",
+    invalid_strengths => "drive_strengths/invalid_strengths.sv",
 }
 
 /// Sanity check that at least one drive-strength file parses into a module.
@@ -23,3 +28,84 @@ fn test_drive_strength_strong1_highz0_structure() {
     let unit = assert_parse_ok("drive_strengths/10.3.4--assignment_strong1_highz0.sv");
     assert!(!unit.items.is_empty());
 }
+
+/// `assign` keeps its strength and delay, and a list of net assignments
+/// becomes one assignment per net that share them
+#[test]
+fn test_continuous_assign_strengths() {
+    let unit = assert_parse_ok("drive_strengths/continuous_assign_strengths.sv");
+    let arena = &unit.module_item_arena;
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(unit.items[0]) else {
+        panic!("Expected module declaration");
+    };
+
+    let strengths: Vec<_> = items
+        .iter()
+        .map(|item| match arena.get(*item) {
+            ModuleItem::Assignment {
+                drive_strength: Some(strength),
+                delay,
+                ..
+            } => (
+                strength.strength0.as_str(),
+                strength.strength1.as_str(),
+                delay.is_some(),
+            ),
+            other => panic!("Expected assignment with a strength, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        strengths,
+        vec![
+            ("weak0", "weak1", false),
+            ("strong1", "highz0", true),
+            ("strong1", "highz0", true),
+        ]
+    );
+
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let errors = parser.analyze_semantics(&unit);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn test_invalid_strength_pairs() {
+    let harness = TestHarness::new();
+    let content = harness.read_fixture("drive_strengths/invalid_strengths.sv");
+    let unit = harness.parse_fixture_ok("drive_strengths/invalid_strengths.sv");
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+
+    let found: Vec<_> = parser
+        .analyze_semantics(&unit)
+        .into_iter()
+        .map(|error| {
+            assert_eq!(error.error_type, SemanticErrorType::InvalidDriveStrength);
+            let text: String = content
+                .chars()
+                .skip(error.span.0)
+                .take(error.span.1 - error.span.0)
+                .collect();
+            (text, error.message)
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "(weak0, strong0)".to_string(),
+                "Drive strength (weak0, strong0) needs one strength for 0 and one for 1"
+                    .to_string()
+            ),
+            (
+                "(highz1, highz0)".to_string(),
+                "Drive strength (highz1, highz0) cannot make both values high impedance"
+                    .to_string()
+            ),
+            (
+                "(pull1, supply1)".to_string(),
+                "Drive strength (pull1, supply1) needs one strength for 0 and one for 1"
+                    .to_string()
+            ),
+        ]
+    );
+}
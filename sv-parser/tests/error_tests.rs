@@ -4,7 +4,9 @@
 mod common;
 
 use common::{assert_directory_fails, assert_parse_err};
-use sv_parser::SystemVerilogParser;
+use std::time::Duration;
+
+use sv_parser::{ParseErrorType, SystemVerilogParser};
 
 /// Error fixtures in `test_files/errors/` should all fail.
 #[test]
@@ -15,6 +17,10 @@ fn test_parse_all_error_files() {
 sv_err_tests! {
     invalid_syntax_fixture => "errors/invalid_syntax.sv",
     incomplete_module_fixture => "errors/incomplete_module.sv",
+    case_range_without_inside => "errors/case_range_without_inside.sv",
+    sequence_end_mismatch => "errors/sequence_end_mismatch.sv",
+    sequence_disable_iff => "errors/sequence_disable_iff.sv",
+    clocking_empty_default_skew => "errors/clocking_empty_default_skew.sv",
 }
 
 #[test]
@@ -84,3 +90,101 @@ fn test_multiple_errors_different_columns() {
         Ok(_) => panic!("Expected parse to fail"),
     }
 }
+
+#[test]
+fn test_error_names_expected_and_found_tokens() {
+    let parser = SystemVerilogParser::new(vec![], Default::default());
+    let content = "module test;\n    wire a, b\nendmodule\n";
+
+    let err = parser.parse_content(content).unwrap_err();
+    let error = err.primary_error();
+    assert_eq!(
+        error.message,
+        "expected ',', ';', '=' or '[', found 'endmodule'"
+    );
+    assert_eq!(
+        error.error_type,
+        ParseErrorType::ExpectedToken("',', ';', '=' or '['".to_string())
+    );
+
+    // The error span covers the whole found token
+    let location = error.location.as_ref().unwrap();
+    assert_eq!((location.line, location.column), (2, 0));
+    let (start, end) = location.span.unwrap();
+    assert_eq!(&content[start..end], "endmodule");
+}
+
+#[test]
+fn test_error_at_end_of_input() {
+    let parser = SystemVerilogParser::new(vec![], Default::default());
+    let content = "module test;\n    logic a\n";
+
+    let err = parser.parse_content(content).unwrap_err();
+    let error = err.primary_error();
+    assert_eq!(error.error_type, ParseErrorType::UnexpectedEndOfInput);
+    assert!(error.message.ends_with("found end of input"));
+}
+
+#[test]
+fn test_error_with_single_expected_token() {
+    let parser = SystemVerilogParser::new(vec![], Default::default());
+    let content = "module test(input a b);\nendmodule\n";
+
+    let err = parser.parse_content(content).unwrap_err();
+    assert_eq!(
+        err.primary_error().message,
+        "expected ')' or ',', found 'b'"
+    );
+}
+
+#[test]
+fn test_error_expecting_an_operand() {
+    let parser = SystemVerilogParser::new(vec![], Default::default());
+    let content = "module test;\n    assign a = (b + ;\nendmodule\n";
+
+    let err = parser.parse_content(content).unwrap_err();
+    let message = &err.primary_error().message;
+    assert!(
+        message.ends_with("identifier, number, string or system task or function, found ';'"),
+        "{}",
+        message
+    );
+}
+
+#[test]
+fn test_error_quotes_tokens_with_a_quote() {
+    let parser = SystemVerilogParser::new(vec![], Default::default());
+    let content = "module test;\n    assign a = 4'b1010 + ';\nendmodule\n";
+
+    let err = parser.parse_content(content).unwrap_err();
+    let message = &err.primary_error().message;
+    assert!(message.contains("\"'{\""), "{}", message);
+    assert!(message.ends_with("found \"'\""), "{}", message);
+}
+
+#[test]
+fn test_parse_timeout_names_the_unit_being_parsed() {
+    let assignments: String = (0..500)
+        .map(|i| format!("    assign y{} = a{} + b{};\n", i, i, i))
+        .collect();
+    let content = format!("module big;\n{}endmodule\n", assignments);
+
+    let mut parser = SystemVerilogParser::new(vec![], Default::default());
+    assert!(parser.parse_content(&content).is_ok());
+
+    parser.set_parse_timeout(Some(Duration::from_nanos(1)));
+    let err = parser.parse_content(&content).unwrap_err();
+    assert_eq!(err.errors.len(), 1);
+    let error = err.primary_error();
+    assert_eq!(error.error_type, ParseErrorType::Timeout);
+    assert_eq!(
+        error.message,
+        "parsing took longer than 1ns and was abandoned in module 'big'"
+    );
+    // The error points where the parser was, somewhere in the module
+    let (start, _) = error.location.as_ref().unwrap().span.unwrap();
+    assert!(start > content.find("big").unwrap() && start < content.len());
+
+    parser.set_parse_timeout(Some(Duration::from_secs(60)));
+    assert!(parser.parse_content(&content).is_ok());
+}
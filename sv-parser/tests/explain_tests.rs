@@ -0,0 +1,76 @@
+//! Diagnostic code explanation tests for `--explain` output.
+
+use std::collections::HashSet;
+
+use sv_parser::explain::{codes, documentation_url, explain};
+use sv_parser::{ClassDiagnosticKind, SemanticErrorType};
+
+#[test]
+fn test_every_code_is_unique_and_explained() {
+    let codes = codes();
+    let unique: HashSet<&String> = codes.iter().collect();
+    assert_eq!(unique.len(), codes.len(), "duplicate codes in {:?}", codes);
+
+    for code in &codes {
+        let page = explain(code).unwrap_or_else(|| panic!("no page for {}", code));
+        assert!(
+            page.starts_with(&format!("# {}: ", code)),
+            "page of {} starts with: {}",
+            code,
+            page.lines().next().unwrap_or("")
+        );
+    }
+}
+
+#[test]
+fn test_semantic_error_page() {
+    assert_eq!(SemanticErrorType::UndeclaredIdentifier.code(), "SV0002");
+    let page = explain("SV0002").unwrap();
+
+    assert!(
+        page.starts_with("# SV0002: Undeclared identifier\n"),
+        "got: {}",
+        page
+    );
+    assert!(
+        page.contains("semantic analysis as an error"),
+        "got: {}",
+        page
+    );
+}
+
+#[test]
+fn test_class_diagnostic_page() {
+    assert_eq!(ClassDiagnosticKind::NonVirtualOverride.code(), "SV0102");
+    let page = explain("sv0102").unwrap();
+
+    assert!(page.starts_with("# SV0102: "), "got: {}", page);
+    assert!(page.contains("as a warning"), "got: {}", page);
+}
+
+#[test]
+fn test_lint_rule_page() {
+    let page = explain("no-casex").unwrap();
+
+    assert!(page.starts_with("# no-casex: "), "got: {}", page);
+    assert!(page.contains("`casez`"), "got: {}", page);
+    assert!(
+        page.contains("[lint.severity]\nno-casex = \"warning\"\n"),
+        "got: {}",
+        page
+    );
+
+    let page = explain("dead-code").unwrap();
+    assert!(page.contains("runs only when named"), "got: {}", page);
+}
+
+#[test]
+fn test_unknown_code() {
+    assert_eq!(explain("SV9999"), None);
+    assert_eq!(explain("no-such-rule"), None);
+}
+
+#[test]
+fn test_documentation_url() {
+    assert!(documentation_url("SV0002").ends_with("/SV0002.md"));
+}
@@ -7,7 +7,8 @@
 mod common;
 
 use common::{assert_directory_parses, assert_parse_ok};
-use sv_parser::{BinaryOp, Expression, ModuleItem};
+use std::collections::HashMap;
+use sv_parser::{simplify, BinaryOp, Expression, ModuleItem, Statement, SystemVerilogParser};
 
 /// Test parsing all expression test files
 #[test]
@@ -17,9 +18,11 @@ fn test_parse_all_expression_files() {
 
 sv_ok_tests! {
     expr_binary_add => "expressions/binary_add.sv",
+    expr_conditional => "expressions/conditional.sv",
     expr_module_with_assignment => "expressions/module_with_assignment.sv",
     expr_numbers => "expressions/numbers.sv",
     expr_parentheses => "expressions/parentheses.sv",
+    expr_precedence => "expressions/precedence.sv",
     expr_systemverilog_number_with_z => "expressions/systemverilog_number_with_z.sv",
 }
 
@@ -151,3 +154,191 @@ fn test_systemverilog_numbers() {
     };
     assert_eq!(right_num, "8'b1101z001");
 }
+
+#[test]
+fn test_conditional_expression_is_right_associative() {
+    let result = assert_parse_ok("expressions/conditional.sv");
+
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::Assignment { expr, .. } = result.module_item_arena.get(items[0]) else {
+        panic!("Expected assignment");
+    };
+    let Expression::Conditional {
+        condition,
+        else_expr,
+        ..
+    } = result.expr_arena.get(*expr)
+    else {
+        panic!("Expected conditional expression");
+    };
+    assert!(matches!(
+        result.expr_arena.get(*condition),
+        Expression::Identifier(name, _) if name == "sel"
+    ));
+    // `a ? b : c ? d : e` nests in the else branch
+    let Expression::Conditional { condition, .. } = result.expr_arena.get(*else_expr) else {
+        panic!("Expected nested conditional in else branch");
+    };
+    assert!(matches!(
+        result.expr_arena.get(*condition),
+        Expression::Binary {
+            op: BinaryOp::Equal,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn test_parse_expression_fragment() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let fragment = parser.parse_expression("  a + (b * 2) ").unwrap();
+
+    let Expression::Binary {
+        op: BinaryOp::Add,
+        left,
+        right,
+        ..
+    } = fragment.expression()
+    else {
+        panic!("Expected addition, got {:?}", fragment.expression());
+    };
+    assert!(matches!(
+        fragment.expr_arena.get(*left),
+        Expression::Identifier(name, _) if name == "a"
+    ));
+    assert!(matches!(
+        fragment.expr_arena.get(*right),
+        Expression::Binary {
+            op: BinaryOp::Mul,
+            ..
+        }
+    ));
+
+    assert!(parser.parse_expression("a +").is_err());
+    assert!(parser.parse_expression("a b").is_err());
+}
+
+#[test]
+fn test_parse_statement_fragment() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let fragment = parser
+        .parse_statement("begin count = count + 1; done = 1'b1; end")
+        .unwrap();
+
+    let Statement::Block { statements, .. } = fragment.statement() else {
+        panic!("Expected block, got {:?}", fragment.statement());
+    };
+    assert_eq!(statements.len(), 2);
+    let Statement::Assignment { expr, .. } = fragment.stmt_arena.get(statements[0]) else {
+        panic!("Expected assignment");
+    };
+    assert!(matches!(
+        fragment.expr_arena.get(*expr),
+        Expression::Binary {
+            op: BinaryOp::Add,
+            ..
+        }
+    ));
+
+    assert!(parser.parse_statement("count = 1").is_err());
+}
+
+/// Chains of binary operators group by precedence, left to right within one
+/// precedence, except for the implications. The simplified form puts every
+/// compound operand in parentheses, which shows the grouping.
+#[test]
+fn test_operator_precedence() {
+    let result = assert_parse_ok("expressions/precedence.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let grouped: Vec<String> = items
+        .iter()
+        .map(|item_ref| {
+            let ModuleItem::Assignment { expr, .. } = result.module_item_arena.get(*item_ref)
+            else {
+                panic!("Expected assignment");
+            };
+            simplify(*expr, &result.expr_arena).to_string()
+        })
+        .collect();
+    assert_eq!(
+        grouped,
+        vec![
+            "(b + (c * d)) - e",
+            "(g & h) | (i ^ j)",
+            "((l == m) && (n < o)) || p",
+            "(r ** s) ** t",
+            "v -> (w -> x)",
+            "(z ? a : b) -> c",
+        ]
+    );
+}
+
+#[test]
+fn test_operators_sharing_a_first_character() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    for (text, op) in [
+        ("a & &b", BinaryOp::And),
+        ("a && b", BinaryOp::LogicalAnd),
+        ("a | |b", BinaryOp::Or),
+        ("a < b", BinaryOp::LessThan),
+        ("a <-> b", BinaryOp::LogicalEquiv),
+        ("a - -b", BinaryOp::Sub),
+        ("a ^~ b", BinaryOp::BitwiseXnor),
+    ] {
+        let fragment = parser.parse_expression(text).unwrap();
+        assert!(
+            matches!(fragment.expression(), Expression::Binary { op: parsed, .. } if *parsed == op),
+            "{} parsed as {:?}",
+            text,
+            fragment.expression()
+        );
+    }
+}
+
+/// A reserved word after `.` or `::` is a member name, like the array
+/// reduction `and` or a method called `new`.
+#[test]
+fn test_keywords_as_member_names() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    for (text, name) in [
+        ("bits.and()", "and"),
+        ("queue.unique()", "unique"),
+        ("item.new", "new"),
+        ("pkg::new", "new"),
+    ] {
+        let fragment = parser.parse_expression(text).unwrap();
+        let member = match fragment.expression() {
+            Expression::FunctionCall { function, .. } => fragment.expr_arena.get(*function),
+            expr => expr,
+        };
+        let parsed = match member {
+            Expression::MemberAccess { member, .. } => member,
+            Expression::ScopedIdentifier { name, .. } => name,
+            expr => panic!("{} parsed as {:?}", text, expr),
+        };
+        assert_eq!(parsed, name, "{}", text);
+    }
+}
+
+/// Names that start with a keyword are whole identifiers.
+#[test]
+fn test_names_starting_with_keywords() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let unit = parser
+        .parse_content(
+            "module top(input logic input_valid, output logic output_data);\n    assign output_data = input_valid & end_flag;\nendmodule\n",
+        )
+        .unwrap();
+    let ModuleItem::ModuleDeclaration { ports, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let names: Vec<_> = ports.iter().map(|port| port.name.as_str()).collect();
+    assert_eq!(names, vec!["input_valid", "output_data"]);
+}
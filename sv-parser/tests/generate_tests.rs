@@ -0,0 +1,179 @@
+//! Generate region, genvar and generate construct tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{AssignmentOp, Expression, ModuleItem, SystemVerilogParser};
+
+/// Ensure all generate fixtures parse without error.
+#[test]
+fn test_parse_all_generate_files() {
+    assert_directory_parses("generate");
+}
+
+sv_ok_tests! {
+    generate_for => "generate/generate_for.sv",
+    generate_if_case => "generate/generate_if_case.sv",
+}
+
+/// A loop inside a generate region keeps its genvar, bounds and labeled body.
+#[test]
+fn test_generate_for_structure() {
+    let result = assert_parse_ok("generate/generate_for.sv");
+    let arena = &result.module_item_arena;
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[0]) else {
+        panic!("Expected module declaration");
+    };
+    assert_eq!(items.len(), 3);
+    let ModuleItem::GenvarDeclaration { genvars, .. } = arena.get(items[1]) else {
+        panic!("Expected genvar declaration");
+    };
+    assert_eq!(genvars[0].name, "i");
+    assert_eq!(genvars[0].name_span, (84, 85));
+
+    let ModuleItem::GenerateRegion { items, .. } = arena.get(items[2]) else {
+        panic!("Expected generate region");
+    };
+    let ModuleItem::GenerateFor {
+        declares_genvar,
+        genvar,
+        genvar_span,
+        condition,
+        step_op,
+        step,
+        body,
+        ..
+    } = arena.get(items[0])
+    else {
+        panic!("Expected generate loop");
+    };
+    assert!(!declares_genvar);
+    assert_eq!(genvar, "i");
+    assert_eq!(*genvar_span, (114, 115));
+    assert!(matches!(
+        result.expr_arena.get(*condition),
+        Expression::Binary { .. }
+    ));
+    // `i++` is kept as `i += 1`
+    assert_eq!(*step_op, AssignmentOp::AddAssign);
+    assert!(matches!(result.expr_arena.get(*step), Expression::Number(n, _) if n == "1"));
+
+    let ModuleItem::GenerateBlock {
+        label,
+        items,
+        end_label,
+        ..
+    } = arena.get(*body)
+    else {
+        panic!("Expected generate block");
+    };
+    assert_eq!(label.as_ref().unwrap().name, "stage");
+    assert!(end_label.is_none());
+    assert_eq!(items.len(), 2);
+    assert!(matches!(
+        arena.get(items[1]),
+        ModuleItem::ModuleInstantiation { instance_name, .. } if instance_name == "u_dff"
+    ));
+}
+
+/// `else if` nests another generate `if` in the else branch, and a branch
+/// needn't be a block.
+#[test]
+fn test_generate_if_else_chain() {
+    let result = assert_parse_ok("generate/generate_if_case.sv");
+    let arena = &result.module_item_arena;
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[0]) else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::GenerateIf {
+        then_item,
+        else_item: Some(else_item),
+        ..
+    } = arena.get(items[1])
+    else {
+        panic!("Expected generate if with an else branch");
+    };
+    assert!(matches!(
+        arena.get(*then_item),
+        ModuleItem::GenerateBlock { label: Some(label), .. } if label.name == "gen_add"
+    ));
+    let ModuleItem::GenerateIf {
+        then_item,
+        else_item: Some(last),
+        ..
+    } = arena.get(*else_item)
+    else {
+        panic!("Expected else if");
+    };
+    assert!(matches!(
+        arena.get(*then_item),
+        ModuleItem::GenerateBlock { label: Some(label), .. } if label.name == "gen_sub"
+    ));
+    assert!(matches!(arena.get(*last), ModuleItem::Assignment { .. }));
+}
+
+/// A generate `case` item lists its values, and `default` lists none.
+#[test]
+fn test_generate_case_items() {
+    let result = assert_parse_ok("generate/generate_if_case.sv");
+    let arena = &result.module_item_arena;
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[0]) else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::GenerateCase { items, .. } = arena.get(items[2]) else {
+        panic!("Expected generate case");
+    };
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].values.len(), 2);
+    assert!(items[1].values.is_empty());
+    assert!(matches!(
+        arena.get(items[1].body),
+        ModuleItem::ElaborationTask { .. }
+    ));
+}
+
+/// A loop can declare its genvar, and its block can be named before `begin`.
+#[test]
+fn test_generate_for_declares_genvar() {
+    let result = assert_parse_ok("generate/generate_if_case.sv");
+    let arena = &result.module_item_arena;
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[0]) else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::GenerateFor {
+        declares_genvar,
+        genvar_span,
+        step_op,
+        body,
+        ..
+    } = arena.get(items[3])
+    else {
+        panic!("Expected generate loop");
+    };
+    assert!(declares_genvar);
+    assert_eq!(*genvar_span, (433, 434));
+    assert_eq!(*step_op, AssignmentOp::AddAssign);
+    let ModuleItem::GenerateBlock {
+        label: Some(label), ..
+    } = arena.get(*body)
+    else {
+        panic!("Expected labeled generate block");
+    };
+    assert_eq!(label.name, "gen_lane");
+    assert_eq!(label.span, (455, 463));
+}
+
+/// A generate loop has to step the genvar it starts.
+#[test]
+fn test_generate_for_steps_its_genvar() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "module top;\n    for (genvar i = 0; i < 4; j++) begin\n    end\nendmodule\n";
+    assert!(parser.parse_content(content).is_err());
+}
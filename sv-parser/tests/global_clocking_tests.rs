@@ -4,7 +4,7 @@
 
 use std::collections::HashMap;
 use std::path::Path;
-use sv_parser::{ModuleItem, SystemVerilogParser};
+use sv_parser::{Expression, ModuleItem, SystemVerilogParser};
 
 #[test]
 fn test_steady_gclk_function() {
@@ -194,3 +194,64 @@ fn test_global_clocking_without_identifier() {
         panic!("Expected module declaration");
     }
 }
+
+#[test]
+fn test_default_clocking_and_disable_iff() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = std::fs::read_to_string(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/default_clocking.sv"),
+    )
+    .unwrap();
+
+    let result = parser.parse_content(&content).unwrap();
+
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let items: Vec<_> = items
+        .iter()
+        .map(|&item_ref| result.module_item_arena.get(item_ref))
+        .collect();
+
+    assert!(items.iter().any(|item| matches!(
+        item,
+        ModuleItem::DefaultClocking { name, .. } if name == "cb"
+    )));
+    let Some(ModuleItem::DefaultDisableIff { condition, .. }) = items
+        .iter()
+        .find(|item| matches!(item, ModuleItem::DefaultDisableIff { .. }))
+    else {
+        panic!("Expected default disable iff");
+    };
+    assert!(matches!(
+        result.expr_arena.get(*condition),
+        Expression::Identifier(name, _) if name == "rst"
+    ));
+
+    let assertions: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ConcurrentAssertion {
+                clocking_event,
+                disable_iff,
+                ..
+            } => Some((*clocking_event, *disable_iff)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(assertions.len(), 2);
+    assert_eq!(assertions[0], (None, None));
+
+    let (Some(clock), Some(disable)) = assertions[1] else {
+        panic!("Expected explicit clock and disable iff");
+    };
+    assert!(matches!(
+        result.expr_arena.get(clock),
+        Expression::Identifier(text, _) if text == "@(posedge fast_clk)"
+    ));
+    assert!(matches!(
+        result.expr_arena.get(disable),
+        Expression::Identifier(name, _) if name == "b"
+    ));
+}
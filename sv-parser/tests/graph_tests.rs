@@ -0,0 +1,207 @@
+//! Module instantiation graph tests.
+
+use std::collections::HashMap;
+use std::path::Path;
+use sv_parser::{GraphOptions, ModuleGraph, ParameterOverride, SystemVerilogParser};
+
+const DESIGN: &str = r#"
+module top;
+    alu u_alu0(.a(x));
+    alu u_alu1(.a(x));
+    DFFX1 u_ff(.d(x));
+endmodule
+module alu(input a);
+    adder u_add(.a(a));
+endmodule
+module adder(input a);
+    AND2 u_and(.a(a));
+endmodule
+"#;
+
+fn design_graph() -> ModuleGraph {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(DESIGN).unwrap();
+    let mut graph = ModuleGraph::new();
+    graph.add_source_unit(Path::new("design.sv"), &ast);
+    graph
+}
+
+#[test]
+fn test_graph_records_instances_and_tops() {
+    let graph = design_graph();
+    assert_eq!(graph.top_modules(), vec!["top"]);
+    assert_eq!(graph.module("top").unwrap().instances.len(), 3);
+    assert!(graph.is_library_cell("AND2"));
+    assert!(!graph.is_library_cell("alu"));
+}
+
+#[test]
+fn test_dot_output() {
+    let dot = design_graph().to_dot(&GraphOptions::default());
+    assert_eq!(
+        dot,
+        r#"digraph modules {
+    node [shape=box];
+    "AND2" [style=dashed];
+    "DFFX1" [style=dashed];
+    "adder";
+    "alu";
+    "top";
+    "adder" -> "AND2" [label="u_and"];
+    "alu" -> "adder" [label="u_add"];
+    "top" -> "DFFX1" [label="u_ff"];
+    "top" -> "alu" [label="u_alu0, u_alu1"];
+}
+"#
+    );
+}
+
+#[test]
+fn test_depth_limit_and_collapsed_cells() {
+    let options = GraphOptions {
+        collapse_library_cells: true,
+        max_depth: Some(1),
+    };
+    let dot = design_graph().to_dot(&options);
+    assert!(dot.contains("\"(library cells)\" [style=dashed];"));
+    assert!(dot.contains("\"top\" -> \"(library cells)\" [label=\"u_ff\"];"));
+    // adder is two levels below top
+    assert!(!dot.contains("adder"));
+    assert!(!dot.contains("DFFX1"));
+
+    let json: serde_json::Value = serde_json::from_str(&design_graph().to_json(&options)).unwrap();
+    assert_eq!(json["top"], serde_json::json!(["top"]));
+    let names: Vec<&str> = json["modules"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|module| module["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["(library cells)", "alu", "top"]);
+    assert_eq!(json["modules"][2]["file"], "design.sv");
+}
+
+#[test]
+fn test_parameter_overrides_reach_the_graph() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let mut ast = parser
+        .parse_content(
+            r#"
+module top;
+    parameter WIDTH = 8;
+    localparam MSB = WIDTH - 1;
+    alu u_alu(.a(x));
+endmodule
+"#,
+        )
+        .unwrap();
+
+    let width = ParameterOverride::parse("top.WIDTH=64").unwrap();
+    assert_eq!(width.apply(&mut ast), Ok(true));
+    let elsewhere = ParameterOverride::parse("alu.WIDTH=4").unwrap();
+    assert_eq!(elsewhere.apply(&mut ast), Ok(false));
+    let local = ParameterOverride::parse("top.MSB=3").unwrap();
+    assert_eq!(
+        local.apply(&mut ast),
+        Err("Cannot override top.MSB: it is a localparam".to_string())
+    );
+    let missing = ParameterOverride::parse("top.DEPTH=3").unwrap();
+    assert_eq!(
+        missing.apply(&mut ast),
+        Err("Module 'top' has no parameter 'DEPTH'".to_string())
+    );
+
+    let mut graph = ModuleGraph::new();
+    graph.add_source_unit(Path::new("top.sv"), &ast);
+    let parameters = &graph.module("top").unwrap().parameters;
+    assert_eq!(parameters["WIDTH"], "64");
+    assert_eq!(parameters["MSB"], "63");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&graph.to_json(&GraphOptions::default())).unwrap();
+    assert_eq!(json["modules"][1]["parameters"]["WIDTH"], "64");
+}
+
+const PACKAGES: &str = r#"
+package types_pkg;
+    typedef logic [7:0] byte_t;
+endpackage
+package bus_pkg;
+    import types_pkg::*;
+endpackage
+import bus_pkg::*;
+module top;
+    import uvm_pkg::*;
+    sub u_sub();
+endmodule
+module sub;
+    import types_pkg::byte_t;
+endmodule
+"#;
+
+fn package_graph() -> ModuleGraph {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(PACKAGES).unwrap();
+    let mut graph = ModuleGraph::new();
+    graph.add_source_unit(Path::new("design.sv"), &ast);
+    graph
+}
+
+/// Packages are nodes too, with an edge from each module or package importing
+/// them; an import outside a design unit counts for the units after it
+#[test]
+fn test_dot_output_with_packages() {
+    let graph = package_graph();
+    assert_eq!(
+        graph.module("top").unwrap().imports,
+        vec!["bus_pkg", "uvm_pkg"]
+    );
+    assert_eq!(graph.package("bus_pkg").unwrap().imports, vec!["types_pkg"]);
+    assert!(graph.package("uvm_pkg").is_none());
+
+    let dot = graph.to_dot(&GraphOptions::default());
+    assert_eq!(
+        dot,
+        r#"digraph modules {
+    node [shape=box];
+    "sub";
+    "top";
+    "bus_pkg" [shape=folder];
+    "types_pkg" [shape=folder];
+    "uvm_pkg" [shape=folder, style=dashed];
+    "top" -> "sub" [label="u_sub"];
+    "sub" -> "bus_pkg" [style=dotted];
+    "sub" -> "types_pkg" [style=dotted];
+    "top" -> "bus_pkg" [style=dotted];
+    "top" -> "uvm_pkg" [style=dotted];
+    "bus_pkg" -> "types_pkg" [style=dotted];
+}
+"#
+    );
+}
+
+#[test]
+fn test_json_output_with_packages() {
+    let options = GraphOptions {
+        max_depth: Some(0),
+        ..GraphOptions::default()
+    };
+    let json: serde_json::Value = serde_json::from_str(&package_graph().to_json(&options)).unwrap();
+    assert_eq!(
+        json["modules"],
+        serde_json::json!([{
+            "name": "top",
+            "file": "design.sv",
+            "instances": [],
+            "imports": ["bus_pkg", "uvm_pkg"]
+        }])
+    );
+    assert_eq!(
+        json["packages"],
+        serde_json::json!([
+            {"name": "bus_pkg", "file": "design.sv", "imports": ["types_pkg"]},
+            {"name": "types_pkg", "file": "design.sv"},
+            {"name": "uvm_pkg", "file": null}
+        ])
+    );
+}
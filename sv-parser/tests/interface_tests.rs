@@ -0,0 +1,103 @@
+//! Interface declaration, modport and interface port tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{ModuleItem, PortDirection, SystemVerilogParser};
+
+/// Ensure all interface fixtures parse without error.
+#[test]
+fn test_parse_all_interface_files() {
+    assert_directory_parses("interfaces");
+}
+
+sv_ok_tests! {
+    interface_empty => "interfaces/empty_interface.sv",
+    interface_bus => "interfaces/bus_interface.sv",
+}
+
+/// An interface holds its ports, signals and modports, with the direction of
+/// a modport signal carried over from the one before it.
+#[test]
+fn test_interface_structure() {
+    let result = assert_parse_ok("interfaces/bus_interface.sv");
+    assert_eq!(result.items.len(), 4);
+
+    let ModuleItem::InterfaceDeclaration {
+        name,
+        name_span,
+        ports,
+        items,
+        ..
+    } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected interface declaration");
+    };
+    assert_eq!(name, "bus_if");
+    assert_eq!(*name_span, (10, 16));
+    assert_eq!(ports.len(), 1);
+    assert_eq!(ports[0].name, "clk");
+    assert_eq!(ports[0].direction, Some(PortDirection::Input));
+    assert_eq!(items.len(), 5);
+
+    let modports: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match result.module_item_arena.get(*item_ref) {
+            ModuleItem::ModportDeclaration { modports, .. } => Some(modports),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    let names: Vec<&str> = modports.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(names, vec!["master", "slave", "monitor"]);
+
+    let master: Vec<(&str, &PortDirection)> = modports[0]
+        .ports
+        .iter()
+        .map(|port| (port.name.as_str(), &port.direction))
+        .collect();
+    assert_eq!(
+        master,
+        vec![
+            ("req", &PortDirection::Output),
+            ("data", &PortDirection::Output),
+            ("gnt", &PortDirection::Input),
+            ("clk", &PortDirection::Input),
+        ]
+    );
+}
+
+/// Modules take interfaces as ports, with or without a modport, and generically.
+#[test]
+fn test_interface_ports() {
+    let result = assert_parse_ok("interfaces/bus_interface.sv");
+
+    let ModuleItem::ModuleDeclaration { ports, .. } = result.module_item_arena.get(result.items[1])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert_eq!(ports[0].name, "bus");
+    assert_eq!(ports[0].direction, None);
+    assert_eq!(ports[0].interface.as_deref(), Some("bus_if"));
+    assert_eq!(ports[0].modport.as_deref(), Some("master"));
+    assert_eq!(ports[1].name, "rst");
+    assert_eq!(ports[1].interface, None);
+
+    let ModuleItem::ModuleDeclaration { ports, .. } = result.module_item_arena.get(result.items[2])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert_eq!(ports[0].interface.as_deref(), Some("interface"));
+    assert_eq!(ports[0].modport, None);
+}
+
+/// The first signal of a modport needs a direction.
+#[test]
+fn test_modport_without_direction() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "interface bus_if;\n    logic req;\n    modport master(req);\nendinterface\n";
+    assert!(parser.parse_content(content).is_err());
+}
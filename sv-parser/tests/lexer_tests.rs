@@ -0,0 +1,157 @@
+//! Tokenizer tests.
+
+use sv_parser::{lex, KeywordVersion, TokenKind};
+
+fn kinds_and_text(source: &str) -> Vec<(TokenKind, &str)> {
+    lex(source)
+        .into_iter()
+        .map(|token| (token.kind, token.text))
+        .collect()
+}
+
+#[test]
+fn test_lex_module_header() {
+    assert_eq!(
+        kinds_and_text("module top(input logic [7:0] a);"),
+        vec![
+            (TokenKind::Keyword, "module"),
+            (TokenKind::Identifier, "top"),
+            (TokenKind::Operator, "("),
+            (TokenKind::Keyword, "input"),
+            (TokenKind::Keyword, "logic"),
+            (TokenKind::Operator, "["),
+            (TokenKind::Number, "7"),
+            (TokenKind::Operator, ":"),
+            (TokenKind::Number, "0"),
+            (TokenKind::Operator, "]"),
+            (TokenKind::Identifier, "a"),
+            (TokenKind::Operator, ")"),
+            (TokenKind::Operator, ";"),
+        ]
+    );
+}
+
+#[test]
+fn test_lex_numbers_and_longest_operator() {
+    assert_eq!(
+        kinds_and_text("x <<<= 8'hA5 + 4'sb1x?0 - '1 * 1_000 ** 2.5;"),
+        vec![
+            (TokenKind::Identifier, "x"),
+            (TokenKind::Operator, "<<<="),
+            (TokenKind::Number, "8'hA5"),
+            (TokenKind::Operator, "+"),
+            (TokenKind::Number, "4'sb1x?0"),
+            (TokenKind::Operator, "-"),
+            (TokenKind::Number, "'1"),
+            (TokenKind::Operator, "*"),
+            (TokenKind::Number, "1_000"),
+            (TokenKind::Operator, "**"),
+            (TokenKind::Number, "2.5"),
+            (TokenKind::Operator, ";"),
+        ]
+    );
+}
+
+#[test]
+fn test_lex_skips_comments_and_keeps_char_spans() {
+    let source = "// é comment\n/* block\n */ $display(\"a \\\" b\"); `WIDTH";
+    let tokens = lex(source);
+    let kinds: Vec<_> = tokens.iter().map(|token| token.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TokenKind::SystemIdentifier,
+            TokenKind::Operator,
+            TokenKind::StringLiteral,
+            TokenKind::Operator,
+            TokenKind::Operator,
+            TokenKind::Directive,
+        ]
+    );
+
+    // Spans count characters, not bytes, so the accented comment doesn't shift them
+    let chars: Vec<char> = source.chars().collect();
+    for token in &tokens {
+        let text: String = chars[token.span.0..token.span.1].iter().collect();
+        assert_eq!(text, token.text);
+    }
+}
+
+#[test]
+fn test_lex_reports_unterminated_input() {
+    let tokens = lex("a \"open\nb /* never closed");
+    let kinds: Vec<_> = tokens
+        .iter()
+        .map(|token| (token.kind, token.text))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (TokenKind::Identifier, "a"),
+            (TokenKind::Unknown, "\"open\n"),
+            (TokenKind::Identifier, "b"),
+            (TokenKind::Unknown, "/* never closed"),
+        ]
+    );
+}
+
+#[test]
+fn test_lex_keyword_versions() {
+    let tokens = lex("logic `begin_keywords \"1364-2005\" logic signed `end_keywords logic");
+    let words: Vec<_> = tokens
+        .iter()
+        .filter(|token| token.text == "logic" || token.text == "signed")
+        .map(|token| (token.kind, token.version))
+        .collect();
+    assert_eq!(
+        words,
+        vec![
+            (TokenKind::Keyword, KeywordVersion::V1800_2017),
+            (TokenKind::Identifier, KeywordVersion::V1364_2005),
+            (TokenKind::Keyword, KeywordVersion::V1364_2005),
+            (TokenKind::Keyword, KeywordVersion::V1800_2017),
+        ]
+    );
+
+    assert_eq!(
+        KeywordVersion::from_specifier("1800-2009"),
+        Some(KeywordVersion::V1800_2009)
+    );
+    assert_eq!(KeywordVersion::from_specifier("2099"), None);
+    assert!(!KeywordVersion::V1800_2005.is_keyword("soft"));
+    assert!(KeywordVersion::V1800_2012.is_keyword("soft"));
+}
+
+#[test]
+fn test_lex_escaped_identifier() {
+    let tokens = lex("\\bus+index  \\module ;");
+    assert_eq!(tokens[0].kind, TokenKind::Identifier);
+    assert_eq!(tokens[0].text, "\\bus+index");
+    assert_eq!(tokens[0].name(), "bus+index");
+    assert_eq!(tokens[1].kind, TokenKind::Identifier);
+    assert_eq!(tokens[1].name(), "module");
+    assert_eq!(tokens[2].text, ";");
+}
+
+#[test]
+fn test_lex_member_names_are_identifiers() {
+    assert_eq!(
+        kinds_and_text("item.new(); pkg::or"),
+        vec![
+            (TokenKind::Identifier, "item"),
+            (TokenKind::Operator, "."),
+            (TokenKind::Identifier, "new"),
+            (TokenKind::Operator, "("),
+            (TokenKind::Operator, ")"),
+            (TokenKind::Operator, ";"),
+            (TokenKind::Identifier, "pkg"),
+            (TokenKind::Operator, "::"),
+            (TokenKind::Identifier, "or"),
+        ]
+    );
+    // Only the word right after the `.` is a name
+    assert_eq!(
+        kinds_and_text("bits.and new")[3],
+        (TokenKind::Keyword, "new")
+    );
+}
@@ -0,0 +1,160 @@
+//! Library and configuration tests.
+
+use std::collections::HashMap;
+use std::path::Path;
+use sv_parser::library::{library_name, WORK_LIBRARY};
+use sv_parser::{
+    ConfigRule, DesignLibraries, LibrarySelection, ModuleItem, SourceUnit, SystemVerilogParser,
+};
+
+const DESIGN: &str = r#"
+module top;
+    alu u_alu0();
+    alu u_alu1();
+    mult u_mult();
+endmodule
+
+module alu;
+    adder u_add();
+endmodule
+
+config fast_cfg;
+    design work.top;
+    default liblist work fast;
+    instance top.u_alu1.u_add use slow.adder;
+    cell mult liblist slow;
+endconfig
+
+config broken_cfg;
+    design work.missing work.top;
+    default liblist work nowhere;
+    instance top.u_alu0 use fast.alu;
+endconfig
+"#;
+
+const FAST: &str = r#"
+module adder;
+endmodule
+"#;
+
+const SLOW: &str = r#"
+module adder;
+endmodule
+
+module mult;
+    adder u_add();
+endmodule
+"#;
+
+fn parse(content: &str) -> SourceUnit {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    parser.parse_content(content).unwrap()
+}
+
+fn libraries() -> DesignLibraries {
+    let mut libraries = DesignLibraries::new();
+    libraries.add_source_unit(WORK_LIBRARY, Path::new("design.sv"), &parse(DESIGN));
+    libraries.add_source_unit("fast", Path::new("fast.v"), &parse(FAST));
+    libraries.add_source_unit("slow", Path::new("slow.v"), &parse(SLOW));
+    libraries
+}
+
+#[test]
+fn test_parse_config_declaration() {
+    let ast = parse(DESIGN);
+    let config = ast
+        .items
+        .iter()
+        .map(|item_ref| ast.module_item_arena.get(*item_ref))
+        .find(|item| matches!(item, ModuleItem::ConfigDeclaration { .. }))
+        .expect("Expected a config declaration");
+    let ModuleItem::ConfigDeclaration {
+        name,
+        design,
+        rules,
+        ..
+    } = config
+    else {
+        unreachable!();
+    };
+
+    assert_eq!(name, "fast_cfg");
+    assert_eq!(design.len(), 1);
+    assert_eq!(design[0].library.as_deref(), Some("work"));
+    assert_eq!(design[0].cell, "top");
+    assert!(matches!(
+        &rules[0],
+        ConfigRule::Default { liblist, .. } if liblist == &["work", "fast"]
+    ));
+    assert!(matches!(
+        &rules[1],
+        ConfigRule::Instance {
+            path,
+            selection: LibrarySelection::Use(cell),
+            ..
+        } if path == "top.u_alu1.u_add" && cell.library.as_deref() == Some("slow") && cell.cell == "adder"
+    ));
+    assert!(matches!(
+        &rules[2],
+        ConfigRule::Cell {
+            cell,
+            selection: LibrarySelection::Liblist(liblist),
+            ..
+        } if cell.library.is_none() && cell.cell == "mult" && liblist == &["slow"]
+    ));
+}
+
+#[test]
+fn test_config_selects_implementations() {
+    let libraries = libraries();
+    assert_eq!(libraries.libraries(), ["work", "fast", "slow"]);
+
+    let hierarchy = libraries.elaborate("fast_cfg").unwrap();
+    assert!(hierarchy.diagnostics.is_empty());
+    let bound: Vec<String> = hierarchy
+        .bindings
+        .iter()
+        .map(|binding| format!("{} {}.{}", binding.path, binding.library, binding.cell))
+        .collect();
+    assert_eq!(
+        bound,
+        vec![
+            "top work.top",
+            "top.u_alu0 work.alu",
+            "top.u_alu0.u_add fast.adder",
+            "top.u_alu1 work.alu",
+            "top.u_alu1.u_add slow.adder",
+            "top.u_mult slow.mult",
+            // The liblist of the cell rule applies below the cell too
+            "top.u_mult.u_add slow.adder",
+        ]
+    );
+    assert!(libraries.elaborate("no_such_cfg").is_none());
+}
+
+#[test]
+fn test_config_diagnostics() {
+    let libraries = libraries();
+    let hierarchy = libraries.elaborate("broken_cfg").unwrap();
+    let messages: Vec<&str> = hierarchy
+        .diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Unknown library 'nowhere'",
+            "Cell 'fast.alu' is not defined in library 'fast'",
+            "Design cell 'work.missing' is not defined",
+        ]
+    );
+    // The rest of the design still elaborates
+    assert_eq!(hierarchy.binding("top").unwrap().library, "work");
+    assert!(hierarchy.binding("top.u_alu0").is_none());
+}
+
+#[test]
+fn test_library_name_is_file_stem() {
+    assert_eq!(library_name(Path::new("vendor/cells.v")), "cells");
+}
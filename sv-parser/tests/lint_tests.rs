@@ -0,0 +1,1338 @@
+//! Lint rule engine tests
+
+use std::collections::HashMap;
+use std::path::Path;
+use sv_parser::{
+    apply_edits, LintDiagnostic, LintEngine, LintFile, LintRule, LintSeverity, ModuleItem,
+    ParameterOverride, ProceduralBlockType, ProjectConfig, SourceUnit, Statement,
+    SystemVerilogParser,
+};
+
+fn lint(content: &str) -> Vec<LintDiagnostic> {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    LintEngine::new().run(&ast)
+}
+
+#[test]
+fn test_blocking_assignment_in_always_ff() {
+    let diagnostics = lint(
+        r#"
+module top(input clk);
+    logic q;
+    always_ff @(posedge clk) q = 1;
+endmodule
+"#,
+    );
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "blocking-in-always-ff");
+}
+
+#[test]
+fn test_blocking_assignment_in_always_comb_is_clean() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic q;
+    always_comb q = 1;
+endmodule
+"#,
+    );
+
+    assert!(diagnostics.is_empty(), "Unexpected: {:?}", diagnostics);
+}
+
+#[test]
+fn test_duplicate_declaration() {
+    let content = r#"
+module top();
+    logic a;
+    wire a;
+endmodule
+"#;
+    let diagnostics = lint(content);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "duplicate-declaration");
+    assert_eq!(
+        &content[diagnostics[0].span.0..diagnostics[0].span.1],
+        "a",
+        "Diagnostic should point at the second declaration's name"
+    );
+    assert!(diagnostics[0].span.0 > content.find("wire").unwrap());
+}
+
+#[test]
+fn test_duplicate_enum_member() {
+    let content = r#"
+module top();
+    typedef enum {IDLE, BUSY} state_t;
+    enum {OFF, IDLE} mode;
+    logic state_t;
+endmodule
+"#;
+    let diagnostics = lint(content);
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "duplicate-declaration",
+                "'IDLE' is already declared in this module"
+            ),
+            (
+                "duplicate-declaration",
+                "'state_t' is already declared in this module"
+            ),
+        ]
+    );
+    assert!(diagnostics[0].span.0 > content.find("OFF").unwrap());
+}
+
+#[test]
+fn test_port_redeclared_as_net_is_clean() {
+    let diagnostics = lint(
+        r#"
+module top(a);
+    input wire a;
+    wire a;
+endmodule
+"#,
+    );
+
+    assert!(diagnostics.is_empty(), "Unexpected: {:?}", diagnostics);
+}
+
+#[test]
+fn test_rule_selection() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser
+        .parse_content(
+            r#"
+module top(input clk);
+    logic q;
+    logic q;
+    always_ff @(posedge clk) q = 1;
+endmodule
+"#,
+        )
+        .unwrap();
+
+    let engine = LintEngine::with_rules(&["duplicate-declaration".to_string()]).unwrap();
+    let diagnostics = engine.run(&ast);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "duplicate-declaration");
+}
+
+#[test]
+fn test_unknown_rule_is_rejected() {
+    let result = LintEngine::with_rules(&["no-such-rule".to_string()]);
+    assert_eq!(
+        result.err(),
+        Some("Unknown lint rule: no-such-rule".to_string())
+    );
+}
+
+#[test]
+fn test_comparison_wider_than_signal_is_constant() {
+    let diagnostics = lint(
+        r#"
+module top(input logic [1:0] sel);
+    logic y, a, b;
+    initial begin
+        y = (sel == 5) ? a : b;
+        y = (4 > sel) ? a : b;
+        y = (sel == 3) ? a : b;
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(diagnostics.len(), 2, "Unexpected: {:?}", diagnostics);
+    assert!(diagnostics.iter().all(|d| d.rule == "constant-condition"));
+    assert_eq!(
+        diagnostics[0].message,
+        "Condition is always false: 'sel' is 2 bits wide and can never hold 5"
+    );
+    assert!(diagnostics[1]
+        .message
+        .starts_with("Condition is always true"));
+}
+
+#[test]
+fn test_folded_case_expression_is_constant() {
+    let diagnostics = lint(
+        r#"
+module top();
+    parameter MSB = 3;
+    logic [MSB:0] count;
+    logic y;
+    always_comb begin
+        case (count != 16)
+            1: y = 1;
+        endcase
+        case (2 > 3)
+            1: y = 0;
+        endcase
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(diagnostics.len(), 2, "Unexpected: {:?}", diagnostics);
+    assert_eq!(
+        diagnostics[0].message,
+        "Case expression is always true: 'count' is 4 bits wide and can never hold 16"
+    );
+    assert_eq!(
+        diagnostics[1].message,
+        "Case expression is always false (folds to 1'd0)"
+    );
+}
+
+#[test]
+fn test_reverse_case_and_signal_conditions_are_clean() {
+    let diagnostics = lint(
+        r#"
+module top(input logic [7:0] data, input logic en);
+    logic y;
+    assign y = (data == 200) ? 1 : 0;
+    always_comb begin
+        case (1'b1)
+            en: y = 1;
+        endcase
+    end
+endmodule
+"#,
+    );
+
+    assert!(diagnostics.is_empty(), "Unexpected: {:?}", diagnostics);
+}
+
+#[test]
+fn test_parameter_override_changes_signal_widths() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let mut ast = parser
+        .parse_content(
+            r#"
+module top();
+    parameter MSB = 3;
+    logic [MSB:0] count;
+    logic y;
+    assign y = (count == 200) ? 1 : 0;
+endmodule
+"#,
+        )
+        .unwrap();
+    assert_eq!(LintEngine::new().run(&ast).len(), 1);
+
+    let msb = ParameterOverride::parse("top.MSB=7").unwrap();
+    assert_eq!(msb.apply(&mut ast), Ok(true));
+    let diagnostics = LintEngine::new().run(&ast);
+    assert!(diagnostics.is_empty(), "Unexpected: {:?}", diagnostics);
+}
+
+#[test]
+fn test_findings_are_ordered_by_position_across_rules() {
+    let diagnostics = lint(
+        r#"
+module top(input clk);
+    logic q;
+    always_ff @(posedge clk) q = 1;
+    logic q;
+endmodule
+"#,
+    );
+
+    let rules: Vec<_> = diagnostics.iter().map(|d| d.rule).collect();
+    assert_eq!(
+        rules,
+        vec!["blocking-in-always-ff", "duplicate-declaration"]
+    );
+    assert!(diagnostics.windows(2).all(|w| w[0].span <= w[1].span));
+
+    // Selecting the rules in the other order gives the same output
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser
+        .parse_content("module top(input clk);\n    logic q;\n    always_ff @(posedge clk) q = 1;\n    logic q;\nendmodule\n")
+        .unwrap();
+    let engine = LintEngine::with_rules(&[
+        "duplicate-declaration".to_string(),
+        "blocking-in-always-ff".to_string(),
+    ])
+    .unwrap();
+    let rules: Vec<_> = engine.run(&ast).iter().map(|d| d.rule).collect();
+    assert_eq!(
+        rules,
+        vec!["blocking-in-always-ff", "duplicate-declaration"]
+    );
+}
+
+#[test]
+fn test_invalid_dimensions() {
+    let diagnostics = lint(
+        r#"
+module top;
+    parameter DEPTH = 0;
+    logic [4'bx000:0] b;
+    logic [7:0] mem [DEPTH];
+    logic [7:0] rom [0:15];
+    logic [7:0] fifo [4];
+endmodule
+"#,
+    );
+
+    let messages: Vec<_> = diagnostics
+        .iter()
+        .filter(|d| d.rule == "invalid-dimension")
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Range bound 4'bx000 has x or z bits",
+            "Array size DEPTH (0) must be positive",
+        ]
+    );
+}
+
+#[test]
+fn test_ascending_range_only_when_named() {
+    let content = r#"
+module top(input [0:7] a);
+    logic [7:0] b;
+    logic [7:0] mem [0:3];
+endmodule
+"#;
+    assert!(lint(content).is_empty());
+
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    let engine = LintEngine::with_rules(&["ascending-range".to_string()]).unwrap();
+    let diagnostics = engine.run(&ast);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Ascending range [0:7]; declare it as [7:0]"
+    );
+}
+
+#[test]
+fn test_naming_rules_only_when_configured() {
+    let content = r#"
+module Top(input clock, input rst_n, input [7:0] Data);
+    parameter width = 8;
+endmodule
+"#;
+    assert!(lint(content).is_empty());
+
+    let config = ProjectConfig::parse(
+        r#"
+[lint.naming]
+parameter = "^[A-Z][A-Z0-9_]*$"
+clock = "^clk(_[a-z0-9_]+)?$"
+"#,
+    )
+    .unwrap();
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    let engine = LintEngine::configured(&config.lint, None).unwrap();
+    let diagnostics = engine.run(&ast);
+
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|d| (d.rule, &content[d.span.0..d.span.1]))
+        .collect();
+    assert_eq!(
+        found,
+        vec![("naming-clock", "clock"), ("naming-parameter", "width")]
+    );
+    assert_eq!(
+        diagnostics[0].message,
+        "Clock name 'clock' does not match the naming convention ^clk(_[a-z0-9_]+)?$"
+    );
+}
+
+#[test]
+fn test_naming_rule_named_uses_default_pattern() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser
+        .parse_content("module Top(input a);\nendmodule\nclass my_class;\nendclass\n")
+        .unwrap();
+    let engine =
+        LintEngine::with_rules(&["naming-module".to_string(), "naming-class".to_string()]).unwrap();
+    let diagnostics = engine.run(&ast);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "naming-module");
+    assert_eq!(
+        diagnostics[0].message,
+        "Module name 'Top' does not match the naming convention ^[a-z][a-z0-9_]*$"
+    );
+}
+
+#[test]
+fn test_invalid_naming_config() {
+    let config = ProjectConfig::parse("[lint.naming]\nsignal = \"[a-z\"\n").unwrap();
+    let err = LintEngine::configured(&config.lint, None).err().unwrap();
+    assert!(
+        err.starts_with("Invalid naming pattern for naming-signal"),
+        "{}",
+        err
+    );
+
+    assert!(ProjectConfig::parse("[lint.naming]\nwire = \"^w_\"\n").is_err());
+}
+
+fn lint_style(content: &str, config: &str) -> Vec<LintDiagnostic> {
+    let config = ProjectConfig::parse(config).unwrap();
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    LintEngine::configured(&config.lint, None)
+        .unwrap()
+        .run_with_text(&ast, content)
+}
+
+#[test]
+fn test_style_rules_only_when_configured() {
+    let content = "module top;\t\n\tlogic a;  \nendmodule";
+    assert!(lint_style(content, "").is_empty());
+
+    let diagnostics = lint_style(
+        content,
+        "[lint.style]\ntab_width = 4\ntrailing_whitespace = true\nfinal_newline = true\n",
+    );
+    let found: Vec<_> = diagnostics.iter().map(|d| (d.rule, d.span)).collect();
+    assert_eq!(
+        found,
+        vec![
+            ("no-tabs", (11, 12)),
+            ("trailing-whitespace", (11, 12)),
+            ("no-tabs", (13, 14)),
+            ("trailing-whitespace", (22, 24)),
+            ("final-newline", (34, 34)),
+        ]
+    );
+}
+
+#[test]
+fn test_line_length() {
+    let content = "module top;\n    logic [7:0] a_signal_with_a_long_name;\nendmodule\n";
+    let diagnostics = lint_style(content, "[lint.style]\nmax_line_length = 30\n");
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "line-length");
+    assert_eq!(
+        diagnostics[0].message,
+        "Line is 42 characters long; the maximum is 30"
+    );
+    assert_eq!(diagnostics[0].span, (42, 54));
+    assert!(diagnostics[0].fix.is_none());
+}
+
+#[test]
+fn test_style_fixes() {
+    let content = "module top;  \r\n  logic\tb;\n\tlogic c;\nendmodule";
+    let diagnostics = lint_style(
+        content,
+        "[lint.style]\ntab_width = 4\ntrailing_whitespace = true\nfinal_newline = true\n",
+    );
+    let fixes: Vec<_> = diagnostics.iter().filter_map(|d| d.fix.clone()).collect();
+    let (fixed, applied) = apply_edits(content, &fixes);
+
+    assert_eq!(applied.len(), 4);
+    assert_eq!(
+        fixed,
+        "module top;\r\n  logic b;\n    logic c;\nendmodule\n"
+    );
+}
+
+#[test]
+fn test_overlapping_style_fixes() {
+    let content = "module m;\nlogic a;\t \nendmodule\n";
+    let diagnostics = lint_style(
+        content,
+        "[lint.style]\ntab_width = 4\ntrailing_whitespace = true\n",
+    );
+    assert_eq!(diagnostics.len(), 2);
+    let fixes: Vec<_> = diagnostics.iter().filter_map(|d| d.fix.clone()).collect();
+
+    // Both fixes rewrite the tab, so only the one starting first is applied
+    // and the trailing whitespace is left for another run
+    let (fixed, applied) = apply_edits(content, &fixes);
+    assert_eq!(applied.len(), 1);
+    assert_eq!(diagnostics[applied[0]].rule, "no-tabs");
+    assert_eq!(fixed, "module m;\nlogic a;     \nendmodule\n");
+
+    let diagnostics = lint_style(
+        &fixed,
+        "[lint.style]\ntab_width = 4\ntrailing_whitespace = true\n",
+    );
+    let fixes: Vec<_> = diagnostics.iter().filter_map(|d| d.fix.clone()).collect();
+    let (fixed, applied) = apply_edits(&fixed, &fixes);
+    assert_eq!(applied, [0]);
+    assert_eq!(fixed, "module m;\nlogic a;\nendmodule\n");
+}
+
+#[test]
+fn test_file_header() {
+    let config = ProjectConfig::parse(
+        "[lint.file]\nheader = '// Copyright \\(c\\) \\d{4}'\nheader_template = '// Copyright (c) 2026 Acme'\n",
+    )
+    .unwrap();
+    let engine = LintEngine::configured(&config.lint, None).unwrap();
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+
+    let content = "// Copyright (c) 2026 Acme\nmodule top;\nendmodule\n";
+    let ast = parser.parse_content(content).unwrap();
+    assert!(engine
+        .run_file(Path::new("top.sv"), &ast, content)
+        .is_empty());
+
+    let content = "module top;\nendmodule\n";
+    let ast = parser.parse_content(content).unwrap();
+    let diagnostics = engine.run_file(Path::new("top.sv"), &ast, content);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "file-header");
+    assert_eq!(diagnostics[0].span, (0, 11));
+    let (fixed, _) = apply_edits(content, &[diagnostics[0].fix.clone().unwrap()]);
+    assert_eq!(
+        fixed,
+        "// Copyright (c) 2026 Acme\nmodule top;\nendmodule\n"
+    );
+}
+
+#[test]
+fn test_file_name_matches_unit() {
+    let config = ProjectConfig::parse("[lint.file]\nname_matches_unit = true\n").unwrap();
+    let engine = LintEngine::configured(&config.lint, None).unwrap();
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+
+    let content = "module fifo;\nendmodule\n";
+    let ast = parser.parse_content(content).unwrap();
+    assert!(engine
+        .run_file(Path::new("rtl/fifo.sv"), &ast, content)
+        .is_empty());
+
+    let diagnostics = engine.run_file(Path::new("rtl/buffer.sv"), &ast, content);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].rule, "file-name");
+    assert_eq!(
+        diagnostics[0].message,
+        "File 'buffer.sv' defines module 'fifo'; rename it to 'fifo.sv'"
+    );
+    assert_eq!(diagnostics[0].span, (7, 11));
+
+    // Files defining several units are not named after any of them
+    let content = "module fifo;\nendmodule\nmodule fifo_ctrl;\nendmodule\n";
+    let ast = parser.parse_content(content).unwrap();
+    assert!(engine
+        .run_file(Path::new("rtl/buffer.sv"), &ast, content)
+        .is_empty());
+}
+
+fn lint_dead_code(content: &str) -> Vec<sv_parser::LintDiagnostic> {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    LintEngine::with_rules(&["dead-code".to_string()])
+        .unwrap()
+        .run(&ast)
+}
+
+#[test]
+fn test_dead_code_after_finish_and_disable() {
+    let content = r#"
+module top;
+    logic a;
+    initial begin : main
+        a = 0;
+        begin : inner
+            a = 1;
+            disable inner;
+            a = 2;
+        end
+        a = 3;
+        $finish;
+        a = 4;
+        a = 5;
+    end
+    initial begin
+        disable other;
+        a = 6;
+    end
+endmodule
+"#;
+    assert!(lint(content).is_empty());
+
+    let diagnostics = lint_dead_code(content);
+    let found: Vec<_> = diagnostics
+        .iter()
+        .map(|d| {
+            (
+                d.message.as_str(),
+                content[d.span.0..d.span.1].trim().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                "Statement is never reached after disable inner",
+                "a = 2;".to_string()
+            ),
+            (
+                "Statement is never reached after $finish",
+                "a = 4;".to_string()
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_dead_case_matches_arms() {
+    let diagnostics = lint_dead_code(
+        r#"
+module top();
+logic [7:0] x;
+initial begin
+    case (v) matches
+        tagged Valid .n &&& (1 > 2): x = n;
+        tagged Invalid: x = 0;
+        .*: x = 1;
+        tagged Valid .n: x = 2;
+        default: x = 3;
+    endcase
+end
+endmodule
+"#,
+    );
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Case arm is never taken: its guard is always false",
+            "Case arm is never taken: an earlier arm matches any value",
+            "Case arm is never taken: an earlier arm matches any value",
+        ]
+    );
+}
+
+#[test]
+fn test_missing_return() {
+    let diagnostics = lint(
+        r#"
+virtual class counter;
+    int count;
+    pure virtual function int peek();
+    function int get();
+        return count;
+    endfunction
+    function int get_by_name();
+        get_by_name = count;
+    endfunction
+    function int forgotten();
+        count = count + 1;
+    endfunction
+    function int matched(v);
+        case (v) matches
+            tagged Valid .n: return n;
+            default: count = 0;
+        endcase
+    endfunction
+    function int all_arms(v);
+        case (v) matches
+            tagged Valid .n: return n;
+            default: return 0;
+        endcase
+    endfunction
+    function void reset();
+        count = 0;
+    endfunction
+endclass
+"#,
+    );
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "Function 'counter::forgotten' can end without returning a value",
+            "Function 'counter::matched' can end without returning a value",
+        ]
+    );
+}
+
+#[test]
+fn test_missing_return_in_extern_method_body() {
+    let diagnostics = lint(
+        r#"
+class counter;
+    int count;
+    extern function int get();
+    extern function int forgotten();
+endclass
+
+function int counter::get();
+    return count;
+endfunction
+
+function int counter::forgotten();
+    count = count + 1;
+endfunction
+"#,
+    );
+
+    let messages: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec!["Function 'counter::forgotten' can end without returning a value"]
+    );
+}
+
+#[test]
+fn test_dead_code_after_return() {
+    let diagnostics = lint_dead_code(
+        r#"
+class counter;
+    int count;
+    function int next();
+        count = count + 1;
+        return count;
+        count = 0;
+    endfunction
+endclass
+"#,
+    );
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(
+        diagnostics[0].message,
+        "Statement is never reached after return"
+    );
+}
+
+fn findings(diagnostics: &[sv_parser::LintDiagnostic]) -> Vec<(&str, &str)> {
+    diagnostics
+        .iter()
+        .map(|d| (d.rule, d.message.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_casez_items_never_taken_and_incomplete() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic [3:0] req;
+    logic [1:0] grant;
+    always_comb begin
+        casez (req)
+            4'b1???: grant = 3;
+            4'b11??: grant = 2;
+            4'b01?1, 4'b0??0: grant = 1;
+            4'b0101: grant = 0;
+        endcase
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "case-incomplete",
+                "Case on 'req' has no default and misses values such as 4'b0001"
+            ),
+            (
+                "case-overlap",
+                "Case item is never taken: earlier items match every value it matches"
+            ),
+            (
+                "case-overlap",
+                "Case item is never taken: earlier items match every value it matches"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unique_case_items_overlap() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic [1:0] sel;
+    logic [1:0] y;
+    always_comb begin
+        unique casez (sel)
+            2'b1?: y = 1;
+            2'b?1: y = 2;
+            2'b00: y = 0;
+        endcase
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![(
+            "case-overlap",
+            "Case item overlaps an earlier item of this unique casez: both match 2'b11"
+        )]
+    );
+}
+
+#[test]
+fn test_casex_wildcards_and_style() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic [1:0] sel;
+    logic y;
+    always_comb begin
+        casex (sel)
+            2'b1x: y = 1;
+            2'b0x: y = 0;
+        endcase
+        case (sel)
+            2'b1x: y = 1;
+            2'b0x: y = 0;
+        endcase
+        casez (sel)
+            2'b1?: y = 1;
+            default: y = 0;
+        endcase
+    end
+endmodule
+"#,
+    );
+
+    // x bits are wildcards only in a casex; in a plain case they match no 0 or 1
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "no-casex",
+                "casex matches x bits of the case expression as wildcards; use casez"
+            ),
+            (
+                "case-incomplete",
+                "Case on 'sel' has no default and misses values such as 2'b00"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_case_inside_wildcards_and_ranges() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic [1:0] sel;
+    logic y;
+    always_comb begin
+        case (sel) inside
+            2'b1x: y = 1;
+            2'b0?: y = 0;
+        endcase
+        case (sel) inside
+            2'b1x: y = 1;
+            2'b11: y = 0;
+        endcase
+        case (sel) inside
+            [2'b00:2'b01]: y = 1;
+            2'b1?: y = 0;
+        endcase
+    end
+endmodule
+"#,
+    );
+
+    // Items compare with ==?, so their x and z bits are wildcards; value
+    // ranges aren't constants and leave the last case unchecked
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "case-incomplete",
+                "Case on 'sel' has no default and misses values such as 2'b00"
+            ),
+            (
+                "case-overlap",
+                "Case item is never taken: earlier items match every value it matches"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_assertion_without_clock() {
+    let diagnostics = lint(
+        r#"
+module top(input logic clk, input logic a);
+    assert property (a);
+    assert property (@(posedge clk) a);
+    always @(posedge clk) assert property (a);
+    initial assert property (a);
+endmodule
+
+module defaults(input logic clk, input logic a);
+    assert property (a);
+    default clocking cb;
+endmodule
+
+module declared(input logic clk, input logic a);
+    property clocked_a;
+        @(posedge clk) a;
+    endproperty
+    sequence unclocked_a;
+        a ##1 a;
+    endsequence
+    assert property (clocked_a);
+    cover property (unclocked_a);
+    initial assert property (@(posedge clk) a);
+endmodule
+"#,
+    );
+
+    let message = "Concurrent assertion has no clock: give the property a clocking event \
+                   like @(posedge clk) or declare a default clocking";
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            ("assertion-clock", message),
+            ("assertion-clock", message),
+            ("assertion-clock", message)
+        ]
+    );
+}
+
+#[test]
+fn test_sampled_value_function_without_clock() {
+    let diagnostics = lint(
+        r#"
+module top(input logic clk, input logic a);
+    logic r, f, s;
+    assign r = $rose(a);
+    always_comb f = $fell(a);
+    always_ff @(posedge clk) s <= $stable(a);
+    always @(posedge clk) if ($past(a)) s <= 1'b0;
+    initial begin
+        @(posedge clk);
+        s = $changed(a);
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "assertion-clock",
+                "$rose has no clock to sample on: call it where an edge is awaited, \
+                 give it a clocking event or declare a default clocking"
+            ),
+            (
+                "assertion-clock",
+                "$fell has no clock to sample on: call it where an edge is awaited, \
+                 give it a clocking event or declare a default clocking"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unique_if_branches_overlap() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic [1:0] sel;
+    logic [1:0] y;
+    always_comb begin
+        unique if (sel == 2'b01) y = 1;
+        else if (sel ==? 2'b0?) y = 2;
+        else y = 0;
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![(
+            "if-overlap",
+            "Branch overlaps an earlier branch of this unique if: both match 2'b01"
+        )]
+    );
+}
+
+#[test]
+fn test_if_branch_never_taken() {
+    let diagnostics = lint(
+        r#"
+module top();
+    logic [1:0] sel;
+    logic [1:0] y;
+    always_comb begin
+        if (sel == 2'b10) y = 1;
+        else if (2'b01 == sel) y = 2;
+        else if (sel === 2'b10) y = 3;
+        else y = 0;
+    end
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![(
+            "if-overlap",
+            "Branch is never taken: earlier conditions match every value it matches"
+        )]
+    );
+}
+
+/// A rule a project could define outside the parser: no `initial` blocks,
+/// and no `wait fork` anywhere
+struct NoInitialOrWaitFork;
+
+impl LintRule for NoInitialOrWaitFork {
+    fn name(&self) -> &'static str {
+        "acme-no-initial"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flag initial blocks and wait fork"
+    }
+
+    fn default_severity(&self) -> LintSeverity {
+        LintSeverity::Error
+    }
+
+    fn check_item(
+        &self,
+        _source_unit: &SourceUnit,
+        item: &ModuleItem,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        if let ModuleItem::ProceduralBlock {
+            block_type: ProceduralBlockType::Initial,
+            span,
+            ..
+        } = item
+        {
+            diagnostics.push(LintDiagnostic {
+                rule: self.name(),
+                message: "initial block".to_string(),
+                span: *span,
+                fix: None,
+            });
+        }
+    }
+
+    fn check_statement(
+        &self,
+        _source_unit: &SourceUnit,
+        statement: &Statement,
+        diagnostics: &mut Vec<LintDiagnostic>,
+    ) {
+        if let Statement::WaitFork { span } = statement {
+            diagnostics.push(LintDiagnostic {
+                rule: self.name(),
+                message: "wait fork".to_string(),
+                span: *span,
+                fix: None,
+            });
+        }
+    }
+}
+
+#[test]
+fn test_extra_rule_sees_nested_items_and_statements() {
+    let content = r#"
+module top;
+    if (1) begin : gen
+        initial begin
+            if (1) wait fork;
+        end
+    end
+endmodule
+
+class runner;
+    function void run();
+        wait fork;
+    endfunction
+endclass
+"#;
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    let names = ["acme-no-initial".to_string()];
+    let engine = LintEngine::configured_with(
+        &ProjectConfig::default().lint,
+        Some(&names),
+        vec![Box::new(NoInitialOrWaitFork)],
+    )
+    .unwrap();
+    let diagnostics = engine.run(&ast);
+
+    let found: Vec<_> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+    assert_eq!(found, vec!["initial block", "wait fork", "wait fork"]);
+    assert_eq!(engine.severity("acme-no-initial"), LintSeverity::Error);
+}
+
+#[test]
+fn test_register_rule() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser
+        .parse_content("module top;\n    initial $display(\"hi\");\nendmodule\n")
+        .unwrap();
+    let mut engine = LintEngine::new();
+    assert!(engine.run(&ast).is_empty());
+
+    engine.register(Box::new(NoInitialOrWaitFork)).unwrap();
+    assert_eq!(engine.run(&ast).len(), 1);
+    assert_eq!(
+        engine
+            .register(Box::new(NoInitialOrWaitFork))
+            .err()
+            .unwrap(),
+        "Lint rule acme-no-initial is defined twice"
+    );
+}
+
+#[test]
+fn test_severity_config() {
+    let config = ProjectConfig::parse("[lint.severity]\nno-casex = \"error\"\n").unwrap();
+    let engine = LintEngine::configured(&config.lint, None).unwrap();
+    assert_eq!(engine.severity("no-casex"), LintSeverity::Error);
+    assert_eq!(engine.severity("dead-code"), LintSeverity::Warning);
+
+    let config = ProjectConfig::parse("[lint.severity]\nno-such-rule = \"info\"\n").unwrap();
+    assert_eq!(
+        LintEngine::configured(&config.lint, None).err().unwrap(),
+        "Unknown lint rule in [lint.severity]: no-such-rule"
+    );
+    assert!(ProjectConfig::parse("[lint.severity]\nno-casex = \"fatal\"\n").is_err());
+}
+
+/// A project rule that needs every file: a module name used by an earlier
+/// file
+struct UniqueModuleNames;
+
+impl LintRule for UniqueModuleNames {
+    fn name(&self) -> &'static str {
+        "acme-unique-module"
+    }
+
+    fn description(&self) -> &'static str {
+        "Flag modules defined in more than one file"
+    }
+
+    fn cross_file(&self) -> bool {
+        true
+    }
+
+    fn check_project(&self, files: &[LintFile], diagnostics: &mut [Vec<LintDiagnostic>]) {
+        let mut seen = HashMap::new();
+        for (index, file) in files.iter().enumerate() {
+            for item_ref in &file.source_unit.items {
+                let ModuleItem::ModuleDeclaration {
+                    name, name_span, ..
+                } = file.source_unit.module_item_arena.get(*item_ref)
+                else {
+                    continue;
+                };
+                if let Some(first) = seen.insert(name.clone(), file.path) {
+                    diagnostics[index].push(LintDiagnostic {
+                        rule: self.name(),
+                        message: format!("Module {} is also defined in {}", name, first.display()),
+                        span: *name_span,
+                        fix: None,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_run_files_matches_run_file() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let sources: Vec<(String, String)> = (0..12)
+        .map(|index| {
+            let content = if index % 3 == 0 {
+                format!(
+                    "module m{}(input clk);\n    logic q;\n    always_ff @(posedge clk) q = 1;\nendmodule\n",
+                    index
+                )
+            } else {
+                format!("module m{};\nendmodule\n", index)
+            };
+            (format!("rtl/m{}.sv", index), content)
+        })
+        .collect();
+    let asts: Vec<SourceUnit> = sources
+        .iter()
+        .map(|(_, content)| parser.parse_content(content).unwrap())
+        .collect();
+    let files: Vec<LintFile> = sources
+        .iter()
+        .zip(&asts)
+        .map(|((path, content), ast)| LintFile {
+            path: Path::new(path),
+            source_unit: ast,
+            text: content,
+        })
+        .collect();
+    let engine = LintEngine::new();
+
+    let diagnostics = engine.run_files(&files);
+    let expected: Vec<Vec<LintDiagnostic>> = files
+        .iter()
+        .map(|file| engine.run_file(file.path, file.source_unit, file.text))
+        .collect();
+    assert_eq!(diagnostics, expected);
+    assert_eq!(
+        diagnostics.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![1, 0, 0, 1, 0, 0, 1, 0, 0, 1, 0, 0]
+    );
+}
+
+#[test]
+fn test_cross_file_rule() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let sources = [
+        ("rtl/fifo.sv", "module fifo;\nendmodule\n"),
+        ("rtl/top.sv", "module top;\nendmodule\n"),
+        ("tb/fifo.sv", "module fifo;\nendmodule\n"),
+    ];
+    let asts: Vec<SourceUnit> = sources
+        .iter()
+        .map(|(_, content)| parser.parse_content(content).unwrap())
+        .collect();
+    let files: Vec<LintFile> = sources
+        .iter()
+        .zip(&asts)
+        .map(|((path, content), ast)| LintFile {
+            path: Path::new(path),
+            source_unit: ast,
+            text: content,
+        })
+        .collect();
+    let mut engine = LintEngine::new();
+    engine.register(Box::new(UniqueModuleNames)).unwrap();
+
+    // A single file has nothing to compare with
+    assert!(engine
+        .run_file(files[2].path, files[2].source_unit, files[2].text)
+        .is_empty());
+
+    let diagnostics = engine.run_files(&files);
+    assert!(diagnostics[0].is_empty());
+    assert!(diagnostics[1].is_empty());
+    assert_eq!(diagnostics[2].len(), 1);
+    assert_eq!(
+        diagnostics[2][0].message,
+        "Module fifo is also defined in rtl/fifo.sv"
+    );
+    assert_eq!(diagnostics[2][0].span, (7, 11));
+}
+
+#[test]
+fn test_implicit_nets() {
+    let diagnostics = lint(
+        r#"
+module leaf(input logic a, output logic y);
+    assign y = a;
+endmodule
+
+module top(input logic clk);
+    logic declared;
+    leaf u0(.a(clk), .y(undriven));
+    leaf u1(clk, undriven);
+    leaf u2(.a(declared), .y(declared));
+    assign typo = declared;
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "implicit-net",
+                "'undriven' is not declared, so this port connection creates an implicit wire net"
+            ),
+            (
+                "implicit-net",
+                "'typo' is not declared, so this continuous assignment creates an implicit \
+                 wire net"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_implicit_nets_follow_default_nettype() {
+    let content = r#"
+`default_nettype tri
+module a_mod();
+    assign x = 1'b0;
+endmodule
+`default_nettype none
+module b_mod();
+    assign z = 1'b0;
+endmodule
+"#;
+    let diagnostics = lint(content);
+    assert_eq!(
+        findings(&diagnostics),
+        vec![(
+            "implicit-net",
+            "'x' is not declared, so this continuous assignment creates an implicit tri net"
+        )]
+    );
+
+    let config = ProjectConfig::parse("[lint.severity]\nimplicit-net = \"error\"\n").unwrap();
+    let engine = LintEngine::configured(&config.lint, None).unwrap();
+    assert_eq!(engine.severity("implicit-net"), LintSeverity::Error);
+}
+
+#[test]
+fn test_unmatched_events() {
+    let diagnostics = lint(
+        r#"
+module top(input logic clk);
+    event start, done, lost, orphan, shared;
+    initial begin
+        -> start;
+        @(done);
+        ->> lost;
+        wait (orphan.triggered);
+        ->> done;
+    end
+    always @(start) -> done;
+    sink u0(.e(shared));
+    initial -> shared;
+endmodule
+"#,
+    );
+
+    assert_eq!(
+        findings(&diagnostics),
+        vec![
+            (
+                "unmatched-event",
+                "Event 'lost' is triggered but never waited on"
+            ),
+            (
+                "unmatched-event",
+                "Event 'orphan' is waited on but never triggered, so the wait never ends"
+            ),
+        ]
+    );
+}
@@ -0,0 +1,112 @@
+//! Merging per-file source units into one.
+
+use std::collections::HashMap;
+use std::fs;
+
+use sv_parser::printer::print;
+use sv_parser::{Expression, ModuleItem, Provenance, SystemVerilogParser};
+use tempfile::TempDir;
+
+const FIRST: &str = r#"module adder(input [7:0] a, input [7:0] b, output [7:0] y);
+    assign y = a + b;
+endmodule
+"#;
+
+const SECOND: &str = r#"module top;
+    logic [7:0] x;
+    adder u_add (.a(x), .b(8'd1), .y());
+    initial begin
+        x = (x << 1) | 1;
+        $display("%d", x);
+    end
+endmodule
+"#;
+
+/// The merged unit holds the same design as the two texts parsed together.
+#[test]
+fn test_merge_matches_parsing_concatenated_text() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let mut merged = parser.parse_content(FIRST).unwrap();
+    merged.merge(parser.parse_content(SECOND).unwrap());
+
+    let together = parser
+        .parse_content(&format!("{}{}", FIRST, SECOND))
+        .unwrap();
+    assert_eq!(merged.items.len(), 2);
+    assert_eq!(print(&merged), print(&together));
+}
+
+/// References inside merged expressions point at the merged nodes.
+#[test]
+fn test_merge_remaps_nested_expressions() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let mut merged = parser.parse_content(SECOND).unwrap();
+    merged.merge(parser.parse_content(FIRST).unwrap());
+
+    let ModuleItem::ModuleDeclaration { items, .. } = merged.module_item_arena.get(merged.items[1])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::Assignment { expr, .. } = merged.module_item_arena.get(items[0]) else {
+        panic!("Expected continuous assignment");
+    };
+    let Expression::Binary { left, right, .. } = merged.expr_arena.get(*expr) else {
+        panic!("Expected binary expression");
+    };
+    assert!(matches!(merged.expr_arena.get(*left), Expression::Identifier(name, _) if name == "a"));
+    assert!(
+        matches!(merged.expr_arena.get(*right), Expression::Identifier(name, _) if name == "b")
+    );
+}
+
+/// Included files go through the same merge, so their expressions survive
+/// being placed after the nodes of the including file.
+#[test]
+fn test_include_keeps_nested_expressions() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("adder.svh"), FIRST).unwrap();
+    fs::write(
+        dir.path().join("top.sv"),
+        format!("{}`include \"adder.svh\"\n", SECOND),
+    )
+    .unwrap();
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_file(&dir.path().join("top.sv")).unwrap();
+    assert!(
+        print(&ast).contains("assign y = a + b;"),
+        "got:\n{}",
+        print(&ast)
+    );
+}
+
+/// Each merged unit keeps its source map, which the spans of its nodes point into.
+#[test]
+fn test_merge_keeps_source_maps() {
+    let dir = TempDir::new().unwrap();
+    let first = dir.path().join("adder.sv");
+    let second = dir.path().join("top.sv");
+    let second_text = format!("`define ONE 8'd1\n{}", SECOND.replace("8'd1", "`ONE"));
+    fs::write(&first, FIRST).unwrap();
+    fs::write(&second, &second_text).unwrap();
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let mut merged = parser.parse_file(&first).unwrap();
+    merged.merge(parser.parse_file(&second).unwrap());
+
+    let source_map = merged.source_map.source_map_of(merged.items[1]);
+    assert_eq!(source_map.file(), Some(second.as_path()));
+    assert_eq!(
+        merged.source_map.source_map_of(merged.items[0]).file(),
+        Some(first.as_path())
+    );
+
+    // The expansion is found in the map of the second file only
+    let usage = second_text.find("`ONE").unwrap();
+    let notes = source_map.provenance((usage, usage + 4));
+    assert!(matches!(
+        notes.as_slice(),
+        [Provenance::Expansion { name, .. }] if name == "ONE"
+    ));
+    assert!(merged.source_map.provenance((usage, usage + 4)).is_empty());
+}
@@ -0,0 +1,84 @@
+//! Module complexity metrics tests for `--report metrics` output.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use sv_parser::metrics;
+use sv_parser::{ModuleMetrics, SystemVerilogParser};
+
+const DESIGN: &str = r#"module leaf(input a, output y);
+  assign y = a ? 1'b0 : 1'b1;
+endmodule
+
+module top(input clk, input a, output y);
+  logic q;
+  leaf u1(.a(a), .y(y));
+  always @(posedge clk) begin
+    q = a && clk;
+    $display("q=%b", q);
+  end
+endmodule
+"#;
+
+fn collect(content: &str) -> Vec<ModuleMetrics> {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    ModuleMetrics::collect(Path::new("design.sv"), &ast, content)
+}
+
+#[test]
+fn test_module_metrics() {
+    let metrics = collect(DESIGN);
+
+    assert_eq!(
+        metrics,
+        vec![
+            ModuleMetrics {
+                file: "design.sv".into(),
+                module: "leaf".to_string(),
+                lines: 3,
+                statements: 0,
+                // y, and the conditional with its three operands
+                expressions: 5,
+                branches: 1,
+                instances: 0,
+            },
+            ModuleMetrics {
+                file: "design.sv".into(),
+                module: "top".to_string(),
+                lines: 8,
+                statements: 2,
+                // Two connections, q = a && clk, and the two $display arguments
+                expressions: 8,
+                branches: 1,
+                instances: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_metrics_csv_and_json() {
+    let metrics = collect(DESIGN);
+
+    assert_eq!(
+        metrics::to_csv(&metrics),
+        "file,module,lines,statements,expressions,branches,instances\n\
+         design.sv,leaf,3,0,5,1,0\n\
+         design.sv,top,8,2,8,1,1\n"
+    );
+    let json: serde_json::Value = serde_json::from_str(&metrics::to_json(&metrics)).unwrap();
+    assert_eq!(json[1]["module"], "top");
+    assert_eq!(json[1]["instances"], 1);
+}
+
+#[test]
+fn test_metrics_csv_quotes_fields() {
+    let metrics = vec![ModuleMetrics {
+        file: "my,design.sv".into(),
+        module: "top".to_string(),
+        ..ModuleMetrics::default()
+    }];
+
+    assert!(metrics::to_csv(&metrics).ends_with("\"my,design.sv\",top,0,0,0,0,0\n"));
+}
@@ -7,7 +7,7 @@
 mod common;
 
 use common::{assert_directory_parses, assert_parse_ok};
-use sv_parser::{ModuleItem, PortDirection};
+use sv_parser::{Expression, ModuleItem, PortConnection, PortDirection};
 
 /// Ensure all module fixtures parse without error.
 #[test]
@@ -23,6 +23,9 @@ sv_ok_tests! {
     module_port_decl => "modules/module_with_port_declaration.sv",
     module_no_dir_ports => "modules/module_no_direction_ports.sv",
     module_whitespace => "modules/whitespace_handling.sv",
+    module_instantiation => "modules/module_instantiation.sv",
+    module_parameters => "modules/parameters.sv",
+    module_nested => "modules/nested_modules.sv",
 }
 
 /// Empty module fixture should produce a single declaration with no ports/items.
@@ -121,3 +124,161 @@ fn test_multiple_modules_structure() {
         panic!("Expected second module");
     }
 }
+
+/// Instantiations should record the module, instance, parameters and connections.
+#[test]
+fn test_module_instantiation_structure() {
+    let result = assert_parse_ok("modules/module_instantiation.sv");
+
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let instances: Vec<&ModuleItem> = items
+        .iter()
+        .map(|item_ref| result.module_item_arena.get(*item_ref))
+        .filter(|item| matches!(item, ModuleItem::ModuleInstantiation { .. }))
+        .collect();
+    assert_eq!(instances.len(), 3);
+
+    let ModuleItem::ModuleInstantiation {
+        module_name,
+        parameters,
+        instance_name,
+        connections,
+        ..
+    } = instances[0]
+    else {
+        unreachable!();
+    };
+    assert_eq!(module_name, "counter");
+    assert_eq!(instance_name, "u_counter");
+    assert!(matches!(&parameters[..], [PortConnection::Named { name, .. }] if name == "WIDTH"));
+    assert_eq!(connections.len(), 4);
+
+    // `.rst` is shorthand for `.rst(rst)`
+    let PortConnection::Named {
+        name,
+        expr: Some(expr),
+        ..
+    } = &connections[1]
+    else {
+        panic!("Expected implicit named connection");
+    };
+    assert_eq!(name, "rst");
+    assert!(matches!(result.expr_arena.get(*expr), Expression::Identifier(n, _) if n == "rst"));
+
+    // `.overflow()` is left unconnected
+    assert!(matches!(
+        &connections[3],
+        PortConnection::Named { name, expr: None, .. } if name == "overflow"
+    ));
+
+    let ModuleItem::ModuleInstantiation { connections, .. } = instances[1] else {
+        unreachable!();
+    };
+    assert_eq!(connections.len(), 2);
+    assert!(connections
+        .iter()
+        .all(|c| matches!(c, PortConnection::Positional(_))));
+
+    let ModuleItem::ModuleInstantiation { connections, .. } = instances[2] else {
+        unreachable!();
+    };
+    assert_eq!(connections, &vec![PortConnection::Wildcard]);
+}
+
+/// Parameter and localparam declarations keep their type, range and value.
+#[test]
+fn test_parameter_declaration_structure() {
+    let result = assert_parse_ok("modules/parameters.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+
+    let parameters: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match result.module_item_arena.get(*item_ref) {
+            ModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                value,
+                ..
+            } => Some((
+                *local,
+                data_type.clone(),
+                range.clone(),
+                name.clone(),
+                *value,
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(parameters.len(), 4);
+
+    let (local, data_type, range, name, _) = &parameters[0];
+    assert!(!local);
+    assert_eq!(data_type, &None);
+    assert_eq!(range, &None);
+    assert_eq!(name, "WIDTH");
+
+    let (_, data_type, _, name, _) = &parameters[1];
+    assert_eq!(data_type.as_deref(), Some("int"));
+    assert_eq!(name, "DEPTH");
+
+    let (local, data_type, range, name, value) = &parameters[2];
+    assert!(local);
+    assert_eq!(data_type.as_deref(), Some("logic"));
+    assert_eq!(range.as_ref().map(|r| r.msb.as_str()), Some("3"));
+    assert_eq!(name, "MASK");
+    assert!(matches!(
+        result.expr_arena.get(*value),
+        Expression::Number(text, _) if text == "4'hF"
+    ));
+
+    let (_, _, _, name, value) = &parameters[3];
+    assert_eq!(name, "MSB");
+    assert!(matches!(
+        result.expr_arena.get(*value),
+        Expression::Binary { .. }
+    ));
+}
+
+/// A module declared inside another is one of its items, next to the import
+/// and the instance of it.
+#[test]
+fn test_nested_module_structure() {
+    let result = assert_parse_ok("modules/nested_modules.sv");
+    assert_eq!(result.items.len(), 1);
+
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert_eq!(items.len(), 4);
+    assert!(matches!(
+        result.module_item_arena.get(items[0]),
+        ModuleItem::ImportDeclaration { .. }
+    ));
+
+    let ModuleItem::ModuleDeclaration {
+        name,
+        ports,
+        items: inner_items,
+        ..
+    } = result.module_item_arena.get(items[2])
+    else {
+        panic!("Expected nested module declaration");
+    };
+    assert_eq!(name, "inner");
+    assert_eq!(ports.len(), 1);
+    assert_eq!(inner_items.len(), 1);
+
+    assert!(matches!(
+        result.module_item_arena.get(items[3]),
+        ModuleItem::ModuleInstantiation { module_name, .. } if module_name == "inner"
+    ));
+}
@@ -0,0 +1,99 @@
+//! Number literal decoding and constant evaluation tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use common::assert_parse_ok;
+use sv_parser::number::parameter_values;
+use sv_parser::{BitPattern, ModuleItem, NumberBase, NumberLiteral};
+
+#[test]
+fn test_parse_sized_hex_literal() {
+    let literal = NumberLiteral::parse("8'hA5").unwrap();
+    assert_eq!(literal.width, Some(8));
+    assert!(!literal.signed);
+    assert_eq!(literal.base, NumberBase::Hex);
+    assert_eq!(literal.value, Some(0xA5));
+    assert_eq!(literal.to_decimal_string().as_deref(), Some("165"));
+    assert_eq!(literal.to_hex_string().as_deref(), Some("8'hA5"));
+    assert_eq!(literal.to_binary_string().as_deref(), Some("8'b10100101"));
+}
+
+#[test]
+fn test_parse_signed_literal_is_negative_when_sign_bit_set() {
+    let literal = NumberLiteral::parse("4'sb1010").unwrap();
+    assert!(literal.signed);
+    assert_eq!(literal.to_i128(), Some(-6));
+    assert_eq!(literal.to_decimal_string().as_deref(), Some("-6"));
+    assert_eq!(literal.to_hex_string().as_deref(), Some("4'hA"));
+}
+
+#[test]
+fn test_parse_unsized_decimal_literal() {
+    let literal = NumberLiteral::parse("1_000").unwrap();
+    assert_eq!(literal.width, None);
+    assert_eq!(literal.bit_width(), 32);
+    assert!(literal.signed);
+    assert_eq!(literal.value, Some(1000));
+}
+
+#[test]
+fn test_parse_truncates_to_width() {
+    let literal = NumberLiteral::parse("4'hFF").unwrap();
+    assert_eq!(literal.value, Some(0xF));
+}
+
+#[test]
+fn test_parse_unknown_bits_have_no_value() {
+    let literal = NumberLiteral::parse("4'b1x0z").unwrap();
+    assert_eq!(literal.width, Some(4));
+    assert_eq!(literal.value, None);
+    assert_eq!(literal.to_decimal_string(), None);
+}
+
+#[test]
+fn test_parse_rejects_invalid_literals() {
+    assert_eq!(NumberLiteral::parse("'1"), None);
+    assert_eq!(NumberLiteral::parse("4'b102"), None);
+    assert_eq!(NumberLiteral::parse("0'h1"), None);
+    assert_eq!(NumberLiteral::parse("abc"), None);
+}
+
+#[test]
+fn test_bit_pattern_keeps_wildcard_bits() {
+    let pattern = BitPattern::parse("4'b1?0x").unwrap();
+    assert_eq!(pattern.width, 4);
+    assert_eq!(pattern.value, 0b1000);
+    assert_eq!(pattern.z, 0b0100);
+    assert_eq!(pattern.x, 0b0001);
+    assert_eq!(pattern.wildcards("case"), 0);
+    assert_eq!(pattern.wildcards("casez"), 0b0100);
+    assert_eq!(pattern.wildcards("casex"), 0b0101);
+
+    // An x or z in the leftmost digit fills the bits above it
+    assert_eq!(BitPattern::parse("8'hz").unwrap().z, 0xFF);
+    assert_eq!(BitPattern::parse("8'bx1").unwrap().x, 0xFE);
+    assert_eq!(BitPattern::parse("4'dx").unwrap().x, 0xF);
+
+    let known = BitPattern::parse("8'hA5").unwrap();
+    assert_eq!((known.value, known.x, known.z), (0xA5, 0, 0));
+    assert_eq!(BitPattern::parse("8'd1x"), None);
+}
+
+#[test]
+fn test_parameter_values_follow_earlier_parameters() {
+    let result = assert_parse_ok("modules/parameters.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+
+    let values = parameter_values(items, &result.module_item_arena, &result.expr_arena);
+    assert_eq!(values["WIDTH"].value, Some(8));
+    assert_eq!(values["DEPTH"].value, Some(16));
+    assert_eq!(values["MSB"].to_i128(), Some(7));
+
+    // The packed range fixes the width of MASK
+    assert_eq!(values["MASK"].width, Some(4));
+    assert_eq!(values["MASK"].value, Some(0xF));
+}
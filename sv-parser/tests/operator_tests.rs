@@ -10,7 +10,7 @@ use common::{
     assert_directory_parses, assert_parse_ok,
     ast::{assignment_expr, first_assignment_expr, first_initial_block_statements},
 };
-use sv_parser::{BinaryOp, Expression, Statement, UnaryOp};
+use sv_parser::{BinaryOp, Expression, IncDecOp, Statement, UnaryOp};
 
 /// Test parsing all operator test files
 #[test]
@@ -160,6 +160,76 @@ fn test_unary_operators() {
     }
 }
 
+/// `++` and `--` go before or after their operand, alone as a statement or
+/// inside a larger expression.
+#[test]
+fn test_increment_decrement_operators() {
+    let result = assert_parse_ok("operators/increment_decrement.sv");
+    let statements = first_initial_block_statements(&result);
+    assert_eq!(statements.len(), 3);
+
+    let Statement::ExpressionStatement { expr, .. } = result.stmt_arena.get(statements[0]) else {
+        panic!("Expected expression statement");
+    };
+    let Expression::IncDec {
+        op,
+        prefix,
+        operand,
+        span,
+    } = result.expr_arena.get(*expr)
+    else {
+        panic!("Expected increment");
+    };
+    assert_eq!(*op, IncDecOp::Increment);
+    assert!(!prefix);
+    assert!(
+        matches!(result.expr_arena.get(*operand), Expression::Identifier(name, _) if name == "i")
+    );
+    assert_eq!(*span, (61, 64));
+
+    let Statement::ExpressionStatement { expr, .. } = result.stmt_arena.get(statements[1]) else {
+        panic!("Expected expression statement");
+    };
+    assert!(matches!(
+        result.expr_arena.get(*expr),
+        Expression::IncDec {
+            op: IncDecOp::Decrement,
+            prefix: true,
+            span: (74, 77),
+            ..
+        }
+    ));
+
+    let Statement::Assignment { expr, .. } = result.stmt_arena.get(statements[2]) else {
+        panic!("Expected assignment");
+    };
+    let Expression::Binary {
+        op: BinaryOp::Add,
+        left,
+        right,
+        ..
+    } = result.expr_arena.get(*expr)
+    else {
+        panic!("Expected addition");
+    };
+    assert!(matches!(
+        result.expr_arena.get(*left),
+        Expression::IncDec {
+            op: IncDecOp::Decrement,
+            prefix: false,
+            ..
+        }
+    ));
+    assert!(matches!(
+        result.expr_arena.get(*right),
+        Expression::IncDec {
+            op: IncDecOp::Increment,
+            prefix: true,
+            ..
+        }
+    ));
+}
+
 #[test]
 fn test_reduction_operators() {
     for (path, expected_op) in [
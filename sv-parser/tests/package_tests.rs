@@ -0,0 +1,170 @@
+//! Package declaration and import tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{ModuleItem, SystemVerilogParser};
+
+/// Ensure all package fixtures parse without error.
+#[test]
+fn test_parse_all_package_files() {
+    assert_directory_parses("packages");
+}
+
+sv_ok_tests! {
+    package_empty => "packages/empty_package.sv",
+    package_bus => "packages/bus_pkg.sv",
+    package_exports => "packages/exports.sv",
+    package_functions => "packages/math_pkg.sv",
+}
+
+/// Functions and tasks declared in a package, with their lifetimes
+#[test]
+fn test_package_functions_and_tasks() {
+    let result = assert_parse_ok("packages/math_pkg.sv");
+    let ModuleItem::PackageDeclaration { items, .. } =
+        result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected package declaration");
+    };
+    let subroutines: Vec<_> = items
+        .iter()
+        .map(|&item| match result.module_item_arena.get(item) {
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                is_automatic,
+                return_type,
+                name,
+                parameters,
+                body,
+                ..
+            } => (
+                *is_task,
+                *is_automatic,
+                return_type.clone(),
+                name.as_str(),
+                parameters.len(),
+                body.len(),
+            ),
+            other => panic!("Unexpected item {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        subroutines,
+        vec![
+            (false, true, Some("int".to_string()), "clamp", 2, 2),
+            (true, false, None, "report", 1, 1),
+        ]
+    );
+}
+
+/// A package holds its parameters, variables and classes, and a
+/// compilation-unit import sits between it and the module.
+#[test]
+fn test_package_structure() {
+    let result = assert_parse_ok("packages/bus_pkg.sv");
+    assert_eq!(result.items.len(), 3);
+
+    let ModuleItem::PackageDeclaration {
+        name,
+        name_span,
+        items,
+        ..
+    } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected package declaration");
+    };
+    assert_eq!(name, "bus_pkg");
+    assert_eq!(*name_span, (8, 15));
+    assert_eq!(items.len(), 4);
+    assert!(matches!(
+        result.module_item_arena.get(items[3]),
+        ModuleItem::ClassDeclaration { name, .. } if name == "transaction"
+    ));
+
+    let ModuleItem::ImportDeclaration { imports, .. } =
+        result.module_item_arena.get(result.items[1])
+    else {
+        panic!("Expected import declaration");
+    };
+    assert_eq!(imports.len(), 1);
+    assert_eq!(imports[0].package, "bus_pkg");
+    assert_eq!(imports[0].name, None);
+}
+
+/// Imports in a module header come first among the module's items, ahead of
+/// the ones in its body.
+#[test]
+fn test_module_imports() {
+    let result = assert_parse_ok("packages/bus_pkg.sv");
+
+    let ModuleItem::ModuleDeclaration { ports, items, .. } =
+        result.module_item_arena.get(result.items[2])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert_eq!(ports.len(), 2);
+
+    let imported: Vec<(&str, Option<&str>)> = items
+        .iter()
+        .filter_map(|item_ref| match result.module_item_arena.get(*item_ref) {
+            ModuleItem::ImportDeclaration { imports, .. } => Some(imports),
+            _ => None,
+        })
+        .flatten()
+        .map(|import| (import.package.as_str(), import.name.as_deref()))
+        .collect();
+    assert_eq!(
+        imported,
+        vec![
+            ("bus_pkg", Some("WIDTH")),
+            ("bus_pkg", Some("DEPTH")),
+            ("bus_pkg", Some("transaction")),
+        ]
+    );
+
+    // Types can be named through their package
+    assert!(matches!(
+        result.module_item_arena.get(items[2]),
+        ModuleItem::VariableDeclaration { data_type, name, .. }
+            if data_type.name() == "bus_pkg::transaction" && name == "tr"
+    ));
+}
+
+/// An import names a package, not just a member.
+#[test]
+fn test_import_without_package() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "module top;\n    import WIDTH;\nendmodule\n";
+    assert!(parser.parse_content(content).is_err());
+}
+
+/// `export` names one imported item, every item of one package, or `*::*`
+/// for every imported item.
+#[test]
+fn test_package_exports() {
+    let result = assert_parse_ok("packages/exports.sv");
+    let ModuleItem::PackageDeclaration { items, .. } =
+        result.module_item_arena.get(result.items[1])
+    else {
+        panic!("Expected package declaration");
+    };
+    assert_eq!(items.len(), 3);
+
+    let exported: Vec<(&str, Option<&str>)> = items
+        .iter()
+        .filter_map(|item_ref| match result.module_item_arena.get(*item_ref) {
+            ModuleItem::ExportDeclaration { exports, .. } => Some(exports),
+            _ => None,
+        })
+        .flatten()
+        .map(|export| (export.package.as_str(), export.name.as_deref()))
+        .collect();
+    assert_eq!(
+        exported,
+        vec![("base_pkg", Some("WIDTH")), ("base_pkg", None), ("*", None)]
+    );
+}
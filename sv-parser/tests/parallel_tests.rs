@@ -0,0 +1,39 @@
+//! Thread pool tests.
+
+use std::collections::HashMap;
+use std::thread;
+use sv_parser::{parallel, SystemVerilogParser};
+
+#[test]
+fn test_map_keeps_input_order() {
+    let items: Vec<u32> = (0..100).collect();
+    let squares = parallel::map(&items, |n| n * n);
+    assert_eq!(squares, items.iter().map(|n| n * n).collect::<Vec<_>>());
+}
+
+/// A `map` inside another one runs on the worker that calls it
+#[test]
+fn test_nested_map_runs_on_its_worker() {
+    let outer: Vec<u32> = (0..8).collect();
+    let inner: Vec<u32> = (0..8).collect();
+    let threads = parallel::map(&outer, |_| {
+        let worker = thread::current().id();
+        parallel::map(&inner, |_| thread::current().id() == worker)
+    });
+    assert!(threads.iter().flatten().all(|same| *same));
+}
+
+/// The grammar runs on a thread of its own, whatever the caller's stack size
+#[test]
+fn test_parse_on_a_small_stack() {
+    let parsed = thread::Builder::new()
+        .stack_size(256 * 1024)
+        .spawn(|| {
+            let parser = SystemVerilogParser::new(vec![], HashMap::new());
+            parser.parse_content("module top;\nendmodule\n").is_ok()
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+    assert!(parsed);
+}
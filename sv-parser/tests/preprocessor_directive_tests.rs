@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 use std::fs;
-use sv_parser::{ModuleItem, SystemVerilogParser};
+use sv_parser::{KeywordVersion, ModuleItem, ParseErrorType, SystemVerilogParser};
 
 #[test]
 fn test_define_directive_simple() {
@@ -295,6 +295,38 @@ module test; endmodule
     let _ = fs::remove_dir_all(&temp_dir);
 }
 
+#[test]
+fn test_parse_file_content_resolves_includes() {
+    let temp_dir = std::env::temp_dir().join("sv_parser_test_file_content_includes");
+    let _ = fs::remove_dir_all(&temp_dir);
+    let inc_dir = temp_dir.join("include");
+    fs::create_dir_all(&inc_dir).unwrap();
+    let include_file = inc_dir.join("defs.svh");
+    fs::write(&include_file, "module helper; endmodule\n").unwrap();
+
+    // The editor's text of a file that has not been saved
+    let main_file = temp_dir.join("main.sv");
+    let content = r#"`include "defs.svh"
+`include "missing.svh"
+module test; endmodule
+"#;
+
+    let parser = SystemVerilogParser::new(vec![inc_dir.clone()], HashMap::new());
+    let ast = parser.parse_file_content(content, &main_file).unwrap();
+
+    let resolved: Vec<_> = ast
+        .items
+        .iter()
+        .filter_map(|&item_ref| match ast.module_item_arena.get(item_ref) {
+            ModuleItem::IncludeDirective { resolved_path, .. } => Some(resolved_path.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(resolved, vec![Some(include_file), None]);
+
+    let _ = fs::remove_dir_all(&temp_dir);
+}
+
 #[test]
 fn test_parse_with_includes_recursive() {
     // Create temporary directory structure
@@ -431,3 +463,285 @@ endmodule
     // Cleanup
     let _ = fs::remove_dir_all(&temp_dir);
 }
+
+#[test]
+fn test_begin_keywords_allows_newer_keywords_as_identifiers() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+
+    let content = r#"
+`begin_keywords "1364-2001"
+module legacy(input a, output y);
+    wire logic;
+    assign logic = a;
+    assign y = logic;
+endmodule
+`end_keywords
+"#;
+    let result = parser.parse_content(content);
+    assert!(result.is_ok(), "Parse should succeed: {:?}", result.err());
+
+    // Outside the region `logic` is reserved again
+    let content = r#"
+`begin_keywords "1364-2001"
+`end_keywords
+module modern;
+    wire logic;
+endmodule
+"#;
+    let error = parser.parse_content(content).unwrap_err();
+    assert_eq!(error.primary_error().message, "'logic' is a keyword");
+}
+
+#[test]
+fn test_invalid_keyword_regions_are_errors() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+
+    let content = "`begin_keywords \"1800-2099\"\nmodule m;\nendmodule\n`end_keywords\n";
+    let error = parser.parse_content(content).unwrap_err();
+    let primary = error.primary_error();
+    assert_eq!(primary.error_type, ParseErrorType::PreprocessorError);
+    assert_eq!(
+        primary.message,
+        "unknown `begin_keywords version \"1800-2099\""
+    );
+    assert_eq!(primary.location.as_ref().unwrap().span, Some((0, 27)));
+
+    let content = "module m;\nendmodule\n`end_keywords\n";
+    let error = parser.parse_content(content).unwrap_err();
+    let primary = error.primary_error();
+    assert_eq!(primary.error_type, ParseErrorType::PreprocessorError);
+    assert_eq!(primary.message, "`end_keywords without `begin_keywords");
+    assert_eq!(primary.location.as_ref().unwrap().line, 2);
+
+    let content = "`begin_keywords 1800-2017\nmodule m;\nendmodule\n`end_keywords\n";
+    let error = parser.parse_content(content).unwrap_err();
+    assert_eq!(
+        error.primary_error().message,
+        "`begin_keywords needs a version in quotes, like \"1800-2017\""
+    );
+}
+
+#[test]
+fn test_escaped_identifier() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+
+    let content = "module \\top+1 (input a); wire \\module ; assign \\module = a; endmodule";
+    let ast = parser.parse_content(content).unwrap();
+    let ModuleItem::ModuleDeclaration { name, .. } = ast.module_item_arena.get(ast.items[0]) else {
+        panic!("Expected ModuleDeclaration");
+    };
+    assert_eq!(name, "top+1");
+}
+
+#[test]
+fn test_macro_table_after_parse_file() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let header = temp_dir.path().join("defs.svh");
+    fs::write(&header, "`define WIDTH 8\n`define MAX(a, b) a\n").unwrap();
+    let top = temp_dir.path().join("top.sv");
+    fs::write(
+        &top,
+        "`include \"defs.svh\"\nmodule top;\n    `define WIDTH 16\nendmodule\n",
+    )
+    .unwrap();
+
+    let mut defines = HashMap::new();
+    defines.insert("SIM".to_string(), "1".to_string());
+    let mut parser = SystemVerilogParser::new(vec![], defines);
+    parser.parse_file(&top).unwrap();
+
+    // The module's definition replaces the one from the header
+    let width = parser.macro_definition("WIDTH").unwrap();
+    assert_eq!(width.value, "16");
+    assert_eq!(width.file.as_deref(), Some(top.as_path()));
+    assert!(width.span.is_some());
+
+    let max = parser.macro_definition("MAX").unwrap();
+    assert_eq!(max.parameters, vec!["a", "b"]);
+    assert_eq!(max.file.as_deref(), Some(header.as_path()));
+
+    let sim = parser.macro_definition("SIM").unwrap();
+    assert_eq!(sim.value, "1");
+    assert_eq!(sim.file, None);
+    assert_eq!(parser.macros().len(), 3);
+    assert!(parser.macro_definition("UNDEFINED").is_none());
+}
+
+#[test]
+fn test_header_file_parses_without_module() {
+    let header = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("test_files")
+        .join("headers")
+        .join("bus_defs.svh");
+    let content = fs::read_to_string(&header).unwrap();
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    assert!(
+        parser.parse_content(&content).is_err(),
+        "Declarations outside a module need header mode"
+    );
+    assert_eq!(
+        parser.parse_header_content(&content).unwrap().items.len(),
+        6
+    );
+
+    // .svh files are parsed as headers automatically
+    let ast = parser.parse_file(&header).unwrap();
+    let kinds: Vec<_> = ast
+        .items
+        .iter()
+        .map(|&item| match ast.module_item_arena.get(item) {
+            ModuleItem::DefineDirective { .. } => "define",
+            ModuleItem::ParameterDeclaration { .. } => "parameter",
+            ModuleItem::VariableDeclaration { .. } => "variable",
+            ModuleItem::ClassDeclaration { .. } => "class",
+            ModuleItem::SubroutineDeclaration { .. } => "function",
+            other => panic!("Unexpected item {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            "define",
+            "parameter",
+            "parameter",
+            "variable",
+            "class",
+            "function"
+        ]
+    );
+}
+
+#[test]
+fn test_header_mode_flag_and_included_headers() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    fs::write(temp_dir.path().join("regs.svh"), "logic [7:0] status;\n").unwrap();
+    let top = temp_dir.path().join("top.sv");
+    fs::write(&top, "module top;\n    `include \"regs.svh\"\nendmodule\n").unwrap();
+    let fragment = temp_dir.path().join("fragment.inc");
+    fs::write(&fragment, "logic ready;\n").unwrap();
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_file(&top).unwrap();
+    let ModuleItem::ModuleDeclaration { items, .. } = ast.module_item_arena.get(ast.items[0])
+    else {
+        panic!("Expected ModuleDeclaration");
+    };
+    assert!(matches!(
+        ast.module_item_arena.get(items[0]),
+        ModuleItem::VariableDeclaration { name, .. } if name == "status"
+    ));
+
+    // Other extensions need header mode to be asked for
+    assert!(parser.parse_file(&fragment).is_err());
+    parser.set_header_mode(true);
+    assert!(parser.parse_file(&fragment).is_ok());
+}
+
+#[test]
+fn test_header_included_once_per_compilation_unit() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    fs::write(temp_dir.path().join("txn.svh"), "class txn;\nendclass\n").unwrap();
+    let first = temp_dir.path().join("first.sv");
+    fs::write(&first, "`include \"txn.svh\"\nmodule first; endmodule\n").unwrap();
+    let second = temp_dir.path().join("second.sv");
+    fs::write(&second, "`include \"txn.svh\"\nmodule second; endmodule\n").unwrap();
+
+    let class_count = |ast: &sv_parser::SourceUnit| {
+        ast.items
+            .iter()
+            .filter(|&&item| {
+                matches!(
+                    ast.module_item_arena.get(item),
+                    ModuleItem::ClassDeclaration { .. }
+                )
+            })
+            .count()
+    };
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    assert_eq!(class_count(&parser.parse_file(&first).unwrap()), 1);
+    let ast = parser.parse_file(&second).unwrap();
+    assert_eq!(class_count(&ast), 0, "txn.svh was already included");
+    // The include is still reported as a dependency of the second file
+    assert_eq!(parser.resolved_includes().len(), 1);
+
+    parser.begin_compilation_unit();
+    assert_eq!(class_count(&parser.parse_file(&second).unwrap()), 1);
+    assert!(parser.duplicate_definitions().is_empty());
+}
+
+#[test]
+fn test_conflicting_definitions_in_compilation_unit() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let first = temp_dir.path().join("first.sv");
+    fs::write(
+        &first,
+        "module helper;\nendmodule\nmodule shared(input a);\nendmodule\n",
+    )
+    .unwrap();
+    let copy = temp_dir.path().join("copy.sv");
+    fs::write(&copy, "module   shared(input a);  endmodule\n").unwrap();
+    let other = temp_dir.path().join("other.sv");
+    fs::write(
+        &other,
+        "// different ports\nmodule shared(input b);\nendmodule\n",
+    )
+    .unwrap();
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    parser.parse_file(&first).unwrap();
+    assert!(parser.duplicate_definitions().is_empty());
+
+    // The same definition again, differing only in whitespace, is not a conflict
+    parser.parse_file(&copy).unwrap();
+    assert!(parser.duplicate_definitions().is_empty());
+
+    parser.parse_file(&other).unwrap();
+    let duplicates = parser.duplicate_definitions();
+    assert_eq!(duplicates.len(), 1);
+    assert_eq!(duplicates[0].name, "shared");
+    assert_eq!(duplicates[0].file, other);
+    assert_eq!(duplicates[0].location.line, 1);
+    assert_eq!(duplicates[0].location.column, 0);
+    assert_eq!(duplicates[0].previous_file, first);
+    assert_eq!(duplicates[0].previous_location.line, 2);
+    assert_eq!(duplicates[0].previous_location.column, 0);
+    assert_eq!(
+        duplicates[0].to_string(),
+        format!(
+            "{}:2:1: 'shared' is already defined differently at {}:3:1",
+            other.display(),
+            first.display()
+        )
+    );
+}
+
+#[test]
+fn test_language_version_by_file_extension() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let source = "module legacy(input a);\n    wire logic;\n    assign logic = a;\nendmodule\n";
+    let verilog = temp_dir.path().join("legacy.v");
+    let system_verilog = temp_dir.path().join("legacy.sv");
+    let custom = temp_dir.path().join("legacy.vp");
+    for path in [&verilog, &system_verilog, &custom] {
+        fs::write(path, source).unwrap();
+    }
+
+    // Verilog files use the Verilog-2005 keywords, where `logic` is a name
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    assert_eq!(
+        parser.language_version_for(&verilog),
+        KeywordVersion::V1364_2005
+    );
+    assert!(parser.parse_file(&verilog).is_ok());
+    assert!(parser.parse_file(&system_verilog).is_err());
+    assert!(parser.parse_file(&custom).is_err());
+
+    parser.set_extension_version(".vp", KeywordVersion::V1364_2001);
+    assert!(parser.parse_file(&custom).is_ok());
+
+    // As with -sverilog
+    parser.set_extension_version("v", KeywordVersion::LATEST);
+    assert!(parser.parse_file(&verilog).is_err());
+}
@@ -2,6 +2,10 @@ use std::collections::HashMap;
 use std::fs;
 use std::path::PathBuf;
 use sv_parser::preprocessor::Preprocessor;
+use sv_parser::{
+    DirectiveKind, Expression, ModuleItem, ParseErrorType, PreprocessLimits, Provenance,
+    SystemVerilogParser,
+};
 use tempfile::TempDir;
 
 fn create_temp_file(dir: &TempDir, filename: &str, content: &str) -> PathBuf {
@@ -217,3 +221,312 @@ fn test_preprocess_file_read_error() {
         .message
         .contains("Failed to read file"));
 }
+
+#[test]
+fn test_conditional_compilation_selects_branch() {
+    let content = "`define SIM\nmodule test(output y);\n`ifdef SIM\n  assign y = 1'b1;\n`else\n  assign y = undeclared;\n`endif\n`ifndef SIM\n  wire skipped;\n`endif\nendmodule\n";
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+
+    assert!(parser.analyze_semantics(&ast).is_empty());
+    let start = content.find("assign y = 1'b1").unwrap();
+    let branches: Vec<String> = ast
+        .source_map
+        .provenance((start, start + 1))
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(branches, vec!["in branch `ifdef SIM"]);
+}
+
+#[test]
+fn test_predefined_macro_selects_else_branch() {
+    let content = "module test(output y);\n`ifdef FPGA\n  assign y = 1'b0;\n`elsif ASIC\n  assign y = 1'b1;\n`else\n  assign y = 1'bx;\n`endif\nendmodule\n";
+    let parser =
+        SystemVerilogParser::new(vec![], HashMap::from([("ASIC".to_string(), String::new())]));
+    let ast = parser.parse_content(content).unwrap();
+
+    let start = content.find("assign y = 1'b1").unwrap();
+    let notes: Vec<String> = ast
+        .source_map
+        .provenance((start, start + 1))
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(notes, vec!["in branch `elsif ASIC"]);
+    let skipped = content.find("1'bx").unwrap();
+    assert!(ast.source_map.provenance((skipped, skipped + 1)).is_empty());
+}
+
+#[test]
+fn test_retained_directives() {
+    let content = "`timescale 1ns/1ps\n`define WIDTH 8\n`ifdef FPGA\n`default_nettype none\n`else\nmodule test;\nendmodule\n`endif\n";
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    assert!(parser
+        .parse_content(content)
+        .unwrap()
+        .source_map
+        .directives()
+        .is_empty());
+
+    parser.set_retain_directives(true);
+    let ast = parser.parse_content(content).unwrap();
+    let directives: Vec<(DirectiveKind, &str, bool)> = ast
+        .source_map
+        .directives()
+        .iter()
+        .map(|directive| {
+            assert_eq!(&content[directive.span.0..directive.span.1], directive.text);
+            (
+                directive.kind.clone(),
+                directive.text.as_str(),
+                directive.active,
+            )
+        })
+        .collect();
+    assert_eq!(
+        directives,
+        vec![
+            (DirectiveKind::Timescale, "`timescale 1ns/1ps", true),
+            (DirectiveKind::Define, "`define WIDTH 8", true),
+            (DirectiveKind::Ifdef, "`ifdef FPGA", true),
+            (
+                DirectiveKind::Other("default_nettype".to_string()),
+                "`default_nettype none",
+                false
+            ),
+            (DirectiveKind::Else, "`else", true),
+            (DirectiveKind::Endif, "`endif", true),
+        ]
+    );
+
+    // Text the preprocessor leaves as is keeps its directives too
+    let ast = parser
+        .parse_content("`timescale 1ns/1ps\nmodule test;\nendmodule\n")
+        .unwrap();
+    assert_eq!(ast.source_map.directives().len(), 1);
+}
+
+#[test]
+fn test_missing_endif() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let error = parser
+        .parse_content("`ifdef SIM\nmodule test; endmodule\n")
+        .unwrap_err();
+
+    let error = error.primary_error();
+    assert_eq!(error.message, "missing `endif for `ifdef SIM");
+    assert_eq!(error.error_type, ParseErrorType::PreprocessorError);
+    assert_eq!(error.location.as_ref().unwrap().span, Some((0, 10)));
+}
+
+#[test]
+fn test_diagnostic_notes_trace_macro_expansion() {
+    let temp_dir = TempDir::new().unwrap();
+    create_temp_file(
+        &temp_dir,
+        "defs.svh",
+        "// shared definitions\n`define DRIVE_ZERO(port) assign port = 1'b0;\n",
+    );
+    let content = "`include \"defs.svh\"\n`define SIM\nmodule test(input a);\n`ifdef SIM\n  `DRIVE_ZERO(a)\n`endif\nendmodule\n";
+    let main_path = create_temp_file(&temp_dir, "main.sv", content);
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_file(&main_path).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+    assert_eq!(errors.len(), 1);
+
+    // The port comes from the macro argument, so the error points at it
+    let argument = content.find("`DRIVE_ZERO(a)").unwrap() + "`DRIVE_ZERO(".len();
+    assert_eq!(errors[0].span, (argument, argument + 1));
+    let notes: Vec<String> = ast
+        .source_map
+        .provenance(errors[0].span)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        notes,
+        vec![
+            "expanded from `DRIVE_ZERO defined at defs.svh:2",
+            "in branch `ifdef SIM",
+        ]
+    );
+}
+
+#[test]
+fn test_parse_error_provenance() {
+    let content =
+        "`define BROKEN assign = 1;\nmodule test;\n`ifdef SIM\n  `BROKEN\n`endif\nendmodule\n";
+    let parser =
+        SystemVerilogParser::new(vec![], HashMap::from([("SIM".to_string(), String::new())]));
+    let error = parser.parse_content(content).unwrap_err();
+
+    let span = error
+        .primary_error()
+        .location
+        .as_ref()
+        .unwrap()
+        .span
+        .unwrap();
+    let notes: Vec<String> = error
+        .source_map
+        .provenance(span)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    assert_eq!(
+        notes,
+        vec![
+            "expanded from `BROKEN defined at line 1",
+            "in branch `ifdef SIM"
+        ]
+    );
+}
+
+#[test]
+fn test_macro_arguments_with_nested_commas() {
+    let content = "`define FIRST(a, b) a\nmodule test(input p, input q, output y);\n  assign y = `FIRST(f(p, q), {p, q}[1, 0]);\nendmodule\n";
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+
+    let ModuleItem::ModuleDeclaration { items, .. } = ast.module_item_arena.get(ast.items[1])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::Assignment { expr, .. } = ast.module_item_arena.get(items[0]) else {
+        panic!("Expected continuous assignment");
+    };
+    let Expression::FunctionCall {
+        function,
+        arguments,
+        ..
+    } = ast.expr_arena.get(*expr)
+    else {
+        panic!("Expected function call");
+    };
+    assert_eq!(arguments.len(), 2);
+    let Expression::Identifier(_, span) = ast.expr_arena.get(*function) else {
+        panic!("Expected function name");
+    };
+    assert_eq!(span.0, content.find("f(p, q)").unwrap());
+}
+
+#[test]
+fn test_macro_argument_spans_stay_in_source_order() {
+    let content = "`define ASSIGN_TO(value, target) assign target = value;\nmodule test(input a, input b);\n  `ASSIGN_TO(b, a)\nendmodule\n";
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+    assert_eq!(errors.len(), 1);
+
+    let usage = content.find("`ASSIGN_TO(b").unwrap();
+    let usage_span = (usage, usage + "`ASSIGN_TO(b, a)".len());
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "a");
+    assert!(usage_span.0 < errors[0].span.0 && errors[0].span.1 < usage_span.1);
+
+    // `value` comes after `target` in the macro text but before it in the
+    // usage, so it points at the whole usage instead
+    let ModuleItem::ModuleDeclaration { items, .. } = ast.module_item_arena.get(ast.items[1])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::Assignment { expr, span, .. } = ast.module_item_arena.get(items[0]) else {
+        panic!("Expected continuous assignment");
+    };
+    assert!(matches!(
+        ast.expr_arena.get(*expr),
+        Expression::Identifier(name, span) if name == "b" && *span == usage_span
+    ));
+    assert_eq!(*span, usage_span);
+}
+
+#[test]
+fn test_command_line_macro_expansion_note() {
+    let content = "module test(input a);\n  assign a = `ZERO;\nendmodule\n";
+    let parser = SystemVerilogParser::new(
+        vec![],
+        HashMap::from([("ZERO".to_string(), "1'b0".to_string())]),
+    );
+    let ast = parser.parse_content(content).unwrap();
+
+    let usage = content.find("`ZERO").unwrap();
+    let notes = ast.source_map.provenance((usage, usage + 5));
+    assert!(matches!(
+        notes.as_slice(),
+        [Provenance::Expansion {
+            definition: None,
+            ..
+        }]
+    ));
+    assert_eq!(
+        notes[0].to_string(),
+        "expanded from `ZERO defined on the command line"
+    );
+}
+
+#[test]
+fn test_macro_blowup_reports_expansion_chain() {
+    let content = "`define X0 abcdefghij\n`define X1 `X0`X0`X0`X0\n`define X2 `X1`X1`X1`X1\n`define X3 `X2`X2`X2`X2\nmodule test;\n  `X3\nendmodule\n";
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    parser.set_preprocess_limits(PreprocessLimits {
+        max_output: 500,
+        ..PreprocessLimits::default()
+    });
+    let error = parser.parse_content(content).unwrap_err();
+
+    let error = error.primary_error();
+    assert_eq!(error.error_type, ParseErrorType::PreprocessorError);
+    assert_eq!(
+        error.message,
+        "preprocessed text exceeds 500 characters while expanding `X3 -> `X2 -> `X1 -> `X0"
+    );
+    // The error points at the usage in the file
+    let usage = content.find("`X3\n").unwrap();
+    assert_eq!(
+        error.location.as_ref().unwrap().span,
+        Some((usage, usage + 3))
+    );
+}
+
+#[test]
+fn test_recursive_macro_reports_nesting_limit() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let error = parser
+        .parse_content("`define LOOP `LOOP\nmodule test;\n`LOOP\nendmodule\n")
+        .unwrap_err();
+
+    assert_eq!(
+        error.primary_error().message,
+        "expansion of `LOOP is nested more than 64 deep: \
+         `LOOP -> `LOOP -> `LOOP -> ... 59 more -> `LOOP -> `LOOP -> `LOOP"
+    );
+}
+
+#[test]
+fn test_include_depth_limit_names_include_chain() {
+    let temp_dir = TempDir::new().unwrap();
+    create_temp_file(&temp_dir, "a.svh", "`include \"b.svh\"\n");
+    create_temp_file(&temp_dir, "b.svh", "`include \"c.svh\"\n");
+    create_temp_file(&temp_dir, "c.svh", "`define DEEP\n");
+    let content = "`include \"a.svh\"\nmodule test;\nendmodule\n";
+    let main_path = create_temp_file(&temp_dir, "main.sv", content);
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    parser.set_preprocess_limits(PreprocessLimits {
+        max_depth: 2,
+        ..PreprocessLimits::default()
+    });
+    let error = parser.parse_file(&main_path).unwrap_err();
+
+    let error = error.primary_error();
+    assert_eq!(
+        error.message,
+        "`include \"c.svh\" is nested more than 2 deep: \"a.svh\" -> \"b.svh\" -> \"c.svh\""
+    );
+    // Reported at the include in the file being parsed
+    assert_eq!(
+        error.location.as_ref().unwrap().span,
+        Some((0, "`include \"a.svh\"".len()))
+    );
+}
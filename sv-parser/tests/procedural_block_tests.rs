@@ -4,6 +4,10 @@
 mod common;
 
 use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{
+    DataType, Delay, Edge, EventControl, Expression, JoinKind, ModuleItem, Pattern, Statement,
+    TimingControl,
+};
 
 /// Ensure every procedural-block fixture parses successfully.
 #[test]
@@ -27,6 +31,19 @@ sv_ok_tests! {
     priority_casez => "procedural_blocks/priority_casez.sv",
     unique_casez => "procedural_blocks/unique_casez.sv",
     unique0_casez => "procedural_blocks/unique0_casez.sv",
+    labeled_blocks => "procedural_blocks/labeled_blocks.sv",
+    process_control => "procedural_blocks/process_control.sv",
+    case_matches => "procedural_blocks/case_matches.sv",
+    casez_items => "procedural_blocks/casez_items.sv",
+    case_inside => "procedural_blocks/case_inside.sv",
+    priority_if => "procedural_blocks/priority_if.sv",
+    unique_if => "procedural_blocks/unique_if.sv",
+    unique0_if => "procedural_blocks/unique0_if.sv",
+    if_else_reset => "procedural_blocks/if_else_reset.sv",
+    loops => "procedural_blocks/loops.sv",
+    event_controls => "procedural_blocks/event_controls.sv",
+    timing_controls => "procedural_blocks/timing_controls.sv",
+    events => "procedural_blocks/events.sv",
 }
 
 /// Example structural check to ensure we still touch the AST helpers when needed.
@@ -35,3 +52,700 @@ fn test_priority_case_structure() {
     let unit = assert_parse_ok("procedural_blocks/priority_case.sv");
     assert!(!unit.items.is_empty());
 }
+
+/// Case items keep their values and statements; `default` has no values.
+#[test]
+fn test_case_items_structure() {
+    let unit = assert_parse_ok("procedural_blocks/casez_items.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let Some(statements) =
+        items
+            .iter()
+            .find_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+                _ => None,
+            })
+    else {
+        panic!("Expected always_comb block");
+    };
+
+    let Statement::CaseStatement {
+        case_type, items, ..
+    } = unit.stmt_arena.get(statements[0])
+    else {
+        panic!("Expected case statement");
+    };
+    assert_eq!(case_type, "casez");
+    let values: Vec<Vec<&str>> = items
+        .iter()
+        .map(|item| {
+            item.values
+                .iter()
+                .map(|value| match unit.expr_arena.get(*value) {
+                    Expression::Number(text, _) => text.as_str(),
+                    other => panic!("Expected number, got {:?}", other),
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            vec!["4'b1???"],
+            vec!["4'b01??"],
+            vec!["4'b001?", "4'b0001"],
+            vec![],
+        ]
+    );
+    assert!(matches!(
+        unit.stmt_arena.get(items[2].statement),
+        Statement::Block { .. }
+    ));
+}
+
+/// Items of a `case ... inside` mix values with `[lo:hi]` value ranges.
+#[test]
+fn test_case_inside_structure() {
+    let unit = assert_parse_ok("procedural_blocks/case_inside.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let Some(statements) =
+        items
+            .iter()
+            .find_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+                _ => None,
+            })
+    else {
+        panic!("Expected always_comb block");
+    };
+
+    let Statement::CaseStatement {
+        modifier,
+        inside,
+        items,
+        ..
+    } = unit.stmt_arena.get(statements[0])
+    else {
+        panic!("Expected case statement");
+    };
+    assert_eq!(modifier.as_deref(), Some("unique"));
+    assert!(inside);
+    let number = |value| match unit.expr_arena.get(value) {
+        Expression::Number(text, _) => text.clone(),
+        other => panic!("Expected number, got {:?}", other),
+    };
+    let values: Vec<Vec<String>> = items
+        .iter()
+        .map(|item| {
+            item.values
+                .iter()
+                .map(|value| match unit.expr_arena.get(*value) {
+                    Expression::ValueRange { low, high, .. } => {
+                        format!("[{}:{}]", number(*low), number(*high))
+                    }
+                    _ => number(*value),
+                })
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        values,
+        vec![
+            vec!["[8'h00:8'h0f]".to_string()],
+            vec!["8'h10".to_string(), "[8'h20:8'h2f]".to_string()],
+            vec!["8'b1???????".to_string()],
+            vec![],
+        ]
+    );
+}
+
+/// The modifier belongs to the first `if`; each `else if` is a conditional in
+/// the else branch, and an `else` goes with the nearest `if`.
+#[test]
+fn test_unique_if_structure() {
+    let unit = assert_parse_ok("procedural_blocks/unique_if.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let Some(statements) =
+        items
+            .iter()
+            .find_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+                _ => None,
+            })
+    else {
+        panic!("Expected initial block");
+    };
+
+    let Statement::Conditional {
+        modifier,
+        else_statement: Some(else_statement),
+        ..
+    } = unit.stmt_arena.get(statements[0])
+    else {
+        panic!("Expected if with an else branch");
+    };
+    assert_eq!(modifier.as_deref(), Some("unique"));
+    let Statement::Conditional {
+        modifier: None,
+        then_statement,
+        else_statement: Some(else_statement),
+        ..
+    } = unit.stmt_arena.get(*else_statement)
+    else {
+        panic!("Expected else if");
+    };
+    assert!(matches!(
+        unit.stmt_arena.get(*then_statement),
+        Statement::Block { .. }
+    ));
+
+    let Statement::Conditional {
+        then_statement,
+        else_statement: None,
+        ..
+    } = unit.stmt_arena.get(*else_statement)
+    else {
+        panic!("Expected last else if without an else");
+    };
+    assert!(matches!(
+        unit.stmt_arena.get(*then_statement),
+        Statement::Conditional {
+            else_statement: Some(_),
+            ..
+        }
+    ));
+}
+
+/// Loop headers keep their parts: declarations and steps of a `for`, and the
+/// skipped dimensions of a `foreach`.
+#[test]
+fn test_loop_structure() {
+    let unit = assert_parse_ok("procedural_blocks/loops.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let blocks: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+            ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+            _ => None,
+        })
+        .collect();
+    let statements = blocks[0];
+
+    let Statement::ForLoop {
+        init,
+        condition: Some(_),
+        step,
+        body,
+        ..
+    } = unit.stmt_arena.get(statements[1])
+    else {
+        panic!("Expected for loop");
+    };
+    assert_eq!(init.len(), 2);
+    assert!(matches!(
+        unit.stmt_arena.get(init[1]),
+        Statement::VariableDeclaration { name, .. } if name == "j"
+    ));
+    assert_eq!(step.len(), 2);
+    assert!(matches!(
+        unit.stmt_arena.get(step[0]),
+        Statement::ExpressionStatement { .. }
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(*body),
+        Statement::Assignment { .. }
+    ));
+
+    assert!(matches!(
+        unit.stmt_arena.get(statements[3]),
+        Statement::ForLoop {
+            condition: None,
+            ..
+        }
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(statements[4]),
+        Statement::While { .. }
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(statements[6]),
+        Statement::DoWhile { .. }
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(statements[8]),
+        Statement::Repeat { .. }
+    ));
+
+    let Statement::Foreach { array, indices, .. } = unit.stmt_arena.get(statements[11]) else {
+        panic!("Expected foreach");
+    };
+    assert_eq!(array, "matrix");
+    let indices: Vec<_> = indices
+        .iter()
+        .map(|index| index.as_ref().map(|index| index.name.as_str()))
+        .collect();
+    assert_eq!(indices, vec![None, Some("j")]);
+
+    assert!(matches!(
+        unit.stmt_arena.get(blocks[1][0]),
+        Statement::Forever { .. }
+    ));
+}
+
+/// Event controls keep their edges, signals and `iff` conditions, on blocks
+/// and on statements alike.
+#[test]
+fn test_event_control_structure() {
+    let unit = assert_parse_ok("procedural_blocks/event_controls.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let blocks: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+            ModuleItem::ProceduralBlock {
+                event_control,
+                statements,
+                ..
+            } => Some((event_control, statements)),
+            _ => None,
+        })
+        .collect();
+    let signal = |expr_ref| match unit.expr_arena.get(expr_ref) {
+        Expression::Identifier(name, _) => name.as_str(),
+        _ => panic!("Expected identifier"),
+    };
+
+    let Some(EventControl::Events { events, .. }) = blocks[0].0 else {
+        panic!("Expected events");
+    };
+    let edges: Vec<_> = events
+        .iter()
+        .map(|event| (event.edge, signal(event.signal), event.iff.is_some()))
+        .collect();
+    assert_eq!(
+        edges,
+        vec![
+            (Some(Edge::Posedge), "clk", false),
+            (Some(Edge::Negedge), "rst_n", false)
+        ]
+    );
+
+    let Some(EventControl::Events { events, .. }) = blocks[1].0 else {
+        panic!("Expected events");
+    };
+    assert_eq!(events[0].iff.map(signal), Some("en"));
+    assert_eq!(events[1].edge, Some(Edge::Edge));
+    assert_eq!(signal(events[1].signal), "ready");
+
+    assert!(matches!(blocks[2].0, Some(EventControl::Implicit { .. })));
+    assert!(matches!(blocks[3].0, Some(EventControl::Implicit { .. })));
+    assert!(blocks[4].0.is_none());
+
+    let statements = blocks[4].1;
+    assert!(matches!(
+        unit.stmt_arena.get(statements[0]),
+        Statement::Timed {
+            statement: None,
+            ..
+        }
+    ));
+    let waits_for_ready = unit.stmt_arena.get(statements[1]);
+    let Statement::Timed {
+        control: TimingControl::Event(EventControl::Events { events, .. }),
+        statement: Some(statement),
+        ..
+    } = waits_for_ready
+    else {
+        panic!("Expected event-controlled statement");
+    };
+    assert_eq!(events[0].edge, None);
+    assert_eq!(signal(events[0].signal), "ready");
+    assert_eq!(waits_for_ready.children(), vec![*statement]);
+    assert!(matches!(
+        unit.stmt_arena.get(*statement),
+        Statement::Assignment { .. }
+    ));
+    assert!(matches!(
+        unit.stmt_arena.get(statements[2]),
+        Statement::Timed {
+            statement: Some(_),
+            ..
+        }
+    ));
+}
+
+/// Delays, event controls and `wait` all prefix a statement, or stand alone
+/// with just a `;`.
+#[test]
+fn test_timing_control_structure() {
+    let unit = assert_parse_ok("procedural_blocks/timing_controls.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let blocks: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+            ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+            _ => None,
+        })
+        .collect();
+
+    let Statement::Forever { body, .. } = unit.stmt_arena.get(blocks[0][0]) else {
+        panic!("Expected forever");
+    };
+    assert!(matches!(
+        unit.stmt_arena.get(*body),
+        Statement::Timed {
+            control: TimingControl::Delay(Delay::Value(value)),
+            statement: Some(_),
+            ..
+        } if value == "5"
+    ));
+
+    let timed: Vec<_> = blocks[1]
+        .iter()
+        .filter_map(|stmt_ref| match unit.stmt_arena.get(*stmt_ref) {
+            Statement::Timed {
+                control, statement, ..
+            } => Some((control, statement.is_some())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(timed.len(), 5);
+    assert!(matches!(timed[0], (TimingControl::Delay(_), true)));
+    assert!(matches!(timed[1], (TimingControl::Event(_), true)));
+    assert!(matches!(timed[2], (TimingControl::Wait(_), true)));
+    assert!(matches!(timed[3], (TimingControl::Wait(_), false)));
+    assert!(matches!(timed[4], (TimingControl::Delay(_), false)));
+
+    let TimingControl::Wait(condition) = timed[3].0 else {
+        panic!("Expected wait");
+    };
+    assert!(matches!(
+        unit.expr_arena.get(*condition),
+        Expression::Binary { .. }
+    ));
+
+    let Statement::Repeat { body, .. } = unit.stmt_arena.get(blocks[1][5]) else {
+        panic!("Expected repeat");
+    };
+    assert!(matches!(
+        unit.stmt_arena.get(*body),
+        Statement::Timed {
+            control: TimingControl::Event(_),
+            statement: None,
+            ..
+        }
+    ));
+}
+
+/// Every statement lists the statements nested directly in it, in source
+/// order.
+#[test]
+fn test_statement_children() {
+    let unit = assert_parse_ok("procedural_blocks/loops.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let Some(statements) =
+        items
+            .iter()
+            .find_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+                _ => None,
+            })
+    else {
+        panic!("Expected initial block");
+    };
+
+    let for_loop = unit.stmt_arena.get(statements[1]);
+    let Statement::ForLoop {
+        init, step, body, ..
+    } = for_loop
+    else {
+        panic!("Expected for loop");
+    };
+    let expected: Vec<_> = init
+        .iter()
+        .chain(step)
+        .chain(std::iter::once(body))
+        .copied()
+        .collect();
+    assert_eq!(for_loop.children(), expected);
+
+    let Statement::While { body, .. } = unit.stmt_arena.get(statements[4]) else {
+        panic!("Expected while loop");
+    };
+    assert_eq!(unit.stmt_arena.get(statements[4]).children(), vec![*body]);
+    let Statement::Block {
+        statements: inner, ..
+    } = unit.stmt_arena.get(*body)
+    else {
+        panic!("Expected begin/end body");
+    };
+    assert_eq!(unit.stmt_arena.get(*body).children(), *inner);
+    assert!(unit.stmt_arena.get(inner[0]).children().is_empty());
+}
+
+/// Block names, end labels and statement labels are kept in the AST.
+#[test]
+fn test_labeled_blocks_structure() {
+    let unit = assert_parse_ok("procedural_blocks/labeled_blocks.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+
+    let blocks: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+            ModuleItem::ProceduralBlock {
+                label,
+                end_label,
+                statements,
+                ..
+            } => Some((label, end_label, statements)),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(blocks.len(), 2);
+
+    let (label, end_label, statements) = blocks[0];
+    assert_eq!(label.as_ref().map(|l| l.name.as_str()), Some("init_blk"));
+    assert_eq!(
+        end_label.as_ref().map(|l| l.name.as_str()),
+        Some("init_blk")
+    );
+    assert_eq!(statements.len(), 4);
+
+    let Statement::Block {
+        label, end_label, ..
+    } = unit.stmt_arena.get(statements[1])
+    else {
+        panic!("Expected nested block");
+    };
+    assert_eq!(label.as_ref().map(|l| l.name.as_str()), Some("inner"));
+    assert_eq!(end_label.as_ref().map(|l| l.name.as_str()), Some("inner"));
+
+    let Statement::Labeled {
+        label, statement, ..
+    } = unit.stmt_arena.get(statements[2])
+    else {
+        panic!("Expected labeled assertion");
+    };
+    assert_eq!(label.name, "check_a");
+    assert!(matches!(
+        unit.stmt_arena.get(*statement),
+        Statement::AssertProperty { .. }
+    ));
+
+    let (label, end_label, statements) = blocks[1];
+    assert!(label.is_none() && end_label.is_none());
+    let Statement::Labeled {
+        label, statement, ..
+    } = unit.stmt_arena.get(statements[0])
+    else {
+        panic!("Expected labeled block");
+    };
+    assert_eq!(label.name, "named");
+    assert!(matches!(
+        unit.stmt_arena.get(*statement),
+        Statement::Block {
+            label: None,
+            end_label: Some(_),
+            ..
+        }
+    ));
+}
+
+/// Fork control statements and process handle calls are parsed into the AST.
+#[test]
+fn test_process_control_structure() {
+    let unit = assert_parse_ok("procedural_blocks/process_control.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let Some(statements) =
+        items
+            .iter()
+            .find_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+                _ => None,
+            })
+    else {
+        panic!("Expected initial block");
+    };
+    let statements: Vec<_> = statements
+        .iter()
+        .map(|stmt_ref| unit.stmt_arena.get(*stmt_ref))
+        .collect();
+    assert_eq!(statements.len(), 7);
+
+    let Statement::Assignment { expr, .. } = statements[1] else {
+        panic!("Expected assignment from process::self()");
+    };
+    let Expression::FunctionCall { function, .. } = unit.expr_arena.get(*expr) else {
+        panic!("Expected call");
+    };
+    let Expression::ScopedIdentifier { scope, name, .. } = unit.expr_arena.get(*function) else {
+        panic!("Expected scoped name");
+    };
+    assert_eq!((scope.as_str(), name.as_str()), ("process", "self"));
+
+    let Statement::Fork {
+        label,
+        statements: processes,
+        join,
+        end_label,
+        ..
+    } = statements[2]
+    else {
+        panic!("Expected fork block");
+    };
+    assert_eq!(
+        label.as_ref().map(|label| label.name.as_str()),
+        Some("workers")
+    );
+    assert_eq!(processes.len(), 2);
+    assert_eq!(*join, JoinKind::JoinNone);
+    assert!(end_label.is_some());
+
+    assert!(matches!(statements[3], Statement::WaitFork { .. }));
+    assert!(matches!(statements[4], Statement::DisableFork { .. }));
+    let Statement::Disable { target, .. } = statements[6] else {
+        panic!("Expected disable of a named block");
+    };
+    assert_eq!(target, "watchdog");
+}
+
+/// Tagged union values and `case ... matches` arms are parsed into dedicated nodes.
+#[test]
+fn test_case_matches_structure() {
+    let unit = assert_parse_ok("procedural_blocks/case_matches.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::TypedefDeclaration {
+        data_type: DataType::Union {
+            tagged, members, ..
+        },
+        name,
+        ..
+    } = unit.module_item_arena.get(items[0])
+    else {
+        panic!("Expected tagged union typedef");
+    };
+    assert!(tagged);
+    assert_eq!(name, "VInt");
+    let members: Vec<_> = members
+        .iter()
+        .map(|member| (member.data_type.name(), member.name.as_str()))
+        .collect();
+    assert_eq!(members, vec![("void", "Invalid"), ("int", "Valid")]);
+
+    let Some(statements) =
+        items
+            .iter()
+            .find_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+                ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+                _ => None,
+            })
+    else {
+        panic!("Expected initial block");
+    };
+
+    let Statement::Assignment { expr, .. } = unit.stmt_arena.get(statements[0]) else {
+        panic!("Expected assignment of a tagged value");
+    };
+    let Expression::TaggedUnion { member, value, .. } = unit.expr_arena.get(*expr) else {
+        panic!("Expected tagged union expression");
+    };
+    assert_eq!(member, "Valid");
+    assert!(matches!(
+        unit.expr_arena.get(value.unwrap()),
+        Expression::Number(n, _) if n == "5"
+    ));
+
+    let Statement::CaseMatches { items, .. } = unit.stmt_arena.get(statements[2]) else {
+        panic!("Expected case matches statement");
+    };
+    assert_eq!(items.len(), 4);
+
+    let Some(Pattern::Tagged {
+        member, pattern, ..
+    }) = &items[0].pattern
+    else {
+        panic!("Expected tagged pattern");
+    };
+    assert_eq!(member, "Valid");
+    assert!(matches!(pattern.as_deref(), Some(Pattern::Variable(name, _)) if name == "n"));
+    assert!(items[0].guard.is_some());
+    assert!(matches!(
+        unit.stmt_arena.get(items[0].statement),
+        Statement::Assignment { .. }
+    ));
+
+    assert!(matches!(
+        &items[1].pattern,
+        Some(Pattern::Tagged { pattern: None, .. })
+    ));
+    assert!(matches!(&items[2].pattern, Some(Pattern::Wildcard(_))));
+    assert!(items[3].pattern.is_none());
+}
+
+/// `->` and `->>` trigger an event that `@` and `.triggered` wait on.
+#[test]
+fn test_event_trigger_structure() {
+    let unit = assert_parse_ok("procedural_blocks/events.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let events: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+            ModuleItem::VariableDeclaration {
+                data_type, name, ..
+            } => Some((data_type.name(), name.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(events, vec![("event", "start"), ("event", "done")]);
+
+    let triggers: Vec<_> = items
+        .iter()
+        .filter_map(|item_ref| match unit.module_item_arena.get(*item_ref) {
+            ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|stmt_ref| match unit.stmt_arena.get(*stmt_ref) {
+            Statement::EventTrigger {
+                nonblocking, event, ..
+            } => match unit.expr_arena.get(*event) {
+                Expression::Identifier(name, _) => Some((*nonblocking, name.as_str())),
+                expr => panic!("Expected event name, got {:?}", expr),
+            },
+            _ => None,
+        })
+        .collect();
+    assert_eq!(triggers, vec![(false, "start"), (true, "done")]);
+}
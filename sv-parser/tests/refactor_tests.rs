@@ -0,0 +1,251 @@
+//! Refactoring tests
+
+use std::collections::HashMap;
+use std::path::Path;
+use sv_parser::refactor::{
+    extract_package, port_owner, procedural_block_at, rename_port_connections, split_always_block,
+};
+use sv_parser::{SystemVerilogParser, TextEdit};
+
+fn split(content: &str) -> Option<TextEdit> {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    let offset = content.find("always").unwrap();
+    let block = procedural_block_at(&ast, offset).expect("Expected a procedural block");
+    split_always_block(&ast, content, block)
+}
+
+fn apply(content: &str, edit: &TextEdit) -> String {
+    let chars: Vec<char> = content.chars().collect();
+    let mut result: String = chars[..edit.span.0].iter().collect();
+    result.push_str(&edit.new_text);
+    result.extend(&chars[edit.span.1..]);
+    result
+}
+
+#[test]
+fn test_split_always_block_by_driven_variable() {
+    let content = r#"module top(input logic a, input logic b);
+    logic x, y, tmp;
+    always_comb begin
+        tmp = a + b;
+        x = tmp;
+        y = b;
+        $display("%0d", y);
+    end
+endmodule
+"#;
+    let edit = split(content).expect("Expected the block to be split");
+    assert_eq!(
+        apply(content, &edit),
+        r#"module top(input logic a, input logic b);
+    logic x, y, tmp;
+    always_comb begin
+        tmp = a + b;
+        x = tmp;
+    end
+
+    always_comb begin
+        y = b;
+        $display("%0d", y);
+    end
+endmodule
+"#
+    );
+}
+
+#[test]
+fn test_split_keeps_event_control() {
+    let content = r#"module top(input logic clk, input logic d);
+    logic q1, q2;
+    always @(posedge clk) begin
+        q1 = d;
+        q2 = d;
+    end
+endmodule
+"#;
+    let edit = split(content).unwrap();
+    assert_eq!(
+        edit.new_text,
+        "always @(posedge clk) begin\n        q1 = d;\n    end\n\n    always @(posedge clk) begin\n        q2 = d;\n    end"
+    );
+}
+
+#[test]
+fn test_no_split_when_statements_are_related() {
+    // Both statements drive x
+    let content = r#"module top(input logic a);
+    logic x;
+    always_comb begin
+        x = 0;
+        x = a;
+    end
+endmodule
+"#;
+    assert_eq!(split(content), None);
+
+    // Named blocks keep their name, so they are not split
+    let content = r#"module top(input logic a);
+    logic x, y;
+    always_comb begin : drive
+        x = a;
+        y = a;
+    end
+endmodule
+"#;
+    assert_eq!(split(content), None);
+}
+
+/// Apply edits given in source order
+fn apply_all<'a>(content: &str, edits: impl Iterator<Item = &'a TextEdit>) -> String {
+    let mut edits: Vec<&TextEdit> = edits.collect();
+    edits.reverse();
+    edits
+        .into_iter()
+        .fold(content.to_string(), |text, edit| apply(&text, edit))
+}
+
+#[test]
+fn test_extract_package_from_repeated_localparams() {
+    let alu = r#"module alu(input logic [7:0] a, output logic [7:0] y);
+    localparam WIDTH = 8;
+    localparam DEPTH = WIDTH * 2;
+    localparam MODE = 1;
+    assign y = a;
+endmodule
+"#;
+    let fifo = r#"module fifo(input logic clk);
+    localparam  WIDTH = 8;
+    localparam MODE = 2;
+    localparam DEPTH = WIDTH * 2;
+endmodule
+
+module regs(input logic clk);
+    localparam MODE = 2;
+endmodule
+"#;
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let alu_ast = parser.parse_content(alu).unwrap();
+    let fifo_ast = parser.parse_content(fifo).unwrap();
+    let extraction = extract_package(
+        "shared_pkg",
+        &[
+            (Path::new("alu.sv"), &alu_ast, alu),
+            (Path::new("fifo.sv"), &fifo_ast, fifo),
+        ],
+    )
+    .expect("Expected shared declarations");
+
+    assert_eq!(
+        extraction.package,
+        "package shared_pkg;\n  localparam WIDTH = 8;\n  localparam DEPTH = WIDTH * 2;\n  localparam MODE = 2;\nendpackage\n"
+    );
+    assert_eq!(extraction.names, vec!["WIDTH", "DEPTH", "MODE"]);
+
+    let edits_for = |file: &'static str| {
+        extraction
+            .edits
+            .iter()
+            .filter(move |(path, _)| path == Path::new(file))
+            .map(|(_, edit)| edit)
+    };
+    // MODE = 1 is alu's own definition, which wins over the import
+    assert_eq!(
+        apply_all(alu, edits_for("alu.sv")),
+        r#"module alu(input logic [7:0] a, output logic [7:0] y);
+    import shared_pkg::*;
+    localparam MODE = 1;
+    assign y = a;
+endmodule
+"#
+    );
+    assert_eq!(
+        apply_all(fifo, edits_for("fifo.sv")),
+        r#"module fifo(input logic clk);
+    import shared_pkg::*;
+endmodule
+
+module regs(input logic clk);
+    import shared_pkg::*;
+endmodule
+"#
+    );
+}
+
+#[test]
+fn test_extract_package_keeps_declarations_reading_local_names() {
+    let content = r#"module a;
+    localparam BASE = 1;
+    localparam TOP = BASE + 4;
+endmodule
+
+module b;
+    localparam BASE = 2;
+    localparam TOP = BASE + 4;
+endmodule
+"#;
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    assert!(extract_package("pkg", &[(Path::new("a.sv"), &ast, content)]).is_none());
+}
+
+#[test]
+fn test_extract_package_moves_typedefs_with_their_parameters() {
+    let content = r#"module a;
+    localparam W = 8;
+    typedef logic [W:0] word_t;
+    typedef enum { IDLE, BUSY } state_t;
+endmodule
+
+module b;
+    localparam W = 8;
+    typedef logic [W:0] word_t;
+    typedef enum { IDLE, DONE } state_t;
+endmodule
+"#;
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+    let extraction = extract_package("pkg", &[(Path::new("a.sv"), &ast, content)]).unwrap();
+    assert_eq!(extraction.names, vec!["W", "word_t"]);
+    assert_eq!(
+        extraction.package,
+        "package pkg;\n  localparam W = 8;\n  typedef logic [W:0] word_t;\nendpackage\n"
+    );
+}
+
+#[test]
+fn test_rename_port_connections() {
+    let content = r#"module alu(input logic [7:0] a, output logic [7:0] y);
+    assign y = a;
+endmodule
+
+module top;
+    logic [7:0] a, y;
+    alu u_explicit (.a(a), .y(y));
+    alu u_implicit (.a, .y);
+    generate
+        if (1) begin : g
+            alu u_nested (.a(a), .y());
+        end
+    endgenerate
+endmodule
+"#;
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser.parse_content(content).unwrap();
+
+    let assign = content.find("assign").unwrap();
+    assert_eq!(port_owner(&ast, assign, "a"), Some("alu"));
+    assert_eq!(port_owner(&ast, assign, "b"), None);
+    assert_eq!(
+        port_owner(&ast, content.find("u_explicit").unwrap(), "a"),
+        None
+    );
+
+    let edits = rename_port_connections(&ast, "alu", "a", "operand");
+    assert_eq!(
+        apply_all(content, edits.iter()),
+        content
+            .replace(".a(a)", ".operand(a)")
+            .replace(".a,", ".operand(a),")
+    );
+}
@@ -0,0 +1,112 @@
+//! Package import resolution tests.
+
+use std::collections::HashMap;
+use sv_parser::resolve::PackageExports;
+use sv_parser::{Import, ImportScope, Resolution};
+
+fn packages() -> PackageExports {
+    let mut packages = PackageExports::new();
+    packages.insert(
+        "bus_pkg".to_string(),
+        HashMap::from([
+            ("WIDTH".to_string(), (10, 15)),
+            ("addr_t".to_string(), (30, 36)),
+        ]),
+    );
+    packages.insert(
+        "cpu_pkg".to_string(),
+        HashMap::from([
+            ("WIDTH".to_string(), (50, 55)),
+            ("opcode_t".to_string(), (70, 78)),
+        ]),
+    );
+    packages
+}
+
+fn import(package: &str, item: Option<&str>, at: usize) -> Import {
+    Import {
+        package: package.to_string(),
+        item: item.map(str::to_string),
+        span: (at, at + 10),
+    }
+}
+
+#[test]
+fn test_wildcard_imports_conflict_only_when_used() {
+    let packages = packages();
+    let mut scope = ImportScope::new();
+    scope.add_import(import("bus_pkg", None, 0));
+    scope.add_import(import("cpu_pkg", None, 20));
+
+    assert!(scope.check_imports(&packages).is_empty());
+    assert!(matches!(
+        scope.resolve("addr_t", &packages),
+        Resolution::Imported {
+            package: "bus_pkg",
+            declaration: (30, 36),
+            ..
+        }
+    ));
+    assert_eq!(
+        scope.resolve("WIDTH", &packages),
+        Resolution::Ambiguous(vec!["bus_pkg", "cpu_pkg"])
+    );
+    assert_eq!(
+        scope.check_reference("WIDTH", (100, 105), &packages).unwrap().message,
+        "'WIDTH' is ambiguous: it is imported with wildcards from 'bus_pkg' and 'cpu_pkg'; import it explicitly"
+    );
+    assert_eq!(scope.resolve("missing", &packages), Resolution::Unresolved);
+}
+
+#[test]
+fn test_explicit_import_and_local_declaration_win() {
+    let packages = packages();
+    let mut scope = ImportScope::new();
+    scope.add_import(import("bus_pkg", None, 0));
+    scope.add_import(import("cpu_pkg", None, 20));
+    scope.add_import(import("cpu_pkg", Some("WIDTH"), 40));
+
+    let Resolution::Imported {
+        package,
+        declaration,
+        import,
+    } = scope.resolve("WIDTH", &packages)
+    else {
+        panic!("Expected WIDTH to resolve");
+    };
+    assert_eq!((package, declaration), ("cpu_pkg", (50, 55)));
+    assert_eq!(import.span, (40, 50));
+    assert!(scope
+        .check_reference("WIDTH", (100, 105), &packages)
+        .is_none());
+
+    scope.add_local("addr_t");
+    assert_eq!(scope.resolve("addr_t", &packages), Resolution::Local);
+}
+
+#[test]
+fn test_check_imports() {
+    let packages = packages();
+    let mut scope = ImportScope::new();
+    scope.add_import(import("bus_pkg", Some("WIDTH"), 0));
+    scope.add_import(import("cpu_pkg", Some("WIDTH"), 20));
+    scope.add_import(import("cpu_pkg", Some("opcode_t"), 40));
+    scope.add_import(import("cpu_pkg", Some("nothing"), 60));
+    scope.add_import(import("no_pkg", None, 80));
+    scope.add_local("opcode_t");
+
+    let messages: Vec<_> = scope
+        .check_imports(&packages)
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "'WIDTH' is already imported from 'bus_pkg'",
+            "'opcode_t' is imported from 'cpu_pkg' but also declared in this scope",
+            "'nothing' is not declared in package 'cpu_pkg'",
+            "Unknown package 'no_pkg'",
+        ]
+    );
+}
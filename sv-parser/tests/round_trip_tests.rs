@@ -0,0 +1,131 @@
+//! Printing parsed fixtures and parsing the output again must give the same tree.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use sv_parser::printer::{print, print_expression, round_trip};
+use sv_parser::testing::for_each_fixture;
+use sv_parser::{ModuleItem, RoundTripError, SystemVerilogParser};
+
+/// Fixture directories left out: files that must not parse, headers and
+/// preprocessor input, and constructs the printer doesn't write back
+const SKIPPED: &[&str] = &["checkers", "errors", "headers", "preprocessor", "timescale"];
+
+/// Every fixture that parses survives printing and parsing again.
+#[test]
+fn test_fixtures_round_trip() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let root = common::default_fixtures_root();
+    let mut failures = Vec::new();
+    for_each_fixture(&parser, &root, |path, _, result| {
+        let relative = path.strip_prefix(&root).unwrap();
+        if SKIPPED.iter().any(|dir| relative.starts_with(dir)) {
+            return;
+        }
+        let Ok(ast) = result else {
+            return;
+        };
+        if let Err(err) = round_trip(&parser, &ast, false) {
+            failures.push(format!("{}:\n{}\n{}", path.display(), err, print(&ast)));
+        }
+    });
+    assert!(failures.is_empty(), "{}", failures.join("\n\n"));
+}
+
+#[test]
+fn test_print_keeps_structure() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"module top(input logic [7:0] a, output b);
+    wire #2 w = a & ~(b | a);
+    assign b = s.valid ? (a + 1) * 2 : \begin ;
+    always_ff @(posedge clk) begin : update
+        q <= d;
+        count += 1;
+    end : update
+endmodule
+"#;
+    let ast = parser.parse_content(content).unwrap();
+    assert_eq!(
+        print(&ast),
+        r#"module top(input logic [7:0] a, output b);
+    wire #2 w = a & ~(b | a);
+    assign b = s.valid ? (a + 1) * 2 : \begin ;
+    always_ff @(posedge clk) begin : update
+        q <= d;
+        count += 1;
+    end : update
+endmodule
+"#
+    );
+    assert!(round_trip(&parser, &ast, false).is_ok());
+}
+
+#[test]
+fn test_print_expression() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let ast = parser
+        .parse_content("module top;\n    assign y = (a+b)*c ? f(x,1) : d-1;\nendmodule")
+        .unwrap();
+    let ModuleItem::Assignment { target, expr, .. } = ast.module_item_arena.get(0) else {
+        panic!("Expected assignment");
+    };
+    assert_eq!(print_expression(&ast, *target), "y");
+    assert_eq!(
+        print_expression(&ast, *expr),
+        "(a + b) * c ? f(x, 1) : d - 1"
+    );
+}
+
+/// Parentheses are printed only where precedence or grouping needs them.
+#[test]
+fn test_print_expression_parentheses() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    for (text, printed) in [
+        ("(a + b) + c", "a + b + c"),
+        ("a + (b + c)", "a + (b + c)"),
+        ("a - (b - c)", "a - (b - c)"),
+        ("(a * b) + (c * d)", "a * b + c * d"),
+        ("(a | b) & c", "(a | b) & c"),
+        ("(a -> b) -> c", "(a -> b) -> c"),
+        ("a -> (b -> c)", "a -> b -> c"),
+        ("(a -> b) ? c : (d -> e)", "(a -> b) ? c : (d -> e)"),
+        ("(a ? b : c) + d", "(a ? b : c) + d"),
+    ] {
+        let ast = parser
+            .parse_content(&format!("module top;\n    assign y = {};\nendmodule", text))
+            .unwrap();
+        let ModuleItem::Assignment { expr, .. } = ast.module_item_arena.get(0) else {
+            panic!("Expected assignment");
+        };
+        assert_eq!(print_expression(&ast, *expr), printed, "{}", text);
+    }
+}
+
+#[test]
+fn test_divergence_names_the_node() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let mut ast = parser
+        .parse_content("module top;\n    assign a = b;\nendmodule")
+        .unwrap();
+    // Only a net declaration makes an implicit assignment, so this one is
+    // printed as an `assign` and comes back explicit
+    if let ModuleItem::Assignment { implicit, .. } = ast.module_item_arena.get_mut(0) {
+        *implicit = true;
+    }
+
+    let Err(RoundTripError::Diverged(divergences)) = round_trip(&parser, &ast, false) else {
+        panic!("Expected the trees to differ");
+    };
+    assert_eq!(divergences.len(), 1);
+    assert_eq!(divergences[0].node, "module item 0");
+    assert!(divergences[0]
+        .original
+        .as_ref()
+        .is_some_and(|node| node.contains("implicit: true")));
+    assert!(divergences[0]
+        .reparsed
+        .as_ref()
+        .is_some_and(|node| node.contains("implicit: false")));
+}
@@ -3,7 +3,9 @@
 //! Tests for semantic validation that goes beyond syntax checking
 
 use std::collections::HashMap;
-use sv_parser::{SemanticErrorType, SystemVerilogParser};
+use sv_parser::{
+    AssertionClock, Expression, SemanticAnalyzer, SemanticErrorType, SystemVerilogParser,
+};
 
 #[test]
 fn test_unknown_system_function() {
@@ -152,6 +154,71 @@ endmodule
     assert_eq!(errors.len(), 0, "All math functions should be valid");
 }
 
+#[test]
+fn test_file_io_calls() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic [7:0] mem [0:15];
+    integer fd, status;
+    string line;
+    initial begin
+        fd = $fopen("data.txt", "r+");
+        while (!$feof(fd)) status = $fgets(line, fd);
+        status = $fscanf(fd, "%h", mem);
+        $fclose(fd);
+        $readmemh("init.hex", top.mem, 0, 15);
+        $writememb("dump.bin", $root.top.mem);
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn test_invalid_file_io_arguments() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic [7:0] mem [0:15];
+    integer fd;
+    initial begin
+        fd = $fopen("data.txt", "rw");
+        $fclose(fd, 1);
+        $fclose("data.txt");
+        $readmemh("init.hex");
+        $readmemh(16, mem);
+        $readmemh("init.hex", 0);
+        $fdisplay;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert!(errors
+        .iter()
+        .all(|error| error.error_type == SemanticErrorType::InvalidSystemCallArguments));
+    let messages: Vec<&str> = errors.iter().map(|error| error.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "'rw' is not a file mode for $fopen",
+            "$fclose takes 1 argument, not 2",
+            "Argument 1 of $fclose is a file descriptor, not a string",
+            "$readmemh takes 2 to 4 arguments, not 1",
+            "Argument 1 of $readmemh is a file name, not a number",
+            "Argument 2 of $readmemh must name a variable",
+            "$fdisplay takes at least 1 argument, not 0",
+        ]
+    );
+}
+
 #[test]
 fn test_nested_expressions_with_errors() {
     let parser = SystemVerilogParser::new(vec![], HashMap::new());
@@ -213,3 +280,1089 @@ endclass
     assert_eq!(errors.len(), 1);
     assert!(errors[0].message.contains("invalid_func"));
 }
+
+#[test]
+fn test_matching_block_labels() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a;
+    initial begin : outer
+        begin : inner
+            a = 1;
+        end : inner
+        named: begin
+            a = 0;
+        end : named
+    end : outer
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn test_mismatched_end_label() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a;
+    initial begin : outer
+        begin : inner
+            a = 1;
+        end : innr
+    end : outer
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_type, SemanticErrorType::LabelMismatch);
+    assert!(errors[0].message.contains("'innr'"));
+    assert!(errors[0].message.contains("'inner'"));
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "innr");
+}
+
+#[test]
+fn test_mismatched_join_label() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a;
+    initial begin
+        fork : workers
+            a = 1;
+        join_any : worker
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_type, SemanticErrorType::LabelMismatch);
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "worker");
+}
+
+#[test]
+fn test_end_label_on_unnamed_block() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a;
+    always_comb begin
+        a = 1;
+    end : blk
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_type, SemanticErrorType::LabelMismatch);
+    assert!(errors[0].message.contains("unnamed block"));
+}
+
+#[test]
+fn test_statement_label_names_block() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a;
+    initial begin
+        named: begin
+            a = 0;
+        end : other
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_type, SemanticErrorType::LabelMismatch);
+    assert!(errors[0].message.contains("'named'"));
+}
+
+#[test]
+fn test_assertion_declaration_end_label() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top(input logic clk, input logic req, input logic gnt);
+    sequence request;
+        req ##1 gnt;
+    endsequence : request
+    property handshake;
+        @(posedge clk) $rose(req) |=> gnt;
+    endproperty : handshak
+    assert property (handshake) else $error("no grant");
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].error_type, SemanticErrorType::LabelMismatch);
+    assert_eq!(
+        errors[0].message,
+        "End label 'handshak' does not match block name 'handshake'"
+    );
+}
+
+#[test]
+fn test_implements_requires_interface_class() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+interface class printable;
+endclass
+
+class base;
+endclass
+
+class good implements printable;
+endclass
+
+class bad implements base;
+endclass
+
+class worse extends printable;
+endclass
+
+class external implements defined_elsewhere;
+endclass
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(errors
+        .iter()
+        .all(|e| e.error_type == SemanticErrorType::InvalidInheritance));
+    assert!(errors[0]
+        .message
+        .contains("'base' is not an interface class"));
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "bad");
+    assert!(errors[1]
+        .message
+        .contains("cannot extend interface class 'printable'"));
+}
+
+#[test]
+fn test_assertions_use_module_defaults() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a, b, clk, rst;
+    assert property (a);
+    assert property (@(posedge clk) disable iff (b) a);
+    default disable iff (rst);
+    default clocking cb;
+endmodule
+
+module other();
+    logic a;
+    assert property (a);
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let mut analyzer = SemanticAnalyzer::new();
+    let errors = analyzer.analyze(&ast);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+
+    let contexts = analyzer.assertion_contexts();
+    assert_eq!(contexts.len(), 3);
+
+    // Defaults apply even to assertions written before them
+    assert_eq!(
+        contexts[0].clock,
+        Some(AssertionClock::Default("cb".to_string()))
+    );
+    let default_disable = contexts[0].disable_iff.expect("default disable iff");
+    assert!(matches!(
+        ast.expr_arena.get(default_disable),
+        Expression::Identifier(name, _) if name == "rst"
+    ));
+
+    // Explicit clock and disable iff win over the defaults
+    assert!(matches!(
+        contexts[1].clock,
+        Some(AssertionClock::Explicit(_))
+    ));
+    assert!(matches!(
+        ast.expr_arena.get(contexts[1].disable_iff.unwrap()),
+        Expression::Identifier(name, _) if name == "b"
+    ));
+
+    // Defaults do not leak into other modules
+    assert_eq!(contexts[2].clock, None);
+    assert_eq!(contexts[2].disable_iff, None);
+}
+
+#[test]
+fn test_duplicate_default_clocking() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    default clocking cb;
+    default clocking cb2;
+    default disable iff (rst);
+    default disable iff (rst_n);
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert!(errors
+        .iter()
+        .all(|e| e.error_type == SemanticErrorType::DuplicateDeclaration));
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "cb2");
+}
+
+#[test]
+fn test_assignment_to_input_and_parameter() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top(input logic a, output logic b);
+    parameter P = 3;
+    input logic c;
+    assign a = b;
+    initial begin
+        P = 1;
+        c = 0;
+        b = a;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::AssignmentToInput);
+    assert_eq!(errors[0].message, "Cannot assign to input port 'a'");
+    let related = errors[0].related.as_ref().expect("declaration note");
+    assert_eq!(&content[related.span.0..related.span.1], "a");
+
+    assert_eq!(
+        errors[1].error_type,
+        SemanticErrorType::AssignmentToParameter
+    );
+    let related = errors[1].related.as_ref().expect("declaration note");
+    assert_eq!(&content[related.span.0..related.span.1], "P");
+
+    // Inputs declared in the module body count too
+    assert_eq!(errors[2].error_type, SemanticErrorType::AssignmentToInput);
+    assert!(errors[2].message.contains("'c'"));
+}
+
+#[test]
+fn test_increment_of_nets_and_inputs() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top(input logic clk, output wire done);
+    wire ready;
+    int count;
+    initial begin
+        count++;
+        ready++;
+        --done;
+        clk++;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::IncDecOnNet);
+    assert_eq!(
+        errors[0].message,
+        "Cannot increment or decrement net 'ready'"
+    );
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "ready");
+    let related = errors[0].related.as_ref().expect("declaration note");
+    assert_eq!(&content[related.span.0..related.span.1], "ready");
+
+    // Net ports count too
+    assert_eq!(errors[1].error_type, SemanticErrorType::IncDecOnNet);
+    assert!(errors[1].message.contains("'done'"));
+
+    // An input can't be written at all
+    assert_eq!(errors[2].error_type, SemanticErrorType::AssignmentToInput);
+    assert_eq!(&content[errors[2].span.0..errors[2].span.1], "clk");
+}
+
+#[test]
+fn test_package_parameters_and_members() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+package cfg_pkg;
+    parameter WIDTH = 8;
+    logic enable;
+endpackage
+
+module top;
+    import cfg_pkg::*;
+    import cfg_pkg::DEPTH;
+    logic enable;
+    initial begin
+        WIDTH = 4;
+        cfg_pkg::WIDTH = 4;
+        enable = cfg_pkg::enabel;
+        other_pkg::X = 1;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 4, "unexpected errors: {:?}", errors);
+    assert_eq!(
+        errors[0].error_type,
+        SemanticErrorType::UndeclaredIdentifier
+    );
+    assert_eq!(errors[0].message, "Package 'cfg_pkg' has no member 'DEPTH'");
+
+    // Imported or named through its package, a parameter is still a parameter
+    for error in &errors[1..3] {
+        assert_eq!(error.error_type, SemanticErrorType::AssignmentToParameter);
+        let related = error.related.as_ref().expect("declaration note");
+        assert_eq!(&content[related.span.0..related.span.1], "WIDTH");
+    }
+    assert_eq!(
+        errors[2].message,
+        "Cannot assign to parameter 'cfg_pkg::WIDTH'"
+    );
+
+    // `other_pkg` is declared elsewhere, so only members of `cfg_pkg` are checked
+    assert_eq!(
+        errors[3].error_type,
+        SemanticErrorType::UndeclaredIdentifier
+    );
+    assert_eq!(
+        errors[3].message,
+        "Package 'cfg_pkg' has no member 'enabel'; did you mean 'enable'?"
+    );
+}
+
+#[test]
+fn test_assignment_to_literal_and_call() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic a;
+    initial begin
+        5 = a;
+        f(a) = 1;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert_eq!(errors[0].error_type, SemanticErrorType::AssignmentToLiteral);
+    assert_eq!(&content[errors[0].span.0..errors[0].span.1], "5");
+    assert_eq!(errors[1].error_type, SemanticErrorType::AssignmentToCall);
+    assert!(errors.iter().all(|e| e.related.is_none()));
+}
+
+#[test]
+fn test_input_names_are_scoped_to_their_module() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module producer(input logic a);
+endmodule
+
+module consumer();
+    logic a;
+    assign a = 1;
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn test_errors_are_reported_in_source_order() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    // Module defaults are checked before the rest of the module, but the
+    // label mismatch comes first in the file
+    let content = r#"
+module top();
+    initial begin : a
+    end : b
+    default clocking cb;
+    default clocking cb2;
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let types: Vec<_> = errors.iter().map(|e| e.error_type).collect();
+    assert_eq!(
+        types,
+        vec![
+            SemanticErrorType::LabelMismatch,
+            SemanticErrorType::DuplicateDeclaration
+        ]
+    );
+}
+
+#[test]
+fn test_this_and_super_inside_class_methods() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+class base;
+    int x;
+    function new(a);
+        this.x = a;
+    endfunction
+    virtual function void show();
+        $display("%0d", this.x);
+    endfunction
+endclass
+
+class derived extends base;
+    function new(a);
+        super.new(a);
+        this.x = a + 1;
+    endfunction
+    function void show();
+        super.show();
+    endfunction
+endclass
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn test_invalid_this_and_super() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic x;
+    initial begin
+        this.x = 1;
+    end
+endmodule
+
+class lone;
+    function void f();
+        super.f();
+    endfunction
+endclass
+
+class late extends lone;
+    function new();
+        $display("start");
+        super.new();
+    endfunction
+    function void g();
+        super.new;
+    endfunction
+    static function void h();
+        this.g();
+    endfunction
+endclass
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let text: String = content
+                .chars()
+                .skip(e.span.0)
+                .take(e.span.1 - e.span.0)
+                .collect();
+            (e.error_type, text, e.message.as_str())
+        })
+        .collect();
+    let invalid = SemanticErrorType::InvalidClassReference;
+    assert_eq!(
+        found,
+        vec![
+            (
+                invalid,
+                "this".to_string(),
+                "'this' can only be used inside a class method"
+            ),
+            (
+                invalid,
+                "super".to_string(),
+                "'super' used in class 'lone', which does not extend another class"
+            ),
+            (
+                invalid,
+                "new".to_string(),
+                "'super.new' must be the first statement of a constructor"
+            ),
+            (
+                invalid,
+                "new".to_string(),
+                "'super.new' must be the first statement of a constructor"
+            ),
+            (
+                invalid,
+                "this".to_string(),
+                "'this' cannot be used in the static method 'late::h'"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_unknown_struct_members() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    struct packed {
+        logic [7:0] addr;
+        logic [7:0] data;
+        logic valid;
+    } pkt = '{addr: 0, data: 1, default: 0};
+    logic [7:0] x;
+    assign x = pkt.adr;
+    initial begin
+        pkt = '{addr: 1, dta: 2, valid: 1};
+        pkt.data = 3;
+        pkt = '{1, 2, 0};
+        pkt.zzz = 0;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let text: String = content
+                .chars()
+                .skip(e.span.0)
+                .take(e.span.1 - e.span.0)
+                .collect();
+            (e.error_type, text, e.message.as_str())
+        })
+        .collect();
+    let unknown = SemanticErrorType::UnknownMember;
+    assert_eq!(
+        found,
+        vec![
+            (
+                unknown,
+                "adr".to_string(),
+                "'pkt' has no member 'adr'; did you mean 'addr'?"
+            ),
+            (
+                unknown,
+                "dta".to_string(),
+                "'pkt' has no member 'dta'; did you mean 'data'?"
+            ),
+            (unknown, "zzz".to_string(), "'pkt' has no member 'zzz'"),
+        ]
+    );
+}
+
+#[test]
+fn test_struct_member_access_through_typedef() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"typedef struct packed {logic a;} pair_t;
+module top;
+    typedef union packed {logic [1:0] raw; pair_t [1:0] pairs;} word_t;
+    pair_t p;
+    word_t w;
+    assign p.c = 0;
+    assign p.a = 1;
+    assign w.row = 0;
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "'p' has no member 'c'; did you mean 'a'?",
+            "'w' has no member 'row'; did you mean 'raw'?"
+        ]
+    );
+}
+
+/// A member whose type is a struct has its own members checked
+#[test]
+fn test_nested_struct_member_access() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"typedef struct packed {logic [3:0] id; logic last;} header_t;
+typedef struct packed {header_t hdr; header_t [1:0] tags; logic [7:0] data;} packet_t;
+module top;
+    packet_t p;
+    assign p.hdr.lst = 0;
+    assign p.hdr.id = 1;
+    assign p.tags.id = 2;
+    assign p.data.id = 3;
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+    let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        vec!["'p.hdr' has no member 'lst'; did you mean 'last'?"]
+    );
+}
+
+#[test]
+fn test_elaboration_tasks() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"module top;
+    localparam WIDTH = 0;
+    $error("WIDTH must be positive, got %0d", WIDTH);
+    $fatal(1, "unsupported configuration");
+    $fatal;
+    $warning("narrow bus");
+    $info("elaborating top");
+    initial $error("only at run time");
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let text: String = content
+                .chars()
+                .skip(e.span.0)
+                .take(e.span.1 - e.span.0)
+                .collect();
+            (e.error_type, text, e.message.as_str())
+        })
+        .collect();
+    let failure = SemanticErrorType::ElaborationFailure;
+    assert_eq!(
+        found,
+        vec![
+            (
+                failure,
+                r#"$error("WIDTH must be positive, got %0d", WIDTH);"#.to_string(),
+                "$error: WIDTH must be positive, got %0d"
+            ),
+            (
+                failure,
+                r#"$fatal(1, "unsupported configuration");"#.to_string(),
+                "$fatal: unsupported configuration"
+            ),
+            (
+                failure,
+                "$fatal;".to_string(),
+                "$fatal reached during elaboration"
+            ),
+        ]
+    );
+}
+
+/// A guard that is constant for the module's parameters decides whether the
+/// task under it is reached.
+#[test]
+fn test_elaboration_task_under_constant_guard() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"module top;
+    localparam WIDTH = 0;
+    if (WIDTH < 1) $error("WIDTH must be positive");
+    if (WIDTH > 0) $error("WIDTH is positive");
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| (e.error_type, e.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![(
+            SemanticErrorType::ElaborationFailure,
+            "$error: WIDTH must be positive"
+        )]
+    );
+}
+
+/// A task in the body of a generate branch only fails elaboration if that
+/// branch is chosen; one in a plain generate region always does.
+#[test]
+fn test_elaboration_tasks_in_generate() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"module top;
+    parameter MODE = 2;
+    if (MODE > 1) begin : gen_bad
+        $error("MODE out of range");
+    end else begin
+        $error("MODE in range");
+    end
+    case (MODE)
+        0: $fatal(1, "MODE 0 is unsupported");
+        2: $info("MODE 2");
+        default: $error("MODE has no case");
+    endcase
+    genvar i;
+    for (i = 0; i < MODE; i++) begin
+        $error("only if the loop runs");
+    end
+    generate
+        $error("always elaborated");
+    endgenerate
+    if (MODE == 0) begin : gen_a
+    end : gen_b
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| (e.error_type, e.message.as_str()))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            (
+                SemanticErrorType::ElaborationFailure,
+                "$error: MODE out of range"
+            ),
+            (
+                SemanticErrorType::ElaborationFailure,
+                "$error: always elaborated"
+            ),
+            (
+                SemanticErrorType::LabelMismatch,
+                "End label 'gen_b' does not match block name 'gen_a'"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_invalid_return() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+module top();
+    logic x;
+    initial begin
+        x = 1;
+        return;
+    end
+endmodule
+
+class counter;
+    int count;
+    function new();
+        count = 0;
+        return count;
+    endfunction
+    function void reset();
+        count = 0;
+        return count;
+    endfunction
+    function int get();
+        return;
+    endfunction
+    function int next();
+        count = count + 1;
+        return count;
+    endfunction
+endclass
+
+function void log_done();
+    return 1;
+endfunction
+
+task automatic wait_cycles(int n);
+    return;
+endtask
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|e| {
+            let text: String = content
+                .chars()
+                .skip(e.span.0)
+                .take(e.span.1 - e.span.0)
+                .collect();
+            (e.error_type, text.trim().to_string(), e.message.as_str())
+        })
+        .collect();
+    let invalid = SemanticErrorType::InvalidReturn;
+    assert_eq!(
+        found,
+        vec![
+            (
+                invalid,
+                "return;".to_string(),
+                "'return' can only be used inside a function"
+            ),
+            (
+                invalid,
+                "return count;".to_string(),
+                "The constructor of class 'counter' cannot return a value"
+            ),
+            (
+                invalid,
+                "return count;".to_string(),
+                "Function 'counter::reset' is void, so 'return' cannot give a value"
+            ),
+            (
+                invalid,
+                "return;".to_string(),
+                "Function 'counter::get' has a return type, so 'return' must give a value"
+            ),
+            (
+                invalid,
+                "return 1;".to_string(),
+                "Function 'log_done' is void, so 'return' cannot give a value"
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_enum_members_are_constants_and_package_members() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+package state_pkg;
+    typedef enum {IDLE, BUSY} state_t;
+endpackage
+
+module top;
+    import state_pkg::BUSY;
+    import state_pkg::IDEL;
+    enum logic [1:0] {RED, GREEN = 2} color;
+    initial begin
+        color = GREEN;
+        RED = 1;
+    end
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 2, "unexpected errors: {:?}", errors);
+    assert_eq!(
+        errors[0].error_type,
+        SemanticErrorType::UndeclaredIdentifier
+    );
+    assert_eq!(
+        errors[0].message,
+        "Package 'state_pkg' has no member 'IDEL'; did you mean 'IDLE'?"
+    );
+
+    assert_eq!(
+        errors[1].error_type,
+        SemanticErrorType::AssignmentToParameter
+    );
+    assert_eq!(errors[1].message, "Cannot assign to enum member 'RED'");
+    let related = errors[1].related.as_ref().expect("declaration note");
+    assert_eq!(&content[related.span.0..related.span.1], "RED");
+}
+
+#[test]
+fn test_errors_of_many_modules_in_source_order() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content: String = (0..16)
+        .map(|index| {
+            format!(
+                "module m{0}();\n    logic a;\n    initial a = $unknown{0}(1);\nendmodule\n",
+                index
+            )
+        })
+        .collect();
+
+    let ast = parser.parse_content(&content).unwrap();
+    let mut analyzer = SemanticAnalyzer::new();
+    let errors = analyzer.analyze(&ast);
+
+    assert_eq!(errors.len(), 16);
+    for (index, error) in errors.iter().enumerate() {
+        assert!(error.message.contains(&format!("unknown{}", index)));
+    }
+    let timed: Vec<_> = analyzer
+        .item_timings()
+        .iter()
+        .map(|(item_ref, _)| *item_ref)
+        .collect();
+    assert_eq!(timed, ast.items);
+}
+
+#[test]
+fn test_package_exports_reach_importers() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+package base_pkg;
+    parameter WIDTH = 8;
+    parameter DEPTH = 4;
+endpackage
+
+package top_pkg;
+    import base_pkg::*;
+    export base_pkg::WIDTH;
+    export base_pkg::SIZE;
+endpackage
+
+module top;
+    import top_pkg::*;
+    import top_pkg::DEPTH;
+    initial WIDTH = 4;
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+    assert!(errors[0]
+        .message
+        .starts_with("Package 'base_pkg' has no member 'SIZE'"));
+    // Only the exported name is a member of `top_pkg`
+    assert!(errors[1]
+        .message
+        .starts_with("Package 'top_pkg' has no member 'DEPTH'"));
+    assert_eq!(
+        errors[2].error_type,
+        SemanticErrorType::AssignmentToParameter
+    );
+    let related = errors[2].related.as_ref().expect("declaration note");
+    assert_eq!(&content[related.span.0..related.span.1], "WIDTH");
+}
+
+#[test]
+fn test_nested_module_sees_enclosing_names() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+package cfg_pkg;
+    parameter WIDTH = 8;
+endpackage
+
+module outer;
+    import cfg_pkg::*;
+    parameter DEPTH = 4;
+
+    module inner(output logic DEPTH);
+        initial begin
+            WIDTH = 1;
+            DEPTH = 1;
+        end
+    endmodule
+
+    inner u_inner();
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    // The port of the inner module hides the parameter of the outer one
+    assert_eq!(errors.len(), 1, "unexpected errors: {:?}", errors);
+    assert_eq!(
+        errors[0].error_type,
+        SemanticErrorType::AssignmentToParameter
+    );
+    assert_eq!(errors[0].message, "Cannot assign to parameter 'WIDTH'");
+}
+
+#[test]
+fn test_import_conflicts() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = r#"
+package bus_pkg;
+    parameter WIDTH = 8;
+    parameter DEPTH = 4;
+endpackage
+
+package cpu_pkg;
+    parameter WIDTH = 16;
+    parameter DEPTH = 2;
+    typedef logic [7:0] opcode_t;
+endpackage
+
+module wildcards;
+    import bus_pkg::*;
+    import cpu_pkg::*;
+    import cpu_pkg::DEPTH;
+    logic [7:0] a, b;
+    initial begin
+        a = WIDTH;
+        b = DEPTH;
+    end
+endmodule
+
+module explicit;
+    import bus_pkg::WIDTH;
+    import cpu_pkg::WIDTH;
+    import cpu_pkg::opcode_t;
+    typedef logic [3:0] opcode_t;
+endmodule
+
+module unused;
+    import bus_pkg::*;
+    import cpu_pkg::*;
+endmodule
+"#;
+
+    let ast = parser.parse_content(content).unwrap();
+    let errors = parser.analyze_semantics(&ast);
+
+    // The explicit import decides DEPTH, and clashing wildcards are fine until a name is used
+    let messages: Vec<_> = errors
+        .iter()
+        .filter(|error| error.error_type == SemanticErrorType::InvalidImport)
+        .map(|error| error.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        vec![
+            "'WIDTH' is ambiguous: it is imported with wildcards from 'bus_pkg' and 'cpu_pkg'; import it explicitly",
+            "'WIDTH' is already imported from 'bus_pkg'",
+            "'opcode_t' is imported from 'cpu_pkg' but also declared in this scope",
+        ]
+    );
+    assert_eq!(errors.len(), 3, "unexpected errors: {:?}", errors);
+    let ambiguous = &errors[0];
+    assert_eq!(&content[ambiguous.span.0..ambiguous.span.1], "WIDTH");
+}
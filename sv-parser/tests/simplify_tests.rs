@@ -0,0 +1,96 @@
+//! Expression simplification tests.
+
+use std::collections::HashMap;
+use sv_parser::{simplify, ModuleItem, SimplifiedExpr, SystemVerilogParser};
+
+/// Simplify the value of the single parameter declared in `module m; parameter P = <expr>;`
+fn simplify_parameter(expr: &str) -> SimplifiedExpr {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = format!("module m;\n    parameter P = {};\nendmodule\n", expr);
+    let unit = parser
+        .parse_content(&content)
+        .unwrap_or_else(|e| panic!("failed to parse '{}': {:?}", expr, e));
+
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::ParameterDeclaration { value, .. } = unit.module_item_arena.get(items[0])
+    else {
+        panic!("Expected parameter declaration");
+    };
+    simplify(*value, &unit.expr_arena)
+}
+
+#[test]
+fn test_constant_expression_folds_to_value() {
+    let simplified = simplify_parameter("(4 * 2) - 1");
+    assert_eq!(simplified.constant().and_then(|c| c.to_i128()), Some(7));
+    assert_eq!(simplified.to_string(), "7");
+}
+
+#[test]
+fn test_associative_chain_is_flattened_with_constants_folded() {
+    assert_eq!(simplify_parameter("(a + 1) + 2").to_string(), "a + 3");
+    assert_eq!(
+        simplify_parameter("1 + (a + (2 + b))").to_string(),
+        "a + b + 3"
+    );
+    assert_eq!(
+        simplify_parameter("(a + 1) + 2"),
+        simplify_parameter("a + (1 + 2)")
+    );
+}
+
+#[test]
+fn test_non_associative_operators_keep_their_shape() {
+    assert_eq!(simplify_parameter("(a - 1) - 2").to_string(), "(a - 1) - 2");
+    assert_eq!(
+        simplify_parameter("(a + b) * (2 + 3)").to_string(),
+        "(a + b) * 5"
+    );
+}
+
+#[test]
+fn test_double_negation_is_removed() {
+    assert_eq!(
+        simplify_parameter("~(~a)"),
+        SimplifiedExpr::Identifier("a".to_string())
+    );
+    assert_eq!(simplify_parameter("-(-a)").to_string(), "a");
+    assert_eq!(simplify_parameter("!(!(a == b))").to_string(), "a == b");
+    // `!!a` is a 1-bit truth value, not `a` itself
+    assert_eq!(simplify_parameter("!(!a)").to_string(), "!(!a)");
+}
+
+#[test]
+fn test_logical_operators_decided_by_a_constant() {
+    assert_eq!(simplify_parameter("a && 0").truth_value(), Some(false));
+    assert_eq!(
+        simplify_parameter("(1 == 1) || a").truth_value(),
+        Some(true)
+    );
+    assert_eq!(simplify_parameter("a && 1").truth_value(), None);
+}
+
+#[test]
+fn test_calls_and_unknown_literals_are_kept_as_written() {
+    assert_eq!(
+        simplify_parameter("$clog2(2 * 8) + 1").to_string(),
+        "$clog2(16) + 1"
+    );
+    assert_eq!(
+        simplify_parameter("4'b1x0z"),
+        SimplifiedExpr::Opaque("4'b1x0z".to_string())
+    );
+    assert_eq!(simplify_parameter("8'hA5 & a").to_string(), "a & 8'hA5");
+}
+
+#[test]
+fn test_conditional_with_constant_condition_picks_a_branch() {
+    assert_eq!(simplify_parameter("(2 > 1) ? a : b").to_string(), "a");
+    assert_eq!(
+        simplify_parameter("sel ? (1 + 1) : b").to_string(),
+        "sel ? 2 : b"
+    );
+}
@@ -5,7 +5,11 @@
 //! go-to-definition, and other IDE functionality.
 
 use std::collections::HashMap;
-use sv_parser::{ModuleItem, SystemVerilogParser};
+use std::path::Path;
+use sv_parser::testing::{for_each_fixture, span_text};
+use sv_parser::{
+    assert_span_contains, assert_span_covers, ClassItem, ModuleItem, Statement, SystemVerilogParser,
+};
 
 /// Test that module declarations have correct spans
 #[test]
@@ -429,3 +433,124 @@ fn test_variable_with_initial_value_span() {
         panic!("Expected module declaration");
     }
 }
+
+/// Test that class properties and methods carry their name and item spans
+#[test]
+fn test_class_item_spans() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content =
+        "class Packet;\n    int len;\n    function int size();\n    endfunction\nendclass";
+
+    let result = parser.parse_content(content).unwrap();
+    let ModuleItem::ClassDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected class declaration");
+    };
+
+    let ClassItem::Property {
+        name_span, span, ..
+    } = &items[0]
+    else {
+        panic!("Expected class property");
+    };
+    assert_eq!(&content[name_span.0..name_span.1], "len");
+    assert_eq!(content[span.0..span.1].trim(), "int len;");
+
+    let ClassItem::Method {
+        name_span, span, ..
+    } = &items[1]
+    else {
+        panic!("Expected class method");
+    };
+    assert_eq!(&content[name_span.0..name_span.1], "size");
+    assert!(content[span.0..span.1]
+        .trim()
+        .starts_with("function int size"));
+    assert!(content[span.0..span.1].ends_with("endfunction"));
+}
+
+/// Test that ANSI and non-ANSI header ports carry their name spans
+#[test]
+fn test_header_port_spans() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "module test(input [3:0] data, clk);\nendmodule";
+
+    let result = parser.parse_content(content).unwrap();
+    let ModuleItem::ModuleDeclaration { ports, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+
+    assert_span_covers!(content, ports[0].name_span, "data");
+    assert_span_covers!(content, ports[0].span, "input [3:0] data");
+    assert_span_covers!(content, ports[1].name_span, "clk");
+}
+
+/// Test that statements inside procedural blocks have spans
+#[test]
+fn test_statement_spans() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "module test;\n    initial begin\n        a = 1;\n    end\nendmodule";
+
+    let result = parser.parse_content(content).unwrap();
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::ProceduralBlock { statements, .. } = result.module_item_arena.get(items[0])
+    else {
+        panic!("Expected procedural block");
+    };
+
+    let Statement::Assignment { span, .. } = result.stmt_arena.get(statements[0]) else {
+        panic!("Expected assignment statement");
+    };
+    assert_eq!(content[span.0..span.1].trim(), "a = 1;");
+}
+
+/// Spans count characters, so text before a declaration that isn't ASCII
+/// doesn't shift it
+#[test]
+fn test_spans_after_non_ascii_text() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "// Größe\nmodule test;\n    logic a;\nendmodule";
+
+    let result = parser.parse_content(content).unwrap();
+    let ModuleItem::ModuleDeclaration {
+        name_span, items, ..
+    } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert_span_covers!(content, *name_span, "test");
+
+    let ModuleItem::VariableDeclaration { span, .. } = result.module_item_arena.get(items[0])
+    else {
+        panic!("Expected variable declaration");
+    };
+    assert_span_contains!(content, *span, "logic a");
+    assert_eq!(span_text(content, (3, 8)), "Größe");
+}
+
+/// Test that the name span of every module in the module fixtures covers its name
+#[test]
+fn test_module_name_spans_in_fixtures() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/modules");
+
+    let mut modules = 0;
+    for_each_fixture(&parser, &fixtures, |path, content, result| {
+        let unit =
+            result.unwrap_or_else(|err| panic!("Failed to parse {}: {}", path.display(), err));
+        for item_ref in &unit.items {
+            if let ModuleItem::ModuleDeclaration {
+                name, name_span, ..
+            } = unit.module_item_arena.get(*item_ref)
+            {
+                assert_span_covers!(content, *name_span, name.as_str());
+                modules += 1;
+            }
+        }
+    });
+    assert!(modules > 0, "No modules found in {}", fixtures.display());
+}
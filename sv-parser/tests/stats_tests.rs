@@ -0,0 +1,71 @@
+//! Timing statistics tests.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+use sv_parser::{PhaseTimes, Stats, SystemVerilogParser};
+
+fn ms(millis: u64) -> Duration {
+    Duration::from_millis(millis)
+}
+
+#[test]
+fn test_report_sums_phases_and_lists_slowest() {
+    let mut stats = Stats::new();
+    stats.record_file(
+        Path::new("fast.sv"),
+        PhaseTimes {
+            parse: ms(1),
+            semantic: ms(1),
+            ..PhaseTimes::default()
+        },
+    );
+    stats.record_file(
+        Path::new("slow.sv"),
+        PhaseTimes {
+            preprocess: ms(2),
+            parse: ms(10),
+            ..PhaseTimes::default()
+        },
+    );
+    stats.record_unit(Path::new("slow.sv"), "big", ms(3));
+    stats.record_unit(Path::new("fast.sv"), "small", ms(1));
+    // Flattening and analysis of the same unit add up
+    stats.record_unit(Path::new("slow.sv"), "big", ms(1));
+
+    assert_eq!(stats.totals().parse, ms(11));
+    assert_eq!(stats.totals().total(), ms(14));
+
+    let report = stats.report(1);
+    assert!(report.starts_with("Timing (2 files, 14.000ms total):\n"));
+    assert!(report
+        .lines()
+        .any(|line| line.split_whitespace().eq(["parse", "11.000ms"])));
+    assert!(report.contains("slow.sv (parse 10.000ms, semantic 0.000ms)"));
+    assert!(!report.contains("fast.sv"));
+    assert!(report.contains("4.000ms  big (slow.sv)"));
+    assert!(!report.contains("small"));
+}
+
+#[test]
+fn test_parser_records_design_unit_timings() {
+    let dir = tempfile::tempdir().unwrap();
+    let file = dir.path().join("units.sv");
+    std::fs::write(
+        &file,
+        "module a; endmodule\nclass c; endclass\nmodule b; endmodule\n",
+    )
+    .unwrap();
+
+    let mut parser = SystemVerilogParser::new(vec![], HashMap::new());
+    parser.parse_file(&file).unwrap();
+
+    let names: Vec<&str> = parser
+        .timings()
+        .units
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .collect();
+    assert_eq!(names, vec!["a", "c", "b"]);
+    assert!(parser.timings().parse > Duration::ZERO);
+}
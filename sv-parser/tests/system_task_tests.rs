@@ -15,6 +15,8 @@ sv_ok_tests! {
     atan_function => "system_tasks/atan_function.sv",
     sin_function => "system_tasks/sin_function.sv",
     cos_function => "system_tasks/cos_function.sv",
+    elaboration_tasks => "system_tasks/elaboration_tasks.sv",
+    file_io => "system_tasks/file_io.sv",
     sampled_rose => "sampled_rose.sv",
     sampled_fell => "sampled_fell.sv",
     sampled_stable => "sampled_stable.sv",
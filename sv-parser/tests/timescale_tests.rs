@@ -0,0 +1,189 @@
+//! `timescale directive and timeunit/timeprecision declaration tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok, TestHarness};
+use sv_parser::{
+    time_scopes, ModuleItem, SemanticErrorType, SystemVerilogParser, TimeSource, TimeUnit,
+};
+
+/// Ensure all time unit fixtures parse without error.
+#[test]
+fn test_parse_all_timescale_files() {
+    assert_directory_parses("timescale");
+}
+
+sv_ok_tests! {
+    module_timeunits => "timescale/module_timeunits.sv",
+    invalid_timeunits => "timescale/invalid_timeunits.sv",
+}
+
+#[test]
+fn test_time_unit_declarations() {
+    let result = assert_parse_ok("timescale/module_timeunits.sv");
+    let arena = &result.module_item_arena;
+
+    let ModuleItem::TimescaleDirective {
+        unit, precision, ..
+    } = arena.get(result.items[0])
+    else {
+        panic!("Expected `timescale directive");
+    };
+    assert_eq!((unit.magnitude, unit.unit), (1, TimeUnit::Ns));
+    assert_eq!((precision.magnitude, precision.unit), (1, TimeUnit::Ps));
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[1]) else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::TimeunitsDeclaration {
+        unit: Some(unit),
+        precision: None,
+        ..
+    } = arena.get(items[0])
+    else {
+        panic!("Expected timeunit declaration");
+    };
+    assert_eq!(unit.text(), "100ps");
+    let ModuleItem::TimeunitsDeclaration {
+        unit: None,
+        precision: Some(precision),
+        ..
+    } = arena.get(items[1])
+    else {
+        panic!("Expected timeprecision declaration");
+    };
+    assert_eq!(precision.text(), "10ps");
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[2]) else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::TimeunitsDeclaration {
+        unit: Some(unit),
+        precision: Some(precision),
+        ..
+    } = arena.get(items[0])
+    else {
+        panic!("Expected timeunit declaration with a precision");
+    };
+    assert_eq!(
+        (unit.text(), precision.text()),
+        ("1us".into(), "1ns".into())
+    );
+}
+
+/// Each module takes its own declarations first, then the `timescale
+/// before it, one for the unit and one for the precision
+#[test]
+fn test_effective_time_units() {
+    let result = assert_parse_ok("timescale/module_timeunits.sv");
+    let scopes: Vec<_> = time_scopes(&result)
+        .into_iter()
+        .map(|scope| {
+            let unit = scope.unit.unwrap();
+            let precision = scope.precision.unwrap();
+            (
+                scope.name,
+                unit.value.text(),
+                unit.source,
+                precision.value.text(),
+                precision.source,
+            )
+        })
+        .collect();
+    assert_eq!(
+        scopes,
+        vec![
+            (
+                "counter".to_string(),
+                "100ps".to_string(),
+                TimeSource::Declaration,
+                "10ps".to_string(),
+                TimeSource::Declaration
+            ),
+            (
+                "driver".to_string(),
+                "1us".to_string(),
+                TimeSource::Declaration,
+                "1ns".to_string(),
+                TimeSource::Declaration
+            ),
+            (
+                "follower".to_string(),
+                "1ns".to_string(),
+                TimeSource::Timescale,
+                "1ps".to_string(),
+                TimeSource::Timescale
+            ),
+        ]
+    );
+
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let errors = parser.analyze_semantics(&result);
+    assert!(errors.is_empty(), "unexpected errors: {:?}", errors);
+}
+
+#[test]
+fn test_module_without_time_units() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let result = parser.parse_content("module top;\nendmodule\n").unwrap();
+    let scopes = time_scopes(&result);
+    assert_eq!(scopes.len(), 1);
+    assert_eq!((scopes[0].unit, scopes[0].precision), (None, None));
+}
+
+#[test]
+fn test_invalid_time_units() {
+    let harness = TestHarness::new();
+    let content = harness.read_fixture("timescale/invalid_timeunits.sv");
+    let result = harness.parse_fixture_ok("timescale/invalid_timeunits.sv");
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let errors = parser.analyze_semantics(&result);
+
+    let found: Vec<_> = errors
+        .iter()
+        .map(|error| {
+            let text: String = content
+                .chars()
+                .skip(error.span.0)
+                .take(error.span.1 - error.span.0)
+                .collect();
+            (error.error_type, text, error.message.as_str())
+        })
+        .collect();
+    let invalid = SemanticErrorType::InvalidTimeUnit;
+    assert_eq!(
+        found,
+        vec![
+            (
+                invalid,
+                "1ns".to_string(),
+                "Time precision 1ns is coarser than the time unit 1ps"
+            ),
+            (
+                invalid,
+                "5ns".to_string(),
+                "Time literal '5ns' must be 1, 10 or 100 of a unit"
+            ),
+            (
+                invalid,
+                "1ns".to_string(),
+                "Time precision 1ns of 'coarse', from its own declaration, is coarser than \
+                 its time unit 10ps, from its own declaration"
+            ),
+            (
+                invalid,
+                "100ps".to_string(),
+                "Time precision 1ns of 'finer_unit', from `timescale, is coarser than its \
+                 time unit 100ps, from its own declaration"
+            ),
+            (
+                SemanticErrorType::DuplicateDeclaration,
+                "10ns".to_string(),
+                "'repeated' already declares timeunit 1ns"
+            ),
+        ]
+    );
+}
@@ -0,0 +1,141 @@
+//! Typedef and enum type tests.
+
+#[path = "common/mod.rs"]
+mod common;
+
+use std::collections::HashMap;
+
+use common::{assert_directory_parses, assert_parse_ok};
+use sv_parser::{DataType, Expression, ModuleItem, SystemVerilogParser};
+
+/// Ensure all type fixtures parse without error.
+#[test]
+fn test_parse_all_type_files() {
+    assert_directory_parses("types");
+}
+
+sv_ok_tests! {
+    typedef_enum => "types/typedef_enum.sv",
+    typedef_alias => "types/typedef_alias.sv",
+}
+
+/// A typedef keeps the type it names, and an enum keeps its base type and the
+/// explicit values of its members.
+#[test]
+fn test_typedef_enum_structure() {
+    let result = assert_parse_ok("types/typedef_enum.sv");
+    let arena = &result.module_item_arena;
+    assert_eq!(result.items.len(), 3);
+
+    let ModuleItem::TypedefDeclaration {
+        data_type: DataType::Named(data_type),
+        range,
+        name,
+        ..
+    } = arena.get(result.items[0])
+    else {
+        panic!("Expected typedef");
+    };
+    assert_eq!(data_type, "logic");
+    assert_eq!(range.as_ref().unwrap().msb, "7");
+    assert_eq!(name, "byte_t");
+
+    let ModuleItem::PackageDeclaration { items, .. } = arena.get(result.items[1]) else {
+        panic!("Expected package declaration");
+    };
+    let ModuleItem::TypedefDeclaration {
+        data_type: DataType::Enum(enum_type),
+        name,
+        ..
+    } = arena.get(items[0])
+    else {
+        panic!("Expected enum typedef");
+    };
+    assert_eq!(name, "state_t");
+    assert_eq!(enum_type.base_type.as_deref(), Some("logic"));
+    assert_eq!(enum_type.range.as_ref().unwrap().msb, "1");
+    let members: Vec<&str> = enum_type.members.iter().map(|m| m.name.as_str()).collect();
+    assert_eq!(members, vec!["IDLE", "BUSY", "DONE"]);
+    assert!(enum_type.members[0].value.is_none());
+    assert!(matches!(
+        result.expr_arena.get(enum_type.members[1].value.unwrap()),
+        Expression::Number(n, _) if n == "2'b10"
+    ));
+    assert!(enum_type.members[2].value.is_none());
+}
+
+/// An anonymous enum declares a variable of type `enum`; struct typedefs keep
+/// their members like inline struct variables.
+#[test]
+fn test_anonymous_enum_variable() {
+    let result = assert_parse_ok("types/typedef_enum.sv");
+    let arena = &result.module_item_arena;
+
+    let ModuleItem::ModuleDeclaration { items, .. } = arena.get(result.items[2]) else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::TypedefDeclaration {
+        data_type: DataType::Struct { members, .. },
+        name,
+        ..
+    } = arena.get(items[1])
+    else {
+        panic!("Expected struct typedef");
+    };
+    assert_eq!(name, "packet_t");
+    assert_eq!(members[0].data_type, DataType::Named("byte_t".to_string()));
+
+    let ModuleItem::VariableDeclaration {
+        data_type: DataType::Enum(enum_type),
+        name,
+        initial_value: Some(initial_value),
+        ..
+    } = arena.get(items[3])
+    else {
+        panic!("Expected anonymous enum variable");
+    };
+    assert_eq!(name, "color");
+    assert!(enum_type.base_type.is_none());
+    assert_eq!(enum_type.members.len(), 3);
+    assert!(matches!(
+        result.expr_arena.get(*initial_value),
+        Expression::Identifier(n, _) if n == "GREEN"
+    ));
+    assert_eq!(arena.get(items[3]).enum_members()[1].name, "GREEN");
+
+    assert!(matches!(
+        arena.get(items[4]),
+        ModuleItem::VariableDeclaration { data_type: DataType::Named(data_type), .. } if data_type == "byte_t"
+    ));
+}
+
+/// Typedefs may name other types and add signing and unpacked dimensions.
+#[test]
+fn test_typedef_aliases() {
+    let result = assert_parse_ok("types/typedef_alias.sv");
+    let arena = &result.module_item_arena;
+
+    assert!(matches!(
+        arena.get(result.items[0]),
+        ModuleItem::TypedefDeclaration { data_type, signing: Some(signing), name, .. }
+            if data_type.name() == "int" && signing == "unsigned" && name == "count_t"
+    ));
+    let ModuleItem::TypedefDeclaration {
+        data_type,
+        unpacked_dimensions,
+        ..
+    } = arena.get(result.items[1])
+    else {
+        panic!("Expected typedef");
+    };
+    assert_eq!(data_type.name(), "byte_t");
+    assert_eq!(unpacked_dimensions.len(), 1);
+}
+
+/// An enum needs at least one member.
+#[test]
+fn test_empty_enum_is_rejected() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let content = "module top;\n    typedef enum {} empty_t;\nendmodule\n";
+    assert!(parser.parse_content(content).is_err());
+}
@@ -4,7 +4,7 @@
 
 use std::collections::HashMap;
 use std::path::Path;
-use sv_parser::SystemVerilogParser;
+use sv_parser::{DataType, Expression, ModuleItem, SystemVerilogParser};
 
 /// Test basic unpacked union declaration
 #[test]
@@ -13,6 +13,96 @@ fn test_basic_union() {
     let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/basic_union.sv");
     let content = std::fs::read_to_string(&path).unwrap();
 
-    // Just verify it parses without error
-    let _result = parser.parse_content(&content).unwrap();
+    let result = parser.parse_content(&content).unwrap();
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module");
+    };
+    let ModuleItem::VariableDeclaration {
+        data_type:
+            DataType::Union {
+                tagged,
+                packed,
+                members,
+            },
+        ..
+    } = result.module_item_arena.get(items[0])
+    else {
+        panic!("Expected union variable");
+    };
+    assert!(!tagged);
+    assert!(!packed);
+    assert_eq!(members.len(), 2);
+}
+
+/// Test that struct members and assignment pattern keys are recorded
+#[test]
+fn test_struct_members_and_assignment_pattern() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_files/struct_members.sv");
+    let content = std::fs::read_to_string(&path).unwrap();
+    let result = parser.parse_content(&content).unwrap();
+
+    let ModuleItem::ModuleDeclaration { items, .. } = result.module_item_arena.get(result.items[0])
+    else {
+        panic!("Expected module");
+    };
+    let ModuleItem::VariableDeclaration {
+        data_type:
+            DataType::Struct {
+                packed,
+                members: struct_members,
+            },
+        name,
+        initial_value,
+        ..
+    } = result.module_item_arena.get(items[0])
+    else {
+        panic!("Expected struct variable");
+    };
+    assert!(packed);
+    assert_eq!(name, "pkt");
+    let members: Vec<_> = struct_members
+        .iter()
+        .map(|member| (member.data_type.name(), member.name.as_str()))
+        .collect();
+    assert_eq!(
+        members,
+        vec![("logic", "addr"), ("logic", "data"), ("bit", "valid")]
+    );
+    assert_eq!(struct_members[0].range.as_ref().unwrap().msb, "7");
+    let text = |span: (usize, usize)| -> String {
+        content.chars().skip(span.0).take(span.1 - span.0).collect()
+    };
+    assert_eq!(text(struct_members[2].name_span), "valid");
+    assert_eq!(text(struct_members[2].span), "bit valid;");
+
+    let Expression::AssignmentPattern { items, span } =
+        result.expr_arena.get(initial_value.unwrap())
+    else {
+        panic!("Expected assignment pattern");
+    };
+    assert_eq!(text(*span), "'{addr: 8'h10, data: 0, default: 0}");
+    let keys: Vec<_> = items.iter().map(|item| item.key.as_deref()).collect();
+    assert_eq!(keys, vec![Some("addr"), Some("data"), Some("default")]);
+    assert_eq!(text(items[1].key_span), "data");
+    assert!(
+        matches!(result.expr_arena.get(items[0].value), Expression::Number(n, _) if n == "8'h10")
+    );
+}
+
+/// Test positional assignment patterns
+#[test]
+fn test_positional_assignment_pattern() {
+    let parser = SystemVerilogParser::new(vec![], HashMap::new());
+    let expr = parser.parse_expression("'{1, a ? b : c}").unwrap();
+    let Expression::AssignmentPattern { items, .. } = expr.expression() else {
+        panic!("Expected assignment pattern");
+    };
+    assert_eq!(items.len(), 2);
+    assert!(items.iter().all(|item| item.key.is_none()));
+    assert!(matches!(
+        expr.expr_arena.get(items[1].value),
+        Expression::Conditional { .. }
+    ));
 }
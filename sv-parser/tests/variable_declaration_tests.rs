@@ -3,7 +3,8 @@
 #[path = "common/mod.rs"]
 mod common;
 
-use common::{assert_directory_parses, assert_parse_ok};
+use common::{assert_directory_parses, assert_parse_ok, TestHarness};
+use sv_parser::{Expression, ModuleItem, Statement};
 
 /// Ensure every variable fixture parses successfully.
 #[test]
@@ -30,6 +31,9 @@ sv_ok_tests! {
     net_trior => "variables/trior_declaration.sv",
     net_tri0 => "variables/tri0_declaration.sv",
     net_tri1 => "variables/tri1_declaration.sv",
+    net_declaration_assignment => "variables/net_declaration_assignment.sv",
+    net_qualifiers => "variables/net_qualifiers.sv",
+    multiple_declarators => "variables/multiple_declarators.sv",
 }
 
 /// Sample structural assertion to ensure helper usage stays easy to adopt.
@@ -38,3 +42,160 @@ fn test_time_unsigned_structure() {
     let unit = assert_parse_ok("variables/time_unsigned.sv");
     assert!(!unit.items.is_empty(), "Expected at least one declaration");
 }
+
+/// A net declaration assignment is an implicit continuous assignment
+#[test]
+fn test_net_declaration_assignment_structure() {
+    let unit = assert_parse_ok("variables/net_declaration_assignment.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let items: Vec<_> = items
+        .iter()
+        .map(|item| unit.module_item_arena.get(*item))
+        .collect();
+    assert_eq!(items.len(), 8);
+
+    let ModuleItem::VariableDeclaration {
+        data_type,
+        name,
+        initial_value,
+        ..
+    } = items[2]
+    else {
+        panic!("Expected declaration, got {:?}", items[2]);
+    };
+    assert_eq!((data_type.name(), name.as_str()), ("wire", "a_and_b"));
+    assert_eq!(*initial_value, None);
+
+    let ModuleItem::Assignment {
+        target,
+        expr,
+        implicit: true,
+        ..
+    } = items[3]
+    else {
+        panic!("Expected implicit assignment, got {:?}", items[3]);
+    };
+    assert!(matches!(unit.expr_arena.get(*target), Expression::Identifier(n, _) if n == "a_and_b"));
+    assert!(matches!(
+        unit.expr_arena.get(*expr),
+        Expression::Binary { .. }
+    ));
+
+    // The net delay applies to the assignment too
+    let ModuleItem::Assignment { delay: Some(_), .. } = items[7] else {
+        panic!("Expected delayed assignment, got {:?}", items[7]);
+    };
+}
+
+/// Strength, vectored and an explicit logic data type may follow the net type
+#[test]
+fn test_net_qualifiers_structure() {
+    let unit = assert_parse_ok("variables/net_qualifiers.sv");
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+    let ModuleItem::VariableDeclaration {
+        signing,
+        drive_strength: Some(strength),
+        ..
+    } = unit.module_item_arena.get(items[0])
+    else {
+        panic!("Expected declaration with drive strength");
+    };
+    assert_eq!(signing.as_deref(), Some("signed"));
+    assert_eq!(strength.strength0, "strong0");
+
+    let data_types: Vec<_> = items
+        .iter()
+        .filter_map(|item| match unit.module_item_arena.get(*item) {
+            ModuleItem::VariableDeclaration { data_type, .. } => Some(data_type.name()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(data_types, vec!["wire", "wire", "wire", "trireg"]);
+}
+
+/// Each variable of a declaration is a declaration of its own, in modules
+/// and in procedural blocks alike
+#[test]
+fn test_multiple_declarators_structure() {
+    let unit = assert_parse_ok("variables/multiple_declarators.sv");
+    let content = TestHarness::new().read_fixture("variables/multiple_declarators.sv");
+    let text = |span: (usize, usize)| content[span.0..span.1].to_string();
+    let ModuleItem::ModuleDeclaration { items, .. } = unit.module_item_arena.get(unit.items[0])
+    else {
+        panic!("Expected module declaration");
+    };
+
+    let declarations: Vec<_> = items
+        .iter()
+        .filter_map(|item| match unit.module_item_arena.get(*item) {
+            ModuleItem::VariableDeclaration {
+                data_type,
+                name,
+                name_span,
+                unpacked_dimensions,
+                initial_value,
+                ..
+            } => Some((
+                data_type.name(),
+                name.as_str(),
+                text(*name_span),
+                unpacked_dimensions.len(),
+                initial_value.is_some(),
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        declarations,
+        vec![
+            ("logic", "a", "a".to_string(), 0, false),
+            ("logic", "b", "b".to_string(), 0, true),
+            ("logic", "c", "c".to_string(), 0, false),
+            ("int", "count", "count".to_string(), 1, false),
+            ("int", "total", "total".to_string(), 0, true),
+            // The net declaration assignment moves to an assignment of its own
+            ("wire", "p", "p".to_string(), 0, false),
+            ("wire", "q", "q".to_string(), 0, false),
+        ]
+    );
+
+    let statements = items
+        .iter()
+        .find_map(|item| match unit.module_item_arena.get(*item) {
+            ModuleItem::ProceduralBlock { statements, .. } => Some(statements),
+            _ => None,
+        })
+        .expect("Expected initial block");
+    let statements: Vec<_> = statements
+        .iter()
+        .map(|stmt| unit.stmt_arena.get(*stmt))
+        .collect();
+    assert_eq!(statements.len(), 4, "got: {:?}", statements);
+    let names: Vec<_> = statements
+        .iter()
+        .filter_map(|statement| match statement {
+            Statement::VariableDeclaration {
+                name,
+                name_span,
+                initial_value,
+                ..
+            } => Some((name.as_str(), text(*name_span), initial_value.is_some())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            ("i", "i".to_string(), true),
+            ("j", "j".to_string(), true),
+            ("k", "k".to_string(), false),
+        ]
+    );
+    assert!(matches!(statements[3], Statement::Assignment { .. }));
+}
@@ -0,0 +1,38 @@
+//! Browser-facing API tests.
+
+use sv_parser::web;
+
+#[test]
+fn test_parse_valid_source_has_no_diagnostics() {
+    assert_eq!(web::parse("module top;\n    logic a;\nendmodule\n"), "[]");
+}
+
+#[test]
+fn test_parse_reports_semantic_errors() {
+    let json = web::parse("module top(input logic a);\n    assign a = 1'b0;\nendmodule\n");
+    let diagnostics: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1, "got: {}", json);
+    assert_eq!(diagnostics[0]["message"], "Cannot assign to input port 'a'");
+    assert_eq!(diagnostics[0]["start"]["line"], 1);
+}
+
+#[test]
+fn test_parse_reports_syntax_errors() {
+    let json = web::parse("module top;\n    logic a\nendmodule\n");
+    let diagnostics: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let diagnostics = diagnostics.as_array().unwrap();
+    assert!(!diagnostics.is_empty(), "got: {}", json);
+    assert!(diagnostics[0]["message"].is_string());
+    assert!(diagnostics[0]["start"]["column"].is_u64());
+}
+
+#[test]
+fn test_format() {
+    assert_eq!(
+        web::format("module top;\nlogic   a;\n  endmodule").unwrap(),
+        "module top;\n    logic a;\nendmodule\n"
+    );
+    assert!(web::format("module top;\n").is_err());
+}
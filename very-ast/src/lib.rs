@@ -0,0 +1,1937 @@
+//! The syntax tree of the very SystemVerilog parser
+//!
+//! The node types, their arenas and the span helpers live here, apart from
+//! the parser that builds them, so tools that only walk or build trees can
+//! depend on this crate alone. Its version moves with changes to the tree,
+//! not with the parser: a parser release that only parses more source, or
+//! parses it faster, leaves these types as they are.
+//!
+//! Nodes refer to each other by index into the arenas of their
+//! [`SourceUnit`], as [`ExprRef`], [`StmtRef`] and [`ModuleItemRef`].
+
+mod merge;
+pub mod position;
+pub mod source_map;
+
+pub use position::{LineColumn, PositionEncoding};
+pub use source_map::{DefinitionSite, Directive, DirectiveKind, Provenance, SourceMap};
+
+/// Span represents a byte range in the source code (start, end)
+pub type Span = (usize, usize);
+
+/// ExprRef is an index into the ExprArena
+pub type ExprRef = u32;
+
+/// Arena for storing all Expression nodes in a flat array
+/// This avoids stack overflow from deeply nested recursive structures
+#[derive(Debug, Clone)]
+pub struct ExprArena {
+    pub nodes: Vec<Expression>,
+}
+
+impl ExprArena {
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    pub fn alloc(&mut self, expr: Expression) -> ExprRef {
+        let idx = self.nodes.len() as u32;
+        self.nodes.push(expr);
+        idx
+    }
+
+    pub fn get(&self, idx: ExprRef) -> &Expression {
+        &self.nodes[idx as usize]
+    }
+
+    pub fn get_mut(&mut self, idx: ExprRef) -> &mut Expression {
+        &mut self.nodes[idx as usize]
+    }
+}
+
+impl Default for ExprArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// StmtRef is an index into the StmtArena
+pub type StmtRef = u32;
+
+/// Arena for storing all Statement nodes in a flat array
+/// This avoids stack overflow from deeply nested recursive structures
+#[derive(Debug, Clone)]
+pub struct StmtArena {
+    pub nodes: Vec<Statement>,
+}
+
+impl StmtArena {
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    pub fn alloc(&mut self, stmt: Statement) -> StmtRef {
+        let idx = self.nodes.len() as u32;
+        self.nodes.push(stmt);
+        idx
+    }
+
+    pub fn get(&self, idx: StmtRef) -> &Statement {
+        &self.nodes[idx as usize]
+    }
+
+    pub fn get_mut(&mut self, idx: StmtRef) -> &mut Statement {
+        &mut self.nodes[idx as usize]
+    }
+}
+
+impl Default for StmtArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ModuleItemRef is an index into the ModuleItemArena
+pub type ModuleItemRef = u32;
+
+/// Arena for storing all ModuleItem nodes in a flat array
+/// This avoids stack overflow from deeply nested module structures
+#[derive(Debug, Clone)]
+pub struct ModuleItemArena {
+    pub nodes: Vec<ModuleItem>,
+}
+
+impl ModuleItemArena {
+    pub fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    pub fn alloc(&mut self, item: ModuleItem) -> ModuleItemRef {
+        let idx = self.nodes.len() as u32;
+        self.nodes.push(item);
+        idx
+    }
+
+    pub fn get(&self, idx: ModuleItemRef) -> &ModuleItem {
+        &self.nodes[idx as usize]
+    }
+
+    pub fn get_mut(&mut self, idx: ModuleItemRef) -> &mut ModuleItem {
+        &mut self.nodes[idx as usize]
+    }
+}
+
+impl Default for ModuleItemArena {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SourceUnit {
+    pub items: Vec<ModuleItemRef>,
+    pub expr_arena: ExprArena,
+    pub stmt_arena: StmtArena,
+    pub module_item_arena: ModuleItemArena,
+    /// Macro expansions, conditional branches and retained directives of the
+    /// file, whose spans are in the file's own text, not in that of its
+    /// includes
+    pub source_map: SourceMap,
+}
+
+#[derive(Debug, Clone)]
+pub enum ModuleItem {
+    ModuleDeclaration {
+        name: String,
+        name_span: Span,
+        ports: Vec<Port>,
+        items: Vec<ModuleItemRef>,
+        span: Span,
+    },
+    /// `interface name(ports); ... endinterface`
+    InterfaceDeclaration {
+        name: String,
+        name_span: Span,
+        ports: Vec<Port>,
+        items: Vec<ModuleItemRef>,
+        span: Span,
+    },
+    /// `checker name(ports); items endchecker`: assertions and the modeling
+    /// code around them, instantiated like a module
+    CheckerDeclaration {
+        name: String,
+        name_span: Span,
+        ports: Vec<Port>,
+        items: Vec<ModuleItemRef>,
+        span: Span,
+    },
+    /// `modport master(output req, input gnt), slave(...);` inside an interface
+    ModportDeclaration { modports: Vec<Modport>, span: Span },
+    /// `package name; ... endpackage`
+    PackageDeclaration {
+        name: String,
+        name_span: Span,
+        items: Vec<ModuleItemRef>,
+        span: Span,
+    },
+    /// `import pkg::*;` or `import pkg::name, other::*;`, in a module, a
+    /// package, a module header or the compilation unit
+    ImportDeclaration {
+        imports: Vec<PackageImport>,
+        span: Span,
+    },
+    /// `export pkg::name;`, `export pkg::*;` or `export *::*;` in a package,
+    /// passing imported names on to the importers of the package; the
+    /// package of `*::*` is `*`
+    ExportDeclaration {
+        exports: Vec<PackageImport>,
+        span: Span,
+    },
+    PortDeclaration {
+        direction: PortDirection,
+        port_type: String,
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    VariableDeclaration {
+        data_type: DataType,
+        signing: Option<String>,
+        drive_strength: Option<DriveStrength>,
+        delay: Option<Delay>,
+        range: Option<Range>,
+        name: String,
+        name_span: Span,
+        unpacked_dimensions: Vec<UnpackedDimension>,
+        /// Always None for nets: `wire a = b;` becomes the declaration
+        /// followed by an implicit Assignment
+        initial_value: Option<ExprRef>,
+        span: Span,
+    },
+    /// `typedef logic [7:0] byte_t;` or `typedef enum { A, B } state_t;`
+    TypedefDeclaration {
+        data_type: DataType,
+        signing: Option<String>,
+        range: Option<Range>,
+        name: String,
+        name_span: Span,
+        unpacked_dimensions: Vec<UnpackedDimension>,
+        span: Span,
+    },
+    Assignment {
+        /// `(weak0, weak1)` in `assign (weak0, weak1) y = a;`; always None
+        /// for an implicit assignment, whose strength is on its net
+        drive_strength: Option<DriveStrength>,
+        delay: Option<Delay>,
+        target: ExprRef,
+        expr: ExprRef,
+        /// Comes from a net declaration assignment rather than an `assign`
+        implicit: bool,
+        span: Span,
+    },
+    ProceduralBlock {
+        block_type: ProceduralBlockType,
+        /// `@(posedge clk)` after the keyword, as in `always_ff @(posedge clk)`
+        event_control: Option<EventControl>,
+        statements: Vec<StmtRef>,
+        label: Option<Label>,     // begin : label
+        end_label: Option<Label>, // end : label
+        span: Span,
+    },
+    DefineDirective {
+        name: String,
+        name_span: Span,
+        parameters: Vec<String>, // macro parameters (e.g., for `define FOO(a, b))
+        value: String,           // macro replacement text
+        span: Span,
+    },
+    IncludeDirective {
+        path: String, // the include path (with quotes or angle brackets)
+        path_span: Span,
+        resolved_path: Option<std::path::PathBuf>, // the resolved absolute path
+        span: Span,
+    },
+    /// `` `timescale 1ns / 1ps ``: time unit and precision of the design
+    /// elements after it that don't declare their own
+    TimescaleDirective {
+        unit: TimeValue,
+        precision: TimeValue,
+        span: Span,
+    },
+    /// `` `default_nettype none ``: the type of the nets that undeclared
+    /// names create in the modules after it, or `none` for an error instead
+    DefaultNettypeDirective { net_type: String, span: Span },
+    /// `timeunit 1ns;`, `timeprecision 1ps;` or `timeunit 1ns / 1ps;`
+    TimeunitsDeclaration {
+        unit: Option<TimeValue>,
+        precision: Option<TimeValue>,
+        span: Span,
+    },
+    ClassDeclaration {
+        /// Declared `virtual class`, so it is only extended and never
+        /// constructed itself
+        is_virtual: bool,
+        name: String,
+        name_span: Span,
+        /// Parameter ports: `#(type T = int, int DEPTH = 8)`
+        parameters: Vec<ClassParameter>,
+        extends: Option<String>,
+        /// Interface classes named in the `implements` clause
+        implements: Vec<String>,
+        items: Vec<ClassItem>,
+        span: Span,
+    },
+    /// `interface class name extends a, b; ... endclass`
+    InterfaceClassDeclaration {
+        name: String,
+        name_span: Span,
+        /// Interface classes this one extends (there may be several)
+        extends: Vec<String>,
+        items: Vec<ClassItem>,
+        span: Span,
+    },
+    /// Body of a method declared `extern` in its class, defined outside it:
+    /// `function void packet::send(int n); ... endfunction`
+    ///
+    /// The class and method names together name the prototype, see
+    /// [`scoped_method_name`].
+    OutOfBlockMethod {
+        return_type: Option<String>, // None for void
+        class_name: String,
+        class_name_span: Span,
+        name: String,
+        name_span: Span,
+        parameters: Vec<String>,
+        body: Vec<StmtRef>,
+        span: Span,
+    },
+    /// Function or task outside a class, in a module, package, header or the
+    /// compilation unit: `function automatic int f(int a); ... endfunction`
+    SubroutineDeclaration {
+        /// A `task`, which returns no value, rather than a `function`
+        is_task: bool,
+        /// Declared `automatic`, so each call has its own variables
+        is_automatic: bool,
+        return_type: Option<String>, // None for void
+        name: String,
+        name_span: Span,
+        parameters: Vec<String>,
+        body: Vec<StmtRef>,
+        span: Span,
+    },
+    /// `assert property (@(posedge clk) req |=> gnt) else $error("...");`,
+    /// or the `assume`, `cover` or `restrict` form
+    ConcurrentAssertion {
+        kind: AssertionKind,
+        /// Explicit clock of the property, like `@(posedge clk)`
+        clocking_event: Option<ExprRef>,
+        /// Explicit `disable iff (...)` condition
+        disable_iff: Option<ExprRef>,
+        property: PropertyExpr,
+        /// Statement run when the property holds
+        pass_action: Option<StmtRef>,
+        /// Statement of the `else` clause, run when it fails
+        fail_action: Option<StmtRef>,
+        span: Span,
+    },
+    /// `sequence name(ports); body endsequence` or `property name(ports);
+    /// body endproperty`, which assertions use by name
+    AssertionDeclaration {
+        kind: AssertionDeclarationKind,
+        name: String,
+        name_span: Span,
+        /// Formal arguments, typed like `int n` or untyped like `a`
+        ports: Vec<Port>,
+        /// Clock of the body, like `@(posedge clk)`
+        clocking_event: Option<ExprRef>,
+        /// `disable iff (...)` condition; only a property can have one
+        disable_iff: Option<ExprRef>,
+        body: PropertyExpr,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    /// `default clocking name;` - clock for assertions in the module without their own
+    DefaultClocking {
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    /// `default disable iff (cond);` - reset condition for assertions in the module
+    DefaultDisableIff { condition: ExprRef, span: Span },
+    GlobalClocking {
+        identifier: Option<String>,
+        identifier_span: Option<Span>,
+        clocking_event: ExprRef, // The event expression like @(posedge clk)
+        end_label: Option<String>,
+        span: Span,
+    },
+    /// `clocking cb @(posedge clk); input a; output #2 b; endclocking`:
+    /// signals sampled and driven at the ticks of a clock
+    ClockingBlock {
+        /// `default clocking cb ...`, which also clocks the assertions in
+        /// the module without their own
+        default: bool,
+        name: String,
+        name_span: Span,
+        clocking_event: ExprRef,
+        /// Skews of `default input #1step output #0;`, as written
+        default_input_skew: Option<String>,
+        default_output_skew: Option<String>,
+        signals: Vec<ClockingSignal>,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    ParameterDeclaration {
+        local: bool, // localparam
+        data_type: Option<String>,
+        range: Option<Range>,
+        name: String,
+        name_span: Span,
+        value: ExprRef,
+        span: Span,
+    },
+    ModuleInstantiation {
+        module_name: String,
+        module_name_span: Span,
+        parameters: Vec<PortConnection>, // parameter value assignments: #(.WIDTH(8))
+        instance_name: String,
+        instance_name_span: Span,
+        connections: Vec<PortConnection>,
+        span: Span,
+    },
+    /// `config name; design lib.top; ... endconfig`: which library each cell
+    /// of a design comes from
+    ConfigDeclaration {
+        name: String,
+        name_span: Span,
+        /// Top-level cells from the `design` statement
+        design: Vec<CellReference>,
+        rules: Vec<ConfigRule>,
+        span: Span,
+    },
+    /// `$fatal`, `$error`, `$warning` or `$info` as a module item, which runs
+    /// when the module is elaborated rather than during simulation
+    ElaborationTask {
+        severity: ElaborationSeverity,
+        arguments: Vec<ExprRef>,
+        span: Span,
+    },
+    /// `genvar i, j;`
+    GenvarDeclaration { genvars: Vec<Genvar>, span: Span },
+    /// `generate ... endgenerate`, which only groups its items
+    GenerateRegion {
+        items: Vec<ModuleItemRef>,
+        span: Span,
+    },
+    /// `begin : name ... end` or `name : begin ... end` as the body of a
+    /// generate loop or branch, naming the scope its items are elaborated in
+    GenerateBlock {
+        label: Option<Label>,
+        items: Vec<ModuleItemRef>,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    /// `for (genvar i = 0; i < N; i++) body` in a module
+    GenerateFor {
+        /// Whether the loop declares its genvar with `for (genvar i = ...`
+        declares_genvar: bool,
+        genvar: String,
+        genvar_span: Span,
+        init: ExprRef,
+        condition: ExprRef,
+        /// How the genvar changes after each iteration; `i++` and `i--` are
+        /// stored as `i += 1` and `i -= 1`
+        step_op: AssignmentOp,
+        step: ExprRef,
+        /// A generate block, or a single item
+        body: ModuleItemRef,
+        span: Span,
+    },
+    /// `if (cond) body else body` in a module; `else if` is an `if` in the else branch
+    GenerateIf {
+        condition: ExprRef,
+        then_item: ModuleItemRef,
+        else_item: Option<ModuleItemRef>,
+        span: Span,
+    },
+    /// `case (expr) value: body ... default: body endcase` in a module
+    GenerateCase {
+        expr: ExprRef,
+        items: Vec<GenerateCaseItem>,
+        span: Span,
+    },
+}
+
+impl ModuleItem {
+    /// The items a generate construct holds, in source order: those of a
+    /// region or block, the body of a loop and the branches of an `if` or
+    /// `case`; empty for other items
+    pub fn generate_items(&self) -> Vec<ModuleItemRef> {
+        match self {
+            ModuleItem::GenerateRegion { items, .. } | ModuleItem::GenerateBlock { items, .. } => {
+                items.clone()
+            }
+            ModuleItem::GenerateFor { body, .. } => vec![*body],
+            ModuleItem::GenerateIf {
+                then_item,
+                else_item,
+                ..
+            } => std::iter::once(*then_item).chain(*else_item).collect(),
+            ModuleItem::GenerateCase { items, .. } => items.iter().map(|item| item.body).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Members of the inline enum type of a variable or typedef; empty for
+    /// other items
+    pub fn enum_members(&self) -> &[EnumMember] {
+        match self {
+            ModuleItem::VariableDeclaration { data_type, .. }
+            | ModuleItem::TypedefDeclaration { data_type, .. } => match data_type {
+                DataType::Enum(enum_type) => &enum_type.members,
+                _ => &[],
+            },
+            _ => &[],
+        }
+    }
+}
+
+/// A signal of a clocking block: `output #2 b`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClockingSignal {
+    pub direction: PortDirection,
+    /// `#2`, `#1step` or `posedge`, as written; None for the default skew
+    pub skew: Option<String>,
+    pub name: String,
+    pub name_span: Span,
+    /// Span of the whole declaration, shared by the signals it lists
+    pub span: Span,
+}
+
+/// One name of a `genvar` declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Genvar {
+    pub name: String,
+    pub name_span: Span,
+}
+
+/// One item of a generate `case`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenerateCaseItem {
+    /// Values compared with the case expression; empty for `default`
+    pub values: Vec<ExprRef>,
+    /// A generate block, or a single item
+    pub body: ModuleItemRef,
+    pub span: Span,
+}
+
+/// `lib.cell`, or `cell` in any library, in a configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellReference {
+    pub library: Option<String>,
+    pub cell: String,
+    pub span: Span,
+}
+
+/// A rule of a configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigRule {
+    /// `default liblist a b;` - libraries to search for every cell
+    Default { liblist: Vec<String>, span: Span },
+    /// `instance top.u1 liblist a;` or `instance top.u1 use lib.cell;`
+    Instance {
+        /// Hierarchical path of the instance, starting with the top cell
+        path: String,
+        selection: LibrarySelection,
+        span: Span,
+    },
+    /// `cell name liblist a;` or `cell lib.name use lib.other;`
+    Cell {
+        cell: CellReference,
+        selection: LibrarySelection,
+        span: Span,
+    },
+}
+
+/// Where a configuration rule takes a cell from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LibrarySelection {
+    /// Search these libraries in order, for the instance and everything below it
+    Liblist(Vec<String>),
+    /// Use this cell
+    Use(CellReference),
+}
+
+/// Which elaboration system task a [`ModuleItem::ElaborationTask`] calls
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ElaborationSeverity {
+    /// `$fatal`, whose first argument may be a finish number
+    Fatal,
+    Error,
+    Warning,
+    Info,
+}
+
+impl ElaborationSeverity {
+    /// The task name without the `$`
+    pub fn name(self) -> &'static str {
+        match self {
+            ElaborationSeverity::Fatal => "fatal",
+            ElaborationSeverity::Error => "error",
+            ElaborationSeverity::Warning => "warning",
+            ElaborationSeverity::Info => "info",
+        }
+    }
+}
+
+/// A port connection in a module instantiation
+///
+/// Parameter value assignments (`#(.WIDTH(8))`) use the same forms.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortConnection {
+    /// Connection by position: `(a, b)`
+    Positional(ExprRef),
+    /// Connection by name: `.port(expr)`, or `.port()` when left unconnected
+    ///
+    /// The implicit form `.port` is stored as `.port(port)`.
+    Named {
+        name: String,
+        name_span: Span,
+        expr: Option<ExprRef>,
+    },
+    /// Wildcard connection: `.*`
+    Wildcard,
+}
+
+#[derive(Debug, Clone)]
+pub enum ClassItem {
+    Property {
+        qualifier: Option<ClassQualifier>,
+        /// Declared `static`, so every object of the class shares it
+        is_static: bool,
+        /// Declared `rand` or `randc`, so `randomize` picks its value
+        rand: Option<RandQualifier>,
+        data_type: DataType,
+        name: String,
+        name_span: Span,
+        unpacked_dimensions: Vec<UnpackedDimension>,
+        initial_value: Option<ExprRef>,
+        span: Span,
+    },
+    Method {
+        qualifier: Option<ClassQualifier>,
+        /// Declared `virtual`, so subclasses may override it
+        is_virtual: bool,
+        /// Declared `pure virtual`: a prototype without a body, which every
+        /// class that is not virtual itself has to implement
+        is_pure: bool,
+        /// Declared `static`, so it is called without an object
+        is_static: bool,
+        /// Declared `extern`, so its body is an [`ModuleItem::OutOfBlockMethod`]
+        /// and `body` is empty
+        is_extern: bool,
+        /// A `task`, which returns no value, rather than a `function`
+        is_task: bool,
+        return_type: Option<String>, // None for void
+        name: String,
+        name_span: Span,
+        parameters: Vec<String>, // simplified for now
+        body: Vec<StmtRef>,
+        span: Span,
+    },
+    Constraint(ConstraintBlock),
+}
+
+impl ClassItem {
+    /// Whether the item is the constructor, the method named `new`
+    pub fn is_constructor(&self) -> bool {
+        matches!(self, ClassItem::Method { name, .. } if name == "new")
+    }
+}
+
+/// `class::method`, the name linking an `extern` method prototype and its
+/// body outside the class
+pub fn scoped_method_name(class: &str, method: &str) -> String {
+    format!("{}::{}", class, method)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClassQualifier {
+    Local,
+    Protected,
+}
+
+/// `rand` or `randc` before a class property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RandQualifier {
+    /// Any value the constraints allow, on each call of `randomize`
+    Rand,
+    /// Every value the constraints allow, in random order, before any repeats
+    Randc,
+}
+
+/// `constraint c { x inside {[0:7]}; }` in a class
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConstraintBlock {
+    /// Declared `static`, so turning it on or off does so for every object
+    pub is_static: bool,
+    pub name: String,
+    pub name_span: Span,
+    pub constraints: Vec<ConstraintExpr>,
+    pub span: Span,
+}
+
+/// One constraint of a constraint block or of the `with` block of a
+/// `randomize` call
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConstraintExpr {
+    /// An expression that has to hold: `len < 8`; a `soft` one gives way to
+    /// the constraints it conflicts with
+    Expression {
+        soft: bool,
+        expr: ExprRef,
+        span: Span,
+    },
+    /// `x inside {1, [4:7]}`: one of the values, or in one of the value ranges
+    Inside {
+        soft: bool,
+        expr: ExprRef,
+        /// Values and [`Expression::ValueRange`]s
+        set: Vec<ExprRef>,
+        span: Span,
+    },
+    /// `y dist {0 := 5, [1:3] :/ 1}`: the values picked with the given weights
+    Dist {
+        soft: bool,
+        expr: ExprRef,
+        items: Vec<DistItem>,
+        span: Span,
+    },
+    /// `mode == 0 -> len < 4;`: constraints that only apply when `condition` holds
+    Implication {
+        condition: ExprRef,
+        constraints: Vec<ConstraintExpr>,
+        span: Span,
+    },
+    /// `if (short) len < 4; else len >= 4;`
+    IfElse {
+        condition: ExprRef,
+        then_constraints: Vec<ConstraintExpr>,
+        else_constraints: Vec<ConstraintExpr>,
+        span: Span,
+    },
+}
+
+impl ConstraintExpr {
+    pub fn span(&self) -> Span {
+        match self {
+            ConstraintExpr::Expression { span, .. }
+            | ConstraintExpr::Inside { span, .. }
+            | ConstraintExpr::Dist { span, .. }
+            | ConstraintExpr::Implication { span, .. }
+            | ConstraintExpr::IfElse { span, .. } => *span,
+        }
+    }
+
+    /// Every expression of the constraint, including those of the
+    /// constraints nested in it, in source order
+    pub fn expressions(&self) -> Vec<ExprRef> {
+        match self {
+            ConstraintExpr::Expression { expr, .. } => vec![*expr],
+            ConstraintExpr::Inside { expr, set, .. } => {
+                std::iter::once(*expr).chain(set.iter().copied()).collect()
+            }
+            ConstraintExpr::Dist { expr, items, .. } => std::iter::once(*expr)
+                .chain(items.iter().flat_map(|item| [item.value, item.weight]))
+                .collect(),
+            ConstraintExpr::Implication {
+                condition,
+                constraints,
+                ..
+            } => std::iter::once(*condition)
+                .chain(constraints.iter().flat_map(ConstraintExpr::expressions))
+                .collect(),
+            ConstraintExpr::IfElse {
+                condition,
+                then_constraints,
+                else_constraints,
+                ..
+            } => std::iter::once(*condition)
+                .chain(
+                    then_constraints
+                        .iter()
+                        .chain(else_constraints)
+                        .flat_map(ConstraintExpr::expressions),
+                )
+                .collect(),
+        }
+    }
+}
+
+/// Which check an assertion makes of its property or condition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertionKind {
+    /// `assert property`: the property has to hold
+    Assert,
+    /// `assume property`: the environment is expected to make it hold
+    Assume,
+    /// `cover property`: count the times it holds
+    Cover,
+    /// `restrict property`: only constrains formal tools, with no action
+    Restrict,
+}
+
+impl AssertionKind {
+    /// The keyword of the assertion, like `assume`
+    pub fn keyword(self) -> &'static str {
+        match self {
+            AssertionKind::Assert => "assert",
+            AssertionKind::Assume => "assume",
+            AssertionKind::Cover => "cover",
+            AssertionKind::Restrict => "restrict",
+        }
+    }
+}
+
+/// When a deferred immediate assertion reports, instead of as soon as it runs
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertionDeferral {
+    /// `assert #0`: in the Observed region, once the time step has settled
+    Observed,
+    /// `assert final`: at the end of the time step
+    Final,
+}
+
+impl AssertionDeferral {
+    /// The text after the assertion keyword, like `#0`
+    pub fn keyword(self) -> &'static str {
+        match self {
+            AssertionDeferral::Observed => "#0",
+            AssertionDeferral::Final => "final",
+        }
+    }
+}
+
+/// Whether a [`ModuleItem::AssertionDeclaration`] declares a sequence or a property
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertionDeclarationKind {
+    Sequence,
+    Property,
+}
+
+impl AssertionDeclarationKind {
+    /// The keyword that starts the declaration, like `sequence`
+    pub fn keyword(self) -> &'static str {
+        match self {
+            AssertionDeclarationKind::Sequence => "sequence",
+            AssertionDeclarationKind::Property => "property",
+        }
+    }
+}
+
+/// A sequence or property, as asserted by a concurrent assertion or
+/// declared by a `sequence` or `property` declaration
+#[derive(Debug, Clone, PartialEq)]
+pub enum PropertyExpr {
+    /// A boolean expression, or an instance of a declared sequence or
+    /// property like `handshake` or `stable_for(data, 3)`
+    Expression { expr: ExprRef, span: Span },
+    /// `a ##2 b` or `a ##[1:3] b`: `right` starts the given number of clock
+    /// ticks after `left` ends; a leading delay like `##1 b` has no `left`
+    Delay {
+        left: Option<Box<PropertyExpr>>,
+        cycles: CycleRange,
+        right: Box<PropertyExpr>,
+        span: Span,
+    },
+    /// `a[*3]`, `a[=2]` or `a[->1:2]`
+    Repetition {
+        operand: Box<PropertyExpr>,
+        kind: RepetitionKind,
+        cycles: CycleRange,
+        span: Span,
+    },
+    /// `req |-> gnt`, `valid throughout burst`
+    Binary {
+        op: PropertyOp,
+        left: Box<PropertyExpr>,
+        right: Box<PropertyExpr>,
+        span: Span,
+    },
+    /// `not p`
+    Not {
+        operand: Box<PropertyExpr>,
+        span: Span,
+    },
+    /// `@(posedge clk) a ##1 b` inside a property, for a clock of its own
+    Clocked {
+        clocking_event: ExprRef,
+        operand: Box<PropertyExpr>,
+        span: Span,
+    },
+}
+
+impl PropertyExpr {
+    pub fn span(&self) -> Span {
+        match self {
+            PropertyExpr::Expression { span, .. }
+            | PropertyExpr::Delay { span, .. }
+            | PropertyExpr::Repetition { span, .. }
+            | PropertyExpr::Binary { span, .. }
+            | PropertyExpr::Not { span, .. }
+            | PropertyExpr::Clocked { span, .. } => *span,
+        }
+    }
+
+    /// How tightly the property binds as an operand: that of its operator,
+    /// and tighter than any operator for an expression
+    pub fn precedence(&self) -> u8 {
+        match self {
+            PropertyExpr::Expression { .. } => u8::MAX,
+            PropertyExpr::Repetition { .. } => 9,
+            PropertyExpr::Delay { .. } => 8,
+            PropertyExpr::Binary { op, .. } => op.precedence(),
+            PropertyExpr::Not { .. } => 4,
+            PropertyExpr::Clocked { .. } => 0,
+        }
+    }
+
+    /// Every expression of the property, including the cycle counts and
+    /// clocking events, in source order
+    pub fn expressions(&self) -> Vec<ExprRef> {
+        match self {
+            PropertyExpr::Expression { expr, .. } => vec![*expr],
+            PropertyExpr::Delay {
+                left,
+                cycles,
+                right,
+                ..
+            } => left
+                .iter()
+                .flat_map(|left| left.expressions())
+                .chain(cycles.expressions())
+                .chain(right.expressions())
+                .collect(),
+            PropertyExpr::Repetition {
+                operand, cycles, ..
+            } => operand
+                .expressions()
+                .into_iter()
+                .chain(cycles.expressions())
+                .collect(),
+            PropertyExpr::Binary { left, right, .. } => left
+                .expressions()
+                .into_iter()
+                .chain(right.expressions())
+                .collect(),
+            PropertyExpr::Not { operand, .. } => operand.expressions(),
+            PropertyExpr::Clocked {
+                clocking_event,
+                operand,
+                ..
+            } => std::iter::once(*clocking_event)
+                .chain(operand.expressions())
+                .collect(),
+        }
+    }
+}
+
+/// Operators between two sequences or properties
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PropertyOp {
+    And,
+    Or,
+    Intersect,
+    Within,
+    Throughout,
+    /// `|->`: the consequent starts in the tick the antecedent ends
+    OverlappedImplication,
+    /// `|=>`: the consequent starts in the tick after the antecedent ends
+    NonOverlappedImplication,
+}
+
+impl PropertyOp {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PropertyOp::And => "and",
+            PropertyOp::Or => "or",
+            PropertyOp::Intersect => "intersect",
+            PropertyOp::Within => "within",
+            PropertyOp::Throughout => "throughout",
+            PropertyOp::OverlappedImplication => "|->",
+            PropertyOp::NonOverlappedImplication => "|=>",
+        }
+    }
+
+    /// Binding strength, higher binds tighter (IEEE 1800-2017 Table 16-3);
+    /// `throughout` and the implications group from the right, the rest
+    /// from the left
+    pub fn precedence(self) -> u8 {
+        match self {
+            PropertyOp::Throughout => 7,
+            PropertyOp::Within => 6,
+            PropertyOp::Intersect => 5,
+            PropertyOp::And => 3,
+            PropertyOp::Or => 2,
+            PropertyOp::OverlappedImplication | PropertyOp::NonOverlappedImplication => 1,
+        }
+    }
+}
+
+/// Which repetition a [`PropertyExpr::Repetition`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RepetitionKind {
+    /// `[*n]`: in `n` consecutive ticks
+    Consecutive,
+    /// `[=n]`: in `n` ticks, not necessarily consecutive
+    NonConsecutive,
+    /// `[->n]`: in `n` ticks, ending at the last of them
+    Goto,
+}
+
+impl RepetitionKind {
+    /// The operator that opens the repetition, like `[->`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RepetitionKind::Consecutive => "[*",
+            RepetitionKind::NonConsecutive => "[=",
+            RepetitionKind::Goto => "[->",
+        }
+    }
+}
+
+/// Number of clock ticks of a delay or repetition: `2`, `1:3` or `1:$`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CycleRange {
+    pub low: ExprRef,
+    /// None for a single number, like the `2` of `##2`
+    pub high: Option<CycleBound>,
+}
+
+impl CycleRange {
+    pub fn expressions(&self) -> Vec<ExprRef> {
+        match self.high {
+            Some(CycleBound::Count(high)) => vec![self.low, high],
+            _ => vec![self.low],
+        }
+    }
+}
+
+/// Upper end of a [`CycleRange`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CycleBound {
+    Count(ExprRef),
+    /// `$`, with no end
+    Unbounded,
+}
+
+/// A value or value range of a `dist` with its weight
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistItem {
+    /// A value, or an [`Expression::ValueRange`]
+    pub value: ExprRef,
+    pub weight: ExprRef,
+    /// `:/`, sharing the weight among the values of the range, rather than
+    /// `:=`, giving it to each of them
+    pub per_range: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProceduralBlockType {
+    Initial,
+    Final,
+    Always,
+    AlwaysComb,
+    AlwaysFF,
+}
+
+/// `@(posedge clk or negedge rst_n)`, `@ready` or `@*`
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventControl {
+    /// `@*` or `@(*)`: any change of what the statement reads
+    Implicit { span: Span },
+    /// Events separated by `or` or `,`; any one of them triggers the control
+    Events {
+        events: Vec<EventExpression>,
+        span: Span,
+    },
+}
+
+impl EventControl {
+    pub fn span(&self) -> Span {
+        match self {
+            EventControl::Implicit { span } | EventControl::Events { span, .. } => *span,
+        }
+    }
+
+    /// The signals and `iff` conditions of the events, in source order
+    pub fn expressions(&self) -> Vec<ExprRef> {
+        match self {
+            EventControl::Implicit { .. } => Vec::new(),
+            EventControl::Events { events, .. } => events
+                .iter()
+                .flat_map(|event| std::iter::once(event.signal).chain(event.iff))
+                .collect(),
+        }
+    }
+}
+
+/// What a timed statement waits for
+#[derive(Debug, Clone, PartialEq)]
+pub enum TimingControl {
+    /// `#10`
+    Delay(Delay),
+    /// `@(posedge clk)`
+    Event(EventControl),
+    /// `wait (ready)`: until the condition holds
+    Wait(ExprRef),
+}
+
+impl TimingControl {
+    /// The expressions the control reads, in source order
+    pub fn expressions(&self) -> Vec<ExprRef> {
+        match self {
+            TimingControl::Delay(_) => Vec::new(),
+            TimingControl::Event(control) => control.expressions(),
+            TimingControl::Wait(condition) => vec![*condition],
+        }
+    }
+}
+
+/// One event of an event control: `posedge clk iff enable`
+#[derive(Debug, Clone, PartialEq)]
+pub struct EventExpression {
+    /// None for any change of the signal
+    pub edge: Option<Edge>,
+    pub signal: ExprRef,
+    /// Condition that must hold for the event to count: `iff enable`
+    pub iff: Option<ExprRef>,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    Posedge,
+    Negedge,
+    /// `edge`: either transition
+    Edge,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssignmentOp {
+    Assign,      // =
+    AddAssign,   // +=
+    SubAssign,   // -=
+    MulAssign,   // *=
+    DivAssign,   // /=
+    ModAssign,   // %=
+    AndAssign,   // &=
+    OrAssign,    // |=
+    XorAssign,   // ^=
+    ShlAssign,   // <<=
+    ShrAssign,   // >>=
+    AShlAssign,  // <<<=
+    AShrAssign,  // >>>=
+    NonBlocking, // <=
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Assignment {
+        target: ExprRef,
+        op: AssignmentOp,
+        /// Intra-assignment delay: `q <= #2 d;`
+        delay: Option<Delay>,
+        expr: ExprRef,
+        span: Span,
+    },
+    SystemCall {
+        name: String,
+        args: Vec<ExprRef>,
+        span: Span,
+    },
+    CaseStatement {
+        modifier: Option<String>, // priority, unique, or unique0
+        case_type: String,        // case, casex, or casez
+        /// `case (expr) inside`: item values are compared with `==?`, and may
+        /// be value ranges
+        inside: bool,
+        expr: ExprRef,
+        items: Vec<CaseItem>,
+        span: Span,
+    },
+    ExpressionStatement {
+        expr: ExprRef,
+        span: Span,
+    },
+    /// `if (cond) ... else ...`; `else if` is a conditional in the else branch
+    Conditional {
+        modifier: Option<String>, // priority, unique, or unique0
+        condition: ExprRef,
+        then_statement: StmtRef,
+        else_statement: Option<StmtRef>,
+        span: Span,
+    },
+    /// A concurrent assertion in a procedure, like
+    /// [`ModuleItem::ConcurrentAssertion`]
+    AssertProperty {
+        kind: AssertionKind,
+        clocking_event: Option<ExprRef>,
+        disable_iff: Option<ExprRef>,
+        property: PropertyExpr,
+        pass_action: Option<StmtRef>,
+        fail_action: Option<StmtRef>,
+        span: Span,
+    },
+    /// `assert (cond) pass; else fail;`, checked when the statement runs,
+    /// or the `assume` or `cover` form; `restrict` has no immediate form
+    ImmediateAssertion {
+        kind: AssertionKind,
+        /// None for a simple assertion, which reports at once
+        deferral: Option<AssertionDeferral>,
+        condition: ExprRef,
+        pass_action: Option<StmtRef>,
+        fail_action: Option<StmtRef>,
+        span: Span,
+    },
+    VariableDeclaration {
+        data_type: DataType,
+        name: String,
+        name_span: Span,
+        initial_value: Option<ExprRef>,
+        span: Span,
+    },
+    /// Sequential block: `begin : label ... end : label`
+    Block {
+        label: Option<Label>,
+        statements: Vec<StmtRef>,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    /// Parallel block: `fork : name ... join_none : name`, whose statements
+    /// run as concurrent processes
+    Fork {
+        label: Option<Label>,
+        statements: Vec<StmtRef>,
+        /// How the block waits for the processes it starts
+        join: JoinKind,
+        end_label: Option<Label>,
+        span: Span,
+    },
+    /// Statement with a label prefix: `label: assert ...`
+    Labeled {
+        label: Label,
+        statement: StmtRef,
+        span: Span,
+    },
+    /// `-> done;` or `->> done;`: trigger a named event, waking the
+    /// processes waiting for it
+    EventTrigger {
+        /// `->>`, which triggers the event in the nonblocking region
+        nonblocking: bool,
+        event: ExprRef,
+        span: Span,
+    },
+    /// `wait fork;` - wait for all child processes
+    WaitFork {
+        span: Span,
+    },
+    /// `disable fork;` - stop all child processes
+    DisableFork {
+        span: Span,
+    },
+    /// `disable name;` - stop a named block or task
+    Disable {
+        target: String,
+        target_span: Span,
+        span: Span,
+    },
+    /// Pattern matching case: `case (expr) matches tagged Valid .n: ...; endcase`
+    CaseMatches {
+        modifier: Option<String>, // priority, unique, or unique0
+        case_type: String,        // case, casex, or casez
+        expr: ExprRef,
+        items: Vec<CaseMatchesItem>,
+        span: Span,
+    },
+    /// `return;` or `return value;` in a function
+    Return {
+        value: Option<ExprRef>,
+        span: Span,
+    },
+    /// `for (int i = 0; i < n; i++) body`
+    ForLoop {
+        /// Variable declarations and assignments before the first `;`
+        init: Vec<StmtRef>,
+        condition: Option<ExprRef>,
+        /// Assignments and increments after the second `;`, like `i++`
+        step: Vec<StmtRef>,
+        body: StmtRef,
+        span: Span,
+    },
+    /// `while (cond) body`
+    While {
+        condition: ExprRef,
+        body: StmtRef,
+        span: Span,
+    },
+    /// `do body while (cond);`
+    DoWhile {
+        body: StmtRef,
+        condition: ExprRef,
+        span: Span,
+    },
+    /// `repeat (count) body`
+    Repeat {
+        count: ExprRef,
+        body: StmtRef,
+        span: Span,
+    },
+    /// `#10 a = b;`, `@(posedge clk) q <= d;` or `wait (ready);`: wait, then
+    /// run the statement, if any
+    Timed {
+        control: TimingControl,
+        statement: Option<StmtRef>,
+        span: Span,
+    },
+    /// `forever body`
+    Forever {
+        body: StmtRef,
+        span: Span,
+    },
+    /// `foreach (array[i, j]) body`
+    Foreach {
+        /// The array, with any hierarchy, like `cfg.data` in `foreach (cfg.data[i])`
+        array: String,
+        array_span: Span,
+        /// One loop variable per dimension; None for a skipped dimension, like
+        /// the first in `foreach (matrix[, j])`
+        indices: Vec<Option<LoopVariable>>,
+        body: StmtRef,
+        span: Span,
+    },
+}
+
+impl Statement {
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Assignment { span, .. }
+            | Statement::SystemCall { span, .. }
+            | Statement::CaseStatement { span, .. }
+            | Statement::ExpressionStatement { span, .. }
+            | Statement::Conditional { span, .. }
+            | Statement::AssertProperty { span, .. }
+            | Statement::ImmediateAssertion { span, .. }
+            | Statement::VariableDeclaration { span, .. }
+            | Statement::Block { span, .. }
+            | Statement::Fork { span, .. }
+            | Statement::Labeled { span, .. }
+            | Statement::EventTrigger { span, .. }
+            | Statement::WaitFork { span }
+            | Statement::DisableFork { span }
+            | Statement::Disable { span, .. }
+            | Statement::CaseMatches { span, .. }
+            | Statement::Return { span, .. }
+            | Statement::ForLoop { span, .. }
+            | Statement::While { span, .. }
+            | Statement::DoWhile { span, .. }
+            | Statement::Repeat { span, .. }
+            | Statement::Timed { span, .. }
+            | Statement::Forever { span, .. }
+            | Statement::Foreach { span, .. } => *span,
+        }
+    }
+
+    /// Statements nested directly in this one, like the branches of an `if`
+    /// or the body of a loop, in source order
+    pub fn children(&self) -> Vec<StmtRef> {
+        match self {
+            Statement::Block { statements, .. } | Statement::Fork { statements, .. } => {
+                statements.clone()
+            }
+            Statement::Labeled { statement, .. } => vec![*statement],
+            Statement::Timed { statement, .. } => statement.iter().copied().collect(),
+            Statement::AssertProperty {
+                pass_action,
+                fail_action,
+                ..
+            }
+            | Statement::ImmediateAssertion {
+                pass_action,
+                fail_action,
+                ..
+            } => pass_action.iter().chain(fail_action).copied().collect(),
+            Statement::Conditional {
+                then_statement,
+                else_statement,
+                ..
+            } => std::iter::once(*then_statement)
+                .chain(*else_statement)
+                .collect(),
+            Statement::CaseStatement { items, .. } => {
+                items.iter().map(|item| item.statement).collect()
+            }
+            Statement::CaseMatches { items, .. } => {
+                items.iter().map(|item| item.statement).collect()
+            }
+            Statement::ForLoop {
+                init, step, body, ..
+            } => init
+                .iter()
+                .chain(step)
+                .chain(std::iter::once(body))
+                .copied()
+                .collect(),
+            Statement::While { body, .. }
+            | Statement::DoWhile { body, .. }
+            | Statement::Repeat { body, .. }
+            | Statement::Forever { body, .. }
+            | Statement::Foreach { body, .. } => vec![*body],
+            Statement::Assignment { .. }
+            | Statement::SystemCall { .. }
+            | Statement::ExpressionStatement { .. }
+            | Statement::VariableDeclaration { .. }
+            | Statement::Return { .. }
+            | Statement::EventTrigger { .. }
+            | Statement::WaitFork { .. }
+            | Statement::DisableFork { .. }
+            | Statement::Disable { .. } => Vec::new(),
+        }
+    }
+}
+
+/// The keyword ending a `fork` block, which says what it waits for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinKind {
+    /// `join`: every process has finished
+    Join,
+    /// `join_any`: any one process has finished
+    JoinAny,
+    /// `join_none`: nothing; the processes run on after the block
+    JoinNone,
+}
+
+impl JoinKind {
+    pub fn keyword(self) -> &'static str {
+        match self {
+            JoinKind::Join => "join",
+            JoinKind::JoinAny => "join_any",
+            JoinKind::JoinNone => "join_none",
+        }
+    }
+}
+
+/// One item of a `case`, `casez` or `casex` statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseItem {
+    /// Values compared with the case expression; empty for `default`
+    pub values: Vec<ExprRef>,
+    pub statement: StmtRef,
+    pub span: Span,
+}
+
+/// One arm of a `case ... matches` statement
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseMatchesItem {
+    /// None for the `default` arm
+    pub pattern: Option<Pattern>,
+    /// Extra condition after `&&&`
+    pub guard: Option<ExprRef>,
+    pub statement: StmtRef,
+    pub span: Span,
+}
+
+/// Pattern in a `case ... matches` arm
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    /// `.name` - binds the matched value to a new variable
+    Variable(String, Span),
+    /// `.*` - matches anything
+    Wildcard(Span),
+    /// `tagged Member [pattern]`
+    Tagged {
+        member: String,
+        member_span: Span,
+        pattern: Option<Box<Pattern>>,
+        span: Span,
+    },
+    /// Constant expression the value must equal
+    Constant(ExprRef),
+}
+
+/// A loop variable of a `foreach` statement
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopVariable {
+    pub name: String,
+    pub span: Span,
+}
+
+/// A block name or statement label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Label {
+    pub name: String,
+    pub span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PortDirection {
+    Input,
+    Output,
+    Inout,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Port {
+    pub name: String,
+    pub name_span: Span,
+    pub direction: Option<PortDirection>,
+    /// Type keyword of an ANSI port, like `logic` in `input logic [7:0] a`
+    pub data_type: Option<String>,
+    pub range: Option<Range>,
+    /// Interface of an interface port, like `bus_if` in `bus_if.master m`, or
+    /// `interface` for a generic interface port
+    pub interface: Option<String>,
+    /// Modport of an interface port, like `master` in `bus_if.master m`
+    pub modport: Option<String>,
+    pub span: Span,
+}
+
+/// One `pkg::name` or `pkg::*` of an import declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackageImport {
+    pub package: String,
+    pub package_span: Span,
+    /// The imported name; None for a wildcard import of the whole package
+    pub name: Option<String>,
+    /// Span of the name, or of the `*`
+    pub name_span: Span,
+    pub span: Span,
+}
+
+/// One modport of a modport declaration: `master(output req, input gnt)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Modport {
+    pub name: String,
+    pub name_span: Span,
+    pub ports: Vec<ModportPort>,
+    pub span: Span,
+}
+
+/// Signal of a modport; a port without a direction takes the direction of
+/// the one before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModportPort {
+    pub direction: PortDirection,
+    pub name: String,
+    pub name_span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Range {
+    pub msb: String, // Most significant bit (e.g., "7" in [7:0])
+    pub lsb: String, // Least significant bit (e.g., "0" in [7:0])
+}
+
+/// Data type of a variable, net or typedef declaration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DataType {
+    /// A type keyword like `logic` or `wire`, or a named type like `packet_t`
+    /// or `pkg::packet_t`
+    Named(String),
+    /// Inline `struct [packed] { ... }` type
+    Struct {
+        packed: bool,
+        members: Vec<StructMember>,
+    },
+    /// Inline `union [tagged] [packed] { ... }` type
+    Union {
+        /// Declared `tagged`, so a value records which member it holds, as
+        /// in `tagged Valid 5`
+        tagged: bool,
+        packed: bool,
+        members: Vec<StructMember>,
+    },
+    /// Inline `enum { ... }` type
+    Enum(EnumType),
+    /// Parameterized class with the values of its parameters: `fifo#(int, 16)`
+    Specialized(TypeReference),
+}
+
+impl DataType {
+    /// The type's keyword or name: "struct", "union" and "enum" for inline types
+    pub fn name(&self) -> &str {
+        match self {
+            DataType::Named(name) => name,
+            DataType::Struct { .. } => "struct",
+            DataType::Union { .. } => "union",
+            DataType::Enum(_) => "enum",
+            DataType::Specialized(reference) => &reference.name,
+        }
+    }
+
+    /// Members of an inline struct or union type; empty for other types
+    pub fn struct_members(&self) -> &[StructMember] {
+        match self {
+            DataType::Struct { members, .. } | DataType::Union { members, .. } => members,
+            _ => &[],
+        }
+    }
+}
+
+/// A class type with the values of its parameters: `fifo#(bit [7:0], 16)`
+///
+/// The values are given by position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeReference {
+    pub name: String,
+    pub name_span: Span,
+    pub arguments: Vec<ParameterArgument>,
+}
+
+/// Value of a class parameter, in a specialization or as its default
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParameterArgument {
+    /// Data type written with a type keyword, as text: `bit [7:0]`
+    Type(String),
+    /// An expression; a lone name may be a type as well as a value
+    Value(ExprRef),
+}
+
+/// Parameter port of a class: `type T = int` or `int DEPTH = 8`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassParameter {
+    /// Declared with `type`, so its value is a data type
+    pub is_type: bool,
+    /// Data type of a value parameter, like `int` or `bit [3:0]`
+    pub data_type: Option<String>,
+    pub name: String,
+    pub name_span: Span,
+    pub default: Option<ParameterArgument>,
+    pub span: Span,
+}
+
+/// Member of an inline struct or union type: `logic [7:0] addr;`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StructMember {
+    pub data_type: DataType,
+    pub range: Option<Range>,
+    pub name: String,
+    pub name_span: Span,
+    pub span: Span,
+}
+
+/// Inline enum type: `enum logic [1:0] { IDLE, BUSY = 2 }`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumType {
+    /// Base type keyword, like `logic`; None for the default `int`
+    pub base_type: Option<String>,
+    pub signing: Option<String>,
+    pub range: Option<Range>,
+    pub members: Vec<EnumMember>,
+}
+
+/// Named value of an enum type: `BUSY = 2`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnumMember {
+    pub name: String,
+    pub name_span: Span,
+    /// Explicit value; None when the member takes the value after the one before it
+    pub value: Option<ExprRef>,
+}
+
+/// Represents an unpacked array dimension
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnpackedDimension {
+    /// Dynamic array dimension: []
+    Dynamic,
+    /// Fixed-size unpacked array dimension: [N]
+    FixedSize(String),
+    /// Range-based unpacked array: [msb:lsb]
+    Range(String, String),
+}
+
+/// Net types, which are driven by continuous assignments
+pub const NET_TYPES: &[&str] = &[
+    "supply0", "supply1", "tri", "triand", "trior", "trireg", "tri0", "tri1", "uwire", "wire",
+    "wand", "wor",
+];
+
+/// Whether a declaration's data type makes it a net rather than a variable
+pub fn is_net_type(data_type: &str) -> bool {
+    NET_TYPES.contains(&data_type)
+}
+
+/// `(strong0, weak1)`, whose strengths may come in either order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriveStrength {
+    pub strength0: String, // First strength as written (e.g., "highz0", "weak1")
+    pub strength1: String, // Second strength as written (e.g., "strong1", "pull0")
+    pub span: Span,
+}
+
+/// A time literal of a `` `timescale `` or time unit declaration, like `10ps`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeValue {
+    /// 1, 10 or 100 in a valid declaration
+    pub magnitude: u32,
+    pub unit: TimeUnit,
+    pub span: Span,
+}
+
+impl TimeValue {
+    /// Length of the time in femtoseconds, the smallest unit
+    pub fn femtoseconds(&self) -> u128 {
+        u128::from(self.magnitude) * self.unit.femtoseconds()
+    }
+
+    /// The literal as written without spaces: `10ps`
+    pub fn text(&self) -> String {
+        format!("{}{}", self.magnitude, self.unit.name())
+    }
+}
+
+/// Units from the shortest to the longest, so they compare by length
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TimeUnit {
+    Fs,
+    Ps,
+    Ns,
+    Us,
+    Ms,
+    S,
+}
+
+impl TimeUnit {
+    /// The unit as written: `ns`
+    pub fn name(self) -> &'static str {
+        match self {
+            TimeUnit::Fs => "fs",
+            TimeUnit::Ps => "ps",
+            TimeUnit::Ns => "ns",
+            TimeUnit::Us => "us",
+            TimeUnit::Ms => "ms",
+            TimeUnit::S => "s",
+        }
+    }
+
+    /// Length of the unit in femtoseconds
+    pub fn femtoseconds(self) -> u128 {
+        1000u128.pow(self as u32)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Delay {
+    /// Simple delay: #10
+    Value(String),
+    /// Delay with expression: #(expr)
+    Expression(String), // For now, store as string; could be Expression later
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expression {
+    Identifier(String, Span),
+    Number(String, Span),
+    StringLiteral(String, Span),
+    /// The current class object: `this`
+    This(Span),
+    /// The base class object, as in `super.show()`; the base class
+    /// constructor is `super.new`, a member access of `new`
+    Super(Span),
+    Binary {
+        op: BinaryOp,
+        left: ExprRef,
+        right: ExprRef,
+        span: Span,
+    },
+    Unary {
+        op: UnaryOp,
+        operand: ExprRef,
+        span: Span,
+    },
+    /// Increment or decrement: `i++`, `--i`
+    IncDec {
+        op: IncDecOp,
+        /// Whether the operator comes before the operand, giving the updated value
+        prefix: bool,
+        operand: ExprRef,
+        span: Span,
+    },
+    MacroUsage {
+        name: String,
+        name_span: Span,
+        arguments: Vec<ExprRef>,
+        span: Span,
+    },
+    SystemFunctionCall {
+        name: String,
+        arguments: Vec<ExprRef>,
+        span: Span,
+    },
+    New {
+        arguments: Vec<ExprRef>,
+        span: Span,
+    },
+    MemberAccess {
+        object: ExprRef,
+        member: String,
+        member_span: Span,
+        span: Span,
+    },
+    FunctionCall {
+        function: ExprRef,
+        arguments: Vec<ExprRef>,
+        span: Span,
+    },
+    /// Conditional operator: `sel ? a : b`
+    Conditional {
+        condition: ExprRef,
+        then_expr: ExprRef,
+        else_expr: ExprRef,
+        span: Span,
+    },
+    /// Tagged union value: `tagged Valid 5`, `tagged Invalid`
+    TaggedUnion {
+        member: String,
+        member_span: Span,
+        value: Option<ExprRef>,
+        span: Span,
+    },
+    /// Class or package scoped name: `process::self`
+    ScopedIdentifier {
+        scope: String,
+        name: String,
+        name_span: Span,
+        span: Span,
+    },
+    /// Assignment pattern: `'{addr: 0, data: 1}`, `'{0, 1}`, `'{default: 0}`
+    AssignmentPattern {
+        items: Vec<AssignmentPatternItem>,
+        span: Span,
+    },
+    /// Value range of a `case ... inside` item: `[lo:hi]`, matching the
+    /// values from `lo` to `hi` inclusive
+    ValueRange {
+        low: ExprRef,
+        high: ExprRef,
+        span: Span,
+    },
+    /// A call with inline constraints: `obj.randomize() with { len < 8; }`
+    RandomizeWith {
+        call: ExprRef,
+        constraints: Vec<ConstraintExpr>,
+        span: Span,
+    },
+}
+
+impl Expression {
+    /// How tightly the expression binds as an operand: that of its binary
+    /// operator, between `||` and the implications for the conditional
+    /// operator, and tighter than any operator for the rest
+    pub fn precedence(&self) -> u8 {
+        match self {
+            Expression::Binary { op, .. } => op.precedence(),
+            Expression::Conditional { .. } => 1,
+            _ => u8::MAX,
+        }
+    }
+}
+
+/// One value of an assignment pattern, with the member name or `default` it is for
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssignmentPatternItem {
+    /// None for a positional value
+    pub key: Option<String>,
+    pub key_span: Span,
+    pub value: ExprRef,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Modulo, // %
+    And,
+    Or,
+    Xor,
+    BitwiseXnor,          // ~^
+    LogicalShiftLeft,     // <<
+    LogicalShiftRight,    // >>
+    ArithmeticShiftLeft,  // <<<
+    ArithmeticShiftRight, // >>>
+    LogicalEquiv,         // <->
+    LogicalImpl,          // ->
+    Equal,                // ==
+    NotEqual,             // !=
+    CaseEqual,            // ===
+    CaseNotEqual,         // !==
+    WildcardEqual,        // ==?
+    WildcardNotEqual,     // !=?
+    LogicalAnd,           // &&
+    LogicalOr,            // ||
+    GreaterThan,          // >
+    LessThan,             // <
+    GreaterEqual,         // >=
+    LessEqual,            // <=
+    Power,                // **
+}
+
+impl BinaryOp {
+    /// How tightly the operator binds, after IEEE 1800 Table 11-2: `**`
+    /// binds tightest, the implications `->` and `<->` loosest
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Power => 12,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Modulo => 11,
+            BinaryOp::Add | BinaryOp::Sub => 10,
+            BinaryOp::LogicalShiftLeft
+            | BinaryOp::LogicalShiftRight
+            | BinaryOp::ArithmeticShiftLeft
+            | BinaryOp::ArithmeticShiftRight => 9,
+            BinaryOp::LessThan
+            | BinaryOp::LessEqual
+            | BinaryOp::GreaterThan
+            | BinaryOp::GreaterEqual => 8,
+            BinaryOp::Equal
+            | BinaryOp::NotEqual
+            | BinaryOp::CaseEqual
+            | BinaryOp::CaseNotEqual
+            | BinaryOp::WildcardEqual
+            | BinaryOp::WildcardNotEqual => 7,
+            BinaryOp::And => 6,
+            BinaryOp::Xor | BinaryOp::BitwiseXnor => 5,
+            BinaryOp::Or => 4,
+            BinaryOp::LogicalAnd => 3,
+            BinaryOp::LogicalOr => 2,
+            BinaryOp::LogicalImpl | BinaryOp::LogicalEquiv => 0,
+        }
+    }
+
+    /// Whether `a op b op c` groups as `a op (b op c)`, as only the
+    /// implications do
+    pub fn is_right_associative(&self) -> bool {
+        matches!(self, BinaryOp::LogicalImpl | BinaryOp::LogicalEquiv)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnaryOp {
+    Plus,          // +
+    Minus,         // -
+    Not,           // ~
+    ReductionAnd,  // &
+    ReductionOr,   // |
+    ReductionXor,  // ^
+    ReductionNand, // ~&
+    ReductionNor,  // ~|
+    ReductionXnor, // ~^
+    LogicalNot,    // !
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncDecOp {
+    Increment, // ++
+    Decrement, // --
+}
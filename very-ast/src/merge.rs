@@ -0,0 +1,1163 @@
+//! Merging source units
+//!
+//! Every node of a [`SourceUnit`] refers to others by their index in its
+//! arenas. Moving the nodes of one unit into another offsets those indices,
+//! so each reference inside the moved nodes is offset by the same amount.
+
+use crate::{
+    AssignmentPatternItem, CaseItem, CaseMatchesItem, ClassItem, ClassParameter, ConstraintBlock,
+    ConstraintExpr, CycleBound, CycleRange, DataType, DistItem, EnumMember, EnumType, EventControl,
+    EventExpression, ExprRef, Expression, GenerateCaseItem, ModuleItem, ModuleItemRef,
+    ParameterArgument, Pattern, PortConnection, PropertyExpr, SourceUnit, Statement, StmtRef,
+    StructMember, TimingControl, TypeReference,
+};
+
+impl SourceUnit {
+    /// Append the design of `other` to this unit, as if its text followed
+    /// this unit's
+    ///
+    /// The nodes of `other` keep their spans, which still point into the text
+    /// it was parsed from; its source map is kept for them, see
+    /// [`SourceMap::source_map_of`](crate::SourceMap::source_map_of).
+    pub fn merge(&mut self, other: SourceUnit) {
+        let items = self.absorb(other);
+        self.items.extend(items);
+    }
+
+    /// Move the nodes of `other` into this unit's arenas and return its
+    /// top-level items, without adding them to this unit's items, for a
+    /// parser splicing an included file in among the items of a module
+    pub fn absorb(&mut self, other: SourceUnit) -> Vec<ModuleItemRef> {
+        let offsets = Offsets {
+            expr: offset(self.expr_arena.nodes.len(), other.expr_arena.nodes.len()),
+            stmt: offset(self.stmt_arena.nodes.len(), other.stmt_arena.nodes.len()),
+            item: offset(
+                self.module_item_arena.nodes.len(),
+                other.module_item_arena.nodes.len(),
+            ),
+        };
+        self.expr_arena.nodes.extend(
+            other
+                .expr_arena
+                .nodes
+                .into_iter()
+                .map(|expr| offsets.expression(expr)),
+        );
+        self.stmt_arena.nodes.extend(
+            other
+                .stmt_arena
+                .nodes
+                .into_iter()
+                .map(|stmt| offsets.statement(stmt)),
+        );
+        self.module_item_arena.nodes.extend(
+            other
+                .module_item_arena
+                .nodes
+                .into_iter()
+                .map(|item| offsets.item(item)),
+        );
+        let items = offsets.item..self.module_item_arena.nodes.len() as u32;
+        self.source_map.record_merged(items, other.source_map);
+        other.items.into_iter().map(|r| r + offsets.item).collect()
+    }
+}
+
+/// Offset of the nodes appended to an arena holding `len` nodes; panics when
+/// the merged arena would outgrow the 32-bit references
+fn offset(len: usize, appended: usize) -> u32 {
+    let total = len.checked_add(appended);
+    assert!(
+        total.is_some_and(|total| u32::try_from(total).is_ok()),
+        "merged arena exceeds the 32-bit reference range"
+    );
+    len as u32
+}
+
+/// How far the references of each arena move
+#[derive(Clone, Copy)]
+struct Offsets {
+    expr: u32,
+    stmt: u32,
+    item: u32,
+}
+
+impl Offsets {
+    fn exprs(self, refs: Vec<ExprRef>) -> Vec<ExprRef> {
+        refs.into_iter().map(|r| r + self.expr).collect()
+    }
+
+    fn stmts(self, refs: Vec<StmtRef>) -> Vec<StmtRef> {
+        refs.into_iter().map(|r| r + self.stmt).collect()
+    }
+
+    fn items(self, refs: Vec<ModuleItemRef>) -> Vec<ModuleItemRef> {
+        refs.into_iter().map(|r| r + self.item).collect()
+    }
+
+    fn data_type(self, data_type: DataType) -> DataType {
+        match data_type {
+            DataType::Enum(enum_type) => DataType::Enum(EnumType {
+                members: enum_type
+                    .members
+                    .into_iter()
+                    .map(|member| EnumMember {
+                        value: member.value.map(|r| r + self.expr),
+                        ..member
+                    })
+                    .collect(),
+                ..enum_type
+            }),
+            DataType::Specialized(reference) => DataType::Specialized(TypeReference {
+                arguments: reference
+                    .arguments
+                    .into_iter()
+                    .map(|argument| self.parameter_argument(argument))
+                    .collect(),
+                ..reference
+            }),
+            DataType::Struct { packed, members } => DataType::Struct {
+                packed,
+                members: self.struct_members(members),
+            },
+            DataType::Union {
+                tagged,
+                packed,
+                members,
+            } => DataType::Union {
+                tagged,
+                packed,
+                members: self.struct_members(members),
+            },
+            data_type => data_type,
+        }
+    }
+
+    fn struct_members(self, members: Vec<StructMember>) -> Vec<StructMember> {
+        members
+            .into_iter()
+            .map(|member| StructMember {
+                data_type: self.data_type(member.data_type),
+                ..member
+            })
+            .collect()
+    }
+
+    fn parameter_argument(self, argument: ParameterArgument) -> ParameterArgument {
+        match argument {
+            ParameterArgument::Value(r) => ParameterArgument::Value(r + self.expr),
+            argument => argument,
+        }
+    }
+
+    fn connection(self, connection: PortConnection) -> PortConnection {
+        match connection {
+            PortConnection::Positional(expr) => PortConnection::Positional(expr + self.expr),
+            PortConnection::Named {
+                name,
+                name_span,
+                expr,
+            } => PortConnection::Named {
+                name,
+                name_span,
+                expr: expr.map(|r| r + self.expr),
+            },
+            PortConnection::Wildcard => PortConnection::Wildcard,
+        }
+    }
+
+    fn expression(self, expr: Expression) -> Expression {
+        match expr {
+            Expression::Binary {
+                op,
+                left,
+                right,
+                span,
+            } => Expression::Binary {
+                op,
+                left: left + self.expr,
+                right: right + self.expr,
+                span,
+            },
+            Expression::Unary { op, operand, span } => Expression::Unary {
+                op,
+                operand: operand + self.expr,
+                span,
+            },
+            Expression::IncDec {
+                op,
+                prefix,
+                operand,
+                span,
+            } => Expression::IncDec {
+                op,
+                prefix,
+                operand: operand + self.expr,
+                span,
+            },
+            Expression::MacroUsage {
+                name,
+                name_span,
+                arguments,
+                span,
+            } => Expression::MacroUsage {
+                name,
+                name_span,
+                arguments: self.exprs(arguments),
+                span,
+            },
+            Expression::SystemFunctionCall {
+                name,
+                arguments,
+                span,
+            } => Expression::SystemFunctionCall {
+                name,
+                arguments: self.exprs(arguments),
+                span,
+            },
+            Expression::New { arguments, span } => Expression::New {
+                arguments: self.exprs(arguments),
+                span,
+            },
+            Expression::MemberAccess {
+                object,
+                member,
+                member_span,
+                span,
+            } => Expression::MemberAccess {
+                object: object + self.expr,
+                member,
+                member_span,
+                span,
+            },
+            Expression::FunctionCall {
+                function,
+                arguments,
+                span,
+            } => Expression::FunctionCall {
+                function: function + self.expr,
+                arguments: self.exprs(arguments),
+                span,
+            },
+            Expression::Conditional {
+                condition,
+                then_expr,
+                else_expr,
+                span,
+            } => Expression::Conditional {
+                condition: condition + self.expr,
+                then_expr: then_expr + self.expr,
+                else_expr: else_expr + self.expr,
+                span,
+            },
+            Expression::TaggedUnion {
+                member,
+                member_span,
+                value,
+                span,
+            } => Expression::TaggedUnion {
+                member,
+                member_span,
+                value: value.map(|r| r + self.expr),
+                span,
+            },
+            Expression::AssignmentPattern { items, span } => Expression::AssignmentPattern {
+                items: items
+                    .into_iter()
+                    .map(|item| AssignmentPatternItem {
+                        value: item.value + self.expr,
+                        ..item
+                    })
+                    .collect(),
+                span,
+            },
+            Expression::ValueRange { low, high, span } => Expression::ValueRange {
+                low: low + self.expr,
+                high: high + self.expr,
+                span,
+            },
+            Expression::RandomizeWith {
+                call,
+                constraints,
+                span,
+            } => Expression::RandomizeWith {
+                call: call + self.expr,
+                constraints: self.constraints(constraints),
+                span,
+            },
+            leaf @ (Expression::Identifier(..)
+            | Expression::Number(..)
+            | Expression::StringLiteral(..)
+            | Expression::This(..)
+            | Expression::Super(..)
+            | Expression::ScopedIdentifier { .. }) => leaf,
+        }
+    }
+
+    fn pattern(self, pattern: Pattern) -> Pattern {
+        match pattern {
+            Pattern::Tagged {
+                member,
+                member_span,
+                pattern,
+                span,
+            } => Pattern::Tagged {
+                member,
+                member_span,
+                pattern: pattern.map(|p| Box::new(self.pattern(*p))),
+                span,
+            },
+            Pattern::Constant(expr) => Pattern::Constant(expr + self.expr),
+            leaf @ (Pattern::Variable(..) | Pattern::Wildcard(..)) => leaf,
+        }
+    }
+
+    fn event_control(self, control: EventControl) -> EventControl {
+        match control {
+            EventControl::Implicit { span } => EventControl::Implicit { span },
+            EventControl::Events { events, span } => EventControl::Events {
+                events: events
+                    .into_iter()
+                    .map(|event| EventExpression {
+                        signal: event.signal + self.expr,
+                        iff: event.iff.map(|iff| iff + self.expr),
+                        ..event
+                    })
+                    .collect(),
+                span,
+            },
+        }
+    }
+
+    fn statement(self, stmt: Statement) -> Statement {
+        match stmt {
+            Statement::Assignment {
+                target,
+                op,
+                delay,
+                expr,
+                span,
+            } => Statement::Assignment {
+                target: target + self.expr,
+                op,
+                delay,
+                expr: expr + self.expr,
+                span,
+            },
+            Statement::SystemCall { name, args, span } => Statement::SystemCall {
+                name,
+                args: self.exprs(args),
+                span,
+            },
+            Statement::Conditional {
+                modifier,
+                condition,
+                then_statement,
+                else_statement,
+                span,
+            } => Statement::Conditional {
+                modifier,
+                condition: condition + self.expr,
+                then_statement: then_statement + self.stmt,
+                else_statement: else_statement.map(|stmt| stmt + self.stmt),
+                span,
+            },
+            Statement::CaseStatement {
+                modifier,
+                case_type,
+                inside,
+                expr,
+                items,
+                span,
+            } => Statement::CaseStatement {
+                modifier,
+                case_type,
+                inside,
+                expr: expr + self.expr,
+                items: items
+                    .into_iter()
+                    .map(|item| CaseItem {
+                        values: self.exprs(item.values),
+                        statement: item.statement + self.stmt,
+                        span: item.span,
+                    })
+                    .collect(),
+                span,
+            },
+            Statement::ExpressionStatement { expr, span } => Statement::ExpressionStatement {
+                expr: expr + self.expr,
+                span,
+            },
+            Statement::AssertProperty {
+                kind,
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                span,
+            } => Statement::AssertProperty {
+                kind,
+                clocking_event: clocking_event.map(|r| r + self.expr),
+                disable_iff: disable_iff.map(|r| r + self.expr),
+                property: self.property(property),
+                pass_action: pass_action.map(|r| r + self.stmt),
+                fail_action: fail_action.map(|r| r + self.stmt),
+                span,
+            },
+            Statement::ImmediateAssertion {
+                kind,
+                deferral,
+                condition,
+                pass_action,
+                fail_action,
+                span,
+            } => Statement::ImmediateAssertion {
+                kind,
+                deferral,
+                condition: condition + self.expr,
+                pass_action: pass_action.map(|r| r + self.stmt),
+                fail_action: fail_action.map(|r| r + self.stmt),
+                span,
+            },
+            Statement::VariableDeclaration {
+                data_type,
+                name,
+                name_span,
+                initial_value,
+                span,
+            } => Statement::VariableDeclaration {
+                data_type: self.data_type(data_type),
+                name,
+                name_span,
+                initial_value: initial_value.map(|r| r + self.expr),
+                span,
+            },
+            Statement::Block {
+                label,
+                statements,
+                end_label,
+                span,
+            } => Statement::Block {
+                label,
+                statements: self.stmts(statements),
+                end_label,
+                span,
+            },
+            Statement::Fork {
+                label,
+                statements,
+                join,
+                end_label,
+                span,
+            } => Statement::Fork {
+                label,
+                statements: self.stmts(statements),
+                join,
+                end_label,
+                span,
+            },
+            Statement::Labeled {
+                label,
+                statement,
+                span,
+            } => Statement::Labeled {
+                label,
+                statement: statement + self.stmt,
+                span,
+            },
+            Statement::CaseMatches {
+                modifier,
+                case_type,
+                expr,
+                items,
+                span,
+            } => Statement::CaseMatches {
+                modifier,
+                case_type,
+                expr: expr + self.expr,
+                items: items
+                    .into_iter()
+                    .map(|item| CaseMatchesItem {
+                        pattern: item.pattern.map(|pattern| self.pattern(pattern)),
+                        guard: item.guard.map(|r| r + self.expr),
+                        statement: item.statement + self.stmt,
+                        span: item.span,
+                    })
+                    .collect(),
+                span,
+            },
+            Statement::EventTrigger {
+                nonblocking,
+                event,
+                span,
+            } => Statement::EventTrigger {
+                nonblocking,
+                event: event + self.expr,
+                span,
+            },
+            Statement::Return { value, span } => Statement::Return {
+                value: value.map(|r| r + self.expr),
+                span,
+            },
+            Statement::ForLoop {
+                init,
+                condition,
+                step,
+                body,
+                span,
+            } => Statement::ForLoop {
+                init: self.stmts(init),
+                condition: condition.map(|r| r + self.expr),
+                step: self.stmts(step),
+                body: body + self.stmt,
+                span,
+            },
+            Statement::While {
+                condition,
+                body,
+                span,
+            } => Statement::While {
+                condition: condition + self.expr,
+                body: body + self.stmt,
+                span,
+            },
+            Statement::DoWhile {
+                body,
+                condition,
+                span,
+            } => Statement::DoWhile {
+                body: body + self.stmt,
+                condition: condition + self.expr,
+                span,
+            },
+            Statement::Repeat { count, body, span } => Statement::Repeat {
+                count: count + self.expr,
+                body: body + self.stmt,
+                span,
+            },
+            Statement::Timed {
+                control,
+                statement,
+                span,
+            } => Statement::Timed {
+                control: match control {
+                    TimingControl::Delay(delay) => TimingControl::Delay(delay),
+                    TimingControl::Event(control) => {
+                        TimingControl::Event(self.event_control(control))
+                    }
+                    TimingControl::Wait(condition) => TimingControl::Wait(condition + self.expr),
+                },
+                statement: statement.map(|statement| statement + self.stmt),
+                span,
+            },
+            Statement::Forever { body, span } => Statement::Forever {
+                body: body + self.stmt,
+                span,
+            },
+            Statement::Foreach {
+                array,
+                array_span,
+                indices,
+                body,
+                span,
+            } => Statement::Foreach {
+                array,
+                array_span,
+                indices,
+                body: body + self.stmt,
+                span,
+            },
+            leaf @ (Statement::WaitFork { .. }
+            | Statement::DisableFork { .. }
+            | Statement::Disable { .. }) => leaf,
+        }
+    }
+
+    fn class_item(self, class_item: ClassItem) -> ClassItem {
+        match class_item {
+            ClassItem::Property {
+                qualifier,
+                is_static,
+                rand,
+                data_type,
+                name,
+                name_span,
+                unpacked_dimensions,
+                initial_value,
+                span,
+            } => ClassItem::Property {
+                qualifier,
+                is_static,
+                rand,
+                data_type: self.data_type(data_type),
+                name,
+                name_span,
+                unpacked_dimensions,
+                initial_value: initial_value.map(|r| r + self.expr),
+                span,
+            },
+            ClassItem::Method {
+                qualifier,
+                is_virtual,
+                is_pure,
+                is_static,
+                is_extern,
+                is_task,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                body,
+                span,
+            } => ClassItem::Method {
+                qualifier,
+                is_virtual,
+                is_pure,
+                is_static,
+                is_extern,
+                is_task,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                body: self.stmts(body),
+                span,
+            },
+            ClassItem::Constraint(block) => ClassItem::Constraint(ConstraintBlock {
+                constraints: self.constraints(block.constraints),
+                ..block
+            }),
+        }
+    }
+
+    fn constraints(self, constraints: Vec<ConstraintExpr>) -> Vec<ConstraintExpr> {
+        constraints
+            .into_iter()
+            .map(|constraint| self.constraint(constraint))
+            .collect()
+    }
+
+    fn constraint(self, constraint: ConstraintExpr) -> ConstraintExpr {
+        match constraint {
+            ConstraintExpr::Expression { soft, expr, span } => ConstraintExpr::Expression {
+                soft,
+                expr: expr + self.expr,
+                span,
+            },
+            ConstraintExpr::Inside {
+                soft,
+                expr,
+                set,
+                span,
+            } => ConstraintExpr::Inside {
+                soft,
+                expr: expr + self.expr,
+                set: self.exprs(set),
+                span,
+            },
+            ConstraintExpr::Dist {
+                soft,
+                expr,
+                items,
+                span,
+            } => ConstraintExpr::Dist {
+                soft,
+                expr: expr + self.expr,
+                items: items
+                    .into_iter()
+                    .map(|item| DistItem {
+                        value: item.value + self.expr,
+                        weight: item.weight + self.expr,
+                        ..item
+                    })
+                    .collect(),
+                span,
+            },
+            ConstraintExpr::Implication {
+                condition,
+                constraints,
+                span,
+            } => ConstraintExpr::Implication {
+                condition: condition + self.expr,
+                constraints: self.constraints(constraints),
+                span,
+            },
+            ConstraintExpr::IfElse {
+                condition,
+                then_constraints,
+                else_constraints,
+                span,
+            } => ConstraintExpr::IfElse {
+                condition: condition + self.expr,
+                then_constraints: self.constraints(then_constraints),
+                else_constraints: self.constraints(else_constraints),
+                span,
+            },
+        }
+    }
+
+    fn property(self, property: PropertyExpr) -> PropertyExpr {
+        let boxed = |property: Box<PropertyExpr>| Box::new(self.property(*property));
+        match property {
+            PropertyExpr::Expression { expr, span } => PropertyExpr::Expression {
+                expr: expr + self.expr,
+                span,
+            },
+            PropertyExpr::Delay {
+                left,
+                cycles,
+                right,
+                span,
+            } => PropertyExpr::Delay {
+                left: left.map(boxed),
+                cycles: self.cycles(cycles),
+                right: boxed(right),
+                span,
+            },
+            PropertyExpr::Repetition {
+                operand,
+                kind,
+                cycles,
+                span,
+            } => PropertyExpr::Repetition {
+                operand: boxed(operand),
+                kind,
+                cycles: self.cycles(cycles),
+                span,
+            },
+            PropertyExpr::Binary {
+                op,
+                left,
+                right,
+                span,
+            } => PropertyExpr::Binary {
+                op,
+                left: boxed(left),
+                right: boxed(right),
+                span,
+            },
+            PropertyExpr::Not { operand, span } => PropertyExpr::Not {
+                operand: boxed(operand),
+                span,
+            },
+            PropertyExpr::Clocked {
+                clocking_event,
+                operand,
+                span,
+            } => PropertyExpr::Clocked {
+                clocking_event: clocking_event + self.expr,
+                operand: boxed(operand),
+                span,
+            },
+        }
+    }
+
+    fn cycles(self, cycles: CycleRange) -> CycleRange {
+        CycleRange {
+            low: cycles.low + self.expr,
+            high: cycles.high.map(|high| match high {
+                CycleBound::Count(high) => CycleBound::Count(high + self.expr),
+                CycleBound::Unbounded => CycleBound::Unbounded,
+            }),
+        }
+    }
+
+    fn item(self, item: ModuleItem) -> ModuleItem {
+        match item {
+            ModuleItem::ModuleDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            } => ModuleItem::ModuleDeclaration {
+                name,
+                name_span,
+                ports,
+                items: self.items(items),
+                span,
+            },
+            ModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            } => ModuleItem::InterfaceDeclaration {
+                name,
+                name_span,
+                ports,
+                items: self.items(items),
+                span,
+            },
+            ModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                ports,
+                items,
+                span,
+            } => ModuleItem::CheckerDeclaration {
+                name,
+                name_span,
+                ports,
+                items: self.items(items),
+                span,
+            },
+            ModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items,
+                span,
+            } => ModuleItem::PackageDeclaration {
+                name,
+                name_span,
+                items: self.items(items),
+                span,
+            },
+            ModuleItem::VariableDeclaration {
+                data_type,
+                signing,
+                drive_strength,
+                delay,
+                range,
+                name,
+                name_span,
+                unpacked_dimensions,
+                initial_value,
+                span,
+            } => ModuleItem::VariableDeclaration {
+                data_type: self.data_type(data_type),
+                signing,
+                drive_strength,
+                delay,
+                range,
+                name,
+                name_span,
+                unpacked_dimensions,
+                initial_value: initial_value.map(|r| r + self.expr),
+                span,
+            },
+            ModuleItem::TypedefDeclaration {
+                data_type,
+                signing,
+                range,
+                name,
+                name_span,
+                unpacked_dimensions,
+                span,
+            } => ModuleItem::TypedefDeclaration {
+                data_type: self.data_type(data_type),
+                signing,
+                range,
+                name,
+                name_span,
+                unpacked_dimensions,
+                span,
+            },
+            ModuleItem::Assignment {
+                drive_strength,
+                delay,
+                target,
+                expr,
+                implicit,
+                span,
+            } => ModuleItem::Assignment {
+                drive_strength,
+                delay,
+                target: target + self.expr,
+                expr: expr + self.expr,
+                implicit,
+                span,
+            },
+            ModuleItem::ProceduralBlock {
+                block_type,
+                event_control,
+                statements,
+                label,
+                end_label,
+                span,
+            } => ModuleItem::ProceduralBlock {
+                block_type,
+                event_control: event_control.map(|control| self.event_control(control)),
+                statements: self.stmts(statements),
+                label,
+                end_label,
+                span,
+            },
+            ModuleItem::ClassDeclaration {
+                is_virtual,
+                name,
+                name_span,
+                parameters,
+                extends,
+                implements,
+                items,
+                span,
+            } => ModuleItem::ClassDeclaration {
+                is_virtual,
+                name,
+                name_span,
+                parameters: parameters
+                    .into_iter()
+                    .map(|parameter| ClassParameter {
+                        default: parameter
+                            .default
+                            .map(|argument| self.parameter_argument(argument)),
+                        ..parameter
+                    })
+                    .collect(),
+                extends,
+                implements,
+                items: items.into_iter().map(|i| self.class_item(i)).collect(),
+                span,
+            },
+            ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                class_name_span,
+                name,
+                name_span,
+                parameters,
+                body,
+                span,
+            } => ModuleItem::OutOfBlockMethod {
+                return_type,
+                class_name,
+                class_name_span,
+                name,
+                name_span,
+                parameters,
+                body: self.stmts(body),
+                span,
+            },
+            ModuleItem::SubroutineDeclaration {
+                is_task,
+                is_automatic,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                body,
+                span,
+            } => ModuleItem::SubroutineDeclaration {
+                is_task,
+                is_automatic,
+                return_type,
+                name,
+                name_span,
+                parameters,
+                body: self.stmts(body),
+                span,
+            },
+            ModuleItem::InterfaceClassDeclaration {
+                name,
+                name_span,
+                extends,
+                items,
+                span,
+            } => ModuleItem::InterfaceClassDeclaration {
+                name,
+                name_span,
+                extends,
+                items: items.into_iter().map(|i| self.class_item(i)).collect(),
+                span,
+            },
+            ModuleItem::ConcurrentAssertion {
+                kind,
+                clocking_event,
+                disable_iff,
+                property,
+                pass_action,
+                fail_action,
+                span,
+            } => ModuleItem::ConcurrentAssertion {
+                kind,
+                clocking_event: clocking_event.map(|r| r + self.expr),
+                disable_iff: disable_iff.map(|r| r + self.expr),
+                property: self.property(property),
+                pass_action: pass_action.map(|r| r + self.stmt),
+                fail_action: fail_action.map(|r| r + self.stmt),
+                span,
+            },
+            ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                ports,
+                clocking_event,
+                disable_iff,
+                body,
+                end_label,
+                span,
+            } => ModuleItem::AssertionDeclaration {
+                kind,
+                name,
+                name_span,
+                ports,
+                clocking_event: clocking_event.map(|r| r + self.expr),
+                disable_iff: disable_iff.map(|r| r + self.expr),
+                body: self.property(body),
+                end_label,
+                span,
+            },
+            ModuleItem::DefaultDisableIff { condition, span } => ModuleItem::DefaultDisableIff {
+                condition: condition + self.expr,
+                span,
+            },
+            ModuleItem::GlobalClocking {
+                identifier,
+                identifier_span,
+                clocking_event,
+                end_label,
+                span,
+            } => ModuleItem::GlobalClocking {
+                identifier,
+                identifier_span,
+                clocking_event: clocking_event + self.expr,
+                end_label,
+                span,
+            },
+            ModuleItem::ClockingBlock {
+                default,
+                name,
+                name_span,
+                clocking_event,
+                default_input_skew,
+                default_output_skew,
+                signals,
+                end_label,
+                span,
+            } => ModuleItem::ClockingBlock {
+                default,
+                name,
+                name_span,
+                clocking_event: clocking_event + self.expr,
+                default_input_skew,
+                default_output_skew,
+                signals,
+                end_label,
+                span,
+            },
+            ModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                name_span,
+                value,
+                span,
+            } => ModuleItem::ParameterDeclaration {
+                local,
+                data_type,
+                range,
+                name,
+                name_span,
+                value: value + self.expr,
+                span,
+            },
+            ModuleItem::ModuleInstantiation {
+                module_name,
+                module_name_span,
+                parameters,
+                instance_name,
+                instance_name_span,
+                connections,
+                span,
+            } => ModuleItem::ModuleInstantiation {
+                module_name,
+                module_name_span,
+                parameters: parameters.into_iter().map(|c| self.connection(c)).collect(),
+                instance_name,
+                instance_name_span,
+                connections: connections
+                    .into_iter()
+                    .map(|c| self.connection(c))
+                    .collect(),
+                span,
+            },
+            ModuleItem::ElaborationTask {
+                severity,
+                arguments,
+                span,
+            } => ModuleItem::ElaborationTask {
+                severity,
+                arguments: self.exprs(arguments),
+                span,
+            },
+            ModuleItem::GenerateRegion { items, span } => ModuleItem::GenerateRegion {
+                items: self.items(items),
+                span,
+            },
+            ModuleItem::GenerateBlock {
+                label,
+                items,
+                end_label,
+                span,
+            } => ModuleItem::GenerateBlock {
+                label,
+                items: self.items(items),
+                end_label,
+                span,
+            },
+            ModuleItem::GenerateFor {
+                declares_genvar,
+                genvar,
+                genvar_span,
+                init,
+                condition,
+                step_op,
+                step,
+                body,
+                span,
+            } => ModuleItem::GenerateFor {
+                declares_genvar,
+                genvar,
+                genvar_span,
+                init: init + self.expr,
+                condition: condition + self.expr,
+                step_op,
+                step: step + self.expr,
+                body: body + self.item,
+                span,
+            },
+            ModuleItem::GenerateIf {
+                condition,
+                then_item,
+                else_item,
+                span,
+            } => ModuleItem::GenerateIf {
+                condition: condition + self.expr,
+                then_item: then_item + self.item,
+                else_item: else_item.map(|r| r + self.item),
+                span,
+            },
+            ModuleItem::GenerateCase { expr, items, span } => ModuleItem::GenerateCase {
+                expr: expr + self.expr,
+                items: items
+                    .into_iter()
+                    .map(|item| GenerateCaseItem {
+                        values: self.exprs(item.values),
+                        body: item.body + self.item,
+                        span: item.span,
+                    })
+                    .collect(),
+                span,
+            },
+            // Listed one by one so that a new variant holding references
+            // cannot slip through unchanged
+            leaf @ (ModuleItem::ModportDeclaration { .. }
+            | ModuleItem::ImportDeclaration { .. }
+            | ModuleItem::ExportDeclaration { .. }
+            | ModuleItem::GenvarDeclaration { .. }
+            | ModuleItem::PortDeclaration { .. }
+            | ModuleItem::DefineDirective { .. }
+            | ModuleItem::IncludeDirective { .. }
+            | ModuleItem::TimescaleDirective { .. }
+            | ModuleItem::DefaultNettypeDirective { .. }
+            | ModuleItem::TimeunitsDeclaration { .. }
+            | ModuleItem::DefaultClocking { .. }
+            | ModuleItem::ConfigDeclaration { .. }) => leaf,
+        }
+    }
+}
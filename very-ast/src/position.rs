@@ -0,0 +1,154 @@
+//! Span to line/column conversion
+//!
+//! Spans are character offsets into the source text, while editors address
+//! text by line and column, with columns counted in UTF-8 bytes, UTF-16 code
+//! units or characters depending on the negotiated [`PositionEncoding`].
+//! These helpers do the conversion both ways, so tools embedding the parser
+//! report the same positions as the language server. With the `lsp` feature
+//! they also produce `lsp_types` ranges directly.
+
+use serde::Serialize;
+
+use crate::Span;
+
+/// Unit in which columns are counted
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PositionEncoding {
+    /// Bytes of the UTF-8 encoding
+    Utf8,
+    /// UTF-16 code units, the LSP default
+    #[default]
+    Utf16,
+    /// Characters (Unicode scalar values)
+    Utf32,
+}
+
+impl PositionEncoding {
+    fn width(self, c: char) -> u32 {
+        match self {
+            PositionEncoding::Utf8 => c.len_utf8() as u32,
+            PositionEncoding::Utf16 => c.len_utf16() as u32,
+            PositionEncoding::Utf32 => 1,
+        }
+    }
+}
+
+/// Zero-based line and column
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize)]
+pub struct LineColumn {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// Line and column of the character offset `offset`; offsets past the end
+/// are clamped to the end of `text`
+pub fn offset_to_line_column(text: &str, offset: usize, encoding: PositionEncoding) -> LineColumn {
+    let mut position = LineColumn::default();
+    for c in text.chars().take(offset) {
+        if c == '\n' {
+            position.line += 1;
+            position.column = 0;
+        } else {
+            position.column += encoding.width(c);
+        }
+    }
+    position
+}
+
+/// Character offset of `position`; columns past the end of their line are
+/// clamped to the line end and lines past the end of `text` to its end
+pub fn line_column_to_offset(
+    text: &str,
+    position: LineColumn,
+    encoding: PositionEncoding,
+) -> usize {
+    let mut offset = 0;
+    let mut line = 0;
+    let mut column = 0;
+    for c in text.chars() {
+        if line == position.line && (c == '\n' || column + encoding.width(c) > position.column) {
+            break;
+        }
+        if c == '\n' {
+            line += 1;
+        } else if line == position.line {
+            column += encoding.width(c);
+        }
+        offset += 1;
+    }
+    offset
+}
+
+/// Start and end line/column of `span`
+pub fn span_to_line_columns(
+    text: &str,
+    span: Span,
+    encoding: PositionEncoding,
+) -> (LineColumn, LineColumn) {
+    (
+        offset_to_line_column(text, span.0, encoding),
+        offset_to_line_column(text, span.1, encoding),
+    )
+}
+
+#[cfg(feature = "lsp")]
+mod lsp {
+    use super::{line_column_to_offset, offset_to_line_column, LineColumn, PositionEncoding, Span};
+    use lsp_types::{Position, PositionEncodingKind, Range};
+
+    impl PositionEncoding {
+        /// The encoding named by an LSP `PositionEncodingKind`, if it is one of the standard ones
+        pub fn from_kind(kind: &PositionEncodingKind) -> Option<Self> {
+            match kind.as_str() {
+                "utf-8" => Some(PositionEncoding::Utf8),
+                "utf-16" => Some(PositionEncoding::Utf16),
+                "utf-32" => Some(PositionEncoding::Utf32),
+                _ => None,
+            }
+        }
+
+        pub fn kind(self) -> PositionEncodingKind {
+            match self {
+                PositionEncoding::Utf8 => PositionEncodingKind::UTF8,
+                PositionEncoding::Utf16 => PositionEncodingKind::UTF16,
+                PositionEncoding::Utf32 => PositionEncodingKind::UTF32,
+            }
+        }
+    }
+
+    impl From<LineColumn> for Position {
+        fn from(position: LineColumn) -> Self {
+            Position::new(position.line, position.column)
+        }
+    }
+
+    impl From<Position> for LineColumn {
+        fn from(position: Position) -> Self {
+            LineColumn {
+                line: position.line,
+                column: position.character,
+            }
+        }
+    }
+
+    /// LSP position of the character offset `offset`
+    pub fn offset_to_position(text: &str, offset: usize, encoding: PositionEncoding) -> Position {
+        offset_to_line_column(text, offset, encoding).into()
+    }
+
+    /// Character offset of an LSP position
+    pub fn position_to_offset(text: &str, position: Position, encoding: PositionEncoding) -> usize {
+        line_column_to_offset(text, position.into(), encoding)
+    }
+
+    /// LSP range covering `span`
+    pub fn span_to_range(text: &str, span: Span, encoding: PositionEncoding) -> Range {
+        Range::new(
+            offset_to_position(text, span.0, encoding),
+            offset_to_position(text, span.1, encoding),
+        )
+    }
+}
+
+#[cfg(feature = "lsp")]
+pub use lsp::{offset_to_position, position_to_offset, span_to_range};
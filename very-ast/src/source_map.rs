@@ -0,0 +1,270 @@
+//! Where parsed text comes from
+//!
+//! The preprocessor rewrites the source before it is parsed, so the text a
+//! node was parsed from may come from a macro or sit in a conditional branch.
+//! A [`SourceMap`] records both, for diagnostics to point back at the
+//! directives, and can keep every directive of the file for tools that need
+//! the source as written.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use crate::{ModuleItemRef, Span};
+
+/// Where a macro used in the source was defined
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DefinitionSite {
+    /// None for text parsed with `parse_content`
+    pub file: Option<PathBuf>,
+    /// Span of the `define in `file`
+    pub span: Span,
+    /// 0-based line of the `define in `file`
+    pub line: usize,
+}
+
+/// Why a piece of the parsed text is there, for text that was not written
+/// as is in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Provenance {
+    /// The text comes from expanding macro `name`, used at `span`
+    Expansion {
+        name: String,
+        span: Span,
+        /// None for macros defined on the command line or in the configuration
+        definition: Option<DefinitionSite>,
+    },
+    /// The text is in the compiled branch of a conditional directive
+    Branch {
+        /// The directive opening the branch: `` `ifdef SIM ``, or
+        /// `` `else of `ifdef SIM ``
+        directive: String,
+        span: Span,
+    },
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provenance::Expansion {
+                name,
+                definition: None,
+                ..
+            } => write!(f, "expanded from `{} defined on the command line", name),
+            Provenance::Expansion {
+                name,
+                definition: Some(site),
+                ..
+            } => match &site.file {
+                Some(file) => {
+                    let file_name = file.file_name().map_or_else(
+                        || file.display().to_string(),
+                        |name| name.to_string_lossy().to_string(),
+                    );
+                    write!(
+                        f,
+                        "expanded from `{} defined at {}:{}",
+                        name,
+                        file_name,
+                        site.line + 1
+                    )
+                }
+                None => write!(
+                    f,
+                    "expanded from `{} defined at line {}",
+                    name,
+                    site.line + 1
+                ),
+            },
+            Provenance::Branch { directive, .. } => write!(f, "in branch {}", directive),
+        }
+    }
+}
+
+/// Which compiler directive a [`Directive`] is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DirectiveKind {
+    Define,
+    Undef,
+    Include,
+    Ifdef,
+    Ifndef,
+    Elsif,
+    Else,
+    Endif,
+    Timescale,
+    /// Any other directive by its name without the backquote, whether the
+    /// standard defines it, like `default_nettype`, or not
+    Other(String),
+}
+
+impl DirectiveKind {
+    /// The kind of the directive named `name`, without the backquote
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "define" => DirectiveKind::Define,
+            "undef" => DirectiveKind::Undef,
+            "include" => DirectiveKind::Include,
+            "ifdef" => DirectiveKind::Ifdef,
+            "ifndef" => DirectiveKind::Ifndef,
+            "elsif" => DirectiveKind::Elsif,
+            "else" => DirectiveKind::Else,
+            "endif" => DirectiveKind::Endif,
+            "timescale" => DirectiveKind::Timescale,
+            _ => DirectiveKind::Other(name.to_string()),
+        }
+    }
+}
+
+/// A compiler directive as written in the source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directive {
+    pub kind: DirectiveKind,
+    /// The directive and its arguments, like `` `timescale 1ns/1ps ``
+    pub text: String,
+    pub span: Span,
+    /// Whether the directive is in compiled text, rather than in a
+    /// conditional branch that is not taken
+    pub active: bool,
+}
+
+/// A macro expansion in the source text
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ExpansionRecord {
+    name: String,
+    span: Span,
+    definition: Option<DefinitionSite>,
+}
+
+/// A compiled branch of a conditional directive
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BranchRecord {
+    directive: String,
+    span: Span,
+    /// From the end of the directive to the directive closing the branch
+    body: Span,
+}
+
+/// The source map of a unit merged into another
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct MergedRecord {
+    /// Where the module items of the unit went in the merged arena
+    items: Range<ModuleItemRef>,
+    source_map: SourceMap,
+}
+
+/// The macro expansions and compiled conditional branches of a file, to
+/// trace the parsed text back to the directives that produced it
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// None for text parsed with `parse_content`
+    file: Option<PathBuf>,
+    expansions: Vec<ExpansionRecord>,
+    branches: Vec<BranchRecord>,
+    /// Every directive of the file in source order, when the parser retains them
+    directives: Vec<Directive>,
+    /// The maps of the units merged into this one, whose spans point into
+    /// their own files
+    merged: Vec<MergedRecord>,
+}
+
+impl SourceMap {
+    pub fn is_empty(&self) -> bool {
+        self.expansions.is_empty()
+            && self.branches.is_empty()
+            && self.directives.is_empty()
+            && self.merged.is_empty()
+    }
+
+    /// The file the text was read from
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn set_file(&mut self, file: Option<PathBuf>) {
+        self.file = file;
+    }
+
+    /// The directives of the file in source order, including those in
+    /// branches not taken; empty unless the parser was asked to retain them
+    pub fn directives(&self) -> &[Directive] {
+        &self.directives
+    }
+
+    /// Note that macro `name`, defined at `definition`, was expanded at `span`
+    pub fn record_expansion(
+        &mut self,
+        name: String,
+        span: Span,
+        definition: Option<DefinitionSite>,
+    ) {
+        self.expansions.push(ExpansionRecord {
+            name,
+            span,
+            definition,
+        });
+    }
+
+    /// Note that the branch opened by `directive` at `span` was compiled,
+    /// with `body` running from the end of the directive to the directive
+    /// closing the branch
+    pub fn record_branch(&mut self, directive: String, span: Span, body: Span) {
+        self.branches.push(BranchRecord {
+            directive,
+            span,
+            body,
+        });
+    }
+
+    /// Note a directive found in the file
+    pub fn record_directive(&mut self, directive: Directive) {
+        self.directives.push(directive);
+    }
+
+    /// Keep the map of a unit merged into this one, whose module items now
+    /// take the indices `items` of the merged arena
+    pub fn record_merged(&mut self, items: Range<ModuleItemRef>, source_map: SourceMap) {
+        self.merged.push(MergedRecord { items, source_map });
+    }
+
+    /// The map of the text module item `item` was parsed from, and the
+    /// spans of its nodes point into: that of the merged unit it came from,
+    /// or this one
+    pub fn source_map_of(&self, item: ModuleItemRef) -> &SourceMap {
+        self.merged
+            .iter()
+            .find(|merged| merged.items.contains(&item))
+            .map_or(self, |merged| {
+                merged.source_map.source_map_of(item - merged.items.start)
+            })
+    }
+
+    /// Where the text at `span` comes from: the macro expansion holding it,
+    /// then the conditional branches holding it, innermost first
+    ///
+    /// Expansions are recorded where they are used in the file, so a macro
+    /// used inside another macro's text is reported as the outer one.
+    pub fn provenance(&self, span: Span) -> Vec<Provenance> {
+        let mut notes: Vec<Provenance> = self
+            .expansions
+            .iter()
+            .filter(|expansion| expansion.span.0 <= span.0 && span.1 <= expansion.span.1)
+            .map(|expansion| Provenance::Expansion {
+                name: expansion.name.clone(),
+                span: expansion.span,
+                definition: expansion.definition.clone(),
+            })
+            .collect();
+        let mut branches: Vec<&BranchRecord> = self
+            .branches
+            .iter()
+            .filter(|branch| branch.body.0 <= span.0 && span.0 < branch.body.1)
+            .collect();
+        branches.sort_by_key(|branch| std::cmp::Reverse(branch.body.0));
+        notes.extend(branches.into_iter().map(|branch| Provenance::Branch {
+            directive: branch.directive.clone(),
+            span: branch.span,
+        }));
+        notes
+    }
+}
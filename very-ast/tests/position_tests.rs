@@ -0,0 +1,81 @@
+//! Span to line/column conversion tests.
+
+use very_ast::position::{line_column_to_offset, offset_to_line_column, span_to_line_columns};
+use very_ast::{LineColumn, PositionEncoding};
+
+// 'é' is 2 bytes and 1 UTF-16 unit, '𝄞' is 4 bytes and 2 UTF-16 units
+const TEXT: &str = "// é 𝄞\nwire w;\n";
+
+fn at(line: u32, column: u32) -> LineColumn {
+    LineColumn { line, column }
+}
+
+#[test]
+fn test_columns_follow_encoding() {
+    // Offset 6 is the newline after '𝄞'
+    assert_eq!(
+        offset_to_line_column(TEXT, 6, PositionEncoding::Utf8),
+        at(0, 10)
+    );
+    assert_eq!(
+        offset_to_line_column(TEXT, 6, PositionEncoding::Utf16),
+        at(0, 7)
+    );
+    assert_eq!(
+        offset_to_line_column(TEXT, 6, PositionEncoding::Utf32),
+        at(0, 6)
+    );
+}
+
+#[test]
+fn test_span_on_later_line() {
+    let start = TEXT.chars().position(|c| c == 'w').unwrap();
+    let (from, to) = span_to_line_columns(TEXT, (start, start + 4), PositionEncoding::Utf16);
+    assert_eq!(from, at(1, 0));
+    assert_eq!(to, at(1, 4));
+}
+
+#[test]
+fn test_offset_round_trip_and_clamping() {
+    for encoding in [
+        PositionEncoding::Utf8,
+        PositionEncoding::Utf16,
+        PositionEncoding::Utf32,
+    ] {
+        for offset in 0..=TEXT.chars().count() {
+            let position = offset_to_line_column(TEXT, offset, encoding);
+            assert_eq!(line_column_to_offset(TEXT, position, encoding), offset);
+        }
+    }
+
+    let end = TEXT.chars().count();
+    assert_eq!(
+        offset_to_line_column(TEXT, end + 10, PositionEncoding::Utf16),
+        at(2, 0)
+    );
+    // Past the end of a line clamps to the line end, past the last line to the end of text
+    assert_eq!(
+        line_column_to_offset(TEXT, at(1, 99), PositionEncoding::Utf16),
+        TEXT.chars().position(|c| c == ';').unwrap() + 1
+    );
+    assert_eq!(
+        line_column_to_offset(TEXT, at(9, 0), PositionEncoding::Utf16),
+        end
+    );
+}
+
+#[cfg(feature = "lsp")]
+#[test]
+fn test_span_to_lsp_range() {
+    use lsp_types::{Position, PositionEncodingKind, Range};
+    use very_ast::position::span_to_range;
+
+    assert_eq!(
+        span_to_range(TEXT, (3, 6), PositionEncoding::Utf16),
+        Range::new(Position::new(0, 3), Position::new(0, 7))
+    );
+    assert_eq!(
+        PositionEncoding::from_kind(&PositionEncodingKind::UTF8),
+        Some(PositionEncoding::Utf8)
+    );
+}
@@ -0,0 +1,82 @@
+//! Building and merging syntax trees without the parser.
+
+use very_ast::{
+    ExprArena, Expression, ModuleItem, ModuleItemArena, Provenance, SourceMap, SourceUnit,
+    StmtArena,
+};
+
+/// `module name; assign target = value; endmodule`, built by hand
+fn module_unit(name: &str, target: &str, value: &str) -> SourceUnit {
+    let mut expr_arena = ExprArena::new();
+    let target = expr_arena.alloc(Expression::Identifier(target.to_string(), (0, 0)));
+    let expr = expr_arena.alloc(Expression::Identifier(value.to_string(), (0, 0)));
+    let mut module_item_arena = ModuleItemArena::new();
+    let assignment = module_item_arena.alloc(ModuleItem::Assignment {
+        drive_strength: None,
+        delay: None,
+        target,
+        expr,
+        implicit: false,
+        span: (0, 0),
+    });
+    let module = module_item_arena.alloc(ModuleItem::ModuleDeclaration {
+        name: name.to_string(),
+        name_span: (0, 0),
+        ports: Vec::new(),
+        items: vec![assignment],
+        span: (0, 0),
+    });
+    SourceUnit {
+        items: vec![module],
+        expr_arena,
+        stmt_arena: StmtArena::new(),
+        module_item_arena,
+        source_map: SourceMap::default(),
+    }
+}
+
+/// The references of a merged unit still lead to its own nodes.
+#[test]
+fn test_merge_offsets_references() {
+    let mut unit = module_unit("first", "a", "b");
+    unit.merge(module_unit("second", "c", "d"));
+    assert_eq!(unit.items.len(), 2);
+
+    let ModuleItem::ModuleDeclaration { name, items, .. } =
+        unit.module_item_arena.get(unit.items[1])
+    else {
+        panic!("Expected module declaration");
+    };
+    assert_eq!(name, "second");
+    let ModuleItem::Assignment { target, expr, .. } = unit.module_item_arena.get(items[0]) else {
+        panic!("Expected assignment");
+    };
+    assert!(matches!(
+        unit.expr_arena.get(*target),
+        Expression::Identifier(name, _) if name == "c"
+    ));
+    assert!(matches!(
+        unit.expr_arena.get(*expr),
+        Expression::Identifier(name, _) if name == "d"
+    ));
+}
+
+/// A source map reports the expansion holding a span before the branches
+/// around it, innermost branch first.
+#[test]
+fn test_source_map_provenance() {
+    let mut source_map = SourceMap::default();
+    assert!(source_map.is_empty());
+    source_map.record_branch("`ifdef SIM".to_string(), (0, 10), (10, 100));
+    source_map.record_branch("`ifdef FAST".to_string(), (20, 31), (31, 80));
+    source_map.record_expansion("WIDTH".to_string(), (40, 46), None);
+
+    let provenance = source_map.provenance((40, 46));
+    assert_eq!(provenance.len(), 3);
+    assert!(matches!(&provenance[0], Provenance::Expansion { name, .. } if name == "WIDTH"));
+    assert_eq!(provenance[1].to_string(), "in branch `ifdef FAST");
+    assert_eq!(provenance[2].to_string(), "in branch `ifdef SIM");
+
+    // Outside the inner branch, only the outer one holds the text
+    assert_eq!(source_map.provenance((90, 95)).len(), 1);
+}